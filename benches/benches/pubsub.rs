@@ -0,0 +1,51 @@
+//! Publish-receive latency and throughput of small and large messages.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use oxidros::prelude::*;
+use oxidros_bench::{PubSub, large_message, small_message};
+use std::time::Duration;
+
+fn pubsub(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let ctx = Context::new().unwrap();
+
+    let mut group = c.benchmark_group("pubsub");
+    group.measurement_time(Duration::from_secs(10));
+
+    let msg = small_message(42);
+    let mut pair = rt.block_on(PubSub::connect(&ctx, &msg)).unwrap();
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("small", |b| {
+        b.iter_custom(|iters| {
+            rt.block_on(async {
+                let mut total = Duration::ZERO;
+                for _ in 0..iters {
+                    total += pair.round_trip(&msg).await.unwrap();
+                }
+                total
+            })
+        })
+    });
+
+    for size in [4 * 1024, 64 * 1024, 1024 * 1024] {
+        let msg = large_message(size);
+        let mut pair = rt.block_on(PubSub::connect(&ctx, &msg)).unwrap();
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("large", size), &msg, |b, msg| {
+            b.iter_custom(|iters| {
+                rt.block_on(async {
+                    let mut total = Duration::ZERO;
+                    for _ in 0..iters {
+                        total += pair.round_trip(msg).await.unwrap();
+                    }
+                    total
+                })
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, pubsub);
+criterion_main!(benches);
@@ -0,0 +1,31 @@
+//! Selector wait overhead, idle and woken up by a message.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use oxidros::prelude::*;
+use oxidros_bench::{SelectorWakeup, selector_idle};
+use std::time::Duration;
+
+fn selector(c: &mut Criterion) {
+    let ctx = Context::new().unwrap();
+
+    c.bench_function("selector/idle", |b| {
+        b.iter_custom(|iters| {
+            let stats = selector_idle(&ctx, iters as usize).unwrap();
+            stats.mean() * iters as u32
+        })
+    });
+
+    let mut wakeup = SelectorWakeup::connect(&ctx).unwrap();
+    c.bench_function("selector/wakeup", |b| {
+        b.iter_custom(|iters| {
+            let mut total = Duration::ZERO;
+            for _ in 0..iters {
+                total += wakeup.round_trip().unwrap();
+            }
+            total
+        })
+    });
+}
+
+criterion_group!(benches, selector);
+criterion_main!(benches);
@@ -0,0 +1,43 @@
+//! CDR encode/decode speed of the messages.
+
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use oxidros::{
+    msg::common_interfaces::{
+        geometry_msgs::msg::PoseWithCovariance, std_msgs::msg::UInt8MultiArray,
+    },
+    prelude::*,
+};
+use oxidros_bench::{large_message, small_message};
+
+fn bench_message<T: TypeSupport>(c: &mut Criterion, name: &str, msg: &T) {
+    let bytes = msg.to_bytes().unwrap();
+
+    let mut group = c.benchmark_group("cdr");
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    group.bench_with_input(BenchmarkId::new("encode", name), msg, |b, msg| {
+        b.iter(|| black_box(msg).to_bytes().unwrap())
+    });
+    group.bench_with_input(BenchmarkId::new("decode", name), &bytes, |b, bytes| {
+        b.iter(|| T::from_bytes(black_box(bytes)).unwrap())
+    });
+    group.finish();
+}
+
+fn serialization(c: &mut Criterion) {
+    bench_message(c, "int64", &small_message(42));
+
+    let mut pose = PoseWithCovariance::new().unwrap();
+    pose.pose.orientation.w = 1.0;
+    for (i, value) in pose.covariance.iter_mut().enumerate() {
+        *value = i as f64;
+    }
+    bench_message(c, "pose_with_covariance", &pose);
+
+    for size in [4 * 1024, 1024 * 1024] {
+        let msg: UInt8MultiArray = large_message(size);
+        bench_message(c, &format!("u8_array_{size}"), &msg);
+    }
+}
+
+criterion_group!(benches, serialization);
+criterion_main!(benches);
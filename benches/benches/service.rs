@@ -0,0 +1,27 @@
+//! Service round trips.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use oxidros::prelude::*;
+use oxidros_bench::ServicePair;
+use std::time::Duration;
+
+fn service(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let ctx = Context::new().unwrap();
+    let mut pair = rt.block_on(ServicePair::connect(&ctx)).unwrap();
+
+    c.bench_function("service/add_two_ints", |b| {
+        b.iter_custom(|iters| {
+            rt.block_on(async {
+                let mut total = Duration::ZERO;
+                for i in 0..iters as i64 {
+                    total += pair.round_trip(i, 1).await.unwrap();
+                }
+                total
+            })
+        })
+    });
+}
+
+criterion_group!(benches, service);
+criterion_main!(benches);
@@ -0,0 +1,506 @@
+//! # Oxidros benchmark harness
+//!
+//! Measures the paths of the backend selected by the `rcl` or `zenoh` feature:
+//!
+//! - publish-receive latency and throughput, for small and large messages
+//! - service round trips
+//! - selector wait overhead
+//!
+//! The criterion benchmarks in `benches/` are built on this harness, along
+//! with the CDR encode/decode benchmarks of the messages. The `oxidros-bench`
+//! binary prints a summary, to compare the backends on the same machine:
+//!
+//! ```text
+//! cargo run --release -p oxidros-bench --features zenoh
+//! cargo run --release -p oxidros-bench --features rcl
+//! ```
+//!
+//! # Baselines
+//!
+//! Save a criterion baseline before a change and compare against it after,
+//! so that performance regressions are caught:
+//!
+//! ```text
+//! cargo bench -p oxidros-bench --features zenoh -- --save-baseline main
+//! cargo bench -p oxidros-bench --features zenoh -- --baseline main
+//! ```
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::prelude::*;
+//! use oxidros_bench::{PubSub, large_message};
+//!
+//! let ctx = Context::new()?;
+//! let msg = large_message(64 * 1024);
+//! let mut pair = PubSub::connect(&ctx, &msg).await?;
+//! let stats = pair.latency(&msg, 1000).await?;
+//! println!("64 KiB: {stats}");
+//! ```
+
+use oxidros::{
+    core::TypeDescription,
+    error::{Error, Result},
+    msg::{
+        common_interfaces::{
+            example_interfaces::{
+                msg::Int64,
+                srv::{AddTwoInts, AddTwoInts_Request, AddTwoInts_Response},
+            },
+            std_msgs::msg::UInt8MultiArray,
+        },
+        msg::U8Seq,
+    },
+    prelude::*,
+    qos::{HistoryPolicy, Profile},
+};
+use std::{
+    cell::Cell,
+    fmt::Display,
+    rc::Rc,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// How long to wait for a message or a response before giving up.
+pub const RECV_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Name of the backend under measurement.
+pub fn backend() -> &'static str {
+    if cfg!(feature = "rcl") {
+        "rcl"
+    } else {
+        "zenoh"
+    }
+}
+
+/// A small message: a single integer.
+pub fn small_message(data: i64) -> Int64 {
+    Int64 { data }
+}
+
+/// A large message of `size` bytes of payload.
+pub fn large_message(size: usize) -> UInt8MultiArray {
+    let mut msg = UInt8MultiArray::new().unwrap();
+    msg.data = U8Seq::new(size).unwrap();
+    for (i, b) in msg.data.as_mut_slice().iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    msg
+}
+
+/// Unique suffix of the names of the entities created by the harness.
+fn unique(prefix: &str) -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Summary of latency samples.
+#[derive(Debug, Clone)]
+pub struct Stats {
+    /// Sorted samples.
+    samples: Vec<Duration>,
+}
+
+impl Stats {
+    /// Summarize `samples`.
+    pub fn new(mut samples: Vec<Duration>) -> Self {
+        samples.sort_unstable();
+        Stats { samples }
+    }
+
+    /// Number of samples.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Check whether there is no sample.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Smallest sample.
+    pub fn min(&self) -> Duration {
+        self.samples.first().copied().unwrap_or_default()
+    }
+
+    /// Largest sample.
+    pub fn max(&self) -> Duration {
+        self.samples.last().copied().unwrap_or_default()
+    }
+
+    /// Mean of the samples.
+    pub fn mean(&self) -> Duration {
+        if self.samples.is_empty() {
+            return Duration::ZERO;
+        }
+        self.samples.iter().sum::<Duration>() / self.samples.len() as u32
+    }
+
+    /// Sample below which `p` percent of the samples fall.
+    pub fn percentile(&self, p: f64) -> Duration {
+        if self.samples.is_empty() {
+            return Duration::ZERO;
+        }
+        let rank = (p / 100.0 * (self.samples.len() - 1) as f64).round() as usize;
+        self.samples[rank.min(self.samples.len() - 1)]
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "n={} min={:?} mean={:?} p50={:?} p99={:?} max={:?}",
+            self.len(),
+            self.min(),
+            self.mean(),
+            self.percentile(50.0),
+            self.percentile(99.0),
+            self.max()
+        )
+    }
+}
+
+/// Result of a throughput run.
+#[derive(Debug, Clone, Copy)]
+pub struct Throughput {
+    /// Messages published.
+    pub sent: usize,
+    /// Messages received.
+    pub received: usize,
+    /// Serialized size of a message.
+    pub message_bytes: usize,
+    /// Time from the first publication to the last reception.
+    pub elapsed: Duration,
+}
+
+impl Throughput {
+    /// Received messages per second.
+    pub fn messages_per_sec(&self) -> f64 {
+        self.received as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// Received payload in MiB per second.
+    pub fn mib_per_sec(&self) -> f64 {
+        (self.received * self.message_bytes) as f64 / self.elapsed.as_secs_f64() / 1048576.0
+    }
+}
+
+impl Display for Throughput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{} received in {:?}: {:.0} msg/s, {:.1} MiB/s",
+            self.received,
+            self.sent,
+            self.elapsed,
+            self.messages_per_sec(),
+            self.mib_per_sec()
+        )
+    }
+}
+
+/// A publisher and a subscriber of the same topic, on two nodes.
+pub struct PubSub<T: TypeSupport> {
+    publisher: Publisher<T>,
+    subscriber: Subscriber<T>,
+    _nodes: [Arc<Node>; 2],
+}
+
+impl<T> PubSub<T>
+where
+    T: TypeSupport + TypeDescription + Send + Sync + 'static,
+{
+    /// Create the pair and wait until `sample` goes through.
+    ///
+    /// The topic keeps all samples, so that throughput runs lose nothing to
+    /// the history depth.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if `sample` is not received within [`RECV_TIMEOUT`].
+    pub async fn connect(ctx: &Arc<Context>, sample: &T) -> Result<Self> {
+        let topic = unique("bench_topic");
+        let pub_node = ctx.create_node(&unique("bench_pub"), None)?;
+        let sub_node = ctx.create_node(&unique("bench_sub"), None)?;
+
+        let qos = Profile {
+            history: HistoryPolicy::KeepAll,
+            ..Default::default()
+        };
+        let publisher = pub_node.create_publisher::<T>(&topic, Some(qos.clone()))?;
+        let subscriber = sub_node.create_subscriber::<T>(&topic, Some(qos))?;
+        let mut pair = PubSub {
+            publisher,
+            subscriber,
+            _nodes: [pub_node, sub_node],
+        };
+
+        // Discovery: publish until the subscriber is matched
+        let deadline = Instant::now() + RECV_TIMEOUT;
+        loop {
+            pair.publisher.send(sample)?;
+            if tokio::time::timeout(Duration::from_millis(100), pair.subscriber.recv())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+            if Instant::now() > deadline {
+                return Err(Error::Timeout);
+            }
+        }
+        while pair.subscriber.try_recv()?.is_some() {}
+        Ok(pair)
+    }
+
+    /// Publish `msg` and wait until it is received.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if `msg` is not received within [`RECV_TIMEOUT`].
+    pub async fn round_trip(&mut self, msg: &T) -> Result<Duration> {
+        let start = Instant::now();
+        self.publisher.send(msg)?;
+        tokio::time::timeout(RECV_TIMEOUT, self.subscriber.recv())
+            .await
+            .map_err(|_| Error::Timeout)??;
+        Ok(start.elapsed())
+    }
+
+    /// Measure `iterations` round trips of `msg`.
+    pub async fn latency(&mut self, msg: &T, iterations: usize) -> Result<Stats> {
+        let mut samples = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            samples.push(self.round_trip(msg).await?);
+        }
+        Ok(Stats::new(samples))
+    }
+
+    /// Publish `count` copies of `msg` back to back and receive them.
+    ///
+    /// The run stops at the first reception taking longer than [`RECV_TIMEOUT`];
+    /// the missing messages are reported in [`Throughput::received`].
+    pub async fn throughput(&mut self, msg: &T, count: usize) -> Result<Throughput> {
+        let message_bytes = msg.to_bytes()?.len();
+        let start = Instant::now();
+        let mut received = 0;
+        let mut last = start;
+        for _ in 0..count {
+            self.publisher.send(msg)?;
+            while self.subscriber.try_recv()?.is_some() {
+                received += 1;
+                last = Instant::now();
+            }
+        }
+        while received < count {
+            match tokio::time::timeout(RECV_TIMEOUT, self.subscriber.recv()).await {
+                Ok(msg) => {
+                    msg?;
+                    received += 1;
+                    last = Instant::now();
+                }
+                Err(_) => break,
+            }
+        }
+        Ok(Throughput {
+            sent: count,
+            received,
+            message_bytes,
+            elapsed: last - start,
+        })
+    }
+}
+
+/// An `AddTwoInts` client and a server answering it in a task.
+pub struct ServicePair {
+    client: Client<AddTwoInts>,
+    server: tokio::task::JoinHandle<Result<()>>,
+    _nodes: [Arc<Node>; 2],
+}
+
+impl ServicePair {
+    /// Create the pair and wait until the server answers.
+    ///
+    /// Must be called within a tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if the server does not answer within [`RECV_TIMEOUT`].
+    pub async fn connect(ctx: &Arc<Context>) -> Result<Self> {
+        let service = unique("bench_service");
+        let server_node = ctx.create_node(&unique("bench_server"), None)?;
+        let client_node = ctx.create_node(&unique("bench_client"), None)?;
+
+        let server = server_node.create_server::<AddTwoInts>(&service, None)?;
+        let server = tokio::spawn(server.serve(|request| AddTwoInts_Response {
+            sum: request.a + request.b,
+        }));
+        let client = client_node.create_client::<AddTwoInts>(&service, None)?;
+
+        let mut pair = ServicePair {
+            client,
+            server,
+            _nodes: [server_node, client_node],
+        };
+        tokio::time::timeout(
+            RECV_TIMEOUT,
+            pair.client.call_with_retry(
+                &AddTwoInts_Request { a: 0, b: 0 },
+                Duration::from_millis(100),
+            ),
+        )
+        .await
+        .map_err(|_| Error::Timeout)??;
+        Ok(pair)
+    }
+
+    /// Send a request and wait for the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if there is no response within [`RECV_TIMEOUT`].
+    pub async fn round_trip(&mut self, a: i64, b: i64) -> Result<Duration> {
+        let start = Instant::now();
+        let response =
+            tokio::time::timeout(RECV_TIMEOUT, self.client.call(&AddTwoInts_Request { a, b }))
+                .await
+                .map_err(|_| Error::Timeout)??;
+        let elapsed = start.elapsed();
+        debug_assert_eq!(response.sum, a + b);
+        Ok(elapsed)
+    }
+
+    /// Measure `iterations` round trips.
+    pub async fn latency(&mut self, iterations: usize) -> Result<Stats> {
+        let mut samples = Vec::with_capacity(iterations);
+        for i in 0..iterations as i64 {
+            samples.push(self.round_trip(i, 1).await?);
+        }
+        Ok(Stats::new(samples))
+    }
+}
+
+impl Drop for ServicePair {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+/// Measure `iterations` calls to `wait_timeout` of a selector with nothing ready.
+pub fn selector_idle(ctx: &Arc<Context>, iterations: usize) -> Result<Stats> {
+    let node = ctx.create_node(&unique("bench_selector_idle"), None)?;
+    let subscriber = node.create_subscriber::<Int64>(&unique("bench_idle_topic"), None)?;
+    let mut selector = ctx.create_selector()?;
+    selector.add_subscriber(subscriber, Box::new(|_| {}));
+
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        selector.wait_timeout(Duration::ZERO)?;
+        samples.push(start.elapsed());
+    }
+    Ok(Stats::new(samples))
+}
+
+/// A selector woken up by the messages of a publisher.
+pub struct SelectorWakeup {
+    publisher: Publisher<Int64>,
+    selector: Selector,
+    received: Rc<Cell<usize>>,
+    _node: Arc<Node>,
+}
+
+impl SelectorWakeup {
+    /// Create the publisher and the selector, and wait until a message goes through.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if no message is received within [`RECV_TIMEOUT`].
+    pub fn connect(ctx: &Arc<Context>) -> Result<Self> {
+        let topic = unique("bench_wakeup_topic");
+        let node = ctx.create_node(&unique("bench_selector_wakeup"), None)?;
+        let publisher = node.create_publisher::<Int64>(&topic, None)?;
+        let subscriber = node.create_subscriber::<Int64>(&topic, None)?;
+
+        let received = Rc::new(Cell::new(0));
+        let mut selector = ctx.create_selector()?;
+        let counter = received.clone();
+        selector.add_subscriber(
+            subscriber,
+            Box::new(move |_| counter.set(counter.get() + 1)),
+        );
+
+        let mut wakeup = SelectorWakeup {
+            publisher,
+            selector,
+            received,
+            _node: node,
+        };
+        let deadline = Instant::now() + RECV_TIMEOUT;
+        while wakeup.received.get() == 0 {
+            wakeup.publisher.send(&small_message(0))?;
+            wakeup.selector.wait_timeout(Duration::from_millis(100))?;
+            if Instant::now() > deadline {
+                return Err(Error::Timeout);
+            }
+        }
+        // Drain the messages of the discovery
+        while wakeup.selector.wait_timeout(Duration::from_millis(10))? {}
+        Ok(wakeup)
+    }
+
+    /// Publish a message and wait until the selector runs its callback.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if the callback does not run within [`RECV_TIMEOUT`].
+    pub fn round_trip(&mut self) -> Result<Duration> {
+        let expected = self.received.get() + 1;
+        let start = Instant::now();
+        self.publisher.send(&small_message(expected as i64))?;
+        while self.received.get() < expected {
+            if start.elapsed() > RECV_TIMEOUT {
+                return Err(Error::Timeout);
+            }
+            self.selector.wait_timeout(RECV_TIMEOUT)?;
+        }
+        Ok(start.elapsed())
+    }
+
+    /// Measure `iterations` round trips.
+    pub fn latency(&mut self, iterations: usize) -> Result<Stats> {
+        let samples = (0..iterations)
+            .map(|_| self.round_trip())
+            .collect::<Result<_>>()?;
+        Ok(Stats::new(samples))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::new((1..=100).rev().map(Duration::from_micros).collect());
+        assert_eq!(stats.len(), 100);
+        assert_eq!(stats.min(), Duration::from_micros(1));
+        assert_eq!(stats.max(), Duration::from_micros(100));
+        assert_eq!(stats.percentile(50.0), Duration::from_micros(51));
+        assert_eq!(stats.percentile(99.0), Duration::from_micros(99));
+        assert_eq!(stats.mean(), Duration::from_nanos(50_500));
+
+        let empty = Stats::new(Vec::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.percentile(99.0), Duration::ZERO);
+    }
+}
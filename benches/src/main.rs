@@ -0,0 +1,70 @@
+//! Print the benchmark summary of the selected backend.
+//!
+//! ```text
+//! cargo run --release -p oxidros-bench --features zenoh [iterations]
+//! ```
+
+use oxidros::{error::Result, prelude::*};
+use oxidros_bench::{
+    PubSub, SelectorWakeup, ServicePair, backend, large_message, selector_idle, small_message,
+};
+
+const LARGE_SIZES: [usize; 3] = [4 * 1024, 64 * 1024, 1024 * 1024];
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() -> Result<()> {
+    let iterations = std::env::args()
+        .nth(1)
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(1000);
+
+    let ctx = Context::new()?;
+    println!("backend: {}, iterations: {iterations}", backend());
+
+    println!("\n## Publish-receive latency");
+    let msg = small_message(42);
+    let mut pair = PubSub::connect(&ctx, &msg).await?;
+    println!("small:        {}", pair.latency(&msg, iterations).await?);
+    for size in LARGE_SIZES {
+        let msg = large_message(size);
+        let mut pair = PubSub::connect(&ctx, &msg).await?;
+        println!(
+            "{:>7} KiB:  {}",
+            size / 1024,
+            pair.latency(&msg, iterations).await?
+        );
+    }
+
+    println!("\n## Throughput");
+    println!(
+        "small:        {}",
+        pair.throughput(&msg, iterations * 10).await?
+    );
+    for size in LARGE_SIZES {
+        let msg = large_message(size);
+        let mut pair = PubSub::connect(&ctx, &msg).await?;
+        println!(
+            "{:>7} KiB:  {}",
+            size / 1024,
+            pair.throughput(&msg, iterations).await?
+        );
+    }
+
+    println!("\n## Service round trip");
+    let mut service = ServicePair::connect(&ctx).await?;
+    println!("AddTwoInts:   {}", service.latency(iterations).await?);
+
+    println!("\n## Selector");
+    let ctx_selector = ctx.clone();
+    let (idle, wakeup) = tokio::task::spawn_blocking(move || -> Result<_> {
+        let idle = selector_idle(&ctx_selector, iterations)?;
+        let wakeup = SelectorWakeup::connect(&ctx_selector)?.latency(iterations)?;
+        Ok((idle, wakeup))
+    })
+    .await
+    .expect("selector benchmark panicked")?;
+    println!("idle wait:    {idle}");
+    println!("wakeup:       {wakeup}");
+
+    Ok(())
+}
@@ -109,7 +109,7 @@ fn main() -> Result<()> {
             }
 
             // List all parameters
-            let names: Vec<_> = params.params.keys().cloned().collect();
+            let names: Vec<_> = params.params().keys().cloned().collect();
             tracing::info!("Parameters: {:?}", names);
         }),
     );
@@ -75,6 +75,13 @@ impl ParseCallbacks for TypeDescCallbacks {
             ros2_parts.push("wstring".to_string());
         }
 
+        // Add string attribute for sequences and arrays of bounded strings
+        // (string<=M[], string<=M[<=N], string<=M[N]); wstrings already have
+        // their `ros2_type` override
+        if ros_type_name == "string[]" && field_info.string_capacity().is_some() {
+            ros2_parts.push("string".to_string());
+        }
+
         if is_sequence {
             ros2_parts.push("sequence".to_string());
         }
@@ -0,0 +1,269 @@
+//! Cross-compilation support for RCL binding generation and linking.
+//!
+//! When building the RCL backend for another architecture (e.g. an aarch64
+//! robot from an x86_64 host), the ROS2 headers and libraries live inside a
+//! target sysroot rather than on the host. This module describes where to find
+//! them so that bindgen and the linker look in the right place.
+//!
+//! # Environment Variables
+//!
+//! | Variable | Description |
+//! |----------|-------------|
+//! | `OXIDROS_SYSROOT` | Target sysroot. `AMENT_PREFIX_PATH` entries are re-rooted under it and it is passed to clang as `--sysroot`. |
+//! | `OXIDROS_CLANG_TARGET` | Explicit clang target triple (defaults to bindgen's mapping of cargo's `TARGET`). |
+//! | `OXIDROS_TARGET_INCLUDE_PATH` | Extra include directories (path-list separated), e.g. toolchain headers. |
+//! | `OXIDROS_TARGET_LIB_PATH` | Extra library directories (path-list separated) for `cargo:rustc-link-search`. |
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // build.rs
+//! use std::path::PathBuf;
+//!
+//! let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+//! let cross = oxidros_build::CrossConfig::builder()
+//!     .sysroot("/opt/sysroots/aarch64-ros-jazzy")
+//!     .include_path("/opt/toolchains/aarch64/include")
+//!     .build();
+//!
+//! oxidros_build::generate_rcl_bindings_with(&out_dir, &cross);
+//! oxidros_build::link_rcl_ros2_libs_with(&cross);
+//! ```
+
+use std::{
+    env,
+    path::{Component, Path, PathBuf},
+};
+
+/// Environment variable holding the target sysroot.
+pub const SYSROOT_ENV: &str = "OXIDROS_SYSROOT";
+/// Environment variable holding an explicit clang target triple.
+pub const CLANG_TARGET_ENV: &str = "OXIDROS_CLANG_TARGET";
+/// Environment variable holding extra target include directories.
+pub const TARGET_INCLUDE_PATH_ENV: &str = "OXIDROS_TARGET_INCLUDE_PATH";
+/// Environment variable holding extra target library directories.
+pub const TARGET_LIB_PATH_ENV: &str = "OXIDROS_TARGET_LIB_PATH";
+
+/// Target sysroot and toolchain paths used for binding generation and linking.
+///
+/// The default value describes a native build: no sysroot, no extra paths.
+/// Use [`CrossConfig::from_env`] to pick up the `OXIDROS_*` environment
+/// variables, or [`CrossConfig::builder`] to configure it from `build.rs`.
+#[derive(Debug, Clone, Default)]
+pub struct CrossConfig {
+    /// Target sysroot that ROS2 install prefixes are resolved against.
+    pub(crate) sysroot: Option<PathBuf>,
+    /// Explicit clang target triple.
+    pub(crate) clang_target: Option<String>,
+    /// Extra include directories passed to clang.
+    pub(crate) include_paths: Vec<PathBuf>,
+    /// Extra library directories emitted as link search paths.
+    pub(crate) lib_paths: Vec<PathBuf>,
+}
+
+impl CrossConfig {
+    /// Creates a new [`CrossConfigBuilder`] for constructing a [`CrossConfig`].
+    pub fn builder() -> CrossConfigBuilder {
+        CrossConfigBuilder::default()
+    }
+
+    /// Builds a [`CrossConfig`] from the `OXIDROS_*` environment variables.
+    ///
+    /// Unset or empty variables leave the corresponding option empty, so this
+    /// returns the native configuration when nothing is set.
+    pub fn from_env() -> Self {
+        let non_empty = |key: &str| env::var(key).ok().filter(|v| !v.is_empty());
+        let path_list = |key: &str| {
+            env::var_os(key)
+                .map(|v| env::split_paths(&v).collect())
+                .unwrap_or_default()
+        };
+        Self {
+            sysroot: non_empty(SYSROOT_ENV).map(PathBuf::from),
+            clang_target: non_empty(CLANG_TARGET_ENV),
+            include_paths: path_list(TARGET_INCLUDE_PATH_ENV),
+            lib_paths: path_list(TARGET_LIB_PATH_ENV),
+        }
+    }
+
+    /// Returns the target sysroot, if any.
+    pub fn sysroot(&self) -> Option<&Path> {
+        self.sysroot.as_deref()
+    }
+
+    /// Returns the explicit clang target triple, if any.
+    pub fn clang_target(&self) -> Option<&str> {
+        self.clang_target.as_deref()
+    }
+
+    /// Returns the extra include directories.
+    pub fn include_paths(&self) -> &[PathBuf] {
+        &self.include_paths
+    }
+
+    /// Returns the extra library directories.
+    pub fn lib_paths(&self) -> &[PathBuf] {
+        &self.lib_paths
+    }
+
+    /// Resolves a target path against the sysroot.
+    ///
+    /// `AMENT_PREFIX_PATH` usually holds paths as seen on the target
+    /// (e.g. `/opt/ros/jazzy`). With a sysroot of `/sysroot` this returns
+    /// `/sysroot/opt/ros/jazzy`. Paths already inside the sysroot, relative
+    /// paths, and any path when no sysroot is set are returned unchanged.
+    pub fn rebase(&self, path: &Path) -> PathBuf {
+        let Some(sysroot) = &self.sysroot else {
+            return path.to_path_buf();
+        };
+        if !path.has_root() || path.starts_with(sysroot) {
+            return path.to_path_buf();
+        }
+        let relative: PathBuf = path
+            .components()
+            .filter(|c| !matches!(c, Component::Prefix(_) | Component::RootDir))
+            .collect();
+        sysroot.join(relative)
+    }
+
+    /// Applies the sysroot, target and include paths to a bindgen builder.
+    pub fn apply_to_bindgen(&self, mut builder: bindgen::Builder) -> bindgen::Builder {
+        if let Some(target) = &self.clang_target {
+            builder = builder.clang_arg(format!("--target={}", target));
+        }
+        if let Some(sysroot) = &self.sysroot {
+            builder = builder.clang_arg(format!("--sysroot={}", sysroot.display()));
+        }
+        for include_path in &self.include_paths {
+            builder = builder.clang_arg(format!("-I{}", include_path.display()));
+        }
+        builder
+    }
+
+    /// Emits `cargo:rustc-link-search` directives for the extra library directories.
+    pub(crate) fn print_lib_search_paths(&self) {
+        for lib_path in &self.lib_paths {
+            println!("cargo:rustc-link-search={}", lib_path.display());
+        }
+    }
+}
+
+/// Builder for constructing a [`CrossConfig`] with a fluent API.
+///
+/// Starts from an empty (native) configuration; call
+/// [`CrossConfigBuilder::from_env`] to start from the environment instead.
+#[derive(Debug, Clone, Default)]
+pub struct CrossConfigBuilder {
+    config: CrossConfig,
+}
+
+impl CrossConfigBuilder {
+    /// Creates a new [`CrossConfigBuilder`] with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a builder pre-populated from the `OXIDROS_*` environment variables.
+    pub fn from_env() -> Self {
+        Self {
+            config: CrossConfig::from_env(),
+        }
+    }
+
+    /// Sets the target sysroot.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Root of the target filesystem (e.g. `/opt/sysroots/aarch64`)
+    pub fn sysroot(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.sysroot = Some(path.into());
+        self
+    }
+
+    /// Sets an explicit clang target triple.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Clang triple (e.g. `aarch64-unknown-linux-gnu`)
+    pub fn clang_target(mut self, target: impl Into<String>) -> Self {
+        self.config.clang_target = Some(target.into());
+        self
+    }
+
+    /// Adds an extra include directory for binding generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Include directory (e.g. toolchain C headers)
+    pub fn include_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.include_paths.push(path.into());
+        self
+    }
+
+    /// Adds an extra library directory for linking.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Library directory containing the target's ROS2 libraries
+    pub fn lib_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.lib_paths.push(path.into());
+        self
+    }
+
+    /// Builds the [`CrossConfig`] with the specified options.
+    pub fn build(self) -> CrossConfig {
+        self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cross_config_default_is_native() {
+        let cross = CrossConfig::builder().build();
+        assert!(cross.sysroot().is_none());
+        assert!(cross.clang_target().is_none());
+        assert!(cross.include_paths().is_empty());
+        assert!(cross.lib_paths().is_empty());
+        assert_eq!(
+            cross.rebase(Path::new("/opt/ros/jazzy")),
+            PathBuf::from("/opt/ros/jazzy")
+        );
+    }
+
+    #[test]
+    fn test_cross_config_builder() {
+        let cross = CrossConfig::builder()
+            .sysroot("/sysroot")
+            .clang_target("aarch64-unknown-linux-gnu")
+            .include_path("/toolchain/include")
+            .lib_path("/toolchain/lib")
+            .build();
+        assert_eq!(cross.sysroot(), Some(Path::new("/sysroot")));
+        assert_eq!(cross.clang_target(), Some("aarch64-unknown-linux-gnu"));
+        assert_eq!(cross.include_paths().len(), 1);
+        assert_eq!(cross.lib_paths().len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cross_config_rebase() {
+        let cross = CrossConfig::builder().sysroot("/sysroot").build();
+        assert_eq!(
+            cross.rebase(Path::new("/opt/ros/jazzy")),
+            PathBuf::from("/sysroot/opt/ros/jazzy")
+        );
+        // Already inside the sysroot
+        assert_eq!(
+            cross.rebase(Path::new("/sysroot/opt/ros/jazzy")),
+            PathBuf::from("/sysroot/opt/ros/jazzy")
+        );
+        // Relative paths are left alone
+        assert_eq!(
+            cross.rebase(Path::new("install/my_pkg")),
+            PathBuf::from("install/my_pkg")
+        );
+    }
+}
@@ -41,6 +41,7 @@
 //!
 //! - **[`msg`] module** — Generate Rust types from message definitions
 //! - **RCL bindings** — (Advanced) FFI bindings for the ROS2 C client library (requires sourced ROS2)
+//! - **[`cross`] module** — Sysroot-aware binding generation and linking for cross-compilation
 //!
 //! # Platform Support
 //!
@@ -51,9 +52,12 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::msg::is_ros2_sourced;
+use crate::msg::{is_lazy_typesupport, is_ros2_sourced};
+pub mod cross;
 pub mod msg;
 
+pub use cross::{CrossConfig, CrossConfigBuilder};
+
 // ============================================================================
 // Distro Detection
 // ============================================================================
@@ -195,12 +199,13 @@ const MSG_REQUIRED_PACKAGES: &[&str] = &[
 /// # Arguments
 ///
 /// * `packages` - A slice of package names to find include paths for
+/// * `cross` - Cross-compilation settings; prefixes are resolved against its sysroot
 ///
 /// # Returns
 ///
 /// A vector of include paths (e.g., `/opt/ros/jazzy/include/rcl`) for each
 /// package found. Paths are returned in AMENT_PREFIX_PATH order (overlay first).
-fn collect_include_paths(packages: &[&str]) -> Vec<PathBuf> {
+fn collect_include_paths(packages: &[&str], cross: &CrossConfig) -> Vec<PathBuf> {
     let Ok(ament_prefix_path) = env::var("AMENT_PREFIX_PATH") else {
         return Vec::new();
     };
@@ -209,7 +214,7 @@ fn collect_include_paths(packages: &[&str]) -> Vec<PathBuf> {
 
     // Iterate through all paths in AMENT_PREFIX_PATH
     for prefix_path in env::split_paths(&ament_prefix_path) {
-        let include_dir = prefix_path.join("include");
+        let include_dir = cross.rebase(&prefix_path).join("include");
         if !include_dir.exists() {
             continue;
         }
@@ -245,6 +250,7 @@ fn collect_include_paths(packages: &[&str]) -> Vec<PathBuf> {
 /// # Emitted Directives
 ///
 /// - Registers `AMENT_PREFIX_PATH`, `CMAKE_PREFIX_PATH`, and `ROS_DISTRO` for change detection
+/// - Registers the [`cross`] environment variables (`OXIDROS_SYSROOT`, ...) for change detection
 ///
 /// # Example
 ///
@@ -258,6 +264,13 @@ pub fn ros2_env_var_changed() {
     println!("cargo:rerun-if-env-changed=AMENT_PREFIX_PATH");
     println!("cargo:rerun-if-env-changed=CMAKE_PREFIX_PATH");
     println!("cargo:rerun-if-env-changed=ROS_DISTRO");
+    println!("cargo:rerun-if-env-changed={}", cross::SYSROOT_ENV);
+    println!("cargo:rerun-if-env-changed={}", cross::CLANG_TARGET_ENV);
+    println!(
+        "cargo:rerun-if-env-changed={}",
+        cross::TARGET_INCLUDE_PATH_ENV
+    );
+    println!("cargo:rerun-if-env-changed={}", cross::TARGET_LIB_PATH_ENV);
 }
 
 /// Creates a base `bindgen::Builder` with common settings for ROS2 bindings.
@@ -334,9 +347,30 @@ pub fn builder_base() -> bindgen::Builder {
 /// // In your lib.rs:
 /// // include!(concat!(env!("OUT_DIR"), "/rcl.rs"));
 /// ```
+///
+/// Cross-compilation settings are read from the environment; see
+/// [`generate_rcl_bindings_with`] to pass them explicitly.
 pub fn generate_rcl_bindings(out_dir: &Path) {
+    generate_rcl_bindings_with(out_dir, &CrossConfig::from_env());
+}
+
+/// Generates Rust FFI bindings for the ROS2 RCL using explicit cross-compilation settings.
+///
+/// Behaves like [`generate_rcl_bindings`], but ROS2 include directories are
+/// resolved against the sysroot of `cross`, and its sysroot, target and extra
+/// include paths are forwarded to clang.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let cross = oxidros_build::CrossConfig::builder()
+///     .sysroot("/opt/sysroots/aarch64")
+///     .build();
+/// oxidros_build::generate_rcl_bindings_with(&out_dir, &cross);
+/// ```
+pub fn generate_rcl_bindings_with(out_dir: &Path, cross: &CrossConfig) {
     // Collect all include paths for required RCL packages from AMENT_PREFIX_PATH
-    let include_paths = collect_include_paths(RCL_REQUIRED_PACKAGES);
+    let include_paths = collect_include_paths(RCL_REQUIRED_PACKAGES, cross);
     if include_paths.is_empty() {
         // No ROS2 environment, skip binding generation
         return;
@@ -347,8 +381,11 @@ pub fn generate_rcl_bindings(out_dir: &Path) {
 #include <rcl/rcl.h>
 #include <rcl/types.h>
 #include <rcl/logging.h>
+#include <rcl/network_flow_endpoints.h>
 #include <rcl_action/rcl_action.h>
 #include <rcutils/error_handling.h>
+#include <rmw/rmw.h>
+#include <rmw/qos_profiles.h>
 #include <action_msgs/srv/cancel_goal.h>
 #include <action_msgs/msg/goal_info.h>
 "#;
@@ -373,6 +410,9 @@ pub fn generate_rcl_bindings(out_dir: &Path) {
         builder = builder.clang_arg(format!("-I{}", include_path.display()));
     }
 
+    // Add sysroot, target and toolchain include paths
+    builder = cross.apply_to_bindgen(builder);
+
     let bindings = builder
         .allowlist_type("rcl_.*")
         .allowlist_function("rcl_.*")
@@ -505,6 +545,12 @@ pub fn get_paths_from_env(key: &str) -> Result<Vec<PathBuf>, VarError> {
 /// This function only links RCL core libraries. For message type libraries,
 /// use [`link_msg_ros2_libs`] instead.
 ///
+/// # Cross-Compilation
+///
+/// `AMENT_PREFIX_PATH` entries are resolved against the sysroot from the
+/// environment, and extra library paths are added; see [`link_rcl_ros2_libs_with`]
+/// to pass them explicitly.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -512,6 +558,15 @@ pub fn get_paths_from_env(key: &str) -> Result<Vec<PathBuf>, VarError> {
 /// oxidros_build::link_rcl_ros2_libs();
 /// ```
 pub fn link_rcl_ros2_libs() {
+    link_rcl_ros2_libs_with(&CrossConfig::from_env());
+}
+
+/// Emits cargo directives to link against ROS2 RCL core libraries using
+/// explicit cross-compilation settings.
+///
+/// Behaves like [`link_rcl_ros2_libs`], but library search paths are resolved
+/// against the sysroot of `cross` and its extra library paths are emitted first.
+pub fn link_rcl_ros2_libs_with(cross: &CrossConfig) {
     if !is_ros2_sourced() {
         return;
     }
@@ -523,8 +578,12 @@ pub fn link_rcl_ros2_libs() {
     println!("cargo:rustc-link-lib=rmw_implementation");
     println!("cargo:rustc-link-lib=rcl_yaml_param_parser");
 
+    // Toolchain library paths take precedence over ROS2 prefixes
+    cross.print_lib_search_paths();
+
     // Add library search paths from AMENT_PREFIX_PATH
     for path in get_paths_from_env("AMENT_PREFIX_PATH").unwrap_or_default() {
+        let path = cross.rebase(&path);
         if cfg!(target_os = "windows") {
             print_all_libs(path.join("Lib"));
         } else {
@@ -579,9 +638,21 @@ pub fn link_rcl_ros2_libs() {
 /// // In your lib.rs:
 /// // include!(concat!(env!("OUT_DIR"), "/runtime_c.rs"));
 /// ```
+///
+/// Cross-compilation settings are read from the environment; see
+/// [`generate_runtime_c_with`] to pass them explicitly.
 pub fn generate_runtime_c(out_dir: &Path) {
+    generate_runtime_c_with(out_dir, &CrossConfig::from_env());
+}
+
+/// Generates Rust FFI bindings for the ROS2 runtime C types using explicit
+/// cross-compilation settings.
+///
+/// Behaves like [`generate_runtime_c`], but include directories are resolved
+/// against the sysroot of `cross` and its settings are forwarded to clang.
+pub fn generate_runtime_c_with(out_dir: &Path, cross: &CrossConfig) {
     // Collect all include paths for required message packages from AMENT_PREFIX_PATH
-    let include_paths = collect_include_paths(MSG_REQUIRED_PACKAGES);
+    let include_paths = collect_include_paths(MSG_REQUIRED_PACKAGES, cross);
     if include_paths.is_empty() {
         // No ROS2 environment, skip binding generation
         return;
@@ -627,6 +698,9 @@ pub fn generate_runtime_c(out_dir: &Path) {
         builder = builder.clang_arg(format!("-I{}", include_path.display()));
     }
 
+    // Add sysroot, target and toolchain include paths
+    builder = cross.apply_to_bindgen(builder);
+
     let bindings = builder
         .allowlist_type("rosidl_.*")
         .allowlist_function("rosidl_.*")
@@ -663,6 +737,8 @@ pub fn generate_runtime_c(out_dir: &Path) {
 ///
 /// For each package, both `*__rosidl_typesupport_c` and `*__rosidl_generator_c`
 /// libraries are linked.
+/// They are not linked if the crate being built enables its `lazy-typesupport`
+/// feature, see [`msg::is_lazy_typesupport`].
 ///
 /// # Distro-Specific Libraries
 ///
@@ -690,6 +766,11 @@ pub fn link_msg_ros2_libs() {
     // This only links rosidl_runtime_c and message-specific libraries
     println!("cargo:rustc-link-lib=rosidl_runtime_c");
 
+    // Loaded at runtime by `ros2_types::typesupport`
+    if is_lazy_typesupport() {
+        return;
+    }
+
     if !cfg!(target_os = "windows") {
         // actionlib_msgs was removed in Jazzy; only present in Humble
         let distro_for_link = env::var("ROS_DISTRO").unwrap_or_default();
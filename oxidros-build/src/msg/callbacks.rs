@@ -68,6 +68,9 @@ impl ParseCallbacks for RosCallbacks {
         attributes.push(
             "#[cfg_attr(not(feature = \"rcl\"), serde(crate = \"ros2_types::serde\"))]".to_string(),
         );
+        // Random instances for property tests, with the `arbitrary` feature of the crate
+        attributes
+            .push("#[cfg_attr(feature = \"arbitrary\", derive(arbitrary::Arbitrary))]".to_string());
         attributes
     }
 
@@ -94,15 +97,27 @@ impl ParseCallbacks for RosCallbacks {
         }
         // Add default value if present
         if let Some(default_value) = field_info.default_value() {
-            // Escape quotes and backslashes in the default value
-            let escaped = default_value.replace('\\', "\\\\").replace('"', "\\\"");
-            ros2_parts.push(format!("default = \"{}\"", escaped));
+            ros2_parts.push(format!("default = \"{}\"", escape(default_value)));
+        }
+        // Add unit if present, so it can be queried with TypeDescription::field_unit
+        if let Some(unit) = field_info.unit() {
+            ros2_parts.push(format!("unit = \"{}\"", escape(unit)));
+            attrs.push(format!("#[doc = \" Unit: `{}`\"]", escape(unit)));
         }
 
         if !ros2_parts.is_empty() {
             attrs.push(format!("#[ros2({})]", ros2_parts.join(", ")));
         }
 
+        // Mark fields documented as deprecated, e.g. `# DEPRECATED: use foo instead`
+        match deprecation_note(field_info.comments()) {
+            Some(note) if !note.is_empty() => {
+                attrs.push(format!("#[deprecated(note = \"{}\")]", escape(&note)));
+            }
+            Some(_) => attrs.push("#[deprecated]".to_string()),
+            None => (),
+        }
+
         // Add serde_big_array attribute for large fixed-size arrays (> 32 elements)
         if let Some(size) = field_info.array_size()
             && size > 32
@@ -196,3 +211,56 @@ impl ParseCallbacks for RosCallbacks {
         }
     }
 }
+
+/// Escape quotes and backslashes for use in a string literal.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Find a `DEPRECATED` comment line and return the text following it.
+///
+/// Returns an empty note if the line has no explanation.
+fn deprecation_note(comments: &[String]) -> Option<String> {
+    comments.iter().find_map(|line| {
+        let line = line.trim_start_matches('#').trim();
+        let (head, rest) = line.split_at_checked("DEPRECATED".len())?;
+        if !head.eq_ignore_ascii_case("DEPRECATED")
+            || rest.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+        {
+            return None;
+        }
+        Some(
+            rest.trim_start_matches([':', '-', '.', ' ', '\t'])
+                .trim()
+                .to_string(),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_deprecation_note() {
+        assert_eq!(
+            deprecation_note(&lines(&["Velocity", "DEPRECATED: use twist instead"])),
+            Some("use twist instead".to_string())
+        );
+        assert_eq!(
+            deprecation_note(&lines(&["# Deprecated - kept for ROS 1"])),
+            Some("kept for ROS 1".to_string())
+        );
+        assert_eq!(
+            deprecation_note(&lines(&["DEPRECATED"])),
+            Some(String::new())
+        );
+        assert_eq!(deprecation_note(&lines(&["DEPRECATEDNESS level"])), None);
+        assert_eq!(deprecation_note(&lines(&["Not deprecated"])), None);
+        assert_eq!(deprecation_note(&[]), None);
+    }
+}
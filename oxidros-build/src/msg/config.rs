@@ -6,6 +6,8 @@
 use std::env;
 use std::path::PathBuf;
 
+use crate::cross::CrossConfig;
+
 /// Supported ROS2 distributions for automatic path detection.
 const SUPPORTED_DISTROS: &[&str] = &["humble", "jazzy", "kilted"];
 
@@ -120,6 +122,7 @@ impl Config {
     ///
     /// Returns `None` if the variable is not set or is empty.
     /// Preserves the original order from AMENT_PREFIX_PATH (overlay workspaces first).
+    /// When `OXIDROS_SYSROOT` is set, each entry is resolved against the sysroot.
     pub fn get_ament_prefix_paths() -> Option<Vec<PathBuf>> {
         let path = env::var("AMENT_PREFIX_PATH").ok()?;
         if path.is_empty() {
@@ -127,8 +130,10 @@ impl Config {
         }
 
         // Preserve order from AMENT_PREFIX_PATH - overlay workspaces come first
+        let cross = CrossConfig::from_env();
         let mut paths: Vec<PathBuf> = Vec::new();
         for p in env::split_paths(&path) {
+            let p = cross.rebase(&p);
             if p.exists() && !paths.contains(&p) {
                 paths.push(p);
             }
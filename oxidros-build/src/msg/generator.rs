@@ -225,11 +225,16 @@ pub(crate) fn collect_interface_files(pkg_path: PathBuf) -> Vec<PathBuf> {
 /// # Arguments
 ///
 /// * `pkg` - The name of the ROS2 package (e.g., `"std_msgs"`, `"geometry_msgs"`)
+/// * `lib_paths` - Library directories, see [`Config::get_lib_paths`]
 ///
 /// # Emitted Directives
 ///
 /// - `cargo:rustc-link-lib=<pkg>__rosidl_typesupport_c` - Type support library
 /// - `cargo:rustc-link-lib=<pkg>__rosidl_generator_c` - Generator library
+/// - `cargo:warning` - For a library missing from `lib_paths`
+///
+/// Nothing is emitted with the `lazy-typesupport` feature, see
+/// [`is_lazy_typesupport`](super::is_lazy_typesupport).
 ///
 /// # Example
 ///
@@ -237,11 +242,39 @@ pub(crate) fn collect_interface_files(pkg_path: PathBuf) -> Vec<PathBuf> {
 /// use oxidros_build::msg::Config;
 ///
 /// // Link libraries for std_msgs package
-/// oxidros_build::msg::emit_ros_idl("std_msgs");
+/// let config = Config::builder().build();
+/// oxidros_build::msg::emit_ros_idl("std_msgs", &config.get_lib_paths());
 /// ```
-pub(crate) fn emit_ros_idl(pkg: &str) {
-    println!("cargo:rustc-link-lib={pkg}__rosidl_typesupport_c");
-    println!("cargo:rustc-link-lib={pkg}__rosidl_generator_c");
+pub(crate) fn emit_ros_idl(pkg: &str, lib_paths: &[PathBuf]) {
+    if super::is_lazy_typesupport() {
+        return;
+    }
+    for library in ["rosidl_typesupport_c", "rosidl_generator_c"] {
+        let name = format!("{pkg}__{library}");
+        if !lib_paths.is_empty() && !lib_paths.iter().any(|dir| has_library(dir, &name)) {
+            println!(
+                "cargo:warning=package {pkg} typesupport not found in AMENT_PREFIX_PATH: \
+                 no {name} library in {lib_paths:?}"
+            );
+            println!(
+                "cargo:warning=Install {pkg}, or enable the `lazy-typesupport` feature \
+                 to load it at runtime"
+            );
+        }
+        println!("cargo:rustc-link-lib={name}");
+    }
+}
+
+/// Returns true if `dir` holds the library `name`, shared or static.
+fn has_library(dir: &Path, name: &str) -> bool {
+    [
+        format!("lib{name}.so"),
+        format!("lib{name}.dylib"),
+        format!("lib{name}.a"),
+        format!("{name}.lib"),
+    ]
+    .iter()
+    .any(|file| dir.join(file).is_file())
 }
 
 /// Creates a base generator configured with the provided [`Config`].
@@ -302,6 +335,11 @@ pub fn get_base_generator(config: &Config) -> Option<Generator> {
     // Detect ROS2 availability
     let availability = detect_ros_availability(config);
     let is_sourced = availability.is_sourced();
+    let lib_paths = if is_sourced {
+        config.get_lib_paths()
+    } else {
+        Vec::new()
+    };
 
     let share_paths = match availability {
         RosAvailability::Sourced { share_paths } => {
@@ -349,7 +387,7 @@ pub fn get_base_generator(config: &Config) -> Option<Generator> {
                     if !files.is_empty() {
                         // Only emit link directives when ROS is fully sourced
                         if is_sourced {
-                            emit_ros_idl(&pkg_name);
+                            emit_ros_idl(&pkg_name, &lib_paths);
                         }
                         packages_found.push(pkg_name.to_string());
                         all_files.extend(files);
@@ -76,6 +76,16 @@
 //! - `Ros2Msg` derive macro implementations for type support
 //! - Proper module hierarchy matching the ROS2 package structure
 //!
+//! # Missing Packages
+//!
+//! With a sourced ROS2 environment, the `rosidl_typesupport_c` and
+//! `rosidl_generator_c` libraries of every generated package are linked. A
+//! library missing from the library directories of `AMENT_PREFIX_PATH` is
+//! reported as a `package <name> typesupport not found in AMENT_PREFIX_PATH`
+//! warning before the link fails. A crate with a `lazy-typesupport` feature
+//! forwarding to `ros2-types/lazy-typesupport` loads them at runtime instead,
+//! see [`is_lazy_typesupport`].
+//!
 //! # Interface File Priority
 //!
 //! When both `.idl` and native (`.msg`, `.srv`, `.action`) files exist for the
@@ -101,6 +111,15 @@ pub fn is_ros2_sourced() -> bool {
     std::env::var("ROS_DISTRO").is_ok()
 }
 
+/// Returns true if the crate being built enables its `lazy-typesupport` feature.
+///
+/// The rosidl libraries of the message packages are then loaded at runtime by
+/// `ros2_types::typesupport` instead of being linked, and no link directives
+/// are emitted for them.
+pub fn is_lazy_typesupport() -> bool {
+    std::env::var_os("CARGO_FEATURE_LAZY_TYPESUPPORT").is_some()
+}
+
 // Re-export public API
 pub use config::{Config, ConfigBuilder};
 pub use generator::{
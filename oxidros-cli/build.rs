@@ -271,7 +271,21 @@ fn convert_field(field: &ros2msg::msg::Field) -> (FieldDesc, Option<String>) {
     // Try primitive type
     if let Some(base_id) = primitive_type_id(type_name) {
         let ft = if field.field_type.is_array {
-            make_container_type(base_id, &field.field_type, "")
+            // Sequences and arrays of bounded strings: string<=M[], string<=M[N]
+            match (type_name, bt.string_upper_bound) {
+                ("string" | "wstring", Some(bound)) => {
+                    let tid = if type_name == "string" {
+                        FIELD_TYPE_BOUNDED_STRING
+                    } else {
+                        FIELD_TYPE_BOUNDED_WSTRING
+                    };
+                    FieldTypeDesc {
+                        string_capacity: bound as u64,
+                        ..make_container_type(tid, &field.field_type, "")
+                    }
+                }
+                _ => make_container_type(base_id, &field.field_type, ""),
+            }
         } else if (type_name == "string" || type_name == "wstring")
             && bt.string_upper_bound.is_some()
         {
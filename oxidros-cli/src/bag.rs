@@ -1,13 +1,23 @@
 use clap::Subcommand;
 use mcap::WriteOptions;
 use mcap::records::MessageHeader;
-use oxidros_zenoh::{Context, GraphCache};
-use std::collections::{BTreeMap, HashSet};
+use oxidros_zenoh::parameter::{Parameters, Value};
+use oxidros_zenoh::{Context, GraphCache, QosMapping};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
 use std::io::BufWriter;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
+use zenoh_ext::{AdvancedSubscriberBuilderExt, HistoryConfig};
+
+/// Name of the node declared by `bag record`.
+///
+/// Its `all`, `topics` and `regex` parameters select the recorded topics and
+/// can be changed while recording, e.g.
+/// `ros2 param set /rosbag2_recorder topics "[/chatter, /odom]"`.
+const RECORDER_NODE: &str = "rosbag2_recorder";
 
 #[derive(Subcommand)]
 pub enum BagCommand {
@@ -19,6 +29,9 @@ pub enum BagCommand {
         /// Record all topics
         #[arg(short, long)]
         all: bool,
+        /// Record the topics whose name matches this regular expression
+        #[arg(short = 'e', long)]
+        regex: Option<String>,
         /// Output file path
         #[arg(short, long, default_value = "rosbag2.mcap")]
         output: PathBuf,
@@ -53,15 +66,19 @@ pub enum BagCommand {
     },
 }
 
-pub async fn run(cmd: BagCommand, ctx: &Context) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn run(cmd: BagCommand, ctx: &Arc<Context>) -> Result<(), Box<dyn std::error::Error>> {
     match cmd {
         BagCommand::Record {
             topics,
             all,
+            regex,
             output,
             compression,
             duration,
-        } => record(ctx, topics, all, output, &compression, duration.as_deref()).await,
+        } => {
+            let selection = TopicSelection::new(all, topics, regex.as_deref().unwrap_or(""))?;
+            record(ctx, selection, output, &compression, duration.as_deref()).await
+        }
         BagCommand::Play {
             path,
             rate,
@@ -77,16 +94,98 @@ pub async fn run(cmd: BagCommand, ctx: &Context) -> Result<(), Box<dyn std::erro
 // bag record
 // ============================================================================
 
-async fn record(
-    ctx: &Context,
-    topics: Vec<String>,
+/// Topics selected for recording.
+struct TopicSelection {
+    /// Record all topics.
     all: bool,
+    /// Topics recorded by name.
+    topics: Vec<String>,
+    /// Regular expression of the `--regex` option (empty if not set).
+    pattern: String,
+    /// Topics recorded when their whole name matches the pattern.
+    regex: Option<Regex>,
+}
+
+impl TopicSelection {
+    fn new(all: bool, topics: Vec<String>, pattern: &str) -> Result<Self, regex::Error> {
+        let regex = if pattern.is_empty() {
+            None
+        } else {
+            Some(Regex::new(&format!("^(?:{pattern})$"))?)
+        };
+        Ok(Self {
+            all,
+            topics,
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+
+    /// Read the selection from the parameters of the recorder node.
+    fn from_parameters(params: &Parameters) -> Result<Self, regex::Error> {
+        let all = matches!(
+            params.get_parameter("all").map(|p| &p.value),
+            Some(Value::Bool(true))
+        );
+        let topics = match params.get_parameter("topics").map(|p| &p.value) {
+            Some(Value::VecString(topics)) => topics.clone(),
+            _ => Vec::new(),
+        };
+        let pattern = match params.get_parameter("regex").map(|p| &p.value) {
+            Some(Value::String(pattern)) => pattern.as_str(),
+            _ => "",
+        };
+        Self::new(all, topics, pattern)
+    }
+
+    /// Declare the selection as parameters of the recorder node.
+    fn declare(&self, params: &mut Parameters) -> oxidros_zenoh::Result<()> {
+        params.set_parameter(
+            "all".to_string(),
+            Value::Bool(self.all),
+            false,
+            Some("Record all topics".to_string()),
+        )?;
+        params.set_parameter(
+            "topics".to_string(),
+            Value::VecString(self.topics.clone()),
+            false,
+            Some("Topics to record".to_string()),
+        )?;
+        params.set_parameter(
+            "regex".to_string(),
+            Value::String(self.pattern.clone()),
+            false,
+            Some("Record the topics matching this regular expression".to_string()),
+        )?;
+        Ok(())
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.all && self.topics.is_empty() && self.regex.is_none()
+    }
+
+    fn matches(&self, topic: &str) -> bool {
+        self.all
+            || self.topics.iter().any(|t| t == topic)
+            || self
+                .regex
+                .as_ref()
+                .is_some_and(|regex| regex.is_match(topic))
+    }
+}
+
+async fn record(
+    ctx: &Arc<Context>,
+    mut selection: TopicSelection,
     output: PathBuf,
     compression: &str,
     duration_str: Option<&str>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    if topics.is_empty() && !all {
-        return Err("Specify topics to record, or use --all (-a) for all topics".into());
+    if selection.is_empty() {
+        return Err(
+            "Specify topics to record, a --regex (-e), or use --all (-a) for all topics".into(),
+        );
     }
 
     let max_duration = duration_str.map(parse_duration).transpose()?;
@@ -115,8 +214,17 @@ async fn record(
     // Fan all subscribers into a single mpsc channel
     let (tx, mut rx) = tokio::sync::mpsc::channel::<(u16, Vec<u8>, i64)>(256);
 
-    // Track which topics are already subscribed (by topic name)
-    let mut subscribed: HashSet<String> = HashSet::new();
+    // MCAP channel of each recorded topic, kept when a topic is removed so
+    // that it is reused if the topic is added again
+    let mut channels: HashMap<String, u16> = HashMap::new();
+    // Subscription task of each topic being recorded
+    let mut subscribed: HashMap<String, tokio::task::JoinHandle<()>> = HashMap::new();
+
+    // The recorder node serves the selection as parameters, so that topics
+    // can be added and removed while recording
+    let node = ctx.z_create_node(RECORDER_NODE, None)?;
+    let mut param_server = node.create_parameter_server()?;
+    selection.declare(&mut param_server.params.write())?;
 
     // Install Ctrl+C handler
     let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel::<()>();
@@ -138,10 +246,15 @@ async fn record(
         "Recording to {} ... Press Ctrl+C to stop.",
         output.display()
     );
-    if all {
+    if selection.all {
         eprintln!("Waiting for topics (--all mode, discovering dynamically)...");
+    } else if selection.regex.is_some() {
+        eprintln!("Waiting for topics matching the regex (discovering dynamically)...");
     } else {
-        eprintln!("Waiting for {} topic(s) to appear...", topics.len());
+        eprintln!(
+            "Waiting for {} topic(s) to appear...",
+            selection.topics.len()
+        );
     }
 
     // Poll interval for discovering new topics
@@ -170,48 +283,80 @@ async fn record(
                     }
                 }
             }
+            // Selection changed through the parameters of the recorder node
+            updated = param_server.wait() => {
+                updated?;
+                let changed = TopicSelection::from_parameters(&param_server.params.read());
+                match changed {
+                    Ok(changed) => selection = changed,
+                    Err(e) => eprintln!("  Warning: invalid regex, selection unchanged: {e}"),
+                }
+            }
             // Periodically discover new topics
             _ = poll_interval.tick() => {
+                // Stop recording the topics removed from the selection
+                subscribed.retain(|topic_name, task| {
+                    let keep = selection.matches(topic_name);
+                    if !keep {
+                        task.abort();
+                        eprintln!("  - {topic_name}");
+                    }
+                    keep
+                });
+
                 let graph = ctx.graph_cache();
-                let newly_discovered = discover_new_topics(&graph, &topics, all, &subscribed);
+                let newly_discovered = discover_new_topics(&graph, &selection, &subscribed);
 
-                for (topic_name, dds_type, type_hash) in newly_discovered {
+                for (topic_name, dds_type, type_hash, history_depth) in newly_discovered {
                     // Register schema + channel in MCAP
                     let ros_type = crate::type_resolve::dds_to_ros_type_name(&dds_type)
                         .unwrap_or_else(|| dds_type.clone());
 
-                    let schema_data =
-                        match crate::type_resolve::resolve(&dds_type, ctx).await
-                        {
-                            Some(type_desc) => type_desc.to_msg_definition(),
-                            None => {
-                                eprintln!(
-                                    "  Warning: no type description for {dds_type}, recording without schema"
-                                );
-                                String::new()
-                            }
-                        };
-
-                    let schema_id = if schema_data.is_empty() {
-                        0u16
-                    } else {
-                        writer.add_schema(&ros_type, "ros2msg", schema_data.as_bytes())?
+                    let channel_id = match channels.get(&topic_name) {
+                        Some(&channel_id) => channel_id,
+                        None => {
+                            let schema_data =
+                                match crate::type_resolve::resolve(&dds_type, ctx).await
+                                {
+                                    Some(type_desc) => type_desc.to_msg_definition(),
+                                    None => {
+                                        eprintln!(
+                                            "  Warning: no type description for {dds_type}, recording without schema"
+                                        );
+                                        String::new()
+                                    }
+                                };
+
+                            let schema_id = if schema_data.is_empty() {
+                                0u16
+                            } else {
+                                writer.add_schema(&ros_type, "ros2msg", schema_data.as_bytes())?
+                            };
+
+                            let mut metadata = BTreeMap::new();
+                            metadata.insert("dds_type".to_string(), dds_type.clone());
+                            metadata.insert("type_hash".to_string(), type_hash.clone());
+                            let channel_id =
+                                writer.add_channel(schema_id, &topic_name, "cdr", &metadata)?;
+                            channels.insert(topic_name.clone(), channel_id);
+                            channel_id
+                        }
                     };
 
-                    let mut metadata = BTreeMap::new();
-                    metadata.insert("dds_type".to_string(), dds_type.clone());
-                    metadata.insert("type_hash".to_string(), type_hash.clone());
-                    let channel_id =
-                        writer.add_channel(schema_id, &topic_name, "cdr", &metadata)?;
-
-                    // Subscribe to the Zenoh key expression
+                    // Subscribe to the Zenoh key expression, querying the
+                    // cache of transient_local publishers
                     let name = topic_name.strip_prefix('/').unwrap_or(&topic_name);
                     let key_expr =
                         format!("{}/{}/{}/{}", ctx.domain_id(), name, dds_type, type_hash);
-                    match ctx.session().declare_subscriber(&key_expr).await {
+                    let sub = ctx
+                        .session()
+                        .declare_subscriber(&key_expr)
+                        .history(HistoryConfig::default().max_samples(history_depth))
+                        .await;
+                    match sub {
                         Ok(sub) => {
                             let tx = tx.clone();
-                            tokio::spawn(async move {
+                            let task = tokio::spawn(async move {
                                 while let Ok(sample) = sub.recv_async().await {
                                     let payload = sample.payload().to_bytes().to_vec();
                                     let timestamp_ns = extract_timestamp(&sample);
@@ -220,14 +365,17 @@ async fn record(
                                     }
                                 }
                             });
-                            eprintln!("  + {topic_name} [{ros_type}]");
+                            if history_depth > 0 {
+                                eprintln!("  + {topic_name} [{ros_type}] (transient_local)");
+                            } else {
+                                eprintln!("  + {topic_name} [{ros_type}]");
+                            }
+                            subscribed.insert(topic_name, task);
                         }
                         Err(e) => {
                             eprintln!("  Warning: subscribe to {topic_name} failed: {e}");
                         }
                     }
-
-                    subscribed.insert(topic_name);
                 }
             }
             // Ctrl+C
@@ -256,7 +404,7 @@ async fn record(
         "Recorded {message_count} messages in {:.1}s to {} ({} topic(s))",
         elapsed.as_secs_f64(),
         output.display(),
-        subscribed.len()
+        channels.len()
     );
 
     Ok(())
@@ -277,40 +425,46 @@ fn extract_timestamp(sample: &zenoh::sample::Sample) -> i64 {
         .unwrap_or(0)
 }
 
-/// Discover topics that are not yet subscribed.
+/// Discover selected topics that are not yet subscribed.
+///
+/// Returns the topic name, DDS type, type hash and history depth to query.
 #[allow(clippy::type_complexity)]
-fn discover_new_topics(
+fn discover_new_topics<T>(
     graph: &GraphCache,
-    topic_filter: &[String],
-    all: bool,
-    already_subscribed: &HashSet<String>,
-) -> Vec<(String, String, String)> {
+    selection: &TopicSelection,
+    already_subscribed: &HashMap<String, T>,
+) -> Vec<(String, String, String, usize)> {
     let mut result = Vec::new();
 
-    if all {
-        let topics = graph.get_topic_names_and_types();
-        for (topic, _type_name) in topics {
-            if already_subscribed.contains(&topic) {
-                continue;
-            }
-            if let Some((dds_type, hash)) = find_topic_type(graph, &topic) {
-                result.push((topic, dds_type, hash));
-            }
+    for (topic, _type_name) in graph.get_topic_names_and_types() {
+        if already_subscribed.contains_key(&topic) || !selection.matches(&topic) {
+            continue;
         }
-    } else {
-        for topic in topic_filter {
-            if already_subscribed.contains(topic) {
-                continue;
-            }
-            if let Some((dds_type, hash)) = find_topic_type(graph, topic) {
-                result.push((topic.clone(), dds_type, hash));
-            }
+        if let Some((dds_type, hash)) = find_topic_type(graph, &topic) {
+            let depth = history_depth(graph, &topic);
+            result.push((topic, dds_type, hash, depth));
         }
     }
 
     result
 }
 
+/// Number of cached samples to query from the publishers of a topic.
+///
+/// The offered QoS of the publishers is read from their liveliness tokens.
+/// Samples are only cached by transient_local publishers, so the history of
+/// a topic is queried up to the deepest of their caches.
+fn history_depth(graph: &GraphCache, topic: &str) -> usize {
+    graph
+        .get_publishers_info(topic)
+        .into_iter()
+        .filter_map(|e| e.qos.as_ref())
+        .filter(|qos| QosMapping::is_transient_local(qos))
+        .map(QosMapping::effective_depth)
+        .max()
+        .unwrap_or(0)
+}
+
 /// Find DDS type name and hash for a topic (prefers publishers).
 fn find_topic_type(graph: &GraphCache, topic: &str) -> Option<(String, String)> {
     let entities = graph.get_publishers_info(topic);
@@ -469,6 +623,7 @@ async fn play(
                 sequence_number: *seq,
                 timestamp_ns: msg.publish_time as i64,
                 gid,
+                ..Default::default()
             };
             *seq += 1;
 
@@ -0,0 +1,86 @@
+use crate::{type_registry, type_resolve::dds_to_ros_type_name};
+use clap::Subcommand;
+use ros2_types::compat::{self, CompatReport, InstalledDescription};
+use ros2_types::types::TypeDescriptionMsg;
+
+#[derive(Subcommand)]
+pub enum InterfaceCommand {
+    /// Compare built-in type descriptions with the ones installed in the
+    /// active ROS2 distro (AMENT_PREFIX_PATH)
+    Compat {
+        /// Fully qualified type names (e.g. sensor_msgs/msg/Imu).
+        /// Checks every built-in type if none is given.
+        types: Vec<String>,
+    },
+}
+
+pub fn run(cmd: InterfaceCommand) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd {
+        InterfaceCommand::Compat { types } => compat(&types),
+    }
+}
+
+fn compat(types: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let check_all = types.is_empty();
+    let mut type_names: Vec<String> = if check_all {
+        type_registry::dds_names()
+            .filter_map(dds_to_ros_type_name)
+            .collect()
+    } else {
+        types.to_vec()
+    };
+    type_names.sort();
+
+    let mut incompatible = 0;
+    let mut not_installed = 0;
+    for type_name in &type_names {
+        let Some(compiled) = lookup(type_name) else {
+            return Err(format!("Unknown type: {type_name}").into());
+        };
+        let Some(report) = check(compiled)? else {
+            not_installed += 1;
+            if !check_all {
+                println!("{type_name}: no installed type description");
+            }
+            continue;
+        };
+        if !report.is_compatible() {
+            incompatible += 1;
+        }
+        if !check_all || !report.is_compatible() {
+            print!("{report}");
+        }
+    }
+
+    if check_all {
+        println!(
+            "{} types checked, {incompatible} incompatible, {not_installed} without installed description",
+            type_names.len()
+        );
+    }
+    if incompatible > 0 {
+        return Err(format!("{incompatible} incompatible types").into());
+    }
+    Ok(())
+}
+
+/// Look up a built-in type by its ROS2 name (`"pkg/msg/Type"`).
+fn lookup(type_name: &str) -> Option<&'static TypeDescriptionMsg> {
+    let parts: Vec<&str> = type_name.split('/').collect();
+    let [pkg, iface, name] = parts.as_slice() else {
+        return None;
+    };
+    type_registry::lookup_dds(&format!("{pkg}::{iface}::dds_::{name}_"))
+}
+
+/// Compare a built-in type with the installed one, if any.
+fn check(
+    compiled: &TypeDescriptionMsg,
+) -> Result<Option<CompatReport>, Box<dyn std::error::Error>> {
+    let Some(path) = compat::find_installed_description(&compiled.type_description.type_name)
+    else {
+        return Ok(None);
+    };
+    let installed = InstalledDescription::from_json(&std::fs::read_to_string(path)?)?;
+    Ok(Some(compat::compare(compiled, &installed)?))
+}
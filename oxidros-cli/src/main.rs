@@ -1,6 +1,7 @@
 use clap::{Parser, Subcommand};
 
 mod bag;
+mod interface;
 mod node;
 mod param;
 mod service;
@@ -50,6 +51,11 @@ enum Commands {
         #[command(subcommand)]
         action: bag::BagCommand,
     },
+    /// Interface type commands
+    Interface {
+        #[command(subcommand)]
+        action: interface::InterfaceCommand,
+    },
 }
 
 /// Wait briefly for graph discovery to populate.
@@ -69,6 +75,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let cli = Cli::parse();
 
+    // Interface commands only inspect local type descriptions
+    let command = match cli.command {
+        Commands::Interface { action } => return interface::run(action),
+        command => command,
+    };
+
     // Create Zenoh context
     let ctx = if cli.domain_id != 0 {
         oxidros_zenoh::Context::with_domain_id(cli.domain_id)?
@@ -81,11 +93,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let graph = ctx.graph_cache();
 
-    match cli.command {
+    match command {
         Commands::Node { action } => node::run(action, &graph),
         Commands::Topic { action } => topic::run(action, &ctx).await,
         Commands::Service { action } => service::run(action, &ctx).await,
         Commands::Param { action } => param::run(action, &ctx).await,
         Commands::Bag { action } => bag::run(action, &ctx).await,
+        Commands::Interface { .. } => unreachable!(),
     }
 }
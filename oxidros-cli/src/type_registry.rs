@@ -28,3 +28,8 @@ static CACHE: LazyLock<HashMap<&'static str, TypeDescriptionMsg>> = LazyLock::ne
 pub fn lookup_dds(dds_name: &str) -> Option<&'static TypeDescriptionMsg> {
     CACHE.get(dds_name)
 }
+
+/// Iterate over the DDS names of all types in the registry.
+pub fn dds_names() -> impl Iterator<Item = &'static str> {
+    CACHE.keys().copied()
+}
@@ -1,4 +1,18 @@
 //! Action-related types and enums.
+//!
+//! An action is made of three services and two topics, named after the
+//! action with an `_action` infix as defined by `rcl_action`:
+//!
+//! | Entity        | Name                           |
+//! |---------------|--------------------------------|
+//! | goal service  | `<action>/_action/send_goal`   |
+//! | cancel service| `<action>/_action/cancel_goal` |
+//! | result service| `<action>/_action/get_result`  |
+//! | feedback topic| `<action>/_action/feedback`    |
+//! | status topic  | `<action>/_action/status`      |
+//!
+//! [`ActionNames`] gives these names, e.g. to inspect the entities with
+//! `ros2 topic` or to set `qos_overrides` parameters for them.
 
 /// Status of an action goal.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -87,3 +101,77 @@ impl From<GoalEvent> for i8 {
         event as i8
     }
 }
+
+/// Infix between the action name and the names of its entities.
+pub const ACTION_INFIX: &str = "/_action/";
+
+/// Names of the services and topics of an action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionNames {
+    /// Name of the action, e.g. `/navigate_to_pose`.
+    pub action: String,
+    /// Name of the goal service.
+    pub send_goal: String,
+    /// Name of the cancel service.
+    pub cancel_goal: String,
+    /// Name of the result service.
+    pub get_result: String,
+    /// Name of the feedback topic.
+    pub feedback: String,
+    /// Name of the status topic.
+    pub status: String,
+}
+
+impl ActionNames {
+    /// Derive the names of the entities of `action_name`.
+    ///
+    /// Pass the fully qualified action name to get fully qualified names.
+    pub fn new(action_name: &str) -> Self {
+        let entity = |suffix: &str| format!("{action_name}{ACTION_INFIX}{suffix}");
+        Self {
+            action: action_name.to_string(),
+            send_goal: entity("send_goal"),
+            cancel_goal: entity("cancel_goal"),
+            get_result: entity("get_result"),
+            feedback: entity("feedback"),
+            status: entity("status"),
+        }
+    }
+
+    /// Get the action name from the name of one of its entities.
+    ///
+    /// Returns `None` if `name` is not the name of an action entity.
+    pub fn action_of(name: &str) -> Option<&str> {
+        let (action, entity) = name.rsplit_once(ACTION_INFIX)?;
+        matches!(
+            entity,
+            "send_goal" | "cancel_goal" | "get_result" | "feedback" | "status"
+        )
+        .then_some(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_names() {
+        let names = ActionNames::new("/robot/navigate");
+        assert_eq!(names.send_goal, "/robot/navigate/_action/send_goal");
+        assert_eq!(names.cancel_goal, "/robot/navigate/_action/cancel_goal");
+        assert_eq!(names.get_result, "/robot/navigate/_action/get_result");
+        assert_eq!(names.feedback, "/robot/navigate/_action/feedback");
+        assert_eq!(names.status, "/robot/navigate/_action/status");
+
+        assert_eq!(
+            ActionNames::action_of(&names.feedback),
+            Some("/robot/navigate")
+        );
+        assert_eq!(
+            ActionNames::action_of("/robot/navigate/_action/other"),
+            None
+        );
+        assert_eq!(ActionNames::action_of("/chatter"), None);
+    }
+}
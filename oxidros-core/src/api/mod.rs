@@ -8,11 +8,50 @@
 //!
 //! - [`RosContext`] - Factory for creating nodes
 //! - [`RosNode`] - Creates publishers, subscribers, clients, and servers
+//! - [`NodeHandle`] - Clonable node handle creating entities through `&self`
 //! - [`RosPublisher`] - Publishes messages to a topic
 //! - [`RosSubscriber`] - Receives messages from a topic
 //! - [`RosClient`] - Sends service requests and receives responses
 //! - [`RosServer`] - Receives service requests and sends responses
 //!
+//! # Cancel Safety
+//!
+//! [`RosSubscriber::recv`], [`RosSubscriber::recv_raw`] and [`RosServer::recv`]
+//! are cancel safe: a message or request is only taken from the middleware
+//! by the poll that returns it, so dropping the future, e.g. in the branch of
+//! a `tokio::select!` that did not complete, loses nothing and the next call
+//! returns it. The wakeup registered by a pending future is removed when it
+//! is dropped.
+//!
+//! [`RosClient::call`] is cancel safe in the sense that dropping the future
+//! abandons the request: its response, if any, is discarded and never
+//! returned by a later call.
+//!
+//! ```ignore
+//! loop {
+//!     tokio::select! {
+//!         msg = subscriber.recv() => handle(msg?),
+//!         _ = interval.tick() => publish_status()?,
+//!     }
+//! }
+//! ```
+//!
+//! # Errors
+//!
+//! Errors of publishing, taking messages and requests, calling services and
+//! sending responses are wrapped in
+//! [`Error::Context`](crate::Error::Context), naming the node, the topic or
+//! service, and the operation. Match on [`Error::root`](crate::Error::root)
+//! to handle the underlying error:
+//!
+//! ```ignore
+//! match subscriber.recv().await {
+//!     Ok(msg) => handle(msg),
+//!     Err(e) if matches!(e.root(), Error::ChannelClosed) => return Ok(()),
+//!     Err(e) => tracing::warn!("{e}"),
+//! }
+//! ```
+//!
 //! # Example
 //!
 //! ```ignore
@@ -25,11 +64,21 @@
 //! }
 //! ```
 
+#[cfg(unix)]
+use crate::selector::{FdInterest, FdReadiness};
 use crate::{
-    ActionMsg, Result, ServiceMsg, TypeDescription, TypeSupport, message::Message, qos::Profile,
+    ActionMsg, NetworkFlowEndpoint, Result, ServiceMsg, TypeDescription, TypeSupport,
+    message::{Message, RequestInfo},
+    qos::{Profile, QosCompatibility},
+    selector::PanicPolicy,
+    service::ServerOptions,
+    shutdown::ShutdownToken,
+    stats::NodeStats,
+    thread::{ThreadOptions, ThreadPlacement},
+    time::VirtualClock,
 };
 use futures_core::Stream;
-use std::{borrow::Cow, pin::Pin, sync::Arc, time::Duration};
+use std::{borrow::Cow, future::Future, pin::Pin, sync::Arc, time::Duration};
 
 // ============================================================================
 // Common Types
@@ -42,6 +91,10 @@ pub trait ServiceRequest<T: ServiceMsg>: Send {
     /// Get the request data.
     fn request(&self) -> &T::Request;
 
+    /// Get the metadata of the request: client GID, sequence number and
+    /// timestamps.
+    fn info(&self) -> RequestInfo;
+
     /// Send a response back to the client.
     ///
     /// Consumes self to ensure only one response is sent.
@@ -84,6 +137,25 @@ pub trait RosContext: Send + Sync + Sized {
 
     /// Get the domain ID.
     fn ros_domain_id(&self) -> u32;
+
+    /// Get the identifier of the middleware implementation, e.g. `rmw_fastrtps_cpp`.
+    fn rmw_implementation(&self) -> &str;
+
+    /// Check whether a publisher and a subscription with the given QoS
+    /// profiles match on this middleware.
+    fn rmw_qos_compatibility_check(
+        &self,
+        publisher: &Profile,
+        subscription: &Profile,
+    ) -> Result<QosCompatibility>;
+
+    /// Get a token triggered when the process receives a termination signal.
+    ///
+    /// See [`ShutdownToken::on_signal`]. Backends which handle the signals
+    /// themselves return [`ShutdownToken::signal_token`] instead.
+    fn shutdown_token(&self) -> ShutdownToken {
+        ShutdownToken::on_signal()
+    }
 }
 
 // ============================================================================
@@ -114,6 +186,10 @@ pub trait RosNode: Send + Sync + Sized {
     /// Get the fully qualified node name (namespace + name).
     fn fully_qualified_name(&self) -> Result<String>;
 
+    /// Get the number of entities of the node and the state of its
+    /// subscriber queues.
+    fn stats(&self) -> NodeStats;
+
     /// Create a publisher.
     ///
     /// # Arguments
@@ -161,6 +237,149 @@ pub trait RosNode: Send + Sync + Sized {
         service_name: &str,
         qos: Option<Profile>,
     ) -> Result<Self::Server<T>>;
+
+    /// Create a service server with an explicit request queue configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_name` - Service name (can be relative or absolute)
+    /// * `qos` - Optional QoS profile (uses default if None)
+    /// * `options` - Size of the request queue and policy when it is full
+    ///
+    /// # Errors
+    ///
+    /// Backends which cannot bound the request queue return
+    /// `Error::NotImplemented` for options other than the default.
+    fn create_server_with_options<T: ServiceMsg>(
+        self: &Arc<Self>,
+        service_name: &str,
+        qos: Option<Profile>,
+        options: ServerOptions,
+    ) -> Result<Self::Server<T>>;
+
+    /// Get a clonable handle to this node.
+    fn handle(self: &Arc<Self>) -> NodeHandle<Self> {
+        NodeHandle::new(self.clone())
+    }
+}
+
+// ============================================================================
+// Node Handle
+// ============================================================================
+
+/// A cheaply clonable handle to a node, creating entities through `&self`.
+///
+/// Entities keep their node alive, so the constructors of [`RosNode`] take
+/// `self: &Arc<Self>`. A handle owns that `Arc`, so it can be stored in a
+/// struct field and create entities from `&self` methods of the struct. The
+/// node itself is reachable through `Deref`.
+///
+/// ```ignore
+/// struct Driver {
+///     node: NodeHandle<Node>,
+///     sensors: Vec<Publisher<Range>>,
+/// }
+///
+/// impl Driver {
+///     fn add_sensor(&mut self, topic: &str) -> Result<()> {
+///         self.sensors.push(self.node.create_publisher(topic, None)?);
+///         Ok(())
+///     }
+/// }
+/// ```
+pub struct NodeHandle<N>(Arc<N>);
+
+impl<N> NodeHandle<N> {
+    /// Create a handle to `node`.
+    pub fn new(node: Arc<N>) -> Self {
+        Self(node)
+    }
+
+    /// Get the `Arc` of the node.
+    pub fn node(&self) -> &Arc<N> {
+        &self.0
+    }
+
+    /// Take the `Arc` of the node.
+    pub fn into_inner(self) -> Arc<N> {
+        self.0
+    }
+}
+
+impl<N: RosNode> NodeHandle<N> {
+    /// Create a publisher, see [`RosNode::create_publisher`].
+    pub fn create_publisher<T: TypeSupport + TypeDescription>(
+        &self,
+        topic_name: &str,
+        qos: Option<Profile>,
+    ) -> Result<N::Publisher<T>> {
+        self.0.create_publisher(topic_name, qos)
+    }
+
+    /// Create a subscriber, see [`RosNode::create_subscriber`].
+    pub fn create_subscriber<T: TypeSupport + TypeDescription>(
+        &self,
+        topic_name: &str,
+        qos: Option<Profile>,
+    ) -> Result<N::Subscriber<T>> {
+        self.0.create_subscriber(topic_name, qos)
+    }
+
+    /// Create a service client, see [`RosNode::create_client`].
+    pub fn create_client<T: ServiceMsg>(
+        &self,
+        service_name: &str,
+        qos: Option<Profile>,
+    ) -> Result<N::Client<T>> {
+        self.0.create_client(service_name, qos)
+    }
+
+    /// Create a service server, see [`RosNode::create_server`].
+    pub fn create_server<T: ServiceMsg>(
+        &self,
+        service_name: &str,
+        qos: Option<Profile>,
+    ) -> Result<N::Server<T>> {
+        self.0.create_server(service_name, qos)
+    }
+
+    /// Create a service server with an explicit request queue configuration,
+    /// see [`RosNode::create_server_with_options`].
+    pub fn create_server_with_options<T: ServiceMsg>(
+        &self,
+        service_name: &str,
+        qos: Option<Profile>,
+        options: ServerOptions,
+    ) -> Result<N::Server<T>> {
+        self.0
+            .create_server_with_options(service_name, qos, options)
+    }
+}
+
+impl<N> Clone for NodeHandle<N> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<N> std::ops::Deref for NodeHandle<N> {
+    type Target = N;
+
+    fn deref(&self) -> &N {
+        &self.0
+    }
+}
+
+impl<N> From<Arc<N>> for NodeHandle<N> {
+    fn from(node: Arc<N>) -> Self {
+        Self(node)
+    }
+}
+
+impl<N: std::fmt::Debug> std::fmt::Debug for NodeHandle<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("NodeHandle").field(&self.0).finish()
+    }
 }
 
 // ============================================================================
@@ -188,6 +407,14 @@ pub trait RosPublisher<T: TypeSupport>: Send + Sync {
     /// Returns an error if the publish operation fails.
     fn send_raw(&self, data: &[u8]) -> Result<()>;
 
+    /// Get the local transport addresses used to send the messages, e.g. the
+    /// UDP ports of DDS.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the middleware does not support the query.
+    fn network_flow_endpoints(&self) -> Result<Vec<NetworkFlowEndpoint>>;
+
     /// Publish multiple messages.
     ///
     /// Default implementation calls `send` for each message.
@@ -201,6 +428,17 @@ pub trait RosPublisher<T: TypeSupport>: Send + Sync {
         }
         Ok(())
     }
+
+    /// Wait until the messages sent so far have been delivered.
+    ///
+    /// Call this before exiting so that the last messages are not lost.
+    /// Reliable publishers wait for the acknowledgment of every matched
+    /// subscriber; the default implementation returns immediately, for
+    /// backends whose `send` hands the message to the transport synchronously.
+    /// Wrap the call in a timeout to bound the wait.
+    fn flush(&self) -> impl Future<Output = Result<()>> + Send {
+        async { Ok(()) }
+    }
 }
 
 // ============================================================================
@@ -223,6 +461,8 @@ pub trait RosSubscriber<T: TypeSupport>: Send {
     /// Returns [`Message<T>`] which contains the data (copied or loaned)
     /// and metadata (sequence number, timestamp, publisher GID).
     ///
+    /// This method is cancel safe, see [Cancel Safety](self#cancel-safety).
+    ///
     /// # Errors
     ///
     /// Returns an error if deserialization fails or the subscription is closed.
@@ -243,6 +483,8 @@ pub trait RosSubscriber<T: TypeSupport>: Send {
     ///
     /// Returns the raw CDR bytes (including encapsulation header) and message metadata.
     /// Useful for message forwarding, recording, and dynamic decoding scenarios.
+    ///
+    /// This method is cancel safe, see [Cancel Safety](self#cancel-safety).
     fn recv_raw(
         &mut self,
     ) -> impl std::future::Future<Output = Result<(Vec<u8>, crate::message::MessageInfo)>> + Send;
@@ -252,16 +494,40 @@ pub trait RosSubscriber<T: TypeSupport>: Send {
     /// Returns `Ok(None)` if no message is currently available.
     fn try_recv_raw(&mut self) -> Result<Option<(Vec<u8>, crate::message::MessageInfo)>>;
 
+    /// Get the local transport addresses used to receive the messages, e.g.
+    /// the UDP ports of DDS.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the middleware does not support the query.
+    fn network_flow_endpoints(&self) -> Result<Vec<NetworkFlowEndpoint>>;
+
     /// Receive up to `limit` messages without blocking.
     ///
     /// Returns immediately with available messages, up to `limit`.
     /// Default implementation calls `try_recv` repeatedly.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`try_recv`](Self::try_recv), if it fails before any message
+    /// is received. An error after some messages is logged and stops the
+    /// batch: the messages received so far are returned.
     fn recv_many(&mut self, limit: usize) -> Result<Vec<Message<T>>> {
         let mut results = Vec::with_capacity(limit.min(64));
         while results.len() < limit {
-            match self.try_recv()? {
-                Some(msg) => results.push(msg),
-                None => break,
+            match self.try_recv() {
+                Ok(Some(msg)) => results.push(msg),
+                Ok(None) => break,
+                Err(e) if results.is_empty() => return Err(e),
+                Err(e) => {
+                    tracing::warn!(
+                        target: crate::targets::SUBSCRIBER,
+                        received = results.len(),
+                        error = %e,
+                        "Stopped receiving a batch of messages"
+                    );
+                    break;
+                }
             }
         }
         Ok(results)
@@ -290,6 +556,8 @@ pub trait RosClient<T: ServiceMsg>: Send {
     /// Send a request and wait for a response.
     ///
     /// Uses a default timeout (implementation-specific).
+    /// Dropping the future abandons the request, see
+    /// [Cancel Safety](self#cancel-safety).
     fn call(
         &mut self,
         request: &T::Request,
@@ -327,6 +595,7 @@ pub trait RosServer<T: ServiceMsg>: Send {
     /// Receive a request asynchronously.
     ///
     /// This method waits until a request is available.
+    /// This method is cancel safe, see [Cancel Safety](self#cancel-safety).
     fn recv(&mut self) -> impl std::future::Future<Output = Result<Self::Request>> + Send;
 
     /// Try to receive a request without blocking.
@@ -375,6 +644,30 @@ pub trait RosServer<T: ServiceMsg>: Send {
         Fut: std::future::Future<Output = T::Response> + Send;
 }
 
+// ============================================================================
+// Action Client Trait
+// ============================================================================
+
+/// A ROS2 action client.
+pub trait RosActionClient<T: ActionMsg> {
+    /// Receiver of the response to a cancel request, listing the goals that
+    /// are being canceled.
+    type CancelRecv<'a>
+    where
+        Self: 'a;
+
+    /// Request to cancel all the goals of the action server, including the
+    /// goals sent by other clients.
+    fn cancel_all_goals(&mut self) -> Result<Self::CancelRecv<'_>>;
+
+    /// Request to cancel all the goals accepted at or before `stamp`, the time
+    /// since the epoch of the clock of the action server.
+    ///
+    /// A zero `stamp` cancels all the goals, like
+    /// [`cancel_all_goals`](Self::cancel_all_goals).
+    fn cancel_goals_before(&mut self, stamp: Duration) -> Result<Self::CancelRecv<'_>>;
+}
+
 // ============================================================================
 // Selector Trait
 // ============================================================================
@@ -472,6 +765,33 @@ pub trait RosSelector: Sized {
     /// Remove a timer by its ID.
     fn delete_timer(&mut self, id: u64);
 
+    /// Drive the timers of the selector by `clock` instead of the monotonic
+    /// clock, e.g. to test timeouts without real sleeps.
+    ///
+    /// The timers already added keep their remaining time. A timer fires in
+    /// [`wait`](Self::wait) once the clock has been advanced past its
+    /// deadline, while the timeout of [`wait_timeout`](Self::wait_timeout)
+    /// stays in real time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotImplemented`](crate::Error::NotImplemented) if the
+    /// backend does not support virtual time.
+    fn set_virtual_clock(&mut self, clock: VirtualClock) -> Result<()> {
+        let _ = clock;
+        Err(crate::Error::NotImplemented {
+            feature: "virtual_clock".into(),
+            reason: "the timers of this backend are driven by the middleware".into(),
+        })
+    }
+
+    /// Set what the selector does when a callback panics.
+    ///
+    /// The panic is caught and logged, and the entity of the callback is
+    /// removed, so that one bad callback does not take down the others. See
+    /// [`PanicPolicy`] for the default.
+    fn set_panic_policy(&mut self, policy: PanicPolicy);
+
     /// Register an action server with handlers.
     ///
     /// # Arguments
@@ -527,4 +847,41 @@ pub trait RosSelector: Sized {
     /// - `Ok(false)` - Timeout elapsed with no events
     /// - `Err(_)` - An error occurred
     fn wait_timeout(&mut self, timeout: Duration) -> Result<bool>;
+
+    /// Wait on a file descriptor, e.g. of a serial port or a CAN socket.
+    ///
+    /// The handler is called by [`wait`](Self::wait) with the readiness of
+    /// `fd` when one of the events of `interest` occurs, so that the IO of a
+    /// driver runs on the thread of the other callbacks. The selector does not
+    /// take ownership of `fd`, which must stay open until it is removed with
+    /// [`remove_fd`](Self::remove_fd).
+    ///
+    /// # Returns
+    ///
+    /// An ID that can be used to remove the file descriptor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`](crate::Error::Io) if `fd` is not open.
+    #[cfg(unix)]
+    fn add_fd(
+        &mut self,
+        fd: std::os::fd::RawFd,
+        interest: FdInterest,
+        handler: Box<dyn FnMut(FdReadiness)>,
+    ) -> Result<u64>;
+
+    /// Stop waiting on a file descriptor added by [`add_fd`](Self::add_fd).
+    #[cfg(unix)]
+    fn remove_fd(&mut self, id: u64);
+
+    /// Pin the thread spinning this selector to CPU cores and set its
+    /// real-time priority.
+    ///
+    /// Callbacks run on the thread calling [`wait`](Self::wait), so the
+    /// options are applied to the calling thread, see
+    /// [`ThreadOptions::apply`].
+    fn set_thread_options(&mut self, options: &ThreadOptions) -> ThreadPlacement {
+        options.apply()
+    }
 }
@@ -2,6 +2,28 @@
 //!
 //! This module provides a unified error type for all oxidros operations,
 //! supporting both RCL-based and Zenoh-based implementations.
+//!
+//! Every error exposes a numeric `code()` following the `rcl_ret_t` values of
+//! the RCL C API, so callers can report failures uniformly whichever backend
+//! produced them.
+//!
+//! Errors of operations on entities (publish, take, send_response...) are
+//! wrapped in [`Error::Context`], naming the node, the topic or service, and
+//! the operation. [`Error::root`] gives the underlying error to match on:
+//!
+//! ```
+//! use oxidros_core::error::{Error, ErrorContext, Operation, RclError, ResultExt};
+//!
+//! let result: Result<(), Error> = Err(RclError::PublisherInvalid.into());
+//! let err = result
+//!     .with_context(|| ErrorContext::new(Operation::Publish, "/chatter").with_node("/talker"))
+//!     .unwrap_err();
+//! assert_eq!(
+//!     err.to_string(),
+//!     "Failed to publish on /chatter of node /talker: RCL error: Publisher is invalid"
+//! );
+//! assert!(matches!(err.root(), Error::Rcl(RclError::PublisherInvalid)));
+//! ```
 
 use thiserror::Error;
 
@@ -26,13 +48,29 @@ pub enum Error {
     #[error("Serialization error: {0}")]
     Serialization(#[from] ros2_types::Error),
 
+    /// Type support of an interface not available, e.g. its package is not
+    /// installed (`lazy-typesupport` feature).
+    #[error("Type support error: {0}")]
+    TypeSupport(ros2_types::Error),
+
+    /// Message construction or conversion error.
+    #[error("Message error: {0}")]
+    Message(#[from] MessageError),
+
     /// Zenoh middleware error.
     #[error("Zenoh error: {0}")]
     Zenoh(String),
 
     /// Invalid name (topic, node, namespace, service).
-    #[error("Invalid name: {0}")]
-    InvalidName(String),
+    #[error("Invalid name '{name}': {reason}")]
+    InvalidName {
+        /// The invalid name
+        name: String,
+        /// Character offset in `name` where the problem was found, if known
+        position: Option<usize>,
+        /// Why the name is invalid
+        reason: String,
+    },
 
     /// Operation timed out.
     #[error("Operation timed out")]
@@ -76,6 +114,10 @@ pub enum Error {
     #[error("Nul byte not found {0}")]
     NullError(#[from] std::ffi::NulError),
 
+    /// File system or other I/O error.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
     /// Generic error with message.
     #[error("{0}")]
     Other(String),
@@ -87,6 +129,34 @@ pub enum Error {
     /// Message attachment is invalid/malformed.
     #[error("Invalid attachment: {0}")]
     InvalidAttachment(String),
+
+    /// A callback panicked in a selector stopped by
+    /// [`PanicPolicy::Shutdown`](crate::selector::PanicPolicy::Shutdown).
+    #[error("Callback of {entity} panicked: {message}")]
+    CallbackPanicked {
+        /// Entity of the callback, e.g. `subscriber /chatter`
+        entity: String,
+        /// Message of the panic
+        message: String,
+    },
+
+    /// Blocking wait for a response of a service whose server cannot answer,
+    /// because its selector is running the callback which waits.
+    #[error("Waiting for {service} would deadlock: its server is blocked in the calling callback")]
+    WouldDeadlock {
+        /// Fully qualified name of the service
+        service: String,
+    },
+
+    /// Error of an operation on an entity, see [`ErrorContext`].
+    #[error("Failed to {context}: {source}")]
+    Context {
+        /// Entity and operation the error occurred in
+        context: ErrorContext,
+        /// The error, possibly with an inner context
+        #[source]
+        source: Box<Error>,
+    },
 }
 
 /// Result type using the unified Error.
@@ -121,10 +191,169 @@ impl Error {
         }
     }
 
+    /// Create an InvalidName error.
+    pub fn invalid_name(
+        name: impl Into<String>,
+        position: Option<usize>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Error::InvalidName {
+            name: name.into(),
+            position,
+            reason: reason.into(),
+        }
+    }
+
     /// Create a Zenoh error from any error type.
     pub fn zenoh(err: impl std::fmt::Display) -> Self {
         Error::Zenoh(err.to_string())
     }
+
+    /// Wrap the error with the entity and operation it occurred in.
+    pub fn with_context(self, context: ErrorContext) -> Self {
+        Error::Context {
+            context,
+            source: Box::new(self),
+        }
+    }
+
+    /// Outermost context of the error, if any.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Error::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Contexts of the error, from the outermost to the innermost.
+    pub fn contexts(&self) -> impl Iterator<Item = &ErrorContext> {
+        let mut next = Some(self);
+        std::iter::from_fn(move || match next? {
+            Error::Context { context, source } => {
+                next = Some(source);
+                Some(context)
+            }
+            _ => None,
+        })
+    }
+
+    /// Underlying error, without its contexts.
+    pub fn root(&self) -> &Error {
+        let mut err = self;
+        while let Error::Context { source, .. } = err {
+            err = source;
+        }
+        err
+    }
+
+    /// Numeric error code, following `rcl_ret_t`.
+    ///
+    /// Errors without an RCL equivalent map to `RCL_RET_ERROR` (1).
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::Context { source, .. } => source.code(),
+            Error::Rcl(e) => e.code(),
+            Error::Action(e) => e.code(),
+            Error::Message(e) => e.code(),
+            Error::Timeout => RclError::Timeout.code(),
+            Error::NotInitialized => RclError::NotInit.code(),
+            Error::InvalidName { .. } | Error::InvalidConfig(_) | Error::NullError(_) => {
+                RclError::InvalidArgument.code()
+            }
+            Error::NotImplemented { .. } => RclError::Unsupported.code(),
+            _ => RclError::Error.code(),
+        }
+    }
+}
+
+// ============================================================================
+// Error Context
+// ============================================================================
+
+/// Operation on an entity, see [`ErrorContext`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// Publish a message.
+    Publish,
+    /// Take a message from a subscriber.
+    Take,
+    /// Call a service from a client.
+    Call,
+    /// Take a request in a server.
+    TakeRequest,
+    /// Send a response from a server.
+    SendResponse,
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Operation::Publish => "publish",
+            Operation::Take => "take",
+            Operation::Call => "call",
+            Operation::TakeRequest => "take request",
+            Operation::SendResponse => "send response",
+        })
+    }
+}
+
+/// Entity and operation an error occurred in.
+///
+/// Displayed as `<operation> on <entity> of node <node>`, e.g.
+/// `take from /chatter of node /listener`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Fully qualified name of the node, if known
+    pub node: Option<String>,
+    /// Fully qualified name of the topic or service
+    pub entity: String,
+    /// Operation which failed
+    pub operation: Operation,
+}
+
+impl ErrorContext {
+    /// Create a context for `operation` on the topic or service `entity`.
+    pub fn new(operation: Operation, entity: impl Into<String>) -> Self {
+        Self {
+            node: None,
+            entity: entity.into(),
+            operation,
+        }
+    }
+
+    /// Set the node of the entity.
+    pub fn with_node(mut self, node: impl Into<String>) -> Self {
+        self.node = Some(node.into());
+        self
+    }
+}
+
+impl std::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let preposition = match self.operation {
+            Operation::Call => "",
+            Operation::Take => " from",
+            _ => " on",
+        };
+        write!(f, "{}{preposition} {}", self.operation, self.entity)?;
+        if let Some(node) = &self.node {
+            write!(f, " of node {node}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Extension trait to attach an [`ErrorContext`] to the error of a result.
+pub trait ResultExt<T> {
+    /// Wrap the error with the context returned by `f`, which is only called
+    /// on error.
+    fn with_context(self, f: impl FnOnce() -> ErrorContext) -> Result<T>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    fn with_context(self, f: impl FnOnce() -> ErrorContext) -> Result<T> {
+        self.map_err(|e| e.with_context(f()))
+    }
 }
 
 // ============================================================================
@@ -289,6 +518,52 @@ pub enum RclError {
     InvalidRetVal,
 }
 
+impl RclError {
+    /// Numeric error code, as the `rcl_ret_t` value returned by RCL.
+    pub fn code(&self) -> i32 {
+        match self {
+            RclError::Error => 1,
+            RclError::Timeout => 2,
+            RclError::Unsupported => 3,
+            RclError::BadAlloc => 10,
+            RclError::InvalidArgument => 11,
+            RclError::AlreadyInit => 100,
+            RclError::NotInit => 101,
+            RclError::MismatchedRmwId => 102,
+            RclError::TopicNameInvalid => 103,
+            RclError::ServiceNameInvalid => 104,
+            RclError::UnknownSubstitution => 105,
+            RclError::AlreadyShutdown => 106,
+            RclError::NodeInvalid => 200,
+            RclError::NodeInvalidName => 201,
+            RclError::NodeInvalidNamespace => 202,
+            RclError::NodeNameNonExistent => 203,
+            RclError::PublisherInvalid => 300,
+            RclError::SubscriptionInvalid => 400,
+            RclError::SubscriptionTakeFailed => 401,
+            RclError::ClientInvalid => 500,
+            RclError::ClientTakeFailed => 501,
+            RclError::ServiceInvalid => 600,
+            RclError::ServiceTakeFailed => 601,
+            RclError::TimerInvalid => 800,
+            RclError::TimerCanceled => 801,
+            RclError::WaitSetInvalid => 900,
+            RclError::WaitSetEmpty => 901,
+            RclError::WaitSetFull => 902,
+            RclError::InvalidRemapRule => 1001,
+            RclError::WrongLexeme => 1002,
+            RclError::InvalidRosArgs => 1003,
+            RclError::InvalidParamRule => 1010,
+            RclError::InvalidLogLevelRule => 1020,
+            RclError::EventInvalid => 2000,
+            RclError::EventTakeFailed => 2001,
+            RclError::LifecycleStateRegistered => 3000,
+            RclError::LifecycleStateNotRegistered => 3001,
+            RclError::InvalidRetVal => 1,
+        }
+    }
+}
+
 // ============================================================================
 // Action Error
 // ============================================================================
@@ -332,6 +607,22 @@ pub enum ActionError {
     #[error("Goal event is invalid")]
     GoalEventInvalid,
 
+    /// Cancel request was rejected by the action server.
+    #[error("Cancel request was rejected")]
+    CancelRejected,
+
+    /// Cancel request refers to a goal unknown to the action server.
+    #[error("Goal ID is unknown")]
+    UnknownGoalId,
+
+    /// Cancel request refers to a goal that already terminated.
+    #[error("Goal already terminated")]
+    GoalTerminated,
+
+    /// Cancel request failed with a `return_code` unknown to `action_msgs`.
+    #[error("Cancel request failed")]
+    CancelFailed,
+
     /// Wrapped RCL error.
     #[error("RCL error: {0}")]
     Rcl(#[from] RclError),
@@ -341,6 +632,103 @@ pub enum ActionError {
     InvalidRetVal,
 }
 
+impl ActionError {
+    /// Numeric error code, as the `rcl_ret_t` value returned by `rcl_action`.
+    pub fn code(&self) -> i32 {
+        match self {
+            ActionError::NameInvalid => 2000,
+            ActionError::GoalAccepted => 2100,
+            ActionError::GoalRejected => 2101,
+            ActionError::ClientInvalid => 2102,
+            ActionError::ClientTakeFailed => 2103,
+            ActionError::ServerInvalid => 2200,
+            ActionError::ServerTakeFailed => 2201,
+            ActionError::GoalHandleInvalid => 2300,
+            ActionError::GoalEventInvalid => 2301,
+            // errors of the CancelGoal response, not returned by `rcl_action`
+            ActionError::CancelRejected
+            | ActionError::UnknownGoalId
+            | ActionError::GoalTerminated
+            | ActionError::CancelFailed => 1,
+            ActionError::Rcl(e) => e.code(),
+            ActionError::InvalidRetVal => 1,
+        }
+    }
+
+    /// Error reported by the `return_code` of a `CancelGoal` response, or
+    /// `None` if the cancel request was accepted.
+    pub fn from_cancel_code(code: i8) -> Option<Self> {
+        match code {
+            0 => None,
+            1 => Some(ActionError::CancelRejected),
+            2 => Some(ActionError::UnknownGoalId),
+            3 => Some(ActionError::GoalTerminated),
+            _ => Some(ActionError::CancelFailed),
+        }
+    }
+
+    /// `return_code` of a `CancelGoal` response reporting this error, or
+    /// `None` if the error is not a cancel error.
+    ///
+    /// [`ActionError::CancelFailed`] has no code in `action_msgs` and is
+    /// reported as `-1`.
+    pub fn cancel_code(&self) -> Option<i8> {
+        match self {
+            ActionError::CancelRejected => Some(1),
+            ActionError::UnknownGoalId => Some(2),
+            ActionError::GoalTerminated => Some(3),
+            ActionError::CancelFailed => Some(-1),
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// Message Error
+// ============================================================================
+
+/// Errors raised while building or converting message data.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MessageError {
+    /// String contains a NUL character and cannot be passed to C.
+    #[error("String contains a NUL character at position {position}")]
+    InteriorNul {
+        /// Position of the first NUL character.
+        position: usize,
+    },
+
+    /// Length exceeds the bound of a bounded string or sequence.
+    #[error("Length {len} exceeds the maximum of {max}")]
+    BoundExceeded {
+        /// Requested length.
+        len: usize,
+        /// Maximum length allowed by the type.
+        max: usize,
+    },
+
+    /// The ROS2 C runtime failed to initialize the value.
+    #[error("Failed to initialize {0}")]
+    InitFailed(&'static str),
+
+    /// The ROS2 C runtime failed to assign the value.
+    #[error("Failed to assign {0}")]
+    AssignFailed(&'static str),
+}
+
+impl MessageError {
+    /// Numeric error code, following `rcl_ret_t`.
+    pub fn code(&self) -> i32 {
+        match self {
+            MessageError::InteriorNul { .. } | MessageError::BoundExceeded { .. } => {
+                RclError::InvalidArgument.code()
+            }
+            MessageError::InitFailed(_) | MessageError::AssignFailed(_) => {
+                RclError::BadAlloc.code()
+            }
+        }
+    }
+}
+
 // ============================================================================
 // Conversions
 // ============================================================================
@@ -377,6 +765,12 @@ mod tests {
 
         let err = Error::node_not_found("my_node");
         assert_eq!(format!("{}", err), "Node not found: my_node");
+
+        let err = Error::invalid_name("foo//bar", Some(4), "repeated forward slashes");
+        assert_eq!(
+            format!("{}", err),
+            "Invalid name 'foo//bar': repeated forward slashes"
+        );
     }
 
     #[test]
@@ -386,10 +780,81 @@ mod tests {
         assert!(matches!(err, Error::Rcl(RclError::Timeout)));
     }
 
+    #[test]
+    fn test_error_code() {
+        assert_eq!(Error::Timeout.code(), 2);
+        assert_eq!(Error::Rcl(RclError::WaitSetFull).code(), 902);
+        assert_eq!(Error::Action(ActionError::GoalRejected).code(), 2101);
+        assert_eq!(
+            Error::Message(MessageError::BoundExceeded { len: 12, max: 10 }).code(),
+            11
+        );
+        assert_eq!(Error::Zenoh("closed".into()).code(), 1);
+    }
+
+    #[test]
+    fn test_error_context() {
+        let err = Error::Rcl(RclError::ServiceInvalid).with_context(
+            ErrorContext::new(Operation::SendResponse, "/add_two_ints").with_node("/server"),
+        );
+        assert_eq!(
+            format!("{}", err),
+            "Failed to send response on /add_two_ints of node /server: RCL error: Service is invalid"
+        );
+        assert_eq!(err.code(), 600);
+        assert_eq!(err.context().unwrap().operation, Operation::SendResponse);
+        assert!(matches!(err.root(), Error::Rcl(RclError::ServiceInvalid)));
+        assert!(std::error::Error::source(&err).is_some());
+
+        let result: Result<()> = Err(err);
+        let err = result
+            .with_context(|| ErrorContext::new(Operation::TakeRequest, "/add_two_ints"))
+            .unwrap_err();
+        let operations: Vec<_> = err.contexts().map(|c| c.operation).collect();
+        assert_eq!(
+            operations,
+            [Operation::TakeRequest, Operation::SendResponse]
+        );
+        assert!(matches!(err.root(), Error::Rcl(RclError::ServiceInvalid)));
+
+        assert_eq!(
+            ErrorContext::new(Operation::Take, "/chatter").to_string(),
+            "take from /chatter"
+        );
+        assert_eq!(
+            ErrorContext::new(Operation::Call, "/add_two_ints")
+                .with_node("/client")
+                .to_string(),
+            "call /add_two_ints of node /client"
+        );
+
+        assert!(Error::Timeout.context().is_none());
+        assert_eq!(Error::Timeout.contexts().count(), 0);
+        assert!(matches!(Error::Timeout.root(), Error::Timeout));
+    }
+
     #[test]
     fn test_action_error_conversion() {
         let action_err = ActionError::GoalRejected;
         let err: Error = action_err.into();
         assert!(matches!(err, Error::Action(ActionError::GoalRejected)));
     }
+
+    #[test]
+    fn test_action_cancel_code() {
+        assert_eq!(ActionError::from_cancel_code(0), None);
+        for code in 1..=3 {
+            let err = ActionError::from_cancel_code(code).unwrap();
+            assert_eq!(err.cancel_code(), Some(code));
+        }
+        assert_eq!(
+            ActionError::from_cancel_code(2),
+            Some(ActionError::UnknownGoalId)
+        );
+        assert_eq!(
+            ActionError::from_cancel_code(42),
+            Some(ActionError::CancelFailed)
+        );
+        assert_eq!(ActionError::GoalRejected.cancel_code(), None);
+    }
 }
@@ -0,0 +1,166 @@
+//! ROS2 graph snapshots and change events.
+//!
+//! Backends take a [`GraphSnapshot`] of the nodes, publishers and subscribers
+//! they have discovered whenever the graph changes, and report the difference
+//! with the previous snapshot as [`GraphEvent`]s. This lets both backends
+//! produce the same events whatever their discovery mechanism is.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A change in the ROS2 graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphEvent {
+    /// A node appeared, with its fully qualified name.
+    NodeAdded(String),
+
+    /// A node disappeared, with its fully qualified name.
+    NodeRemoved(String),
+
+    /// A publisher was created on the topic.
+    PublisherAdded(String),
+
+    /// A publisher was destroyed on the topic.
+    PublisherRemoved(String),
+
+    /// A subscriber was created on the topic.
+    SubscriberAdded(String),
+
+    /// A subscriber was destroyed on the topic.
+    SubscriberRemoved(String),
+}
+
+/// The nodes and topic endpoints of the ROS2 graph at one point in time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphSnapshot {
+    /// Fully qualified names of the nodes.
+    pub nodes: BTreeSet<String>,
+
+    /// Number of publishers per topic.
+    pub publishers: BTreeMap<String, usize>,
+
+    /// Number of subscribers per topic.
+    pub subscribers: BTreeMap<String, usize>,
+}
+
+impl GraphSnapshot {
+    /// Check if a node is in the graph.
+    ///
+    /// `name` is the fully qualified node name; a name without a leading `/`
+    /// is looked up in the root namespace.
+    pub fn contains_node(&self, name: &str) -> bool {
+        if name.starts_with('/') {
+            self.nodes.contains(name)
+        } else {
+            self.nodes.contains(&format!("/{name}"))
+        }
+    }
+
+    /// Number of publishers on a topic.
+    pub fn count_publishers(&self, topic: &str) -> usize {
+        self.publishers.get(topic).copied().unwrap_or(0)
+    }
+
+    /// Number of subscribers on a topic.
+    pub fn count_subscribers(&self, topic: &str) -> usize {
+        self.subscribers.get(topic).copied().unwrap_or(0)
+    }
+
+    /// Events leading from `self` to `newer`.
+    ///
+    /// Removals are reported before additions. A topic whose endpoint count
+    /// changed by `n` yields `n` events.
+    pub fn diff(&self, newer: &GraphSnapshot) -> Vec<GraphEvent> {
+        let mut events = Vec::new();
+
+        events.extend(
+            self.nodes
+                .difference(&newer.nodes)
+                .map(|n| GraphEvent::NodeRemoved(n.clone())),
+        );
+        events.extend(
+            newer
+                .nodes
+                .difference(&self.nodes)
+                .map(|n| GraphEvent::NodeAdded(n.clone())),
+        );
+
+        diff_counts(
+            &self.publishers,
+            &newer.publishers,
+            GraphEvent::PublisherAdded,
+            GraphEvent::PublisherRemoved,
+            &mut events,
+        );
+        diff_counts(
+            &self.subscribers,
+            &newer.subscribers,
+            GraphEvent::SubscriberAdded,
+            GraphEvent::SubscriberRemoved,
+            &mut events,
+        );
+
+        events
+    }
+}
+
+fn diff_counts(
+    older: &BTreeMap<String, usize>,
+    newer: &BTreeMap<String, usize>,
+    added: fn(String) -> GraphEvent,
+    removed: fn(String) -> GraphEvent,
+    events: &mut Vec<GraphEvent>,
+) {
+    let topics: BTreeSet<&String> = older.keys().chain(newer.keys()).collect();
+    for topic in topics {
+        let before = older.get(topic).copied().unwrap_or(0);
+        let after = newer.get(topic).copied().unwrap_or(0);
+        if after > before {
+            events.extend((before..after).map(|_| added(topic.clone())));
+        } else {
+            events.extend((after..before).map(|_| removed(topic.clone())));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(nodes: &[&str], publishers: &[(&str, usize)]) -> GraphSnapshot {
+        GraphSnapshot {
+            nodes: nodes.iter().map(|n| n.to_string()).collect(),
+            publishers: publishers
+                .iter()
+                .map(|(t, n)| (t.to_string(), *n))
+                .collect(),
+            subscribers: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_contains_node() {
+        let s = snapshot(&["/talker", "/ns/listener"], &[]);
+        assert!(s.contains_node("/talker"));
+        assert!(s.contains_node("talker"));
+        assert!(s.contains_node("/ns/listener"));
+        assert!(!s.contains_node("listener"));
+    }
+
+    #[test]
+    fn test_diff() {
+        let older = snapshot(&["/a", "/b"], &[("/chatter", 1), ("/old", 1)]);
+        let newer = snapshot(&["/b", "/c"], &[("/chatter", 3)]);
+
+        assert_eq!(
+            older.diff(&newer),
+            vec![
+                GraphEvent::NodeRemoved("/a".into()),
+                GraphEvent::NodeAdded("/c".into()),
+                GraphEvent::PublisherAdded("/chatter".into()),
+                GraphEvent::PublisherAdded("/chatter".into()),
+                GraphEvent::PublisherRemoved("/old".into()),
+            ]
+        );
+        assert!(newer.diff(&newer).is_empty());
+    }
+}
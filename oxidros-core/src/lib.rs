@@ -8,12 +8,18 @@ pub mod action;
 pub mod api;
 pub mod delta_list;
 pub mod error;
+pub mod graph;
 pub mod helper;
 pub mod message;
 pub mod msg;
+pub mod network;
 pub mod parameter;
 pub mod qos;
 pub mod selector;
+pub mod service;
+pub mod shutdown;
+pub mod stats;
+pub mod thread;
 pub mod time;
 pub mod tracing;
 
@@ -21,22 +27,35 @@ pub mod tracing;
 pub mod logging;
 
 // Re-export commonly used error types
-pub use error::{ActionError, Error, RclError, Result};
+pub use error::{
+    ActionError, Error, ErrorContext, MessageError, Operation, RclError, Result, ResultExt,
+};
+pub use graph::{GraphEvent, GraphSnapshot};
 
 // Re-export API traits
 pub use api::{
-    MessageStream, RosClient, RosContext, RosNode, RosPublisher, RosSelector, RosServer,
-    RosSubscriber, ServiceRequest,
+    MessageStream, NodeHandle, RosActionClient, RosClient, RosContext, RosNode, RosPublisher,
+    RosSelector, RosServer, RosSubscriber, ServiceRequest,
 };
 
 // Re-export message traits
-pub use message::{Message, MessageData, MessageInfo};
+pub use message::{Message, MessageData, MessageInfo, RequestInfo};
 pub use msg::{
     ActionGoal, ActionMsg, ActionResult, GetUUID, GoalResponse, ResultResponse, ServiceMsg,
-    TryClone, TypeSupport,
+    Stamped, TryClone, TypeCheckPolicy, TypeInfo, TypeSupport,
+};
+pub use network::NetworkFlowEndpoint;
+pub use parameter::{
+    Descriptor, FloatingPointRange, IntegerRange, Parameter, ParameterType, Value,
+};
+pub use qos::{
+    DurabilityPolicy, HistoryPolicy, LivelinessPolicy, Profile, PublisherEventKind,
+    QosCompatibility, QosEventStatus, QosOverridingOptions, QosPolicyKind, ReliabilityPolicy,
 };
-pub use parameter::{Descriptor, FloatingPointRange, IntegerRange, Parameter, Value};
-pub use qos::{DurabilityPolicy, HistoryPolicy, LivelinessPolicy, Profile, ReliabilityPolicy};
 pub use ros2_types::*;
+pub use service::{ServerOptions, SheddingPolicy};
+pub use shutdown::ShutdownToken;
+pub use stats::NodeStats;
+pub use thread::{ThreadOptions, ThreadPlacement};
 pub use time::{UnsafeDuration, UnsafeTime};
-pub use tracing::targets;
+pub use tracing::{spans, targets};
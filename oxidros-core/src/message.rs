@@ -4,6 +4,9 @@ use std::ops::{Deref, DerefMut};
 #[derive(Debug, Clone, Copy, Default)]
 pub struct MessageInfo {
     /// Sequence number of the message.
+    ///
+    /// Each publisher numbers its messages from 1, increasing by one per
+    /// message. Zero or negative if the middleware does not support it.
     pub sequence_number: i64,
     /// Source timestamp in nanoseconds since UNIX epoch.
     pub source_timestamp_ns: i64,
@@ -12,6 +15,32 @@ pub struct MessageInfo {
     pub writer_gid: [u8; 16],
 }
 
+/// Metadata about a service request received by a server.
+///
+/// The GID of the client and the sequence number together identify a request,
+/// e.g. to answer a retried request from a cache or to rate limit each client.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestInfo {
+    /// Global identifier (GID) of the client that sent the request.
+    pub client_gid: [u8; 16],
+    /// Sequence number of the request, numbered by its client.
+    pub sequence_number: i64,
+    /// Time at which the client sent the request, in nanoseconds since UNIX
+    /// epoch. Zero if the middleware does not support it.
+    pub source_timestamp_ns: i64,
+    /// Time at which the server received the request, in nanoseconds since
+    /// UNIX epoch. Zero if the middleware does not support it.
+    pub received_timestamp_ns: i64,
+}
+
+impl RequestInfo {
+    /// Identifier of the request: the GID of its client and its sequence
+    /// number.
+    pub fn request_id(&self) -> ([u8; 16], i64) {
+        (self.client_gid, self.sequence_number)
+    }
+}
+
 /// The underlying message data, which can be copied or loaned (zero-copy).
 pub enum MessageData<T> {
     /// Message data was copied into owned memory.
@@ -6,5 +6,79 @@
 // Re-export all traits from ros2-type-hash
 pub use ros2_types::{
     ActionGoal, ActionMsg, ActionResult, GetUUID, GoalResponse, ResultResponse, ServiceMsg,
-    TryClone, TypeSupport,
+    Stamped, TryClone, TypeSupport, TypeSupportBorrowed,
 };
+use ros2_types::{ServiceTypeDescription, TypeDescription};
+use std::fmt;
+
+/// How a subscriber handles messages of publishers announcing another type
+/// hash for the same type name, e.g. nodes built against another ROS 2
+/// distribution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TypeCheckPolicy {
+    /// Ignore the messages of publishers with another type hash.
+    #[default]
+    Strict,
+    /// Deliver the messages of publishers with another type hash, and log a
+    /// warning once per type hash. Messages which do not deserialize are
+    /// reported as errors.
+    WarnOnly,
+    /// Deliver the messages of publishers with another type hash if they
+    /// deserialize, i.e. if the field layout matches, and skip the others.
+    BestEffortDecode,
+}
+
+/// Type of the messages of an endpoint, as advertised in the ROS2 graph.
+///
+/// Returned by the `type_info` methods of publishers, subscribers, clients
+/// and servers, and shown by their `Debug` implementations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeInfo {
+    /// Type name, in the form used by the backend, e.g. `std_msgs/msg/String`
+    /// for RCL or `std_msgs::msg::dds_::String_` for Zenoh.
+    pub type_name: String,
+    /// Type hash, `RIHS01_<64_character_hex_sha256>`.
+    pub rihs01_hash: String,
+    /// Serialization format of the messages.
+    pub serialization: &'static str,
+}
+
+impl TypeInfo {
+    /// Serialization format of all the messages, encapsulated CDR.
+    pub const CDR: &'static str = "cdr";
+
+    /// Type information of CDR-serialized messages.
+    pub fn new(type_name: impl Into<String>, rihs01_hash: impl Into<String>) -> Self {
+        Self {
+            type_name: type_name.into(),
+            rihs01_hash: rihs01_hash.into(),
+            serialization: Self::CDR,
+        }
+    }
+
+    /// Type information of a message type, computed from its type description.
+    pub fn of_message<T: TypeDescription>() -> crate::Result<Self> {
+        Ok(Self::new(
+            T::message_type_name().full_name(),
+            T::compute_hash()?,
+        ))
+    }
+
+    /// Type information of a service type, computed from its type description.
+    pub fn of_service<T: ServiceTypeDescription>() -> crate::Result<Self> {
+        Ok(Self::new(
+            T::service_type_name().full_name(),
+            T::compute_hash()?,
+        ))
+    }
+}
+
+impl fmt::Display for TypeInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}, {})",
+            self.type_name, self.rihs01_hash, self.serialization
+        )
+    }
+}
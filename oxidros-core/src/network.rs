@@ -0,0 +1,160 @@
+//! Network flow endpoints of publishers and subscribers.
+//!
+//! A [`NetworkFlowEndpoint`] is a local transport address used by the
+//! middleware to send or receive the messages of a topic, e.g. a UDP port of
+//! DDS. It tells which ports and interfaces to look at when debugging the
+//! network, without external tools.
+
+use std::fmt;
+
+/// Transport protocol of a [`NetworkFlowEndpoint`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TransportProtocol {
+    /// Not reported by the middleware.
+    #[default]
+    Unknown,
+
+    /// UDP, also for protocols on top of it such as QUIC.
+    Udp,
+
+    /// TCP, also for protocols on top of it such as TLS.
+    Tcp,
+}
+
+impl fmt::Display for TransportProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "unknown"),
+            Self::Udp => write!(f, "udp"),
+            Self::Tcp => write!(f, "tcp"),
+        }
+    }
+}
+
+/// Internet protocol of a [`NetworkFlowEndpoint`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum InternetProtocol {
+    /// Not reported by the middleware, or a host name.
+    #[default]
+    Unknown,
+
+    /// IPv4.
+    Ipv4,
+
+    /// IPv6.
+    Ipv6,
+}
+
+impl fmt::Display for InternetProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "unknown"),
+            Self::Ipv4 => write!(f, "ipv4"),
+            Self::Ipv6 => write!(f, "ipv6"),
+        }
+    }
+}
+
+/// A local transport address of a publisher or subscriber.
+///
+/// Mirrors `rmw_network_flow_endpoint_t`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct NetworkFlowEndpoint {
+    /// Transport protocol.
+    pub transport_protocol: TransportProtocol,
+    /// Internet protocol.
+    pub internet_protocol: InternetProtocol,
+    /// Port, `0` if unknown.
+    pub transport_port: u16,
+    /// IPv6 flow label, `0` if unset.
+    pub flow_label: u32,
+    /// Differentiated services code point, `0` if unset.
+    pub dscp: u8,
+    /// Address, without the port nor brackets.
+    pub internet_address: String,
+}
+
+impl NetworkFlowEndpoint {
+    /// Parse a Zenoh locator, e.g. `tcp/192.168.1.10:7447` or
+    /// `udp/[fe80::1]:7447`.
+    ///
+    /// TLS, QUIC and WebSocket locators are reported with the protocol they
+    /// run on. Returns `None` if the locator has no IP address and port,
+    /// e.g. a shared memory or serial link.
+    pub fn from_locator(locator: &str) -> Option<Self> {
+        // Drop the metadata and configuration of the endpoint
+        let locator = locator.split(['?', '#']).next()?;
+        let (protocol, address) = locator.split_once('/')?;
+        let transport_protocol = match protocol {
+            "tcp" | "tls" | "ws" => TransportProtocol::Tcp,
+            "udp" | "quic" => TransportProtocol::Udp,
+            _ => return None,
+        };
+
+        let (host, port) = address.rsplit_once(':')?;
+        let transport_port = port.parse().ok()?;
+        let (internet_protocol, internet_address) = match host
+            .strip_prefix('[')
+            .and_then(|h| h.strip_suffix(']'))
+        {
+            Some(host) => (InternetProtocol::Ipv6, host),
+            None if host.parse::<std::net::Ipv4Addr>().is_ok() => (InternetProtocol::Ipv4, host),
+            // Host name
+            None => (InternetProtocol::Unknown, host),
+        };
+
+        Some(Self {
+            transport_protocol,
+            internet_protocol,
+            transport_port,
+            flow_label: 0,
+            dscp: 0,
+            internet_address: internet_address.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for NetworkFlowEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.internet_protocol {
+            InternetProtocol::Ipv6 => write!(
+                f,
+                "{}/[{}]:{}",
+                self.transport_protocol, self.internet_address, self.transport_port
+            ),
+            _ => write!(
+                f,
+                "{}/{}:{}",
+                self.transport_protocol, self.internet_address, self.transport_port
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_locator() {
+        let endpoint = NetworkFlowEndpoint::from_locator("tcp/192.168.1.10:7447").unwrap();
+        assert_eq!(endpoint.transport_protocol, TransportProtocol::Tcp);
+        assert_eq!(endpoint.internet_protocol, InternetProtocol::Ipv4);
+        assert_eq!(endpoint.transport_port, 7447);
+        assert_eq!(endpoint.internet_address, "192.168.1.10");
+        assert_eq!(endpoint.to_string(), "tcp/192.168.1.10:7447");
+
+        let endpoint = NetworkFlowEndpoint::from_locator("quic/[fe80::1]:7447?iface=eth0").unwrap();
+        assert_eq!(endpoint.transport_protocol, TransportProtocol::Udp);
+        assert_eq!(endpoint.internet_protocol, InternetProtocol::Ipv6);
+        assert_eq!(endpoint.internet_address, "fe80::1");
+        assert_eq!(endpoint.to_string(), "udp/[fe80::1]:7447");
+
+        let endpoint = NetworkFlowEndpoint::from_locator("tcp/localhost:7447").unwrap();
+        assert_eq!(endpoint.internet_protocol, InternetProtocol::Unknown);
+
+        assert!(NetworkFlowEndpoint::from_locator("shm/oxidros").is_none());
+        assert!(NetworkFlowEndpoint::from_locator("serial//dev/ttyUSB0#baudrate=115200").is_none());
+        assert!(NetworkFlowEndpoint::from_locator("tcp/192.168.1.10").is_none());
+    }
+}
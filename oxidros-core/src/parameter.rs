@@ -5,6 +5,10 @@ use num_traits::Zero;
 use std::{
     collections::{BTreeMap, BTreeSet},
     fmt::Display,
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicU64, Ordering},
+    },
 };
 
 /// Describes a range of integers for parameter validation.
@@ -88,7 +92,7 @@ impl Contains for FloatingPointRange {
 }
 
 /// Describes a parameter including its constraints and metadata.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Descriptor {
     /// Human-readable description of the parameter.
     pub description: String,
@@ -151,6 +155,67 @@ impl Parameter {
     }
 }
 
+/// Type of a parameter, as in `rcl_interfaces/msg/ParameterType`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ParameterType {
+    /// Parameter is not set.
+    NotSet = 0,
+
+    /// Boolean.
+    Bool = 1,
+
+    /// 64-bit signed integer.
+    Integer = 2,
+
+    /// 64-bit floating point.
+    Double = 3,
+
+    /// String.
+    String = 4,
+
+    /// Array of bytes.
+    ByteArray = 5,
+
+    /// Array of booleans.
+    BoolArray = 6,
+
+    /// Array of 64-bit signed integers.
+    IntegerArray = 7,
+
+    /// Array of 64-bit floating point values.
+    DoubleArray = 8,
+
+    /// Array of strings.
+    StringArray = 9,
+}
+
+impl TryFrom<u8> for ParameterType {
+    type Error = crate::error::Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(ParameterType::NotSet),
+            1 => Ok(ParameterType::Bool),
+            2 => Ok(ParameterType::Integer),
+            3 => Ok(ParameterType::Double),
+            4 => Ok(ParameterType::String),
+            5 => Ok(ParameterType::ByteArray),
+            6 => Ok(ParameterType::BoolArray),
+            7 => Ok(ParameterType::IntegerArray),
+            8 => Ok(ParameterType::DoubleArray),
+            9 => Ok(ParameterType::StringArray),
+            _ => Err(format!("invalid parameter type: {value}").into()),
+        }
+    }
+}
+
+impl From<ParameterType> for u8 {
+    fn from(ty: ParameterType) -> Self {
+        ty as u8
+    }
+}
+
 /// Represents a parameter value of various types.
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum Value {
@@ -202,6 +267,22 @@ impl Value {
         )
     }
 
+    /// Returns the ROS2 parameter type of this value.
+    pub fn parameter_type(&self) -> ParameterType {
+        match self {
+            Value::NotSet => ParameterType::NotSet,
+            Value::Bool(_) => ParameterType::Bool,
+            Value::I64(_) => ParameterType::Integer,
+            Value::F64(_) => ParameterType::Double,
+            Value::String(_) => ParameterType::String,
+            Value::VecU8(_) => ParameterType::ByteArray,
+            Value::VecBool(_) => ParameterType::BoolArray,
+            Value::VecI64(_) => ParameterType::IntegerArray,
+            Value::VecF64(_) => ParameterType::DoubleArray,
+            Value::VecString(_) => ParameterType::StringArray,
+        }
+    }
+
     /// Returns the type name of this value as a string.
     pub fn type_name(&self) -> &'static str {
         match self {
@@ -236,6 +317,53 @@ impl Display for Value {
     }
 }
 
+macro_rules! impl_value_conversions {
+    ($($ty:ty => $variant:ident),* $(,)?) => {$(
+        impl From<$ty> for Value {
+            fn from(value: $ty) -> Self {
+                Value::$variant(value)
+            }
+        }
+
+        #[allow(clippy::clone_on_copy)]
+        impl TryFrom<&Value> for $ty {
+            type Error = crate::error::Error;
+
+            fn try_from(value: &Value) -> Result<Self> {
+                match value {
+                    Value::$variant(v) => Ok(v.clone()),
+                    _ => {
+                        let msg = format!(
+                            "failed type checking: dst = {}, src = {}",
+                            stringify!($variant),
+                            value.type_name()
+                        );
+                        Err(msg.into())
+                    }
+                }
+            }
+        }
+    )*};
+}
+
+impl_value_conversions!(
+    bool => Bool,
+    i64 => I64,
+    f64 => F64,
+    String => String,
+    Vec<bool> => VecBool,
+    Vec<i64> => VecI64,
+    Vec<u8> => VecU8,
+    Vec<f64> => VecF64,
+    Vec<String> => VecString,
+);
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
 /// Parameters.
 ///
 /// # Example
@@ -255,8 +383,12 @@ impl Display for Value {
 /// ```
 #[derive(Debug, Default)]
 pub struct Parameters {
-    pub params: BTreeMap<String, Parameter>,
+    params: BTreeMap<String, Parameter>,
     pub updated: BTreeSet<String>,
+    /// Incremented on every change.
+    generation: u64,
+    /// Copy of `generation` shared with the snapshots, created by the first one.
+    counter: OnceLock<Arc<AtomicU64>>,
 }
 
 impl Parameters {
@@ -264,6 +396,76 @@ impl Parameters {
         Self {
             params: BTreeMap::new(),
             updated: BTreeSet::new(),
+            generation: 0,
+            counter: OnceLock::new(),
+        }
+    }
+
+    /// Get the parameters.
+    pub fn params(&self) -> &BTreeMap<String, Parameter> {
+        &self.params
+    }
+
+    /// Get the parameters for a direct change, which makes the snapshots
+    /// stale.
+    pub fn params_mut(&mut self) -> &mut BTreeMap<String, Parameter> {
+        self.touch();
+        &mut self.params
+    }
+
+    /// Mark `name` as updated and count the change.
+    fn mark_updated(&mut self, name: String) {
+        self.updated.insert(name);
+        self.touch();
+    }
+
+    /// Count a change, so that the snapshots become stale.
+    fn touch(&mut self) {
+        self.generation += 1;
+        if let Some(counter) = self.counter.get() {
+            counter.store(self.generation, Ordering::Release);
+        }
+    }
+
+    /// Number of changes of the parameters so far.
+    ///
+    /// Every successful declaration, update and change of descriptor, and
+    /// every call of [`Parameters::params_mut`], increments it.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Take an immutable copy of the parameters.
+    ///
+    /// Realtime loops can read a snapshot without taking the lock of the
+    /// parameter server, and check with [`ParameterSnapshot::is_stale`],
+    /// which only reads an atomic counter, whether it must be taken again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oxidros_core::parameter::{Parameters, Value};
+    /// use parking_lot::RwLock;
+    ///
+    /// let params = RwLock::new(Parameters::new());
+    /// params.write().set_parameter("gain".into(), Value::F64(1.0), false, None).unwrap();
+    ///
+    /// let mut snapshot = params.read().snapshot();
+    /// assert!(!snapshot.refresh_with(|| params.read().snapshot()));
+    ///
+    /// params.write().set_parameter("gain".into(), Value::F64(2.0), false, None).unwrap();
+    /// assert!(snapshot.is_stale());
+    /// assert!(snapshot.refresh_with(|| params.read().snapshot()));
+    /// assert_eq!(snapshot.get_as::<f64>("gain").unwrap(), 2.0);
+    /// ```
+    pub fn snapshot(&self) -> ParameterSnapshot {
+        ParameterSnapshot {
+            params: Arc::new(self.params.clone()),
+            generation: self.generation,
+            counter: self
+                .counter
+                .get_or_init(|| Arc::new(AtomicU64::new(self.generation)))
+                .clone(),
         }
     }
 
@@ -280,7 +482,7 @@ impl Parameters {
             let msg: String = format!("{} is already declared", name);
             Err(msg.into())
         } else if parameter.check_range(&parameter.value) {
-            self.updated.insert(name.clone());
+            self.mark_updated(name.clone());
             self.params.insert(name, parameter);
             Ok(())
         } else {
@@ -316,7 +518,7 @@ impl Parameters {
 
             if param.value.type_check(&value) {
                 param.value = value;
-                self.updated.insert(name);
+                self.mark_updated(name);
                 Ok(())
             } else {
                 let msg = format!(
@@ -333,7 +535,7 @@ impl Parameters {
                 false,
                 description.unwrap_or_else(|| name.clone()),
             );
-            self.updated.insert(name.clone());
+            self.mark_updated(name.clone());
             self.params.insert(name, param);
             Ok(())
         }
@@ -363,7 +565,7 @@ impl Parameters {
             }
 
             param.value = value;
-            self.updated.insert(name);
+            self.mark_updated(name);
         } else {
             let param = Parameter::new(
                 value,
@@ -371,7 +573,7 @@ impl Parameters {
                 true,
                 description.unwrap_or_else(|| name.clone()),
             );
-            self.updated.insert(name.clone());
+            self.mark_updated(name.clone());
             self.params.insert(name, param);
         }
         Ok(())
@@ -394,11 +596,13 @@ impl Parameters {
 
             if param.descriptor.dynamic_typing {
                 param.descriptor.floating_point_range = Some(range);
+                self.touch();
                 Ok(())
             } else {
                 match &param.value {
                     Value::F64(_) | Value::VecF64(_) => {
                         param.descriptor.floating_point_range = Some(range);
+                        self.touch();
                         Ok(())
                     }
                     _ => {
@@ -417,6 +621,59 @@ impl Parameters {
         }
     }
 
+    /// Replace the descriptor of a declared parameter.
+    ///
+    /// The current value must satisfy the new ranges, and a range can only be
+    /// set on a value of its type unless the parameter is dynamically typed.
+    /// The parameter is marked as updated so that handlers are notified.
+    pub fn set_descriptor(&mut self, name: &str, descriptor: Descriptor) -> Result<()> {
+        let Some(param) = self.params.get_mut(name) else {
+            let msg = format!("no such parameter: name = {}", name);
+            return Err(msg.into());
+        };
+
+        if !descriptor.dynamic_typing {
+            let integer = matches!(param.value, Value::I64(_) | Value::VecI64(_));
+            let floating = matches!(param.value, Value::F64(_) | Value::VecF64(_));
+            if (descriptor.integer_range.is_some() && !integer)
+                || (descriptor.floating_point_range.is_some() && !floating)
+            {
+                let msg = format!(
+                    "{}({}) does not match the type of the range.",
+                    name,
+                    param.value.type_name()
+                );
+                return Err(msg.into());
+            }
+        }
+
+        let updated = Parameter {
+            descriptor,
+            value: param.value.clone(),
+        };
+        if !updated.check_range(&updated.value) {
+            let msg = format!("{:?} is not in the range.", param.value);
+            return Err(msg.into());
+        }
+
+        param.descriptor = updated.descriptor;
+        self.mark_updated(name.to_string());
+        Ok(())
+    }
+
+    /// Modify the descriptor of a declared parameter.
+    ///
+    /// See [`set_descriptor`](Self::set_descriptor) for the validation rules.
+    pub fn update_descriptor(&mut self, name: &str, f: impl FnOnce(&mut Descriptor)) -> Result<()> {
+        let Some(param) = self.params.get(name) else {
+            let msg = format!("no such parameter: name = {}", name);
+            return Err(msg.into());
+        };
+        let mut descriptor = param.descriptor.clone();
+        f(&mut descriptor);
+        self.set_descriptor(name, descriptor)
+    }
+
     pub fn set_integer_range(&mut self, name: &str, min: i64, max: i64, step: usize) -> Result<()> {
         let range = IntegerRange { min, max, step };
 
@@ -428,11 +685,13 @@ impl Parameters {
 
             if param.descriptor.dynamic_typing {
                 param.descriptor.integer_range = Some(range);
+                self.touch();
                 Ok(())
             } else {
                 match &param.value {
                     Value::I64(_) | Value::VecI64(_) => {
                         param.descriptor.integer_range = Some(range);
+                        self.touch();
                         Ok(())
                     }
                     _ => {
@@ -452,6 +711,491 @@ impl Parameters {
     }
 }
 
+impl Parameters {
+    /// Declare the parameters of the group `prefix`, named `<prefix>.<name>`.
+    ///
+    /// A parameter already set, e.g. from a YAML file, keeps its value if it
+    /// has the type of the declared default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oxidros_core::parameter::{Parameters, Value};
+    ///
+    /// let mut params = Parameters::new();
+    /// params
+    ///     .declare_group("controller", |g| {
+    ///         g.declare("kp", 1.0);
+    ///         g.declare("ki", 0.0);
+    ///         g.group("limits", |g| {
+    ///             g.declare("max_output", 10.0);
+    ///         });
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let kp = params.get_parameter("controller.kp").unwrap();
+    /// assert_eq!(kp.value, Value::F64(1.0));
+    /// assert!(params.get_parameter("controller.limits.max_output").is_some());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first declaration that failed; the
+    /// declarations after it are skipped.
+    pub fn declare_group(
+        &mut self,
+        prefix: &str,
+        f: impl FnOnce(&mut ParameterGroup<'_>),
+    ) -> Result<()> {
+        let mut group = ParameterGroup {
+            params: self,
+            prefix: prefix.to_string(),
+            error: None,
+        };
+        f(&mut group);
+        match group.error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the parameters of the group `prefix`, by name relative to it.
+    ///
+    /// Nested groups are included, e.g. `limits.max_output` for the group
+    /// `controller`.
+    pub fn group(&self, prefix: &str) -> BTreeMap<&str, &Parameter> {
+        let prefix = format!("{prefix}.");
+        self.params
+            .range(prefix.clone()..)
+            .map_while(|(name, param)| Some((name.strip_prefix(&prefix)?, param)))
+            .collect()
+    }
+}
+
+/// Immutable copy of the parameters, see [`Parameters::snapshot`].
+#[derive(Debug, Clone)]
+pub struct ParameterSnapshot {
+    params: Arc<BTreeMap<String, Parameter>>,
+    generation: u64,
+    counter: Arc<AtomicU64>,
+}
+
+impl ParameterSnapshot {
+    /// Generation of the parameters when the snapshot was taken, see
+    /// [`Parameters::generation`].
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Whether the parameters changed since the snapshot was taken.
+    ///
+    /// This does not take the lock of the parameters.
+    pub fn is_stale(&self) -> bool {
+        self.counter.load(Ordering::Acquire) != self.generation
+    }
+
+    /// Replace the snapshot by `take()` if it is stale, e.g.
+    /// `snapshot.refresh_with(|| server.params.read().snapshot())`.
+    ///
+    /// Returns whether the snapshot was replaced.
+    pub fn refresh_with(&mut self, take: impl FnOnce() -> ParameterSnapshot) -> bool {
+        if self.is_stale() {
+            *self = take();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get the parameters.
+    pub fn params(&self) -> &BTreeMap<String, Parameter> {
+        &self.params
+    }
+
+    /// Get the parameter `name`.
+    pub fn get_parameter(&self, name: &str) -> Option<&Parameter> {
+        self.params.get(name)
+    }
+
+    /// Get the value of the parameter `name` as a `T`, see
+    /// [`Value::deserialize_into`].
+    pub fn get_as<T: serde::de::DeserializeOwned>(&self, name: &str) -> Result<T> {
+        get_as(&self.params, name)
+    }
+}
+
+/// Names and prefixes answered by the `~/list_parameters` service.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ListParametersResult {
+    /// Names of the listed parameters.
+    pub names: Vec<String>,
+    /// Prefixes of the listed parameters, in order of first appearance.
+    pub prefixes: Vec<String>,
+}
+
+/// Parameter services.
+///
+/// Both backends answer the standard parameter services with these methods,
+/// so that `ros2 param` behaves the same whatever the middleware.
+impl Parameters {
+    /// Separator of the components of a parameter name.
+    pub const SEPARATOR: char = '.';
+
+    /// List the parameters as `rcl_interfaces/srv/ListParameters` does.
+    ///
+    /// Without `prefixes`, the parameters with less than `depth` separators
+    /// are listed; with `prefixes`, the parameters named like a prefix or
+    /// below it, with less than `depth` separators after it. A `depth` of `0`
+    /// is recursive.
+    pub fn list(&self, prefixes: &[String], depth: u64) -> ListParametersResult {
+        let separators = |s: &str| s.matches(Self::SEPARATOR).count() as u64;
+        let mut result = ListParametersResult::default();
+        for name in self.params.keys() {
+            let get_all = prefixes.is_empty() && (depth == 0 || separators(name) < depth);
+            let prefix_matches = prefixes.iter().any(|prefix| {
+                name == prefix
+                    || name
+                        .strip_prefix(prefix.as_str())
+                        .and_then(|rest| rest.strip_prefix(Self::SEPARATOR))
+                        .is_some_and(|rest| depth == 0 || separators(rest) < depth)
+            });
+            if get_all || prefix_matches {
+                result.names.push(name.clone());
+                if let Some((prefix, _)) = name.rsplit_once(Self::SEPARATOR)
+                    && !result.prefixes.iter().any(|p| p == prefix)
+                {
+                    result.prefixes.push(prefix.to_string());
+                }
+            }
+        }
+        result
+    }
+
+    /// Check that a parameter service may set `name` to `value`.
+    ///
+    /// The parameter must be declared, writable, in range and of the same
+    /// type unless it is dynamically typed. The error is the reason returned
+    /// to the client.
+    pub fn check_update(&self, name: &str, value: &Value) -> std::result::Result<(), String> {
+        let Some(param) = self.params.get(name) else {
+            return Err(format!("no such parameter: name = {name}"));
+        };
+        if param.descriptor.read_only {
+            Err(format!("{name} is read only"))
+        } else if !param.check_range(value) {
+            Err(format!("{name} is not in the range"))
+        } else if !param.descriptor.dynamic_typing && !param.value.type_check(value) {
+            Err(format!(
+                "failed type checking: dst = {}, src = {}",
+                param.value.type_name(),
+                value.type_name()
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Set `name` to `value` on behalf of a parameter service, after
+    /// [`Parameters::check_update`].
+    pub fn update(&mut self, name: &str, value: Value) -> std::result::Result<(), String> {
+        self.check_update(name, &value)?;
+        if let Some(param) = self.params.get_mut(name) {
+            param.value = value;
+            self.mark_updated(name.to_string());
+        }
+        Ok(())
+    }
+
+    /// Set all parameters or none of them, as
+    /// `rcl_interfaces/srv/SetParametersAtomically` does.
+    pub fn update_atomically(
+        &mut self,
+        parameters: Vec<(String, Value)>,
+    ) -> std::result::Result<(), String> {
+        for (name, value) in &parameters {
+            self.check_update(name, value)?;
+        }
+        for (name, value) in parameters {
+            self.update(&name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Declares the parameters of a group, see [`Parameters::declare_group`].
+pub struct ParameterGroup<'a> {
+    params: &'a mut Parameters,
+    prefix: String,
+    error: Option<crate::error::Error>,
+}
+
+impl ParameterGroup<'_> {
+    /// Get the prefix of the parameters of this group.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Declare `<prefix>.<name>` with the value `default`.
+    pub fn declare(&mut self, name: &str, default: impl Into<Value>) -> &mut Self {
+        self.declare_parameter(name, default.into(), None)
+    }
+
+    /// Declare `<prefix>.<name>` with the value `default` and a description.
+    pub fn declare_described(
+        &mut self,
+        name: &str,
+        default: impl Into<Value>,
+        description: &str,
+    ) -> &mut Self {
+        self.declare_parameter(name, default.into(), Some(description.to_string()))
+    }
+
+    /// Declare the nested group `<prefix>.<name>`.
+    pub fn group(&mut self, name: &str, f: impl FnOnce(&mut ParameterGroup<'_>)) -> &mut Self {
+        if self.error.is_none() {
+            let mut group = ParameterGroup {
+                prefix: self.full_name(name),
+                params: &mut *self.params,
+                error: None,
+            };
+            f(&mut group);
+            self.error = group.error;
+        }
+        self
+    }
+
+    fn full_name(&self, name: &str) -> String {
+        if self.prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", self.prefix, name)
+        }
+    }
+
+    fn declare_parameter(
+        &mut self,
+        name: &str,
+        default: Value,
+        description: Option<String>,
+    ) -> &mut Self {
+        if self.error.is_some() {
+            return self;
+        }
+        let name = self.full_name(name);
+        let result = match self.params.get_parameter(&name) {
+            Some(param) if param.descriptor.dynamic_typing || param.value.type_check(&default) => {
+                Ok(())
+            }
+            Some(param) => {
+                let msg = format!(
+                    "failed type checking: {}: dst = {}, src = {}",
+                    name,
+                    default.type_name(),
+                    param.value.type_name()
+                );
+                Err(msg.into())
+            }
+            None => self.params.set_parameter(name, default, false, description),
+        };
+        if let Err(e) = result {
+            self.error = Some(e);
+        }
+        self
+    }
+}
+
+/// Conversions between values and Rust types through serde.
+///
+/// Any type (de)serializing as a boolean, an integer, a float, a string, a byte
+/// array or a sequence of these can be read from and written to a parameter,
+/// e.g. `u32`, `f32`, `Option<T>`, `Vec<String>`, `[f64; 3]` or a unit enum.
+///
+/// ```
+/// use oxidros_core::parameter::Parameters;
+///
+/// let mut params = Parameters::new();
+/// params.set_from("gains", &[0.5, 0.1, 0.0]).unwrap();
+/// params.set_from("frames", &["base_link", "odom"]).unwrap();
+///
+/// let gains: [f64; 3] = params.get_as("gains").unwrap();
+/// let frames: Vec<String> = params.get_as("frames").unwrap();
+/// assert_eq!(gains, [0.5, 0.1, 0.0]);
+/// assert_eq!(frames, ["base_link", "odom"]);
+///
+/// let err = params.get_as::<Vec<f64>>("frames").unwrap_err();
+/// assert!(err.to_string().contains("frames"));
+/// ```
+impl Value {
+    /// Convert the value to `T`.
+    ///
+    /// [`Value::NotSet`] converts to `None` or `()`.
+    pub fn deserialize_into<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_value(self.to_json()).map_err(|e| {
+            format!(
+                "cannot convert {} to {}: {e}",
+                self.type_name(),
+                std::any::type_name::<T>()
+            )
+            .into()
+        })
+    }
+
+    /// Convert `value` to a value, of the type `hint` where ambiguous.
+    ///
+    /// Integers convert to [`Value::I64`] and sequences of integers to
+    /// [`Value::VecI64`], or to [`Value::F64`], [`Value::VecF64`] and
+    /// [`Value::VecU8`] with the matching hint. An empty sequence converts to
+    /// the array type of the hint, [`Value::VecI64`] without one.
+    pub fn serialize_from<T: serde::Serialize + ?Sized>(
+        value: &T,
+        hint: Option<ParameterType>,
+    ) -> Result<Self> {
+        let json = serde_json::to_value(value).map_err(|e| e.to_string())?;
+        Self::from_json(json, hint).map_err(|e| {
+            format!(
+                "cannot convert {} to a parameter value: {e}",
+                std::any::type_name::<T>()
+            )
+            .into()
+        })
+    }
+
+    /// Convert the value to JSON.
+    ///
+    /// [`Value::NotSet`] and non-finite floats convert to `null`.
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value as Json;
+        fn array<T: Into<Json> + Clone>(v: &[T]) -> Json {
+            Json::Array(v.iter().cloned().map(Into::into).collect())
+        }
+        match self {
+            Value::NotSet => Json::Null,
+            Value::Bool(v) => Json::Bool(*v),
+            Value::I64(v) => (*v).into(),
+            Value::F64(v) => (*v).into(),
+            Value::String(v) => v.as_str().into(),
+            Value::VecBool(v) => array(v),
+            Value::VecI64(v) => array(v),
+            Value::VecU8(v) => array(v),
+            Value::VecF64(v) => array(v),
+            Value::VecString(v) => array(v),
+        }
+    }
+
+    fn from_json(
+        json: serde_json::Value,
+        hint: Option<ParameterType>,
+    ) -> std::result::Result<Self, String> {
+        use serde_json::Value as Json;
+        fn integer(n: &serde_json::Number) -> std::result::Result<i64, String> {
+            n.as_i64()
+                .ok_or_else(|| format!("{n} is not a 64-bit signed integer"))
+        }
+        fn float(n: &serde_json::Number) -> std::result::Result<f64, String> {
+            n.as_f64().ok_or_else(|| format!("{n} is not a number"))
+        }
+        fn collect<T>(
+            items: &[Json],
+            f: impl Fn(&Json) -> Option<std::result::Result<T, String>>,
+        ) -> std::result::Result<Vec<T>, String> {
+            items
+                .iter()
+                .map(|item| f(item).unwrap_or_else(|| Err(format!("mixed types in array: {item}"))))
+                .collect()
+        }
+
+        match json {
+            Json::Null => Ok(Value::NotSet),
+            Json::Bool(v) => Ok(Value::Bool(v)),
+            Json::Number(n) if n.is_f64() || hint == Some(ParameterType::Double) => {
+                float(&n).map(Value::F64)
+            }
+            Json::Number(n) => integer(&n).map(Value::I64),
+            Json::String(v) => Ok(Value::String(v)),
+            Json::Array(items) => {
+                let Some(first) = items.first() else {
+                    return Ok(match hint {
+                        Some(ParameterType::BoolArray) => Value::VecBool(Vec::new()),
+                        Some(ParameterType::ByteArray) => Value::VecU8(Vec::new()),
+                        Some(ParameterType::DoubleArray) => Value::VecF64(Vec::new()),
+                        Some(ParameterType::StringArray) => Value::VecString(Vec::new()),
+                        _ => Value::VecI64(Vec::new()),
+                    });
+                };
+                let floats = hint == Some(ParameterType::DoubleArray)
+                    || items
+                        .iter()
+                        .any(|item| item.as_number().is_some_and(|n| n.is_f64()));
+                match first {
+                    Json::Bool(_) => {
+                        collect(&items, |item| item.as_bool().map(Ok)).map(Value::VecBool)
+                    }
+                    Json::Number(_) if floats => {
+                        collect(&items, |item| item.as_number().map(float)).map(Value::VecF64)
+                    }
+                    Json::Number(_) if hint == Some(ParameterType::ByteArray) => {
+                        collect(&items, |item| {
+                            item.as_number().map(|n| {
+                                n.as_u64()
+                                    .and_then(|n| u8::try_from(n).ok())
+                                    .ok_or_else(|| format!("{n} is not a byte"))
+                            })
+                        })
+                        .map(Value::VecU8)
+                    }
+                    Json::Number(_) => {
+                        collect(&items, |item| item.as_number().map(integer)).map(Value::VecI64)
+                    }
+                    Json::String(_) => {
+                        collect(&items, |item| item.as_str().map(|s| Ok(s.to_string())))
+                            .map(Value::VecString)
+                    }
+                    _ => Err(format!("unsupported array element: {first}")),
+                }
+            }
+            Json::Object(_) => Err("maps and structs are not parameter values".to_string()),
+        }
+    }
+}
+
+impl Parameters {
+    /// Get the value of the parameter `name` as a `T`, see
+    /// [`Value::deserialize_into`].
+    pub fn get_as<T: serde::de::DeserializeOwned>(&self, name: &str) -> Result<T> {
+        get_as(&self.params, name)
+    }
+
+    /// Set the parameter `name` from a `T`, see [`Value::serialize_from`].
+    ///
+    /// A declared parameter keeps its type: integers convert to floats and
+    /// bytes where it has these types. An undeclared parameter is declared as
+    /// statically typed, like [`set_parameter`](Self::set_parameter).
+    pub fn set_from<T: serde::Serialize + ?Sized>(&mut self, name: &str, value: &T) -> Result<()> {
+        let param = self.params.get(name);
+        let hint = param.map(|param| param.value.parameter_type());
+        let value = Value::serialize_from(value, hint)
+            .map_err(|e| crate::error::Error::from(format!("parameter {name}: {e}")))?;
+        if param.is_some_and(|param| param.descriptor.dynamic_typing) {
+            self.set_dynamically_typed_parameter(name.to_string(), value, false, None)
+        } else {
+            self.set_parameter(name.to_string(), value, false, None)
+        }
+    }
+}
+
+fn get_as<T: serde::de::DeserializeOwned>(
+    params: &BTreeMap<String, Parameter>,
+    name: &str,
+) -> Result<T> {
+    let param = params
+        .get(name)
+        .ok_or_else(|| format!("parameter {name} is not declared"))?;
+    param
+        .value
+        .deserialize_into()
+        .map_err(|e| format!("parameter {name}: {e}").into())
+}
+
 #[cfg(feature = "yaml")]
 impl TryFrom<&yaml_rust2::Yaml> for Value {
     type Error = crate::error::Error;
@@ -500,3 +1244,523 @@ impl TryFrom<&yaml_rust2::Yaml> for Value {
         }
     }
 }
+
+#[cfg(feature = "yaml")]
+impl From<&Value> for yaml_rust2::Yaml {
+    fn from(value: &Value) -> Self {
+        use yaml_rust2::Yaml;
+        fn real(v: f64) -> Yaml {
+            Yaml::Real(format!("{v:?}"))
+        }
+        match value {
+            Value::NotSet => Yaml::Null,
+            Value::Bool(v) => Yaml::Boolean(*v),
+            Value::I64(v) => Yaml::Integer(*v),
+            Value::F64(v) => real(*v),
+            Value::String(v) => Yaml::String(v.clone()),
+            Value::VecBool(v) => Yaml::Array(v.iter().map(|&x| Yaml::Boolean(x)).collect()),
+            Value::VecI64(v) => Yaml::Array(v.iter().map(|&x| Yaml::Integer(x)).collect()),
+            Value::VecU8(v) => Yaml::Array(v.iter().map(|&x| Yaml::Integer(x.into())).collect()),
+            Value::VecF64(v) => Yaml::Array(v.iter().map(|&x| real(x)).collect()),
+            Value::VecString(v) => Yaml::Array(v.iter().cloned().map(Yaml::String).collect()),
+        }
+    }
+}
+
+/// Persistence in the `ros2 param dump` YAML format.
+///
+/// ```yaml
+/// /my_ns/my_node:
+///   ros__parameters:
+///     gain: 1.5
+///     camera:
+///       exposure: 20
+/// ```
+///
+/// Dotted parameter names (`camera.exposure`) are written as nested mappings.
+/// When loading, node keys may be fully qualified names or wildcard patterns
+/// (`/**`, `/robot/*`), as in parameter files passed with `--params-file`.
+#[cfg(feature = "yaml")]
+impl Parameters {
+    /// Serialize all parameters of the node `node_fqn` to YAML.
+    pub fn to_yaml(&self, node_fqn: &str) -> Result<String> {
+        use yaml_rust2::{Yaml, YamlEmitter, yaml::Hash};
+
+        let mut ros_params = Hash::new();
+        for (name, param) in self.params.iter() {
+            if param.value != Value::NotSet {
+                insert_nested(&mut ros_params, name, (&param.value).into());
+            }
+        }
+
+        let mut node = Hash::new();
+        node.insert(
+            Yaml::String("ros__parameters".to_string()),
+            Yaml::Hash(ros_params),
+        );
+        let mut root = Hash::new();
+        root.insert(Yaml::String(node_fqn.to_string()), Yaml::Hash(node));
+
+        let mut out = String::new();
+        YamlEmitter::new(&mut out)
+            .dump(&Yaml::Hash(root))
+            .map_err(|e| format!("failed to emit YAML: {e}"))?;
+        let mut out = out.strip_prefix("---\n").map(str::to_string).unwrap_or(out);
+        out.push('\n');
+        Ok(out)
+    }
+
+    /// Write all parameters of the node `node_fqn` to a YAML file.
+    pub fn dump_yaml(&self, node_fqn: &str, path: impl AsRef<std::path::Path>) -> Result<()> {
+        let yaml = self.to_yaml(node_fqn)?;
+        std::fs::write(path, yaml)?;
+        Ok(())
+    }
+
+    /// Set the parameters that apply to the node `node_fqn` from YAML.
+    ///
+    /// Parameters that fail validation (read only, type mismatch, out of
+    /// range) are skipped with a warning. Returns the names of the parameters
+    /// that were set.
+    pub fn load_yaml_str(&mut self, yaml: &str, node_fqn: &str) -> Result<BTreeSet<String>> {
+        use yaml_rust2::{Yaml, YamlLoader};
+
+        let docs =
+            YamlLoader::load_from_str(yaml).map_err(|e| format!("failed to parse YAML: {e}"))?;
+        let Some(doc) = docs.first() else {
+            return Ok(BTreeSet::new());
+        };
+        let root = doc
+            .as_hash()
+            .ok_or("root element must be a mapping of node names")?;
+
+        let ros_params_key = Yaml::String("ros__parameters".to_string());
+        let mut values = Vec::new();
+        for (node_key, node_value) in root {
+            let Some(node_key) = node_key.as_str() else {
+                continue;
+            };
+            if !node_matches(node_key, node_fqn) {
+                continue;
+            }
+            if let Some(Yaml::Hash(ros_params)) =
+                node_value.as_hash().and_then(|h| h.get(&ros_params_key))
+            {
+                collect_values("", ros_params, &mut values)?;
+            }
+        }
+
+        let mut loaded = BTreeSet::new();
+        for (name, value) in values {
+            let result = match self.params.get(&name) {
+                Some(param) if param.descriptor.dynamic_typing => {
+                    self.set_dynamically_typed_parameter(name.clone(), value, false, None)
+                }
+                Some(param) => {
+                    let value = coerce_value(&param.value, value);
+                    self.set_parameter(name.clone(), value, false, None)
+                }
+                None => self.set_parameter(name.clone(), value, false, None),
+            };
+            match result {
+                Ok(()) => {
+                    loaded.insert(name);
+                }
+                Err(e) => tracing::warn!(
+                    target: crate::targets::PARAMETER,
+                    name = %name,
+                    error = %e,
+                    "Skipped parameter while loading YAML"
+                ),
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Set the parameters that apply to the node `node_fqn` from a YAML file.
+    ///
+    /// See [`Parameters::load_yaml_str`].
+    pub fn load_yaml(
+        &mut self,
+        node_fqn: &str,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<BTreeSet<String>> {
+        let yaml = std::fs::read_to_string(path)?;
+        self.load_yaml_str(&yaml, node_fqn)
+    }
+}
+
+/// Insert `value` under a dotted `name`, creating nested mappings.
+#[cfg(feature = "yaml")]
+fn insert_nested(hash: &mut yaml_rust2::yaml::Hash, name: &str, value: yaml_rust2::Yaml) {
+    use yaml_rust2::{Yaml, yaml::Hash};
+
+    if let Some((head, rest)) = name.split_once('.') {
+        let key = Yaml::String(head.to_string());
+        if !hash.contains_key(&key) {
+            hash.insert(key.clone(), Yaml::Hash(Hash::new()));
+        }
+        if let Some(Yaml::Hash(inner)) = hash.get_mut(&key) {
+            insert_nested(inner, rest, value);
+            return;
+        }
+    }
+    // Leaf, or a prefix already used by a parameter: keep the name flat
+    hash.insert(Yaml::String(name.to_string()), value);
+}
+
+/// Flatten nested mappings into dotted parameter names.
+#[cfg(feature = "yaml")]
+fn collect_values(
+    prefix: &str,
+    hash: &yaml_rust2::yaml::Hash,
+    out: &mut Vec<(String, Value)>,
+) -> Result<()> {
+    use yaml_rust2::Yaml;
+
+    for (key, value) in hash {
+        let key = key.as_str().ok_or("parameter name must be a string")?;
+        let name = if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            Yaml::Hash(inner) => collect_values(&name, inner, out)?,
+            _ => match Value::try_from(value) {
+                Ok(value) => out.push((name, value)),
+                Err(e) => tracing::warn!(
+                    target: crate::targets::PARAMETER,
+                    name = %name,
+                    error = %e,
+                    "Skipped parameter while loading YAML"
+                ),
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Check whether a node key of a parameter file applies to `node_fqn`.
+#[cfg(feature = "yaml")]
+fn node_matches(key: &str, node_fqn: &str) -> bool {
+    if key.contains('*') {
+        ros2args::match_wildcard_pattern(key, node_fqn)
+    } else {
+        key.trim_start_matches('/') == node_fqn.trim_start_matches('/')
+    }
+}
+
+/// Convert values whose YAML representation is ambiguous to the declared type.
+#[cfg(feature = "yaml")]
+fn coerce_value(declared: &Value, value: Value) -> Value {
+    match (declared, value) {
+        (Value::F64(_), Value::I64(v)) => Value::F64(v as f64),
+        (Value::VecF64(_), Value::VecI64(v)) => {
+            Value::VecF64(v.into_iter().map(|x| x as f64).collect())
+        }
+        (Value::VecU8(_), Value::VecI64(v)) => match v.iter().map(|&x| u8::try_from(x)).collect() {
+            Ok(bytes) => Value::VecU8(bytes),
+            Err(_) => Value::VecI64(v),
+        },
+        // Empty YAML arrays carry no element type
+        (Value::VecBool(_), Value::VecI64(v)) if v.is_empty() => Value::VecBool(Vec::new()),
+        (Value::VecString(_), Value::VecI64(v)) if v.is_empty() => Value::VecString(Vec::new()),
+        (_, value) => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde_bridging() {
+        let mut params = Parameters::new();
+        params.set_from("rate", &10u32).unwrap();
+        params.set_from("names", &vec!["a", "b"]).unwrap();
+        params
+            .set_parameter("gain".into(), Value::F64(1.0), false, None)
+            .unwrap();
+        params
+            .set_parameter("key".into(), Value::VecU8(vec![]), false, None)
+            .unwrap();
+
+        assert_eq!(params.get_as::<u32>("rate").unwrap(), 10);
+        assert_eq!(params.get_as::<f64>("rate").unwrap(), 10.0);
+        assert_eq!(params.get_as::<Vec<String>>("names").unwrap(), ["a", "b"]);
+
+        // the declared type decides between integers, floats and bytes
+        params.set_from("gain", &2).unwrap();
+        assert_eq!(params.get_parameter("gain").unwrap().value, Value::F64(2.0));
+        params.set_from("key", &[1u8, 2, 255]).unwrap();
+        assert_eq!(
+            params.get_parameter("key").unwrap().value,
+            Value::VecU8(vec![1, 2, 255])
+        );
+        assert!(params.set_from("key", &[256]).is_err());
+
+        let err = params.get_as::<u8>("names").unwrap_err().to_string();
+        assert!(err.contains("parameter names"), "{err}");
+        assert!(err.contains("VecString"), "{err}");
+        assert!(params.set_from("rate", "fast").is_err());
+        assert!(params.get_as::<i64>("missing").is_err());
+
+        params
+            .set_dynamically_typed_parameter("optional".into(), Value::NotSet, false, None)
+            .unwrap();
+        assert_eq!(params.get_as::<Option<f64>>("optional").unwrap(), None);
+        params.set_from("optional", &Some(0.5)).unwrap();
+        assert_eq!(params.get_as::<Option<f64>>("optional").unwrap(), Some(0.5));
+    }
+
+    #[test]
+    fn test_declare_group() {
+        let mut params = Parameters::new();
+        params
+            .set_parameter("controller.kp".into(), Value::F64(2.5), false, None)
+            .unwrap();
+        params
+            .set_parameter("controllers".into(), Value::Bool(true), false, None)
+            .unwrap();
+
+        params
+            .declare_group("controller", |g| {
+                g.declare("kp", 1.0).declare("ki", 0.0);
+                g.group("limits", |g| {
+                    g.declare_described("max_output", 10.0, "Saturation");
+                });
+            })
+            .unwrap();
+
+        // values set before the declaration are kept
+        let group = params.group("controller");
+        let names: Vec<_> = group.keys().copied().collect();
+        assert_eq!(names, ["ki", "kp", "limits.max_output"]);
+        assert_eq!(group["kp"].value, Value::F64(2.5));
+        assert_eq!(
+            group["limits.max_output"].descriptor.description,
+            "Saturation"
+        );
+
+        // declarations stop at the first error
+        let result = params.declare_group("controller", |g| {
+            g.declare("kp", "high").declare("kd", 0.5);
+        });
+        assert!(result.is_err());
+        assert!(params.get_parameter("controller.kd").is_none());
+    }
+
+    #[test]
+    fn test_list() {
+        let mut params = Parameters::new();
+        for name in ["a", "b.c", "b.d.e", "bb.f"] {
+            params
+                .set_parameter(name.into(), Value::I64(0), false, None)
+                .unwrap();
+        }
+
+        let all = params.list(&[], 0);
+        assert_eq!(all.names, ["a", "b.c", "b.d.e", "bb.f"]);
+        assert_eq!(all.prefixes, ["b", "b.d", "bb"]);
+        assert_eq!(params.list(&[], 1).names, ["a"]);
+        assert_eq!(params.list(&[], 2).names, ["a", "b.c", "bb.f"]);
+
+        let b = params.list(&["b".into()], 0);
+        assert_eq!(b.names, ["b.c", "b.d.e"]);
+        assert_eq!(params.list(&["b".into()], 1).names, ["b.c"]);
+        assert_eq!(params.list(&["b.c".into()], 1).names, ["b.c"]);
+    }
+
+    #[test]
+    fn test_update() {
+        let mut params = Parameters::new();
+        params
+            .set_parameter("rate".into(), Value::F64(10.0), false, None)
+            .unwrap();
+        params
+            .set_parameter("id".into(), Value::I64(1), true, None)
+            .unwrap();
+        params
+            .set_dynamically_typed_parameter("any".into(), Value::Bool(true), false, None)
+            .unwrap();
+        params.take_updated();
+
+        assert!(params.update("rate", Value::I64(1)).is_err());
+        assert!(params.update("id", Value::I64(2)).is_err());
+        assert!(params.update("unknown", Value::I64(2)).is_err());
+        assert!(params.update("any", Value::I64(2)).is_ok());
+
+        // nothing is set when one of the updates fails
+        let result = params.update_atomically(vec![
+            ("rate".into(), Value::F64(20.0)),
+            ("id".into(), Value::I64(2)),
+        ]);
+        assert!(result.is_err());
+        assert_eq!(
+            params.get_parameter("rate").unwrap().value,
+            Value::F64(10.0)
+        );
+        params
+            .update_atomically(vec![("rate".into(), Value::F64(20.0))])
+            .unwrap();
+        assert_eq!(
+            params.get_parameter("rate").unwrap().value,
+            Value::F64(20.0)
+        );
+        assert_eq!(
+            params.take_updated().into_iter().collect::<Vec<_>>(),
+            ["any", "rate"]
+        );
+    }
+
+    #[test]
+    fn test_set_descriptor() {
+        let mut params = Parameters::new();
+        params
+            .set_parameter("rate".into(), Value::I64(10), false, None)
+            .unwrap();
+        params.take_updated();
+
+        params
+            .update_descriptor("rate", |desc| {
+                desc.description = "Publishing rate".into();
+                desc.additional_constraints = "Multiple of 5".into();
+                desc.integer_range = Some(IntegerRange {
+                    min: 0,
+                    max: 100,
+                    step: 5,
+                });
+            })
+            .unwrap();
+        let descriptor = &params.get_parameter("rate").unwrap().descriptor;
+        assert_eq!(descriptor.additional_constraints, "Multiple of 5");
+        assert!(params.take_updated().contains("rate"));
+        assert!(
+            params
+                .set_parameter("rate".into(), Value::I64(12), false, None)
+                .is_err()
+        );
+
+        // the current value must satisfy the new constraints
+        let mut narrow = params.get_parameter("rate").unwrap().descriptor.clone();
+        narrow.integer_range = Some(IntegerRange {
+            min: 20,
+            max: 100,
+            step: 1,
+        });
+        assert!(params.set_descriptor("rate", narrow).is_err());
+
+        // ranges must match the type of the value
+        let wrong_type = Descriptor {
+            floating_point_range: Some(FloatingPointRange {
+                min: 0.0,
+                max: 1.0,
+                step: 0.0,
+            }),
+            ..Default::default()
+        };
+        assert!(params.set_descriptor("rate", wrong_type).is_err());
+        assert!(
+            params
+                .set_descriptor("missing", Descriptor::default())
+                .is_err()
+        );
+        assert!(params.take_updated().is_empty());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_roundtrip() {
+        let mut params = Parameters::new();
+        params
+            .set_parameter("gain".into(), Value::F64(1.0), false, None)
+            .unwrap();
+        params
+            .set_parameter("camera.exposure".into(), Value::I64(20), false, None)
+            .unwrap();
+        params
+            .set_parameter(
+                "frames".into(),
+                Value::VecString(vec!["map".into(), "odom".into()]),
+                false,
+                None,
+            )
+            .unwrap();
+
+        let yaml = params.to_yaml("/robot/driver").unwrap();
+        assert!(yaml.starts_with("/robot/driver:"));
+        assert!(yaml.contains("camera:"));
+
+        let mut loaded = Parameters::new();
+        let names = loaded.load_yaml_str(&yaml, "/robot/driver").unwrap();
+        assert_eq!(names.len(), 3);
+        assert_eq!(
+            loaded.get_parameter("camera.exposure").unwrap().value,
+            Value::I64(20)
+        );
+        assert_eq!(loaded.get_parameter("gain").unwrap().value, Value::F64(1.0));
+    }
+
+    #[test]
+    fn test_snapshot_generation() {
+        let mut params = Parameters::new();
+        params
+            .set_parameter("gain".into(), Value::F64(0.5), false, None)
+            .unwrap();
+        let generation = params.generation();
+        let snapshot = params.snapshot();
+        assert_eq!(snapshot.generation(), generation);
+        assert!(!snapshot.is_stale());
+
+        // Failed updates are not counted
+        assert!(params.update("gain", Value::Bool(true)).is_err());
+        assert!(!snapshot.is_stale());
+
+        params.update("gain", Value::F64(2.0)).unwrap();
+        assert!(snapshot.is_stale());
+        assert_eq!(params.generation(), generation + 1);
+        assert_eq!(snapshot.get_as::<f64>("gain").unwrap(), 0.5);
+
+        let mut snapshot = params.snapshot();
+        params
+            .set_floating_point_range("gain", 0.0, 10.0, 0.0)
+            .unwrap();
+        assert!(snapshot.is_stale());
+        assert!(snapshot.refresh_with(|| params.snapshot()));
+        assert!(
+            snapshot
+                .get_parameter("gain")
+                .unwrap()
+                .descriptor
+                .floating_point_range
+                .is_some()
+        );
+
+        params.params_mut().remove("gain");
+        assert!(snapshot.refresh_with(|| params.snapshot()));
+        assert!(snapshot.params().is_empty());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_load_wildcard_and_coercion() {
+        let mut params = Parameters::new();
+        params
+            .set_parameter("gain".into(), Value::F64(0.5), false, None)
+            .unwrap();
+
+        let yaml = "
+/**:
+  ros__parameters:
+    gain: 2
+/other_node:
+  ros__parameters:
+    ignored: true
+";
+        let names = params.load_yaml_str(yaml, "/robot/driver").unwrap();
+        assert_eq!(names.into_iter().collect::<Vec<_>>(), vec!["gain"]);
+        assert_eq!(params.get_parameter("gain").unwrap().value, Value::F64(2.0));
+        assert!(params.get_parameter("ignored").is_none());
+    }
+}
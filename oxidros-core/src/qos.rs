@@ -1,6 +1,10 @@
 //! Quality of Service (QoS) policies and profiles for ROS2.
 
-use std::time::Duration;
+use crate::{
+    error::{Error, Result},
+    parameter::Value,
+};
+use std::{sync::Arc, time::Duration};
 
 /// QoS history policy - how samples are stored.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -180,6 +184,25 @@ impl Profile {
         }
     }
 
+    /// Clock QoS profile, of the subscribers of the simulated time:
+    /// - History: Keep last
+    /// - Depth: 1
+    /// - Reliability: Best effort
+    /// - Durability: Volatile
+    pub const fn clock() -> Self {
+        Self {
+            history: HistoryPolicy::KeepLast,
+            depth: 1,
+            reliability: ReliabilityPolicy::BestEffort,
+            durability: DurabilityPolicy::Volatile,
+            deadline: Duration::ZERO,
+            lifespan: Duration::ZERO,
+            liveliness: LivelinessPolicy::SystemDefault,
+            liveliness_lease_duration: Duration::ZERO,
+            avoid_ros_namespace_conventions: false,
+        }
+    }
+
     /// Parameters QoS profile:
     /// - History: Keep last
     /// - Depth: 1000
@@ -199,3 +222,449 @@ impl Profile {
         }
     }
 }
+
+// ============================================================================
+// QoS compatibility
+// ============================================================================
+
+/// Result of a QoS compatibility check between a publisher and a subscription.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QosCompatibility {
+    /// The endpoints match.
+    Ok,
+    /// The endpoints may not match, or match with a degraded behavior.
+    Warning(String),
+    /// The endpoints do not match.
+    Error(String),
+}
+
+impl QosCompatibility {
+    /// Whether the endpoints match, possibly with a warning.
+    pub fn is_compatible(&self) -> bool {
+        !matches!(self, Self::Error(_))
+    }
+}
+
+// ============================================================================
+// QoS events
+// ============================================================================
+
+/// QoS event of a publisher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublisherEventKind {
+    /// The publisher did not publish within [`Profile::deadline`].
+    OfferedDeadlineMissed,
+    /// The publisher did not assert its liveliness within
+    /// [`Profile::liveliness_lease_duration`].
+    LivelinessLost,
+}
+
+/// Counters reported by a QoS event.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QosEventStatus {
+    /// Number of times the event occurred.
+    pub total_count: i32,
+    /// Change of `total_count` since the status was last read.
+    pub total_count_change: i32,
+}
+
+// ============================================================================
+// QoS overrides
+// ============================================================================
+
+/// A QoS policy that can be overridden through parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QosPolicyKind {
+    /// [`Profile::history`]
+    History,
+    /// [`Profile::depth`]
+    Depth,
+    /// [`Profile::reliability`]
+    Reliability,
+    /// [`Profile::durability`]
+    Durability,
+    /// [`Profile::deadline`]
+    Deadline,
+    /// [`Profile::lifespan`]
+    Lifespan,
+    /// [`Profile::liveliness`]
+    Liveliness,
+    /// [`Profile::liveliness_lease_duration`]
+    LivelinessLeaseDuration,
+    /// [`Profile::avoid_ros_namespace_conventions`]
+    AvoidRosNamespaceConventions,
+}
+
+impl QosPolicyKind {
+    /// Name of the policy in `qos_overrides` parameters.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            QosPolicyKind::History => "history",
+            QosPolicyKind::Depth => "depth",
+            QosPolicyKind::Reliability => "reliability",
+            QosPolicyKind::Durability => "durability",
+            QosPolicyKind::Deadline => "deadline",
+            QosPolicyKind::Lifespan => "lifespan",
+            QosPolicyKind::Liveliness => "liveliness",
+            QosPolicyKind::LivelinessLeaseDuration => "liveliness_lease_duration",
+            QosPolicyKind::AvoidRosNamespaceConventions => "avoid_ros_namespace_conventions",
+        }
+    }
+}
+
+/// Kind of entity whose QoS is overridden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QosEntityKind {
+    /// A publisher.
+    Publisher,
+    /// A subscriber.
+    Subscription,
+}
+
+impl QosEntityKind {
+    /// Name of the entity kind in `qos_overrides` parameters.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            QosEntityKind::Publisher => "publisher",
+            QosEntityKind::Subscription => "subscription",
+        }
+    }
+}
+
+/// Callback validating a QoS profile after the overrides are applied.
+///
+/// Returns the reason of the rejection on failure.
+pub type QosValidationCallback =
+    Arc<dyn Fn(&Profile) -> std::result::Result<(), String> + Send + Sync>;
+
+/// Which QoS policies of an entity may be overridden through parameters.
+///
+/// This follows the `qos_overrides` parameter convention of rclcpp. For a
+/// publisher on `/scan`, the reliability is read from the parameter
+/// `qos_overrides./scan.publisher.reliability`; for a subscriber it is read
+/// from `qos_overrides./scan.subscription.reliability`. When an `id` is set,
+/// the entity kind becomes `publisher_<id>` or `subscription_<id>` so that
+/// several entities on the same topic can be tuned separately.
+///
+/// Overrides are taken from the parameters given on the command line and in
+/// parameter files when the entity is created.
+///
+/// # Example
+///
+/// ```
+/// use oxidros_core::qos::{QosOverridingOptions, QosPolicyKind};
+///
+/// let options = QosOverridingOptions::new(&[QosPolicyKind::Reliability, QosPolicyKind::Depth])
+///     .validation_callback(|qos| {
+///         if qos.depth > 0 {
+///             Ok(())
+///         } else {
+///             Err("depth must be positive".into())
+///         }
+///     });
+/// ```
+#[derive(Clone, Default)]
+pub struct QosOverridingOptions {
+    policies: Vec<QosPolicyKind>,
+    id: Option<String>,
+    validation: Option<QosValidationCallback>,
+}
+
+impl std::fmt::Debug for QosOverridingOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QosOverridingOptions")
+            .field("policies", &self.policies)
+            .field("id", &self.id)
+            .field("validation", &self.validation.is_some())
+            .finish()
+    }
+}
+
+impl QosOverridingOptions {
+    /// Allow overriding `policies`.
+    pub fn new(policies: &[QosPolicyKind]) -> Self {
+        Self {
+            policies: policies.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// Allow overriding the history, depth and reliability policies.
+    pub fn with_default_policies() -> Self {
+        Self::new(&[
+            QosPolicyKind::History,
+            QosPolicyKind::Depth,
+            QosPolicyKind::Reliability,
+        ])
+    }
+
+    /// Distinguish the parameters of this entity from the ones of other
+    /// entities on the same topic.
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Validate the profile after the overrides are applied.
+    pub fn validation_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&Profile) -> std::result::Result<(), String> + Send + Sync + 'static,
+    {
+        self.validation = Some(Arc::new(callback));
+        self
+    }
+
+    /// Policies that may be overridden.
+    pub fn policies(&self) -> &[QosPolicyKind] {
+        &self.policies
+    }
+
+    /// Name of the parameter overriding `policy` of an entity on `topic`.
+    ///
+    /// `topic` is the fully qualified topic name.
+    pub fn parameter_name(
+        &self,
+        topic: &str,
+        entity: QosEntityKind,
+        policy: QosPolicyKind,
+    ) -> String {
+        match &self.id {
+            Some(id) => format!(
+                "qos_overrides.{topic}.{}_{id}.{}",
+                entity.as_str(),
+                policy.as_str()
+            ),
+            None => format!(
+                "qos_overrides.{topic}.{}.{}",
+                entity.as_str(),
+                policy.as_str()
+            ),
+        }
+    }
+
+    /// Apply the overrides found by `lookup` to `qos`, then validate it.
+    ///
+    /// `lookup` returns the value of a parameter by name, if it is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if a parameter has an invalid value or
+    /// the validation callback rejects the profile.
+    pub fn apply<F>(
+        &self,
+        topic: &str,
+        entity: QosEntityKind,
+        mut qos: Profile,
+        lookup: F,
+    ) -> Result<Profile>
+    where
+        F: Fn(&str) -> Option<Value>,
+    {
+        for &policy in &self.policies {
+            let name = self.parameter_name(topic, entity, policy);
+            if let Some(value) = lookup(&name) {
+                set_policy(&mut qos, policy, &value).map_err(|reason| {
+                    Error::InvalidConfig(format!("parameter '{name}' = {value}: {reason}"))
+                })?;
+            }
+        }
+
+        if let Some(validation) = &self.validation {
+            validation(&qos).map_err(|reason| {
+                Error::InvalidConfig(format!(
+                    "QoS overrides of {} on '{topic}' rejected: {reason}",
+                    entity.as_str()
+                ))
+            })?;
+        }
+
+        Ok(qos)
+    }
+}
+
+/// Set a policy of `qos` from a parameter value.
+fn set_policy(
+    qos: &mut Profile,
+    policy: QosPolicyKind,
+    value: &Value,
+) -> std::result::Result<(), String> {
+    match policy {
+        QosPolicyKind::History => {
+            qos.history = match as_str(value)? {
+                "keep_last" => HistoryPolicy::KeepLast,
+                "keep_all" => HistoryPolicy::KeepAll,
+                "system_default" => HistoryPolicy::SystemDefault,
+                s => return Err(format!("unknown history policy '{s}'")),
+            }
+        }
+        QosPolicyKind::Depth => {
+            qos.depth = usize::try_from(as_i64(value)?).map_err(|_| "depth must not be negative")?
+        }
+        QosPolicyKind::Reliability => {
+            qos.reliability = match as_str(value)? {
+                "reliable" => ReliabilityPolicy::Reliable,
+                "best_effort" => ReliabilityPolicy::BestEffort,
+                "best_available" => ReliabilityPolicy::BestAvailable,
+                "system_default" => ReliabilityPolicy::SystemDefault,
+                s => return Err(format!("unknown reliability policy '{s}'")),
+            }
+        }
+        QosPolicyKind::Durability => {
+            qos.durability = match as_str(value)? {
+                "volatile" => DurabilityPolicy::Volatile,
+                "transient_local" => DurabilityPolicy::TransientLocal,
+                "best_available" => DurabilityPolicy::BestAvailable,
+                "system_default" => DurabilityPolicy::SystemDefault,
+                s => return Err(format!("unknown durability policy '{s}'")),
+            }
+        }
+        QosPolicyKind::Deadline => qos.deadline = as_duration(value)?,
+        QosPolicyKind::Lifespan => qos.lifespan = as_duration(value)?,
+        QosPolicyKind::Liveliness => {
+            qos.liveliness = match as_str(value)? {
+                "automatic" => LivelinessPolicy::Automatic,
+                "manual_by_topic" => LivelinessPolicy::ManualByTopic,
+                "best_available" => LivelinessPolicy::BestAvailable,
+                "system_default" => LivelinessPolicy::SystemDefault,
+                s => return Err(format!("unknown liveliness policy '{s}'")),
+            }
+        }
+        QosPolicyKind::LivelinessLeaseDuration => {
+            qos.liveliness_lease_duration = as_duration(value)?
+        }
+        QosPolicyKind::AvoidRosNamespaceConventions => match value {
+            Value::Bool(b) => qos.avoid_ros_namespace_conventions = *b,
+            _ => return Err("expected a bool".into()),
+        },
+    }
+    Ok(())
+}
+
+fn as_str(value: &Value) -> std::result::Result<&str, String> {
+    match value {
+        Value::String(s) => Ok(s.as_str()),
+        _ => Err("expected a string".into()),
+    }
+}
+
+fn as_i64(value: &Value) -> std::result::Result<i64, String> {
+    match value {
+        Value::I64(v) => Ok(*v),
+        _ => Err("expected an integer".into()),
+    }
+}
+
+/// Durations are given in nanoseconds, like in rclcpp.
+fn as_duration(value: &Value) -> std::result::Result<Duration, String> {
+    u64::try_from(as_i64(value)?)
+        .map(Duration::from_nanos)
+        .map_err(|_| "duration must not be negative".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn lookup(params: &[(&str, Value)]) -> impl Fn(&str) -> Option<Value> + use<> {
+        let params: BTreeMap<String, Value> = params
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+        move |name| params.get(name).cloned()
+    }
+
+    #[test]
+    fn test_parameter_name() {
+        let options = QosOverridingOptions::with_default_policies();
+        assert_eq!(
+            options.parameter_name(
+                "/scan",
+                QosEntityKind::Subscription,
+                QosPolicyKind::Reliability
+            ),
+            "qos_overrides./scan.subscription.reliability"
+        );
+
+        let options = options.id("fast");
+        assert_eq!(
+            options.parameter_name("/scan", QosEntityKind::Publisher, QosPolicyKind::Depth),
+            "qos_overrides./scan.publisher_fast.depth"
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let params = lookup(&[
+            (
+                "qos_overrides./scan.subscription.reliability",
+                Value::String("best_effort".into()),
+            ),
+            ("qos_overrides./scan.subscription.depth", Value::I64(3)),
+            (
+                "qos_overrides./scan.subscription.deadline",
+                Value::I64(1_000_000),
+            ),
+            (
+                "qos_overrides./scan.publisher.durability",
+                Value::String("transient_local".into()),
+            ),
+        ]);
+
+        let options = QosOverridingOptions::new(&[
+            QosPolicyKind::Reliability,
+            QosPolicyKind::Depth,
+            QosPolicyKind::Durability,
+        ]);
+        let qos = options
+            .apply(
+                "/scan",
+                QosEntityKind::Subscription,
+                Profile::default(),
+                &params,
+            )
+            .unwrap();
+        assert_eq!(qos.reliability, ReliabilityPolicy::BestEffort);
+        assert_eq!(qos.depth, 3);
+        // Not an overridable policy
+        assert_eq!(qos.deadline, Duration::ZERO);
+        // Only set for publishers
+        assert_eq!(qos.durability, DurabilityPolicy::Volatile);
+    }
+
+    #[test]
+    fn test_apply_invalid() {
+        let params = lookup(&[(
+            "qos_overrides./scan.publisher.reliability",
+            Value::String("sometimes".into()),
+        )]);
+        let options = QosOverridingOptions::with_default_policies();
+        assert!(matches!(
+            options.apply(
+                "/scan",
+                QosEntityKind::Publisher,
+                Profile::default(),
+                &params
+            ),
+            Err(Error::InvalidConfig(_))
+        ));
+
+        let params = lookup(&[("qos_overrides./scan.publisher.depth", Value::I64(0))]);
+        let options = options.validation_callback(|qos| {
+            if qos.depth > 0 {
+                Ok(())
+            } else {
+                Err("depth must be positive".into())
+            }
+        });
+        assert!(matches!(
+            options.apply(
+                "/scan",
+                QosEntityKind::Publisher,
+                Profile::default(),
+                &params
+            ),
+            Err(Error::InvalidConfig(_))
+        ));
+    }
+}
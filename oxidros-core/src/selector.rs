@@ -4,8 +4,11 @@
 //! where multiple sources (subscriptions, services, timers) can be waited on
 //! simultaneously.
 
-use crate::{Message, ServiceMsg, parameter::Parameters};
-use std::collections::BTreeSet;
+use crate::{Error, Message, Result, ServiceMsg, parameter::Parameters, targets};
+use std::{
+    any::Any, cell::RefCell, collections::BTreeSet, panic::AssertUnwindSafe, rc::Rc,
+    time::SystemTime,
+};
 
 /// Result type for callback functions.
 #[derive(Debug, Eq, PartialEq)]
@@ -17,6 +20,156 @@ pub enum CallbackResult {
     Remove,
 }
 
+/// What a periodic timer does when its ticks are late.
+///
+/// A tick is missed when the previous callbacks, or any other callback of the
+/// selector, ran for longer than the period of the timer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissedTickPolicy {
+    /// Call the handler once for every missed tick, then keep the original
+    /// timeline.
+    Burst,
+
+    /// Call the handler once, drop the missed ticks and keep the original
+    /// timeline.
+    #[default]
+    Skip,
+
+    /// Call the handler once and restart the timeline one period after it.
+    Delay,
+}
+
+/// Tick of a periodic timer, passed to its handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerTick {
+    /// Time at which this tick was scheduled.
+    pub expected: SystemTime,
+
+    /// Number of ticks missed before this one.
+    ///
+    /// With [`MissedTickPolicy::Burst`], the number of missed ticks still to
+    /// be handled after this one.
+    pub missed: u64,
+}
+
+/// What a selector does when a callback panics.
+///
+/// In every case, the panic is caught before it unwinds through the selector,
+/// logged with the name of the entity of the callback, and the entity is
+/// removed from the selector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanicPolicy {
+    /// Keep waiting on the other entities.
+    #[default]
+    RemoveEntity,
+
+    /// Resume the panic once the other ready callbacks have run, so that it
+    /// unwinds from `wait` in the thread of the selector.
+    Rethrow,
+
+    /// Stop the selector: `wait` returns [`Error::CallbackPanicked`] from
+    /// then on, so that the loop calling it ends.
+    Shutdown,
+}
+
+/// Catches the panics of the callbacks of a selector and applies its
+/// [`PanicPolicy`].
+///
+/// Clones share their state, so that the callbacks registered on a selector
+/// report their panics to it.
+#[derive(Clone, Default)]
+pub struct PanicContainment {
+    state: Rc<RefCell<PanicState>>,
+}
+
+#[derive(Default)]
+struct PanicState {
+    policy: PanicPolicy,
+    /// Panic to resume with [`PanicPolicy::Rethrow`].
+    payload: Option<Box<dyn Any + Send>>,
+    /// Entity and message of the panic that stopped the selector.
+    shutdown: Option<(String, String)>,
+}
+
+impl PanicContainment {
+    /// How the panics of the callbacks are handled.
+    pub fn policy(&self) -> PanicPolicy {
+        self.state.borrow().policy
+    }
+
+    /// Set how the panics of the callbacks are handled.
+    pub fn set_policy(&self, policy: PanicPolicy) {
+        self.state.borrow_mut().policy = policy;
+    }
+
+    /// Call the callback of `entity`.
+    ///
+    /// Returns `None` if the callback panicked, in which case the caller
+    /// removes the entity from the selector.
+    pub fn call<R>(&self, entity: &str, callback: impl FnOnce() -> R) -> Option<R> {
+        let payload = match std::panic::catch_unwind(AssertUnwindSafe(callback)) {
+            Ok(ret) => return Some(ret),
+            Err(payload) => payload,
+        };
+        let message = panic_message(payload.as_ref());
+        let mut state = self.state.borrow_mut();
+        tracing::error!(
+            target: targets::SELECTOR,
+            entity,
+            message,
+            policy = ?state.policy,
+            "Callback panicked, removing its entity"
+        );
+        match state.policy {
+            PanicPolicy::RemoveEntity => {}
+            PanicPolicy::Rethrow => {
+                state.payload.get_or_insert(payload);
+            }
+            PanicPolicy::Shutdown => {
+                state
+                    .shutdown
+                    .get_or_insert_with(|| (entity.to_string(), message.to_string()));
+            }
+        }
+        None
+    }
+
+    /// Apply the policy to the panics caught so far, called by the selector
+    /// before and after dispatching the callbacks.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CallbackPanicked`] once a callback panicked with
+    /// [`PanicPolicy::Shutdown`].
+    ///
+    /// # Panics
+    ///
+    /// Resumes the first panic caught with [`PanicPolicy::Rethrow`].
+    pub fn check(&self) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        if let Some(payload) = state.payload.take() {
+            drop(state);
+            std::panic::resume_unwind(payload);
+        }
+        match &state.shutdown {
+            Some((entity, message)) => Err(Error::CallbackPanicked {
+                entity: entity.clone(),
+                message: message.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Message of a panic payload, as printed by the default panic hook.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>")
+}
+
 pub type ServerCallback<T> =
     Box<dyn FnMut(Message<<T as ServiceMsg>::Request>) -> <T as ServiceMsg>::Response>;
 pub type ParameterCallback = Box<dyn FnMut(&mut Parameters, BTreeSet<String>)>;
@@ -28,3 +181,188 @@ pub struct ConditionHandler<T> {
 }
 
 pub type ActionHandler = Box<dyn FnMut() -> CallbackResult>;
+
+/// Events of a file descriptor waited on by a selector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FdInterest {
+    /// Wait until data can be read.
+    pub readable: bool,
+
+    /// Wait until data can be written.
+    pub writable: bool,
+}
+
+impl FdInterest {
+    /// Wait until data can be read.
+    pub const READABLE: Self = Self {
+        readable: true,
+        writable: false,
+    };
+
+    /// Wait until data can be written.
+    pub const WRITABLE: Self = Self {
+        readable: false,
+        writable: true,
+    };
+
+    /// Wait until data can be read or written.
+    pub const READ_WRITE: Self = Self {
+        readable: true,
+        writable: true,
+    };
+}
+
+/// Readiness of a file descriptor, passed to its handler.
+///
+/// Readiness is level-triggered: the handler is called again as long as the
+/// file descriptor stays ready, e.g. until the available data are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FdReadiness {
+    /// Data can be read.
+    pub readable: bool,
+
+    /// Data can be written.
+    pub writable: bool,
+
+    /// The peer closed its end, e.g. the device of a serial port was unplugged.
+    pub hangup: bool,
+
+    /// An error occurred on the file descriptor, or it is not open.
+    pub error: bool,
+}
+
+impl FdReadiness {
+    /// Whether any event occurred.
+    pub fn is_ready(&self) -> bool {
+        self.readable || self.writable || self.hangup || self.error
+    }
+}
+
+/// Wait until one of `fds` is ready or `timeout` elapses, forever if `None`.
+///
+/// Returns the readiness of each file descriptor, in the order of `fds`. An
+/// interrupted wait returns with no file descriptor ready.
+///
+/// # Errors
+///
+/// Returns the error of `poll(2)`.
+#[cfg(unix)]
+pub fn poll_fds(
+    fds: &[(std::os::fd::RawFd, FdInterest)],
+    timeout: Option<std::time::Duration>,
+) -> std::io::Result<Vec<FdReadiness>> {
+    let mut pollfds: Vec<libc::pollfd> = fds
+        .iter()
+        .map(|&(fd, interest)| {
+            let mut events = 0;
+            if interest.readable {
+                events |= libc::POLLIN;
+            }
+            if interest.writable {
+                events |= libc::POLLOUT;
+            }
+            libc::pollfd {
+                fd,
+                events,
+                revents: 0,
+            }
+        })
+        .collect();
+    let timeout = timeout.map_or(-1, |t| {
+        // Round up so that a short timeout does not busy-loop
+        t.as_nanos()
+            .div_ceil(1_000_000)
+            .min(libc::c_int::MAX as u128) as libc::c_int
+    });
+
+    // SAFETY: `pollfds` is a valid array of `pollfds.len()` entries.
+    let ret = unsafe { libc::poll(pollfds.as_mut_ptr(), pollfds.len() as libc::nfds_t, timeout) };
+    if ret < 0 {
+        let err = std::io::Error::last_os_error();
+        if err.kind() != std::io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+        pollfds.iter_mut().for_each(|p| p.revents = 0);
+    }
+
+    Ok(pollfds
+        .iter()
+        .map(|p| FdReadiness {
+            readable: p.revents & libc::POLLIN != 0,
+            writable: p.revents & libc::POLLOUT != 0,
+            hangup: p.revents & libc::POLLHUP != 0,
+            error: p.revents & (libc::POLLERR | libc::POLLNVAL) != 0,
+        })
+        .collect())
+}
+
+/// Check that `fd` is an open file descriptor.
+///
+/// # Errors
+///
+/// Returns `EBADF` if it is not.
+#[cfg(unix)]
+pub fn check_fd(fd: std::os::fd::RawFd) -> std::io::Result<()> {
+    // SAFETY: F_GETFD only reads the flags of the file descriptor.
+    if fd < 0 || unsafe { libc::fcntl(fd, libc::F_GETFD) } < 0 {
+        return Err(std::io::Error::from_raw_os_error(libc::EBADF));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(unix)]
+    use std::{io::Write, os::fd::AsRawFd, os::unix::net::UnixStream, time::Duration};
+
+    #[test]
+    fn test_panic_containment() {
+        let panics = PanicContainment::default();
+        assert_eq!(panics.call("ok", || 1), Some(1));
+        assert_eq!(panics.call("remove", || -> i32 { panic!("bad") }), None);
+        assert!(panics.check().is_ok());
+
+        panics.set_policy(PanicPolicy::Rethrow);
+        assert_eq!(panics.call("rethrow", || panic!("again")), None::<()>);
+        let payload = std::panic::catch_unwind(AssertUnwindSafe(|| panics.check())).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "again");
+        assert!(panics.check().is_ok());
+
+        panics.set_policy(PanicPolicy::Shutdown);
+        let _ = panics.call::<()>("subscriber /chatter", || panic!("{}", String::from("stop")));
+        let err = panics.check().unwrap_err();
+        assert!(matches!(
+            err,
+            Error::CallbackPanicked { entity, message }
+                if entity == "subscriber /chatter" && message == "stop"
+        ));
+        assert!(panics.check().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_poll_fds() {
+        let (mut a, b) = UnixStream::pair().unwrap();
+        let fds = [(b.as_raw_fd(), FdInterest::READABLE)];
+
+        let ready = poll_fds(&fds, Some(Duration::from_millis(10))).unwrap();
+        assert!(!ready[0].is_ready());
+
+        a.write_all(b"ping").unwrap();
+        let ready = poll_fds(&fds, Some(Duration::from_millis(100))).unwrap();
+        assert!(ready[0].readable);
+
+        drop(a);
+        let ready = poll_fds(&fds, Some(Duration::from_millis(100))).unwrap();
+        assert!(ready[0].readable || ready[0].hangup);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_fd() {
+        let (a, _b) = UnixStream::pair().unwrap();
+        assert!(check_fd(a.as_raw_fd()).is_ok());
+        assert!(check_fd(-1).is_err());
+    }
+}
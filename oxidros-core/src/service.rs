@@ -0,0 +1,30 @@
+//! Request queue of service servers.
+//!
+//! A server buffers the requests it has not received yet. Bounding that
+//! queue protects a node against request storms from misbehaving clients;
+//! [`ServerOptions`] sets its size and what happens to the requests arriving
+//! when it is full. Pass them to
+//! [`RosNode::create_server_with_options`](crate::api::RosNode::create_server_with_options).
+
+/// What a server does with an incoming request when its queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SheddingPolicy {
+    /// Answer the incoming request with an error, so that the client fails
+    /// immediately instead of timing out.
+    #[default]
+    RejectWithError,
+    /// Drop the oldest pending request to make room for the incoming one.
+    ///
+    /// The client of the dropped request receives no response.
+    DropOldest,
+}
+
+/// Configuration of the queue between the middleware and a server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ServerOptions {
+    /// Maximum number of requests waiting to be received (default of the
+    /// backend if `None`).
+    pub max_pending: Option<usize>,
+    /// Policy applied when the queue is full.
+    pub shedding: SheddingPolicy,
+}
@@ -0,0 +1,217 @@
+//! Cooperative shutdown of async tasks.
+//!
+//! A [`ShutdownToken`] is a cheap, clonable flag that async loops can wait on
+//! next to their regular work. Once the token is triggered, the loops finish
+//! the message they are handling, flush their publishers with
+//! [`RosPublisher::flush`](crate::api::RosPublisher::flush) and return,
+//! instead of being killed mid-publish.
+//!
+//! [`ShutdownToken::on_signal`] returns the process-wide token triggered by
+//! SIGINT, SIGTERM, SIGHUP and SIGQUIT (SIGINT and SIGTERM on Windows). A
+//! second signal received while shutting down exits the process at once, so a
+//! stuck drain can still be interrupted. Backends which handle the signals
+//! themselves trigger the same token, see [`ShutdownToken::signal_token`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::prelude::*;
+//!
+//! let shutdown = ctx.shutdown_token();
+//! loop {
+//!     tokio::select! {
+//!         _ = shutdown.wait() => break,
+//!         msg = subscriber.recv() => publisher.send(&process(msg?))?,
+//!     }
+//! }
+//! publisher.flush().await?;
+//! ```
+
+use crate::targets;
+use parking_lot::Mutex;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    task::{Context, Poll, Waker},
+};
+
+static SIGNAL_TOKEN: OnceLock<ShutdownToken> = OnceLock::new();
+
+#[derive(Debug, Default)]
+struct Inner {
+    triggered: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+/// A flag telling async tasks to stop.
+///
+/// Clones share the same state: triggering one triggers all of them.
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownToken {
+    inner: Arc<Inner>,
+}
+
+impl ShutdownToken {
+    /// Create a token that is only triggered by [`ShutdownToken::shutdown`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the process-wide token triggered by termination signals.
+    ///
+    /// The signal handlers are installed on the first call. From then on,
+    /// the first signal no longer terminates the process: the application is
+    /// expected to wait on the token and return.
+    pub fn on_signal() -> Self {
+        static INSTALLED: OnceLock<()> = OnceLock::new();
+
+        let token = Self::signal_token();
+        INSTALLED.get_or_init(|| install_signal_handlers(token.clone()));
+        token
+    }
+
+    /// Get the process-wide token without installing signal handlers.
+    ///
+    /// For backends which receive the termination signals themselves and
+    /// trigger this token, so that the handlers are not installed twice.
+    pub fn signal_token() -> Self {
+        SIGNAL_TOKEN.get_or_init(ShutdownToken::new).clone()
+    }
+
+    /// Trigger the token and wake every task waiting on it.
+    pub fn shutdown(&self) {
+        if self.inner.triggered.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let wakers = std::mem::take(&mut *self.inner.wakers.lock());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Return `true` once the token has been triggered.
+    pub fn is_shutdown(&self) -> bool {
+        self.inner.triggered.load(Ordering::SeqCst)
+    }
+
+    /// Wait until the token is triggered.
+    ///
+    /// The returned future is cancel-safe and can be used in `select!`.
+    pub fn wait(&self) -> Shutdown {
+        Shutdown {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// Future returned by [`ShutdownToken::wait`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Shutdown {
+    inner: Arc<Inner>,
+}
+
+impl Future for Shutdown {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.inner.triggered.load(Ordering::SeqCst) {
+            return Poll::Ready(());
+        }
+
+        let mut wakers = self.inner.wakers.lock();
+        // Check again under the lock, as `shutdown` takes the wakers after
+        // setting the flag.
+        if self.inner.triggered.load(Ordering::SeqCst) {
+            return Poll::Ready(());
+        }
+        if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+/// Trigger `token` on the first signal and exit on the second one.
+fn on_signal(token: &ShutdownToken, signal: i32) {
+    if token.is_shutdown() {
+        std::process::exit(128 + signal);
+    }
+    tracing::info!(
+        target: targets::ROOT,
+        signal = signal,
+        "Shutdown requested"
+    );
+    token.shutdown();
+}
+
+#[cfg(not(target_os = "windows"))]
+fn install_signal_handlers(token: ShutdownToken) {
+    use signal_hook::{consts::*, iterator::Signals};
+
+    let mut signals =
+        Signals::new([SIGINT, SIGTERM, SIGHUP, SIGQUIT]).expect("register signal handlers");
+    std::thread::Builder::new()
+        .name("oxidros-shutdown".into())
+        .spawn(move || {
+            for signal in signals.forever() {
+                on_signal(&token, signal);
+            }
+        })
+        .expect("spawn shutdown thread");
+}
+
+#[cfg(target_os = "windows")]
+fn install_signal_handlers(token: ShutdownToken) {
+    use signal_hook::consts::*;
+
+    for signal in [SIGINT, SIGTERM] {
+        let token = token.clone();
+        // SAFETY: On Windows, signal handlers run in a separate thread created by Windows
+        // (Console Control Handler), so using locks is safe.
+        unsafe {
+            signal_hook::low_level::register(signal, move || on_signal(&token, signal))
+                .expect("register signal handlers");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::Wake;
+
+    struct Flag(AtomicBool);
+
+    impl Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_shutdown_wakes_waiters() {
+        let token = ShutdownToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_shutdown());
+
+        let flag = Arc::new(Flag(AtomicBool::new(false)));
+        let waker = Waker::from(flag.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut wait = Box::pin(clone.wait());
+        assert!(wait.as_mut().poll(&mut cx).is_pending());
+        assert!(wait.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(token.inner.wakers.lock().len(), 1);
+
+        token.shutdown();
+        assert!(flag.0.load(Ordering::SeqCst));
+        assert!(clone.is_shutdown());
+        assert!(wait.as_mut().poll(&mut cx).is_ready());
+        assert!(Box::pin(token.wait()).as_mut().poll(&mut cx).is_ready());
+    }
+}
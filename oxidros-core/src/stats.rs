@@ -0,0 +1,143 @@
+//! Introspection counters of nodes.
+//!
+//! Backends count the publishers, subscribers, servers and clients of each
+//! node with [`EntityCounters`]: every entity holds an [`EntityToken`] for as
+//! long as it lives. [`RosNode::stats`](crate::api::RosNode::stats) reports
+//! these counts as [`NodeStats`], along with the state of the subscriber and
+//! server queues when the backend can observe it.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+/// Kind of entity counted by [`EntityCounters`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityKind {
+    /// A topic publisher.
+    Publisher,
+    /// A topic subscriber.
+    Subscriber,
+    /// A service server.
+    Server,
+    /// A service client.
+    Client,
+}
+
+/// Introspection counters of a node at one point in time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodeStats {
+    /// Number of live publishers.
+    pub publishers: usize,
+
+    /// Number of live subscribers.
+    pub subscribers: usize,
+
+    /// Number of live service servers.
+    pub servers: usize,
+
+    /// Number of live service clients.
+    pub clients: usize,
+
+    /// Messages received and not taken yet, summed over the subscribers.
+    ///
+    /// `None` if the backend does not expose its queues (RCL).
+    pub queued_messages: Option<usize>,
+
+    /// Messages dropped because a subscriber queue was full, summed over the
+    /// live subscribers.
+    ///
+    /// `None` if the backend does not expose its queues (RCL).
+    pub dropped_messages: Option<u64>,
+
+    /// Requests received and not taken yet, summed over the servers.
+    ///
+    /// `None` if the backend does not expose its queues (RCL).
+    pub pending_requests: Option<usize>,
+
+    /// Requests rejected or dropped because a server queue was full, summed
+    /// over the live servers.
+    ///
+    /// `None` if the backend does not expose its queues (RCL).
+    pub shed_requests: Option<u64>,
+}
+
+/// Number of live entities of a node, by kind.
+#[derive(Debug, Default)]
+pub struct EntityCounters {
+    counts: [AtomicUsize; 4],
+}
+
+impl EntityCounters {
+    /// Create counters with no entity.
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Count an entity of `kind` until the returned token is dropped.
+    pub fn register(self: &Arc<Self>, kind: EntityKind) -> EntityToken {
+        self.counts[kind as usize].fetch_add(1, Ordering::Relaxed);
+        EntityToken {
+            counters: self.clone(),
+            kind,
+        }
+    }
+
+    /// Number of live entities of `kind`.
+    pub fn count(&self, kind: EntityKind) -> usize {
+        self.counts[kind as usize].load(Ordering::Relaxed)
+    }
+
+    /// Entity counts as [`NodeStats`], without queue information.
+    pub fn stats(&self) -> NodeStats {
+        NodeStats {
+            publishers: self.count(EntityKind::Publisher),
+            subscribers: self.count(EntityKind::Subscriber),
+            servers: self.count(EntityKind::Server),
+            clients: self.count(EntityKind::Client),
+            queued_messages: None,
+            dropped_messages: None,
+            pending_requests: None,
+            shed_requests: None,
+        }
+    }
+}
+
+/// Keeps an entity counted by [`EntityCounters`] until dropped.
+#[derive(Debug)]
+pub struct EntityToken {
+    counters: Arc<EntityCounters>,
+    kind: EntityKind,
+}
+
+impl Drop for EntityToken {
+    fn drop(&mut self) {
+        self.counters.counts[self.kind as usize].fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_counters() {
+        let counters = EntityCounters::new();
+        let publisher = counters.register(EntityKind::Publisher);
+        let _subscriber = counters.register(EntityKind::Subscriber);
+        let _client = counters.register(EntityKind::Client);
+        let second = counters.register(EntityKind::Publisher);
+
+        let stats = counters.stats();
+        assert_eq!(stats.publishers, 2);
+        assert_eq!(stats.subscribers, 1);
+        assert_eq!(stats.servers, 0);
+        assert_eq!(stats.clients, 1);
+        assert_eq!(stats.queued_messages, None);
+
+        drop(publisher);
+        drop(second);
+        assert_eq!(counters.count(EntityKind::Publisher), 0);
+        assert_eq!(counters.count(EntityKind::Subscriber), 1);
+    }
+}
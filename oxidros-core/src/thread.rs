@@ -0,0 +1,227 @@
+//! CPU affinity and real-time priority of executor threads.
+//!
+//! Deterministic control nodes keep their callbacks off the cores used by the
+//! rest of the system and run them with a real-time priority. A
+//! [`ThreadOptions`] describes the placement of a thread: the CPU cores it may
+//! run on and its `SCHED_FIFO` priority.
+//!
+//! Applying the options never fails: a setting the process is not allowed to
+//! apply (e.g. a real-time priority without `CAP_SYS_NICE` or an `rtprio`
+//! limit) is skipped with a warning on the [`THREAD`](crate::targets::THREAD)
+//! target, and the returned [`ThreadPlacement`] tells what was applied.
+//! Only Linux is supported; on other platforms nothing is applied.
+//!
+//! # Example
+//!
+//! ```
+//! use oxidros_core::thread::ThreadOptions;
+//!
+//! let options = ThreadOptions::new().cpus([2, 3]).fifo_priority(50);
+//!
+//! std::thread::spawn(move || {
+//!     let placement = options.apply();
+//!     if placement.fifo_priority.is_none() {
+//!         // Unprivileged: running with the default scheduler
+//!     }
+//! })
+//! .join()
+//! .unwrap();
+//! ```
+
+use crate::targets;
+
+/// Placement of a thread: CPU cores and scheduler priority.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThreadOptions {
+    /// CPU cores the thread may run on. Empty to keep the inherited affinity.
+    pub cpus: Vec<usize>,
+
+    /// Pin each worker to a single core of [`cpus`](Self::cpus), in turn,
+    /// instead of letting every worker run on all of them.
+    pub pin_per_worker: bool,
+
+    /// `SCHED_FIFO` priority, clamped to the range of the platform (1 to 99
+    /// on Linux). `None` to keep the inherited scheduler.
+    pub fifo_priority: Option<i32>,
+}
+
+/// Settings actually applied by [`ThreadOptions::apply`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThreadPlacement {
+    /// CPU cores the thread is pinned to, if the affinity was set.
+    pub cpus: Option<Vec<usize>>,
+
+    /// `SCHED_FIFO` priority of the thread, if the scheduler was set.
+    pub fifo_priority: Option<i32>,
+}
+
+impl ThreadOptions {
+    /// Options keeping the inherited placement.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the CPU cores the thread may run on.
+    pub fn cpus(mut self, cpus: impl IntoIterator<Item = usize>) -> Self {
+        self.cpus = cpus.into_iter().collect();
+        self
+    }
+
+    /// Pin each worker to a single core, see [`apply_to_worker`](Self::apply_to_worker).
+    pub fn pin_per_worker(mut self, enable: bool) -> Self {
+        self.pin_per_worker = enable;
+        self
+    }
+
+    /// Set the `SCHED_FIFO` priority of the thread.
+    pub fn fifo_priority(mut self, priority: i32) -> Self {
+        self.fifo_priority = Some(priority);
+        self
+    }
+
+    /// Whether the options keep the inherited placement.
+    pub fn is_default(&self) -> bool {
+        self.cpus.is_empty() && self.fifo_priority.is_none()
+    }
+
+    /// Apply the options to the calling thread.
+    ///
+    /// With [`pin_per_worker`](Self::pin_per_worker), the thread is pinned to
+    /// the first core.
+    pub fn apply(&self) -> ThreadPlacement {
+        self.apply_to_worker(0)
+    }
+
+    /// Apply the options to the calling thread, the `index`-th worker of a
+    /// pool.
+    ///
+    /// With [`pin_per_worker`](Self::pin_per_worker), the worker is pinned to
+    /// the core `cpus[index % cpus.len()]`.
+    pub fn apply_to_worker(&self, index: usize) -> ThreadPlacement {
+        let cpus = if self.pin_per_worker && !self.cpus.is_empty() {
+            vec![self.cpus[index % self.cpus.len()]]
+        } else {
+            self.cpus.clone()
+        };
+
+        let mut placement = ThreadPlacement::default();
+        if !cpus.is_empty() {
+            match sys::set_affinity(&cpus) {
+                Ok(()) => placement.cpus = Some(cpus),
+                Err(e) => tracing::warn!(
+                    target: targets::THREAD,
+                    "Cannot pin the thread to CPUs {cpus:?}: {e}"
+                ),
+            }
+        }
+        if let Some(priority) = self.fifo_priority {
+            match sys::set_fifo_priority(priority) {
+                Ok(priority) => placement.fifo_priority = Some(priority),
+                Err(e) => tracing::warn!(
+                    target: targets::THREAD,
+                    "Cannot set the SCHED_FIFO priority {priority}: {e}"
+                ),
+            }
+        }
+        placement
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::io;
+
+    pub(super) fn set_affinity(cpus: &[usize]) -> io::Result<()> {
+        // SAFETY: `cpu_set_t` is a plain bit set, zeroed is the empty set.
+        let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        for &cpu in cpus {
+            if cpu >= libc::CPU_SETSIZE as usize {
+                return Err(io::Error::from(io::ErrorKind::InvalidInput));
+            }
+            // SAFETY: `cpu` is below `CPU_SETSIZE`, checked above.
+            unsafe { libc::CPU_SET(cpu, &mut set) };
+        }
+        // SAFETY: `set` is a valid CPU set and 0 is the calling thread.
+        let ret = unsafe { libc::sched_setaffinity(0, std::mem::size_of_val(&set), &set) };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    pub(super) fn set_fifo_priority(priority: i32) -> io::Result<i32> {
+        // SAFETY: plain queries of the scheduler.
+        let (min, max) = unsafe {
+            (
+                libc::sched_get_priority_min(libc::SCHED_FIFO),
+                libc::sched_get_priority_max(libc::SCHED_FIFO),
+            )
+        };
+        let priority = priority.clamp(min, max);
+        let param = libc::sched_param {
+            sched_priority: priority,
+        };
+        // SAFETY: `param` is valid for the duration of the call.
+        let ret =
+            unsafe { libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param) };
+        if ret == 0 {
+            Ok(priority)
+        } else {
+            Err(io::Error::from_raw_os_error(ret))
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use std::io;
+
+    pub(super) fn set_affinity(_cpus: &[usize]) -> io::Result<()> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+
+    pub(super) fn set_fifo_priority(_priority: i32) -> io::Result<i32> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_options() {
+        let options = ThreadOptions::new();
+        assert!(options.is_default());
+        assert_eq!(options.apply(), ThreadPlacement::default());
+    }
+
+    #[test]
+    fn test_invalid_cpu_is_skipped() {
+        let placement = std::thread::spawn(|| ThreadOptions::new().cpus([usize::MAX]).apply())
+            .join()
+            .unwrap();
+        assert_eq!(placement.cpus, None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_pin_per_worker() {
+        // SAFETY: plain query of the affinity of the calling thread.
+        let allowed = unsafe {
+            let mut set: libc::cpu_set_t = std::mem::zeroed();
+            libc::sched_getaffinity(0, std::mem::size_of_val(&set), &mut set);
+            (0..libc::CPU_SETSIZE as usize)
+                .filter(|&cpu| libc::CPU_ISSET(cpu, &set))
+                .collect::<Vec<_>>()
+        };
+        let options = ThreadOptions::new()
+            .cpus(allowed.clone())
+            .pin_per_worker(true);
+        let placement = std::thread::spawn(move || options.apply_to_worker(1))
+            .join()
+            .unwrap();
+        assert_eq!(placement.cpus, Some(vec![allowed[1 % allowed.len()]]));
+    }
+}
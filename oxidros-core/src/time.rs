@@ -1,6 +1,403 @@
 //! Time-related types for ROS2 messages.
 //!
-//! This module re-exports the time types from `ros2-type-hash`.
+//! This module re-exports the time types from `ros2-type-hash` and defines the
+//! types shared by the clock implementations to report time jumps, the
+//! stamping of messages by publishers, and the [`VirtualClock`] driving timers
+//! in tests.
+
+use crate::{
+    RclError, Result,
+    msg::{Stamped, TryClone},
+};
+use parking_lot::Mutex;
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
 
 // Re-export time types from ros2-type-hash
 pub use ros2_types::{UnsafeDuration, UnsafeTime};
+
+/// Time source of a clock.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum ClockType {
+    /// ROS time: the system time, or the simulated time when overridden.
+    #[default]
+    Ros,
+
+    /// Wall clock time; may jump when the system time is set.
+    System,
+
+    /// Monotonic time, unaffected by changes of the system time.
+    Steady,
+}
+
+/// Change of time source that accompanies a time jump.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClockChange {
+    /// ROS time is active and its value changed.
+    RosTimeNoChange,
+
+    /// ROS time override was enabled (e.g., sim time started).
+    RosTimeActivated,
+
+    /// ROS time override was disabled.
+    RosTimeDeactivated,
+
+    /// System time changed (e.g., an NTP step).
+    SystemTimeNoChange,
+}
+
+/// Description of a time jump, passed to the post-jump callback.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TimeJump {
+    /// Change of time source, if any.
+    pub clock_change: ClockChange,
+
+    /// Size of the jump in nanoseconds; negative for backward jumps.
+    pub delta_nanos: i64,
+}
+
+/// Conditions under which jump callbacks are invoked.
+///
+/// # Example
+///
+/// ```
+/// use oxidros_core::time::JumpThreshold;
+/// use std::time::Duration;
+///
+/// // React to any backward jump and to sim time being toggled.
+/// let threshold = JumpThreshold {
+///     on_clock_change: true,
+///     min_forward: None,
+///     min_backward: Some(Duration::from_nanos(1)),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JumpThreshold {
+    /// Invoke the callbacks when the time source changes.
+    pub on_clock_change: bool,
+
+    /// Minimum forward jump that invokes the callbacks; `None` disables it.
+    pub min_forward: Option<Duration>,
+
+    /// Minimum backward jump (as a magnitude) that invokes the callbacks;
+    /// `None` disables it.
+    pub min_backward: Option<Duration>,
+}
+
+impl JumpThreshold {
+    /// Returns `true` if `jump` should invoke the callbacks.
+    ///
+    /// Follows the rules of `rcl`: clock changes are only reported when
+    /// `on_clock_change` is set, other jumps are compared against the forward
+    /// and backward thresholds.
+    pub fn is_exceeded(&self, jump: &TimeJump) -> bool {
+        match jump.clock_change {
+            ClockChange::RosTimeActivated | ClockChange::RosTimeDeactivated => self.on_clock_change,
+            ClockChange::RosTimeNoChange | ClockChange::SystemTimeNoChange => {
+                let delta = i128::from(jump.delta_nanos);
+                let forward = self
+                    .min_forward
+                    .is_some_and(|min| !min.is_zero() && delta >= min.as_nanos() as i128);
+                let backward = self
+                    .min_backward
+                    .is_some_and(|min| !min.is_zero() && -delta >= min.as_nanos() as i128);
+                forward || backward
+            }
+        }
+    }
+}
+
+/// Parameter switching the ROS time of a node to the simulated time.
+///
+/// The parameter servers declare it, and while it is `true` the clock of the
+/// node follows the messages of [`CLOCK_TOPIC`].
+pub const USE_SIM_TIME: &str = "use_sim_time";
+
+/// Topic of the simulated time, of type `rosgraph_msgs/msg/Clock`.
+pub const CLOCK_TOPIC: &str = "/clock";
+
+/// Callback invoked before a time jump is applied.
+pub type JumpPreCallback = Box<dyn FnMut() + Send>;
+
+/// Callback invoked after a time jump was applied.
+pub type JumpPostCallback = Box<dyn FnMut(&TimeJump) + Send>;
+
+/// Identifies a jump callback registered on a clock.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct JumpHandle(u64);
+
+impl JumpHandle {
+    /// Create a handle from a backend-specific identifier.
+    pub const fn from_raw(id: u64) -> Self {
+        Self(id)
+    }
+
+    /// Returns the backend-specific identifier.
+    pub const fn as_raw(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Stamping function of a publisher with automatic stamping, see
+/// [`stamp_if_unset`].
+pub type StampFn<T> = fn(&T, Duration) -> Result<Option<T>>;
+
+/// Copy `msg` with its stamp set to `now`, a time since the epoch, if its
+/// stamp is zero.
+///
+/// Publishers with automatic stamping publish the copy instead of `msg`.
+/// Returns `None` if the stamp of `msg` is set.
+///
+/// # Errors
+///
+/// Returns [`RclError::BadAlloc`] if `msg` cannot be copied.
+pub fn stamp_if_unset<T: Stamped + TryClone>(msg: &T, now: Duration) -> Result<Option<T>> {
+    if msg.stamp() != UnsafeTime::zero() {
+        return Ok(None);
+    }
+    let mut copy = msg.try_clone().ok_or(RclError::BadAlloc)?;
+    copy.set_stamp(UnsafeTime::new(now.as_secs() as i32, now.subsec_nanos()));
+    Ok(Some(copy))
+}
+
+/// Manually advanced clock, for fast and deterministic tests of timeout,
+/// watchdog and retry logic.
+///
+/// The time of the clock only changes by [`VirtualClock::advance`]. It drives
+/// the timers of the selectors it is set on (see
+/// [`RosSelector::set_virtual_clock`](crate::api::RosSelector::set_virtual_clock))
+/// and the futures returned by [`VirtualClock::sleep`], which complete when
+/// the clock is advanced past their deadline. Clones share the same time.
+///
+/// # Example
+///
+/// ```
+/// use oxidros_core::time::VirtualClock;
+/// use std::{
+///     future::Future,
+///     pin::pin,
+///     task::{Context, Waker},
+///     time::Duration,
+/// };
+///
+/// let clock = VirtualClock::new();
+/// let mut sleep = pin!(clock.sleep(Duration::from_secs(5)));
+/// let mut cx = Context::from_waker(Waker::noop());
+///
+/// clock.advance(Duration::from_secs(4));
+/// assert!(sleep.as_mut().poll(&mut cx).is_pending());
+/// clock.advance(Duration::from_secs(1));
+/// assert!(sleep.as_mut().poll(&mut cx).is_ready());
+/// ```
+#[derive(Clone, Default)]
+pub struct VirtualClock {
+    time: Arc<Mutex<VirtualTime>>,
+}
+
+#[derive(Default)]
+struct VirtualTime {
+    now: Duration,
+    /// Deadlines and wakers of the pending sleeps, by identifier.
+    sleepers: BTreeMap<u64, (Duration, Waker)>,
+    next_sleeper_id: u64,
+}
+
+impl VirtualClock {
+    /// Create a clock at time zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Time elapsed on the clock since its creation.
+    pub fn now(&self) -> Duration {
+        self.time.lock().now
+    }
+
+    /// Move the time forward by `duration`, waking the sleeps whose deadline
+    /// is reached.
+    pub fn advance(&self, duration: Duration) {
+        let wakers: Vec<_> = {
+            let mut time = self.time.lock();
+            time.now += duration;
+            let now = time.now;
+            let expired: Vec<_> = time
+                .sleepers
+                .iter()
+                .filter(|(_, (deadline, _))| *deadline <= now)
+                .map(|(&id, _)| id)
+                .collect();
+            expired
+                .into_iter()
+                .filter_map(|id| time.sleepers.remove(&id))
+                .map(|(_, waker)| waker)
+                .collect()
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Future completing once the clock is advanced by `duration`.
+    pub fn sleep(&self, duration: Duration) -> VirtualSleep {
+        self.sleep_until(self.now() + duration)
+    }
+
+    /// Future completing once the time of the clock reaches `deadline`.
+    pub fn sleep_until(&self, deadline: Duration) -> VirtualSleep {
+        let mut time = self.time.lock();
+        let id = time.next_sleeper_id;
+        time.next_sleeper_id += 1;
+        VirtualSleep {
+            clock: self.clone(),
+            deadline,
+            id,
+        }
+    }
+}
+
+impl std::fmt::Debug for VirtualClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let time = self.time.lock();
+        f.debug_struct("VirtualClock")
+            .field("now", &time.now)
+            .field("sleepers", &time.sleepers.len())
+            .finish()
+    }
+}
+
+/// Future returned by [`VirtualClock::sleep`] and
+/// [`VirtualClock::sleep_until`].
+#[must_use = "futures do nothing unless polled"]
+pub struct VirtualSleep {
+    clock: VirtualClock,
+    deadline: Duration,
+    id: u64,
+}
+
+impl VirtualSleep {
+    /// Time of the clock at which the future completes.
+    pub fn deadline(&self) -> Duration {
+        self.deadline
+    }
+}
+
+impl Future for VirtualSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut time = self.clock.time.lock();
+        if time.now >= self.deadline {
+            time.sleepers.remove(&self.id);
+            Poll::Ready(())
+        } else {
+            time.sleepers
+                .insert(self.id, (self.deadline, cx.waker().clone()));
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for VirtualSleep {
+    fn drop(&mut self) {
+        self.clock.time.lock().sleepers.remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jump_threshold() {
+        let threshold = JumpThreshold {
+            on_clock_change: false,
+            min_forward: Some(Duration::from_secs(1)),
+            min_backward: Some(Duration::from_millis(1)),
+        };
+
+        let jump = |clock_change, delta_nanos| TimeJump {
+            clock_change,
+            delta_nanos,
+        };
+        assert!(threshold.is_exceeded(&jump(ClockChange::RosTimeNoChange, 1_000_000_000)));
+        assert!(!threshold.is_exceeded(&jump(ClockChange::RosTimeNoChange, 999_999_999)));
+        assert!(threshold.is_exceeded(&jump(ClockChange::SystemTimeNoChange, -1_000_000)));
+        assert!(!threshold.is_exceeded(&jump(ClockChange::SystemTimeNoChange, -999_999)));
+        assert!(!threshold.is_exceeded(&jump(ClockChange::RosTimeActivated, 0)));
+        assert!(
+            JumpThreshold {
+                on_clock_change: true,
+                ..Default::default()
+            }
+            .is_exceeded(&jump(ClockChange::RosTimeDeactivated, 0))
+        );
+    }
+
+    struct Stamp(UnsafeTime);
+
+    impl Stamped for Stamp {
+        fn stamp(&self) -> UnsafeTime {
+            self.0
+        }
+
+        fn set_stamp(&mut self, stamp: UnsafeTime) {
+            self.0 = stamp;
+        }
+    }
+
+    impl TryClone for Stamp {
+        fn try_clone(&self) -> Option<Self> {
+            Some(Stamp(self.0))
+        }
+    }
+
+    #[test]
+    fn test_stamp_if_unset() {
+        let now = Duration::new(12, 34);
+        let stamped = stamp_if_unset(&Stamp(UnsafeTime::zero()), now).unwrap();
+        assert_eq!(stamped.map(|s| s.0), Some(UnsafeTime::new(12, 34)));
+
+        let set = Stamp(UnsafeTime::new(1, 0));
+        assert!(stamp_if_unset(&set, now).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_virtual_clock() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::task::Wake;
+
+        struct Counter(AtomicUsize);
+
+        impl Wake for Counter {
+            fn wake(self: Arc<Self>) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let clock = VirtualClock::new();
+        let counter = Arc::new(Counter(AtomicUsize::new(0)));
+        let waker = Waker::from(counter.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut short = std::pin::pin!(clock.sleep(Duration::from_millis(100)));
+        let mut long = std::pin::pin!(clock.sleep(Duration::from_secs(1)));
+        assert!(short.as_mut().poll(&mut cx).is_pending());
+        assert!(long.as_mut().poll(&mut cx).is_pending());
+
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(clock.now(), Duration::from_millis(100));
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        assert!(short.as_mut().poll(&mut cx).is_ready());
+        assert!(long.as_mut().poll(&mut cx).is_pending());
+
+        // Clones share the time
+        clock.clone().advance(Duration::from_secs(1));
+        assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+        assert!(long.as_mut().poll(&mut cx).is_ready());
+        assert_eq!(clock.time.lock().sleepers.len(), 0);
+    }
+}
@@ -35,11 +35,39 @@
 //! - `oxidros::action` - Action client/server operations
 //! - `oxidros::selector` - Selector/spin operations
 //! - `oxidros::timer` - Timer operations
+//! - `oxidros::clock` - Clocks and simulated time
 //! - `oxidros::rcl` - RCL-specific operations
 //! - `oxidros::zenoh` - Zenoh-specific operations
 //! - `oxidros::zenoh::publisher` - Zenoh publisher operations
 //! - `oxidros::zenoh::subscriber` - Zenoh subscriber operations
 //! - `oxidros::zenoh::service` - Zenoh service operations
+//! - `oxidros::zenoh::transport` - Zenoh transport events
+//! - `oxidros::task` - Slow polls of watched async tasks
+//!
+//! # Spans
+//!
+//! Callback dispatch is wrapped in `DEBUG` level spans whose names are listed
+//! in [`spans`]. Each span carries the entity name (`topic`, `service` or
+//! `timer`) and, where available, the message sequence number (`seq`), so
+//! exporters such as OpenTelemetry or `tokio-console` can break latency down
+//! per entity:
+//!
+//! ```ignore
+//! use oxidros_core::spans;
+//!
+//! let span = tracing::debug_span!(
+//!     target: oxidros_core::targets::SELECTOR,
+//!     spans::SUBSCRIBER_CALLBACK,
+//!     topic = %topic,
+//!     seq = msg.info.sequence_number,
+//! );
+//! let _enter = span.enter();
+//! ```
+//!
+//! Background threads spawned by oxidros are named with the `oxidros-` prefix
+//! so they can be told apart in debuggers and profilers. Background async
+//! tasks run in a [`spans::TASK`] span carrying their name in `task`; built
+//! with `--cfg tokio_unstable`, they are also named for `tokio-console`.
 
 /// Tracing target constants for consistent naming across crates.
 pub mod targets {
@@ -64,6 +92,9 @@ pub mod targets {
     /// Target for timer operations.
     pub const TIMER: &str = "oxidros::timer";
 
+    /// Target for clocks and the simulated time.
+    pub const CLOCK: &str = "oxidros::clock";
+
     /// Target for RCL backend operations.
     pub const RCL: &str = "oxidros::rcl";
 
@@ -79,6 +110,9 @@ pub mod targets {
     /// Target for Zenoh service operations.
     pub const ZENOH_SERVICE: &str = "oxidros::zenoh::service";
 
+    /// Target for Zenoh transport events.
+    pub const ZENOH_TRANSPORT: &str = "oxidros::zenoh::transport";
+
     /// Target for node lifecycle operations.
     pub const NODE: &str = "oxidros::node";
 
@@ -87,4 +121,47 @@ pub mod targets {
 
     /// Target for parameter operations.
     pub const PARAMETER: &str = "oxidros::parameter";
+
+    /// Target for bonds and their supervision.
+    pub const BOND: &str = "oxidros::bond";
+
+    /// Target for launched nodes.
+    pub const LAUNCH: &str = "oxidros::launch";
+
+    /// Target for the node templates of the kit.
+    pub const KIT: &str = "oxidros::kit";
+
+    /// Target for the watchdog of async tasks.
+    pub const TASK: &str = "oxidros::task";
+
+    /// Target for the CPU affinity and priority of threads.
+    pub const THREAD: &str = "oxidros::thread";
+}
+
+/// Span name constants for callback and wait loop instrumentation.
+pub mod spans {
+    /// Span entered while a subscriber callback runs.
+    pub const SUBSCRIBER_CALLBACK: &str = "subscriber_callback";
+
+    /// Span entered while a service handler runs.
+    pub const SERVICE_CALLBACK: &str = "service_callback";
+
+    /// Span entered while a timer callback runs.
+    pub const TIMER_CALLBACK: &str = "timer_callback";
+
+    /// Span entered while a parameter server callback runs.
+    pub const PARAMETER_CALLBACK: &str = "parameter_callback";
+
+    /// Span entered while the handler of a file descriptor runs.
+    pub const FD_CALLBACK: &str = "fd_callback";
+
+    /// Span covering one iteration of a selector wait loop.
+    pub const SELECTOR_WAIT: &str = "selector_wait";
+
+    /// Span entered while an async task watched for slow polls is polled.
+    pub const ROS_TASK: &str = "ros_task";
+
+    /// Span entered while a background async task of oxidros is polled, e.g.
+    /// a connection of a bridge server.
+    pub const TASK: &str = "task";
 }
@@ -0,0 +1,145 @@
+//! Attribute parsing for `RosNodeStruct`
+
+use darling::FromMeta;
+use std::time::Duration;
+
+/// A Rust path given either bare (`on_scan`) or as a string (`"on_scan"`).
+#[derive(Debug, Clone)]
+pub struct PathArg(pub syn::Path);
+
+impl FromMeta for PathArg {
+    fn from_expr(expr: &syn::Expr) -> darling::Result<Self> {
+        match expr {
+            syn::Expr::Path(path) => Ok(Self(path.path.clone())),
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) => Self::from_string(&s.value()),
+            _ => Err(darling::Error::unexpected_expr_type(expr)),
+        }
+    }
+
+    fn from_string(value: &str) -> darling::Result<Self> {
+        syn::parse_str(value)
+            .map(Self)
+            .map_err(|e| darling::Error::custom(e.to_string()))
+    }
+}
+
+impl PathArg {
+    /// The path as a method name.
+    pub fn ident(&self) -> darling::Result<&syn::Ident> {
+        self.0
+            .get_ident()
+            .ok_or_else(|| darling::Error::custom("expected a method name").with_span(&self.0))
+    }
+}
+
+/// An expression kept as written, including string literals.
+#[derive(Debug, Clone)]
+pub struct ExprArg(pub syn::Expr);
+
+impl FromMeta for ExprArg {
+    fn from_expr(expr: &syn::Expr) -> darling::Result<Self> {
+        Ok(Self(expr.clone()))
+    }
+}
+
+/// `#[subscriber(topic = "scan", msg = LaserScan, handler = on_scan)]`
+#[derive(Debug, FromMeta)]
+pub struct SubscriberOpts {
+    /// Topic name
+    pub topic: String,
+
+    /// Message type
+    pub msg: PathArg,
+
+    /// Method called with every received message
+    pub handler: PathArg,
+
+    /// QoS profile expression (default profile if omitted)
+    #[darling(default)]
+    pub qos: Option<ExprArg>,
+}
+
+/// `#[timer(period = "100ms", handler = on_tick)]`
+#[derive(Debug, FromMeta)]
+pub struct TimerOpts {
+    /// Period with a unit suffix: `ns`, `us`, `ms` or `s`
+    pub period: syn::LitStr,
+
+    /// Method called on every tick
+    pub handler: PathArg,
+}
+
+/// `#[publisher(topic = "cmd_vel")]`
+#[derive(Debug, FromMeta)]
+pub struct PublisherOpts {
+    /// Topic name
+    pub topic: String,
+
+    /// QoS profile expression (default profile if omitted)
+    #[darling(default)]
+    pub qos: Option<ExprArg>,
+}
+
+/// `#[parameter(default = 1.0)]`
+#[derive(Debug, Default, FromMeta)]
+pub struct ParameterOpts {
+    /// Parameter name (the field name if omitted)
+    #[darling(default)]
+    pub name: Option<String>,
+
+    /// Default value, converted with `Into` to the field type
+    #[darling(default)]
+    pub default: Option<ExprArg>,
+
+    /// Parameter description
+    #[darling(default)]
+    pub description: Option<String>,
+
+    /// Reject updates through the parameter services
+    #[darling(default)]
+    pub read_only: bool,
+}
+
+/// Parse a period such as `"100ms"` or `"2s"`.
+pub fn parse_period(period: &str) -> Result<Duration, String> {
+    let period = period.trim();
+    let split = period
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| format!("missing unit in period `{period}` (ns, us, ms or s)"))?;
+    let (value, unit) = period.split_at(split);
+    let value: u64 = value
+        .parse()
+        .map_err(|_| format!("invalid period `{period}`"))?;
+
+    let duration = match unit.trim() {
+        "ns" => Duration::from_nanos(value),
+        "us" => Duration::from_micros(value),
+        "ms" => Duration::from_millis(value),
+        "s" => Duration::from_secs(value),
+        unit => return Err(format!("unknown unit `{unit}` in period (ns, us, ms or s)")),
+    };
+
+    if duration.is_zero() {
+        return Err("timer period must not be zero".to_string());
+    }
+    Ok(duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_period() {
+        assert_eq!(parse_period("100ms"), Ok(Duration::from_millis(100)));
+        assert_eq!(parse_period("2 s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_period("250us"), Ok(Duration::from_micros(250)));
+        assert!(parse_period("100").is_err());
+        assert!(parse_period("0ms").is_err());
+        assert!(parse_period("1.5s").is_err());
+        assert!(parse_period("10h").is_err());
+    }
+}
@@ -0,0 +1,87 @@
+//! Derive macros for composing oxidros nodes
+//!
+//! - `RosNodeStruct`: Generates the entity creation, parameter declaration and
+//!   selector registration around a struct with handler methods
+//!
+//! The generated code refers to the `oxidros` crate, which re-exports the
+//! macros; use them through `oxidros::prelude::*` rather than this crate.
+//!
+//! # Container Attributes
+//!
+//! - `#[subscriber(topic = "scan", msg = LaserScan, handler = on_scan)]` - Subscribe
+//!   and call `self.on_scan(msg)` for every message (optional `qos = expr`)
+//! - `#[timer(period = "100ms", handler = on_tick)]` - Call `self.on_tick()`
+//!   periodically (units: `ns`, `us`, `ms`, `s`)
+//!
+//! # Field Attributes
+//!
+//! - `#[publisher(topic = "cmd_vel")]` - Create a publisher (optional `qos = expr`)
+//! - `#[parameter(default = 1.0)]` - Declare a parameter named after the field
+//!   (optional `name = "..."`, `description = "..."`, `read_only`)
+//!
+//! Fields without attributes are initialized with `Default::default()`.
+
+use proc_macro::TokenStream;
+use syn::{DeriveInput, parse_macro_input};
+
+mod attrs;
+mod node_struct;
+
+/// Derive macro wiring a struct into a node
+///
+/// Generates an associated function
+/// `register(node: &Arc<Node>, selector: &mut Selector) -> Result<Rc<RefCell<Self>>>`
+/// which:
+///
+/// 1. declares the `#[parameter]` fields on a new parameter server, keeping
+///    values passed on the command line or in parameter files,
+/// 2. creates the `#[publisher]` fields,
+/// 3. registers the `#[subscriber]` and `#[timer]` handlers with the selector,
+/// 4. updates the `#[parameter]` fields when they are set through the
+///    parameter services.
+///
+/// Handlers are methods taking `&mut self`; subscriber handlers also receive
+/// the `Message<T>`.
+///
+/// # Example
+///
+/// ```ignore
+/// use oxidros::prelude::*;
+/// use oxidros::msg::common_interfaces::{geometry_msgs::msg::Twist, sensor_msgs::msg::LaserScan};
+///
+/// #[derive(RosNodeStruct)]
+/// #[subscriber(topic = "scan", msg = LaserScan, handler = on_scan)]
+/// #[timer(period = "100ms", handler = on_tick)]
+/// struct Avoider {
+///     #[publisher(topic = "cmd_vel")]
+///     cmd: Publisher<Twist>,
+///     #[parameter(default = 0.5, description = "Minimum distance to obstacles")]
+///     min_distance: f64,
+///     closest: f32,
+/// }
+///
+/// impl Avoider {
+///     fn on_scan(&mut self, msg: Message<LaserScan>) {
+///         self.closest = msg.sample.ranges.iter().copied().fold(f32::MAX, f32::min);
+///     }
+///
+///     fn on_tick(&mut self) {
+///         let mut twist = Twist::new().unwrap();
+///         if f64::from(self.closest) > self.min_distance {
+///             twist.linear.x = 0.2;
+///         }
+///         let _ = self.cmd.publish(&twist);
+///     }
+/// }
+///
+/// let avoider = Avoider::register(&node, &mut selector)?;
+/// ```
+#[proc_macro_derive(RosNodeStruct, attributes(subscriber, timer, publisher, parameter))]
+pub fn derive_ros_node_struct(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match node_struct::derive_ros_node_struct_impl(input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.write_errors().into(),
+    }
+}
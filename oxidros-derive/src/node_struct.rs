@@ -0,0 +1,353 @@
+//! RosNodeStruct derive macro implementation
+
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Attribute, DeriveInput, LitStr};
+
+use crate::attrs::{
+    ExprArg, ParameterOpts, PublisherOpts, SubscriberOpts, TimerOpts, parse_period,
+};
+
+/// How a field of the node struct is initialized.
+enum FieldKind {
+    Publisher(PublisherOpts),
+    Parameter(ParameterOpts),
+    Plain,
+}
+
+/// Parse the single node attribute of a field, if any.
+fn field_kind(attrs: &[Attribute]) -> darling::Result<FieldKind> {
+    let mut kind = FieldKind::Plain;
+    for attr in attrs {
+        let parsed = if attr.path().is_ident("publisher") {
+            FieldKind::Publisher(PublisherOpts::from_meta(&attr.meta)?)
+        } else if attr.path().is_ident("parameter") {
+            match &attr.meta {
+                // `#[parameter]` without arguments
+                syn::Meta::Path(_) => FieldKind::Parameter(ParameterOpts::default()),
+                meta => FieldKind::Parameter(ParameterOpts::from_meta(meta)?),
+            }
+        } else {
+            continue;
+        };
+
+        if !matches!(kind, FieldKind::Plain) {
+            return Err(darling::Error::custom(
+                "a field can have at most one #[publisher] or #[parameter] attribute",
+            )
+            .with_span(attr));
+        }
+        kind = parsed;
+    }
+    Ok(kind)
+}
+
+fn qos_tokens(qos: &Option<ExprArg>) -> TokenStream {
+    match qos {
+        Some(qos) => {
+            let qos = &qos.0;
+            quote! { ::core::option::Option::Some(#qos) }
+        }
+        None => quote! { ::core::option::Option::None },
+    }
+}
+
+pub fn derive_ros_node_struct_impl(input: DeriveInput) -> darling::Result<TokenStream> {
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(darling::Error::custom(
+                "RosNodeStruct can only be derived for structs with named fields",
+            )
+            .with_span(&input));
+        }
+    };
+
+    let mut errors = darling::Error::accumulator();
+
+    // Container attributes
+    let mut subscribers = Vec::new();
+    let mut timers = Vec::new();
+    for attr in &input.attrs {
+        if attr.path().is_ident("subscriber") {
+            if let Some(opts) = errors.handle(SubscriberOpts::from_meta(&attr.meta)) {
+                subscribers.push(opts);
+            }
+        } else if attr.path().is_ident("timer")
+            && let Some(opts) = errors.handle(TimerOpts::from_meta(&attr.meta))
+        {
+            match parse_period(&opts.period.value()) {
+                Ok(period) => timers.push((opts, period)),
+                Err(e) => errors.push(darling::Error::custom(e).with_span(&opts.period)),
+            }
+        }
+    }
+
+    // Field initialization
+    let mut lets = Vec::new();
+    let mut inits = Vec::new();
+    let mut param_updates = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let ty = &field.ty;
+        let var = format_ident!("__field_{}", ident);
+
+        let Some(kind) = errors.handle(field_kind(&field.attrs)) else {
+            continue;
+        };
+        match kind {
+            FieldKind::Publisher(opts) => {
+                let topic = &opts.topic;
+                let qos = qos_tokens(&opts.qos);
+                lets.push(quote! {
+                    let #var: #ty = <::oxidros::prelude::Node as ::oxidros::core::api::RosNode>::create_publisher(
+                        node, #topic, #qos,
+                    )?;
+                });
+            }
+            FieldKind::Parameter(opts) => {
+                let name = opts.name.clone().unwrap_or_else(|| ident.to_string());
+                let name = LitStr::new(&name, ident.span());
+                let default = match &opts.default {
+                    Some(default) => {
+                        let default = &default.0;
+                        quote! { ::core::convert::Into::into(#default) }
+                    }
+                    None => quote! { ::core::default::Default::default() },
+                };
+                let description = match &opts.description {
+                    Some(description) => {
+                        quote! { ::core::option::Option::Some(::std::string::String::from(#description)) }
+                    }
+                    None => quote! { ::core::option::Option::None },
+                };
+                let read_only = opts.read_only;
+
+                // Values given on the command line or in parameter files take
+                // precedence over the default.
+                lets.push(quote! {
+                    let #var: #ty = {
+                        let mut __params = __param_server.params.write();
+                        match __params.get_parameter(#name) {
+                            ::core::option::Option::Some(__param) => {
+                                <#ty as ::core::convert::TryFrom<&::oxidros::core::parameter::Value>>::try_from(&__param.value)?
+                            }
+                            ::core::option::Option::None => {
+                                let __value: #ty = #default;
+                                __params.set_parameter(
+                                    ::std::string::String::from(#name),
+                                    ::oxidros::core::parameter::Value::from(::core::clone::Clone::clone(&__value)),
+                                    #read_only,
+                                    #description,
+                                )?;
+                                __value
+                            }
+                        }
+                    };
+                });
+                param_updates.push(quote! {
+                    #name => {
+                        if let ::core::result::Result::Ok(__value) =
+                            <#ty as ::core::convert::TryFrom<&::oxidros::core::parameter::Value>>::try_from(&__param.value)
+                        {
+                            __this.#ident = __value;
+                        }
+                    }
+                });
+            }
+            FieldKind::Plain => {
+                lets.push(quote! {
+                    let #var: #ty = ::core::default::Default::default();
+                });
+            }
+        }
+        inits.push(quote! { #ident: #var });
+    }
+
+    // Callback registration
+    let mut registrations = Vec::new();
+    for opts in &subscribers {
+        let Some(handler) = errors.handle(opts.handler.ident()) else {
+            continue;
+        };
+        let topic = &opts.topic;
+        let msg = &opts.msg.0;
+        let qos = qos_tokens(&opts.qos);
+        registrations.push(quote! {
+            {
+                let __subscriber = <::oxidros::prelude::Node as ::oxidros::core::api::RosNode>::create_subscriber::<#msg>(
+                    node, #topic, #qos,
+                )?;
+                let __this = ::std::rc::Rc::clone(&__this);
+                let __added = <::oxidros::prelude::Selector as ::oxidros::core::api::RosSelector>::add_subscriber(
+                    selector,
+                    __subscriber,
+                    ::std::boxed::Box::new(move |__msg| __this.borrow_mut().#handler(__msg)),
+                );
+                if !__added {
+                    return ::core::result::Result::Err(::oxidros::error::Error::Other(
+                        ::std::format!("failed to add the subscriber of {} to the selector", #topic),
+                    ));
+                }
+            }
+        });
+    }
+    for (opts, period) in &timers {
+        let Some(handler) = errors.handle(opts.handler.ident()) else {
+            continue;
+        };
+        let name = handler.to_string();
+        let nanos = period.as_nanos() as u64;
+        registrations.push(quote! {
+            {
+                let __this = ::std::rc::Rc::clone(&__this);
+                <::oxidros::prelude::Selector as ::oxidros::core::api::RosSelector>::add_wall_timer(
+                    selector,
+                    #name,
+                    ::std::time::Duration::from_nanos(#nanos),
+                    ::std::boxed::Box::new(move || __this.borrow_mut().#handler()),
+                );
+            }
+        });
+    }
+
+    errors.finish()?;
+
+    // The parameter server is only created when parameters are declared
+    let has_params = !param_updates.is_empty();
+    let create_param_server = has_params.then(|| {
+        quote! {
+            let __param_server = node.create_parameter_server()?;
+        }
+    });
+    let register_param_server = has_params.then(|| {
+        quote! {
+            {
+                let __this = ::std::rc::Rc::clone(&__this);
+                <::oxidros::prelude::Selector as ::oxidros::core::api::RosSelector>::add_parameter_server(
+                    selector,
+                    __param_server,
+                    ::std::boxed::Box::new(move |__params, __updated| {
+                        let mut __this = __this.borrow_mut();
+                        for __name in __updated.iter() {
+                            let ::core::option::Option::Some(__param) = __params.get_parameter(__name) else {
+                                continue;
+                            };
+                            match __name.as_str() {
+                                #(#param_updates)*
+                                _ => {}
+                            }
+                        }
+                    }),
+                );
+            }
+        }
+    });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Create the publishers and parameters of this node on `node` and
+            /// register its subscribers, timers and parameter updates with
+            /// `selector`.
+            pub fn register(
+                node: &::std::sync::Arc<::oxidros::prelude::Node>,
+                selector: &mut ::oxidros::prelude::Selector,
+            ) -> ::oxidros::error::Result<::std::rc::Rc<::std::cell::RefCell<Self>>> {
+                #create_param_server
+                #(#lets)*
+                let __this = ::std::rc::Rc::new(::std::cell::RefCell::new(Self {
+                    #(#inits,)*
+                }));
+                #(#registrations)*
+                #register_param_server
+                ::core::result::Result::Ok(__this)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn expand(input: DeriveInput) -> String {
+        derive_ros_node_struct_impl(input)
+            .expect("valid input")
+            .to_string()
+    }
+
+    #[test]
+    fn test_expand_entities() {
+        let expanded = expand(parse_quote! {
+            #[subscriber(topic = "scan", msg = LaserScan, handler = on_scan)]
+            #[timer(period = "100ms", handler = "on_tick")]
+            struct Avoider {
+                #[publisher(topic = "cmd_vel", qos = Profile::sensor_data())]
+                cmd: Publisher<Twist>,
+                closest: f32,
+            }
+        });
+
+        assert!(expanded.contains("create_publisher (node , \"cmd_vel\" , :: core :: option :: Option :: Some (Profile :: sensor_data ()) ,)"));
+        assert!(expanded.contains("create_subscriber :: < LaserScan > (node , \"scan\" , :: core :: option :: Option :: None ,)"));
+        assert!(expanded.contains("__this . borrow_mut () . on_scan (__msg)"));
+        assert!(expanded.contains("add_wall_timer (selector , \"on_tick\" , :: std :: time :: Duration :: from_nanos (100000000u64)"));
+        assert!(
+            expanded.contains(
+                "let __field_closest : f32 = :: core :: default :: Default :: default () ;"
+            )
+        );
+        // No parameter server without parameters
+        assert!(!expanded.contains("create_parameter_server"));
+    }
+
+    #[test]
+    fn test_expand_parameters() {
+        let expanded = expand(parse_quote! {
+            struct Limits {
+                #[parameter(default = 0.5, description = "Minimum distance")]
+                min_distance: f64,
+                #[parameter(name = "max_speed", read_only)]
+                speed: f64,
+            }
+        });
+
+        assert!(expanded.contains("let __param_server = node . create_parameter_server () ? ;"));
+        assert!(expanded.contains("get_parameter (\"min_distance\")"));
+        assert!(expanded.contains(":: core :: convert :: Into :: into (0.5)"));
+        assert!(expanded.contains("get_parameter (\"max_speed\")"));
+        assert!(expanded.contains("\"max_speed\" => {"));
+        assert!(expanded.contains("__this . speed = __value"));
+        assert!(expanded.contains("add_parameter_server"));
+    }
+
+    #[test]
+    fn test_expand_errors() {
+        let err = derive_ros_node_struct_impl(parse_quote! {
+            struct Tuple(u64);
+        })
+        .unwrap_err();
+        assert_eq!(err.len(), 1);
+
+        // Errors of all attributes are reported together
+        let err = derive_ros_node_struct_impl(parse_quote! {
+            #[timer(period = "100", handler = on_tick)]
+            #[timer(period = "1s", handler = Self::on_tick)]
+            struct Ticker {
+                #[publisher(topic = "a")]
+                #[parameter]
+                value: u64,
+            }
+        })
+        .unwrap_err();
+        assert_eq!(err.len(), 3);
+    }
+}
@@ -18,7 +18,9 @@
 mod decoder;
 mod encoder;
 mod error;
+mod message;
 
 pub use decoder::decode_cdr;
 pub use encoder::encode_cdr;
 pub use error::{DynamicError, Result};
+pub use message::DynamicMessage;
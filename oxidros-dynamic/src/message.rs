@@ -0,0 +1,109 @@
+//! Runtime-typed message container.
+//!
+//! A [`DynamicMessage`] pairs a `serde_json::Value` with the
+//! [`TypeDescriptionMsg`] that describes its layout, so it can be converted
+//! to and from CDR without compile-time type knowledge.
+
+use crate::error::Result;
+use crate::{decode_cdr, encode_cdr};
+use ros2_types::types::TypeDescriptionMsg;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// A message whose type is only known at runtime.
+///
+/// The type description is reference-counted so that many messages of the
+/// same type (e.g. every response of a generic client) can share it.
+///
+/// # Example
+///
+/// ```ignore
+/// use oxidros_dynamic::DynamicMessage;
+///
+/// let request = DynamicMessage::new(request_desc, serde_json::json!({"a": 1, "b": 2}));
+/// let cdr = request.to_cdr()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct DynamicMessage {
+    type_desc: Arc<TypeDescriptionMsg>,
+    value: Value,
+}
+
+impl DynamicMessage {
+    /// Create a message from a type description and a JSON value.
+    ///
+    /// The value is not validated until it is encoded with [`to_cdr`](Self::to_cdr).
+    pub fn new(type_desc: impl Into<Arc<TypeDescriptionMsg>>, value: Value) -> Self {
+        Self {
+            type_desc: type_desc.into(),
+            value,
+        }
+    }
+
+    /// Decode a message from CDR bytes (including the 4-byte encapsulation header).
+    pub fn from_cdr(data: &[u8], type_desc: impl Into<Arc<TypeDescriptionMsg>>) -> Result<Self> {
+        let type_desc = type_desc.into();
+        let value = decode_cdr(data, &type_desc)?;
+        Ok(Self { type_desc, value })
+    }
+
+    /// Encode the message into CDR bytes (including the 4-byte encapsulation header).
+    pub fn to_cdr(&self) -> Result<Vec<u8>> {
+        encode_cdr(&self.value, &self.type_desc)
+    }
+
+    /// Fully qualified ROS2 type name (e.g. `"example_interfaces/srv/AddTwoInts_Request"`).
+    pub fn type_name(&self) -> &str {
+        &self.type_desc.type_description.type_name
+    }
+
+    /// Type description of this message.
+    pub fn type_description(&self) -> &Arc<TypeDescriptionMsg> {
+        &self.type_desc
+    }
+
+    /// Message contents.
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Mutable access to the message contents.
+    pub fn value_mut(&mut self) -> &mut Value {
+        &mut self.value
+    }
+
+    /// Consume the message and return its contents.
+    pub fn into_value(self) -> Value {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ros2_types::types::*;
+
+    fn add_two_ints_request() -> TypeDescriptionMsg {
+        TypeDescriptionMsg {
+            type_description: IndividualTypeDescription {
+                type_name: "example_interfaces/srv/AddTwoInts_Request".to_string(),
+                fields: vec![
+                    Field::new("a", FieldType::primitive(FIELD_TYPE_INT64)),
+                    Field::new("b", FieldType::primitive(FIELD_TYPE_INT64)),
+                ],
+            },
+            referenced_type_descriptions: vec![],
+        }
+    }
+
+    #[test]
+    fn test_dynamic_message_roundtrip() {
+        let msg = DynamicMessage::new(add_two_ints_request(), serde_json::json!({"a": 1, "b": 2}));
+        assert_eq!(msg.type_name(), "example_interfaces/srv/AddTwoInts_Request");
+
+        let cdr = msg.to_cdr().unwrap();
+        let decoded = DynamicMessage::from_cdr(&cdr, msg.type_description().clone()).unwrap();
+        assert_eq!(decoded.value()["a"], 1);
+        assert_eq!(decoded.value()["b"], 2);
+    }
+}
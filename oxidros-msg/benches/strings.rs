@@ -0,0 +1,43 @@
+//! Benchmarks of `RosString`, as used by `Header::frame_id`.
+//!
+//! Run with `cargo bench -p oxidros-msg` (add `--features rcl` for the FFI
+//! strings).
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use oxidros_msg::{TryClone, common_interfaces::std_msgs::msg::Header};
+
+const FRAMES: [&str; 4] = ["base_link", "odom", "map", "camera_color_optical_frame"];
+
+fn assign(c: &mut Criterion) {
+    let mut header = Header::new().unwrap();
+    let mut i = 0;
+    c.bench_function("frame_id assign", |b| {
+        b.iter(|| {
+            i = (i + 1) % FRAMES.len();
+            header.frame_id.assign(black_box(FRAMES[i]));
+        })
+    });
+}
+
+fn access(c: &mut Criterion) {
+    let mut header = Header::new().unwrap();
+    header.frame_id.assign("camera_color_optical_frame");
+
+    c.bench_function("frame_id as_str", |b| {
+        b.iter(|| black_box(&header).frame_id.as_str().map(str::len))
+    });
+    c.bench_function("frame_id get_string", |b| {
+        b.iter(|| black_box(&header).frame_id.get_string().len())
+    });
+}
+
+fn header_clone(c: &mut Criterion) {
+    let mut header = Header::new().unwrap();
+    header.frame_id.assign("camera_color_optical_frame");
+    c.bench_function("header try_clone", |b| {
+        b.iter(|| black_box(&header).try_clone().unwrap())
+    });
+}
+
+criterion_group!(benches, assign, access, header_clone);
+criterion_main!(benches);
@@ -12,12 +12,92 @@
 //! - **No ROS2**: Copies pre-committed `src/generated/` files into `OUT_DIR/generated/`
 //!
 //! To update the pre-committed files, set `OXIDROS_REGENERATE_SRC=1` with ROS2 sourced.
+//!
+//! The list of the generated types deriving `arbitrary::Arbitrary` is written
+//! to `OUT_DIR/roundtrip_types.rs`, for the round-trip tests of every type.
 
 use std::env;
+use std::fmt::Write;
 use std::path::{Path, PathBuf};
 
 use oxidros_build::msg::{Config, RosAvailability, detect_ros_availability, get_base_generator};
 
+/// Attribute of the generated types implementing `arbitrary::Arbitrary`.
+const ARBITRARY_DERIVE: &str =
+    r#"#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]"#;
+
+/// Convert a type name to snake case, e.g. `AddTwoInts_Request` to `add_two_ints_request`.
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_uppercase() && prev_lower {
+            snake.push('_');
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+/// Collect `(test name, type path)` of the types deriving `Arbitrary` in the
+/// generated files under `dir`, whose module path is `modules`.
+fn collect_arbitrary_types(
+    dir: &Path,
+    modules: &mut Vec<String>,
+    types: &mut Vec<(String, String)>,
+) {
+    for entry in std::fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("Failed to read directory {}: {}", dir.display(), e))
+    {
+        let path = entry.unwrap().path();
+        let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+        if path.is_dir() {
+            modules.push(name);
+            collect_arbitrary_types(&path, modules, types);
+            modules.pop();
+        } else if path.extension().is_some_and(|ext| ext == "rs") && name != "mod" {
+            let source = std::fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+            // Files are re-exported by their module with `pub use file::*`
+            let mut arbitrary = false;
+            for line in source.lines().map(str::trim) {
+                if line == ARBITRARY_DERIVE {
+                    arbitrary = true;
+                } else if let Some(rest) = line.strip_prefix("pub struct ") {
+                    let ty: String = rest
+                        .chars()
+                        .take_while(|c| c.is_alphanumeric() || *c == '_')
+                        .collect();
+                    if arbitrary {
+                        types.push((
+                            format!("{}_{}", modules.join("_"), to_snake_case(&ty)),
+                            format!("oxidros_msg::{}::{}", modules.join("::"), ty),
+                        ));
+                    }
+                    arbitrary = false;
+                }
+            }
+        }
+    }
+}
+
+/// Write the `roundtrip_tests!` invocation covering the generated types
+/// deriving `Arbitrary`, included by `tests/roundtrip.rs`.
+fn write_roundtrip_types(generated: &Path, out_file: &Path) {
+    let mut types = Vec::new();
+    collect_arbitrary_types(generated, &mut Vec::new(), &mut types);
+    types.sort();
+
+    let mut tests = String::from("roundtrip_tests! {\n");
+    for (name, path) in &types {
+        writeln!(tests, "    {name}: {path},").unwrap();
+    }
+    tests.push_str("}\n");
+    std::fs::write(out_file, tests)
+        .unwrap_or_else(|e| panic!("Failed to write {}: {}", out_file.display(), e));
+}
+
 /// Recursively copy a directory tree.
 fn copy_dir_recursive(src: &Path, dst: &Path) {
     std::fs::create_dir_all(dst)
@@ -181,6 +261,8 @@ fn main() {
         copy_dir_recursive(&src_generated, &out_generated);
     }
 
+    write_roundtrip_types(&out_generated, &out_path.join("roundtrip_types.rs"));
+
     // Generate runtime_c.rs using bindgen (only when ROS2 is sourced for rcl feature)
     if availability.is_sourced() {
         oxidros_build::generate_runtime_c(out_path);
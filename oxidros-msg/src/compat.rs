@@ -0,0 +1,45 @@
+//! Compatibility of compiled-in message types with the active ROS2 distro.
+//!
+//! Binaries built against one distro may be deployed next to nodes of
+//! another, e.g. in mixed Humble/Jazzy fleets. [`check_compat`] compares the
+//! type description compiled into this crate with the one installed in the
+//! distro found in `AMENT_PREFIX_PATH`, and reports hash drift and changed
+//! fields.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros_msg::common_interfaces::sensor_msgs::msg::Imu;
+//! use oxidros_msg::compat::check_compat;
+//!
+//! let report = check_compat::<Imu>()?;
+//! if !report.is_compatible() {
+//!     eprintln!("{report}");
+//! }
+//! ```
+
+use oxidros_core::{Error, Result, TypeDescription};
+
+pub use ros2_types::compat::{CompatReport, Difference, InstalledDescription};
+
+/// Compare the compiled-in description of `T` with the installed one.
+///
+/// # Errors
+///
+/// - `Error::NotFound` if the distro does not install a description of `T`.
+///   Humble installs none.
+/// - `Error::Io` if the description cannot be read.
+/// - `Error::Serialization` if the description is malformed.
+pub fn check_compat<T: TypeDescription>() -> Result<CompatReport> {
+    let compiled = T::type_description();
+    let type_name = &compiled.type_description.type_name;
+    let path = ros2_types::compat::find_installed_description(type_name).ok_or_else(|| {
+        Error::NotFound {
+            kind: "Installed type description",
+            name: type_name.clone(),
+        }
+    })?;
+
+    let installed = InstalledDescription::from_json(&std::fs::read_to_string(path)?)?;
+    Ok(ros2_types::compat::compare(&compiled, &installed)?)
+}
@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GoalID {
     pub stamp: super::super::super::builtin_interfaces::msg::time::Time,
     pub id: crate::msg::RosString<0>,
@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GoalStatus {
     pub goal_id: super::super::super::actionlib_msgs::msg::goal_id::GoalID,
     pub status: u8,
@@ -13,6 +13,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Fibonacci_Goal {
     pub order: i32,
 }
@@ -29,6 +30,7 @@ pub struct Fibonacci_Goal {
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Fibonacci_Result {
     pub sequence: crate::msg::I32Seq<0>,
 }
@@ -45,6 +47,7 @@ pub struct Fibonacci_Result {
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Fibonacci_Feedback {
     pub sequence: crate::msg::I32Seq<0>,
 }
@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Float32MultiArray {
     pub layout: super::super::super::example_interfaces::msg::multi_array_layout::MultiArrayLayout,
     pub data: crate::msg::F32Seq<0>,
@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Accel {
     pub linear: super::super::super::geometry_msgs::msg::vector3::Vector3,
     pub angular: super::super::super::geometry_msgs::msg::vector3::Vector3,
@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Pose {
     pub position: super::super::super::geometry_msgs::msg::point::Point,
     pub orientation: super::super::super::geometry_msgs::msg::quaternion::Quaternion,
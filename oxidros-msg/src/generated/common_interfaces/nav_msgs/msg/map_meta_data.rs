@@ -9,10 +9,17 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MapMetaData {
     pub map_load_time: super::super::super::builtin_interfaces::msg::time::Time,
+    /// Unit: `m/cell`
+    #[ros2(unit = "m/cell")]
     pub resolution: f32,
+    /// Unit: `cells`
+    #[ros2(unit = "cells")]
     pub width: u32,
+    /// Unit: `cells`
+    #[ros2(unit = "cells")]
     pub height: u32,
     pub origin: super::super::super::geometry_msgs::msg::pose::Pose,
 }
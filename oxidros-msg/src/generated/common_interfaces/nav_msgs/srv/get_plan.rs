@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GetPlan_Request {
     pub start: super::super::super::geometry_msgs::msg::pose_stamped::PoseStamped,
     pub goal: super::super::super::geometry_msgs::msg::pose_stamped::PoseStamped,
@@ -23,6 +24,7 @@ pub struct GetPlan_Request {
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GetPlan_Response {
     pub plan: super::super::super::nav_msgs::msg::path::Path,
 }
@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SetMap_Request {
     pub map: super::super::super::nav_msgs::msg::occupancy_grid::OccupancyGrid,
     pub initial_pose: super::super::super::geometry_msgs::msg::pose_with_covariance_stamped::PoseWithCovarianceStamped,
@@ -22,6 +23,7 @@ pub struct SetMap_Request {
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SetMap_Response {
     pub success: bool,
 }
@@ -9,15 +9,34 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MultiEchoLaserScan {
     pub header: super::super::super::std_msgs::msg::header::Header,
+    /// Unit: `rad`
+    #[ros2(unit = "rad")]
     pub angle_min: f32,
+    /// Unit: `rad`
+    #[ros2(unit = "rad")]
     pub angle_max: f32,
+    /// Unit: `rad`
+    #[ros2(unit = "rad")]
     pub angle_increment: f32,
+    /// Unit: `seconds`
+    #[ros2(unit = "seconds")]
     pub time_increment: f32,
+    /// Unit: `seconds`
+    #[ros2(unit = "seconds")]
     pub scan_time: f32,
+    /// Unit: `m`
+    #[ros2(unit = "m")]
     pub range_min: f32,
+    /// Unit: `m`
+    #[ros2(unit = "m")]
     pub range_max: f32,
+    /// Unit: `m`
+    #[ros2(unit = "m")]
     pub ranges: super::super::super::sensor_msgs::msg::laser_echo::LaserEchoSeq<0>,
+    /// Unit: `device-specific units`
+    #[ros2(unit = "device-specific units")]
     pub intensities: super::super::super::sensor_msgs::msg::laser_echo::LaserEchoSeq<0>,
 }
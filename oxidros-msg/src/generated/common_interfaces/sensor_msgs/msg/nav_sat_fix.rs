@@ -9,12 +9,21 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct NavSatFix {
     pub header: super::super::super::std_msgs::msg::header::Header,
     pub status: super::super::super::sensor_msgs::msg::nav_sat_status::NavSatStatus,
+    /// Unit: `degrees`
+    #[ros2(unit = "degrees")]
     pub latitude: f64,
+    /// Unit: `degrees`
+    #[ros2(unit = "degrees")]
     pub longitude: f64,
+    /// Unit: `m`
+    #[ros2(unit = "m")]
     pub altitude: f64,
+    /// Unit: `m^2`
+    #[ros2(unit = "m^2")]
     pub position_covariance: [f64; 9],
     pub position_covariance_type: u8,
 }
@@ -9,12 +9,21 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Range {
     pub header: super::super::super::std_msgs::msg::header::Header,
     pub radiation_type: u8,
+    /// Unit: `enum`
+    #[ros2(unit = "enum")]
     pub field_of_view: f32,
+    /// Unit: `rad`
+    #[ros2(unit = "rad")]
     pub min_range: f32,
+    /// Unit: `m`
+    #[ros2(unit = "m")]
     pub max_range: f32,
+    /// Unit: `m`
+    #[ros2(unit = "m")]
     pub range: f32,
     pub variance: f32,
 }
@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SetCameraInfo_Request {
     pub camera_info: super::super::super::sensor_msgs::msg::camera_info::CameraInfo,
 }
@@ -21,6 +22,7 @@ pub struct SetCameraInfo_Request {
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SetCameraInfo_Response {
     pub success: bool,
     pub status_message: crate::msg::RosString<0>,
@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MultiDOFJointTrajectoryPoint {
     pub transforms: super::super::super::geometry_msgs::msg::transform::TransformSeq<0>,
     pub velocities: super::super::super::geometry_msgs::msg::twist::TwistSeq<0>,
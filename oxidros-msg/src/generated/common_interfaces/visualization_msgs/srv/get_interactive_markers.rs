@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GetInteractiveMarkers_Request {
     pub structure_needs_at_least_one_member: u8,
 }
@@ -21,6 +22,7 @@ pub struct GetInteractiveMarkers_Request {
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GetInteractiveMarkers_Response {
     pub sequence_number: u64,
     pub markers: super::super::super::visualization_msgs::msg::interactive_marker::InteractiveMarkerSeq<
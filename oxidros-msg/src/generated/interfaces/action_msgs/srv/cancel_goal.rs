@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct CancelGoal_Request {
     pub goal_info: super::super::super::action_msgs::msg::goal_info::GoalInfo,
 }
@@ -21,6 +22,7 @@ pub struct CancelGoal_Request {
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct CancelGoal_Response {
     pub return_code: i8,
     pub goals_canceling: super::super::super::action_msgs::msg::goal_info::GoalInfoSeq<
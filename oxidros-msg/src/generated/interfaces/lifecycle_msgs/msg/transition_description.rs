@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TransitionDescription {
     pub transition: super::super::super::lifecycle_msgs::msg::transition::Transition,
     pub start_state: super::super::super::lifecycle_msgs::msg::state::State,
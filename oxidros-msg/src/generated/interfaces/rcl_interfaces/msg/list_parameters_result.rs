@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ListParametersResult {
     pub names: crate::msg::RosStringSeq<0, 0>,
     pub prefixes: crate::msg::RosStringSeq<0, 0>,
@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ParameterEventDescriptors {
     pub new_parameters: super::super::super::rcl_interfaces::msg::parameter_descriptor::ParameterDescriptorSeq<
         0,
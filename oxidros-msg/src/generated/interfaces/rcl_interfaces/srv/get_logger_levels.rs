@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GetLoggerLevels_Request {
     pub names: crate::msg::RosStringSeq<0, 0>,
 }
@@ -21,6 +22,7 @@ pub struct GetLoggerLevels_Request {
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GetLoggerLevels_Response {
     pub levels: super::super::super::rcl_interfaces::msg::logger_level::LoggerLevelSeq<
         0,
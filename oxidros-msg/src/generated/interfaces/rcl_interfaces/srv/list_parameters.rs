@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ListParameters_Request {
     pub prefixes: crate::msg::RosStringSeq<0, 0>,
     pub depth: u64,
@@ -25,6 +26,7 @@ impl ListParameters_Request {
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ListParameters_Response {
     pub result: super::super::super::rcl_interfaces::msg::list_parameters_result::ListParametersResult,
 }
@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SetLoggerLevels_Request {
     pub levels: super::super::super::rcl_interfaces::msg::logger_level::LoggerLevelSeq<
         0,
@@ -23,6 +24,7 @@ pub struct SetLoggerLevels_Request {
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SetLoggerLevels_Response {
     pub results: super::super::super::rcl_interfaces::msg::set_logger_levels_result::SetLoggerLevelsResultSeq<
         0,
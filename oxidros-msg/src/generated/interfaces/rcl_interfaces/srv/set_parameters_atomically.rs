@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SetParametersAtomically_Request {
     pub parameters: super::super::super::rcl_interfaces::msg::parameter::ParameterSeq<0>,
 }
@@ -21,6 +22,7 @@ pub struct SetParametersAtomically_Request {
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SetParametersAtomically_Response {
     pub result: super::super::super::rcl_interfaces::msg::set_parameters_result::SetParametersResult,
 }
@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct IndividualTypeDescription {
     pub type_name: crate::msg::RosString<255>,
     pub fields: super::super::super::type_description_interfaces::msg::field::FieldSeq<
@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TypeDescription {
     pub type_description: super::super::super::type_description_interfaces::msg::individual_type_description::IndividualTypeDescription,
     pub referenced_type_descriptions: super::super::super::type_description_interfaces::msg::individual_type_description::IndividualTypeDescriptionSeq<
@@ -9,6 +9,7 @@
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GetTypeDescription_Request {
     pub type_name: crate::msg::RosString<0>,
     pub type_hash: crate::msg::RosString<0>,
@@ -24,6 +25,7 @@ pub struct GetTypeDescription_Request {
     derive(ros2_types::serde::Serialize, ros2_types::serde::Deserialize)
 )]
 #[cfg_attr(not(feature = "rcl"), serde(crate = "ros2_types::serde"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GetTypeDescription_Response {
     pub successful: bool,
     pub failure_reason: crate::msg::RosString<0>,
@@ -0,0 +1,383 @@
+//! Math helpers for `geometry_msgs` poses, quaternions and covariances.
+//!
+//! Covariances of [`PoseWithCovariance`] and [`TwistWithCovariance`] are 6x6
+//! row-major matrices over `(x, y, z, rotation about x, rotation about y,
+//! rotation about z)`; the [`axis`] constants name the rows and columns.
+//!
+//! # Example
+//!
+//! ```
+//! use oxidros_msg::common_interfaces::geometry_msgs::msg::PoseWithCovariance;
+//! use oxidros_msg::geometry::{axis, covariance_from_sigmas};
+//!
+//! let mut msg = PoseWithCovariance::new().unwrap();
+//! msg.covariance = covariance_from_sigmas([0.1, 0.1, 0.5, 0.01, 0.01, 0.05]);
+//! msg.set_covariance_at(axis::X, axis::Y, 0.002);
+//! assert_eq!(msg.covariance_at(axis::Y, axis::X), 0.002);
+//! ```
+
+use crate::common_interfaces::geometry_msgs::msg::{
+    Point, Pose, PoseWithCovariance, Quaternion, TwistWithCovariance, Vector3,
+};
+
+/// Rows and columns of a 6x6 covariance.
+pub mod axis {
+    /// Translation along x.
+    pub const X: usize = 0;
+    /// Translation along y.
+    pub const Y: usize = 1;
+    /// Translation along z.
+    pub const Z: usize = 2;
+    /// Rotation about x.
+    pub const ROLL: usize = 3;
+    /// Rotation about y.
+    pub const PITCH: usize = 4;
+    /// Rotation about z.
+    pub const YAW: usize = 5;
+}
+
+/// Index of `(row, col)` in a 6x6 row-major covariance.
+///
+/// # Panics
+///
+/// Panics if `row` or `col` is not below 6.
+pub const fn covariance_index(row: usize, col: usize) -> usize {
+    assert!(row < 6 && col < 6, "covariance index out of range");
+    row * 6 + col
+}
+
+/// Diagonal covariance from the standard deviation of each [`axis`].
+pub fn covariance_from_sigmas(sigmas: [f64; 6]) -> [f64; 36] {
+    let mut covariance = [0.0; 36];
+    for (i, sigma) in sigmas.into_iter().enumerate() {
+        covariance[covariance_index(i, i)] = sigma * sigma;
+    }
+    covariance
+}
+
+macro_rules! impl_covariance_accessors {
+    ($ty:ty) => {
+        impl $ty {
+            /// Covariance between the `row` and `col` [`axis`].
+            ///
+            /// # Panics
+            ///
+            /// Panics if `row` or `col` is not below 6.
+            pub fn covariance_at(&self, row: usize, col: usize) -> f64 {
+                self.covariance[covariance_index(row, col)]
+            }
+
+            /// Set the covariance between the `row` and `col` [`axis`],
+            /// keeping the matrix symmetric.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `row` or `col` is not below 6.
+            pub fn set_covariance_at(&mut self, row: usize, col: usize, value: f64) {
+                self.covariance[covariance_index(row, col)] = value;
+                self.covariance[covariance_index(col, row)] = value;
+            }
+
+            /// Standard deviation along `axis`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `axis` is not below 6.
+            pub fn sigma(&self, axis: usize) -> f64 {
+                self.covariance_at(axis, axis).sqrt()
+            }
+        }
+    };
+}
+
+impl_covariance_accessors!(PoseWithCovariance);
+impl_covariance_accessors!(TwistWithCovariance);
+
+impl Quaternion {
+    /// Quaternion from its components.
+    pub fn from_xyzw(x: f64, y: f64, z: f64, w: f64) -> Self {
+        Quaternion { x, y, z, w }
+    }
+
+    /// Rotation of `angle` radians about `axis`, which must not be zero.
+    pub fn from_axis_angle(axis: [f64; 3], angle: f64) -> Self {
+        let norm = (axis[0] * axis[0] + axis[1] * axis[1] + axis[2] * axis[2]).sqrt();
+        let (sin, cos) = (angle / 2.0).sin_cos();
+        let s = sin / norm;
+        Quaternion::from_xyzw(axis[0] * s, axis[1] * s, axis[2] * s, cos)
+    }
+
+    /// Rotation from roll, pitch and yaw, applied in this order about the fixed axes.
+    pub fn from_rpy(roll: f64, pitch: f64, yaw: f64) -> Self {
+        let (sr, cr) = (roll / 2.0).sin_cos();
+        let (sp, cp) = (pitch / 2.0).sin_cos();
+        let (sy, cy) = (yaw / 2.0).sin_cos();
+        Quaternion::from_xyzw(
+            sr * cp * cy - cr * sp * sy,
+            cr * sp * cy + sr * cp * sy,
+            cr * cp * sy - sr * sp * cy,
+            cr * cp * cy + sr * sp * sy,
+        )
+    }
+
+    /// Roll, pitch and yaw of the rotation, see [`Quaternion::from_rpy`].
+    pub fn to_rpy(&self) -> (f64, f64, f64) {
+        let Quaternion { x, y, z, w } = *self;
+        let roll = (2.0 * (w * x + y * z)).atan2(1.0 - 2.0 * (x * x + y * y));
+        let pitch = (2.0 * (w * y - z * x)).clamp(-1.0, 1.0).asin();
+        let yaw = (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z));
+        (roll, pitch, yaw)
+    }
+
+    /// Norm of the quaternion, 1 for a rotation.
+    pub fn norm(&self) -> f64 {
+        (self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w).sqrt()
+    }
+
+    /// The quaternion scaled to a norm of 1.
+    pub fn normalized(&self) -> Self {
+        let n = self.norm();
+        Quaternion::from_xyzw(self.x / n, self.y / n, self.z / n, self.w / n)
+    }
+
+    /// The inverse rotation of a unit quaternion.
+    pub fn conjugate(&self) -> Self {
+        Quaternion::from_xyzw(-self.x, -self.y, -self.z, self.w)
+    }
+
+    /// Hamilton product, the rotation `other` followed by `self`.
+    pub fn multiply(&self, other: &Quaternion) -> Self {
+        let (a, b) = (self, other);
+        Quaternion::from_xyzw(
+            a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+            a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+            a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+            a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+        )
+    }
+
+    /// Rotate `v` by this unit quaternion.
+    pub fn rotate(&self, v: [f64; 3]) -> [f64; 3] {
+        let m = self.to_rotation_matrix();
+        [
+            m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+            m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+            m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+        ]
+    }
+
+    /// 3x3 rotation matrix of this unit quaternion, indexed `[row][col]`.
+    pub fn to_rotation_matrix(&self) -> [[f64; 3]; 3] {
+        let Quaternion { x, y, z, w } = *self;
+        [
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - z * w),
+                2.0 * (x * z + y * w),
+            ],
+            [
+                2.0 * (x * y + z * w),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - x * w),
+            ],
+            [
+                2.0 * (x * z - y * w),
+                2.0 * (y * z + x * w),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ]
+    }
+
+    /// Unit quaternion of a 3x3 rotation matrix, indexed `[row][col]`.
+    pub fn from_rotation_matrix(m: &[[f64; 3]; 3]) -> Self {
+        let trace = m[0][0] + m[1][1] + m[2][2];
+        let q = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Quaternion::from_xyzw(
+                (m[2][1] - m[1][2]) / s,
+                (m[0][2] - m[2][0]) / s,
+                (m[1][0] - m[0][1]) / s,
+                s / 4.0,
+            )
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+            Quaternion::from_xyzw(
+                s / 4.0,
+                (m[0][1] + m[1][0]) / s,
+                (m[0][2] + m[2][0]) / s,
+                (m[2][1] - m[1][2]) / s,
+            )
+        } else if m[1][1] > m[2][2] {
+            let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+            Quaternion::from_xyzw(
+                (m[0][1] + m[1][0]) / s,
+                s / 4.0,
+                (m[1][2] + m[2][1]) / s,
+                (m[0][2] - m[2][0]) / s,
+            )
+        } else {
+            let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+            Quaternion::from_xyzw(
+                (m[0][2] + m[2][0]) / s,
+                (m[1][2] + m[2][1]) / s,
+                s / 4.0,
+                (m[1][0] - m[0][1]) / s,
+            )
+        };
+        q.normalized()
+    }
+}
+
+impl Point {
+    /// Point from its coordinates.
+    pub fn from_xyz(x: f64, y: f64, z: f64) -> Self {
+        Point { x, y, z }
+    }
+}
+
+impl Vector3 {
+    /// Vector from its coordinates.
+    pub fn from_xyz(x: f64, y: f64, z: f64) -> Self {
+        Vector3 { x, y, z }
+    }
+}
+
+impl Pose {
+    /// Pose from a position and a unit quaternion.
+    pub fn from_parts(position: [f64; 3], orientation: Quaternion) -> Self {
+        Pose {
+            position: Point::from_xyz(position[0], position[1], position[2]),
+            orientation,
+        }
+    }
+
+    /// The pose `other`, expressed in the frame of `self`, in the parent
+    /// frame of `self`.
+    pub fn compose(&self, other: &Pose) -> Pose {
+        let t = self
+            .orientation
+            .rotate([other.position.x, other.position.y, other.position.z]);
+        Pose::from_parts(
+            [
+                self.position.x + t[0],
+                self.position.y + t[1],
+                self.position.z + t[2],
+            ],
+            self.orientation.multiply(&other.orientation).normalized(),
+        )
+    }
+
+    /// The parent frame expressed in the frame of this pose.
+    pub fn inverse(&self) -> Pose {
+        let q = self.orientation.conjugate();
+        let t = q.rotate([self.position.x, self.position.y, self.position.z]);
+        Pose::from_parts([-t[0], -t[1], -t[2]], q)
+    }
+
+    /// 4x4 homogeneous transformation matrix, indexed `[row][col]`.
+    pub fn to_matrix(&self) -> [[f64; 4]; 4] {
+        let r = self.orientation.to_rotation_matrix();
+        let p = &self.position;
+        [
+            [r[0][0], r[0][1], r[0][2], p.x],
+            [r[1][0], r[1][1], r[1][2], p.y],
+            [r[2][0], r[2][1], r[2][2], p.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ]
+    }
+
+    /// Pose of a 4x4 homogeneous transformation matrix, indexed `[row][col]`.
+    pub fn from_matrix(m: &[[f64; 4]; 4]) -> Pose {
+        let r = [
+            [m[0][0], m[0][1], m[0][2]],
+            [m[1][0], m[1][1], m[1][2]],
+            [m[2][0], m[2][1], m[2][2]],
+        ];
+        Pose::from_parts(
+            [m[0][3], m[1][3], m[2][3]],
+            Quaternion::from_rotation_matrix(&r),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    const EPS: f64 = 1e-9;
+
+    fn assert_pose_eq(a: &Pose, b: &Pose) {
+        let (pa, pb) = (&a.position, &b.position);
+        assert!(
+            (pa.x - pb.x).abs() < EPS && (pa.y - pb.y).abs() < EPS && (pa.z - pb.z).abs() < EPS
+        );
+        // q and -q are the same rotation
+        let (qa, qb) = (&a.orientation, &b.orientation);
+        let dot = qa.x * qb.x + qa.y * qb.y + qa.z * qb.z + qa.w * qb.w;
+        assert!((dot.abs() - 1.0).abs() < EPS, "{qa:?} != {qb:?}");
+    }
+
+    #[test]
+    fn test_covariance() {
+        assert_eq!(covariance_index(axis::Y, axis::YAW), 11);
+
+        let mut msg = PoseWithCovariance::new().unwrap();
+        msg.covariance = covariance_from_sigmas([1.0, 2.0, 3.0, 0.1, 0.2, 0.3]);
+        assert_eq!(msg.covariance_at(axis::Y, axis::Y), 4.0);
+        assert_eq!(msg.covariance_at(axis::X, axis::Y), 0.0);
+        assert!((msg.sigma(axis::YAW) - 0.3).abs() < EPS);
+
+        msg.set_covariance_at(axis::X, axis::YAW, 0.5);
+        assert_eq!(msg.covariance[5], 0.5);
+        assert_eq!(msg.covariance[30], 0.5);
+
+        let mut twist = TwistWithCovariance::new().unwrap();
+        twist.set_covariance_at(axis::Z, axis::Z, 9.0);
+        assert_eq!(twist.sigma(axis::Z), 3.0);
+    }
+
+    #[test]
+    fn test_quaternion() {
+        let q = Quaternion::from_rpy(0.1, -0.2, 0.3);
+        let (r, p, y) = q.to_rpy();
+        assert!((r - 0.1).abs() < EPS && (p + 0.2).abs() < EPS && (y - 0.3).abs() < EPS);
+        assert!((q.norm() - 1.0).abs() < EPS);
+
+        let yaw = Quaternion::from_axis_angle([0.0, 0.0, 2.0], FRAC_PI_2);
+        let v = yaw.rotate([1.0, 0.0, 0.0]);
+        assert!(v[0].abs() < EPS && (v[1] - 1.0).abs() < EPS && v[2].abs() < EPS);
+
+        let m = q.to_rotation_matrix();
+        let back = Quaternion::from_rotation_matrix(&m);
+        assert_pose_eq(
+            &Pose::from_parts([0.0; 3], back),
+            &Pose::from_parts([0.0; 3], q),
+        );
+    }
+
+    #[test]
+    fn test_pose() {
+        let a = Pose::from_parts(
+            [1.0, 2.0, 3.0],
+            Quaternion::from_axis_angle([0.0, 0.0, 1.0], FRAC_PI_2),
+        );
+        let b = Pose::from_parts([1.0, 0.0, 0.0], Quaternion::from_rpy(0.0, 0.0, 0.0));
+
+        // b is one meter ahead of a, which faces +y
+        let c = a.compose(&b);
+        assert_pose_eq(
+            &c,
+            &Pose::from_parts([1.0, 3.0, 3.0], a.orientation.clone()),
+        );
+
+        let identity = Pose::from_parts([0.0; 3], Quaternion::from_xyzw(0.0, 0.0, 0.0, 1.0));
+        assert_pose_eq(&a.compose(&a.inverse()), &identity);
+        assert_pose_eq(&a.inverse().compose(&c), &b);
+
+        let m = a.to_matrix();
+        assert_eq!(m[3], [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!([m[0][3], m[1][3], m[2][3]], [1.0, 2.0, 3.0]);
+        assert_pose_eq(&Pose::from_matrix(&m), &a);
+    }
+}
@@ -0,0 +1,432 @@
+//! Conversions between `sensor_msgs` images and the [`image`] crate.
+//!
+//! [`to_dynamic_image`] and [`from_dynamic_image`] convert raw
+//! [`Image`] messages, following the [`encodings`] of `sensor_msgs`.
+//! [`compress`] and [`decompress`] handle the JPEG and PNG data of
+//! [`CompressedImage`] messages, as published by `image_transport`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros_msg::images::{self, Compression, encodings};
+//!
+//! let frame = image::open("frame.png")?;
+//! let mut msg = images::from_dynamic_image(&frame, encodings::BGR8)?;
+//! msg.header.frame_id.assign("camera");
+//!
+//! let compressed = images::compress(&msg, Compression::Jpeg { quality: 90 })?;
+//! ```
+
+use crate::TryClone;
+use crate::common_interfaces::sensor_msgs::msg::{CompressedImage, Image};
+use crate::msg::{RosString, U8Seq};
+use image::{
+    DynamicImage, ImageBuffer, Rgb, Rgba,
+    codecs::{jpeg::JpegEncoder, png::PngEncoder},
+};
+use oxidros_core::{Error, Result, error::MessageError};
+
+/// Encodings of [`Image::encoding`] supported by this module.
+pub mod encodings {
+    /// 8-bit red, green, blue.
+    pub const RGB8: &str = "rgb8";
+    /// 8-bit red, green, blue, alpha.
+    pub const RGBA8: &str = "rgba8";
+    /// 8-bit blue, green, red.
+    pub const BGR8: &str = "bgr8";
+    /// 8-bit blue, green, red, alpha.
+    pub const BGRA8: &str = "bgra8";
+    /// 16-bit red, green, blue.
+    pub const RGB16: &str = "rgb16";
+    /// 16-bit red, green, blue, alpha.
+    pub const RGBA16: &str = "rgba16";
+    /// 8-bit grayscale.
+    pub const MONO8: &str = "mono8";
+    /// 16-bit grayscale.
+    pub const MONO16: &str = "mono16";
+    /// YUV 4:2:2 in UYVY order, 2 bytes per pixel.
+    pub const YUV422: &str = "yuv422";
+    /// YUV 4:2:2 in YUYV order, 2 bytes per pixel.
+    pub const YUV422_YUY2: &str = "yuv422_yuy2";
+}
+
+/// Compression of a [`CompressedImage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Lossy JPEG, with a quality from 1 to 100.
+    ///
+    /// Alpha channels are dropped and 16-bit images are reduced to 8 bits.
+    Jpeg {
+        /// Quality of the encoder, from 1 to 100.
+        quality: u8,
+    },
+    /// Lossless PNG.
+    Png,
+}
+
+impl Compression {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Compression::Jpeg { .. } => "jpeg",
+            Compression::Png => "png",
+        }
+    }
+}
+
+/// Convert an image message into a [`DynamicImage`].
+///
+/// BGR images are converted to RGB and YUV 4:2:2 images to `rgb8`.
+///
+/// # Errors
+///
+/// - `Error::NotImplemented` if the encoding is not one of [`encodings`], or
+/// - `Error::Other` if the data is too short for the size of the image.
+pub fn to_dynamic_image(msg: &Image) -> Result<DynamicImage> {
+    let encoding = msg.encoding.get_string();
+    let image = match encoding.as_str() {
+        encodings::RGB8 => {
+            DynamicImage::ImageRgb8(buffer(msg, 3, |row, out| out.extend_from_slice(row))?)
+        }
+        encodings::BGR8 => DynamicImage::ImageRgb8(buffer(msg, 3, |row, out| {
+            row.chunks_exact(3)
+                .for_each(|p| out.extend_from_slice(&[p[2], p[1], p[0]]))
+        })?),
+        encodings::RGBA8 => {
+            DynamicImage::ImageRgba8(buffer(msg, 4, |row, out| out.extend_from_slice(row))?)
+        }
+        encodings::BGRA8 => DynamicImage::ImageRgba8(buffer(msg, 4, |row, out| {
+            row.chunks_exact(4)
+                .for_each(|p| out.extend_from_slice(&[p[2], p[1], p[0], p[3]]))
+        })?),
+        encodings::MONO8 => {
+            DynamicImage::ImageLuma8(buffer(msg, 1, |row, out| out.extend_from_slice(row))?)
+        }
+        encodings::MONO16 => DynamicImage::ImageLuma16(buffer16(msg, 1)?),
+        encodings::RGB16 => DynamicImage::ImageRgb16(buffer16(msg, 3)?),
+        encodings::RGBA16 => DynamicImage::ImageRgba16(buffer16(msg, 4)?),
+        encodings::YUV422 => DynamicImage::ImageRgb8(buffer(msg, 2, |row, out| {
+            row.chunks_exact(4).for_each(|p| {
+                let [u, y0, v, y1] = [p[0], p[1], p[2], p[3]];
+                out.extend_from_slice(&yuv_to_rgb(y0, u, v));
+                out.extend_from_slice(&yuv_to_rgb(y1, u, v));
+            })
+        })?),
+        encodings::YUV422_YUY2 => DynamicImage::ImageRgb8(buffer(msg, 2, |row, out| {
+            row.chunks_exact(4).for_each(|p| {
+                let [y0, u, y1, v] = [p[0], p[1], p[2], p[3]];
+                out.extend_from_slice(&yuv_to_rgb(y0, u, v));
+                out.extend_from_slice(&yuv_to_rgb(y1, u, v));
+            })
+        })?),
+        _ => return Err(unsupported(&encoding)),
+    };
+    Ok(image)
+}
+
+/// Convert a [`DynamicImage`] into an image message with the given encoding.
+///
+/// The header of the message is left to the caller.
+///
+/// # Errors
+///
+/// - `Error::NotImplemented` if the encoding is not one of [`encodings`], or
+/// - `Error::Other` if a YUV 4:2:2 image has an odd width, or
+/// - `Error::Message` if the message cannot be allocated.
+pub fn from_dynamic_image(image: &DynamicImage, encoding: &str) -> Result<Image> {
+    let (width, height) = (image.width(), image.height());
+
+    let (data, bytes_per_pixel) = match encoding {
+        encodings::RGB8 => (image.to_rgb8().into_raw(), 3),
+        encodings::BGR8 => (
+            image
+                .to_rgb8()
+                .pixels()
+                .flat_map(|Rgb([r, g, b])| [*b, *g, *r])
+                .collect(),
+            3,
+        ),
+        encodings::RGBA8 => (image.to_rgba8().into_raw(), 4),
+        encodings::BGRA8 => (
+            image
+                .to_rgba8()
+                .pixels()
+                .flat_map(|Rgba([r, g, b, a])| [*b, *g, *r, *a])
+                .collect(),
+            4,
+        ),
+        encodings::MONO8 => (image.to_luma8().into_raw(), 1),
+        encodings::MONO16 => (to_ne_bytes(&image.to_luma16().into_raw()), 2),
+        encodings::RGB16 => (to_ne_bytes(&image.to_rgb16().into_raw()), 6),
+        encodings::RGBA16 => (to_ne_bytes(&image.to_rgba16().into_raw()), 8),
+        encodings::YUV422 | encodings::YUV422_YUY2 => {
+            if width % 2 != 0 {
+                return Err(Error::Other(format!(
+                    "{encoding} images must have an even width, got {width}"
+                )));
+            }
+            let yuy2 = encoding == encodings::YUV422_YUY2;
+            let data = image
+                .to_rgb8()
+                .into_raw()
+                .chunks_exact(6)
+                .flat_map(|p| {
+                    let (y0, u0, v0) = rgb_to_yuv(p[0], p[1], p[2]);
+                    let (y1, u1, v1) = rgb_to_yuv(p[3], p[4], p[5]);
+                    let u = ((u16::from(u0) + u16::from(u1)) / 2) as u8;
+                    let v = ((u16::from(v0) + u16::from(v1)) / 2) as u8;
+                    if yuy2 { [y0, u, y1, v] } else { [u, y0, v, y1] }
+                })
+                .collect();
+            (data, 2)
+        }
+        _ => return Err(unsupported(encoding)),
+    };
+
+    let mut msg = Image::new().ok_or(MessageError::InitFailed("Image"))?;
+    msg.width = width;
+    msg.height = height;
+    msg.step = width * bytes_per_pixel;
+    msg.is_bigendian = u8::from(cfg!(target_endian = "big"));
+    msg.encoding = RosString::try_new(encoding)?;
+    msg.data =
+        U8Seq::try_from(data.as_slice()).map_err(|_| MessageError::InitFailed("image data"))?;
+    Ok(msg)
+}
+
+/// Compress an image message, keeping its header.
+///
+/// The format of the compressed message follows `image_transport`,
+/// e.g. `"bgr8; jpeg compressed bgr8"`.
+///
+/// # Errors
+///
+/// - the errors of [`to_dynamic_image`], or
+/// - `Error::Other` if the image cannot be encoded.
+pub fn compress(msg: &Image, compression: Compression) -> Result<CompressedImage> {
+    let image = to_dynamic_image(msg)?;
+    let gray = matches!(
+        image,
+        DynamicImage::ImageLuma8(_)
+            | DynamicImage::ImageLuma16(_)
+            | DynamicImage::ImageLumaA8(_)
+            | DynamicImage::ImageLumaA16(_)
+    );
+    let color = image.color();
+    let wide =
+        matches!(compression, Compression::Png) && color.bytes_per_pixel() > color.channel_count();
+
+    let mut data = Vec::new();
+    let result = match compression {
+        Compression::Jpeg { quality } => {
+            let encoder = JpegEncoder::new_with_quality(&mut data, quality.clamp(1, 100));
+            // JPEG has neither alpha nor 16-bit channels
+            if gray {
+                DynamicImage::ImageLuma8(image.to_luma8()).write_with_encoder(encoder)
+            } else {
+                DynamicImage::ImageRgb8(image.to_rgb8()).write_with_encoder(encoder)
+            }
+        }
+        Compression::Png => image.write_with_encoder(PngEncoder::new(&mut data)),
+    };
+    result.map_err(|e| Error::Other(format!("failed to encode the image: {e}")))?;
+
+    let target = match (gray, wide) {
+        (true, false) => encodings::MONO8,
+        (true, true) => encodings::MONO16,
+        (false, false) => encodings::BGR8,
+        (false, true) => "bgr16",
+    };
+    let format = format!(
+        "{}; {} compressed {target}",
+        msg.encoding.get_string(),
+        compression.as_str()
+    );
+
+    let mut compressed =
+        CompressedImage::new().ok_or(MessageError::InitFailed("CompressedImage"))?;
+    compressed.header = msg
+        .header
+        .try_clone()
+        .ok_or(MessageError::InitFailed("Header"))?;
+    compressed.format = RosString::try_new(&format)?;
+    compressed.data =
+        U8Seq::try_from(data.as_slice()).map_err(|_| MessageError::InitFailed("image data"))?;
+    Ok(compressed)
+}
+
+/// Decode the JPEG or PNG data of a compressed image message.
+///
+/// The format is detected from the data, so that messages with a plain
+/// `"jpeg"` or `"png"` format are decoded as well.
+///
+/// # Errors
+///
+/// - `Error::Other` if the data cannot be decoded.
+pub fn decompress(msg: &CompressedImage) -> Result<DynamicImage> {
+    image::load_from_memory(msg.data.as_slice())
+        .map_err(|e| Error::Other(format!("failed to decode the image: {e}")))
+}
+
+/// Copy the rows of `msg` without their padding, converting them with `f`.
+fn buffer<P: image::Pixel<Subpixel = u8>>(
+    msg: &Image,
+    bytes_per_pixel: usize,
+    mut f: impl FnMut(&[u8], &mut Vec<u8>),
+) -> Result<ImageBuffer<P, Vec<u8>>> {
+    let mut out =
+        Vec::with_capacity(msg.width as usize * msg.height as usize * P::CHANNEL_COUNT as usize);
+    for row in rows(msg, bytes_per_pixel)? {
+        f(row, &mut out);
+    }
+    ImageBuffer::from_raw(msg.width, msg.height, out).ok_or_else(|| too_short(msg))
+}
+
+/// Copy the rows of a 16-bit image in the byte order of this machine.
+fn buffer16<P: image::Pixel<Subpixel = u16>>(
+    msg: &Image,
+    channels: usize,
+) -> Result<ImageBuffer<P, Vec<u16>>> {
+    let from_bytes = if msg.is_bigendian != 0 {
+        u16::from_be_bytes
+    } else {
+        u16::from_le_bytes
+    };
+    let mut out = Vec::with_capacity(msg.width as usize * msg.height as usize * channels);
+    for row in rows(msg, channels * 2)? {
+        out.extend(row.chunks_exact(2).map(|b| from_bytes([b[0], b[1]])));
+    }
+    ImageBuffer::from_raw(msg.width, msg.height, out).ok_or_else(|| too_short(msg))
+}
+
+fn rows(msg: &Image, bytes_per_pixel: usize) -> Result<impl Iterator<Item = &[u8]>> {
+    let data = msg.data.as_slice();
+    let row_len = msg.width as usize * bytes_per_pixel;
+    let step = (msg.step as usize).max(row_len);
+    let height = msg.height as usize;
+    if height > 0 && data.len() < step * (height - 1) + row_len {
+        return Err(too_short(msg));
+    }
+    Ok((0..height).map(move |i| &data[i * step..i * step + row_len]))
+}
+
+fn to_ne_bytes(data: &[u16]) -> Vec<u8> {
+    data.iter().flat_map(|v| v.to_ne_bytes()).collect()
+}
+
+/// BT.601 conversion, as done by `cv_bridge`.
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> [u8; 3] {
+    let (y, u, v) = (f32::from(y), f32::from(u) - 128.0, f32::from(v) - 128.0);
+    [
+        (y + 1.402 * v).round().clamp(0.0, 255.0) as u8,
+        (y - 0.344_136 * u - 0.714_136 * v)
+            .round()
+            .clamp(0.0, 255.0) as u8,
+        (y + 1.772 * u).round().clamp(0.0, 255.0) as u8,
+    ]
+}
+
+fn rgb_to_yuv(r: u8, g: u8, b: u8) -> (u8, u8, u8) {
+    let (r, g, b) = (f32::from(r), f32::from(g), f32::from(b));
+    let y = 0.299 * r + 0.587 * g + 0.114 * b;
+    let u = -0.168_736 * r - 0.331_264 * g + 0.5 * b + 128.0;
+    let v = 0.5 * r - 0.418_688 * g - 0.081_312 * b + 128.0;
+    let clamp = |x: f32| x.round().clamp(0.0, 255.0) as u8;
+    (clamp(y), clamp(u), clamp(v))
+}
+
+fn unsupported(encoding: &str) -> Error {
+    Error::NotImplemented {
+        feature: format!("image encoding '{encoding}'"),
+        reason: "not supported by the image conversions".into(),
+    }
+}
+
+fn too_short(msg: &Image) -> Error {
+    Error::Other(format!(
+        "image data of {} bytes is too short for {}x{} {}",
+        msg.data.len(),
+        msg.width,
+        msg.height,
+        msg.encoding.get_string()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma, RgbImage};
+
+    fn sample() -> RgbImage {
+        RgbImage::from_fn(4, 2, |x, y| Rgb([(x * 60) as u8, (y * 120) as u8, 200]))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let rgb = DynamicImage::ImageRgb8(sample());
+        for encoding in [encodings::RGB8, encodings::BGR8, encodings::RGB16] {
+            let msg = from_dynamic_image(&rgb, encoding).unwrap();
+            assert_eq!(msg.encoding.get_string(), encoding);
+            assert_eq!(to_dynamic_image(&msg).unwrap().to_rgb8(), sample());
+        }
+
+        let msg = from_dynamic_image(&rgb, encodings::BGRA8).unwrap();
+        assert_eq!(msg.step, 16);
+        assert_eq!(&msg.data.as_slice()[..4], &[200, 0, 0, 255]);
+        assert_eq!(to_dynamic_image(&msg).unwrap().to_rgb8(), sample());
+
+        let gray = DynamicImage::ImageLuma16(ImageBuffer::<Luma<u16>, _>::from_fn(3, 3, |x, y| {
+            Luma([(x * 1000 + y) as u16])
+        }));
+        let msg = from_dynamic_image(&gray, encodings::MONO16).unwrap();
+        assert_eq!(to_dynamic_image(&msg).unwrap(), gray);
+
+        assert!(from_dynamic_image(&rgb, "bayer_rggb8").is_err());
+    }
+
+    #[test]
+    fn test_row_padding() {
+        let mut msg = Image::new().unwrap();
+        msg.width = 2;
+        msg.height = 2;
+        msg.step = 4;
+        msg.encoding = RosString::try_new(encodings::MONO8).unwrap();
+        msg.data = U8Seq::try_from(&[1, 2, 0, 0, 3, 4][..]).unwrap();
+
+        let gray = to_dynamic_image(&msg).unwrap().to_luma8();
+        assert_eq!(gray, GrayImage::from_raw(2, 2, vec![1, 2, 3, 4]).unwrap());
+
+        msg.data = U8Seq::try_from(&[1, 2, 0, 0, 3][..]).unwrap();
+        assert!(to_dynamic_image(&msg).is_err());
+    }
+
+    #[test]
+    fn test_yuv422() {
+        let gray = RgbImage::from_pixel(4, 1, Rgb([128, 128, 128]));
+        let rgb = DynamicImage::ImageRgb8(gray.clone());
+        for encoding in [encodings::YUV422, encodings::YUV422_YUY2] {
+            let msg = from_dynamic_image(&rgb, encoding).unwrap();
+            assert_eq!(msg.step, 8);
+            assert_eq!(to_dynamic_image(&msg).unwrap().to_rgb8(), gray);
+        }
+
+        let odd = DynamicImage::ImageRgb8(RgbImage::new(3, 1));
+        assert!(from_dynamic_image(&odd, encodings::YUV422).is_err());
+    }
+
+    #[test]
+    fn test_compress() {
+        let mut msg =
+            from_dynamic_image(&DynamicImage::ImageRgb8(sample()), encodings::BGR8).unwrap();
+        msg.header.frame_id = RosString::try_new("camera").unwrap();
+
+        let png = compress(&msg, Compression::Png).unwrap();
+        assert_eq!(png.format.get_string(), "bgr8; png compressed bgr8");
+        assert_eq!(png.header.frame_id.get_string(), "camera");
+        assert_eq!(decompress(&png).unwrap().to_rgb8(), sample());
+
+        let jpeg = compress(&msg, Compression::Jpeg { quality: 90 }).unwrap();
+        assert_eq!(jpeg.format.get_string(), "bgr8; jpeg compressed bgr8");
+        let decoded = decompress(&jpeg).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (4, 2));
+    }
+}
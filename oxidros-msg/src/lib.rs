@@ -24,7 +24,14 @@ pub mod rcl {
     pub use crate::runtime_c::*;
 }
 
+pub mod compat;
+pub mod geometry;
+#[cfg(feature = "image")]
+pub mod images;
 pub mod primitives;
+#[cfg(feature = "arbitrary")]
+pub mod roundtrip;
+pub mod std_primitive;
 pub mod strings;
 
 // Include runtime C bindings first (provides rosidl_runtime_c types)
@@ -54,7 +61,7 @@ pub mod builtin_interfaces {
 
 // Re-export ros2-types traits and macros for generated code
 pub use ros2_types::{
-    Ros2Msg, SequenceRaw, ServiceMsg, TryClone, TypeSupport, ros2_action, ros2_service,
+    Ros2Msg, SequenceRaw, ServiceMsg, Stamped, TryClone, TypeSupport, ros2_action, ros2_service,
 };
 
 // Include generated message modules from OUT_DIR/generated/
@@ -94,6 +101,7 @@ pub use oxidros_core::{UnsafeDuration, UnsafeTime};
 use crate::interfaces::rcl_interfaces::msg::ParameterValue;
 use crate::msg::{BoolSeq, ByteSeq, F64Seq, I64Seq, RosString, RosStringSeq};
 use oxidros_core::Value;
+use oxidros_core::parameter::ParameterType;
 
 impl From<&oxidros_core::parameter::IntegerRange>
     for interfaces::rcl_interfaces::msg::IntegerRange
@@ -121,32 +129,32 @@ impl From<&oxidros_core::parameter::FloatingPointRange>
 
 impl From<&ParameterValue> for Value {
     fn from(var: &ParameterValue) -> Self {
-        match var.r#type {
-            1 => Value::Bool(var.bool_value),
-            2 => Value::I64(var.integer_value),
-            3 => Value::F64(var.double_value),
-            4 => Value::String(var.string_value.to_string()),
-            5 => {
+        match ParameterType::try_from(var.r#type) {
+            Ok(ParameterType::Bool) => Value::Bool(var.bool_value),
+            Ok(ParameterType::Integer) => Value::I64(var.integer_value),
+            Ok(ParameterType::Double) => Value::F64(var.double_value),
+            Ok(ParameterType::String) => Value::String(var.string_value.to_string()),
+            Ok(ParameterType::ByteArray) => {
                 let mut v = Vec::new();
                 var.byte_array_value.iter().for_each(|x| v.push(*x));
                 Value::VecU8(v)
             }
-            6 => {
+            Ok(ParameterType::BoolArray) => {
                 let mut v = Vec::new();
                 var.bool_array_value.iter().for_each(|x| v.push(*x));
                 Value::VecBool(v)
             }
-            7 => {
+            Ok(ParameterType::IntegerArray) => {
                 let mut v = Vec::new();
                 var.integer_array_value.iter().for_each(|x| v.push(*x));
                 Value::VecI64(v)
             }
-            8 => {
+            Ok(ParameterType::DoubleArray) => {
                 let mut v = Vec::new();
                 var.double_array_value.iter().for_each(|x| v.push(*x));
                 Value::VecF64(v)
             }
-            9 => {
+            Ok(ParameterType::StringArray) => {
                 let mut v = Vec::new();
                 var.string_array_value
                     .iter()
@@ -161,29 +169,25 @@ impl From<&ParameterValue> for Value {
 impl From<&Value> for ParameterValue {
     fn from(var: &Value) -> Self {
         let mut result = ParameterValue::new().unwrap();
+        result.r#type = var.parameter_type().into();
         match var {
-            Value::NotSet => result.r#type = 0,
+            Value::NotSet => {}
             Value::Bool(val) => {
-                result.r#type = 1;
                 result.bool_value = *val;
             }
             Value::I64(val) => {
-                result.r#type = 2;
                 result.integer_value = *val;
             }
             Value::F64(val) => {
-                result.r#type = 3;
                 result.double_value = *val;
             }
             Value::String(val) => {
-                result.r#type = 4;
-                result.string_value = RosString::new(val).unwrap_or_else(|| {
-                    log::error!("{}:{}: failed allocation", file!(), line!());
+                result.string_value = RosString::try_new(val).unwrap_or_else(|e| {
+                    log::error!("{}:{}: {e}", file!(), line!());
                     RosString::null()
                 });
             }
             Value::VecU8(val) => {
-                result.r#type = 5;
                 result.byte_array_value = ByteSeq::new(val.len()).unwrap_or_else(|| {
                     log::error!("{}:{}: failed allocation", file!(), line!());
                     ByteSeq::null()
@@ -195,7 +199,6 @@ impl From<&Value> for ParameterValue {
                     .for_each(|(dst, src)| *dst = *src);
             }
             Value::VecBool(val) => {
-                result.r#type = 6;
                 result.bool_array_value = BoolSeq::new(val.len()).unwrap_or_else(|| {
                     log::error!("{}:{}: failed allocation", file!(), line!());
                     BoolSeq::null()
@@ -207,7 +210,6 @@ impl From<&Value> for ParameterValue {
                     .for_each(|(dst, src)| *dst = *src);
             }
             Value::VecI64(val) => {
-                result.r#type = 7;
                 result.integer_array_value = I64Seq::new(val.len()).unwrap_or_else(|| {
                     log::error!("{}:{}: failed allocation", file!(), line!());
                     I64Seq::null()
@@ -219,7 +221,6 @@ impl From<&Value> for ParameterValue {
                     .for_each(|(dst, src)| *dst = *src);
             }
             Value::VecF64(val) => {
-                result.r#type = 8;
                 result.double_array_value = F64Seq::new(val.len()).unwrap_or_else(|| {
                     log::error!("{}:{}: failed allocation", file!(), line!());
                     F64Seq::null()
@@ -231,9 +232,8 @@ impl From<&Value> for ParameterValue {
                     .for_each(|(dst, src)| *dst = *src);
             }
             Value::VecString(val) => {
-                result.r#type = 9;
-                result.string_array_value = RosStringSeq::new(val.len()).unwrap_or_else(|| {
-                    log::error!("{}:{}: failed allocation", file!(), line!());
+                result.string_array_value = RosStringSeq::try_new(val.len()).unwrap_or_else(|e| {
+                    log::error!("{}:{}: {e}", file!(), line!());
                     RosStringSeq::null()
                 });
                 result
@@ -241,7 +241,9 @@ impl From<&Value> for ParameterValue {
                     .iter_mut()
                     .zip(val.iter())
                     .for_each(|(dst, src)| {
-                        dst.assign(src);
+                        if let Err(e) = dst.try_assign(src) {
+                            log::error!("{}:{}: {e}", file!(), line!());
+                        }
                     });
             }
         }
@@ -394,4 +396,134 @@ mod tests {
             "One or more parameter service type hashes do not match ROS2 expectations"
         );
     }
+
+    #[test]
+    fn test_parameter_type_matches_constants() {
+        use super::interfaces::rcl_interfaces::msg::ParameterType as Msg;
+        use oxidros_core::parameter::ParameterType;
+        let expected = [
+            (Msg::PARAMETER_NOT_SET, ParameterType::NotSet),
+            (Msg::PARAMETER_BOOL, ParameterType::Bool),
+            (Msg::PARAMETER_INTEGER, ParameterType::Integer),
+            (Msg::PARAMETER_DOUBLE, ParameterType::Double),
+            (Msg::PARAMETER_STRING, ParameterType::String),
+            (Msg::PARAMETER_BYTE_ARRAY, ParameterType::ByteArray),
+            (Msg::PARAMETER_BOOL_ARRAY, ParameterType::BoolArray),
+            (Msg::PARAMETER_INTEGER_ARRAY, ParameterType::IntegerArray),
+            (Msg::PARAMETER_DOUBLE_ARRAY, ParameterType::DoubleArray),
+            (Msg::PARAMETER_STRING_ARRAY, ParameterType::StringArray),
+        ];
+        for (raw, ty) in expected {
+            assert_eq!(ParameterType::try_from(raw).unwrap(), ty);
+            assert_eq!(u8::from(ty), raw);
+        }
+        assert!(ParameterType::try_from(10).is_err());
+    }
+
+    #[test]
+    fn test_goal_status_matches_constants() {
+        use super::interfaces::action_msgs::msg::GoalStatus as Msg;
+        use oxidros_core::action::GoalStatus;
+        let expected = [
+            (Msg::STATUS_UNKNOWN, GoalStatus::Unknown),
+            (Msg::STATUS_ACCEPTED, GoalStatus::Accepted),
+            (Msg::STATUS_EXECUTING, GoalStatus::Executing),
+            (Msg::STATUS_CANCELING, GoalStatus::Canceling),
+            (Msg::STATUS_SUCCEEDED, GoalStatus::Succeeded),
+            (Msg::STATUS_CANCELED, GoalStatus::Canceled),
+            (Msg::STATUS_ABORTED, GoalStatus::Aborted),
+        ];
+        for (raw, status) in expected {
+            assert_eq!(GoalStatus::from(raw), status);
+            assert_eq!(i8::from(status), raw);
+        }
+    }
+
+    #[test]
+    fn test_default_field_values() {
+        use super::common_interfaces::{
+            geometry_msgs::msg::Quaternion, sensor_msgs::msg::NavSatStatus,
+        };
+        use super::interfaces::{
+            rcl_interfaces::msg::ParameterDescriptor,
+            type_description_interfaces::srv::GetTypeDescription_Request,
+        };
+
+        // Same values as the rosidl generated init functions
+        let quaternion = Quaternion::new().expect("quaternion");
+        assert_eq!(
+            (quaternion.x, quaternion.y, quaternion.z, quaternion.w),
+            (0.0, 0.0, 0.0, 1.0)
+        );
+        assert_eq!(NavSatStatus::new().expect("status").status, -2);
+        assert_eq!(NavSatStatus::new().expect("status").service, 0);
+        assert!(
+            GetTypeDescription_Request::new()
+                .expect("request")
+                .include_type_sources
+        );
+        let descriptor = ParameterDescriptor::new().expect("descriptor");
+        assert!(!descriptor.read_only);
+        assert!(!descriptor.dynamic_typing);
+    }
+
+    #[test]
+    fn test_fixed_size_array_type_hash() {
+        use super::common_interfaces::{
+            geometry_msgs::msg::PoseWithCovariance, sensor_msgs::msg::Imu,
+        };
+        use oxidros_core::TypeDescription;
+
+        // float64[36] and float64[9] fields
+        assert_eq!(
+            PoseWithCovariance::compute_hash().expect("hash"),
+            "RIHS01_9a7c0fd234b7f45c6098745ecccd773ca1085670e64107135397aee31c02e1bb"
+        );
+        assert_eq!(
+            Imu::compute_hash().expect("hash"),
+            "RIHS01_7d9a00ff131080897a5ec7e26e315954b8eae3353c3f995c55faf71574000b5b"
+        );
+
+        let description = PoseWithCovariance::type_description();
+        let covariance = description
+            .type_description
+            .fields
+            .iter()
+            .find(|field| field.name == "covariance")
+            .expect("covariance field");
+        assert_eq!(
+            covariance.field_type.type_id,
+            ros2_types::types::FIELD_TYPE_DOUBLE_ARRAY
+        );
+        assert_eq!(covariance.field_type.capacity, 36);
+    }
+
+    #[test]
+    fn test_fixed_size_array_cdr_layout() {
+        use super::common_interfaces::geometry_msgs::msg::PoseWithCovariance;
+        use oxidros_core::TypeSupport;
+
+        let mut msg = PoseWithCovariance::new().unwrap();
+        msg.pose.position.x = 1.0;
+        msg.pose.orientation.w = 1.0;
+        for (i, value) in msg.covariance.iter_mut().enumerate() {
+            *value = i as f64;
+        }
+
+        // Fixed-size arrays have no length prefix: header, 7 doubles of the
+        // pose, then the 36 doubles of the covariance
+        let bytes = msg.to_bytes().unwrap();
+        assert_eq!(bytes.len(), 4 + (7 + 36) * 8);
+        assert_eq!(&bytes[..4], &[0, 1, 0, 0]);
+        assert_eq!(&bytes[4..12], &1.0f64.to_le_bytes());
+        assert_eq!(&bytes[52..60], &1.0f64.to_le_bytes());
+        for i in 0..36 {
+            let offset = 4 + 7 * 8 + i * 8;
+            assert_eq!(&bytes[offset..offset + 8], &(i as f64).to_le_bytes());
+        }
+
+        let decoded = PoseWithCovariance::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.covariance, msg.covariance);
+        assert_eq!(decoded.pose.orientation.w, 1.0);
+    }
 }
@@ -39,6 +39,14 @@ mod rcl_impl {
                     $ty(msg)
                 }
 
+                /// Whether the C runtime allocated the elements.
+                ///
+                /// Sequences created by `null()`, or whose initialization
+                /// failed, own nothing and are never finalized.
+                pub fn is_initialized(&self) -> bool {
+                    !self.0.data.is_null()
+                }
+
                 pub fn as_slice(&self) -> &[$ty_orig] {
                     if self.0.data.is_null() {
                         &[]
@@ -80,6 +88,9 @@ mod rcl_impl {
 
             impl<const N: usize> Drop for $ty<N> {
                 fn drop(&mut self) {
+                    if !self.is_initialized() {
+                        return;
+                    }
                     unsafe { $fini(&mut self.0 as *mut _) };
                 }
             }
@@ -245,7 +256,10 @@ mod rcl_impl {
 // ============================================================================
 #[cfg(not(feature = "rcl"))]
 mod non_rcl_impl {
-    use ros2_types::serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use ros2_types::{
+        FromDefaultLiteral,
+        serde::{Deserialize, Deserializer, Serialize, Serializer},
+    };
 
     macro_rules! def_sequence {
         ($ty: ident, $ty_orig:ty) => {
@@ -343,6 +357,13 @@ mod non_rcl_impl {
                 }
             }
 
+            impl<const N: usize> FromDefaultLiteral for $ty<N> {
+                fn from_default_literal(literal: &str) -> Option<Self> {
+                    let elements = Vec::<$ty_orig>::from_default_literal(literal)?;
+                    (N == 0 || elements.len() <= N).then_some($ty(elements))
+                }
+            }
+
             unsafe impl<const N: usize> Sync for $ty<N> {}
             unsafe impl<const N: usize> Send for $ty<N> {}
         };
@@ -419,6 +440,15 @@ mod tests {
 
     use super::*;
 
+    #[cfg(feature = "rcl")]
+    #[test]
+    fn test_init_state() {
+        assert!(!F64Seq::<0>::null().is_initialized());
+        // empty sequences allocate nothing
+        assert!(!F64Seq::<0>::new(0).unwrap().is_initialized());
+        assert!(F64Seq::<0>::new(1).unwrap().is_initialized());
+    }
+
     #[test]
     fn test_clone() {
         let v1: BoolSeq<0> = [true; 10].as_slice().try_into().unwrap();
@@ -428,6 +458,29 @@ mod tests {
         let v2 = v1.try_clone().unwrap();
         assert_eq!(v1, v2);
     }
+
+    #[test]
+    fn test_sequence_lifecycle() {
+        // dropping a never initialized sequence must not finalize it
+        drop(F64Seq::<0>::null());
+        drop(F64Seq::<0>::default());
+
+        let null = U8Seq::<0>::null();
+        assert!(null.is_empty());
+        let c = null.try_clone().unwrap();
+        assert!(c.is_empty());
+
+        let v1 = I64Seq::<0>::new(0).unwrap();
+        let v2 = v1.try_clone().unwrap();
+        assert_eq!(v1, v2);
+
+        let v1: F32Seq<0> = [1.5; 4].as_slice().try_into().unwrap();
+        let v2 = v1.try_clone().unwrap();
+        drop(v1);
+        assert_eq!(v2.as_slice(), &[1.5; 4]);
+
+        assert!(BoolSeq::<2>::new(3).is_none());
+    }
 }
 
 // ============================================================================
@@ -463,3 +516,44 @@ impl_ros_field_type_seq!(I64Seq, FIELD_TYPE_INT64);
 impl_ros_field_type_seq!(U64Seq, FIELD_TYPE_UINT64);
 impl_ros_field_type_seq!(F32Seq, FIELD_TYPE_FLOAT);
 impl_ros_field_type_seq!(F64Seq, FIELD_TYPE_DOUBLE);
+
+// ============================================================================
+// Arbitrary implementations for primitive sequences
+// ============================================================================
+
+#[cfg(feature = "arbitrary")]
+macro_rules! impl_arbitrary_seq {
+    ($ty:ident, $ty_orig:ty) => {
+        impl<'a, const N: usize> arbitrary::Arbitrary<'a> for $ty<N> {
+            fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                let mut len = u.arbitrary_len::<$ty_orig>()?;
+                if N != 0 {
+                    len = len.min(N);
+                }
+                let mut seq = Self::new(len).ok_or(arbitrary::Error::IncorrectFormat)?;
+                for elem in seq.iter_mut() {
+                    *elem = u.arbitrary()?;
+                }
+                Ok(seq)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impl {
+    use super::*;
+
+    impl_arbitrary_seq!(BoolSeq, bool);
+    impl_arbitrary_seq!(I8Seq, i8);
+    impl_arbitrary_seq!(U8Seq, u8);
+    impl_arbitrary_seq!(ByteSeq, u8);
+    impl_arbitrary_seq!(I16Seq, i16);
+    impl_arbitrary_seq!(U16Seq, u16);
+    impl_arbitrary_seq!(I32Seq, i32);
+    impl_arbitrary_seq!(U32Seq, u32);
+    impl_arbitrary_seq!(I64Seq, i64);
+    impl_arbitrary_seq!(U64Seq, u64);
+    impl_arbitrary_seq!(F32Seq, f32);
+    impl_arbitrary_seq!(F64Seq, f64);
+}
@@ -0,0 +1,114 @@
+//! Round-trip checks of generated messages, for property tests.
+//!
+//! With the `arbitrary` feature, the generated messages implement
+//! `arbitrary::Arbitrary`, so that random instances can be built from the
+//! bytes given by a fuzzer or a property testing framework.
+//! [`check_roundtrip`] builds such an instance and verifies that:
+//!
+//! - it is decoded from its CDR encoding unchanged, and the decoded message
+//!   encodes to the same bytes, so that hashes of the encoding are stable;
+//! - its copy with [`TryClone`] (the FFI copy functions with the `rcl`
+//!   feature) is equal to it and encodes to the same bytes.
+//!
+//! Messages holding a NaN are not equal to themselves: only their encodings
+//! are compared.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros_msg::common_interfaces::sensor_msgs::msg::Imu;
+//! use oxidros_msg::roundtrip::check_roundtrip;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     #[test]
+//!     fn imu_roundtrip(data in prop::collection::vec(any::<u8>(), 0..1024)) {
+//!         check_roundtrip::<Imu>(&data).map_err(|e| TestCaseError::fail(e.to_string()))?;
+//!     }
+//! }
+//! ```
+
+use arbitrary::{Arbitrary, Unstructured};
+use ros2_types::{TryClone, TypeSupport};
+use std::fmt::{self, Debug, Display};
+
+/// Failure of [`check_roundtrip`].
+#[derive(Debug)]
+pub enum RoundTripError {
+    /// The message could not be encoded.
+    Encode(ros2_types::Error),
+    /// The encoding of the message could not be decoded.
+    Decode(ros2_types::Error),
+    /// The message could not be copied.
+    Copy,
+    /// A decoded or copied message differs from the original.
+    Mismatch {
+        /// Step producing the different message, `decode` or `copy`.
+        step: &'static str,
+        /// Debug representation of the original message.
+        expected: String,
+        /// Debug representation of the different message.
+        actual: String,
+    },
+}
+
+impl Display for RoundTripError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encode(e) => write!(f, "failed to encode the message: {e}"),
+            Self::Decode(e) => write!(f, "failed to decode the message: {e}"),
+            Self::Copy => write!(f, "failed to copy the message"),
+            Self::Mismatch {
+                step,
+                expected,
+                actual,
+            } => write!(f, "{step} changed the message:\n{expected}\nto\n{actual}"),
+        }
+    }
+}
+
+impl std::error::Error for RoundTripError {}
+
+/// Build a `T` from `data` and check its round-trips through CDR and
+/// [`TryClone`].
+///
+/// Data from which no `T` can be built passes the check.
+pub fn check_roundtrip<T>(data: &[u8]) -> Result<(), RoundTripError>
+where
+    T: for<'a> Arbitrary<'a> + TypeSupport + TryClone + PartialEq + Debug,
+{
+    let Ok(msg) = T::arbitrary_take_rest(Unstructured::new(data)) else {
+        return Ok(());
+    };
+    let bytes = msg.to_bytes().map_err(RoundTripError::Encode)?;
+
+    let decoded = T::from_bytes(&bytes).map_err(RoundTripError::Decode)?;
+    check_same(&msg, &bytes, &decoded, "decode")?;
+
+    let copied = msg.try_clone().ok_or(RoundTripError::Copy)?;
+    check_same(&msg, &bytes, &copied, "copy")
+}
+
+/// Compare `actual` with `expected`, whose encoding is `bytes`.
+fn check_same<T>(
+    expected: &T,
+    bytes: &[u8],
+    actual: &T,
+    step: &'static str,
+) -> Result<(), RoundTripError>
+where
+    T: TypeSupport + PartialEq + Debug,
+{
+    let actual_bytes = actual.to_bytes().map_err(RoundTripError::Encode)?;
+    // A message holding a NaN is not equal to itself
+    #[allow(clippy::eq_op)]
+    let comparable = expected == expected;
+    if actual_bytes != bytes || (comparable && actual != expected) {
+        return Err(RoundTripError::Mismatch {
+            step,
+            expected: format!("{expected:?}"),
+            actual: format!("{actual:?}"),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,99 @@
+//! Mapping of Rust primitive types and `String` to `std_msgs` messages.
+//!
+//! | Rust type | Message |
+//! |-----------|---------|
+//! | `bool` | `std_msgs/msg/Bool` |
+//! | `i8`, `i16`, `i32`, `i64` | `std_msgs/msg/Int8` ... `Int64` |
+//! | `u8`, `u16`, `u32`, `u64` | `std_msgs/msg/UInt8` ... `UInt64` |
+//! | `f32`, `f64` | `std_msgs/msg/Float32`, `Float64` |
+//! | `String` | `std_msgs/msg/String` |
+
+use crate::common_interfaces::std_msgs::msg;
+use crate::msg::RosString;
+use oxidros_core::{MessageError, Result, TypeDescription, TypeSupport};
+
+/// A Rust type carried by the `data` field of a `std_msgs` message.
+///
+/// # Example
+///
+/// ```
+/// use oxidros_msg::std_primitive::StdPrimitive;
+///
+/// let msg = 21.5f64.to_msg().unwrap();
+/// assert_eq!(msg.data, 21.5);
+/// assert_eq!(f64::from_msg(&msg), 21.5);
+/// ```
+pub trait StdPrimitive: Sized + Send + Sync + 'static {
+    /// The `std_msgs` message carrying this type.
+    type Msg: TypeSupport + TypeDescription;
+
+    /// Wrap the value in a message.
+    fn to_msg(&self) -> Result<Self::Msg>;
+
+    /// Unwrap the value of a message.
+    fn from_msg(msg: &Self::Msg) -> Self;
+}
+
+macro_rules! impl_std_primitive {
+    ($ty:ty, $msg:ident) => {
+        impl StdPrimitive for $ty {
+            type Msg = msg::$msg;
+
+            fn to_msg(&self) -> Result<Self::Msg> {
+                let mut msg = msg::$msg::new().ok_or(MessageError::InitFailed(concat!(
+                    "std_msgs/msg/",
+                    stringify!($msg)
+                )))?;
+                msg.data = *self;
+                Ok(msg)
+            }
+
+            fn from_msg(msg: &Self::Msg) -> Self {
+                msg.data
+            }
+        }
+    };
+}
+
+impl_std_primitive!(bool, Bool);
+impl_std_primitive!(i8, Int8);
+impl_std_primitive!(i16, Int16);
+impl_std_primitive!(i32, Int32);
+impl_std_primitive!(i64, Int64);
+impl_std_primitive!(u8, UInt8);
+impl_std_primitive!(u16, UInt16);
+impl_std_primitive!(u32, UInt32);
+impl_std_primitive!(u64, UInt64);
+impl_std_primitive!(f32, Float32);
+impl_std_primitive!(f64, Float64);
+
+impl StdPrimitive for String {
+    type Msg = msg::String;
+
+    fn to_msg(&self) -> Result<Self::Msg> {
+        let mut msg = msg::String::new().ok_or(MessageError::InitFailed("std_msgs/msg/String"))?;
+        msg.data = RosString::try_new(self)?;
+        Ok(msg)
+    }
+
+    fn from_msg(msg: &Self::Msg) -> Self {
+        msg.data.get_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        assert!(bool::from_msg(&true.to_msg().unwrap()));
+        assert_eq!(i8::from_msg(&(-3i8).to_msg().unwrap()), -3);
+        assert_eq!(u64::from_msg(&u64::MAX.to_msg().unwrap()), u64::MAX);
+        assert_eq!(f32::from_msg(&1.5f32.to_msg().unwrap()), 1.5);
+
+        let msg = "hello".to_string().to_msg().unwrap();
+        assert_eq!(msg.data.get_string(), "hello");
+        assert_eq!(String::from_msg(&msg), "hello");
+    }
+}
@@ -1,5 +1,5 @@
 //! Definition of Strings
-use oxidros_core::msg::TryClone;
+use oxidros_core::{error::MessageError, msg::TryClone};
 
 use std::fmt::Display;
 
@@ -10,7 +10,7 @@ use std::fmt::Display;
 mod rcl_impl {
     use super::*;
     use crate::rcl::*;
-    use std::{ffi::CString, mem::transmute};
+    use std::mem::transmute;
 
     /// String.
     /// `N` represents the maximum number of characters excluding `\0`.
@@ -21,18 +21,30 @@ mod rcl_impl {
 
     impl<const N: usize> RosString<N> {
         pub fn new(s: &str) -> Option<Self> {
+            Self::try_new(s).ok()
+        }
+
+        /// Create a string, reporting why it could not be created.
+        pub fn try_new(s: &str) -> Result<Self, MessageError> {
             let mut msg: rosidl_runtime_c__String = unsafe { std::mem::zeroed() };
 
             // initialize string
-            if unsafe { rosidl_runtime_c__String__init(&mut msg) } {
-                if Self::assign_string(&mut msg, s) {
-                    Some(Self(msg))
-                } else {
-                    None
-                }
-            } else {
-                None
+            if !unsafe { rosidl_runtime_c__String__init(&mut msg) } {
+                return Err(MessageError::InitFailed("string"));
             }
+
+            // finalized by Drop if the assignment fails
+            let mut result = Self(msg);
+            result.try_assign(s)?;
+            Ok(result)
+        }
+
+        /// Whether the C runtime allocated this value.
+        ///
+        /// Values created by `null()`, or whose initialization failed, own
+        /// nothing and are never finalized.
+        pub fn is_initialized(&self) -> bool {
+            !self.0.data.is_null()
         }
 
         pub fn null() -> Self {
@@ -40,19 +52,57 @@ mod rcl_impl {
             Self(msg)
         }
 
-        fn assign_string(msg: &mut rosidl_runtime_c__String, s: &str) -> bool {
-            let cs = CString::new(s).unwrap();
+        pub fn assign(&mut self, s: &str) -> bool {
+            self.try_assign(s).is_ok()
+        }
 
-            // assign string
-            if N == 0 {
-                unsafe { rosidl_runtime_c__String__assign(msg, cs.as_ptr()) }
+        /// Assign a string, reporting why it could not be assigned.
+        ///
+        /// The current buffer is reused when it is large enough, so assigning
+        /// the same short strings over and over (e.g. `frame_id`) does not
+        /// allocate.
+        pub fn try_assign(&mut self, s: &str) -> Result<(), MessageError> {
+            if let Some(position) = s.bytes().position(|b| b == 0) {
+                return Err(MessageError::InteriorNul { position });
+            }
+            if N > 0 && s.len() > N {
+                return Err(MessageError::BoundExceeded {
+                    len: s.len(),
+                    max: N,
+                });
+            }
+
+            // `capacity` includes the terminating `\0`
+            if !self.0.data.is_null() && s.len() < self.0.capacity {
+                unsafe {
+                    std::ptr::copy_nonoverlapping(s.as_ptr(), self.0.data.cast::<u8>(), s.len());
+                    *self.0.data.add(s.len()) = 0;
+                }
+                self.0.size = s.len();
+                return Ok(());
+            }
+
+            // (re)allocate with the allocator of the C runtime
+            let assigned = unsafe {
+                rosidl_runtime_c__String__assignn(&mut self.0, s.as_ptr().cast(), s.len())
+            };
+            if assigned {
+                Ok(())
             } else {
-                unsafe { rosidl_runtime_c__String__assignn(msg, cs.as_ptr(), N as _) }
+                Err(MessageError::AssignFailed("string"))
             }
         }
 
-        pub fn assign(&mut self, s: &str) -> bool {
-            Self::assign_string(&mut self.0, s)
+        /// Get the string without copying it.
+        ///
+        /// Returns `None` if the string is not valid UTF-8.
+        pub fn as_str(&self) -> Option<&str> {
+            if self.0.data.is_null() {
+                return Some("");
+            }
+            let bytes =
+                unsafe { std::slice::from_raw_parts(self.0.data.cast::<u8>(), self.0.size) };
+            std::str::from_utf8(bytes).ok()
         }
 
         pub fn as_slice(&self) -> &[std::os::raw::c_char] {
@@ -71,25 +121,26 @@ mod rcl_impl {
             }
         }
 
+        /// Copy the string, empty if it is not valid UTF-8.
+        ///
+        /// Prefer [`Self::as_str`] to avoid the copy.
         pub fn get_string(&self) -> String {
-            if let Ok(m) = String::from_utf8(self.as_slice().iter().map(|&c| c as u8).collect()) {
-                m
-            } else {
-                "".to_string()
-            }
+            self.as_str().unwrap_or_default().to_string()
         }
     }
 
     impl<const N: usize> Drop for RosString<N> {
         fn drop(&mut self) {
+            if !self.is_initialized() {
+                return;
+            }
             unsafe { rosidl_runtime_c__String__fini(&mut self.0 as *mut _) };
         }
     }
 
     impl<const N: usize> Display for RosString<N> {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            let s = self.get_string();
-            write!(f, "{s}")
+            f.write_str(self.as_str().unwrap_or_default())
         }
     }
 
@@ -138,19 +189,35 @@ mod rcl_impl {
 
     impl<const STRLEN: usize, const SEQLEN: usize> RosStringSeq<STRLEN, SEQLEN> {
         pub fn new(size: usize) -> Option<Self> {
+            Self::try_new(size).ok()
+        }
+
+        /// Create a sequence of `size` empty strings, reporting why it could not be created.
+        pub fn try_new(size: usize) -> Result<Self, MessageError> {
             if SEQLEN != 0 && size > SEQLEN {
                 // the size exceeds the maximum number
-                return None;
+                return Err(MessageError::BoundExceeded {
+                    len: size,
+                    max: SEQLEN,
+                });
             }
 
             let mut msg: rosidl_runtime_c__String__Sequence = unsafe { std::mem::zeroed() };
             if unsafe { rosidl_runtime_c__String__Sequence__init(&mut msg, size as _) } {
-                Some(Self(msg))
+                Ok(Self(msg))
             } else {
-                None
+                Err(MessageError::InitFailed("string sequence"))
             }
         }
 
+        /// Whether the C runtime allocated this value.
+        ///
+        /// Values created by `null()`, or whose initialization failed, own
+        /// nothing and are never finalized.
+        pub fn is_initialized(&self) -> bool {
+            !self.0.data.is_null()
+        }
+
         pub fn null() -> Self {
             let msg: rosidl_runtime_c__String__Sequence = unsafe { std::mem::zeroed() };
             Self(msg)
@@ -195,6 +262,9 @@ mod rcl_impl {
 
     impl<const STRLEN: usize, const SEQLEN: usize> Drop for RosStringSeq<STRLEN, SEQLEN> {
         fn drop(&mut self) {
+            if !self.is_initialized() {
+                return;
+            }
             unsafe { rosidl_runtime_c__String__Sequence__fini(&mut self.0 as *mut _) };
         }
     }
@@ -245,18 +315,30 @@ mod rcl_impl {
 
     impl<const N: usize> RosWString<N> {
         pub fn new(s: &str) -> Option<Self> {
+            Self::try_new(s).ok()
+        }
+
+        /// Create a wide string, reporting why it could not be created.
+        pub fn try_new(s: &str) -> Result<Self, MessageError> {
             let mut msg: rosidl_runtime_c__U16String = unsafe { std::mem::zeroed() };
 
             // initialize string
-            if unsafe { rosidl_runtime_c__U16String__init(&mut msg) } {
-                if Self::assign_string(&mut msg, s) {
-                    Some(Self(msg))
-                } else {
-                    None
-                }
-            } else {
-                None
+            if !unsafe { rosidl_runtime_c__U16String__init(&mut msg) } {
+                return Err(MessageError::InitFailed("wstring"));
             }
+
+            // finalized by Drop if the assignment fails
+            let mut result = Self(msg);
+            result.try_assign(s)?;
+            Ok(result)
+        }
+
+        /// Whether the C runtime allocated this value.
+        ///
+        /// Values created by `null()`, or whose initialization failed, own
+        /// nothing and are never finalized.
+        pub fn is_initialized(&self) -> bool {
+            !self.0.data.is_null()
         }
 
         pub fn null() -> Self {
@@ -264,19 +346,29 @@ mod rcl_impl {
             Self(msg)
         }
 
-        fn assign_string(msg: &mut rosidl_runtime_c__U16String, s: &str) -> bool {
-            let cs = widestring::U16CString::from_str(s).unwrap();
+        pub fn assign(&mut self, s: &str) -> bool {
+            self.try_assign(s).is_ok()
+        }
+
+        /// Assign a wide string, reporting why it could not be assigned.
+        pub fn try_assign(&mut self, s: &str) -> Result<(), MessageError> {
+            let cs =
+                widestring::U16CString::from_str(s).map_err(|e| MessageError::InteriorNul {
+                    position: e.nul_position(),
+                })?;
 
             // assign string
-            if N == 0 {
-                unsafe { rosidl_runtime_c__U16String__assign(msg, cs.as_ptr()) }
+            let assigned = if N == 0 {
+                unsafe { rosidl_runtime_c__U16String__assign(&mut self.0, cs.as_ptr()) }
             } else {
-                unsafe { rosidl_runtime_c__U16String__assignn(msg, cs.as_ptr(), N as _) }
-            }
-        }
+                unsafe { rosidl_runtime_c__U16String__assignn(&mut self.0, cs.as_ptr(), N as _) }
+            };
 
-        pub fn assign(&mut self, s: &str) -> bool {
-            Self::assign_string(&mut self.0, s)
+            if assigned {
+                Ok(())
+            } else {
+                Err(MessageError::AssignFailed("wstring"))
+            }
         }
 
         pub fn as_slice(&self) -> &[u16] {
@@ -306,6 +398,9 @@ mod rcl_impl {
 
     impl<const N: usize> Drop for RosWString<N> {
         fn drop(&mut self) {
+            if !self.is_initialized() {
+                return;
+            }
             unsafe { rosidl_runtime_c__U16String__fini(&mut self.0 as *mut _) };
         }
     }
@@ -362,19 +457,35 @@ mod rcl_impl {
 
     impl<const STRLEN: usize, const SEQLEN: usize> RosWStringSeq<STRLEN, SEQLEN> {
         pub fn new(size: usize) -> Option<Self> {
+            Self::try_new(size).ok()
+        }
+
+        /// Create a sequence of `size` empty strings, reporting why it could not be created.
+        pub fn try_new(size: usize) -> Result<Self, MessageError> {
             if SEQLEN != 0 && size > SEQLEN {
                 // the size exceeds the maximum number
-                return None;
+                return Err(MessageError::BoundExceeded {
+                    len: size,
+                    max: SEQLEN,
+                });
             }
 
             let mut msg: rosidl_runtime_c__U16String__Sequence = unsafe { std::mem::zeroed() };
             if unsafe { rosidl_runtime_c__U16String__Sequence__init(&mut msg, size as _) } {
-                Some(Self(msg))
+                Ok(Self(msg))
             } else {
-                None
+                Err(MessageError::InitFailed("wstring sequence"))
             }
         }
 
+        /// Whether the C runtime allocated this value.
+        ///
+        /// Values created by `null()`, or whose initialization failed, own
+        /// nothing and are never finalized.
+        pub fn is_initialized(&self) -> bool {
+            !self.0.data.is_null()
+        }
+
         pub fn null() -> Self {
             let msg: rosidl_runtime_c__U16String__Sequence = unsafe { std::mem::zeroed() };
             Self(msg)
@@ -421,6 +532,9 @@ mod rcl_impl {
 
     impl<const STRLEN: usize, const SEQLEN: usize> Drop for RosWStringSeq<STRLEN, SEQLEN> {
         fn drop(&mut self) {
+            if !self.is_initialized() {
+                return;
+            }
             unsafe { rosidl_runtime_c__U16String__Sequence__fini(&mut self.0 as *mut _) };
         }
     }
@@ -469,7 +583,10 @@ mod rcl_impl {
 #[cfg(not(feature = "rcl"))]
 mod non_rcl_impl {
     use super::*;
-    use ros2_types::serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use ros2_types::{
+        FromDefaultLiteral,
+        serde::{Deserialize, Deserializer, Serialize, Serializer},
+    };
 
     /// String.
     /// `N` represents the maximum number of characters excluding `\0`.
@@ -479,11 +596,14 @@ mod non_rcl_impl {
 
     impl<const N: usize> RosString<N> {
         pub fn new(s: &str) -> Option<Self> {
-            // Check length constraint if N > 0
-            if N > 0 && s.len() > N {
-                return None;
-            }
-            Some(Self(s.to_string()))
+            Self::try_new(s).ok()
+        }
+
+        /// Create a string, reporting why it could not be created.
+        pub fn try_new(s: &str) -> Result<Self, MessageError> {
+            let mut result = Self::null();
+            result.try_assign(s)?;
+            Ok(result)
         }
 
         pub fn null() -> Self {
@@ -491,12 +611,31 @@ mod non_rcl_impl {
         }
 
         pub fn assign(&mut self, s: &str) -> bool {
+            self.try_assign(s).is_ok()
+        }
+
+        /// Assign a string, reporting why it could not be assigned.
+        ///
+        /// The current buffer is reused when it is large enough.
+        pub fn try_assign(&mut self, s: &str) -> Result<(), MessageError> {
             // Check length constraint if N > 0
             if N > 0 && s.len() > N {
-                return false;
+                return Err(MessageError::BoundExceeded {
+                    len: s.len(),
+                    max: N,
+                });
             }
-            self.0 = s.to_string();
-            true
+            self.0.clear();
+            self.0.push_str(s);
+            Ok(())
+        }
+
+        /// Get the string without copying it.
+        ///
+        /// Always `Some`; the `Option` matches the FFI string, which may hold
+        /// invalid UTF-8.
+        pub fn as_str(&self) -> Option<&str> {
+            Some(&self.0)
         }
 
         pub fn get_string(&self) -> String {
@@ -564,6 +703,12 @@ mod non_rcl_impl {
         }
     }
 
+    impl<const N: usize> FromDefaultLiteral for RosString<N> {
+        fn from_default_literal(literal: &str) -> Option<Self> {
+            Self::new(&String::from_default_literal(literal)?)
+        }
+    }
+
     unsafe impl<const N: usize> Sync for RosString<N> {}
     unsafe impl<const N: usize> Send for RosString<N> {}
 
@@ -577,15 +722,23 @@ mod non_rcl_impl {
 
     impl<const STRLEN: usize, const SEQLEN: usize> RosStringSeq<STRLEN, SEQLEN> {
         pub fn new(size: usize) -> Option<Self> {
+            Self::try_new(size).ok()
+        }
+
+        /// Create a sequence of `size` empty strings, reporting why it could not be created.
+        pub fn try_new(size: usize) -> Result<Self, MessageError> {
             if SEQLEN != 0 && size > SEQLEN {
                 // the size exceeds the maximum number
-                return None;
+                return Err(MessageError::BoundExceeded {
+                    len: size,
+                    max: SEQLEN,
+                });
             }
             let mut v = Vec::with_capacity(size);
             for _ in 0..size {
                 v.push(RosString::null());
             }
-            Some(Self(v))
+            Ok(Self(v))
         }
 
         pub fn null() -> Self {
@@ -669,6 +822,13 @@ mod non_rcl_impl {
         }
     }
 
+    impl<const STRLEN: usize, const SEQLEN: usize> FromDefaultLiteral for RosStringSeq<STRLEN, SEQLEN> {
+        fn from_default_literal(literal: &str) -> Option<Self> {
+            let elements = Vec::<RosString<STRLEN>>::from_default_literal(literal)?;
+            (SEQLEN == 0 || elements.len() <= SEQLEN).then_some(Self(elements))
+        }
+    }
+
     unsafe impl<const STRLEN: usize, const SEQLEN: usize> Sync for RosStringSeq<STRLEN, SEQLEN> {}
     unsafe impl<const STRLEN: usize, const SEQLEN: usize> Send for RosStringSeq<STRLEN, SEQLEN> {}
 
@@ -680,11 +840,14 @@ mod non_rcl_impl {
 
     impl<const N: usize> RosWString<N> {
         pub fn new(s: &str) -> Option<Self> {
-            // Check length constraint if N > 0 (count UTF-16 code units)
-            if N > 0 && s.encode_utf16().count() > N {
-                return None;
-            }
-            Some(Self(s.to_string()))
+            Self::try_new(s).ok()
+        }
+
+        /// Create a wide string, reporting why it could not be created.
+        pub fn try_new(s: &str) -> Result<Self, MessageError> {
+            let mut result = Self::null();
+            result.try_assign(s)?;
+            Ok(result)
         }
 
         pub fn null() -> Self {
@@ -692,12 +855,18 @@ mod non_rcl_impl {
         }
 
         pub fn assign(&mut self, s: &str) -> bool {
+            self.try_assign(s).is_ok()
+        }
+
+        /// Assign a wide string, reporting why it could not be assigned.
+        pub fn try_assign(&mut self, s: &str) -> Result<(), MessageError> {
             // Check length constraint if N > 0 (count UTF-16 code units)
-            if N > 0 && s.encode_utf16().count() > N {
-                return false;
+            let len = s.encode_utf16().count();
+            if N > 0 && len > N {
+                return Err(MessageError::BoundExceeded { len, max: N });
             }
             self.0 = s.to_string();
-            true
+            Ok(())
         }
 
         pub fn get_string(&self) -> String {
@@ -764,6 +933,12 @@ mod non_rcl_impl {
         }
     }
 
+    impl<const N: usize> FromDefaultLiteral for RosWString<N> {
+        fn from_default_literal(literal: &str) -> Option<Self> {
+            Self::new(&String::from_default_literal(literal)?)
+        }
+    }
+
     unsafe impl<const N: usize> Sync for RosWString<N> {}
     unsafe impl<const N: usize> Send for RosWString<N> {}
 
@@ -777,15 +952,23 @@ mod non_rcl_impl {
 
     impl<const STRLEN: usize, const SEQLEN: usize> RosWStringSeq<STRLEN, SEQLEN> {
         pub fn new(size: usize) -> Option<Self> {
+            Self::try_new(size).ok()
+        }
+
+        /// Create a sequence of `size` empty strings, reporting why it could not be created.
+        pub fn try_new(size: usize) -> Result<Self, MessageError> {
             if SEQLEN != 0 && size > SEQLEN {
                 // the size exceeds the maximum number
-                return None;
+                return Err(MessageError::BoundExceeded {
+                    len: size,
+                    max: SEQLEN,
+                });
             }
             let mut v = Vec::with_capacity(size);
             for _ in 0..size {
                 v.push(RosWString::null());
             }
-            Some(Self(v))
+            Ok(Self(v))
         }
 
         pub fn null() -> Self {
@@ -869,6 +1052,15 @@ mod non_rcl_impl {
         }
     }
 
+    impl<const STRLEN: usize, const SEQLEN: usize> FromDefaultLiteral
+        for RosWStringSeq<STRLEN, SEQLEN>
+    {
+        fn from_default_literal(literal: &str) -> Option<Self> {
+            let elements = Vec::<RosWString<STRLEN>>::from_default_literal(literal)?;
+            (SEQLEN == 0 || elements.len() <= SEQLEN).then_some(Self(elements))
+        }
+    }
+
     unsafe impl<const STRLEN: usize, const SEQLEN: usize> Sync for RosWStringSeq<STRLEN, SEQLEN> {}
     unsafe impl<const STRLEN: usize, const SEQLEN: usize> Send for RosWStringSeq<STRLEN, SEQLEN> {}
 }
@@ -942,3 +1134,207 @@ impl<const STRLEN: usize, const SEQLEN: usize> RosFieldType for RosWStringSeq<ST
         }
     }
 }
+
+// ============================================================================
+// KeyField implementations for string types
+// ============================================================================
+
+impl<const N: usize> ros2_types::KeyField for RosString<N> {
+    type Key = String;
+
+    fn key_value(&self) -> Self::Key {
+        self.get_string()
+    }
+}
+
+impl<const N: usize> ros2_types::KeyField for RosWString<N> {
+    type Key = String;
+
+    fn key_value(&self) -> Self::Key {
+        self.get_string()
+    }
+}
+
+// ============================================================================
+// Arbitrary implementations for string types
+// ============================================================================
+
+/// Random strings within the bounds, without NUL characters, which the C
+/// strings of RCL cannot hold.
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impl {
+    use super::*;
+    use arbitrary::{Arbitrary, Error, Result, Unstructured};
+
+    /// Arbitrary string of at most `max` bytes, or `max` UTF-16 code units
+    /// with `utf16`. `max == 0` means unbounded.
+    fn arbitrary_str(u: &mut Unstructured<'_>, max: usize, utf16: bool) -> Result<String> {
+        let s: &str = u.arbitrary()?;
+        let mut len = 0;
+        Ok(s.chars()
+            .filter(|&c| c != '\0')
+            .take_while(|c| {
+                len += if utf16 { c.len_utf16() } else { c.len_utf8() };
+                max == 0 || len <= max
+            })
+            .collect())
+    }
+
+    impl<'a, const N: usize> Arbitrary<'a> for RosString<N> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Self::new(&arbitrary_str(u, N, false)?).ok_or(Error::IncorrectFormat)
+        }
+    }
+
+    impl<'a, const N: usize> Arbitrary<'a> for RosWString<N> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Self::new(&arbitrary_str(u, N, true)?).ok_or(Error::IncorrectFormat)
+        }
+    }
+
+    impl<'a, const STRLEN: usize, const SEQLEN: usize> Arbitrary<'a> for RosStringSeq<STRLEN, SEQLEN> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let mut len = u.arbitrary_len::<RosString<STRLEN>>()?;
+            if SEQLEN != 0 {
+                len = len.min(SEQLEN);
+            }
+            let mut seq = Self::new(len).ok_or(Error::IncorrectFormat)?;
+            for elem in seq.iter_mut() {
+                *elem = u.arbitrary()?;
+            }
+            Ok(seq)
+        }
+    }
+
+    impl<'a, const STRLEN: usize, const SEQLEN: usize> Arbitrary<'a> for RosWStringSeq<STRLEN, SEQLEN> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let mut len = u.arbitrary_len::<RosWString<STRLEN>>()?;
+            if SEQLEN != 0 {
+                len = len.min(SEQLEN);
+            }
+            let mut seq = Self::new(len).ok_or(Error::IncorrectFormat)?;
+            for elem in seq.iter_mut() {
+                *elem = u.arbitrary()?;
+            }
+            Ok(seq)
+        }
+    }
+}
+
+// With the `rcl` feature these lifecycles run through the C runtime, which
+// miri cannot execute; check them for leaks and double frees with valgrind.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "rcl")]
+    #[test]
+    fn test_init_state() {
+        assert!(!RosString::<0>::null().is_initialized());
+        assert!(RosString::<0>::new("").unwrap().is_initialized());
+        assert!(!RosWString::<0>::null().is_initialized());
+        assert!(RosWString::<0>::new("").unwrap().is_initialized());
+
+        assert!(!RosStringSeq::<0, 0>::null().is_initialized());
+        assert!(RosStringSeq::<0, 0>::new(1).unwrap().is_initialized());
+        assert!(!RosWStringSeq::<0, 0>::null().is_initialized());
+        assert!(RosWStringSeq::<0, 0>::new(1).unwrap().is_initialized());
+    }
+
+    #[test]
+    fn test_string_lifecycle() {
+        // dropping a never initialized string must not finalize it
+        drop(RosString::<0>::null());
+        drop(RosString::<0>::default());
+
+        let s = RosString::<0>::new("hello").unwrap();
+        let c = s.try_clone().unwrap();
+        assert_eq!(s, c);
+        drop(s);
+        assert_eq!(c.get_string(), "hello");
+
+        let mut n = RosString::<0>::null();
+        assert!(n.assign("assigned after null"));
+        assert_eq!(n.get_string(), "assigned after null");
+
+        // a failed assignment leaves the string droppable
+        assert!(RosString::<3>::try_new("too long").is_err());
+        #[cfg(feature = "rcl")]
+        assert!(RosString::<0>::try_new("nul\0").is_err());
+    }
+
+    #[test]
+    fn test_string_reassign() {
+        let mut s = RosString::<0>::new("camera_color_optical_frame").unwrap();
+        for frame in [
+            "base_link",
+            "",
+            "odom",
+            "camera_color_optical_frame",
+            "a longer frame than before",
+        ] {
+            assert!(s.assign(frame));
+            assert_eq!(s.as_str(), Some(frame));
+            assert_eq!(s.to_string(), frame);
+        }
+
+        let mut bounded = RosString::<4>::new("ab").unwrap();
+        assert!(bounded.try_assign("abcde").is_err());
+        assert_eq!(bounded.as_str(), Some("ab"));
+    }
+
+    #[test]
+    fn test_wstring_lifecycle() {
+        drop(RosWString::<0>::null());
+        drop(RosWString::<0>::default());
+
+        let s = RosWString::<0>::new("hello").unwrap();
+        let c = s.try_clone().unwrap();
+        assert_eq!(s, c);
+        drop(s);
+        assert_eq!(c.get_string(), "hello");
+
+        assert!(RosWString::<3>::try_new("too long").is_err());
+    }
+
+    #[test]
+    fn test_string_seq_lifecycle() {
+        drop(RosStringSeq::<0, 0>::null());
+        drop(RosStringSeq::<0, 0>::default());
+
+        let null = RosStringSeq::<0, 0>::null();
+        assert!(null.is_empty());
+        let c = null.try_clone().unwrap();
+        assert!(c.is_empty());
+
+        let mut seq = RosStringSeq::<0, 3>::new(3).unwrap();
+        for (i, s) in seq.iter_mut().enumerate() {
+            assert!(s.assign(&format!("item{i}")));
+        }
+        let c = seq.try_clone().unwrap();
+        assert_eq!(seq, c);
+        drop(seq);
+        assert_eq!(c.as_slice()[2].get_string(), "item2");
+
+        assert!(RosStringSeq::<0, 3>::try_new(4).is_err());
+    }
+
+    #[test]
+    fn test_wstring_seq_lifecycle() {
+        drop(RosWStringSeq::<0, 0>::null());
+        drop(RosWStringSeq::<0, 0>::default());
+
+        let null = RosWStringSeq::<0, 0>::null();
+        let c = null.try_clone().unwrap();
+        assert!(c.is_empty());
+
+        let mut seq = RosWStringSeq::<0, 2>::new(2).unwrap();
+        assert!(seq.as_mut_slice()[1].assign("second"));
+        let c = seq.try_clone().unwrap();
+        assert_eq!(seq, c);
+        drop(seq);
+        assert_eq!(c.as_slice()[1].get_string(), "second");
+
+        assert!(RosWStringSeq::<0, 2>::try_new(3).is_err());
+    }
+}
@@ -0,0 +1,37 @@
+//! Property tests round-tripping random messages through CDR and copies.
+
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use oxidros_msg::common_interfaces::shape_msgs;
+use oxidros_msg::interfaces::type_description_interfaces::msg::FieldType;
+use oxidros_msg::roundtrip::check_roundtrip;
+use proptest::prelude::*;
+
+macro_rules! roundtrip_tests {
+    ($($name:ident: $ty:ty,)*) => {
+        proptest! {
+            $(
+                #[test]
+                fn $name(data in prop::collection::vec(any::<u8>(), 0..2048)) {
+                    check_roundtrip::<$ty>(&data).map_err(|e| TestCaseError::fail(e.to_string()))?;
+                }
+            )*
+        }
+    };
+}
+
+// Every generated type deriving `Arbitrary`, listed by the build script
+include!(concat!(env!("OUT_DIR"), "/roundtrip_types.rs"));
+
+#[test]
+fn test_bounded_fields() {
+    let data: Vec<u8> = (0..4096).map(|i| (i * 31 % 251) as u8).collect();
+    let mut u = Unstructured::new(&data);
+
+    let primitive = shape_msgs::msg::SolidPrimitive::arbitrary(&mut u).unwrap();
+    assert!(primitive.dimensions.len() <= 3);
+
+    let field = FieldType::arbitrary(&mut u).unwrap();
+    assert!(field.nested_type_name.get_string().len() <= 255);
+}
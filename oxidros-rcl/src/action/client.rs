@@ -4,17 +4,28 @@ use oxidros_core::selector::CallbackResult;
 use oxidros_core::{ActionError, Error, RclError};
 use std::future::Future;
 use std::pin::Pin;
-use std::{ffi::CString, marker::PhantomData, sync::Arc, task::Poll, time::Duration};
+use std::{
+    collections::BTreeMap,
+    ffi::CString,
+    marker::PhantomData,
+    sync::Arc,
+    task::Poll,
+    time::{Duration, Instant},
+};
 
 use crate::helper::is_unpin;
 use crate::{
     error::Result,
     get_allocator, is_halt,
     msg::{
-        ActionMsg,
-        interfaces::action_msgs::{
-            msg::GoalStatusArray,
-            srv::{CancelGoal_Request, CancelGoal_Response},
+        ActionMsg, GoalResponse, ResultResponse,
+        interfaces::{
+            action_msgs::{
+                msg::{GoalInfo, GoalStatusArray},
+                srv::{CancelGoal_Request, CancelGoal_Response},
+            },
+            builtin_interfaces::msg::Time,
+            unique_identifier_msgs::msg::UUID,
         },
     },
     node::Node,
@@ -25,16 +36,39 @@ use crate::{
 };
 
 use super::{
-    GetResultServiceRequest, GetResultServiceResponse, SendGoalServiceRequest,
-    SendGoalServiceResponse,
+    ActionNames, GetResultServiceRequest, GetResultServiceResponse, GoalStatus,
+    SendGoalServiceRequest, SendGoalServiceResponse, server::ServerQosOption,
 };
 
+/// Options of an action client, with a QoS profile per service and topic.
+///
+/// The defaults match `rcl_action_client_get_default_options`. The profiles
+/// of the topics should be compatible with the ones of the server, see
+/// [`ServerQosOption`](super::server::ServerQosOption).
 pub struct ClientQosOption {
-    goal_service: Profile,
-    result_service: Profile,
-    cancel_service: Profile,
-    feedback_topic: Profile,
-    status_topic: Profile,
+    /// QoS of the `_action/send_goal` service.
+    pub goal_service: Profile,
+    /// QoS of the `_action/get_result` service.
+    pub result_service: Profile,
+    /// QoS of the `_action/cancel_goal` service.
+    pub cancel_service: Profile,
+    /// QoS of the `_action/feedback` topic.
+    pub feedback_topic: Profile,
+    /// QoS of the `_action/status` topic.
+    pub status_topic: Profile,
+}
+
+impl Default for ClientQosOption {
+    fn default() -> Self {
+        let server = ServerQosOption::default();
+        Self {
+            goal_service: server.goal_service,
+            result_service: server.result_service,
+            cancel_service: server.cancel_service,
+            feedback_topic: server.feedback_topic,
+            status_topic: server.status_topic,
+        }
+    }
 }
 
 impl From<ClientQosOption> for rcl::rcl_action_client_options_t {
@@ -70,10 +104,19 @@ unsafe impl Send for ClientData {}
 /// Consult `examples/action_client.rs` for a working example.
 pub struct Client<T: ActionMsg> {
     data: Arc<ClientData>,
+    /// Results retrieved by [`Client::request_result`], by goal UUID.
+    results: BTreeMap<[u8; 16], CachedResult<T>>,
+    /// How long results are kept in `results`.
+    result_cache_duration: Duration,
     // TODO: do like server::Client add Dbs
     _phantom: PhantomData<T>,
 }
 
+struct CachedResult<T: ActionMsg> {
+    response: Arc<GetResultServiceResponse<T>>,
+    received: Instant,
+}
+
 impl<T> Client<T>
 where
     T: ActionMsg,
@@ -91,7 +134,8 @@ where
             guard.rcl_action_client_init(
                 &mut client,
                 unsafe { node.as_ptr_mut() },
-                T::type_support() as *const rcl::rosidl_action_type_support_t,
+                T::try_type_support().map_err(Error::TypeSupport)?
+                    as *const rcl::rosidl_action_type_support_t,
                 action_name.as_ptr(),
                 &options,
             )?;
@@ -99,10 +143,23 @@ where
 
         Ok(Self {
             data: Arc::new(ClientData { client, node }),
+            results: BTreeMap::new(),
+            result_cache_duration: Duration::ZERO,
             _phantom: Default::default(),
         })
     }
 
+    /// Get the fully qualified action name.
+    pub fn action_name(&self) -> Result<String> {
+        let guard = rcl::MT_UNSAFE_FN.lock();
+        guard.rcl_action_client_get_action_name(&self.data.client)
+    }
+
+    /// Get the fully qualified names of the services and topics of the action.
+    pub fn names(&self) -> Result<ActionNames> {
+        Ok(ActionNames::new(&self.action_name()?))
+    }
+
     /// Returns true if the corresponding action server is available.
     pub fn is_server_available(&self) -> Result<bool> {
         let guard = rcl::MT_UNSAFE_FN.lock();
@@ -173,6 +230,71 @@ where
         })
     }
 
+    /// Get the result of the goal `uuid`, from the cache of the client or
+    /// else from the server.
+    ///
+    /// The goal may have been sent earlier, e.g. before a reconnection, or by
+    /// another process: the server answers for any goal it still keeps the
+    /// result of. The response is sent once the goal terminates. Responses
+    /// with the status [`GoalStatus::Unknown`], sent by servers which do not
+    /// know the goal, are not cached.
+    ///
+    /// Results of terminated goals are cached for the duration set by
+    /// [`Client::set_result_cache_duration`], so that several tools
+    /// monitoring a goal only ask the server once.
+    pub async fn request_result(
+        &mut self,
+        uuid: [u8; 16],
+    ) -> Result<Arc<GetResultServiceResponse<T>>> {
+        if let Some(response) = self.cached_result(&uuid) {
+            return Ok(response);
+        }
+
+        let request = <T as ActionMsg>::new_result_request(uuid);
+        let (response, _header) = self.send_result_request(&request)?.recv().await?;
+        let response = Arc::new(response);
+
+        self.prune_results();
+        let status = GoalStatus::from(response.get_status() as i8);
+        if !self.result_cache_duration.is_zero() && status != GoalStatus::Unknown {
+            self.results.insert(
+                uuid,
+                CachedResult {
+                    response: response.clone(),
+                    received: Instant::now(),
+                },
+            );
+        }
+        Ok(response)
+    }
+
+    /// Get the cached result of the goal `uuid`, see [`Client::request_result`].
+    pub fn cached_result(&self, uuid: &[u8; 16]) -> Option<Arc<GetResultServiceResponse<T>>> {
+        self.results
+            .get(uuid)
+            .filter(|cached| cached.received.elapsed() < self.result_cache_duration)
+            .map(|cached| cached.response.clone())
+    }
+
+    /// Set how long the results retrieved by [`Client::request_result`] are
+    /// cached. Results are not cached by default; a zero `duration` clears
+    /// the cache.
+    pub fn set_result_cache_duration(&mut self, duration: Duration) {
+        self.result_cache_duration = duration;
+        self.prune_results();
+    }
+
+    /// Get how long results are cached, see [`Client::set_result_cache_duration`].
+    pub fn result_cache_duration(&self) -> Duration {
+        self.result_cache_duration
+    }
+
+    fn prune_results(&mut self) {
+        let duration = self.result_cache_duration;
+        self.results
+            .retain(|_, cached| cached.received.elapsed() < duration);
+    }
+
     /// Send a cancel request. Use the returned [`ClientCancelRecv<T>`] to receive the response.
     pub fn send_cancel_request(
         &mut self,
@@ -193,6 +315,28 @@ where
         })
     }
 
+    /// Request to cancel all the goals of the action server, including the goals
+    /// sent by other clients. Use the returned [`ClientCancelRecv<T>`] to receive
+    /// the response listing the goals that are being canceled.
+    pub fn cancel_all_goals(&mut self) -> Result<ClientCancelRecv<'_, T>> {
+        self.cancel_goals_before(Time { sec: 0, nanosec: 0 })
+    }
+
+    /// Request to cancel all the goals accepted at or before `stamp`.
+    /// A zero `stamp` cancels all the goals, like [`Client::cancel_all_goals`].
+    /// Use the returned [`ClientCancelRecv<T>`] to receive the response listing
+    /// the goals that are being canceled.
+    pub fn cancel_goals_before(&mut self, stamp: Time) -> Result<ClientCancelRecv<'_, T>> {
+        // a zeroed goal ID selects the goals by their stamp only
+        let request = CancelGoal_Request {
+            goal_info: GoalInfo {
+                goal_id: UUID { uuid: [0; 16] },
+                stamp,
+            },
+        };
+        self.send_cancel_request(&request)
+    }
+
     /// Takes a feedback for the goal. If there is no feedback
     pub fn try_recv_feedback(&mut self) -> Result<Option<<T as ActionMsg>::Feedback>> {
         match rcl_action_take_feedback::<T>(&self.data.client) {
@@ -321,6 +465,19 @@ impl<'a, T: ActionMsg> ClientGoalRecv<'a, T> {
         }
         .await
     }
+
+    /// Asynchronously receive the response, and return
+    /// [`ActionError::GoalRejected`] if the goal was rejected.
+    pub async fn recv_accepted(
+        self,
+    ) -> Result<(SendGoalServiceResponse<T>, rcl::rmw_request_id_t)> {
+        let (response, header) = self.recv().await?;
+        if response.is_accepted() {
+            Ok((response, header))
+        } else {
+            Err(ActionError::GoalRejected.into())
+        }
+    }
 }
 
 pub struct AsyncGoalReceiver<'a, T: ActionMsg> {
@@ -430,6 +587,17 @@ impl<'a, T: ActionMsg> ClientCancelRecv<'a, T> {
         }
         .await
     }
+
+    /// Asynchronously receive the response, and return the error reported by
+    /// its `return_code`, e.g. [`ActionError::UnknownGoalId`], if the cancel
+    /// request was not accepted.
+    pub async fn recv_accepted(self) -> Result<(CancelGoal_Response, rcl::rmw_request_id_t)> {
+        let (response, header) = self.recv().await?;
+        match ActionError::from_cancel_code(response.return_code) {
+            None => Ok((response, header)),
+            Some(e) => Err(e.into()),
+        }
+    }
 }
 
 pub struct AsyncCancelReceiver<'a, T: ActionMsg> {
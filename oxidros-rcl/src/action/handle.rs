@@ -2,7 +2,13 @@
 
 use oxidros_core::{RclError, TryClone, targets};
 use parking_lot::Mutex;
-use std::{collections::BTreeMap, rc::Rc, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    future::poll_fn,
+    sync::{Arc, atomic::Ordering},
+    task::Poll,
+    time::{Duration, Instant},
+};
 
 use super::{GoalEvent, GoalStatus, server::ServerData};
 use crate::{error::Result, msg::ActionMsg, rcl};
@@ -10,9 +16,18 @@ use crate::{error::Result, msg::ActionMsg, rcl};
 /// GoalHandle contains information about an action goal and is used by server worker threads to send feedback and results.
 pub struct GoalHandle<T: ActionMsg> {
     pub goal_id: [u8; 16],
-    pub(crate) handle: Rc<GoalHandleData>,
+    pub(crate) handle: Arc<GoalHandleData>,
     data: Arc<ServerData>,
     pub results: Arc<Mutex<BTreeMap<[u8; 16], T::ResultContent>>>,
+    throttle: Arc<Mutex<FeedbackThrottle<T::FeedbackContent>>>,
+}
+
+/// Feedback of a goal held back by the rate limits.
+struct FeedbackThrottle<C> {
+    last_sent: Option<Instant>,
+    /// Latest feedback which came too early, published when the goal terminates.
+    pending: Option<C>,
+    dropped: u64,
 }
 
 impl<T> Clone for GoalHandle<T>
@@ -25,6 +40,7 @@ where
             handle: self.handle.clone(),
             data: self.data.clone(),
             results: self.results.clone(),
+            throttle: self.throttle.clone(),
         }
     }
 }
@@ -41,14 +57,90 @@ where
     ) -> Self {
         Self {
             goal_id,
-            handle: Rc::new(GoalHandleData(goal_handle)),
+            handle: Arc::new(GoalHandleData(goal_handle)),
             data,
             results,
+            throttle: Arc::new(Mutex::new(FeedbackThrottle {
+                last_sent: None,
+                pending: None,
+                dropped: 0,
+            })),
         }
     }
 
     /// Publish a feedback.
+    ///
+    /// Feedback is rate limited if the server was created with
+    /// [`ServerQosOption::feedback_rate_limit`](super::server::ServerQosOption::feedback_rate_limit),
+    /// see [`GoalHandle::feedback_throttled`].
     pub fn feedback(&self, content: T::FeedbackContent) -> Result<()> {
+        self.feedback_throttled(content, Duration::ZERO)?;
+        Ok(())
+    }
+
+    /// Publish a feedback unless the previous one was published less than
+    /// `min_interval` ago, or the rate limit of the server.
+    ///
+    /// A feedback coming too early is held back until the next one replaces
+    /// it, and then counted by [`GoalHandle::feedback_dropped`]. The feedback
+    /// held back when the goal terminates is published before the result, so
+    /// that clients always receive the last feedback.
+    ///
+    /// Returns `true` if the feedback was published.
+    pub fn feedback_throttled(
+        &self,
+        content: T::FeedbackContent,
+        min_interval: Duration,
+    ) -> Result<bool> {
+        let min_interval = min_interval.max(self.data.feedback_rate_limit.unwrap_or_default());
+        let now = Instant::now();
+
+        // Locked while publishing to keep the feedback in order
+        let mut throttle = self.throttle.lock();
+        if let Some(last_sent) = throttle.last_sent
+            && now.duration_since(last_sent) < min_interval
+        {
+            let replaced = throttle.pending.replace(content).is_some();
+            self.count_dropped(&mut throttle, replaced);
+            return Ok(false);
+        }
+
+        // The feedback held back is older than this one
+        let replaced = throttle.pending.take().is_some();
+        self.count_dropped(&mut throttle, replaced);
+        throttle.last_sent = Some(now);
+        self.publish_feedback(content)?;
+        Ok(true)
+    }
+
+    /// Number of feedback messages of this goal dropped by the rate limits.
+    pub fn feedback_dropped(&self) -> u64 {
+        self.throttle.lock().dropped
+    }
+
+    fn count_dropped(&self, throttle: &mut FeedbackThrottle<T::FeedbackContent>, dropped: bool) {
+        if dropped {
+            throttle.dropped += 1;
+            self.data.feedback_dropped.fetch_add(1, Ordering::Relaxed);
+            tracing::trace!(
+                target: targets::ACTION,
+                goal_id = ?self.goal_id,
+                "Feedback dropped by the rate limit"
+            );
+        }
+    }
+
+    /// Publish the feedback held back by the rate limits, if any.
+    fn flush_feedback(&self) -> Result<()> {
+        let mut throttle = self.throttle.lock();
+        if let Some(content) = throttle.pending.take() {
+            throttle.last_sent = Some(Instant::now());
+            self.publish_feedback(content)?;
+        }
+        Ok(())
+    }
+
+    fn publish_feedback(&self, content: T::FeedbackContent) -> Result<()> {
         let mut msg = <T as ActionMsg>::new_feedback_message(content, self.goal_id);
 
         let guard = rcl::MT_UNSAFE_FN.lock();
@@ -61,9 +153,11 @@ where
 
     /// Notify the server that the goal is successfully canceled.
     pub fn canceled(&self, result: T::ResultContent) -> Result<()> {
+        self.flush_feedback()?;
         self.update_result(result)?;
 
         self.update(GoalEvent::Canceled)?;
+        self.data.goals.lock().terminate(&self.goal_id)?;
         self.data.publish_goal_status()?;
 
         Ok(())
@@ -71,9 +165,11 @@ where
 
     /// Notify the server that the goal is successfully finished.
     pub fn finish(&self, result: T::ResultContent) -> Result<()> {
+        self.flush_feedback()?;
         self.update_result(result)?;
 
         self.update(GoalEvent::Succeed)?;
+        self.data.goals.lock().terminate(&self.goal_id)?;
         self.data.publish_goal_status()?;
 
         Ok(())
@@ -90,11 +186,33 @@ where
     }
 
     pub fn abort(&self) -> Result<()> {
+        self.flush_feedback()?;
         self.update(GoalEvent::Abort)?;
+        self.data.goals.lock().terminate(&self.goal_id)?;
         self.data.publish_goal_status()?;
         Ok(())
     }
 
+    /// Wait until the goal leaves the ACCEPTED state.
+    ///
+    /// Goals queued by [`GoalAcceptancePolicy::Queue`](super::server::GoalAcceptancePolicy::Queue)
+    /// stay ACCEPTED until the goals before them terminate; other goals execute right away.
+    /// Returns `false` if the goal was canceled while waiting.
+    pub async fn wait_until_executing(&self) -> Result<bool> {
+        poll_fn(|cx| {
+            let mut goals = self.data.goals.lock();
+            match self.status() {
+                Ok(GoalStatus::Accepted) => {
+                    goals.waiters.insert(self.goal_id, cx.waker().clone());
+                    Poll::Pending
+                }
+                Ok(status) => Poll::Ready(Ok(status == GoalStatus::Executing)),
+                Err(e) => Poll::Ready(Err(e)),
+            }
+        })
+        .await
+    }
+
     pub(crate) fn update(&self, event: GoalEvent) -> Result<()> {
         self.handle.update_goal_state(event)
     }
@@ -152,13 +270,7 @@ where
     }
 
     fn status(&self) -> Result<GoalStatus> {
-        let mut s: rcl::rcl_action_goal_state_t = GoalStatus::Unknown as i8;
-        let guard = rcl::MT_UNSAFE_FN.lock();
-        guard
-            .rcl_action_goal_handle_get_status(self.handle.0, &mut s)
-            .unwrap();
-
-        Ok(GoalStatus::from(s))
+        self.handle.status()
     }
 }
 
@@ -168,12 +280,26 @@ unsafe impl<T> Sync for GoalHandle<T> where T: ActionMsg {}
 /// `GoalHandleData` wraps the pointer to `rcl_action_goal_handle_t` and finalizes it when dropped.
 pub(crate) struct GoalHandleData(pub *mut rcl::rcl_action_goal_handle_t);
 
+// The goal handle is only used while holding `MT_UNSAFE_FN`.
+unsafe impl Send for GoalHandleData {}
+unsafe impl Sync for GoalHandleData {}
+
 impl GoalHandleData {
     pub(crate) fn update_goal_state(&self, event: GoalEvent) -> Result<()> {
         let guard = rcl::MT_UNSAFE_FN.lock();
         guard.rcl_action_update_goal_state(self.0, event.into())?;
         Ok(())
     }
+
+    pub(crate) fn status(&self) -> Result<GoalStatus> {
+        let mut s: rcl::rcl_action_goal_state_t = GoalStatus::Unknown as i8;
+        let guard = rcl::MT_UNSAFE_FN.lock();
+        guard
+            .rcl_action_goal_handle_get_status(self.0, &mut s)
+            .unwrap();
+
+        Ok(GoalStatus::from(s))
+    }
 }
 
 impl Drop for GoalHandleData {
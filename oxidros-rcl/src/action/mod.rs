@@ -15,4 +15,4 @@ type GetResultServiceRequest<T> = <<T as ActionMsg>::Result as ActionResult>::Re
 type GetResultServiceResponse<T> = <<T as ActionMsg>::Result as ActionResult>::Response;
 pub type CancelRequest = action_msgs__srv__CancelGoal_Request;
 
-pub use oxidros_core::action::{GoalEvent, GoalStatus};
+pub use oxidros_core::action::{ActionNames, GoalEvent, GoalStatus};
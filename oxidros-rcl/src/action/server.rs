@@ -10,7 +10,16 @@ use oxidros_msg::interfaces::action_msgs::srv::CancelGoal_Response;
 use oxidros_msg::interfaces::unique_identifier_msgs::msg::UUID;
 use parking_lot::Mutex;
 use std::future::Future;
-use std::{collections::BTreeMap, ffi::CString, sync::Arc, task::Poll, time::Duration};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ffi::CString,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    task::{Poll, Waker},
+    time::Duration,
+};
 
 use crate::PhantomUnsync;
 use crate::helper::is_unpin;
@@ -35,16 +44,72 @@ use crate::{
     signal_handler::Signaled,
 };
 
-use super::GoalEvent;
-use super::{GetResultServiceRequest, GoalStatus, SendGoalServiceRequest, handle::GoalHandle};
+use super::{ActionNames, GoalEvent};
+use super::{
+    GetResultServiceRequest, GoalStatus, SendGoalServiceRequest,
+    handle::{GoalHandle, GoalHandleData},
+};
 
+/// Decides what happens to a new goal while other goals are still active.
+///
+/// A goal is active from the moment it is accepted until it reaches a terminal state
+/// through [`GoalHandle::finish`], [`GoalHandle::canceled`] or [`GoalHandle::abort`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GoalAcceptancePolicy {
+    /// Accept every goal and execute the goals concurrently.
+    #[default]
+    AcceptAll,
+    /// Reject new goals while a goal is active.
+    RejectIfBusy,
+    /// Accept the new goal and request cancellation of the executing goals.
+    ///
+    /// The preempted goals transition to CANCELING, and their workers finish them with
+    /// [`GoalHandle::canceled`] as for a cancel request from a client.
+    PreemptCurrent,
+    /// Accept new goals into a FIFO queue while a goal is active, and reject them once
+    /// `limit` goals are waiting.
+    ///
+    /// Queued goals stay ACCEPTED and start executing one after another; their workers
+    /// wait for their turn with [`GoalHandle::wait_until_executing`].
+    Queue { limit: usize },
+}
+
+/// Options of an action server, with a QoS profile per service and topic.
+///
+/// The defaults match `rcl_action_server_get_default_options`. For example,
+/// to send large feedback messages over a lossy link:
+///
+/// ```ignore
+/// let qos = ServerQosOption {
+///     feedback_topic: Profile {
+///         reliability: ReliabilityPolicy::BestEffort,
+///         ..Profile::default()
+///     },
+///     ..Default::default()
+/// };
+/// ```
+///
+/// See [`ActionNames`](super::ActionNames) for the names of these entities.
 pub struct ServerQosOption {
+    /// QoS of the `_action/send_goal` service.
     pub goal_service: Profile,
+    /// QoS of the `_action/get_result` service.
     pub result_service: Profile,
+    /// QoS of the `_action/cancel_goal` service.
     pub cancel_service: Profile,
+    /// QoS of the `_action/feedback` topic.
     pub feedback_topic: Profile,
+    /// QoS of the `_action/status` topic.
     pub status_topic: Profile,
+    /// Duration for which the result of a finished goal is kept.
     pub result_timeout: Duration,
+    /// Policy applied to incoming goals by [`ServerGoalSend::accept`].
+    pub goal_acceptance: GoalAcceptancePolicy,
+    /// Minimum interval between the feedback messages of a goal, `None` not
+    /// to limit them.
+    ///
+    /// Applies to [`GoalHandle::feedback`] and [`GoalHandle::feedback_throttled`].
+    pub feedback_rate_limit: Option<Duration>,
 }
 
 impl Default for ServerQosOption {
@@ -66,6 +131,8 @@ impl Default for ServerQosOption {
             feedback_topic: Profile::default(),
             status_topic: status_topic_profile,
             result_timeout: Duration::from_secs(15 * 60),
+            goal_acceptance: GoalAcceptancePolicy::default(),
+            feedback_rate_limit: None,
         }
     }
 }
@@ -91,6 +158,95 @@ pub(crate) struct ServerData {
     pub node: Arc<Node>,
     pub(crate) clock: Mutex<Clock>,
     pub(crate) pending_result_requests: Mutex<BTreeMap<[u8; 16], Vec<rmw_request_id_t>>>,
+    pub(crate) goals: Mutex<GoalQueue>,
+    pub(crate) feedback_rate_limit: Option<Duration>,
+    /// Feedback messages dropped by the rate limits, over all goals.
+    pub(crate) feedback_dropped: AtomicU64,
+}
+
+/// Active and queued goals, kept to enforce the [`GoalAcceptancePolicy`].
+pub(crate) struct GoalQueue {
+    policy: GoalAcceptancePolicy,
+    active: BTreeMap<[u8; 16], Arc<GoalHandleData>>,
+    queued: VecDeque<([u8; 16], Arc<GoalHandleData>)>,
+    /// Wakers of the goals waiting in [`GoalHandle::wait_until_executing`].
+    pub(crate) waiters: BTreeMap<[u8; 16], Waker>,
+}
+
+impl GoalQueue {
+    fn new(policy: GoalAcceptancePolicy) -> Self {
+        Self {
+            policy,
+            active: BTreeMap::new(),
+            queued: VecDeque::new(),
+            waiters: BTreeMap::new(),
+        }
+    }
+
+    /// Returns false if the policy rejects a new goal.
+    fn admits(&self) -> bool {
+        match self.policy {
+            GoalAcceptancePolicy::AcceptAll | GoalAcceptancePolicy::PreemptCurrent => true,
+            GoalAcceptancePolicy::RejectIfBusy => self.active.is_empty(),
+            GoalAcceptancePolicy::Queue { limit } => {
+                self.active.is_empty() || self.queued.len() < limit
+            }
+        }
+    }
+
+    /// Register an accepted goal, and move it to EXECUTING unless it has to wait in the queue.
+    fn insert(&mut self, goal_id: [u8; 16], handle: Arc<GoalHandleData>) -> Result<()> {
+        match self.policy {
+            GoalAcceptancePolicy::PreemptCurrent => {
+                for (id, active) in self.active.iter() {
+                    if active.status()? == GoalStatus::Executing {
+                        tracing::debug!(
+                            target: targets::ACTION,
+                            goal_id = ?id,
+                            "Preempting goal"
+                        );
+                        active.update_goal_state(GoalEvent::CancelGoal)?;
+                    }
+                }
+            }
+            GoalAcceptancePolicy::Queue { .. } if !self.active.is_empty() => {
+                self.queued.push_back((goal_id, handle));
+                return Ok(());
+            }
+            _ => (),
+        }
+        handle.update_goal_state(GoalEvent::Execute)?;
+        self.active.insert(goal_id, handle);
+        Ok(())
+    }
+
+    /// Remove a canceled goal from the queue, as it no longer waits for its turn.
+    fn cancel(&mut self, goal_id: &[u8; 16]) {
+        self.queued.retain(|(id, _)| id != goal_id);
+        self.wake(goal_id);
+    }
+
+    /// Forget a goal which reached a terminal state, and start the next queued goal if any.
+    pub(crate) fn terminate(&mut self, goal_id: &[u8; 16]) -> Result<()> {
+        self.active.remove(goal_id);
+        self.queued.retain(|(id, _)| id != goal_id);
+        self.waiters.remove(goal_id);
+
+        if self.active.is_empty()
+            && let Some((id, handle)) = self.queued.pop_front()
+        {
+            handle.update_goal_state(GoalEvent::Execute)?;
+            self.active.insert(id, handle);
+            self.wake(&id);
+        }
+        Ok(())
+    }
+
+    fn wake(&mut self, goal_id: &[u8; 16]) {
+        if let Some(waker) = self.waiters.remove(goal_id) {
+            waker.wake();
+        }
+    }
 }
 
 impl ServerData {
@@ -146,6 +302,11 @@ where
     /// Create a server.
     pub fn new(node: Arc<Node>, action_name: &str, qos: Option<ServerQosOption>) -> Result<Self> {
         let mut server = rcl::MTSafeFn::rcl_action_get_zero_initialized_server();
+        let policy = qos
+            .as_ref()
+            .map(|qos| qos.goal_acceptance)
+            .unwrap_or_default();
+        let feedback_rate_limit = qos.as_ref().and_then(|qos| qos.feedback_rate_limit);
         let options = qos
             .map(rcl::rcl_action_server_options_t::from)
             .unwrap_or_else(rcl::MTSafeFn::rcl_action_server_get_default_options);
@@ -158,7 +319,8 @@ where
                 &mut server,
                 unsafe { node.as_ptr_mut() },
                 unsafe { clock.as_ptr_mut() },
-                T::type_support() as *const rcl::rosidl_action_type_support_t,
+                T::try_type_support().map_err(Error::TypeSupport)?
+                    as *const rcl::rosidl_action_type_support_t,
                 action_name.as_ptr(),
                 &options,
             )?;
@@ -170,6 +332,9 @@ where
                 node,
                 clock: Mutex::new(clock),
                 pending_result_requests: Mutex::new(BTreeMap::new()),
+                goals: Mutex::new(GoalQueue::new(policy)),
+                feedback_rate_limit,
+                feedback_dropped: AtomicU64::new(0),
             }),
             results: Arc::new(Mutex::new(BTreeMap::new())),
             handles: Arc::new(Mutex::new(BTreeMap::new())),
@@ -178,6 +343,24 @@ where
         Ok(server)
     }
 
+    /// Get the fully qualified action name.
+    pub fn action_name(&self) -> Result<String> {
+        let guard = rcl::MT_UNSAFE_FN.lock();
+        guard.rcl_action_server_get_action_name(&self.data.server)
+    }
+
+    /// Get the fully qualified names of the services and topics of the action.
+    pub fn names(&self) -> Result<ActionNames> {
+        Ok(ActionNames::new(&self.action_name()?))
+    }
+
+    /// Number of feedback messages dropped by the rate limits, over all goals.
+    ///
+    /// See [`GoalHandle::feedback_throttled`].
+    pub fn feedback_dropped(&self) -> u64 {
+        self.data.feedback_dropped.load(Ordering::Relaxed)
+    }
+
     pub fn try_recv_goal_request(
         &mut self,
     ) -> Result<Option<(ServerGoalSend<T>, SendGoalServiceRequest<T>)>> {
@@ -281,6 +464,9 @@ pub struct ServerGoalSend<T: ActionMsg> {
 
 impl<T: ActionMsg> ServerGoalSend<T> {
     /// Accept the goal request.
+    ///
+    /// The goal is rejected instead, without calling `handler`, if the server's
+    /// [`GoalAcceptancePolicy`] does not admit it.
     pub fn accept<F>(self, handler: F) -> Result<()>
     where
         F: FnOnce(GoalHandle<T>),
@@ -289,7 +475,22 @@ impl<T: ActionMsg> ServerGoalSend<T> {
             let mut clock = self.server.data.clock.lock();
             get_timestamp(&mut clock)
         };
-        let handle = self.accept_goal(timestamp)?;
+        let handle = {
+            let mut goals = self.server.data.goals.lock();
+            if !goals.admits() {
+                drop(goals);
+                tracing::debug!(
+                    target: targets::ACTION,
+                    goal_id = ?self.goal_id,
+                    "Goal rejected by the acceptance policy"
+                );
+                return self.send(false, timestamp);
+            }
+            let handle = self.accept_goal(timestamp)?;
+            goals.insert(self.goal_id, handle.handle.clone())?;
+            handle
+        };
+        self.server.data.publish_goal_status()?;
         {
             let mut handles = self.server.handles.lock();
             handler(handle.clone());
@@ -337,9 +538,6 @@ impl<T: ActionMsg> ServerGoalSend<T> {
             self.server.results.clone(),
         );
 
-        handle.update(GoalEvent::Execute)?;
-        self.server.data.publish_goal_status()?;
-
         Ok(handle)
     }
 }
@@ -425,7 +623,14 @@ impl<T: ActionMsg> ServerCancelSend<T> {
     pub fn send(mut self, mut accepted_goals: Vec<GoalInfo>) -> Result<()> {
         let mut response = rcl::MTSafeFn::rcl_action_get_zero_initialized_cancel_response();
 
-        let code = self.cancel_goals(&accepted_goals)?;
+        let code = match self.cancel_goals(&accepted_goals) {
+            Ok(()) => CancelGoal_Response::ERROR_NONE,
+            Err(Error::Action(e)) => match e.cancel_code() {
+                Some(code) => code,
+                None => return Err(e.into()),
+            },
+            Err(e) => return Err(e),
+        };
         response.msg.return_code = code;
         if code == CancelGoal_Response::ERROR_NONE {
             response.msg.goals_canceling = action_msgs__msg__GoalInfo__Sequence {
@@ -453,31 +658,34 @@ impl<T: ActionMsg> ServerCancelSend<T> {
         Ok(())
     }
 
-    /// Cancel the goals. Returns the status code for the CancelGoal response.
-    fn cancel_goals(&mut self, goals: &[GoalInfo]) -> Result<i8> {
+    /// Cancel the goals. The errors reported by the CancelGoal response are
+    /// returned as [`ActionError::cancel_code`] errors.
+    fn cancel_goals(&mut self, goals: &[GoalInfo]) -> Result<()> {
         if goals.is_empty() {
-            return Ok(CancelGoal_Response::ERROR_REJECTED);
+            return Err(ActionError::CancelRejected.into());
         }
         let handles = self.server.handles.lock();
         // Make sure that all the goals are found in the handles beforehand
         for goal in goals {
             if !handles.contains_key(&goal.goal_id.uuid) {
-                return Ok(CancelGoal_Response::ERROR_UNKNOWN_GOAL_ID);
+                return Err(ActionError::UnknownGoalId.into());
             }
         }
         // Make sure all the goals are not in terminal state
         for goal in goals {
             let handle = handles.get(&goal.goal_id.uuid).unwrap();
             if handle.is_terminal()? {
-                return Ok(CancelGoal_Response::ERROR_GOAL_TERMINATED);
+                return Err(ActionError::GoalTerminated.into());
             }
         }
+        let mut queue = self.server.data.goals.lock();
         for goal in goals {
             let uuid = goal.goal_id.uuid;
             let handle = handles.get(&uuid).unwrap();
             handle.update(GoalEvent::CancelGoal)?;
+            queue.cancel(&uuid);
         }
-        Ok(CancelGoal_Response::ERROR_NONE)
+        Ok(())
     }
 }
 
@@ -23,14 +23,50 @@
 //!     }
 //! }
 //! ```
+//!
+//! # Message Pools
+//!
+//! Messages initialized by the C runtime, such as the strings and sequences
+//! filled by a subscriber, are allocated through
+//! `rcutils_get_default_allocator()`. A [`PoolAllocator`] hands out
+//! fixed-size blocks of a preallocated region without locking, so that a
+//! control loop taking or building messages at 1 kHz does not touch the heap.
+//!
+//! A pool is installed per node with [`NodeOptions::message_pool`], or per
+//! publisher and subscriber with `set_message_pool`. Requests that do not
+//! fit a block, or arrive when the pool is exhausted, fall back to
+//! [`ALLOCATOR`].
+//!
+//! ```ignore
+//! use oxidros_rcl::{allocator::PoolAllocator, node::NodeOptions};
+//!
+//! // 4096 blocks of 256 bytes
+//! let pool = PoolAllocator::new(256, 4096)?;
+//! let node = ctx.create_node_with_opt("control", None, NodeOptions::new().message_pool(pool))?;
+//!
+//! let subscriber = node.create_subscriber::<JointState>("joint_states", None)?;
+//! let publisher = node.create_publisher::<JointState>("joint_commands", None)?;
+//!
+//! loop {
+//!     // strings and sequences of `state` are taken from the pool
+//!     let state = subscriber.recv_blocking()?;
+//!     let command = publisher.with_message_pool(JointState::new).unwrap();
+//!     // ...
+//! }
+//! ```
+//!
+//! [`NodeOptions::message_pool`]: crate::node::NodeOptions::message_pool
 
+use crate::error::{Error, RclError, Result};
 use oxidros_core::helper::Contains;
 use std::{
     alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
     mem::size_of,
     os::raw::c_void,
-    ptr::{null_mut, write_volatile},
+    ptr::{NonNull, copy_nonoverlapping, null_mut, write_bytes, write_volatile},
     slice::{from_raw_parts, from_raw_parts_mut},
+    sync::atomic::{AtomicPtr, AtomicU32, AtomicU64, AtomicUsize, Ordering},
 };
 
 pub struct CustomAllocator {
@@ -102,11 +138,234 @@ unsafe impl GlobalAlloc for CustomAllocator {
     }
 }
 
+/// Alignment of the blocks of a [`PoolAllocator`].
+pub const POOL_ALIGNMENT: usize = 16;
+
+/// Maximum number of pools that can be created.
+pub const MAX_POOLS: usize = 16;
+
+static POOLS: [AtomicPtr<PoolAllocator>; MAX_POOLS] =
+    [const { AtomicPtr::new(null_mut()) }; MAX_POOLS];
+
+/// Marks a slot of [`POOLS`] taken by a pool being created.
+const RESERVED: *mut PoolAllocator = NonNull::dangling().as_ptr();
+
+thread_local! {
+    static CURRENT_POOL: Cell<Option<&'static PoolAllocator>> = const { Cell::new(None) };
+}
+
+/// Lock-free pool of fixed-size blocks.
+///
+/// The free blocks form a stack whose head is tagged with a counter, so that
+/// a block popped and pushed back concurrently is not mistaken for the old
+/// head (ABA problem). Pools live for the whole program and are shared as
+/// `&'static PoolAllocator`.
+pub struct PoolAllocator {
+    heap_start: usize,
+    block_size: usize,
+    block_count: usize,
+    /// `tag << 32 | (index + 1)` of the first free block, `0` if none.
+    head: AtomicU64,
+    /// `index + 1` of the free block after each free block, `0` if none.
+    next: Box<[AtomicU32]>,
+    available: AtomicUsize,
+}
+
+impl PoolAllocator {
+    /// Create a pool of `block_count` blocks of `block_size` bytes.
+    ///
+    /// The region is allocated and touched once, and the pool is never freed.
+    ///
+    /// # Errors
+    ///
+    /// - `Error::InvalidConfig` if `block_size` or `block_count` is `0`, or
+    ///   `block_count` exceeds `u32::MAX - 1`, or
+    /// - `RclError::BadAlloc` if the region cannot be allocated or
+    ///   [`MAX_POOLS`] pools already exist.
+    pub fn new(block_size: usize, block_count: usize) -> Result<&'static PoolAllocator> {
+        if block_size == 0 || block_count == 0 || block_count >= u32::MAX as usize {
+            return Err(Error::InvalidConfig(format!(
+                "invalid pool of {block_count} blocks of {block_size} bytes"
+            )));
+        }
+
+        let block_size = block_size.next_multiple_of(POOL_ALIGNMENT);
+        let size = block_size
+            .checked_mul(block_count)
+            .ok_or(Error::Rcl(RclError::BadAlloc))?;
+        let layout = Layout::from_size_align(size, POOL_ALIGNMENT)
+            .map_err(|_| Error::Rcl(RclError::BadAlloc))?;
+
+        // Take a slot before allocating, so that a pool which cannot be
+        // registered is not leaked.
+        let slot = POOLS
+            .iter()
+            .find(|slot| {
+                slot.compare_exchange(null_mut(), RESERVED, Ordering::AcqRel, Ordering::Acquire)
+                    .is_ok()
+            })
+            .ok_or(Error::Rcl(RclError::BadAlloc))?;
+
+        let heap_start = unsafe { System.alloc(layout) };
+        if heap_start.is_null() {
+            slot.store(null_mut(), Ordering::Release);
+            return Err(Error::Rcl(RclError::BadAlloc));
+        }
+
+        // Touch memory.
+        for i in (0..size).step_by(4096) {
+            unsafe { write_volatile(heap_start.add(i), 0) };
+        }
+
+        // Every block is free, in address order.
+        let next = (0..block_count)
+            .map(|i| AtomicU32::new(if i + 1 < block_count { i as u32 + 2 } else { 0 }))
+            .collect();
+        let pool: &'static PoolAllocator = Box::leak(Box::new(PoolAllocator {
+            heap_start: heap_start as usize,
+            block_size,
+            block_count,
+            head: AtomicU64::new(1),
+            next,
+            available: AtomicUsize::new(block_count),
+        }));
+
+        slot.store(pool as *const _ as *mut _, Ordering::Release);
+        Ok(pool)
+    }
+
+    /// Size of a block in bytes, rounded up to [`POOL_ALIGNMENT`].
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Number of blocks.
+    pub fn capacity(&self) -> usize {
+        self.block_count
+    }
+
+    /// Number of free blocks.
+    pub fn available(&self) -> usize {
+        self.available.load(Ordering::Relaxed)
+    }
+
+    /// Check whether `ptr` points into the region of the pool.
+    pub fn contains(&self, ptr: *const u8) -> bool {
+        let addr = ptr as usize;
+        (self.heap_start..self.heap_start + self.block_size * self.block_count).contains(&addr)
+    }
+
+    fn pop(&self) -> *mut u8 {
+        let mut head = self.head.load(Ordering::Acquire);
+        loop {
+            let index = head as u32;
+            if index == 0 {
+                return null_mut();
+            }
+            let next = self.next[index as usize - 1].load(Ordering::Relaxed);
+            let new = ((head >> 32).wrapping_add(1) << 32) | next as u64;
+            match self
+                .head
+                .compare_exchange_weak(head, new, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => {
+                    self.available.fetch_sub(1, Ordering::Relaxed);
+                    return (self.heap_start + (index as usize - 1) * self.block_size) as *mut u8;
+                }
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    fn push(&self, ptr: *mut u8) {
+        let index = (ptr as usize - self.heap_start) / self.block_size;
+        let mut head = self.head.load(Ordering::Acquire);
+        loop {
+            self.next[index].store(head as u32, Ordering::Relaxed);
+            let new = ((head >> 32).wrapping_add(1) << 32) | (index as u64 + 1);
+            match self
+                .head
+                .compare_exchange_weak(head, new, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => {
+                    self.available.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                Err(current) => head = current,
+            }
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for PoolAllocator {
+    /// Return a block, or null if `layout` does not fit a block or the pool is exhausted.
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() > self.block_size || layout.align() > POOL_ALIGNMENT {
+            return null_mut();
+        }
+        self.pop()
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+        self.push(ptr)
+    }
+}
+
+/// Run `f` with `pool` used for the messages initialized by the C runtime on this thread.
+///
+/// `None` uses [`ALLOCATOR`]. The previous pool is restored when `f` returns.
+pub fn with_message_pool<R>(pool: Option<&'static PoolAllocator>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<&'static PoolAllocator>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT_POOL.with(|current| current.set(self.0));
+        }
+    }
+
+    let _restore = Restore(CURRENT_POOL.with(|current| current.replace(pool)));
+    f()
+}
+
+/// The pool installed on this thread by [`with_message_pool`].
+pub fn current_message_pool() -> Option<&'static PoolAllocator> {
+    CURRENT_POOL.with(|current| current.get())
+}
+
+/// Find the pool which `ptr` was allocated from.
+fn owner_pool(ptr: *const u8) -> Option<&'static PoolAllocator> {
+    POOLS
+        .iter()
+        .map(|slot| slot.load(Ordering::Acquire))
+        .filter(|&pool| !pool.is_null() && pool != RESERVED)
+        .map(|pool| unsafe { &*pool })
+        .find(|pool| pool.contains(ptr))
+}
+
+/// Allocate `layout` from the pool passed as the `state` of the allocator, or from [`ALLOCATOR`].
+unsafe fn alloc_with_state(layout: Layout, state: *mut c_void) -> *mut u8 {
+    if !state.is_null() {
+        let pool = &*(state as *const PoolAllocator);
+        let result = pool.alloc(layout);
+        if !result.is_null() {
+            return result;
+        }
+    }
+    ALLOCATOR.alloc(layout)
+}
+
+/// Free a block allocated by [`alloc_with_state`], whichever thread allocated it.
+unsafe fn dealloc_any(ptr: *mut u8, layout: Layout) {
+    match owner_pool(ptr) {
+        Some(pool) => pool.dealloc(ptr, layout),
+        None => ALLOCATOR.dealloc(ptr, layout),
+    }
+}
+
 #[no_mangle]
-pub unsafe extern "C" fn allocate(size: usize, _state: *mut c_void) -> *mut c_void {
+pub unsafe extern "C" fn allocate(size: usize, state: *mut c_void) -> *mut c_void {
     let layout =
         Layout::from_size_align(size as usize + size_of::<usize>(), size_of::<usize>()).unwrap();
-    let result = ALLOCATOR.alloc(layout);
+    let result = alloc_with_state(layout, state);
 
     if result.is_null() {
         return null_mut();
@@ -133,7 +392,7 @@ pub unsafe extern "C" fn deallocate(pointer: *mut c_void, _state: *mut c_void) {
     let layout =
         Layout::from_size_align(size as usize + size_of::<usize>(), size_of::<usize>()).unwrap();
 
-    ALLOCATOR.dealloc(addr as _, layout);
+    dealloc_any(addr as _, layout);
 }
 
 #[no_mangle]
@@ -155,6 +414,16 @@ pub unsafe extern "C" fn reallocate(
         Layout::from_size_align(prev_size as usize + size_of::<usize>(), size_of::<usize>())
             .unwrap();
 
+    // Blocks of a pool cannot grow in place: move them.
+    if !state.is_null() || owner_pool(addr as _).is_some() {
+        let result = allocate(size, state);
+        if !result.is_null() {
+            copy_nonoverlapping(pointer as *const u8, result as *mut u8, prev_size.min(size));
+            deallocate(pointer, state);
+        }
+        return result;
+    }
+
     let result = ALLOCATOR.realloc(addr as _, layout, size as usize + size_of::<usize>());
 
     if result.is_null() {
@@ -172,12 +441,20 @@ pub unsafe extern "C" fn reallocate(
 pub unsafe extern "C" fn zero_allocate(
     number_of_elements: usize,
     size_of_element: usize,
-    _state: *mut c_void,
+    state: *mut c_void,
 ) -> *mut c_void {
     let size = (number_of_elements * size_of_element) as usize;
 
     let layout = Layout::from_size_align(size + size_of::<usize>(), size_of::<usize>()).unwrap();
-    let result = ALLOCATOR.alloc_zeroed(layout);
+    let result = if state.is_null() {
+        ALLOCATOR.alloc_zeroed(layout)
+    } else {
+        let result = alloc_with_state(layout, state);
+        if !result.is_null() {
+            write_bytes(result, 0, layout.size());
+        }
+        result
+    };
 
     if result.is_null() {
         return null_mut();
@@ -1,27 +1,86 @@
-use std::time::Duration;
+use std::{collections::BTreeMap, ffi::c_void, panic::AssertUnwindSafe, sync::Arc, time::Duration};
+
+use oxidros_core::{
+    targets,
+    time::{ClockType, JumpHandle, JumpPostCallback, JumpPreCallback, JumpThreshold, TimeJump},
+};
+use parking_lot::Mutex;
 
 use crate::{error::Result, get_allocator, rcl};
 
-/// A clock. For now only SystemTime/ROSTime is implemented.
-#[derive(Debug)]
+/// A clock backed by `rcl_clock_t`.
+///
+/// [`Clock::new`] creates a ROS time clock, which follows the simulated time
+/// once overridden. [`Clock::with_type`] creates a system or steady clock.
+/// The clock of a node is shared by [`Node::get_clock`](crate::node::Node::get_clock)
+/// and drives the timers added by
+/// [`Selector::add_timer_with_clock`](crate::selector::Selector::add_timer_with_clock).
+///
+/// # Time jumps
+///
+/// When ROS time is overridden (e.g., by sim time), the time can jump
+/// forward or backward. Register callbacks with
+/// [`Clock::create_jump_callback`] to invalidate time dependent state such
+/// as timers or TF buffers.
+///
+/// ```ignore
+/// use oxidros_core::time::JumpThreshold;
+/// use std::time::Duration;
+///
+/// let mut clock = Clock::new()?;
+/// let handle = clock.create_jump_callback(
+///     JumpThreshold {
+///         on_clock_change: true,
+///         min_forward: None,
+///         min_backward: Some(Duration::from_nanos(1)),
+///     },
+///     None,
+///     Some(Box::new(|jump| println!("time jumped by {} ns", jump.delta_nanos))),
+/// )?;
+/// ```
 pub struct Clock {
     pub(crate) clock: *mut rcl::rcl_clock_t,
+    clock_type: ClockType,
+    jump_callbacks: BTreeMap<u64, Box<Mutex<JumpCallbacks>>>,
+    next_jump_id: u64,
+    /// Held while rcl reads or changes the jump callbacks of the clock, see
+    /// [`change_unlocked`].
+    jump_lock: Arc<Mutex<()>>,
+}
+
+struct JumpCallbacks {
+    pre: Option<JumpPreCallback>,
+    post: Option<JumpPostCallback>,
 }
 
 impl Clock {
-    /// Create a clock.
+    /// Create a ROS time clock.
     pub fn new() -> Result<Self> {
+        Self::with_type(ClockType::Ros)
+    }
+
+    /// Create a clock of the given type.
+    pub fn with_type(clock_type: ClockType) -> Result<Self> {
         let mut clock = unsafe { std::mem::zeroed() };
 
         let guard = rcl::MT_UNSAFE_FN.lock();
-        guard.rcl_ros_clock_init(&mut clock, &mut get_allocator())?;
+        guard.rcl_clock_init(clock_type.into(), &mut clock, &mut get_allocator())?;
 
         let b = Box::new(clock);
         Ok(Self {
             clock: Box::into_raw(b),
+            clock_type,
+            jump_callbacks: BTreeMap::new(),
+            next_jump_id: 0,
+            jump_lock: Arc::new(Mutex::new(())),
         })
     }
 
+    /// Get the type of the clock.
+    pub fn clock_type(&self) -> ClockType {
+        self.clock_type
+    }
+
     pub(crate) unsafe fn as_ptr_mut(&self) -> *mut rcl::rcl_clock_t {
         self.clock
     }
@@ -31,12 +90,134 @@ impl Clock {
         rcl::MTSafeFn::rcl_clock_get_now(self.clock, &mut now)?;
         Ok(Duration::from_nanos(now as u64))
     }
+
+    /// Register callbacks invoked before and after a time jump that exceeds
+    /// `threshold`.
+    ///
+    /// The callbacks run on the thread that changes the time, e.g. the one
+    /// calling [`Clock::set_ros_time_override`]. They may read the clock of a
+    /// node when the simulated time changes it, but must not add or remove
+    /// jump callbacks.
+    pub fn create_jump_callback(
+        &mut self,
+        threshold: JumpThreshold,
+        pre_cb: Option<JumpPreCallback>,
+        post_cb: Option<JumpPostCallback>,
+    ) -> Result<JumpHandle> {
+        let callbacks = Box::new(Mutex::new(JumpCallbacks {
+            pre: pre_cb,
+            post: post_cb,
+        }));
+        let user_data = &*callbacks as *const Mutex<JumpCallbacks> as *mut c_void;
+
+        let _jump_lock = self.jump_lock.lock();
+        let guard = rcl::MT_UNSAFE_FN.lock();
+        guard.rcl_clock_add_jump_callback(
+            self.clock,
+            (&threshold).into(),
+            Some(on_time_jump),
+            user_data,
+        )?;
+
+        let id = self.next_jump_id;
+        self.next_jump_id += 1;
+        self.jump_callbacks.insert(id, callbacks);
+        Ok(JumpHandle::from_raw(id))
+    }
+
+    /// Unregister callbacks added by [`Clock::create_jump_callback`].
+    pub fn remove_jump_callback(&mut self, handle: JumpHandle) -> Result<()> {
+        let Some(callbacks) = self.jump_callbacks.get(&handle.as_raw()) else {
+            return Err(oxidros_core::RclError::InvalidArgument.into());
+        };
+        let user_data = &**callbacks as *const Mutex<JumpCallbacks> as *mut c_void;
+
+        let _jump_lock = self.jump_lock.lock();
+        let guard = rcl::MT_UNSAFE_FN.lock();
+        guard.rcl_clock_remove_jump_callback(self.clock, Some(on_time_jump), user_data)?;
+        drop(guard);
+
+        self.jump_callbacks.remove(&handle.as_raw());
+        Ok(())
+    }
+
+    /// Use the time set by [`Clock::set_ros_time_override`] instead of the
+    /// system time.
+    pub fn enable_ros_time_override(&mut self) -> Result<()> {
+        rcl::MTSafeFn::rcl_enable_ros_time_override(self.clock)
+    }
+
+    /// Go back to the system time.
+    pub fn disable_ros_time_override(&mut self) -> Result<()> {
+        rcl::MTSafeFn::rcl_disable_ros_time_override(self.clock)
+    }
+
+    /// Set the ROS time, e.g. from a `/clock` message.
+    pub fn set_ros_time_override(&mut self, time: Duration) -> Result<()> {
+        rcl::MTSafeFn::rcl_set_ros_time_override(self.clock, time.as_nanos() as i64)
+    }
+}
+
+/// Change the time of a shared clock with `change`, without holding its lock.
+///
+/// rcl invokes the jump callbacks while changing the time, so holding the lock
+/// of the clock would deadlock callbacks reading it, e.g. through
+/// [`Node::get_clock`](crate::node::Node::get_clock).
+pub(crate) fn change_unlocked(
+    clock: &Mutex<Clock>,
+    change: impl FnOnce(*mut rcl::rcl_clock_t) -> Result<()>,
+) -> Result<()> {
+    let (raw, jump_lock) = {
+        let clock = clock.lock();
+        (clock.clock, clock.jump_lock.clone())
+    };
+    // The rcl clock lives as long as `clock`, and its jump callbacks are not
+    // changed while `jump_lock` is held
+    let _jump_lock = jump_lock.lock();
+    change(raw)
+}
+
+unsafe extern "C" fn on_time_jump(
+    time_jump: *const rcl::rcl_time_jump_t,
+    before_jump: bool,
+    user_data: *mut c_void,
+) {
+    let callbacks = unsafe { &*(user_data as *const Mutex<JumpCallbacks>) };
+    let mut callbacks = callbacks.lock();
+
+    // Unwinding into rcl is undefined behavior
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        if before_jump {
+            if let Some(pre) = callbacks.pre.as_mut() {
+                pre();
+            }
+        } else if let (Some(post), Some(jump)) =
+            (callbacks.post.as_mut(), unsafe { time_jump.as_ref() })
+        {
+            post(&TimeJump::from(jump));
+        }
+    }));
+
+    if result.is_err() {
+        tracing::error!(target: targets::CLOCK, "a time jump callback panicked");
+    }
+}
+
+impl std::fmt::Debug for Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Clock")
+            .field("clock", &self.clock)
+            .field("clock_type", &self.clock_type)
+            .field("jump_callbacks", &self.jump_callbacks.len())
+            .finish()
+    }
 }
 
 impl Drop for Clock {
     fn drop(&mut self) {
+        // The jump callbacks are freed after the clock is finalized
         let guard = rcl::MT_UNSAFE_FN.lock();
-        let _ = guard.rcl_ros_clock_fini(self.clock);
+        let _ = guard.rcl_clock_fini(self.clock);
         let _ = unsafe { Box::from_raw(self.clock) };
     }
 }
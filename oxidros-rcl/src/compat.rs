@@ -0,0 +1,120 @@
+//! Differences between the RCL APIs of the supported ROS2 distributions.
+//!
+//! The build script emits one `ros_distro_*` cfg for the detected distro.
+//! Instead of testing distro names, code outside the FFI layer should test
+//! the capabilities of [`rcl_features`], which are evaluated at compile time
+//! but can be queried at runtime:
+//!
+//! ```
+//! use oxidros_rcl::compat::rcl_features;
+//!
+//! if rcl_features::has_type_description_service() {
+//!     // ~/get_type_description is served by each node
+//! }
+//! ```
+//!
+//! | Capability | Humble | Jazzy | Kilted | Lyrical |
+//! |------------|--------|-------|--------|---------|
+//! | [`has_type_description_service`](rcl_features::has_type_description_service) | | ✓ | ✓ | ✓ |
+//! | [`has_service_introspection`](rcl_features::has_service_introspection) | | ✓ | | ✓ |
+//! | [`has_best_available_qos`](rcl_features::has_best_available_qos) | | ✓ | ✓ | ✓ |
+//! | [`has_disable_loaned_message`](rcl_features::has_disable_loaned_message) | | ✓ | ✓ | ✓ |
+//! | [`has_timer_autostart`](rcl_features::has_timer_autostart) | | ✓ | ✓ | ✓ |
+//! | [`has_manual_by_node_liveliness`](rcl_features::has_manual_by_node_liveliness) | ✓ | ✓ | ✓ | |
+//!
+//! The crate-internal shims of this module convert the FFI types whose
+//! layout differs between distros.
+
+use crate::rcl;
+
+/// Capabilities of the RCL API oxidros was built against.
+pub mod rcl_features {
+    /// Name of the ROS2 distro oxidros was built against, if it was detected.
+    pub const fn distro() -> Option<&'static str> {
+        if cfg!(ros_distro_humble) {
+            Some("humble")
+        } else if cfg!(ros_distro_jazzy) {
+            Some("jazzy")
+        } else if cfg!(ros_distro_kilted) {
+            Some("kilted")
+        } else if cfg!(ros_distro_lyrical) {
+            Some("lyrical")
+        } else {
+            None
+        }
+    }
+
+    /// Nodes serve `~/get_type_description` (REP 2011).
+    pub const fn has_type_description_service() -> bool {
+        cfg!(any(ros_distro_jazzy, ros_distro_kilted, ros_distro_lyrical))
+    }
+
+    /// Servers can publish their requests and responses on
+    /// `<service>/_service_event`.
+    pub const fn has_service_introspection() -> bool {
+        cfg!(any(ros_distro_jazzy, ros_distro_lyrical))
+    }
+
+    /// The `BestAvailable` QoS policies are supported by the middleware.
+    ///
+    /// On older distros, they are converted to `Unknown`.
+    pub const fn has_best_available_qos() -> bool {
+        cfg!(any(ros_distro_jazzy, ros_distro_kilted, ros_distro_lyrical))
+    }
+
+    /// Loaned messages can be disabled per publisher and subscriber.
+    pub const fn has_disable_loaned_message() -> bool {
+        cfg!(any(ros_distro_jazzy, ros_distro_kilted, ros_distro_lyrical))
+    }
+
+    /// Timers can be created without starting them.
+    pub const fn has_timer_autostart() -> bool {
+        !cfg!(ros_distro_humble)
+    }
+
+    /// The deprecated `MANUAL_BY_NODE` liveliness policy still exists.
+    pub const fn has_manual_by_node_liveliness() -> bool {
+        !cfg!(ros_distro_lyrical)
+    }
+}
+
+/// GID of the publisher of a message, whose `data` has 24 bytes on Humble
+/// and 16 bytes since Jazzy.
+pub(crate) fn gid_bytes(gid: &rcl::rmw_gid_t) -> [u8; 16] {
+    let mut bytes = [0; 16];
+    for (o, i) in bytes.iter_mut().zip(gid.data) {
+        *o = i;
+    }
+    bytes
+}
+
+/// GUID of the writer of a request, an `int8_t` array on Humble and a
+/// `uint8_t` array since Jazzy.
+#[cfg(ros_distro_humble)]
+pub(crate) fn writer_guid_bytes(guid: &[i8; 16]) -> [u8; 16] {
+    guid.map(|b| b as u8)
+}
+
+/// GUID of the writer of a request, an `int8_t` array on Humble and a
+/// `uint8_t` array since Jazzy.
+#[cfg(not(ros_distro_humble))]
+pub(crate) fn writer_guid_bytes(guid: &[u8; 16]) -> [u8; 16] {
+    *guid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rcl_features;
+
+    #[test]
+    fn test_features() {
+        assert!(rcl_features::has_timer_autostart() || rcl_features::distro() == Some("humble"));
+        if rcl_features::has_service_introspection() {
+            assert!(rcl_features::has_type_description_service());
+        }
+        if rcl_features::has_type_description_service() {
+            assert!(rcl_features::has_best_available_qos());
+            assert!(rcl_features::has_disable_loaned_message());
+        }
+    }
+}
@@ -22,13 +22,19 @@ use crate::{
     error::*,
     get_allocator,
     node::{Node, NodeOptions},
+    qos::{Profile, QosCompatibility},
     rcl,
     selector::{Selector, async_selector},
     signal_handler,
 };
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
-use std::{env, ffi::CString, sync::Arc};
+use ros2args::RosEnv;
+use std::{
+    env,
+    ffi::{CStr, CString},
+    sync::Arc,
+};
 
 static CONTEXT: Lazy<Mutex<Option<Arc<Context>>>> = Lazy::new(|| Mutex::new(None));
 
@@ -43,11 +49,16 @@ static CARGS: Lazy<Vec<usize>> = Lazy::new(|| {
 /// Context of ROS2.
 pub struct Context {
     context: rcl::rcl_context_t,
+    ros_env: RosEnv,
+    domain_id: u32,
 }
 
 impl Context {
     /// Create a new context.
     ///
+    /// `__node:=`/`__ns:=` remapping rules, `ROS_DOMAIN_ID` and `ROS_LOCALHOST_ONLY`
+    /// are applied by RCL. Nodes created without a namespace use `ROS_NAMESPACE`.
+    ///
     /// # Example
     ///
     /// ```
@@ -66,14 +77,50 @@ impl Context {
             }
         }
 
+        let context = Arc::new(Self::init(None)?);
+        {
+            let mut guard = CONTEXT.lock();
+            *guard = Some(context.clone());
+        }
+
+        Ok(context)
+    }
+
+    /// Create a new context on the domain `domain_id`, ignoring `ROS_DOMAIN_ID`.
+    ///
+    /// Unlike [`Context::new`], which always returns the context shared by the
+    /// process, every call creates a separate context, so that nodes of
+    /// several domains can live in one process.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oxidros_rcl::context::Context;
+    ///
+    /// let ctx = Context::with_domain_id(42).unwrap();
+    /// assert_eq!(ctx.domain_id(), 42);
+    /// ```
+    pub fn with_domain_id(domain_id: u32) -> Result<Arc<Self>> {
+        signal_handler::init();
+        Ok(Arc::new(Self::init(Some(domain_id))?))
+    }
+
+    fn init(domain_id: Option<u32>) -> Result<Self> {
+        let ros_env = RosEnv::from_env().map_err(|e| Error::InvalidConfig(e.to_string()))?;
+
         // allocate context
         let mut context = rcl::MTSafeFn::rcl_get_zero_initialized_context();
 
-        let options = InitOptions::new()?;
+        let mut options = InitOptions::new()?;
+        let mut actual_domain_id = 0;
 
         {
             let guard = rcl::MT_UNSAFE_FN.lock();
 
+            if let Some(domain_id) = domain_id {
+                guard.rcl_init_options_set_domain_id(options.as_ptr_mut(), domain_id as usize)?;
+            }
+
             // initialize context
             guard.rcl_init(
                 CARGS.len() as i32,
@@ -84,15 +131,15 @@ impl Context {
 
             guard.rcl_logging_configure(&context.global_arguments, &crate::get_allocator())?;
             guard.rcl_logging_fini()?;
-        }
 
-        let context = Arc::new(Context { context });
-        {
-            let mut guard = CONTEXT.lock();
-            *guard = Some(context.clone());
+            guard.rcl_context_get_domain_id(&mut context, &mut actual_domain_id)?;
         }
 
-        Ok(context)
+        Ok(Context {
+            context,
+            ros_env,
+            domain_id: actual_domain_id as u32,
+        })
     }
 
     /// Create a new node of ROS2.
@@ -187,6 +234,85 @@ impl Context {
         Selector::new(self.clone())
     }
 
+    /// ROS2 environment variables read when the context was created.
+    pub fn ros_env(&self) -> &RosEnv {
+        &self.ros_env
+    }
+
+    /// Get the ROS domain ID of the context.
+    pub fn domain_id(&self) -> u32 {
+        self.domain_id
+    }
+
+    /// Get the identifier of the RMW implementation, e.g. `rmw_fastrtps_cpp`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oxidros_rcl::context::Context;
+    ///
+    /// let ctx = Context::new().unwrap();
+    /// assert!(ctx.rmw_implementation().starts_with("rmw_"));
+    /// ```
+    pub fn rmw_implementation(&self) -> &'static str {
+        let identifier = rcl::MTSafeFn::rmw_get_implementation_identifier();
+        if identifier.is_null() {
+            return "";
+        }
+        unsafe { CStr::from_ptr(identifier) }
+            .to_str()
+            .unwrap_or_default()
+    }
+
+    /// Check whether a publisher and a subscription with the given QoS
+    /// profiles match, as reported by the RMW implementation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oxidros_rcl::{context::Context, qos::{Profile, ReliabilityPolicy}};
+    ///
+    /// let ctx = Context::new().unwrap();
+    /// let publisher = Profile {
+    ///     reliability: ReliabilityPolicy::BestEffort,
+    ///     ..Default::default()
+    /// };
+    /// let compatibility = ctx
+    ///     .rmw_qos_compatibility_check(&publisher, &Profile::default())
+    ///     .unwrap();
+    /// assert!(!compatibility.is_compatible());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - `RCLError::Error` if the RMW implementation failed to check the profiles.
+    pub fn rmw_qos_compatibility_check(
+        &self,
+        publisher: &Profile,
+        subscription: &Profile,
+    ) -> Result<QosCompatibility> {
+        let mut compatibility = rcl::rmw_qos_compatibility_type_t::RMW_QOS_COMPATIBILITY_OK;
+        let mut reason = [0 as std::os::raw::c_char; 2048];
+        rcl::MTSafeFn::rmw_qos_profile_check_compatible(
+            publisher.into(),
+            subscription.into(),
+            &mut compatibility,
+            reason.as_mut_ptr(),
+            reason.len(),
+        )?;
+
+        let reason = unsafe { CStr::from_ptr(reason.as_ptr()) }
+            .to_string_lossy()
+            .into_owned();
+        Ok(match compatibility {
+            rcl::rmw_qos_compatibility_type_t::RMW_QOS_COMPATIBILITY_OK => QosCompatibility::Ok,
+            rcl::rmw_qos_compatibility_type_t::RMW_QOS_COMPATIBILITY_WARNING => {
+                QosCompatibility::Warning(reason)
+            }
+            _ => QosCompatibility::Error(reason),
+        })
+    }
+
     pub(crate) fn as_ptr(&self) -> *const rcl::rcl_context_t {
         &self.context as *const _
     }
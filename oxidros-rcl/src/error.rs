@@ -1,8 +1,13 @@
 //! Errors returned by ROS2.
 
 pub use crate::rcl::RclRetErr;
-use crate::rcl::{self, rcutils_error_string_t};
-pub use oxidros_core::error::{ActionError, Error, RclError, Result};
+use crate::{
+    node::Node,
+    rcl::{self, rcutils_error_string_t},
+};
+pub use oxidros_core::error::{
+    ActionError, Error, ErrorContext, Operation, RclError, Result, ResultExt,
+};
 
 /// Convert a rcl-style, C-style, return value to a Rust-style value.
 /// If `n` indicates successful, this returns Ok(()),
@@ -35,3 +40,25 @@ pub(crate) fn rcutils_error_string_to_err(v: rcutils_error_string_t) -> Error {
     let err = unsafe { std::ffi::CStr::from_ptr(v.str_.as_ptr()) };
     Error::Other(err.to_string_lossy().to_string())
 }
+
+/// Context of `operation` on the topic or service `entity` of `node`.
+pub(crate) fn entity_context(node: &Node, operation: Operation, entity: &str) -> ErrorContext {
+    let context = ErrorContext::new(operation, entity);
+    match node.fully_qualified_name() {
+        Ok(node) => context.with_node(node),
+        Err(_) => context,
+    }
+}
+
+/// Convert a name validation error of `ros2args` to an `Error::InvalidName`.
+pub(crate) fn name_err_to_err(e: ros2args::Ros2ArgsError) -> Error {
+    match e {
+        ros2args::Ros2ArgsError::InvalidName {
+            name,
+            position,
+            reason,
+            ..
+        } => Error::invalid_name(name, position, reason),
+        e => Error::Other(e.to_string()),
+    }
+}
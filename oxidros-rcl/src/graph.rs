@@ -0,0 +1,315 @@
+//! Graph of ROS2 as seen by a node.
+//! A graph can be obtained by `Node::graph`.
+//!
+//! The graph reports the nodes, publishers and subscribers discovered by RCL,
+//! and lets tasks wait for changes through the graph guard condition of the
+//! node instead of polling.
+//!
+//! # Example
+//!
+//! ```
+//! use oxidros_rcl::{context::Context, error::Result, graph::GraphEvent};
+//! use futures_util::StreamExt;
+//! use std::time::Duration;
+//!
+//! async fn wait_for_talker() -> Result<()> {
+//!     let ctx = Context::new()?;
+//!     let node = ctx.create_node("graph_rs", None)?;
+//!     let graph = node.graph();
+//!
+//!     // Wait until `/talker` joins the graph.
+//!     graph.wait_for_node("/talker", Duration::from_secs(5)).await?;
+//!
+//!     // Watch the graph.
+//!     let mut events = graph.events()?;
+//!     while let Some(event) = events.next().await {
+//!         if let GraphEvent::NodeRemoved(name) = event? {
+//!             println!("{name} left");
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{
+    error::{Error, Result},
+    node::Node,
+    rcl,
+    selector::async_selector::{self, Command},
+    signal_handler::{Signaled, is_halt},
+};
+use futures_util::Stream;
+use oxidros_core::selector::CallbackResult;
+use parking_lot::Mutex;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ffi::CString,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{self, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+pub use oxidros_core::graph::{GraphEvent, GraphSnapshot};
+
+/// Tasks waiting for a change of the graph of a node.
+#[derive(Default)]
+pub(crate) struct GraphWaiters {
+    next_id: u64,
+    wakers: BTreeMap<u64, Waker>,
+    /// Keys of the timeout timers, by waiter.
+    timers: BTreeMap<u64, u64>,
+}
+
+impl GraphWaiters {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.wakers.is_empty()
+    }
+}
+
+fn wake_all(waiters: &Mutex<GraphWaiters>) {
+    let wakers = std::mem::take(&mut waiters.lock().wakers);
+    for waker in wakers.into_values() {
+        waker.wake();
+    }
+}
+
+/// Graph of ROS2 as seen by a node.
+#[derive(Clone)]
+pub struct Graph {
+    node: Arc<Node>,
+}
+
+impl Graph {
+    pub(crate) fn new(node: Arc<Node>) -> Self {
+        Graph { node }
+    }
+
+    /// Get the fully qualified names of the nodes in the graph.
+    pub fn node_names(&self) -> Result<Vec<String>> {
+        let guard = rcl::MT_UNSAFE_FN.lock();
+        guard.rcl_get_node_names(self.node.as_ptr())
+    }
+
+    /// Take a snapshot of the nodes, publishers and subscribers in the graph.
+    pub fn snapshot(&self) -> Result<GraphSnapshot> {
+        let guard = rcl::MT_UNSAFE_FN.lock();
+        let node = self.node.as_ptr();
+
+        let mut snapshot = GraphSnapshot {
+            nodes: guard.rcl_get_node_names(node)?.into_iter().collect(),
+            ..Default::default()
+        };
+        for topic in guard.rcl_get_topic_names(node)? {
+            let topic_c = CString::new(topic.as_str()).unwrap_or_default();
+            let publishers = guard.rcl_count_publishers(node, &topic_c)?;
+            let subscribers = guard.rcl_count_subscribers(node, &topic_c)?;
+            if publishers > 0 {
+                snapshot.publishers.insert(topic.clone(), publishers);
+            }
+            if subscribers > 0 {
+                snapshot.subscribers.insert(topic, subscribers);
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Wait until the graph changes or `timeout` elapses.
+    ///
+    /// Spurious wakeups are possible, so the graph must be checked again
+    /// after this returns.
+    pub async fn wait_for_change(&self, timeout: Option<Duration>) -> Result<()> {
+        GraphChange {
+            node: &self.node,
+            timeout,
+            id: None,
+        }
+        .await
+    }
+
+    /// Wait until a node with the fully qualified name `name` is in the graph.
+    ///
+    /// A name without a leading `/` is looked up in the root namespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if the node does not appear within `timeout`.
+    pub async fn wait_for_node(&self, name: &str, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.snapshot()?.contains_node(name) {
+                return Ok(());
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::Timeout);
+            }
+            self.wait_for_change(Some(deadline - now)).await?;
+        }
+    }
+
+    /// Stream of the changes of the graph from now on.
+    pub fn events(&self) -> Result<GraphEvents> {
+        Ok(GraphEvents {
+            node: self.node.clone(),
+            last: self.snapshot()?,
+            pending: VecDeque::new(),
+            id: None,
+        })
+    }
+}
+
+/// Register `waker` to be woken on the next change of the graph of `node`.
+fn register(node: &Arc<Node>, waker: &Waker, timeout: Option<Duration>) -> Result<u64> {
+    let id = {
+        let mut waiters = node.graph_waiters.lock();
+        let id = waiters.next_id;
+        waiters.next_id += 1;
+        waiters.wakers.insert(id, waker.clone());
+        id
+    };
+
+    let waiters = node.graph_waiters.clone();
+    async_selector::send_command(
+        &node.context,
+        Command::Graph(
+            node.clone(),
+            Box::new(move || {
+                wake_all(&waiters);
+                CallbackResult::Ok
+            }),
+        ),
+    )?;
+
+    if let Some(timeout) = timeout {
+        let key = async_selector::new_timer_key();
+        node.graph_waiters.lock().timers.insert(id, key);
+        let waiters = node.graph_waiters.clone();
+        async_selector::send_command(
+            &node.context,
+            Command::Timer(key, timeout, Box::new(move || wake_all(&waiters))),
+        )?;
+    }
+
+    Ok(id)
+}
+
+/// Remove a waiter, and stop watching the graph if no task waits for it.
+fn unregister(node: &Arc<Node>, id: u64) {
+    let timer = {
+        let mut waiters = node.graph_waiters.lock();
+        waiters.wakers.remove(&id);
+        waiters.timers.remove(&id)
+    };
+    if let Some(key) = timer {
+        let _ = async_selector::send_command(&node.context, Command::RemoveTimer(key));
+    }
+    let _ = async_selector::send_command(&node.context, Command::RemoveGraph(node.clone()));
+}
+
+/// Check if a registered waiter has been woken, or refresh its waker.
+fn is_woken(node: &Node, id: u64, waker: &Waker) -> bool {
+    match node.graph_waiters.lock().wakers.get_mut(&id) {
+        Some(w) => {
+            w.clone_from(waker);
+            false
+        }
+        None => true,
+    }
+}
+
+/// Future returned by `Graph::wait_for_change`.
+struct GraphChange<'a> {
+    node: &'a Arc<Node>,
+    timeout: Option<Duration>,
+    id: Option<u64>,
+}
+
+impl Future for GraphChange<'_> {
+    type Output = Result<()>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        if is_halt() {
+            return Poll::Ready(Err(Signaled.into()));
+        }
+
+        match self.id {
+            Some(id) if is_woken(self.node, id, cx.waker()) => {
+                // woken by a change or by the timeout
+                self.id = None;
+                unregister(self.node, id);
+                Poll::Ready(Ok(()))
+            }
+            Some(_) => Poll::Pending,
+            None => {
+                self.id = Some(register(self.node, cx.waker(), self.timeout)?);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for GraphChange<'_> {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            unregister(self.node, id);
+        }
+    }
+}
+
+/// Stream of graph changes returned by `Graph::events`.
+///
+/// Changes are found by comparing snapshots of the graph, so an entity that
+/// appears and disappears between two wakeups is not reported.
+pub struct GraphEvents {
+    node: Arc<Node>,
+    last: GraphSnapshot,
+    pending: VecDeque<GraphEvent>,
+    id: Option<u64>,
+}
+
+impl Stream for GraphEvents {
+    type Item = Result<GraphEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        if is_halt() {
+            return Poll::Ready(None);
+        }
+
+        let this = &mut *self;
+        loop {
+            if let Some(event) = this.pending.pop_front() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+
+            match this.id {
+                Some(id) if !is_woken(&this.node, id, cx.waker()) => return Poll::Pending,
+                Some(_) => this.id = None,
+                None => (),
+            }
+
+            let snapshot = match Graph::new(this.node.clone()).snapshot() {
+                Ok(snapshot) => snapshot,
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            };
+            this.pending.extend(this.last.diff(&snapshot));
+            this.last = snapshot;
+
+            if this.pending.is_empty() {
+                match register(&this.node, cx.waker(), None) {
+                    Ok(id) => this.id = Some(id),
+                    Err(e) => return Poll::Ready(Some(Err(e))),
+                }
+            }
+        }
+    }
+}
+
+impl Drop for GraphEvents {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            unregister(&self.node, id);
+        }
+    }
+}
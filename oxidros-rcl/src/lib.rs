@@ -220,8 +220,10 @@ pub mod allocator;
 
 pub mod action;
 pub mod clock;
+pub mod compat;
 pub mod context;
 pub mod error;
+pub mod graph;
 pub mod helper;
 pub mod logger;
 pub mod msg;
@@ -231,17 +233,19 @@ pub(crate) mod rcl;
 pub mod selector;
 pub mod service;
 pub mod topic;
+pub mod type_description;
 use oxidros_core::Message;
 pub use oxidros_core::qos;
 
 mod signal_handler;
+mod time_source;
 
 type PhantomUnsync = PhantomData<Cell<()>>;
 type PhantomUnsend = PhantomData<MutexGuard<'static, ()>>;
 
 use msg::ServiceMsg;
 use service::client::ClientRecv;
-pub use signal_handler::is_halt;
+pub use signal_handler::{is_halt, shutdown_token};
 
 // Re-export oxidros_core so external crates can access traits without direct dependency
 pub use oxidros_core;
@@ -305,12 +309,16 @@ type RcutilsAllocator = rcl::rcutils_allocator_s;
 pub(crate) fn get_allocator() -> RcutilsAllocator {
     use std::ptr::null_mut;
 
+    // the pool installed by `allocator::with_message_pool`, if any
+    let state =
+        allocator::current_message_pool().map_or(null_mut(), |pool| pool as *const _ as *mut _);
+
     RcutilsAllocator {
         allocate: Some(allocator::allocate),
         deallocate: Some(allocator::deallocate),
         reallocate: Some(allocator::reallocate),
         zero_allocate: Some(allocator::zero_allocate),
-        state: null_mut(),
+        state,
     }
 }
 
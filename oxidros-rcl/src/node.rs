@@ -18,16 +18,25 @@
 use libc::atexit;
 
 use crate::{
+    clock::Clock,
     context::{Context, remove_context},
-    error::Result,
+    error::{Result, name_err_to_err},
+    graph::{Graph, GraphWaiters},
     msg::{ServiceMsg, TypeSupport},
-    parameter::ParameterServer,
-    qos, rcl,
+    parameter::{ParameterServer, Value},
+    qos::{self, QosEntityKind, QosOverridingOptions},
+    rcl,
     service::{client::Client, server::Server},
     topic::publisher::Publisher,
     topic::subscriber::Subscriber,
+    type_description::TypeDescriptionServer,
 };
-use std::{ffi::CString, sync::Arc};
+use oxidros_core::stats::{EntityCounters, NodeStats};
+use parking_lot::Mutex;
+use std::{collections::BTreeMap, ffi::CString, sync::Arc};
+
+#[cfg(feature = "custom_alloc")]
+use crate::allocator::PoolAllocator;
 
 static SET_ATEXIT: std::sync::OnceLock<()> = std::sync::OnceLock::new();
 
@@ -35,7 +44,12 @@ static SET_ATEXIT: std::sync::OnceLock<()> = std::sync::OnceLock::new();
 pub struct Node {
     node: rcl::rcl_node_t,
     init_param_server: std::sync::OnceLock<()>,
+    clock: Mutex<Option<Arc<Mutex<Clock>>>>,
+    pub(crate) graph_waiters: Arc<Mutex<GraphWaiters>>,
+    pub(crate) entities: Arc<EntityCounters>,
     pub(crate) context: Arc<Context>,
+    #[cfg(feature = "custom_alloc")]
+    pub(crate) message_pool: Option<&'static PoolAllocator>,
 }
 
 impl Node {
@@ -48,7 +62,9 @@ impl Node {
         let mut node = rcl::MTSafeFn::rcl_get_zero_initialized_node();
 
         let name_c = CString::new(name).unwrap();
-        let namespace_c = CString::new(namespace.unwrap_or_default()).unwrap();
+        // `__ns:=` remapping rules still take precedence inside RCL
+        let namespace = context.ros_env().resolve_namespace(namespace);
+        let namespace_c = CString::new(namespace).unwrap();
 
         {
             let guard = rcl::MT_UNSAFE_FN.lock();
@@ -71,7 +87,12 @@ impl Node {
         Ok(Arc::new(Node {
             node,
             init_param_server: std::sync::OnceLock::new(),
+            clock: Mutex::new(None),
+            graph_waiters: Default::default(),
+            entities: EntityCounters::new(),
             context,
+            #[cfg(feature = "custom_alloc")]
+            message_pool: options.message_pool,
         }))
     }
 
@@ -98,6 +119,76 @@ impl Node {
         guard.rcl_node_get_namespace(&self.node)
     }
 
+    /// Check that `name` is a valid topic or service name for this node.
+    ///
+    /// The name is expanded (`~`, `{node}`, `{ns}`, relative names) the same
+    /// way RCL does, so that an invalid name is reported with its position and
+    /// reason instead of an opaque RCL error code.
+    pub(crate) fn validate_topic_name(&self, name: &str) -> Result<()> {
+        let namespace = self.namespace()?;
+        let node_name = self.name()?;
+        ros2args::names::expand_topic_name(&namespace, &node_name, name)
+            .map(|_| ())
+            .map_err(name_err_to_err)
+    }
+
+    /// Get the ROS time clock of this node.
+    ///
+    /// The clock is created on the first call and shared afterwards, so that
+    /// overriding its time, e.g. from `/clock`, applies to every timer added
+    /// with it by [`Selector::add_timer_with_clock`](crate::selector::Selector::add_timer_with_clock).
+    pub fn get_clock(&self) -> Result<Arc<Mutex<Clock>>> {
+        let mut guard = self.clock.lock();
+        if let Some(clock) = guard.as_ref() {
+            return Ok(clock.clone());
+        }
+        let clock = Arc::new(Mutex::new(Clock::new()?));
+        *guard = Some(clock.clone());
+        Ok(clock)
+    }
+
+    /// Get the number of publishers, subscribers, servers and clients of
+    /// this node.
+    ///
+    /// RCL does not expose the subscriber and server queues, so their
+    /// counters in [`NodeStats`] are `None`.
+    pub fn stats(&self) -> NodeStats {
+        self.entities.stats()
+    }
+
+    /// Get the graph of ROS2 as seen by this node.
+    pub fn graph(self: &Arc<Self>) -> Graph {
+        Graph::new(self.clone())
+    }
+
+    /// Parameters given for this node on the command line and in parameter files.
+    pub(crate) fn parameter_overrides(&self) -> Result<BTreeMap<String, Value>> {
+        let fqn = self.fully_qualified_name()?;
+        let mut guard = rcl::MT_UNSAFE_FN.lock();
+        let arguments = unsafe { &mut (*self.context.as_ptr_mut()).global_arguments };
+        guard.parameter_map(fqn.as_str(), arguments)
+    }
+
+    /// Apply the `qos_overrides` parameters of an entity on `topic_name`.
+    fn override_qos(
+        &self,
+        topic_name: &str,
+        entity: QosEntityKind,
+        qos: Option<qos::Profile>,
+        options: &QosOverridingOptions,
+    ) -> Result<qos::Profile> {
+        self.validate_topic_name(topic_name)?;
+        let topic_c = CString::new(topic_name)?;
+        let topic = {
+            let guard = rcl::MT_UNSAFE_FN.lock();
+            guard.rcl_node_resolve_name(&self.node, &topic_c, false)?
+        };
+        let overrides = self.parameter_overrides()?;
+        options.apply(&topic, entity, qos.unwrap_or_default(), |name| {
+            overrides.get(name).cloned()
+        })
+    }
+
     pub fn create_parameter_server(self: &Arc<Self>) -> Result<ParameterServer> {
         match self.init_param_server.set(()) {
             Ok(()) => ParameterServer::new(self.clone()),
@@ -105,6 +196,18 @@ impl Node {
         }
     }
 
+    /// Create the `~/get_type_description` service of this node.
+    ///
+    /// See [`crate::type_description`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotImplemented` on distros without the service, see
+    /// [`rcl_features::has_type_description_service`](crate::compat::rcl_features::has_type_description_service).
+    pub fn create_type_description_server(self: &Arc<Self>) -> Result<TypeDescriptionServer> {
+        TypeDescriptionServer::new(self.clone())
+    }
+
     /// Create a publisher.
     /// If `qos` is specified `None`,
     /// the default profile is used.
@@ -155,6 +258,41 @@ impl Node {
         Publisher::new_disable_loaned_message(self.clone(), topic_name, qos)
     }
 
+    /// Create a publisher whose QoS can be overridden by parameters.
+    /// If `qos` is specified `None`,
+    /// the default profile is used.
+    ///
+    /// The policies allowed by `options` are read from the
+    /// `qos_overrides.<topic>.publisher.<policy>` parameters given on the
+    /// command line or in parameter files, then the profile is validated by
+    /// the callback of `options`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oxidros_rcl::{
+    ///     msg::common_interfaces::std_msgs, node::Node, qos::QosOverridingOptions,
+    ///     topic::publisher::Publisher,
+    /// };
+    /// use std::sync::Arc;
+    ///
+    /// // ros2 run ... --ros-args -p 'qos_overrides./topic_name.publisher.depth:=1'
+    /// fn create_tunable_publisher(node: Arc<Node>) -> Publisher<std_msgs::msg::Bool> {
+    ///     let options = QosOverridingOptions::with_default_policies();
+    ///     node.create_publisher_with_qos_overrides("topic_name", None, &options)
+    ///         .unwrap()
+    /// }
+    /// ```
+    pub fn create_publisher_with_qos_overrides<T: TypeSupport>(
+        self: &Arc<Self>,
+        topic_name: &str,
+        qos: Option<qos::Profile>,
+        options: &QosOverridingOptions,
+    ) -> Result<Publisher<T>> {
+        let qos = self.override_qos(topic_name, QosEntityKind::Publisher, qos, options)?;
+        Publisher::new(self.clone(), topic_name, Some(qos))
+    }
+
     /// Create a subscriber.
     /// If `qos` is specified `None`,
     /// the default profile is used.
@@ -203,6 +341,23 @@ impl Node {
         Subscriber::new_disable_loaned_message(self.clone(), topic_name, qos)
     }
 
+    /// Create a subscriber whose QoS can be overridden by parameters.
+    /// If `qos` is specified `None`,
+    /// the default profile is used.
+    ///
+    /// The policies allowed by `options` are read from the
+    /// `qos_overrides.<topic>.subscription.<policy>` parameters, as for
+    /// `create_publisher_with_qos_overrides`.
+    pub fn create_subscriber_with_qos_overrides<T: TypeSupport>(
+        self: &Arc<Self>,
+        topic_name: &str,
+        qos: Option<qos::Profile>,
+        options: &QosOverridingOptions,
+    ) -> Result<Subscriber<T>> {
+        let qos = self.override_qos(topic_name, QosEntityKind::Subscription, qos, options)?;
+        Subscriber::new(self.clone(), topic_name, Some(qos))
+    }
+
     /// Create a server.
     /// If `qos` is specified `None`,
     /// the default profile is used.
@@ -262,12 +417,18 @@ impl Drop for Node {
 /// Options for nodes.
 pub struct NodeOptions {
     options: rcl::rcl_node_options_t,
+    #[cfg(feature = "custom_alloc")]
+    message_pool: Option<&'static PoolAllocator>,
 }
 
 impl Default for NodeOptions {
     fn default() -> Self {
         let options = rcl::MTSafeFn::rcl_node_get_default_options();
-        NodeOptions { options }
+        NodeOptions {
+            options,
+            #[cfg(feature = "custom_alloc")]
+            message_pool: None,
+        }
     }
 }
 
@@ -278,6 +439,15 @@ impl NodeOptions {
         Default::default()
     }
 
+    /// Allocate the messages of the publishers and subscribers of the node from `pool`.
+    ///
+    /// See [`crate::allocator`].
+    #[cfg(feature = "custom_alloc")]
+    pub fn message_pool(mut self, pool: &'static PoolAllocator) -> Self {
+        self.message_pool = Some(pool);
+        self
+    }
+
     pub(crate) fn as_ptr(&self) -> *const rcl::rcl_node_options_t {
         &self.options
     }
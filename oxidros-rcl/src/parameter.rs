@@ -154,6 +154,15 @@
 //! // let rt = tokio::runtime::Runtime::new().unwrap(); --- IGNORE ---
 //! // rt.block_on(run_wait(param_server)); // Spawn an asynchronous task.
 //! ```
+//!
+//! ## Simulated time
+//!
+//! The parameter server declares the `use_sim_time` parameter. While it is
+//! `true`, the clock of the node, returned by
+//! [`Node::get_clock`](crate::node::Node::get_clock), follows the `/clock`
+//! topic, and so do the timers added with it. The parameter can be switched
+//! at runtime through the parameter services or
+//! [`ParameterServer::set_use_sim_time`].
 
 use crate::{
     error::Result,
@@ -186,11 +195,20 @@ use crate::{
         guard_condition::GuardCondition,
     },
     signal_handler::Signaled,
+    time_source::TimeSource,
 };
 pub use oxidros_core::parameter::*;
-use oxidros_core::selector::CallbackResult;
-use parking_lot::RwLock;
-use std::{cell::Cell, collections::BTreeSet, future::Future, rc::Rc, sync::Arc, task::Poll};
+use oxidros_core::{selector::CallbackResult, targets, time::USE_SIM_TIME};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    cell::Cell,
+    collections::BTreeSet,
+    future::Future,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+    task::Poll,
+};
 
 /// Parameter server.
 ///
@@ -253,20 +271,29 @@ pub struct ParameterServer {
     cond_halt: GuardCondition,
     pub(crate) cond_callback: GuardCondition,
     node: Arc<Node>,
+    auto_save: Arc<Mutex<Option<PathBuf>>>,
+    time_source: Arc<Mutex<TimeSource>>,
 }
 
 impl ParameterServer {
     pub(crate) fn new(node: Arc<Node>) -> Result<Self> {
-        let params_value = {
-            let fqn = node.fully_qualified_name()?;
-            let mut guard = crate::rcl::MT_UNSAFE_FN.lock();
-            let arguments = unsafe { &mut (*node.context.as_ptr_mut()).global_arguments };
-            guard.parameter_map(fqn.as_str(), arguments)?
-        };
+        let params_value = node.parameter_overrides()?;
         let mut params = Parameters::new();
         for (k, v) in params_value.into_iter() {
             let _ = params.set_parameter(k, v, false, None);
         }
+        if params.get_parameter(USE_SIM_TIME).is_none() {
+            params.set_parameter(
+                USE_SIM_TIME.to_string(),
+                Value::Bool(false),
+                false,
+                Some("Use the simulated time of the /clock topic".to_string()),
+            )?;
+        }
+
+        let time_source = Arc::new(Mutex::new(TimeSource::new(node.clone())?));
+        apply_use_sim_time(&params, &time_source);
+
         let params = Arc::new(RwLock::new(params));
         let ps = params.clone();
         let n = node.clone();
@@ -277,8 +304,24 @@ impl ParameterServer {
         let cond_callback = GuardCondition::new(node.context.clone())?;
         let cond_callback_cloned = cond_callback.clone();
 
-        let handler =
-            std::thread::spawn(move || param_server(n, ps, cond_halt_cloned, cond_callback_cloned));
+        let auto_save = Arc::new(Mutex::new(None));
+        let auto_save_cloned = auto_save.clone();
+
+        let time_source_cloned = time_source.clone();
+
+        let handler = std::thread::Builder::new()
+            .name("oxidros-param-server".into())
+            .spawn(move || {
+                param_server(
+                    n,
+                    ps,
+                    cond_halt_cloned,
+                    cond_callback_cloned,
+                    auto_save_cloned,
+                    time_source_cloned,
+                )
+            })
+            .expect("spawn parameter server thread");
 
         Ok(Self {
             params,
@@ -286,9 +329,58 @@ impl ParameterServer {
             cond_halt,
             cond_callback,
             node,
+            auto_save,
+            time_source,
         })
     }
 
+    /// Set the `use_sim_time` parameter, and switch the clock of the node to
+    /// the simulated time or back to the system time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameter is read-only or not a boolean.
+    pub fn set_use_sim_time(&self, enable: bool) -> Result<()> {
+        let mut params = self.params.write();
+        params.update(USE_SIM_TIME, Value::Bool(enable))?;
+        apply_use_sim_time(&params, &self.time_source);
+        Ok(())
+    }
+
+    /// Whether the clock of the node follows the simulated time.
+    pub fn use_sim_time(&self) -> bool {
+        self.time_source.lock().use_sim_time()
+    }
+
+    /// Take an immutable copy of the parameters, see [`Parameters::snapshot`].
+    pub fn snapshot(&self) -> ParameterSnapshot {
+        self.params.read().snapshot()
+    }
+
+    /// Write all parameters to a YAML file in the `ros2 param dump` format.
+    pub fn dump_yaml(&self, path: impl AsRef<Path>) -> Result<()> {
+        let fqn = self.node.fully_qualified_name()?;
+        self.params.read().dump_yaml(&fqn, path)
+    }
+
+    /// Load parameters from a YAML parameter file.
+    ///
+    /// Only the entries whose node key matches this node (exactly or through a
+    /// wildcard such as `/**`) are applied. Returns the names of the parameters
+    /// that were set.
+    pub fn load_yaml(&self, path: impl AsRef<Path>) -> Result<BTreeSet<String>> {
+        let fqn = self.node.fully_qualified_name()?;
+        self.params.write().load_yaml(&fqn, path)
+    }
+
+    /// Rewrite `path` whenever parameters are set through the parameter services,
+    /// so values tuned at runtime survive a restart.
+    ///
+    /// Pass `None` to disable auto-saving.
+    pub fn set_auto_save(&self, path: Option<PathBuf>) {
+        *self.auto_save.lock() = path;
+    }
+
     pub fn wait(&mut self) -> AsyncWait<'_> {
         AsyncWait {
             param_server: self,
@@ -312,6 +404,8 @@ fn param_server(
     params: Arc<RwLock<Parameters>>,
     cond_halt: GuardCondition,
     cond_callback: GuardCondition,
+    auto_save: Arc<Mutex<Option<PathBuf>>>,
+    time_source: Arc<Mutex<TimeSource>>,
 ) -> Result<()> {
     if let Ok(mut selector) = node.context.create_selector() {
         add_srv_list(&node, &mut selector, params.clone())?;
@@ -321,6 +415,8 @@ fn param_server(
             params.clone(),
             "set_parameters",
             cond_callback.clone(),
+            auto_save.clone(),
+            time_source.clone(),
         )?;
         add_srv_set_atomic(
             &node,
@@ -328,6 +424,8 @@ fn param_server(
             params.clone(),
             "set_parameters_atomically",
             cond_callback,
+            auto_save,
+            time_source,
         )?;
         add_srv_get(&node, &mut selector, params.clone())?;
         add_srv_get_types(&node, &mut selector, params.clone())?;
@@ -355,13 +453,47 @@ fn param_server(
     Ok(())
 }
 
+/// Write the parameters to the auto-save file, if one is configured.
+fn save_if_enabled(fqn: &str, params: &RwLock<Parameters>, auto_save: &Mutex<Option<PathBuf>>) {
+    let auto_save = auto_save.lock();
+    let Some(path) = auto_save.as_ref() else {
+        return;
+    };
+    if let Err(e) = params.read().dump_yaml(fqn, path) {
+        tracing::warn!(
+            target: targets::PARAMETER,
+            path = %path.display(),
+            error = %e,
+            "failed to auto-save parameters"
+        );
+    }
+}
+
+/// Switch the time source to the value of the `use_sim_time` parameter.
+fn apply_use_sim_time(params: &Parameters, time_source: &Mutex<TimeSource>) {
+    let enable = params
+        .get_parameter(USE_SIM_TIME)
+        .is_some_and(|param| matches!(param.value, Value::Bool(true)));
+    if let Err(e) = time_source.lock().set_use_sim_time(enable) {
+        tracing::error!(
+            target: "oxidros",
+            use_sim_time = enable,
+            error = %e,
+            "failed to switch the time source"
+        );
+    }
+}
+
 fn add_srv_set(
     node: &Arc<Node>,
     selector: &mut Selector,
     params: Arc<RwLock<Parameters>>,
     service_name: &str,
     cond_callback: GuardCondition,
+    auto_save: Arc<Mutex<Option<PathBuf>>>,
+    time_source: Arc<Mutex<TimeSource>>,
 ) -> Result<()> {
+    let fqn = node.fully_qualified_name()?;
     let name = node.name()?;
     let srv_set = node.create_server::<SetParameters>(
         &format!("{name}/{service_name}"),
@@ -387,43 +519,24 @@ fn add_srv_set(
                     let key = param.name.to_string();
                     let val: Value = (&param.value).into();
 
-                    if let Some(original) = guard.params.get_mut(&key) {
-                        if original.descriptor.read_only {
-                            let reason = format!("{} is read only", key);
-                            slice[i].reason.assign(&reason);
-                            slice[i].successful = false;
-                            continue;
-                        }
-
-                        if !original.check_range(&val) {
-                            let reason = format!("{} is not in the range", key);
-                            slice[i].reason.assign(&reason);
-                            slice[i].successful = false;
-                            continue;
-                        }
-
-                        if original.descriptor.dynamic_typing || original.value.type_check(&val) {
-                            original.value = val;
+                    match guard.update(&key, val) {
+                        Ok(()) => {
                             slice[i].successful = true;
                             updated += 1;
-                            guard.updated.insert(key);
-                        } else {
-                            let reason = format!(
-                                "failed type checking: dst = {}, src = {}",
-                                original.value.type_name(),
-                                val.type_name()
-                            );
+                        }
+                        Err(reason) => {
                             slice[i].reason.assign(&reason);
                             slice[i].successful = false;
                         }
-                    } else {
-                        let reason = format!("no such parameter: name = {}", key);
-                        slice[i].reason.assign(&reason);
-                        slice[i].successful = false;
                     }
                 }
             }
 
+            if updated > 0 {
+                apply_use_sim_time(&params.read(), &time_source);
+                save_if_enabled(&fqn, &params, &auto_save);
+            }
+
             if updated > 0 && cond_callback.trigger().is_err() {
                 tracing::error!(
                     target: "oxidros",
@@ -449,7 +562,10 @@ fn add_srv_set_atomic(
     params: Arc<RwLock<Parameters>>,
     service_name: &str,
     cond_callback: GuardCondition,
+    auto_save: Arc<Mutex<Option<PathBuf>>>,
+    time_source: Arc<Mutex<TimeSource>>,
 ) -> Result<()> {
+    let fqn = node.fully_qualified_name()?;
     let name = node.name()?;
     let srv_set = node.create_server::<SetParametersAtomically>(
         &format!("{name}/{service_name}"),
@@ -466,50 +582,28 @@ fn add_srv_set_atomic(
                 return response;
             };
 
-            let mut updated = 0;
-            {
-                let mut guard = params.write();
-                for param in req.parameters.iter() {
-                    let key = param.name.to_string();
-                    let val: Value = (&param.value).into();
-
-                    if let Some(original) = guard.params.get_mut(&key) {
-                        if original.descriptor.read_only {
-                            let reason = format!("{} is read only", key);
-                            results.reason.assign(&reason);
-                            results.successful = false;
-                            break;
-                        }
-
-                        if !original.check_range(&val) {
-                            let reason = format!("{} is not in the range", key);
-                            results.reason.assign(&reason);
-                            results.successful = false;
-                            break;
-                        }
-
-                        if original.descriptor.dynamic_typing || original.value.type_check(&val) {
-                            original.value = val;
-                            results.successful = true;
-                            updated += 1;
-                            guard.updated.insert(key);
-                        } else {
-                            let reason = format!(
-                                "failed type checking: dst = {}, src = {}",
-                                original.value.type_name(),
-                                val.type_name()
-                            );
-                            results.reason.assign(&reason);
-                            results.successful = false;
-                            break;
-                        }
-                    } else {
-                        let reason = format!("no such parameter: name = {}", key);
-                        results.reason.assign(&reason);
-                        results.successful = false;
-                        break;
-                    }
+            let parameters: Vec<(String, Value)> = req
+                .parameters
+                .iter()
+                .map(|param| (param.name.to_string(), (&param.value).into()))
+                .collect();
+            let updated = parameters.len();
+            let result = params.write().update_atomically(parameters);
+            let updated = match result {
+                Ok(()) => {
+                    results.successful = true;
+                    updated
+                }
+                Err(reason) => {
+                    results.reason.assign(&reason);
+                    results.successful = false;
+                    0
                 }
+            };
+
+            if updated > 0 {
+                apply_use_sim_time(&params.read(), &time_source);
+                save_if_enabled(&fqn, &params, &auto_save);
             }
 
             if updated > 0 && cond_callback.trigger().is_err() {
@@ -544,13 +638,16 @@ fn add_srv_get(
     selector.add_server(
         srv_get,
         Box::new(move |req| {
+            // Unknown parameters are NOT_SET, so values match names by index
+            let not_set = Value::NotSet;
             let mut result = Vec::new();
 
             let gurad = params.read();
             for name in req.names.iter() {
                 let key = name.to_string();
-                if let Some(param) = gurad.params.get(&key) {
-                    result.push(&param.value);
+                match gurad.get_parameter(&key) {
+                    Some(param) => result.push(&param.value),
+                    None => result.push(&not_set),
                 }
             }
 
@@ -598,7 +695,7 @@ fn add_srv_describe(
             let mut results = Vec::new();
             for name in req.names.iter() {
                 let key = name.to_string();
-                if let Some(param) = gurad.params.get(&key) {
+                if let Some(param) = gurad.get_parameter(&key) {
                     let value: ParameterValue = (&param.value).into();
                     let description =
                         unwrap_or_continue!(RosString::new(&param.descriptor.description));
@@ -673,7 +770,7 @@ fn add_srv_get_types(
             let gurad = params.read();
             for name in req.names.iter() {
                 let key = name.to_string();
-                if let Some(param) = gurad.params.get(&key) {
+                if let Some(param) = gurad.get_parameter(&key) {
                     let v: ParameterValue = (&param.value).into();
                     types.push(v.r#type);
                 } else {
@@ -709,62 +806,13 @@ fn add_srv_list(
     selector.add_server(
         srv_list,
         Box::new(move |req| {
-            let separator = b'.';
-
-            let mut result = Vec::new();
-            let mut result_prefix = Vec::new();
             let prefixes: Vec<String> = req
                 .prefixes
                 .iter()
                 .map(|prefix| prefix.get_string())
                 .collect();
-
-            let guard = params.write();
-
-            for (k, _v) in guard.params.iter() {
-                let cnt = k.as_bytes().iter().filter(|c| **c == separator).count();
-                let get_all = prefixes.is_empty() && req.depth == 0 || cnt < req.depth as usize;
-
-                let matches = prefixes.iter().find(|prefix| {
-                    if k == *prefix {
-                        true
-                    } else {
-                        let mut prefix_sep = (*prefix).clone();
-                        prefix_sep.push(separator as char);
-
-                        if k.starts_with(&prefix_sep) {
-                            if req.depth == 0 {
-                                true
-                            } else {
-                                let cnt = k.as_bytes()[..prefix.len()]
-                                    .iter()
-                                    .filter(|c| **c == separator)
-                                    .count();
-                                req.depth == 0 || cnt < req.depth as usize
-                            }
-                        } else {
-                            false
-                        }
-                    }
-                });
-
-                if get_all || matches.is_some() {
-                    result.push(k);
-                    let separated: Vec<_> = k.split(separator as char).collect();
-                    if separated.len() > 1 {
-                        let prefix = separated[..separated.len() - 1].iter().fold(
-                            String::new(),
-                            |mut s, item| {
-                                s.push_str(item);
-                                s
-                            },
-                        );
-                        if !result_prefix.contains(&prefix) {
-                            result_prefix.push(prefix);
-                        }
-                    }
-                }
-            }
+            let list = params.read().list(&prefixes, req.depth);
+            let (result, result_prefix) = (list.names, list.prefixes);
 
             let mut response = ListParameters_Response::new().unwrap();
             if let (Some(mut seq_names), Some(mut seq_prefixes)) = (
@@ -6,6 +6,7 @@ use std::fmt;
 use std::slice::from_raw_parts;
 use std::time::Duration;
 
+use crate::compat;
 use crate::error::ActionError;
 use crate::rcl::{rmw_message_info_t, rmw_service_info_t};
 
@@ -189,6 +190,55 @@ impl From<&oxidros_core::Profile> for super::rmw_qos_profile_t {
     }
 }
 
+impl From<oxidros_core::time::ClockType> for super::rcl_clock_type_t {
+    fn from(value: oxidros_core::time::ClockType) -> Self {
+        use oxidros_core::time::ClockType::*;
+        match value {
+            Ros => Self::RCL_ROS_TIME,
+            System => Self::RCL_SYSTEM_TIME,
+            Steady => Self::RCL_STEADY_TIME,
+        }
+    }
+}
+
+impl From<super::rcl_clock_change_t> for oxidros_core::time::ClockChange {
+    fn from(value: super::rcl_clock_change_t) -> Self {
+        use super::rcl_clock_change_t::*;
+        match value {
+            RCL_ROS_TIME_NO_CHANGE => Self::RosTimeNoChange,
+            RCL_ROS_TIME_ACTIVATED => Self::RosTimeActivated,
+            RCL_ROS_TIME_DEACTIVATED => Self::RosTimeDeactivated,
+            RCL_SYSTEM_TIME_NO_CHANGE => Self::SystemTimeNoChange,
+        }
+    }
+}
+
+impl From<&super::rcl_time_jump_t> for oxidros_core::time::TimeJump {
+    fn from(value: &super::rcl_time_jump_t) -> Self {
+        Self {
+            clock_change: value.clock_change.into(),
+            delta_nanos: value.delta.nanoseconds,
+        }
+    }
+}
+
+impl From<&oxidros_core::time::JumpThreshold> for super::rcl_jump_threshold_t {
+    fn from(value: &oxidros_core::time::JumpThreshold) -> Self {
+        // rcl disables a threshold with 0 and expects min_backward to be negative
+        let nanos =
+            |d: Option<Duration>| d.map_or(0, |d| d.as_nanos().min(i64::MAX as u128) as i64);
+        Self {
+            on_clock_change: value.on_clock_change,
+            min_forward: super::rcl_duration_t {
+                nanoseconds: nanos(value.min_forward),
+            },
+            min_backward: super::rcl_duration_t {
+                nanoseconds: -nanos(value.min_backward),
+            },
+        }
+    }
+}
+
 impl From<super::rcl_action_goal_event_t> for oxidros_core::action::GoalEvent {
     fn from(value: super::rcl_action_goal_event_t) -> Self {
         use super::rcl_action_goal_event_t::*;
@@ -363,6 +413,10 @@ impl From<ActionError> for RclRetErr {
             ActionError::ServerTakeFailed => super::RCL_RET_ACTION_SERVER_TAKE_FAILED.into(),
             ActionError::GoalHandleInvalid => super::RCL_RET_ACTION_GOAL_HANDLE_INVALID.into(),
             ActionError::GoalEventInvalid => super::RCL_RET_ACTION_GOAL_EVENT_INVALID.into(),
+            ActionError::CancelRejected
+            | ActionError::UnknownGoalId
+            | ActionError::GoalTerminated
+            | ActionError::CancelFailed => super::RCL_RET_ERROR.into(),
             ActionError::Rcl(err) => err.into(),
             ActionError::InvalidRetVal => (!0).into(),
         }
@@ -381,48 +435,74 @@ impl From<i32> for RclRetErr {
     }
 }
 
-impl From<rmw_message_info_t> for oxidros_core::message::MessageInfo {
-    #[cfg(ros_distro_humble)]
-    fn from(value: rmw_message_info_t) -> Self {
-        let mut writer_gid = [0; 16];
-        for (o, i) in writer_gid.iter_mut().zip(value.publisher_gid.data) {
-            *o = i;
-        }
+impl From<&super::rmw_network_flow_endpoint_t> for oxidros_core::NetworkFlowEndpoint {
+    fn from(value: &super::rmw_network_flow_endpoint_t) -> Self {
+        use super::{rmw_internet_protocol_t::*, rmw_transport_protocol_t::*};
+        use oxidros_core::network::{InternetProtocol, TransportProtocol};
+
+        let transport_protocol = match value.transport_protocol {
+            RMW_TRANSPORT_PROTOCOL_UDP => TransportProtocol::Udp,
+            RMW_TRANSPORT_PROTOCOL_TCP => TransportProtocol::Tcp,
+            RMW_TRANSPORT_PROTOCOL_UNKNOWN | RMW_TRANSPORT_PROTOCOL_COUNT => {
+                TransportProtocol::Unknown
+            }
+        };
+        let internet_protocol = match value.internet_protocol {
+            RMW_INTERNET_PROTOCOL_IPV4 => InternetProtocol::Ipv4,
+            RMW_INTERNET_PROTOCOL_IPV6 => InternetProtocol::Ipv6,
+            RMW_INTERNET_PROTOCOL_UNKNOWN | RMW_INTERNET_PROTOCOL_COUNT => {
+                InternetProtocol::Unknown
+            }
+        };
+        // NUL terminated, unless the address fills the buffer
+        let address = unsafe {
+            from_raw_parts(
+                value.internet_address.as_ptr() as *const u8,
+                value.internet_address.len(),
+            )
+        };
+        let address = CStr::from_bytes_until_nul(address)
+            .map(|a| a.to_string_lossy().into_owned())
+            .unwrap_or_else(|_| String::from_utf8_lossy(address).into_owned());
+
         Self {
-            sequence_number: value.publication_sequence_number as i64,
-            source_timestamp_ns: value.source_timestamp,
-            writer_gid,
+            transport_protocol,
+            internet_protocol,
+            transport_port: value.transport_port,
+            flow_label: value.flow_label,
+            dscp: value.dscp,
+            internet_address: address,
         }
     }
-    #[cfg(not(ros_distro_humble))]
+}
+
+impl From<rmw_message_info_t> for oxidros_core::message::MessageInfo {
     fn from(value: rmw_message_info_t) -> Self {
         Self {
             sequence_number: value.publication_sequence_number as i64,
             source_timestamp_ns: value.source_timestamp,
-            writer_gid: value.publisher_gid.data,
+            writer_gid: compat::gid_bytes(&value.publisher_gid),
         }
     }
 }
 
 impl From<rmw_service_info_t> for oxidros_core::message::MessageInfo {
-    #[cfg(ros_distro_humble)]
     fn from(value: rmw_service_info_t) -> Self {
-        let mut writer_gid = [0; 16];
-        for (o, i) in writer_gid.iter_mut().zip(value.request_id.writer_guid) {
-            *o = i.try_into().unwrap_or_default();
-        }
         Self {
             sequence_number: value.request_id.sequence_number,
             source_timestamp_ns: value.source_timestamp,
-            writer_gid,
+            writer_gid: compat::writer_guid_bytes(&value.request_id.writer_guid),
         }
     }
-    #[cfg(not(ros_distro_humble))]
+}
+
+impl From<rmw_service_info_t> for oxidros_core::message::RequestInfo {
     fn from(value: rmw_service_info_t) -> Self {
         Self {
+            client_gid: compat::writer_guid_bytes(&value.request_id.writer_guid),
             sequence_number: value.request_id.sequence_number,
             source_timestamp_ns: value.source_timestamp,
-            writer_gid: value.request_id.writer_guid,
+            received_timestamp_ns: value.received_timestamp,
         }
     }
 }
@@ -14,7 +14,7 @@
 #![allow(clashing_extern_declarations)]
 #![allow(clippy::enum_variant_names)]
 
-use oxidros_core::RclError;
+use oxidros_core::{ActionError, RclError};
 use regex::Regex;
 
 pub(crate) mod conversions;
@@ -30,6 +30,7 @@ include!(concat!(env!("OUT_DIR"), "/rcl.rs"));
 
 use crate::{
     error::{Result, action_ret_val_to_err, ret_val_to_err},
+    get_allocator,
     parameter::Value,
 };
 use once_cell::sync::Lazy;
@@ -83,6 +84,22 @@ impl MTUnsafeFn {
         ret_val_to_err(unsafe { self::rcl_init_options_fini(init_options) })
     }
 
+    pub fn rcl_init_options_set_domain_id(
+        &self,
+        init_options: *mut rcl_init_options_t,
+        domain_id: usize,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_init_options_set_domain_id(init_options, domain_id) })
+    }
+
+    pub fn rcl_context_get_domain_id(
+        &self,
+        context: *mut rcl_context_t,
+        domain_id: *mut usize,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_context_get_domain_id(context, domain_id) })
+    }
+
     pub fn rcl_node_init(
         &self,
         node: *mut rcl_node_t,
@@ -123,6 +140,19 @@ impl MTUnsafeFn {
         ret_val_to_err(unsafe { self::rcl_publisher_fini(publisher, node) })
     }
 
+    pub fn rcl_publisher_event_init(
+        &self,
+        event: *mut rcl_event_t,
+        publisher: *const rcl_publisher_t,
+        event_type: rcl_publisher_event_type_t,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_publisher_event_init(event, publisher, event_type) })
+    }
+
+    pub fn rcl_event_fini(&self, event: *mut rcl_event_t) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_event_fini(event) })
+    }
+
     pub fn rcl_subscription_fini(
         &self,
         subscription: *mut rcl_subscription_t,
@@ -183,44 +213,6 @@ impl MTUnsafeFn {
         })
     }
 
-    pub fn rcl_wait_set_clear(&self, wait_set: *mut rcl_wait_set_t) -> Result<()> {
-        ret_val_to_err(unsafe { self::rcl_wait_set_clear(wait_set) })
-    }
-
-    pub fn rcl_wait_set_resize(
-        &self,
-        wait_set: *mut rcl_wait_set_t,
-        subscriptions_size: usize,
-        guard_conditions_size: usize,
-        timers_size: usize,
-        clients_size: usize,
-        services_size: usize,
-        events_size: usize,
-    ) -> Result<()> {
-        ret_val_to_err(unsafe {
-            self::rcl_wait_set_resize(
-                wait_set,
-                subscriptions_size,
-                guard_conditions_size,
-                timers_size,
-                clients_size,
-                services_size,
-                events_size,
-            )
-        })
-    }
-
-    pub fn rcl_wait_set_add_subscription(
-        &self,
-        wait_set: *mut rcl_wait_set_t,
-        subscription: *const rcl_subscription_t,
-        index: *mut usize,
-    ) -> Result<()> {
-        ret_val_to_err(unsafe {
-            self::rcl_wait_set_add_subscription(wait_set, subscription, index)
-        })
-    }
-
     pub fn rcl_wait_set_fini(&self, wait_set: *mut rcl_wait_set_t) -> Result<()> {
         ret_val_to_err(unsafe { self::rcl_wait_set_fini(wait_set) })
     }
@@ -248,17 +240,6 @@ impl MTUnsafeFn {
         ret_val_to_err(unsafe { self::rcl_guard_condition_fini(guard_condition) })
     }
 
-    pub fn rcl_wait_set_add_guard_condition(
-        &self,
-        wait_set: *mut rcl_wait_set_t,
-        guard_condition: *const rcl_guard_condition_t,
-        index: *mut usize,
-    ) -> Result<()> {
-        ret_val_to_err(unsafe {
-            self::rcl_wait_set_add_guard_condition(wait_set, guard_condition, index)
-        })
-    }
-
     pub fn rcl_service_init(
         &self,
         service: *mut rcl_service_t,
@@ -319,24 +300,6 @@ impl MTUnsafeFn {
         })
     }
 
-    pub fn rcl_wait_set_add_client(
-        &self,
-        wait_set: *mut rcl_wait_set_t,
-        client: *const rcl_client_t,
-        index: *mut usize,
-    ) -> Result<()> {
-        ret_val_to_err(unsafe { self::rcl_wait_set_add_client(wait_set, client, index) })
-    }
-
-    pub fn rcl_wait_set_add_service(
-        &self,
-        wait_set: *mut rcl_wait_set_t,
-        service: *const rcl_service_t,
-        index: *mut usize,
-    ) -> Result<()> {
-        ret_val_to_err(unsafe { self::rcl_wait_set_add_service(wait_set, service, index) })
-    }
-
     pub fn rcl_borrow_loaned_message(
         &self,
         publisher: *const rcl_publisher_t,
@@ -382,6 +345,70 @@ impl MTUnsafeFn {
         ret_val_to_err(unsafe { self::rcl_ros_clock_fini(clock) })
     }
 
+    pub fn rcl_clock_init(
+        &self,
+        clock_type: rcl_clock_type_t,
+        clock: *mut rcl_clock_t,
+        allocator: *mut rcl_allocator_t,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_clock_init(clock_type, clock, allocator) })
+    }
+
+    pub fn rcl_clock_fini(&self, clock: *mut rcl_clock_t) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_clock_fini(clock) })
+    }
+
+    #[cfg(ros_distro_humble)]
+    pub fn rcl_timer_init(
+        &self,
+        timer: *mut rcl_timer_t,
+        clock: *mut rcl_clock_t,
+        context: *mut rcl_context_t,
+        period: i64,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe {
+            self::rcl_timer_init(timer, clock, context, period, None, get_allocator())
+        })
+    }
+
+    #[cfg(not(ros_distro_humble))]
+    pub fn rcl_timer_init(
+        &self,
+        timer: *mut rcl_timer_t,
+        clock: *mut rcl_clock_t,
+        context: *mut rcl_context_t,
+        period: i64,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe {
+            self::rcl_timer_init2(timer, clock, context, period, None, get_allocator(), true)
+        })
+    }
+
+    pub fn rcl_timer_fini(&self, timer: *mut rcl_timer_t) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_timer_fini(timer) })
+    }
+
+    pub fn rcl_clock_add_jump_callback(
+        &self,
+        clock: *mut rcl_clock_t,
+        threshold: rcl_jump_threshold_t,
+        callback: rcl_jump_callback_t,
+        user_data: *mut ::std::os::raw::c_void,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe {
+            self::rcl_clock_add_jump_callback(clock, threshold, callback, user_data)
+        })
+    }
+
+    pub fn rcl_clock_remove_jump_callback(
+        &self,
+        clock: *mut rcl_clock_t,
+        callback: rcl_jump_callback_t,
+        user_data: *mut ::std::os::raw::c_void,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_clock_remove_jump_callback(clock, callback, user_data) })
+    }
+
     pub fn rcl_return_loaned_message_from_subscription(
         &self,
         subscription: *const rcl_subscription_t,
@@ -854,6 +881,24 @@ impl MTUnsafeFn {
         })
     }
 
+    #[cfg(any(ros_distro_jazzy, ros_distro_kilted, ros_distro_lyrical))]
+    pub fn rcl_node_type_description_service_handle_request(
+        &self,
+        node: *mut rcl_node_t,
+        request_header: *const rmw_request_id_t,
+        request: *const type_description_interfaces__srv__GetTypeDescription_Request,
+        response: *mut type_description_interfaces__srv__GetTypeDescription_Response,
+    ) {
+        unsafe {
+            self::rcl_node_type_description_service_handle_request(
+                node,
+                request_header,
+                request,
+                response,
+            )
+        }
+    }
+
     pub fn rcl_node_get_name(&self, node: *const rcl_node_t) -> Result<String> {
         let name_c = unsafe { self::rcl_node_get_name(node) };
         if name_c.is_null() {
@@ -888,6 +933,113 @@ impl MTUnsafeFn {
             .map_err(|_| RclError::NodeInvalidName)?
             .to_owned())
     }
+    /// Expand and remap a topic or service name the way RCL does when
+    /// creating an entity.
+    pub fn rcl_node_resolve_name(
+        &self,
+        node: *const rcl_node_t,
+        input_name: &CStr,
+        is_service: bool,
+    ) -> Result<String> {
+        let allocator = get_allocator();
+        let mut output: *mut std::os::raw::c_char = std::ptr::null_mut();
+        ret_val_to_err(unsafe {
+            self::rcl_node_resolve_name(
+                node,
+                input_name.as_ptr(),
+                allocator,
+                is_service,
+                false,
+                &mut output,
+            )
+        })?;
+
+        let name = unsafe { CStr::from_ptr(output).to_string_lossy().into_owned() };
+        if let Some(deallocate) = allocator.deallocate {
+            unsafe { deallocate(output as *mut _, allocator.state) };
+        }
+        Ok(name)
+    }
+    pub fn rcl_node_get_graph_guard_condition(
+        &self,
+        node: *const rcl_node_t,
+    ) -> Result<*const rcl_guard_condition_t> {
+        let cond = unsafe { self::rcl_node_get_graph_guard_condition(node) };
+        if cond.is_null() {
+            return Err(RclError::NodeInvalid.into());
+        }
+        Ok(cond)
+    }
+
+    /// Get the fully qualified names of the nodes in the graph.
+    pub fn rcl_get_node_names(&self, node: *const rcl_node_t) -> Result<Vec<String>> {
+        let mut names = unsafe { self::rcutils_get_zero_initialized_string_array() };
+        let mut namespaces = unsafe { self::rcutils_get_zero_initialized_string_array() };
+        let ret =
+            unsafe { self::rcl_get_node_names(node, get_allocator(), &mut names, &mut namespaces) };
+        ret_val_to_err(ret)?;
+
+        let fqns = (0..names.size)
+            .map(|i| unsafe {
+                let name = CStr::from_ptr(*names.data.add(i)).to_string_lossy();
+                let namespace = CStr::from_ptr(*namespaces.data.add(i)).to_string_lossy();
+                ros2args::build_node_fqn(&namespace, &name)
+            })
+            .collect();
+
+        unsafe {
+            self::rcutils_string_array_fini(&mut names);
+            self::rcutils_string_array_fini(&mut namespaces);
+        }
+        Ok(fqns)
+    }
+
+    /// Get the names of the topics with at least one publisher or subscriber.
+    pub fn rcl_get_topic_names(&self, node: *const rcl_node_t) -> Result<Vec<String>> {
+        let mut allocator = get_allocator();
+        let mut names_and_types = unsafe { self::rcl_get_zero_initialized_names_and_types() };
+        let ret = unsafe {
+            self::rcl_get_topic_names_and_types(node, &mut allocator, false, &mut names_and_types)
+        };
+        ret_val_to_err(ret)?;
+
+        let names = &names_and_types.names;
+        let topics = (0..names.size)
+            .map(|i| unsafe {
+                CStr::from_ptr(*names.data.add(i))
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+
+        ret_val_to_err(unsafe { self::rcl_names_and_types_fini(&mut names_and_types) })?;
+        Ok(topics)
+    }
+
+    pub fn rcl_count_publishers(
+        &self,
+        node: *const rcl_node_t,
+        topic_name: &CStr,
+    ) -> Result<usize> {
+        let mut count = 0;
+        ret_val_to_err(unsafe {
+            self::rcl_count_publishers(node, topic_name.as_ptr(), &mut count)
+        })?;
+        Ok(count)
+    }
+
+    pub fn rcl_count_subscribers(
+        &self,
+        node: *const rcl_node_t,
+        topic_name: &CStr,
+    ) -> Result<usize> {
+        let mut count = 0;
+        ret_val_to_err(unsafe {
+            self::rcl_count_subscribers(node, topic_name.as_ptr(), &mut count)
+        })?;
+        Ok(count)
+    }
+
     pub fn rcl_publisher_get_topic_name(
         &self,
         publisher: *const rcl_publisher_t,
@@ -938,6 +1090,52 @@ impl MTUnsafeFn {
             .map_err(|_| RclError::ServiceNameInvalid)?
             .to_owned())
     }
+    pub fn rcl_action_server_get_action_name(
+        &self,
+        action_server: *const rcl_action_server_t,
+    ) -> Result<String> {
+        let name_c = unsafe { self::rcl_action_server_get_action_name(action_server) };
+        action_name_to_string(name_c)
+    }
+    pub fn rcl_action_client_get_action_name(
+        &self,
+        action_client: *const rcl_action_client_t,
+    ) -> Result<String> {
+        let name_c = unsafe { self::rcl_action_client_get_action_name(action_client) };
+        action_name_to_string(name_c)
+    }
+}
+
+fn action_name_to_string(name_c: *const std::os::raw::c_char) -> Result<String> {
+    if name_c.is_null() {
+        return Err(ActionError::NameInvalid.into());
+    }
+    let name_c = unsafe { CStr::from_ptr(name_c) };
+    Ok(name_c
+        .to_str()
+        .map_err(|_| ActionError::NameInvalid)?
+        .to_owned())
+}
+
+/// Copy the network flow endpoints filled by `get` into Rust values.
+fn network_flow_endpoints(
+    get: impl FnOnce(*mut rcutils_allocator_t, *mut rmw_network_flow_endpoint_array_t) -> rcl_ret_t,
+) -> Result<Vec<oxidros_core::NetworkFlowEndpoint>> {
+    // The array keeps a pointer to the allocator until it is finalized
+    let mut allocator = unsafe { self::rcutils_get_default_allocator() };
+    let mut array = unsafe { self::rmw_get_zero_initialized_network_flow_endpoint_array() };
+    ret_val_to_err(get(&mut allocator, &mut array))?;
+
+    let endpoints = if array.network_flow_endpoint.is_null() {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(array.network_flow_endpoint, array.size) }
+            .iter()
+            .map(Into::into)
+            .collect()
+    };
+    ret_val_to_err(unsafe { self::rmw_network_flow_endpoint_array_fini(&mut array) })?;
+    Ok(endpoints)
 }
 
 impl MTUnsafeLogFn {
@@ -1029,6 +1227,50 @@ impl MTSafeFn {
         })
     }
 
+    pub fn rcl_publisher_wait_for_all_acked(
+        publisher: *const rcl_publisher_t,
+        timeout: rcl_duration_value_t,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_publisher_wait_for_all_acked(publisher, timeout) })
+    }
+
+    pub fn rcl_publisher_assert_liveliness(publisher: *const rcl_publisher_t) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_publisher_assert_liveliness(publisher) })
+    }
+
+    pub fn rcl_publisher_get_network_flow_endpoints(
+        publisher: *const rcl_publisher_t,
+    ) -> Result<Vec<oxidros_core::NetworkFlowEndpoint>> {
+        network_flow_endpoints(|allocator, array| unsafe {
+            self::rcl_publisher_get_network_flow_endpoints(publisher, allocator, array)
+        })
+    }
+
+    pub fn rcl_subscription_get_network_flow_endpoints(
+        subscription: *const rcl_subscription_t,
+    ) -> Result<Vec<oxidros_core::NetworkFlowEndpoint>> {
+        network_flow_endpoints(|allocator, array| unsafe {
+            self::rcl_subscription_get_network_flow_endpoints(subscription, allocator, array)
+        })
+    }
+
+    pub fn rcl_publisher_get_actual_qos(
+        publisher: *const rcl_publisher_t,
+    ) -> *const rmw_qos_profile_t {
+        unsafe { self::rcl_publisher_get_actual_qos(publisher) }
+    }
+
+    pub fn rcl_get_zero_initialized_event() -> rcl_event_t {
+        unsafe { self::rcl_get_zero_initialized_event() }
+    }
+
+    pub fn rcl_take_event(
+        event: *const rcl_event_t,
+        event_info: *mut ::std::os::raw::c_void,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_take_event(event, event_info) })
+    }
+
     #[allow(clippy::useless_conversion)]
     pub fn rcl_publish_serialized_message(
         publisher: *const rcl_publisher_t,
@@ -1056,6 +1298,28 @@ impl MTSafeFn {
         unsafe { self::rmw_get_default_publisher_options() }
     }
 
+    pub fn rmw_get_implementation_identifier() -> *const ::std::os::raw::c_char {
+        unsafe { self::rmw_get_implementation_identifier() }
+    }
+
+    pub fn rmw_qos_profile_check_compatible(
+        publisher_profile: rmw_qos_profile_t,
+        subscription_profile: rmw_qos_profile_t,
+        compatibility: *mut rmw_qos_compatibility_type_t,
+        reason: *mut ::std::os::raw::c_char,
+        reason_size: usize,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe {
+            self::rmw_qos_profile_check_compatible(
+                publisher_profile,
+                subscription_profile,
+                compatibility,
+                reason,
+                reason_size,
+            )
+        })
+    }
+
     pub fn rcl_get_zero_initialized_subscription() -> rcl_subscription_t {
         unsafe { self::rcl_get_zero_initialized_subscription() }
     }
@@ -1072,6 +1336,86 @@ impl MTSafeFn {
         ret_val_to_err(unsafe { self::rcl_wait(wait_set, timeout) })
     }
 
+    // A wait set is only accessed by the selector owning it, so the functions
+    // below do not need the global lock.
+    pub fn rcl_wait_set_clear(wait_set: *mut rcl_wait_set_t) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_wait_set_clear(wait_set) })
+    }
+
+    pub fn rcl_wait_set_resize(
+        wait_set: *mut rcl_wait_set_t,
+        subscriptions_size: usize,
+        guard_conditions_size: usize,
+        timers_size: usize,
+        clients_size: usize,
+        services_size: usize,
+        events_size: usize,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe {
+            self::rcl_wait_set_resize(
+                wait_set,
+                subscriptions_size,
+                guard_conditions_size,
+                timers_size,
+                clients_size,
+                services_size,
+                events_size,
+            )
+        })
+    }
+
+    pub fn rcl_wait_set_add_subscription(
+        wait_set: *mut rcl_wait_set_t,
+        subscription: *const rcl_subscription_t,
+        index: *mut usize,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe {
+            self::rcl_wait_set_add_subscription(wait_set, subscription, index)
+        })
+    }
+
+    pub fn rcl_wait_set_add_guard_condition(
+        wait_set: *mut rcl_wait_set_t,
+        guard_condition: *const rcl_guard_condition_t,
+        index: *mut usize,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe {
+            self::rcl_wait_set_add_guard_condition(wait_set, guard_condition, index)
+        })
+    }
+
+    pub fn rcl_wait_set_add_client(
+        wait_set: *mut rcl_wait_set_t,
+        client: *const rcl_client_t,
+        index: *mut usize,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_wait_set_add_client(wait_set, client, index) })
+    }
+
+    pub fn rcl_wait_set_add_service(
+        wait_set: *mut rcl_wait_set_t,
+        service: *const rcl_service_t,
+        index: *mut usize,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_wait_set_add_service(wait_set, service, index) })
+    }
+
+    pub fn rcl_wait_set_add_timer(
+        wait_set: *mut rcl_wait_set_t,
+        timer: *const rcl_timer_t,
+        index: *mut usize,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_wait_set_add_timer(wait_set, timer, index) })
+    }
+
+    pub fn rcl_wait_set_add_event(
+        wait_set: *mut rcl_wait_set_t,
+        event: *const rcl_event_t,
+        index: *mut usize,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_wait_set_add_event(wait_set, event, index) })
+    }
+
     pub fn rcl_get_zero_initialized_guard_condition() -> rcl_guard_condition_t {
         unsafe { self::rcl_get_zero_initialized_guard_condition() }
     }
@@ -1107,6 +1451,54 @@ impl MTSafeFn {
         ret_val_to_err(unsafe { self::rcl_clock_get_now(clock, time_point_value) })
     }
 
+    pub fn rcl_get_zero_initialized_timer() -> rcl_timer_t {
+        unsafe { self::rcl_get_zero_initialized_timer() }
+    }
+
+    pub fn rcl_timer_call(timer: *mut rcl_timer_t) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_timer_call(timer) })
+    }
+
+    pub fn rcl_timer_cancel(timer: *mut rcl_timer_t) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_timer_cancel(timer) })
+    }
+
+    pub fn rcl_timer_reset(timer: *mut rcl_timer_t) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_timer_reset(timer) })
+    }
+
+    pub fn rcl_timer_is_canceled(timer: *const rcl_timer_t, is_canceled: *mut bool) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_timer_is_canceled(timer, is_canceled) })
+    }
+
+    pub fn rcl_timer_get_period(timer: *const rcl_timer_t, period: *mut i64) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_timer_get_period(timer, period) })
+    }
+
+    pub fn rcl_timer_get_time_until_next_call(
+        timer: *const rcl_timer_t,
+        time_until_next_call: *mut i64,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe {
+            self::rcl_timer_get_time_until_next_call(timer, time_until_next_call)
+        })
+    }
+
+    pub fn rcl_enable_ros_time_override(clock: *mut rcl_clock_t) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_enable_ros_time_override(clock) })
+    }
+
+    pub fn rcl_disable_ros_time_override(clock: *mut rcl_clock_t) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_disable_ros_time_override(clock) })
+    }
+
+    pub fn rcl_set_ros_time_override(
+        clock: *mut rcl_clock_t,
+        time_value: rcl_time_point_value_t,
+    ) -> Result<()> {
+        ret_val_to_err(unsafe { self::rcl_set_ros_time_override(clock, time_value) })
+    }
+
     pub fn rcl_action_get_zero_initialized_client() -> rcl_action_client_t {
         unsafe { self::rcl_action_get_zero_initialized_client() }
     }
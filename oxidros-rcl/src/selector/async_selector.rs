@@ -3,19 +3,39 @@ use crate::{
     action,
     context::Context,
     error::Result,
+    node::Node,
     service::{client::ClientData, server::ServerData},
     signal_handler,
     topic::subscriber::RCLSubscription,
 };
 use crossbeam_channel::{Receiver, Sender};
-use oxidros_core::selector::CallbackResult;
+use once_cell::sync::Lazy;
+use oxidros_core::{selector::CallbackResult, targets};
 use parking_lot::Mutex;
 use std::{
-    sync::{Arc, OnceLock},
+    collections::BTreeMap,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
     thread::{self, JoinHandle, yield_now},
+    time::Duration,
 };
 
-static SELECTOR_DATA: OnceLock<SelectorData> = OnceLock::new();
+/// Selector threads, one per context, keyed by the address of the context.
+/// A wait set can only wait for the entities of its own context.
+///
+/// A thread exits and removes its entry once no task has used it for
+/// [`IDLE_TIMEOUT`], releasing its context.
+static SELECTOR_DATA: Lazy<Mutex<BTreeMap<usize, Arc<SelectorData>>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+/// Selector threads which removed their entry, to be joined.
+static EXITED: Lazy<Mutex<Vec<JoinHandle<Result<()>>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// How long a selector thread without entities waits for commands before
+/// exiting.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(1);
 
 pub(crate) enum Command {
     Subscription(
@@ -54,6 +74,14 @@ pub(crate) enum Command {
         Box<dyn FnMut() -> CallbackResult + Send + Sync + 'static>,
     ),
     RemoveConditionVar(GuardCondition),
+    Graph(
+        Arc<Node>,
+        Box<dyn FnMut() -> CallbackResult + Send + Sync + 'static>,
+    ),
+    RemoveGraph(Arc<Node>),
+    /// One-shot timer, identified by a key from [`new_timer_key`].
+    Timer(u64, Duration, Box<dyn FnMut() + Send + Sync + 'static>),
+    RemoveTimer(u64),
     Halt,
 }
 
@@ -64,20 +92,54 @@ struct SelectorData {
 }
 
 pub(crate) fn halt() -> Result<()> {
-    if let Some(data) = SELECTOR_DATA.get() {
-        data.tx
-            .send(Command::Halt)
-            .map_err(|_| crate::error::Error::ChannelClosed)?;
-        data.cond.trigger()?;
+    let selectors: Vec<_> = SELECTOR_DATA.lock().values().cloned().collect();
 
-        yield_now();
+    // halt every selector even if some of them cannot be reached
+    let mut errors = Vec::new();
+    for data in &selectors {
+        if data.tx.send(Command::Halt).is_err() {
+            errors.push(crate::error::Error::ChannelClosed);
+            continue;
+        }
+        if let Err(e) = data.cond.trigger() {
+            errors.push(e);
+        }
+    }
 
+    yield_now();
+
+    for data in &selectors {
         if let Some(th) = data.th.lock().take() {
             let _ = th.join();
         }
     }
+    let exited = std::mem::take(&mut *EXITED.lock());
+    for th in exited {
+        let _ = th.join();
+    }
 
-    Ok(())
+    for e in errors.iter().skip(1) {
+        tracing::warn!(target: targets::SELECTOR, error = %e, "Failed to halt an async selector");
+    }
+    match errors.into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Join the selector threads which have exited.
+fn join_exited() {
+    let finished: Vec<_> = {
+        let mut exited = EXITED.lock();
+        let (finished, running) = std::mem::take(&mut *exited)
+            .into_iter()
+            .partition(|th: &JoinHandle<_>| th.is_finished());
+        *exited = running;
+        finished
+    };
+    for th in finished {
+        let _ = th.join();
+    }
 }
 
 pub(crate) fn send_command(context: &Arc<Context>, cmd: Command) -> Result<()> {
@@ -85,19 +147,28 @@ pub(crate) fn send_command(context: &Arc<Context>, cmd: Command) -> Result<()> {
         return halt();
     }
 
-    let data = SELECTOR_DATA.get_or_init(|| {
-        let (tx, rx) = crossbeam_channel::unbounded();
-        let guard =
-            super::guard_condition::GuardCondition::new(context.clone()).expect("guard cond");
-        let ctx = context.clone();
-        let guard2 = guard.clone();
-        let th = thread::spawn(move || select(ctx, guard2, rx));
-        SelectorData {
-            tx,
-            th: Mutex::new(Some(th)),
-            cond: guard,
-        }
-    });
+    join_exited();
+
+    // send while holding the lock, so that the thread does not exit in between
+    let mut selectors = SELECTOR_DATA.lock();
+    let data = selectors
+        .entry(Arc::as_ptr(context) as usize)
+        .or_insert_with(|| {
+            let (tx, rx) = crossbeam_channel::unbounded();
+            let guard =
+                super::guard_condition::GuardCondition::new(context.clone()).expect("guard cond");
+            let ctx = context.clone();
+            let guard2 = guard.clone();
+            let th = thread::Builder::new()
+                .name("oxidros-async-selector".into())
+                .spawn(move || select(ctx, guard2, rx))
+                .expect("spawn async selector thread");
+            Arc::new(SelectorData {
+                tx,
+                th: Mutex::new(Some(th)),
+                cond: guard,
+            })
+        });
 
     data.tx
         .send(cmd)
@@ -105,8 +176,47 @@ pub(crate) fn send_command(context: &Arc<Context>, cmd: Command) -> Result<()> {
     data.cond.trigger()
 }
 
+/// Whether no task waits for an entity of `selector`.
+fn is_idle(selector: &super::Selector) -> bool {
+    selector.subscriptions.is_empty()
+        && selector.services.is_empty()
+        && selector.clients.is_empty()
+        && selector.action_servers.is_empty()
+        && selector.action_clients.is_empty()
+        && selector.graphs.is_empty()
+        && selector.events.is_empty()
+        && selector.timer.is_empty()
+        && selector.rcl_timers.is_empty()
+        // the guard conditions of the selector itself have no handler
+        && selector.cond.values().all(|h| h.handler.is_none())
+}
+
+/// Remove the entry of the thread of the context at `key` if no command is
+/// pending, in which case the thread must exit.
+fn remove_if_idle(key: usize, rx: &Receiver<Command>) -> bool {
+    let mut selectors = SELECTOR_DATA.lock();
+    if !rx.is_empty() {
+        return false;
+    }
+    if let Some(data) = selectors.remove(&key)
+        && let Some(th) = data.th.lock().take()
+    {
+        EXITED.lock().push(th);
+    }
+    true
+}
+
+/// Get a key for a [`Command::Timer`], unique across the selector threads.
+pub(crate) fn new_timer_key() -> u64 {
+    static NEXT_TIMER_KEY: AtomicU64 = AtomicU64::new(0);
+    NEXT_TIMER_KEY.fetch_add(1, Ordering::Relaxed)
+}
+
 fn select(context: Arc<Context>, guard: GuardCondition, rx: Receiver<Command>) -> Result<()> {
+    let key = Arc::as_ptr(&context) as usize;
     let mut selector = super::Selector::new(context)?;
+    // IDs in `selector` of the timers, by key
+    let mut timers = BTreeMap::new();
 
     selector.add_guard_condition(&guard, None, false);
 
@@ -144,11 +254,41 @@ fn select(context: Arc<Context>, guard: GuardCondition, rx: Receiver<Command>) -
                 Command::RemoveActionServer(s) => selector.remove_action_server_data(&s),
                 Command::ConditionVar(c, h) => selector.add_guard_condition(&c, Some(h), true),
                 Command::RemoveConditionVar(c) => selector.remove_guard_condition(&c),
+                Command::Graph(n, h) => {
+                    let _ = selector.add_graph_condition(n, Some(h), true);
+                }
+                Command::RemoveGraph(n) => {
+                    // other tasks may still wait for changes of this graph
+                    if n.graph_waiters.lock().is_empty() {
+                        selector.remove_graph_condition(&n);
+                    }
+                }
+                Command::Timer(key, t, h) => {
+                    timers.insert(key, selector.add_timer(t, h));
+                }
+                Command::RemoveTimer(key) => {
+                    // a no-op if the timer has already fired
+                    if let Some(id) = timers.remove(&key) {
+                        selector.remove_timer(id);
+                    }
+                }
                 Command::Halt => return Ok(()),
             }
         }
 
-        if selector.wait().is_err() && signal_handler::is_halt() {
+        let woken = if is_idle(&selector) {
+            selector.wait_timeout(IDLE_TIMEOUT)
+        } else {
+            selector.wait().map(|()| true)
+        };
+        if let Ok(false) = woken
+            && remove_if_idle(key, &rx)
+        {
+            tracing::debug!(target: targets::SELECTOR, "Idle async selector exited");
+            return Ok(());
+        }
+
+        if woken.is_err() && signal_handler::is_halt() {
             for (_, h) in selector.subscriptions.iter_mut() {
                 if let Some(handler) = &mut h.handler {
                     (*handler)();
@@ -173,6 +313,12 @@ fn select(context: Arc<Context>, guard: GuardCondition, rx: Receiver<Command>) -
                 }
             }
 
+            for (_, h) in selector.graphs.iter_mut() {
+                if let Some(handler) = &mut h.handler {
+                    (*handler)();
+                }
+            }
+
             return Ok(());
         }
     }
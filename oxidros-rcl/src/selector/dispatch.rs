@@ -0,0 +1,87 @@
+//! Detection of service calls which would deadlock in callbacks.
+//!
+//! A selector runs its callbacks on the thread calling [`Selector::wait`], so
+//! while a callback runs, none of the servers of the selector can answer. A
+//! callback which calls one of these services and blocks waiting for the
+//! response, e.g. with [`ClientRecv::recv_timeout`] on another selector, would
+//! never get it.
+//!
+//! Selectors record the servers they hold while dispatching callbacks, per
+//! thread, and blocking waits of clients of the same context for one of these
+//! services fail with [`Error::WouldDeadlock`] instead.
+//!
+//! To call such a service from a callback, send the request and take the
+//! response in a later callback with [`ClientRecv::try_recv`], or serve the
+//! service from a selector spun by another thread.
+//!
+//! [`Selector::wait`]: super::Selector::wait
+//! [`ClientRecv::recv_timeout`]: crate::service::client::ClientRecv::recv_timeout
+//! [`ClientRecv::try_recv`]: crate::service::client::ClientRecv::try_recv
+
+use crate::{
+    error::Result,
+    rcl,
+    service::{client::ClientData, server::ServerData},
+};
+use oxidros_core::{Error, targets};
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+/// Servers of a selector, shared with the dispatches of its callbacks.
+pub(crate) type Servers = Rc<[Arc<ServerData>]>;
+
+thread_local! {
+    /// Servers of the selectors dispatching callbacks on this thread, one
+    /// entry per nested dispatch.
+    static DISPATCHING: RefCell<Vec<Servers>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Servers of a selector recorded as blocked until dropped.
+pub(crate) struct Dispatching(());
+
+impl Dispatching {
+    /// Record `servers` as blocked while the callbacks of their selector run.
+    pub(crate) fn enter(servers: Servers) -> Self {
+        DISPATCHING.with(|dispatching| dispatching.borrow_mut().push(servers));
+        Dispatching(())
+    }
+}
+
+impl Drop for Dispatching {
+    fn drop(&mut self) {
+        DISPATCHING.with(|dispatching| dispatching.borrow_mut().pop());
+    }
+}
+
+/// Fail if a blocking wait for a response of `client` would deadlock,
+/// because the server of its service is blocked in a callback of this thread.
+pub(crate) fn check_wait(client: &ClientData) -> Result<()> {
+    DISPATCHING.with(|dispatching| {
+        let dispatching = dispatching.borrow();
+        let context = client.node.context.as_ptr();
+        let mut servers = dispatching
+            .iter()
+            .flat_map(|servers| servers.iter())
+            .filter(|server| server.node.context.as_ptr() == context)
+            .peekable();
+        if servers.peek().is_none() {
+            return Ok(());
+        }
+
+        let guard = rcl::MT_UNSAFE_FN.lock();
+        let service = guard.rcl_client_get_service_name(&client.client)?;
+        let blocked = servers.any(|server| {
+            guard
+                .rcl_service_get_service_name(&server.service)
+                .is_ok_and(|name| name == service)
+        });
+        if blocked {
+            tracing::error!(
+                target: targets::SELECTOR,
+                service = %service,
+                "Waiting for a response of a server blocked in a callback"
+            );
+            return Err(Error::WouldDeadlock { service });
+        }
+        Ok(())
+    })
+}
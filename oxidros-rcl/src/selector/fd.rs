@@ -0,0 +1,164 @@
+//! File descriptors waited on by a [`Selector`](super::Selector).
+//!
+//! rcl wait sets only hold ROS entities, so each file descriptor is polled by
+//! a background thread which triggers a guard condition of the selector when
+//! it is ready. The thread then waits for the handler to run on the selector
+//! before polling again, so that a file descriptor which stays ready wakes the
+//! selector once per handler call.
+//!
+//! With the `uring` feature on Linux, all file descriptors are polled by a
+//! single io_uring reactor thread instead (see [`super::uring`]), falling back
+//! to threads if io_uring is not available.
+
+use super::guard_condition::GuardCondition;
+use crate::{context::Context, error::Result};
+use oxidros_core::{
+    selector::{FdInterest, FdReadiness, check_fd, poll_fds},
+    targets,
+};
+use parking_lot::Mutex;
+use std::{
+    os::fd::RawFd,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// Interval at which the thread checks whether it must stop.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Background polling of a file descriptor.
+pub(crate) struct FdWatcher {
+    pub(crate) cond: GuardCondition,
+    ready: Arc<Mutex<Option<FdReadiness>>>,
+    backend: Backend,
+}
+
+enum Backend {
+    /// Thread polling the file descriptor.
+    Thread {
+        rearm: mpsc::Sender<()>,
+        stop: Arc<AtomicBool>,
+        thread: Option<JoinHandle<()>>,
+    },
+    /// File descriptor registered in the io_uring reactor.
+    #[cfg(all(feature = "uring", target_os = "linux"))]
+    Uring {
+        reactor: &'static super::uring::Reactor,
+        key: u64,
+    },
+}
+
+impl FdWatcher {
+    pub(crate) fn new(context: Arc<Context>, fd: RawFd, interest: FdInterest) -> Result<Self> {
+        check_fd(fd)?;
+        let cond = GuardCondition::new(context)?;
+        let ready = Arc::new(Mutex::new(None));
+
+        #[cfg(all(feature = "uring", target_os = "linux"))]
+        if let Some(reactor) = super::uring::Reactor::global() {
+            let key = reactor.add(super::uring::Watch {
+                fd,
+                interest,
+                cond: cond.clone(),
+                ready: ready.clone(),
+            });
+            return Ok(FdWatcher {
+                cond,
+                ready,
+                backend: Backend::Uring { reactor, key },
+            });
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let (rearm, rearmed) = mpsc::channel();
+
+        let thread = {
+            let cond = cond.clone();
+            let ready = ready.clone();
+            let stop = stop.clone();
+            thread::Builder::new()
+                .name(format!("oxidros-fd-{fd}"))
+                .spawn(move || watch(fd, interest, &cond, &ready, &stop, &rearmed))?
+        };
+
+        Ok(FdWatcher {
+            cond,
+            ready,
+            backend: Backend::Thread {
+                rearm,
+                stop,
+                thread: Some(thread),
+            },
+        })
+    }
+
+    /// Take the reported readiness, and poll the file descriptor again.
+    pub(crate) fn take(&self) -> Option<FdReadiness> {
+        let readiness = self.ready.lock().take();
+        if readiness.is_some() {
+            match &self.backend {
+                Backend::Thread { rearm, .. } => {
+                    let _ = rearm.send(());
+                }
+                #[cfg(all(feature = "uring", target_os = "linux"))]
+                Backend::Uring { reactor, key } => reactor.rearm(*key),
+            }
+        }
+        readiness
+    }
+}
+
+impl Drop for FdWatcher {
+    fn drop(&mut self) {
+        match &mut self.backend {
+            Backend::Thread { stop, thread, .. } => {
+                stop.store(true, Ordering::Relaxed);
+                if let Some(thread) = thread.take() {
+                    let _ = thread.join();
+                }
+            }
+            #[cfg(all(feature = "uring", target_os = "linux"))]
+            Backend::Uring { reactor, key } => reactor.remove(*key),
+        }
+    }
+}
+
+fn watch(
+    fd: RawFd,
+    interest: FdInterest,
+    cond: &GuardCondition,
+    ready: &Mutex<Option<FdReadiness>>,
+    stop: &AtomicBool,
+    rearmed: &mpsc::Receiver<()>,
+) {
+    while !stop.load(Ordering::Relaxed) {
+        let readiness = match poll_fds(&[(fd, interest)], Some(STOP_CHECK_INTERVAL)) {
+            Ok(readiness) if readiness[0].is_ready() => readiness[0],
+            Ok(_) => continue,
+            Err(e) => {
+                tracing::error!(target: targets::SELECTOR, fd, error = %e, "Failed to poll");
+                return;
+            }
+        };
+
+        *ready.lock() = Some(readiness);
+        if let Err(e) = cond.trigger() {
+            tracing::error!(target: targets::SELECTOR, fd, error = %e, "Failed to trigger");
+            return;
+        }
+
+        // Wait for the handler before polling again
+        loop {
+            match rearmed.recv_timeout(STOP_CHECK_INTERVAL) {
+                Ok(()) => break,
+                Err(RecvTimeoutError::Timeout) if !stop.load(Ordering::Relaxed) => (),
+                Err(_) => return,
+            }
+        }
+    }
+}
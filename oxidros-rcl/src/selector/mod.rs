@@ -50,15 +50,20 @@
 //! }
 //! ```
 
-use self::guard_condition::{GuardCondition, RCLGuardCondition};
+use self::{
+    guard_condition::{GuardCondition, RCLGuardCondition},
+    timer::RCLTimer,
+};
 
 use crate::{
     PhantomUnsend, PhantomUnsync,
     action::{self, SendGoalServiceRequest, handle::GoalHandle},
+    clock::Clock,
     context::Context,
     error::Result,
     get_allocator,
     msg::{ActionMsg, GetUUID, ServiceMsg, TypeSupport, interfaces::action_msgs::msg::GoalInfo},
+    node::Node,
     parameter::ParameterServer,
     rcl::{self, rcl_action_client_t},
     service::{
@@ -66,23 +71,29 @@ use crate::{
         server::{Server, ServerData},
     },
     signal_handler::{self, Signaled},
-    topic::subscriber::{RCLSubscription, Subscriber},
+    topic::{
+        publisher::{PublisherEvent, RCLPublisherEvent},
+        subscriber::{RCLSubscription, Subscriber},
+    },
 };
 use oxidros_core::{
     Error, RclError,
     delta_list::DeltaList,
     message::Message,
+    qos::QosEventStatus,
     selector::{
-        ActionHandler, CallbackResult, ConditionHandler, ParameterCallback, ServerCallback,
+        ActionHandler, CallbackResult, ConditionHandler, PanicContainment, PanicPolicy,
+        ParameterCallback, ServerCallback,
     },
-    targets,
+    spans, targets,
+    time::{ClockType, VirtualClock},
 };
 use std::{
     cell::Cell,
     collections::{BTreeMap, BTreeSet},
     ptr::null_mut,
     rc::Rc,
-    sync::Arc,
+    sync::{Arc, Weak},
     time::{Duration, SystemTime},
 };
 
@@ -90,7 +101,18 @@ use crate::rcl::rcl_action_server_t;
 use parking_lot::Mutex;
 
 pub(crate) mod async_selector;
+pub(crate) mod dispatch;
+#[cfg(unix)]
+pub(crate) mod fd;
 pub(crate) mod guard_condition;
+pub(crate) mod timer;
+#[cfg(all(feature = "uring", target_os = "linux"))]
+pub(crate) mod uring;
+
+pub use oxidros_core::selector::{FdInterest, FdReadiness, MissedTickPolicy, TimerTick};
+
+/// Default time a callback keeps draining its subscriber or server after a wakeup.
+pub const DEFAULT_DRAIN_BUDGET: Duration = Duration::from_millis(1);
 
 struct ActionClientConditionHandler {
     client: *const rcl_action_client_t,
@@ -108,11 +130,143 @@ struct ActionServerConditionHandler {
 }
 
 enum TimerType {
-    WallTimer(Rc<String>, Duration),
+    WallTimer(WallTimer),
     OneShot,
 }
 
-#[derive(Debug)]
+/// Periodic timer scheduled on the timeline of the selector.
+struct WallTimer {
+    name: Rc<String>,
+    period: Duration,
+    policy: MissedTickPolicy,
+    /// Scheduled time of the next tick.
+    next: SystemTime,
+    /// Tick read by the handler.
+    tick: Rc<Cell<TimerTick>>,
+}
+
+impl WallTimer {
+    /// Call `handler` for the ticks due at `now` and schedule the next one.
+    ///
+    /// Returns [`CallbackResult::Remove`] as soon as `handler` does.
+    fn fire(
+        &mut self,
+        now: SystemTime,
+        handler: &mut Box<dyn FnMut() -> CallbackResult>,
+    ) -> CallbackResult {
+        let late = now.duration_since(self.next).unwrap_or_default();
+        let missed = if self.period.is_zero() {
+            0
+        } else {
+            (late.as_nanos() / self.period.as_nanos()) as u64
+        };
+
+        if missed > 0 {
+            tracing::debug!(
+                target: targets::SELECTOR,
+                timer_name = %self.name,
+                missed = missed,
+                policy = ?self.policy,
+                "Wall timer missed ticks"
+            );
+        }
+
+        match self.policy {
+            MissedTickPolicy::Burst => {
+                for i in 0..=missed {
+                    self.tick.set(TimerTick {
+                        expected: self.next,
+                        missed: missed - i,
+                    });
+                    if handler() == CallbackResult::Remove {
+                        return CallbackResult::Remove;
+                    }
+                    self.next += self.period;
+                }
+                CallbackResult::Ok
+            }
+            MissedTickPolicy::Skip => {
+                self.next += self.periods(missed);
+                self.tick.set(TimerTick {
+                    expected: self.next,
+                    missed,
+                });
+                let result = handler();
+                self.next += self.period;
+                result
+            }
+            MissedTickPolicy::Delay => {
+                self.tick.set(TimerTick {
+                    expected: self.next,
+                    missed,
+                });
+                let result = handler();
+                self.next = now + self.period;
+                result
+            }
+        }
+    }
+
+    fn periods(&self, n: u64) -> Duration {
+        self.period
+            .saturating_mul(u32::try_from(n).unwrap_or(u32::MAX))
+    }
+}
+
+struct RCLTimerHandler {
+    id: u64,
+    timer: RCLTimer,
+    handler: Box<dyn FnMut()>,
+    /// If true, the timer is a wall timer on the steady clock of the selector.
+    wall: bool,
+}
+
+/// Clock of the timeline of the selector timers.
+enum TimerClock {
+    /// The system time.
+    System,
+    /// Manually advanced time, mapped to the timeline from `origin` on.
+    Virtual {
+        clock: VirtualClock,
+        /// Time of the timeline when the clock was set.
+        origin: SystemTime,
+        /// Time of the clock when it was set.
+        start: Duration,
+    },
+}
+
+impl TimerClock {
+    fn now(&self) -> SystemTime {
+        match self {
+            TimerClock::System => SystemTime::now(),
+            TimerClock::Virtual {
+                clock,
+                origin,
+                start,
+            } => *origin + clock.now().saturating_sub(*start),
+        }
+    }
+}
+
+/// Keeps a subscriber registered in a selector, see [`Selector::add_scoped_subscriber`].
+///
+/// Dropping the handle, from any thread, wakes the selector up, which
+/// removes and destroys the subscriber in its next [`Selector::wait`].
+pub struct SubscriptionHandle {
+    _scope: Arc<Scope>,
+}
+
+struct Scope {
+    wake: GuardCondition,
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let _ = self.wake.trigger();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct EntitySize {
     subscriptions: usize,
     guard_condititons: usize,
@@ -138,16 +292,38 @@ pub struct Selector {
     param_server: Option<ParameterServer>,
     timer: DeltaList<(ConditionHandler<TimerType>, u64)>,
     base_time: SystemTime,
+    timer_clock: TimerClock,
+    rcl_timers: BTreeMap<*const rcl::rcl_timer_t, RCLTimerHandler>,
+    steady_clock: Option<Arc<Mutex<Clock>>>,
     signal_cond: GuardCondition,
+    /// Triggered when a [`SubscriptionHandle`] is dropped.
+    scope_cond: GuardCondition,
+    /// Subscriptions added by [`Selector::add_scoped_subscriber`].
+    scoped: Vec<(Weak<Scope>, Weak<RCLSubscription>)>,
     wait_set: rcl::rcl_wait_set_t,
+    /// Capacity of `wait_set`, which is only resized when the entities change.
+    wait_set_size: Option<EntitySize>,
+    /// Reused buffer of the rcl timers ready after a wait.
+    ready_timers: Vec<*const rcl::rcl_timer_t>,
     services: BTreeMap<*const rcl::rcl_service_t, ConditionHandler<Arc<ServerData>>>,
+    /// Servers of `services` recorded while dispatching, rebuilt when they change.
+    dispatch_servers: Option<dispatch::Servers>,
     clients: BTreeMap<*const rcl::rcl_client_t, ConditionHandler<Arc<ClientData>>>,
     subscriptions: BTreeMap<*const rcl::rcl_subscription_t, ConditionHandler<Arc<RCLSubscription>>>,
     action_servers: BTreeMap<*const rcl::rcl_action_server_t, Vec<ActionServerConditionHandler>>,
     action_clients: BTreeMap<*const rcl::rcl_action_client_t, ActionClientConditionHandler>,
     cond: BTreeMap<*const rcl::rcl_guard_condition_t, ConditionHandler<Arc<RCLGuardCondition>>>,
+    graphs: BTreeMap<*const rcl::rcl_guard_condition_t, ConditionHandler<Arc<Node>>>,
+    events: BTreeMap<*const rcl::rcl_event_t, ConditionHandler<Arc<RCLPublisherEvent>>>,
+    /// File descriptors added by [`Selector::add_fd`], with their guard conditions in `cond`.
+    #[cfg(unix)]
+    fds: BTreeMap<u64, Rc<fd::FdWatcher>>,
+    #[cfg(unix)]
+    fd_id: u64,
     timer_ids: BTreeSet<u64>,
     timer_id: u64,
+    drain_budget: Duration,
+    panics: PanicContainment,
     context: Arc<Context>,
 
     _unused: (PhantomUnsync, PhantomUnsend),
@@ -173,20 +349,37 @@ impl Selector {
         }
 
         let signal_cond = GuardCondition::new(context.clone())?;
+        let scope_cond = GuardCondition::new(context.clone())?;
         let mut selector = Selector {
             param_server: None,
             timer: DeltaList::Nil,
             base_time: SystemTime::now(),
+            timer_clock: TimerClock::System,
+            rcl_timers: Default::default(),
+            steady_clock: None,
             signal_cond: signal_cond.clone(),
+            scope_cond: scope_cond.clone(),
+            scoped: Vec::new(),
             wait_set,
+            wait_set_size: None,
+            ready_timers: Vec::new(),
             subscriptions: Default::default(),
             services: Default::default(),
+            dispatch_servers: None,
             clients: Default::default(),
             action_servers: Default::default(),
             action_clients: Default::default(),
             cond: Default::default(),
+            graphs: Default::default(),
+            events: Default::default(),
+            #[cfg(unix)]
+            fds: Default::default(),
+            #[cfg(unix)]
+            fd_id: 0,
             timer_ids: Default::default(),
             timer_id: 0,
+            drain_budget: DEFAULT_DRAIN_BUDGET,
+            panics: PanicContainment::default(),
 
             context,
 
@@ -194,6 +387,7 @@ impl Selector {
         };
 
         selector.add_guard_condition(&signal_cond, None, false);
+        selector.add_guard_condition(&scope_cond, None, false);
         signal_handler::register_guard_condition(signal_cond);
 
         tracing::debug!(
@@ -204,6 +398,52 @@ impl Selector {
         Ok(selector)
     }
 
+    /// Set how long a callback keeps taking queued messages or requests after a
+    /// wakeup before yielding to the other entities of the selector.
+    ///
+    /// At least one message is handled per wakeup, so `Duration::ZERO` handles
+    /// exactly one, favoring fairness, while a longer budget favors the
+    /// throughput of high-rate topics. The default is [`DEFAULT_DRAIN_BUDGET`].
+    ///
+    /// The budget applies to the subscribers and servers added afterwards.
+    pub fn set_drain_budget(&mut self, budget: Duration) {
+        self.drain_budget = budget;
+    }
+
+    /// Time a callback keeps draining its entity after a wakeup.
+    pub fn drain_budget(&self) -> Duration {
+        self.drain_budget
+    }
+
+    /// Set what happens when a callback panics.
+    ///
+    /// The panic is always caught before it unwinds through the rcl frames of
+    /// the selector, and the entity of the callback is removed; see
+    /// [`PanicPolicy`] for what the following calls of [`Selector::wait`] do.
+    pub fn set_panic_policy(&mut self, policy: PanicPolicy) {
+        self.panics.set_policy(policy);
+    }
+
+    /// What happens when a callback panics.
+    pub fn panic_policy(&self) -> PanicPolicy {
+        self.panics.policy()
+    }
+
+    /// Wrap the callback of `entity` to catch its panics, removing the entity
+    /// if it panicked.
+    fn contain(
+        &self,
+        entity: String,
+        mut handler: Box<dyn FnMut() -> CallbackResult>,
+    ) -> Box<dyn FnMut() -> CallbackResult> {
+        let panics = self.panics.clone();
+        Box::new(move || {
+            panics
+                .call(&entity, &mut handler)
+                .unwrap_or(CallbackResult::Remove)
+        })
+    }
+
     /// Register a subscriber with callback function.
     /// The callback function will be invoked when arriving data.
     ///
@@ -238,7 +478,10 @@ impl Selector {
         let sub = subscriber.subscription.clone();
         let context_ptr = subscriber.subscription.node.context.as_ptr();
 
-        let topic_name = subscriber.fully_qualified_topic_name().ok();
+        let topic_name = subscriber
+            .fully_qualified_topic_name()
+            .map(|name| name.into_owned())
+            .unwrap_or_default();
 
         if self.context.as_ptr() != context_ptr {
             return false;
@@ -246,17 +489,26 @@ impl Selector {
 
         tracing::debug!(
             target: targets::SELECTOR,
-            topic = ?topic_name,
+            topic = %topic_name,
             "Added subscriber"
         );
 
+        let entity = format!("subscriber {topic_name}");
+        let budget = self.drain_budget;
         let f = move || {
             let start = SystemTime::now();
-            let dur = Duration::from_millis(1);
+            let dur = budget;
 
             loop {
                 match subscriber.try_recv() {
                     Ok(Some(n)) => {
+                        let span = tracing::debug_span!(
+                            target: targets::SELECTOR,
+                            spans::SUBSCRIBER_CALLBACK,
+                            topic = %topic_name,
+                            seq = n.info.sequence_number,
+                        );
+                        let _enter = span.enter();
                         handler(n);
                     }
                     Ok(None) => return CallbackResult::Ok,
@@ -280,10 +532,85 @@ impl Selector {
             }
         };
 
-        self.add_rcl_subscription(sub, Some(Box::new(f)), false);
+        let f = self.contain(entity, Box::new(f));
+        self.add_rcl_subscription(sub, Some(f), false);
         true
     }
 
+    /// Register a subscriber for as long as the returned handle lives.
+    ///
+    /// This is the same as [`Selector::add_subscriber`], except that the
+    /// subscriber is removed and destroyed once the [`SubscriptionHandle`] is
+    /// dropped, so that another thread can tear it down while the selector
+    /// keeps running.
+    ///
+    /// Returns `None` if the subscriber was created by a different context.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oxidros_rcl::{msg::common_interfaces::std_msgs, node::Node, selector::Selector};
+    /// use std::sync::Arc;
+    ///
+    /// fn subscribe_for_a_while(selector: &mut Selector, node: Arc<Node>) {
+    ///     let subscriber = node
+    ///         .create_subscriber::<std_msgs::msg::Bool>("scoped_topic", None)
+    ///         .unwrap();
+    ///     let handle = selector
+    ///         .add_scoped_subscriber(subscriber, Box::new(|_msg| ()))
+    ///         .unwrap();
+    ///
+    ///     // ...
+    ///
+    ///     // Unsubscribe.
+    ///     drop(handle);
+    /// }
+    /// ```
+    pub fn add_scoped_subscriber<T: TypeSupport + 'static>(
+        &mut self,
+        subscriber: Subscriber<T>,
+        handler: Box<dyn FnMut(Message<T>)>,
+    ) -> Option<SubscriptionHandle> {
+        let subscription = Arc::downgrade(&subscriber.subscription);
+        if !self.add_subscriber(subscriber, handler) {
+            return None;
+        }
+
+        let scope = Arc::new(Scope {
+            wake: self.scope_cond.clone(),
+        });
+        self.scoped.push((Arc::downgrade(&scope), subscription));
+        Some(SubscriptionHandle { _scope: scope })
+    }
+
+    /// Remove the subscriptions whose [`SubscriptionHandle`] was dropped.
+    fn remove_unscoped(&mut self) {
+        let subscriptions = &mut self.subscriptions;
+        self.scoped.retain(|(scope, subscription)| {
+            // already removed by its callback
+            let Some(subscription) = subscription.upgrade() else {
+                return false;
+            };
+            if scope.strong_count() > 0 {
+                return true;
+            }
+
+            // the key is only valid while the subscription is alive
+            let key: *const rcl::rcl_subscription_t = subscription.subscription.as_ref();
+            if subscriptions
+                .get(&key)
+                .is_some_and(|h| Arc::ptr_eq(&h.event, &subscription))
+            {
+                subscriptions.remove(&key);
+                tracing::debug!(
+                    target: targets::SELECTOR,
+                    "Removed scoped subscriber"
+                );
+            }
+            false
+        });
+    }
+
     pub(crate) fn add_rcl_subscription(
         &mut self,
         subscription: Arc<RCLSubscription>,
@@ -307,16 +634,19 @@ impl Selector {
     ) {
         let params = param_server.params.clone();
 
-        self.add_guard_condition(
-            &param_server.cond_callback,
-            Some(Box::new(move || {
+        let f = self.contain(
+            "parameter server".to_string(),
+            Box::new(move || {
+                let _enter =
+                    tracing::debug_span!(target: targets::SELECTOR, spans::PARAMETER_CALLBACK)
+                        .entered();
                 let mut guard = params.write();
                 let updated = guard.take_updated();
                 handler(&mut guard, updated);
                 CallbackResult::Ok
-            })),
-            false,
+            }),
         );
+        self.add_guard_condition(&param_server.cond_callback, Some(f), false);
         self.param_server = Some(param_server);
     }
 
@@ -358,14 +688,27 @@ impl Selector {
     ) -> bool {
         let context_ptr = server.data.node.context.as_ptr();
         let srv = server.data.clone();
+        let service_name = server
+            .fully_qualified_service_name()
+            .map(|name| name.into_owned())
+            .unwrap_or_default();
 
+        let entity = format!("server {service_name}");
+        let budget = self.drain_budget;
         let f = move || {
             let start = SystemTime::now();
-            let dur = Duration::from_millis(1);
+            let dur = budget;
 
             loop {
                 match server.try_recv() {
                     Ok(Some(service_req)) => {
+                        let span = tracing::debug_span!(
+                            target: targets::SELECTOR,
+                            spans::SERVICE_CALLBACK,
+                            service = %service_name,
+                            seq = service_req.request.info.sequence_number,
+                        );
+                        let _enter = span.enter();
                         let result = handler(service_req.request);
                         match service_req.sender.send(&result) {
                             Ok(()) => {}
@@ -403,7 +746,8 @@ impl Selector {
         };
 
         if self.context.as_ptr() == context_ptr {
-            self.add_server_data(srv, Some(Box::new(f)), false);
+            let f = self.contain(entity, Box::new(f));
+            self.add_server_data(srv, Some(f), false);
             true
         } else {
             false
@@ -418,6 +762,7 @@ impl Selector {
     ) {
         let cloned = server.clone();
         if self.context.as_ptr() == server.node.context.as_ptr() {
+            self.dispatch_servers = None;
             self.services.insert(
                 &server.service,
                 ConditionHandler {
@@ -502,12 +847,13 @@ impl Selector {
         A: Fn(GoalHandle<T>) + 'static,
         CR: Fn(&GoalInfo) -> bool + 'static,
     {
+        let budget = self.drain_budget;
         let server = Arc::new(Mutex::new(server));
         let goal = {
             let server = server.clone();
             move || {
                 let start = SystemTime::now();
-                let dur = Duration::from_millis(1);
+                let dur = budget;
                 let mut server = server.lock();
 
                 loop {
@@ -556,7 +902,7 @@ impl Selector {
             let server = server.clone();
             move || {
                 let start = SystemTime::now();
-                let dur = Duration::from_millis(1);
+                let dur = budget;
                 let mut server = server.lock();
 
                 loop {
@@ -605,7 +951,7 @@ impl Selector {
             let server = server.clone();
             move || {
                 let start = SystemTime::now();
-                let dur = Duration::from_millis(1);
+                let dur = budget;
                 let mut server = server.lock();
 
                 loop {
@@ -645,10 +991,12 @@ impl Selector {
         let server = server.lock();
         let context_ptr = server.data.node.context.as_ptr();
         if self.context.as_ptr() == context_ptr {
+            let goal = self.contain("action server goal".to_string(), Box::new(goal));
+            let cancel = self.contain("action server cancel".to_string(), Box::new(cancel));
             self.add_action_server_data(
                 server.data.clone(),
-                Some(Box::new(goal)),
-                Some(Box::new(cancel)),
+                Some(goal),
+                Some(cancel),
                 Some(Box::new(result)),
             );
             true
@@ -745,12 +1093,165 @@ impl Selector {
         self.cond.remove(&(cond.cond.cond.as_ref() as *const _));
     }
 
+    /// Wait for changes of the graph seen by `node`.
+    pub(crate) fn add_graph_condition(
+        &mut self,
+        node: Arc<Node>,
+        handler: Option<Box<dyn FnMut() -> CallbackResult>>,
+        is_once: bool,
+    ) -> Result<()> {
+        let cond = {
+            let guard = rcl::MT_UNSAFE_FN.lock();
+            guard.rcl_node_get_graph_guard_condition(node.as_ptr())?
+        };
+        self.graphs.insert(
+            cond,
+            ConditionHandler {
+                event: node,
+                handler,
+                is_once,
+            },
+        );
+        Ok(())
+    }
+
+    pub(crate) fn remove_graph_condition(&mut self, node: &Arc<Node>) {
+        self.graphs.retain(|_, h| !Arc::ptr_eq(&h.event, node));
+    }
+
+    /// Register a QoS event of a publisher.
+    /// The callback function will be invoked with the status of the event when it occurs.
+    ///
+    /// # Error
+    ///
+    /// If a selector takes an event created by a different context,
+    /// `add_publisher_event()` must fail.
+    pub fn add_publisher_event(
+        &mut self,
+        event: PublisherEvent,
+        mut handler: Box<dyn FnMut(QosEventStatus)>,
+    ) -> bool {
+        let event = event.event;
+        if self.context.as_ptr() != event.node().context.as_ptr() {
+            return false;
+        }
+
+        tracing::debug!(
+            target: targets::SELECTOR,
+            kind = ?event.kind(),
+            "Added publisher event"
+        );
+
+        let entity = format!("publisher event {:?}", event.kind());
+        let ev = event.clone();
+        let f = move || match ev.take() {
+            Ok(status) => {
+                handler(status);
+                CallbackResult::Ok
+            }
+            Err(e) => {
+                tracing::error!(
+                    target: targets::SELECTOR,
+                    error = %e,
+                    "Failed to take a publisher event"
+                );
+                CallbackResult::Remove
+            }
+        };
+
+        let f = self.contain(entity, Box::new(f));
+        let key: *const rcl::rcl_event_t = &event.event;
+        self.events.insert(
+            key,
+            ConditionHandler {
+                event,
+                handler: Some(f),
+                is_once: false,
+            },
+        );
+        true
+    }
+
+    /// Wait on a file descriptor, e.g. of a serial port or a CAN socket.
+    /// The `handler` is called with the readiness of `fd` when one of the events
+    /// of `interest` occurs, as long as it stays ready.
+    ///
+    /// The file descriptor is polled by a background thread, which wakes the
+    /// selector through a guard condition. The selector does not take
+    /// ownership of `fd`, which must stay open until it is removed.
+    ///
+    /// # Return Value
+    ///
+    /// The identifier of the file descriptor.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `fd` is not open.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use oxidros_rcl::selector::{FdInterest, Selector};
+    /// use std::{io::Read, os::fd::AsRawFd};
+    ///
+    /// fn add_serial_port(selector: &mut Selector, mut port: std::fs::File) {
+    ///     let fd = port.as_raw_fd();
+    ///     selector
+    ///         .add_fd(
+    ///             fd,
+    ///             FdInterest::READABLE,
+    ///             Box::new(move |_readiness| {
+    ///                 let mut buf = [0; 256];
+    ///                 let _ = port.read(&mut buf);
+    ///             }),
+    ///         )
+    ///         .unwrap();
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn add_fd(
+        &mut self,
+        fd: std::os::fd::RawFd,
+        interest: FdInterest,
+        mut handler: Box<dyn FnMut(FdReadiness)>,
+    ) -> Result<u64> {
+        let watcher = Rc::new(fd::FdWatcher::new(self.context.clone(), fd, interest)?);
+        let id = self.fd_id;
+        self.fd_id += 1;
+
+        let w = watcher.clone();
+        let f = move || {
+            if let Some(readiness) = w.take() {
+                let _enter =
+                    tracing::debug_span!(target: targets::SELECTOR, spans::FD_CALLBACK, fd)
+                        .entered();
+                handler(readiness);
+            }
+            CallbackResult::Ok
+        };
+        let f = self.contain(format!("fd {fd}"), Box::new(f));
+        self.add_guard_condition(&watcher.cond, Some(f), false);
+        self.fds.insert(id, watcher);
+
+        tracing::debug!(target: targets::SELECTOR, fd, id, "Added file descriptor");
+        Ok(id)
+    }
+
+    /// Remove a file descriptor added by [`Selector::add_fd`].
+    #[cfg(unix)]
+    pub fn remove_fd(&mut self, id: u64) {
+        if let Some(watcher) = self.fds.remove(&id) {
+            self.remove_guard_condition(&watcher.cond);
+        }
+    }
+
     pub(crate) fn remove_rcl_subscription(&mut self, subscription: &Arc<RCLSubscription>) {
         self.subscriptions
             .remove(&(subscription.subscription.as_ref() as *const _));
     }
 
     pub(crate) fn remove_server_data(&mut self, server: &Arc<ServerData>) {
+        self.dispatch_servers = None;
         self.services.remove(&(&server.service as *const _));
     }
 
@@ -789,14 +1290,14 @@ impl Selector {
     /// }
     /// ```
     pub fn add_timer(&mut self, t: Duration, mut handler: Box<dyn FnMut()>) -> u64 {
-        self.add_timer_inner(
-            t,
+        let handler = self.contain(
+            "one-shot timer".to_string(),
             Box::new(move || {
                 handler();
                 CallbackResult::Ok
             }),
-            TimerType::OneShot,
-        )
+        );
+        self.add_timer_inner(t, handler, TimerType::OneShot)
     }
 
     /// Add a wall timer.
@@ -804,6 +1305,10 @@ impl Selector {
     /// The `handler` will be automatically reloaded after calling it.
     /// It means the `handler` is called periodically.
     ///
+    /// The timer is an `rcl_timer_t` on a steady clock, so it can be
+    /// canceled and reset with [`Selector::cancel_timer`] and
+    /// [`Selector::reset_timer`].
+    ///
     /// # Return Value
     ///
     /// The identifier of the timer.
@@ -836,14 +1341,256 @@ impl Selector {
             "Added wall timer"
         );
 
-        self.add_timer_inner(
-            t,
+        // rcl timers do not follow a virtual clock
+        if let TimerClock::Virtual { .. } = self.timer_clock {
+            return self.add_selector_wall_timer(
+                name,
+                t,
+                MissedTickPolicy::default(),
+                Box::new(move |_| handler()),
+            );
+        }
+
+        let timer = self
+            .steady_clock()
+            .and_then(|clock| RCLTimer::new(self.context.clone(), clock, name, t));
+        match timer {
+            Ok(timer) => self.add_rcl_timer(timer, handler, true),
+            Err(e) => {
+                tracing::error!(
+                    target: targets::SELECTOR,
+                    timer_name = %name,
+                    error = %e,
+                    "Failed to create an rcl timer, falling back to the selector timer"
+                );
+                self.add_selector_wall_timer(
+                    name,
+                    t,
+                    MissedTickPolicy::default(),
+                    Box::new(move |_| handler()),
+                )
+            }
+        }
+    }
+
+    /// Add a wall timer with a policy for its missed ticks.
+    ///
+    /// The ticks are scheduled on an absolute timeline: the `n`-th tick is
+    /// expected `n * period` after the timer was added, however long the
+    /// callbacks take. When ticks are missed, `policy` decides how many
+    /// times the `handler` is called, and the [`TimerTick`] passed to it
+    /// tells when the tick was expected and how many were missed.
+    ///
+    /// Unlike [`Selector::add_wall_timer`], the timer is driven by the
+    /// selector and not by an `rcl_timer_t`, so it can only be removed with
+    /// [`Selector::remove_timer`].
+    ///
+    /// # Return Value
+    ///
+    /// The identifier of the timer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oxidros_rcl::selector::{MissedTickPolicy, Selector};
+    /// use std::time::Duration;
+    ///
+    /// fn add_new_wall_timer(selector: &mut Selector) {
+    ///     selector.add_wall_timer_with_policy(
+    ///         "timer_name",
+    ///         Duration::from_millis(100),
+    ///         MissedTickPolicy::Burst,
+    ///         Box::new(|tick| {
+    ///             if tick.missed > 0 {
+    ///                 // catching up
+    ///             }
+    ///         }),
+    ///     );
+    /// }
+    /// ```
+    pub fn add_wall_timer_with_policy(
+        &mut self,
+        name: &str,
+        period: Duration,
+        policy: MissedTickPolicy,
+        handler: Box<dyn FnMut(TimerTick)>,
+    ) -> u64 {
+        tracing::debug!(
+            target: targets::SELECTOR,
+            timer_name = %name,
+            interval_ms = period.as_millis() as u64,
+            policy = ?policy,
+            "Added wall timer"
+        );
+
+        self.add_selector_wall_timer(name, period, policy, handler)
+    }
+
+    fn add_selector_wall_timer(
+        &mut self,
+        name: &str,
+        period: Duration,
+        policy: MissedTickPolicy,
+        handler: Box<dyn FnMut(TimerTick)>,
+    ) -> u64 {
+        let id = self.new_timer_id();
+        self.insert_selector_wall_timer(id, name, period, period, policy, handler);
+        id
+    }
+
+    /// Insert a selector wall timer whose first tick is `delay` from now.
+    fn insert_selector_wall_timer(
+        &mut self,
+        id: u64,
+        name: &str,
+        period: Duration,
+        delay: Duration,
+        policy: MissedTickPolicy,
+        mut handler: Box<dyn FnMut(TimerTick)>,
+    ) {
+        let next = self.timer_clock.now() + delay;
+        let tick = Rc::new(Cell::new(TimerTick {
+            expected: next,
+            missed: 0,
+        }));
+        let tick_cloned = tick.clone();
+        let handler = self.contain(
+            format!("timer {name}"),
             Box::new(move || {
-                handler();
+                handler(tick_cloned.get());
                 CallbackResult::Ok
             }),
-            TimerType::WallTimer(Rc::new(name.to_string()), t),
-        )
+        );
+
+        self.insert_timer(
+            delay,
+            handler,
+            TimerType::WallTimer(WallTimer {
+                name: Rc::new(name.to_string()),
+                period,
+                policy,
+                next,
+                tick,
+            }),
+            id,
+        );
+    }
+
+    /// Add a periodic timer driven by `clock`.
+    ///
+    /// With the ROS clock of a node (see
+    /// [`Node::get_clock`](crate::node::Node::get_clock)), the timer follows
+    /// the simulated time once the ROS time is overridden: it fires when the
+    /// ROS time has advanced by `period`, whatever the wall time.
+    ///
+    /// # Return Value
+    ///
+    /// The identifier of the timer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oxidros_rcl::{error::Result, node::Node, selector::Selector};
+    /// use std::{sync::Arc, time::Duration};
+    ///
+    /// fn add_ros_timer(selector: &mut Selector, node: &Node) -> Result<u64> {
+    ///     selector.add_timer_with_clock(
+    ///         "timer_name",
+    ///         Duration::from_millis(100),
+    ///         node.get_clock()?,
+    ///         Box::new(|| /* some tasks */ ()), // Callback function.
+    ///     )
+    /// }
+    /// ```
+    pub fn add_timer_with_clock(
+        &mut self,
+        name: &str,
+        period: Duration,
+        clock: Arc<Mutex<Clock>>,
+        handler: Box<dyn FnMut()>,
+    ) -> Result<u64> {
+        let clock_type = clock.lock().clock_type();
+        let timer = RCLTimer::new(self.context.clone(), clock, name, period)?;
+
+        tracing::debug!(
+            target: targets::SELECTOR,
+            timer_name = %name,
+            interval_ms = period.as_millis() as u64,
+            clock_type = ?clock_type,
+            "Added timer"
+        );
+
+        Ok(self.add_rcl_timer(timer, handler, false))
+    }
+
+    /// Stop calling the handler of a periodic timer until it is reset.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotFound` if `id` is not a periodic timer of this selector.
+    pub fn cancel_timer(&mut self, id: u64) -> Result<()> {
+        self.get_rcl_timer(id)?.cancel()
+    }
+
+    /// Restart a periodic timer: its next call is one period from now.
+    ///
+    /// A canceled timer is resumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotFound` if `id` is not a periodic timer of this selector.
+    pub fn reset_timer(&mut self, id: u64) -> Result<()> {
+        self.get_rcl_timer(id)?.reset()
+    }
+
+    /// Returns `true` if the periodic timer `id` is canceled.
+    pub fn is_timer_canceled(&self, id: u64) -> Result<bool> {
+        self.get_rcl_timer(id)?.is_canceled()
+    }
+
+    /// Time until the next call of the periodic timer `id`.
+    pub fn time_until_next_call(&self, id: u64) -> Result<Duration> {
+        self.get_rcl_timer(id)?.time_until_next_call()
+    }
+
+    /// Period of the periodic timer `id`.
+    pub fn timer_period(&self, id: u64) -> Result<Duration> {
+        self.get_rcl_timer(id)?.period()
+    }
+
+    fn get_rcl_timer(&self, id: u64) -> Result<&RCLTimer> {
+        self.rcl_timers
+            .values()
+            .find(|h| h.id == id)
+            .map(|h| &h.timer)
+            .ok_or_else(|| Error::NotFound {
+                kind: "Timer",
+                name: id.to_string(),
+            })
+    }
+
+    fn add_rcl_timer(&mut self, timer: RCLTimer, handler: Box<dyn FnMut()>, wall: bool) -> u64 {
+        let id = self.new_timer_id();
+        self.rcl_timers.insert(
+            timer.as_ptr(),
+            RCLTimerHandler {
+                id,
+                timer,
+                handler,
+                wall,
+            },
+        );
+        id
+    }
+
+    /// The steady clock shared by the wall timers of this selector.
+    fn steady_clock(&mut self) -> Result<Arc<Mutex<Clock>>> {
+        if let Some(clock) = &self.steady_clock {
+            return Ok(clock.clone());
+        }
+        let clock = Arc::new(Mutex::new(Clock::with_type(ClockType::Steady)?));
+        self.steady_clock = Some(clock.clone());
+        Ok(clock)
     }
 
     fn add_timer_inner(
@@ -852,7 +1599,19 @@ impl Selector {
         handler: Box<dyn FnMut() -> CallbackResult>,
         timer_type: TimerType,
     ) -> u64 {
-        let now_time = SystemTime::now();
+        let timer_id = self.new_timer_id();
+        self.insert_timer(t, handler, timer_type, timer_id);
+        timer_id
+    }
+
+    fn insert_timer(
+        &mut self,
+        t: Duration,
+        handler: Box<dyn FnMut() -> CallbackResult>,
+        timer_type: TimerType,
+        timer_id: u64,
+    ) {
+        let now_time = self.timer_clock.now();
 
         if self.timer.is_empty() {
             self.base_time = now_time;
@@ -874,8 +1633,6 @@ impl Selector {
             t
         };
 
-        let timer_id = self.new_timer_id();
-
         self.timer.insert(
             delta,
             (
@@ -887,8 +1644,67 @@ impl Selector {
                 timer_id,
             ),
         );
+    }
 
-        timer_id
+    /// Drive the timers by `clock` instead of the system time.
+    ///
+    /// The timers already added keep their remaining time. A timer fires in
+    /// [`Selector::wait`] once `clock` has been advanced past its deadline,
+    /// while the timeout of [`Selector::wait_timeout`] stays in real time.
+    /// Advancing the clock does not wake a selector blocked in a wait.
+    ///
+    /// The wall timers become selector timers, which cannot be canceled or
+    /// reset; canceled ones stay on the steady clock. Timers added with
+    /// [`Selector::add_timer_with_clock`] keep following their clock.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oxidros_core::time::VirtualClock;
+    /// use oxidros_rcl::{error::Result, selector::Selector};
+    /// use std::time::Duration;
+    ///
+    /// fn fire_timeout(selector: &mut Selector) -> Result<()> {
+    ///     let clock = VirtualClock::new();
+    ///     selector.set_virtual_clock(clock.clone());
+    ///     selector.add_timer(Duration::from_secs(30), Box::new(|| println!("timeout")));
+    ///
+    ///     clock.advance(Duration::from_secs(30));
+    ///     selector.wait_timeout(Duration::ZERO)?; // prints "timeout"
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_virtual_clock(&mut self, clock: VirtualClock) {
+        let origin = self.timer_clock.now();
+        let start = clock.now();
+        self.timer_clock = TimerClock::Virtual {
+            clock,
+            origin,
+            start,
+        };
+
+        let rcl_timers = std::mem::take(&mut self.rcl_timers);
+        for (p, h) in rcl_timers {
+            let (true, Ok(false), Ok(period), Ok(delay)) = (
+                h.wall,
+                h.timer.is_canceled(),
+                h.timer.period(),
+                h.timer.time_until_next_call(),
+            ) else {
+                self.rcl_timers.insert(p, h);
+                continue;
+            };
+
+            let mut handler = h.handler;
+            self.insert_selector_wall_timer(
+                h.id,
+                &h.timer.name,
+                period,
+                delay,
+                MissedTickPolicy::default(),
+                Box::new(move |_| handler()),
+            );
+        }
     }
 
     /// Wait events and invoke registered callback functions.
@@ -916,6 +1732,12 @@ impl Selector {
     /// }
     /// ```
     pub fn wait_timeout(&mut self, t: Duration) -> Result<bool> {
+        // a virtual clock does not advance while waiting
+        if let TimerClock::Virtual { .. } = self.timer_clock {
+            let timeout = self.wait_inner(Some(t))?;
+            return Ok(!timeout);
+        }
+
         let flag = Rc::new(Cell::new(false));
         let flag_cloned = flag.clone();
 
@@ -935,6 +1757,12 @@ impl Selector {
 
     pub fn remove_timer(&mut self, id: u64) {
         self.timer.filter(|e| e.1 != id);
+
+        let len = self.rcl_timers.len();
+        self.rcl_timers.retain(|_, h| h.id != id);
+        if self.rcl_timers.len() != len {
+            self.timer_ids.remove(&id);
+        }
     }
 
     fn new_timer_id(&mut self) -> u64 {
@@ -949,22 +1777,26 @@ impl Selector {
     }
 
     fn set_rcl_wait(&mut self) -> Result<()> {
-        let guard = rcl::MT_UNSAFE_FN.lock();
-        guard.rcl_wait_set_clear(&mut self.wait_set)?;
+        rcl::MTSafeFn::rcl_wait_set_clear(&mut self.wait_set)?;
 
+        // resizing reallocates the arrays of the wait set
         let entities = self.get_num_entities()?;
-        guard.rcl_wait_set_resize(
-            &mut self.wait_set,
-            entities.subscriptions,
-            entities.guard_condititons,
-            entities.timers,
-            entities.clients,
-            entities.services,
-            entities.events,
-        )?;
+        if self.wait_set_size != Some(entities) {
+            rcl::MTSafeFn::rcl_wait_set_resize(
+                &mut self.wait_set,
+                entities.subscriptions,
+                entities.guard_condititons,
+                entities.timers,
+                entities.clients,
+                entities.services,
+                entities.events,
+            )?;
+            self.wait_set_size = Some(entities);
+            self.ready_timers.reserve(self.rcl_timers.len());
+        }
         // set subscriptions
         for (_, h) in self.subscriptions.iter() {
-            guard.rcl_wait_set_add_subscription(
+            rcl::MTSafeFn::rcl_wait_set_add_subscription(
                 &mut self.wait_set,
                 h.event.subscription.as_ref(),
                 null_mut(),
@@ -972,20 +1804,45 @@ impl Selector {
         }
         // set guard conditions
         for (_, h) in self.cond.iter() {
-            guard.rcl_wait_set_add_guard_condition(
+            rcl::MTSafeFn::rcl_wait_set_add_guard_condition(
                 &mut self.wait_set,
                 h.event.cond.as_ref(),
                 null_mut(),
             )?;
         }
+        // set graph guard conditions, right after the other guard conditions
+        for (cond, _) in self.graphs.iter() {
+            rcl::MTSafeFn::rcl_wait_set_add_guard_condition(&mut self.wait_set, *cond, null_mut())?;
+        }
         // set clients
         for (_, h) in self.clients.iter() {
-            guard.rcl_wait_set_add_client(&mut self.wait_set, &h.event.client, null_mut())?;
+            rcl::MTSafeFn::rcl_wait_set_add_client(
+                &mut self.wait_set,
+                &h.event.client,
+                null_mut(),
+            )?;
         }
         // set services
         for (_, h) in self.services.iter() {
-            guard.rcl_wait_set_add_service(&mut self.wait_set, &h.event.service, null_mut())?;
+            rcl::MTSafeFn::rcl_wait_set_add_service(
+                &mut self.wait_set,
+                &h.event.service,
+                null_mut(),
+            )?;
+        }
+        // set timers, before the timers of actions
+        for (timer, _) in self.rcl_timers.iter() {
+            rcl::MTSafeFn::rcl_wait_set_add_timer(&mut self.wait_set, *timer, null_mut())?;
         }
+        // set events
+        for (event, _) in self.events.iter() {
+            rcl::MTSafeFn::rcl_wait_set_add_event(&mut self.wait_set, *event, null_mut())?;
+        }
+
+        if self.action_clients.is_empty() && self.action_servers.is_empty() {
+            return Ok(());
+        }
+        let guard = rcl::MT_UNSAFE_FN.lock();
         // set action clients
         for (_, h) in self.action_clients.iter() {
             guard.rcl_action_wait_set_add_action_client(
@@ -1019,39 +1876,100 @@ impl Selector {
     /// }
     /// ```
     pub fn wait(&mut self) -> Result<()> {
+        self.wait_inner(None).map(|_| ())
+    }
+
+    /// Wait events and invoke the callbacks, at most `limit` in real time if
+    /// the timers are driven by a virtual clock.
+    ///
+    /// Returns `true` if nothing happened before `limit`.
+    fn wait_inner(&mut self, limit: Option<Duration>) -> Result<bool> {
+        let _enter =
+            tracing::debug_span!(target: targets::SELECTOR, spans::SELECTOR_WAIT).entered();
+        self.panics.check()?;
+
+        self.remove_unscoped();
+
         // set rcl wait
         self.set_rcl_wait()?;
 
         // wait events
-        self.wait_timer()?;
+        let timeout = self.wait_timer(limit)?;
+
+        // the servers cannot answer until the callbacks return
+        let servers = self
+            .dispatch_servers
+            .get_or_insert_with(|| self.services.values().map(|h| h.event.clone()).collect());
+        let _dispatching = dispatch::Dispatching::enter(servers.clone());
 
         // notify timers
         self.notify_timer();
+        self.notify_rcl_timers();
 
         // notify subscriptions
         notify(&mut self.subscriptions, self.wait_set.subscriptions);
 
         // notify services
+        let n_services = self.services.len();
         notify(&mut self.services, self.wait_set.services);
+        if self.services.len() != n_services {
+            self.dispatch_servers = None;
+        }
 
         // notify clients
         notify(&mut self.clients, self.wait_set.clients);
 
         // notify guard conditions
+        let n_cond = self.cond.len();
         notify(&mut self.cond, self.wait_set.guard_conditions);
 
+        // notify graph guard conditions
+        notify(&mut self.graphs, unsafe {
+            self.wait_set.guard_conditions.add(n_cond)
+        });
+
+        // notify QoS events
+        notify(&mut self.events, self.wait_set.events);
+
         notify_action_server(&mut self.action_servers, &self.wait_set)?;
         notify_action_client(&mut self.action_clients, &self.wait_set)?;
 
-        Ok(())
+        self.panics.check()?;
+        Ok(timeout)
     }
 
-    fn wait_timer(&mut self) -> Result<()> {
+    /// Wait on the wait set until the head timer is due, or at most `limit`
+    /// if the timers are driven by a virtual clock.
+    ///
+    /// Returns `true` if the wait timed out on `limit`.
+    fn wait_timer(&mut self, limit: Option<Duration>) -> Result<bool> {
         if signal_handler::is_halt() {
             return Err(Signaled.into());
         }
 
-        if self.timer.is_empty() {
+        let until_timer = self.timer.front().map(|head| {
+            let now_time = self.timer_clock.now();
+            let head_delta = *head.0;
+            if self.base_time <= now_time {
+                let diff = now_time.duration_since(self.base_time).unwrap();
+                if diff < head_delta {
+                    head_delta - diff
+                } else {
+                    Duration::ZERO
+                }
+            } else {
+                head_delta + self.base_time.duration_since(now_time).unwrap()
+            }
+        });
+
+        // a virtual clock does not advance while waiting
+        let (timeout, on_limit) = match (&self.timer_clock, until_timer) {
+            (TimerClock::System, Some(t)) => (Some(t), false),
+            (TimerClock::Virtual { .. }, Some(t)) if t.is_zero() => (Some(t), false),
+            _ => (limit, true),
+        };
+
+        let Some(timeout) = timeout else {
             let wait_start = std::time::Instant::now();
 
             // wait forever until arriving events
@@ -1062,101 +1980,170 @@ impl Selector {
                 latency_us = wait_start.elapsed().as_micros() as u64,
                 "rcl_wait completed (no timer)"
             );
+
+            if signal_handler::is_halt() {
+                return Err(Signaled.into());
+            }
+            return Ok(false);
+        };
+
+        let timeout_nanos = timeout.as_nanos();
+        let timeout_nanos = if timeout_nanos > i64::MAX as u128 {
+            tracing::error!(
+                target: targets::SELECTOR,
+                timeout_nanos = timeout_nanos as u64,
+                "Timeout value overflow"
+            );
+            i64::MAX
         } else {
-            // insert timer
-            let now_time = SystemTime::now();
-            let head_delta = *self.timer.front().unwrap().0;
-            let timeout = if self.base_time <= now_time {
-                let diff = now_time.duration_since(self.base_time).unwrap();
-                if diff < head_delta {
-                    head_delta - diff
-                } else {
-                    Duration::ZERO
-                }
-            } else {
-                head_delta + self.base_time.duration_since(now_time).unwrap()
-            };
+            timeout_nanos as i64
+        };
 
-            let timeout_nanos = timeout.as_nanos();
-            let timeout_nanos = if timeout_nanos > i64::MAX as u128 {
-                tracing::error!(
+        let wait_start = std::time::Instant::now();
+
+        let timed_out = match rcl::MTSafeFn::rcl_wait(&mut self.wait_set, timeout_nanos) {
+            Err(Error::Rcl(RclError::Timeout)) => on_limit,
+            Err(e) => return Err(e),
+            _ => {
+                tracing::debug!(
                     target: targets::SELECTOR,
-                    timeout_nanos = timeout_nanos as u64,
-                    "Timeout value overflow"
+                    latency_us = wait_start.elapsed().as_micros() as u64,
+                    "rcl_wait completed (with timer)"
                 );
-                i64::MAX
-            } else {
-                timeout_nanos as i64
-            };
-
-            let wait_start = std::time::Instant::now();
-
-            match rcl::MTSafeFn::rcl_wait(&mut self.wait_set, timeout_nanos) {
-                Err(Error::Rcl(RclError::Timeout)) => (),
-                Err(e) => return Err(e),
-                _ => {
-                    tracing::debug!(
-                        target: targets::SELECTOR,
-                        latency_us = wait_start.elapsed().as_micros() as u64,
-                        "rcl_wait completed (with timer)"
-                    );
-                }
+                false
             }
-        }
+        };
 
         if signal_handler::is_halt() {
             return Err(Signaled.into());
         }
 
-        Ok(())
+        Ok(timed_out)
     }
 
     fn notify_timer(&mut self) {
-        let now_time = SystemTime::now();
-        let mut reload = Vec::new(); // wall timer to be reloaded: (name, delay, original_period, handler)
+        let now_time = self.timer_clock.now();
+        let mut reload = Vec::new(); // wall timers to be reloaded: (id, timer, handler)
 
         while let Some(head) = self.timer.front() {
-            if let Some(head_time) = self.base_time.checked_add(*head.0) {
-                if head_time < now_time {
-                    // pop and execute a callback function
-                    let mut dlist = self.timer.pop().unwrap();
-                    let head = dlist.front_mut().unwrap();
-                    self.base_time += *head.0;
+            let Some(head_time) = self.base_time.checked_add(*head.0) else {
+                break;
+            };
+            if head_time >= now_time {
+                break;
+            }
 
-                    let handler = head.1.0.handler.take();
-                    if let Some(mut handler) = handler {
-                        let start = std::time::Instant::now();
+            // pop and execute a callback function
+            let mut dlist = self.timer.pop().unwrap();
+            let head = dlist.front_mut().unwrap();
+            self.base_time += *head.0;
 
-                        handler(); // invoke the callback function
+            let id = head.1.1;
+            let Some(mut handler) = head.1.0.handler.take() else {
+                continue;
+            };
 
-                        // register the wall timer again.
-                        if let TimerType::WallTimer(name, period) = &head.1.0.event {
-                            let elapsed = now_time.elapsed().unwrap();
+            match std::mem::replace(&mut head.1.0.event, TimerType::OneShot) {
+                TimerType::WallTimer(mut timer) => {
+                    let start = std::time::Instant::now();
+                    let result = tracing::debug_span!(
+                        target: targets::SELECTOR,
+                        spans::TIMER_CALLBACK,
+                        timer = %timer.name,
+                    )
+                    .in_scope(|| timer.fire(now_time, &mut handler));
 
-                            tracing::debug!(
-                                target: targets::SELECTOR,
-                                timer_name = %name,
-                                latency_us = start.elapsed().as_micros() as u64,
-                                "Wall timer callback completed"
-                            );
+                    tracing::debug!(
+                        target: targets::SELECTOR,
+                        timer_name = %timer.name,
+                        latency_us = start.elapsed().as_micros() as u64,
+                        "Wall timer callback completed"
+                    );
 
-                            // Calculate the delay until next fire, compensating for elapsed time
-                            let delay = period.saturating_sub(elapsed);
-                            // Store (name, delay, original_period, handler)
-                            reload.push((name.clone(), delay, *period, handler));
-                        } else {
-                            self.timer_ids.remove(&head.1.1);
-                        }
+                    if result == CallbackResult::Remove {
+                        self.timer_ids.remove(&id);
+                    } else {
+                        reload.push((id, timer, handler));
                     }
-                } else {
-                    break;
+                }
+                TimerType::OneShot => {
+                    tracing::debug_span!(
+                        target: targets::SELECTOR,
+                        spans::TIMER_CALLBACK,
+                        timer = "one_shot",
+                    )
+                    .in_scope(|| handler());
+                    self.timer_ids.remove(&id);
                 }
             }
         }
 
-        // reload wall timers
-        for (name, delay, period, handler) in reload {
-            self.add_timer_inner(delay, handler, TimerType::WallTimer(name, period));
+        // reload wall timers on their timeline, keeping their identifiers
+        for (id, timer, handler) in reload {
+            let delay = timer
+                .next
+                .duration_since(self.timer_clock.now())
+                .unwrap_or_default();
+            self.insert_timer(delay, handler, TimerType::WallTimer(timer), id);
+        }
+    }
+
+    fn notify_rcl_timers(&mut self) {
+        let mut ready = std::mem::take(&mut self.ready_timers);
+        ready.clear();
+        ready.extend(
+            (0..self.rcl_timers.len())
+                .map(|i| unsafe { *self.wait_set.timers.add(i) })
+                .filter(|p| !p.is_null()),
+        );
+
+        let mut panicked = Vec::new();
+        for &p in ready.iter() {
+            let Some(h) = self.rcl_timers.get_mut(&p) else {
+                continue;
+            };
+
+            // Schedule the next call; skip the handler if canceled meanwhile
+            match h.timer.call() {
+                Ok(true) => (),
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::error!(
+                        target: targets::SELECTOR,
+                        timer_name = %h.timer.name,
+                        error = %e,
+                        "rcl_timer_call failed"
+                    );
+                    continue;
+                }
+            }
+
+            let start = std::time::Instant::now();
+            let span = tracing::debug_span!(
+                target: targets::SELECTOR,
+                spans::TIMER_CALLBACK,
+                timer = %h.timer.name,
+            );
+            let entity = format!("timer {}", h.timer.name);
+            if self
+                .panics
+                .call(&entity, || span.in_scope(|| (h.handler)()))
+                .is_none()
+            {
+                panicked.push(h.id);
+            }
+
+            tracing::debug!(
+                target: targets::SELECTOR,
+                timer_name = %h.timer.name,
+                latency_us = start.elapsed().as_micros() as u64,
+                "Wall timer callback completed"
+            );
+        }
+        self.ready_timers = ready;
+
+        for id in panicked {
+            self.remove_timer(id);
         }
     }
 
@@ -1206,16 +2193,19 @@ impl Selector {
                 + action_server_subscriptions_size * n_servers
                 + action_client_subscriptions_size * n_clients,
             guard_condititons: self.cond.len()
+                + self.graphs.len()
                 + action_server_guard_conditions_size * n_servers
                 + action_client_guard_conditions_size * n_clients,
-            timers: action_server_timers_size * n_servers + action_client_timers_size * n_clients,
+            timers: self.rcl_timers.len()
+                + action_server_timers_size * n_servers
+                + action_client_timers_size * n_clients,
             clients: self.clients.len()
                 + action_server_clients_size * n_servers
                 + action_client_clients_size * n_clients,
             services: self.services.len()
                 + action_server_services_size * n_servers
                 + action_client_services_size * n_clients,
-            events: 0,
+            events: self.events.len(),
         })
     }
 }
@@ -0,0 +1,97 @@
+use crate::{clock::Clock, context::Context, error::Result, rcl};
+use oxidros_core::{Error, RclError};
+use parking_lot::Mutex;
+use std::{sync::Arc, time::Duration};
+
+/// A periodic `rcl_timer_t` driven by a [`Clock`].
+pub(crate) struct RCLTimer {
+    timer: Box<rcl::rcl_timer_t>,
+    pub(crate) name: String,
+
+    // The timer must be finalized before its clock and context
+    _clock: Arc<Mutex<Clock>>,
+    _context: Arc<Context>,
+}
+
+impl RCLTimer {
+    pub(crate) fn new(
+        context: Arc<Context>,
+        clock: Arc<Mutex<Clock>>,
+        name: &str,
+        period: Duration,
+    ) -> Result<Self> {
+        let mut timer = Box::new(rcl::MTSafeFn::rcl_get_zero_initialized_timer());
+        let period = period.as_nanos().min(i64::MAX as u128) as i64;
+
+        {
+            let clock_ptr = unsafe { clock.lock().as_ptr_mut() };
+            let guard = rcl::MT_UNSAFE_FN.lock();
+            guard.rcl_timer_init(
+                timer.as_mut(),
+                clock_ptr,
+                unsafe { context.as_ptr_mut() },
+                period,
+            )?;
+        }
+
+        Ok(RCLTimer {
+            timer,
+            name: name.to_string(),
+            _clock: clock,
+            _context: context,
+        })
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const rcl::rcl_timer_t {
+        self.timer.as_ref() as *const _
+    }
+
+    fn as_ptr_mut(&self) -> *mut rcl::rcl_timer_t {
+        self.timer.as_ref() as *const _ as *mut _
+    }
+
+    /// Mark the timer as called, scheduling its next call.
+    ///
+    /// Returns `false` if the timer was canceled in the meantime.
+    pub(crate) fn call(&self) -> Result<bool> {
+        match rcl::MTSafeFn::rcl_timer_call(self.as_ptr_mut()) {
+            Ok(()) => Ok(true),
+            Err(Error::Rcl(RclError::TimerCanceled)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub(crate) fn cancel(&self) -> Result<()> {
+        rcl::MTSafeFn::rcl_timer_cancel(self.as_ptr_mut())
+    }
+
+    pub(crate) fn reset(&self) -> Result<()> {
+        rcl::MTSafeFn::rcl_timer_reset(self.as_ptr_mut())
+    }
+
+    pub(crate) fn is_canceled(&self) -> Result<bool> {
+        let mut is_canceled = false;
+        rcl::MTSafeFn::rcl_timer_is_canceled(self.as_ptr(), &mut is_canceled)?;
+        Ok(is_canceled)
+    }
+
+    pub(crate) fn period(&self) -> Result<Duration> {
+        let mut period = 0;
+        rcl::MTSafeFn::rcl_timer_get_period(self.as_ptr(), &mut period)?;
+        Ok(Duration::from_nanos(period.max(0) as u64))
+    }
+
+    /// Time until the next call; zero if the call is overdue.
+    pub(crate) fn time_until_next_call(&self) -> Result<Duration> {
+        let mut time = 0;
+        rcl::MTSafeFn::rcl_timer_get_time_until_next_call(self.as_ptr(), &mut time)?;
+        Ok(Duration::from_nanos(time.max(0) as u64))
+    }
+}
+
+impl Drop for RCLTimer {
+    fn drop(&mut self) {
+        let guard = rcl::MT_UNSAFE_FN.lock();
+        let _ = guard.rcl_timer_fini(self.timer.as_mut());
+    }
+}
@@ -0,0 +1,271 @@
+//! io_uring reactor polling the file descriptors of selectors (`uring` feature).
+//!
+//! rmw implementations do not expose file descriptors for ROS entities, so
+//! rcl_wait stays the waiting primitive of a [`Selector`](super::Selector) and
+//! guard conditions remain the bridge from file descriptors to its wait set.
+//! Instead of one polling thread per file descriptor, a single reactor thread
+//! shared by all selectors submits a one-shot `POLL_ADD` per file descriptor
+//! and triggers its guard condition on completion. Commands are delivered
+//! through an eventfd polled by the same ring, so adding, re-arming and
+//! removing a file descriptor wakes the reactor immediately.
+//!
+//! If io_uring is not available, e.g. on older kernels or when it is blocked
+//! by seccomp, [`Reactor::global`] returns `None` and file descriptors are
+//! polled by threads. The same holds once the reactor stopped on an error,
+//! after reporting an error readiness for the file descriptors it polled.
+
+use super::guard_condition::GuardCondition;
+use io_uring::{IoUring, opcode, types};
+use oxidros_core::{
+    selector::{FdInterest, FdReadiness},
+    targets,
+};
+use parking_lot::Mutex;
+use std::{
+    collections::BTreeMap,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    thread,
+};
+
+/// Number of submission queue entries of the ring.
+const RING_ENTRIES: u32 = 256;
+
+/// User data of the poll of the eventfd.
+const WAKE: u64 = 0;
+
+/// User data of the removal of polls.
+const CANCEL: u64 = u64::MAX;
+
+/// File descriptor registered in the reactor.
+pub(crate) struct Watch {
+    pub(crate) fd: RawFd,
+    pub(crate) interest: FdInterest,
+    pub(crate) cond: GuardCondition,
+    pub(crate) ready: Arc<Mutex<Option<FdReadiness>>>,
+}
+
+enum Command {
+    Add(u64, Watch),
+    Rearm(u64),
+    Remove(u64),
+}
+
+/// Reactor thread shared by all selectors.
+pub(crate) struct Reactor {
+    commands: Arc<Mutex<Vec<Command>>>,
+    wake: OwnedFd,
+    next_key: AtomicU64,
+    /// Set when the reactor thread stopped on an error.
+    stopped: Arc<AtomicBool>,
+}
+
+impl Reactor {
+    /// Get the reactor, starting it on first use.
+    ///
+    /// Returns `None` if io_uring is not available or the reactor stopped.
+    pub(crate) fn global() -> Option<&'static Reactor> {
+        static REACTOR: OnceLock<Option<Reactor>> = OnceLock::new();
+        REACTOR
+            .get_or_init(|| match Reactor::start() {
+                Ok(reactor) => Some(reactor),
+                Err(e) => {
+                    tracing::warn!(
+                        target: targets::SELECTOR,
+                        error = %e,
+                        "io_uring is not available, polling file descriptors with threads"
+                    );
+                    None
+                }
+            })
+            .as_ref()
+            .filter(|reactor| !reactor.stopped.load(Ordering::Acquire))
+    }
+
+    fn start() -> std::io::Result<Self> {
+        let ring = IoUring::new(RING_ENTRIES)?;
+        // SAFETY: eventfd has no memory safety requirements.
+        let wake = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) };
+        if wake < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        // SAFETY: `wake` is a new file descriptor owned by nobody else.
+        let wake = unsafe { OwnedFd::from_raw_fd(wake) };
+        let commands = Arc::new(Mutex::new(Vec::new()));
+        let stopped = Arc::new(AtomicBool::new(false));
+        {
+            let wake = wake.as_raw_fd();
+            let commands = commands.clone();
+            let stopped = stopped.clone();
+            thread::Builder::new()
+                .name("oxidros-uring".to_string())
+                .spawn(move || {
+                    let mut watches = BTreeMap::new();
+                    if let Err(e) = run(ring, wake, &commands, &mut watches) {
+                        tracing::error!(
+                            target: targets::SELECTOR,
+                            error = %e,
+                            "io_uring reactor stopped, polling file descriptors with threads"
+                        );
+                        stopped.store(true, Ordering::Release);
+
+                        // The file descriptors added meanwhile are not polled either
+                        for command in commands.lock().drain(..) {
+                            if let Command::Add(key, watch) = command {
+                                watches.insert(key, watch);
+                            }
+                        }
+                        for watch in watches.values() {
+                            *watch.ready.lock() = Some(FdReadiness {
+                                error: true,
+                                ..Default::default()
+                            });
+                            let _ = watch.cond.trigger();
+                        }
+                    }
+                })?;
+        }
+        Ok(Reactor {
+            commands,
+            wake,
+            next_key: AtomicU64::new(WAKE + 1),
+            stopped,
+        })
+    }
+
+    /// Start polling a file descriptor. Returns its key in the reactor.
+    pub(crate) fn add(&self, watch: Watch) -> u64 {
+        let key = self.next_key.fetch_add(1, Ordering::Relaxed);
+        self.send(Command::Add(key, watch));
+        key
+    }
+
+    /// Poll a file descriptor again after its readiness was taken.
+    pub(crate) fn rearm(&self, key: u64) {
+        self.send(Command::Rearm(key));
+    }
+
+    /// Stop polling a file descriptor.
+    pub(crate) fn remove(&self, key: u64) {
+        self.send(Command::Remove(key));
+    }
+
+    fn send(&self, command: Command) {
+        let mut commands = self.commands.lock();
+        if self.stopped.load(Ordering::Acquire) {
+            return;
+        }
+        commands.push(command);
+        drop(commands);
+        let one: u64 = 1;
+        // SAFETY: writes the 8 bytes of `one` to the eventfd.
+        unsafe {
+            libc::write(
+                self.wake.as_raw_fd(),
+                &one as *const u64 as *const libc::c_void,
+                size_of::<u64>(),
+            );
+        }
+    }
+}
+
+fn poll_entry(fd: RawFd, interest: FdInterest, key: u64) -> io_uring::squeue::Entry {
+    let mut events = 0;
+    if interest.readable {
+        events |= libc::POLLIN;
+    }
+    if interest.writable {
+        events |= libc::POLLOUT;
+    }
+    opcode::PollAdd::new(types::Fd(fd), events as u32)
+        .build()
+        .user_data(key)
+}
+
+fn push(ring: &mut IoUring, entry: &io_uring::squeue::Entry) -> std::io::Result<()> {
+    // SAFETY: poll entries do not reference buffers.
+    while unsafe { ring.submission().push(entry) }.is_err() {
+        // The submission queue is full, make room for the entry
+        ring.submit()?;
+    }
+    Ok(())
+}
+
+fn run(
+    mut ring: IoUring,
+    wake: RawFd,
+    commands: &Mutex<Vec<Command>>,
+    watches: &mut BTreeMap<u64, Watch>,
+) -> std::io::Result<()> {
+    push(&mut ring, &poll_entry(wake, FdInterest::READABLE, WAKE))?;
+
+    loop {
+        match ring.submit_and_wait(1) {
+            Ok(_) => (),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+        let completions: Vec<(u64, i32)> = ring
+            .completion()
+            .map(|cqe| (cqe.user_data(), cqe.result()))
+            .collect();
+
+        for (key, result) in completions {
+            if key == WAKE {
+                let mut count = [0u8; 8];
+                // SAFETY: reads at most 8 bytes into `count`.
+                unsafe { libc::read(wake, count.as_mut_ptr() as *mut libc::c_void, count.len()) };
+                let pending = std::mem::take(&mut *commands.lock());
+                for command in pending {
+                    match command {
+                        Command::Add(key, watch) => {
+                            push(&mut ring, &poll_entry(watch.fd, watch.interest, key))?;
+                            watches.insert(key, watch);
+                        }
+                        Command::Rearm(key) => {
+                            if let Some(watch) = watches.get(&key) {
+                                push(&mut ring, &poll_entry(watch.fd, watch.interest, key))?;
+                            }
+                        }
+                        Command::Remove(key) => {
+                            // The cancelled poll completes with a key which is
+                            // no longer watched
+                            if watches.remove(&key).is_some() {
+                                let entry = opcode::PollRemove::new(key).build().user_data(CANCEL);
+                                push(&mut ring, &entry)?;
+                            }
+                        }
+                    }
+                }
+                push(&mut ring, &poll_entry(wake, FdInterest::READABLE, WAKE))?;
+                continue;
+            }
+
+            let Some(watch) = watches.get(&key) else {
+                continue;
+            };
+            let readiness = if result < 0 {
+                FdReadiness {
+                    error: true,
+                    ..Default::default()
+                }
+            } else {
+                let revents = result as libc::c_short;
+                FdReadiness {
+                    readable: revents & libc::POLLIN != 0,
+                    writable: revents & libc::POLLOUT != 0,
+                    hangup: revents & libc::POLLHUP != 0,
+                    error: revents & (libc::POLLERR | libc::POLLNVAL) != 0,
+                }
+            };
+            *watch.ready.lock() = Some(readiness);
+            if let Err(e) = watch.cond.trigger() {
+                tracing::error!(target: targets::SELECTOR, fd = watch.fd, error = %e, "Failed to trigger");
+                watches.remove(&key);
+            }
+        }
+    }
+}
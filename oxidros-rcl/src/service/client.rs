@@ -58,13 +58,18 @@ use crate::{
     node::Node,
     qos::Profile,
     rcl::{self, MT_UNSAFE_FN},
-    selector::{Selector, async_selector},
+    selector::{Selector, async_selector, dispatch},
     signal_handler::Signaled,
 };
-use oxidros_core::{Error, Message, RclError, selector::CallbackResult};
+use oxidros_core::{
+    Error, Message, RclError, ServiceTypeDescription, TypeInfo,
+    selector::CallbackResult,
+    stats::{EntityKind, EntityToken},
+    targets,
+};
 use std::{
-    borrow::Cow, ffi::CString, future::Future, marker::PhantomData, os::raw::c_void, sync::Arc,
-    task::Poll, time::Duration,
+    borrow::Cow, ffi::CString, fmt, future::Future, marker::PhantomData, os::raw::c_void,
+    sync::Arc, task::Poll, time::Duration,
 };
 
 pub(crate) struct ClientData {
@@ -85,11 +90,13 @@ unsafe impl Send for ClientData {}
 /// Client.
 pub struct Client<T> {
     pub(crate) data: Arc<ClientData>,
+    _entity: EntityToken,
     _phantom: PhantomData<T>,
 }
 
 impl<T: ServiceMsg> Client<T> {
     pub(crate) fn new(node: Arc<Node>, service_name: &str, qos: Option<Profile>) -> Result<Self> {
+        node.validate_topic_name(service_name)?;
         let mut client = rcl::MTSafeFn::rcl_get_zero_initialized_client();
         let service_name_c = CString::new(service_name).unwrap_or_default();
         let profile = qos.unwrap_or_else(Profile::services_default);
@@ -102,12 +109,14 @@ impl<T: ServiceMsg> Client<T> {
         guard.rcl_client_init(
             &mut client,
             node.as_ptr(),
-            <T as ServiceMsg>::type_support() as *const rcl::rosidl_service_type_support_t,
+            <T as ServiceMsg>::try_type_support().map_err(Error::TypeSupport)?
+                as *const rcl::rosidl_service_type_support_t,
             service_name_c.as_ptr(),
             &options,
         )?;
 
         Ok(Client {
+            _entity: node.entities.register(EntityKind::Client),
             data: Arc::new(ClientData { client, node }),
             _phantom: Default::default(),
         })
@@ -247,6 +256,31 @@ impl<T: ServiceMsg> Client<T> {
         let name = fq_name.rsplit('/').next().unwrap_or(&fq_name).to_string();
         Ok(Cow::Owned(name))
     }
+
+    /// Get the parent node.
+    pub fn node(&self) -> &Arc<Node> {
+        &self.data.node
+    }
+}
+
+impl<T: ServiceMsg + ServiceTypeDescription> Client<T> {
+    /// Get the type name and hash of the service, as advertised in the graph.
+    pub fn type_info(&self) -> Result<TypeInfo> {
+        TypeInfo::of_service::<T>()
+    }
+}
+
+impl<T: ServiceMsg + ServiceTypeDescription> fmt::Debug for Client<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("Client");
+        if let Ok(service) = self.fully_qualified_service_name() {
+            s.field("service", &service);
+        }
+        if let Ok(type_info) = self.type_info() {
+            s.field("type_info", &type_info);
+        }
+        s.finish_non_exhaustive()
+    }
 }
 
 /// Receiver to receive a response.
@@ -262,30 +296,43 @@ impl<'a, T: ServiceMsg> ClientRecv<'a, T> {
     /// returns `Ok(None)`.
     /// So, please retry later if this value is returned.
     ///
+    /// Responses to earlier requests, whose receivers were dropped before
+    /// the response arrived, are discarded.
+    ///
     /// # Errors
     ///
     /// - `RCLError::InvalidArgument` if any arguments are invalid, or
     /// - `RCLError::ClientInvalid` if the client is invalid, or
     /// - `RCLError::Error` if an unspecified error occurs.
     pub fn try_recv(&self) -> Result<Option<Message<<T as ServiceMsg>::Response>>> {
-        let (response, header) = match rcl_take_response_with_info::<<T as ServiceMsg>::Response>(
-            &self.data.data.client,
-            self.seq,
-        ) {
-            Ok(data) => data,
-            Err(Error::Rcl(RclError::ClientTakeFailed)) => return Ok(None),
-            Err(e) => return Err(e),
-        };
+        loop {
+            let (response, header) = match rcl_take_response_with_info::<<T as ServiceMsg>::Response>(
+                &self.data.data.client,
+                self.seq,
+            ) {
+                Ok(data) => data,
+                Err(Error::Rcl(RclError::ClientTakeFailed)) => return Ok(None),
+                Err(e) => return Err(e),
+            };
 
-        if header.request_id.sequence_number != self.seq {
-            return Ok(None);
-        }
+            if header.request_id.sequence_number == self.seq {
+                return Ok(Some(Message::new(response, header.into())));
+            }
 
-        Ok(Some(Message::new(response, header.into())))
+            tracing::debug!(
+                target: targets::RCL,
+                seq = header.request_id.sequence_number,
+                expected = self.seq,
+                "Discarded the response to a cancelled request"
+            );
+        }
     }
 
     /// Receive a response asynchronously.
     ///
+    /// Dropping the future before it completes abandons the request: a later
+    /// response to it is discarded by the next receivers of this client.
+    ///
     /// # Example
     ///
     /// ```
@@ -332,6 +379,12 @@ impl<'a, T: ServiceMsg> ClientRecv<'a, T> {
 
     /// Receive a message.
     ///
+    /// # Errors
+    ///
+    /// Fails with [`Error::WouldDeadlock`](oxidros_core::Error::WouldDeadlock)
+    /// when called from a callback of a selector holding the server of the
+    /// service, which cannot answer before the callback returns.
+    ///
     /// # Example
     ///
     /// ```
@@ -380,6 +433,9 @@ impl<'a, T: ServiceMsg> ClientRecv<'a, T> {
         t: Duration,
         selector: &mut Selector,
     ) -> Result<Option<Message<<T as ServiceMsg>::Response>>> {
+        // Fail if the server is blocked in the calling callback.
+        dispatch::check_wait(&self.data.data)?;
+
         // Add the receiver.
         selector.add_client_recv(self);
         // Wait a response with timeout.
@@ -91,7 +91,7 @@
 use crate::msg::interfaces::rosgraph_msgs::msg::Clock;
 use crate::{
     PhantomUnsync,
-    error::Result,
+    error::{Operation, Result, ResultExt, entity_context},
     get_allocator,
     helper::is_unpin,
     is_halt,
@@ -102,11 +102,15 @@ use crate::{
     selector::async_selector,
     signal_handler::Signaled,
 };
-use oxidros_core::{Error, Message, RclError, selector::CallbackResult};
+use oxidros_core::{
+    Error, Message, RclError, RequestInfo, ServiceTypeDescription, TypeInfo,
+    selector::CallbackResult,
+    stats::{EntityKind, EntityToken},
+};
 use oxidros_msg::TypeSupport;
 use std::{
-    borrow::Cow, ffi::CString, future::Future, marker::PhantomData, os::raw::c_void, sync::Arc,
-    task::Poll,
+    borrow::Cow, ffi::CString, fmt, future::Future, marker::PhantomData, os::raw::c_void,
+    sync::Arc, task::Poll,
 };
 
 pub(crate) struct ServerData {
@@ -159,12 +163,14 @@ unsafe impl Send for ServerData {}
 #[must_use]
 pub struct Server<T> {
     pub(crate) data: Arc<ServerData>,
+    _entity: EntityToken,
     _phantom: PhantomData<T>,
     _unsync: PhantomUnsync,
 }
 
 impl<T: ServiceMsg> Server<T> {
     pub(crate) fn new(node: Arc<Node>, service_name: &str, qos: Option<Profile>) -> Result<Self> {
+        node.validate_topic_name(service_name)?;
         let mut service = rcl::MTSafeFn::rcl_get_zero_initialized_service();
         let service_name_c = CString::new(service_name).unwrap_or_default();
         let profile = qos.unwrap_or_else(Profile::services_default);
@@ -178,13 +184,15 @@ impl<T: ServiceMsg> Server<T> {
             guard.rcl_service_init(
                 &mut service,
                 node.as_ptr(),
-                <T as ServiceMsg>::type_support() as *const rcl::rosidl_service_type_support_t,
+                <T as ServiceMsg>::try_type_support().map_err(Error::TypeSupport)?
+                    as *const rcl::rosidl_service_type_support_t,
                 service_name_c.as_ptr(),
                 &options,
             )?;
         }
 
         Ok(Server {
+            _entity: node.entities.register(EntityKind::Server),
             data: Arc::new(ServerData { service, node }),
             _phantom: Default::default(),
             _unsync: Default::default(),
@@ -269,6 +277,7 @@ impl<T: ServiceMsg> Server<T> {
 
         Ok(Some(ServiceRequest {
             request: Message::new(request, header.into()),
+            info: header.into(),
             sender: ServerSend {
                 data: self.data.clone(),
                 request_id: header.request_id,
@@ -280,6 +289,9 @@ impl<T: ServiceMsg> Server<T> {
 
     /// Receive a request asynchronously.
     ///
+    /// This is cancel safe: the request is taken by the poll that returns it,
+    /// so dropping the future, e.g. in `tokio::select!`, loses no request.
+    ///
     /// # Example
     ///
     /// ```
@@ -337,6 +349,31 @@ impl<T: ServiceMsg> Server<T> {
         let name = fq_name.rsplit('/').next().unwrap_or(&fq_name).to_string();
         Ok(Cow::Owned(name))
     }
+
+    /// Get the parent node.
+    pub fn node(&self) -> &Arc<Node> {
+        &self.data.node
+    }
+}
+
+impl<T: ServiceMsg + ServiceTypeDescription> Server<T> {
+    /// Get the type name and hash of the service, as advertised in the graph.
+    pub fn type_info(&self) -> Result<TypeInfo> {
+        TypeInfo::of_service::<T>()
+    }
+}
+
+impl<T: ServiceMsg + ServiceTypeDescription> fmt::Debug for Server<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("Server");
+        if let Ok(service) = self.fully_qualified_service_name() {
+            s.field("service", &service);
+        }
+        if let Ok(type_info) = self.type_info() {
+            s.field("type_info", &type_info);
+        }
+        s.finish_non_exhaustive()
+    }
 }
 
 unsafe impl<T> Send for Server<T> {}
@@ -400,6 +437,13 @@ impl<T: ServiceMsg> ServerSend<T> {
             &mut self.request_id,
             data as *const _ as *mut c_void,
         )
+        .with_context(|| {
+            let service = MT_UNSAFE_FN
+                .lock()
+                .rcl_service_get_service_name(&server_data.service)
+                .unwrap_or_default();
+            entity_context(&server_data.node, Operation::SendResponse, &service)
+        })
     }
 }
 
@@ -497,6 +541,7 @@ impl<'a, T> Drop for AsyncReceiver<'a, T> {
 pub struct ServiceRequest<T: ServiceMsg> {
     /// The request message.
     pub request: Message<<T as ServiceMsg>::Request>,
+    pub(crate) info: RequestInfo,
     pub(crate) sender: ServerSend<T>,
 }
 
@@ -518,6 +563,12 @@ where
     pub fn request(&self) -> &T::Request {
         &self.request.sample
     }
+
+    /// Get the metadata of the request: client GID, sequence number, and the
+    /// times at which it was sent and received.
+    pub fn info(&self) -> RequestInfo {
+        self.info
+    }
 }
 
 impl<T: ServiceMsg> oxidros_core::api::ServiceRequest<T> for ServiceRequest<T>
@@ -528,6 +579,10 @@ where
         &self.request.sample
     }
 
+    fn info(&self) -> RequestInfo {
+        self.info
+    }
+
     fn respond(self, response: &T::Response) -> Result<()> {
         self.sender.send(response)
     }
@@ -2,7 +2,7 @@
 
 use crate::{rcl, selector::guard_condition::GuardCondition};
 use once_cell::sync::Lazy;
-use oxidros_core::targets;
+use oxidros_core::{shutdown::ShutdownToken, targets};
 #[cfg(target_os = "windows")]
 use parking_lot::Condvar;
 use parking_lot::{Mutex, RawMutex, lock_api::MutexGuard};
@@ -63,7 +63,10 @@ pub(crate) fn init() {
         let mut guard = SIGHDL.lock();
         *guard = Some(handle);
 
-        let th = thread::spawn(move || handler(signals));
+        let th = thread::Builder::new()
+            .name("oxidros-signal-handler".into())
+            .spawn(move || handler(signals))
+            .unwrap();
         *THREAD.lock() = Some(th);
     });
 }
@@ -82,7 +85,10 @@ pub(crate) fn init() {
             })
             .unwrap();
         }
-        let th = thread::spawn(handler);
+        let th = thread::Builder::new()
+            .name("oxidros-signal-handler".into())
+            .spawn(handler)
+            .unwrap();
         *THREAD.lock() = Some(th);
     });
 }
@@ -97,6 +103,13 @@ pub(crate) fn unregister_guard_condition(cond: &GuardCondition) {
     guard.remove(&KeyCond(cond.cond.as_ptr()));
 }
 
+/// Get the process-wide token, triggered by this handler when it sets
+/// [`is_halt`].
+pub fn shutdown_token() -> ShutdownToken {
+    init();
+    ShutdownToken::signal_token()
+}
+
 /// After receiving SIGINT, SIGTERM, SIGQUIT, or SIGHUP, this function return `true`.
 /// If `is_halt()` is `true`, some functions to receive or wait returns error to halt the process.
 pub fn is_halt() -> bool {
@@ -135,6 +148,7 @@ fn handler(mut signals: SignalsInfo) {
         match signal {
             SIGTERM | SIGQUIT | SIGHUP => {
                 IS_HALT.store(true, Ordering::SeqCst);
+                ShutdownToken::signal_token().shutdown();
                 let mut cond = get_guard_condition();
                 let cond = std::mem::take(&mut *cond);
 
@@ -162,6 +176,7 @@ fn handler() {
     }
 
     IS_HALT.store(true, Ordering::SeqCst);
+    ShutdownToken::signal_token().shutdown();
     let mut cond = get_guard_condition();
     let cond = std::mem::take(&mut *cond);
 
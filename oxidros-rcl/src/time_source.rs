@@ -0,0 +1,131 @@
+//! Simulated time of the clock of a node.
+//!
+//! As the `TimeSource` of `rclcpp`, [`TimeSource`] overrides the ROS time of
+//! the clock of a node with the messages of the `/clock` topic while the
+//! `use_sim_time` parameter is `true`. The [`ParameterServer`] of the node
+//! declares the parameter and switches the time source when it is set, so
+//! that the clock and the timers added with it follow the simulated time
+//! without restarting the node.
+//!
+//! [`ParameterServer`]: crate::parameter::ParameterServer
+
+use crate::{
+    clock::{self, Clock},
+    error::Result,
+    msg::interfaces::rosgraph_msgs::msg::Clock as ClockMsg,
+    node::Node,
+    rcl,
+    selector::guard_condition::GuardCondition,
+};
+use oxidros_core::{qos::Profile, selector::CallbackResult, targets, time::CLOCK_TOPIC};
+use parking_lot::Mutex;
+use std::{cell::Cell, rc::Rc, sync::Arc, thread::JoinHandle, time::Duration};
+
+/// Subscriber of `/clock` overriding the ROS time of the clock of a node.
+pub(crate) struct TimeSource {
+    node: Arc<Node>,
+    clock: Arc<Mutex<Clock>>,
+    /// Thread receiving `/clock` and the condition stopping it, while the
+    /// simulated time is used.
+    receiver: Option<(GuardCondition, JoinHandle<Result<()>>)>,
+}
+
+impl TimeSource {
+    pub(crate) fn new(node: Arc<Node>) -> Result<Self> {
+        let clock = node.get_clock()?;
+        Ok(Self {
+            node,
+            clock,
+            receiver: None,
+        })
+    }
+
+    /// Whether the clock follows the simulated time.
+    pub(crate) fn use_sim_time(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// Switch the clock to the simulated time, or back to the system time.
+    ///
+    /// The simulated time is zero until the first message of `/clock`.
+    pub(crate) fn set_use_sim_time(&mut self, enable: bool) -> Result<()> {
+        if enable == self.use_sim_time() {
+            return Ok(());
+        }
+
+        if enable {
+            clock::change_unlocked(&self.clock, rcl::MTSafeFn::rcl_enable_ros_time_override)?;
+
+            let cond_halt = GuardCondition::new(self.node.context.clone())?;
+            let handler = std::thread::Builder::new()
+                .name("oxidros-time-source".into())
+                .spawn({
+                    let node = self.node.clone();
+                    let clock = self.clock.clone();
+                    let cond_halt = cond_halt.clone();
+                    move || receive_clock(node, clock, cond_halt)
+                })?;
+            self.receiver = Some((cond_halt, handler));
+        } else {
+            self.stop();
+            clock::change_unlocked(&self.clock, rcl::MTSafeFn::rcl_disable_ros_time_override)?;
+        }
+
+        tracing::debug!(target: targets::CLOCK, use_sim_time = enable, "Time source switched");
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        if let Some((cond_halt, handler)) = self.receiver.take()
+            && cond_halt.trigger().is_ok()
+        {
+            let _ = handler.join();
+        }
+    }
+}
+
+impl Drop for TimeSource {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn receive_clock(
+    node: Arc<Node>,
+    clock: Arc<Mutex<Clock>>,
+    cond_halt: GuardCondition,
+) -> Result<()> {
+    let mut selector = node.context.create_selector()?;
+    let subscriber = node.create_subscriber::<ClockMsg>(CLOCK_TOPIC, Some(Profile::clock()))?;
+    selector.add_subscriber(
+        subscriber,
+        Box::new(move |msg| {
+            let time = Duration::new(msg.clock.sec.max(0) as u64, msg.clock.nanosec);
+            let nanos = time.as_nanos() as i64;
+            let result = clock::change_unlocked(&clock, |raw| {
+                rcl::MTSafeFn::rcl_set_ros_time_override(raw, nanos)
+            });
+            if let Err(e) = result {
+                tracing::error!(target: targets::CLOCK, error = %e, "Failed to set the simulated time");
+            }
+        }),
+    );
+
+    let is_halt = Rc::new(Cell::new(false));
+    selector.add_guard_condition(
+        &cond_halt,
+        Some(Box::new({
+            let is_halt = is_halt.clone();
+            move || {
+                is_halt.set(true);
+                CallbackResult::Remove
+            }
+        })),
+        false,
+    );
+
+    while !is_halt.get() {
+        selector.wait()?;
+    }
+    Ok(())
+}
@@ -1,6 +1,48 @@
 //! Publish and subscribe messages.
 
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
 pub mod publisher;
 pub mod publisher_loaned_message;
 pub mod subscriber;
 pub mod subscriber_loaned_message;
+
+/// Number of messages sent or taken by a publisher or a subscriber,
+/// by whether the middleware loaned the memory or the message was copied.
+///
+/// Use these counters to check that a zero-copy deployment actually avoids
+/// the copies, see [`publisher::Publisher::loan_stats`] and
+/// [`subscriber::Subscriber::loan_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoanStats {
+    /// Messages in memory loaned by the middleware.
+    pub loaned: u64,
+    /// Messages copied from or to the memory of the application.
+    pub copied: u64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct LoanCounters {
+    loaned: AtomicU64,
+    copied: AtomicU64,
+    warned: AtomicBool,
+}
+
+impl LoanCounters {
+    pub(crate) fn count(&self, loaned: bool) {
+        let counter = if loaned { &self.loaned } else { &self.copied };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn stats(&self) -> LoanStats {
+        LoanStats {
+            loaned: self.loaned.load(Ordering::Relaxed),
+            copied: self.copied.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns `true` the first time only, to warn once about the fallback to copies.
+    pub(crate) fn first_fallback(&self) -> bool {
+        !self.warned.swap(true, Ordering::Relaxed)
+    }
+}
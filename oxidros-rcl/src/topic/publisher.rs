@@ -46,19 +46,58 @@
 //! ```
 //!
 //! `None` of the 2nd argument of `create_publisher` is equivalent to `Some(Profile::default())`.
+//!
+//! ## Automatic Stamping
+//!
+//! Publishers of messages with a header can fill the stamps left to zero with
+//! the time of the clock of the node, see [`Publisher::set_auto_stamp`].
+//!
+//! ```
+//! use oxidros_rcl::{context::Context, msg::common_interfaces::geometry_msgs};
+//!
+//! let ctx = Context::new().unwrap();
+//! let node = ctx
+//!     .create_node_with_opt("publisher_rs_stamp", None, Default::default())
+//!     .unwrap();
+//!
+//! let mut publisher = node
+//!     .create_publisher::<geometry_msgs::msg::PoseStamped>("publisher_rs_stamp_topic", None)
+//!     .unwrap();
+//! publisher.set_auto_stamp(true);
+//!
+//! // Sent with the current time as stamp.
+//! let msg = geometry_msgs::msg::PoseStamped::new().unwrap();
+//! publisher.send(&msg).unwrap();
+//! ```
 
 use crate::{
-    error::Result,
+    error::{Error, RclError, Result},
     get_allocator,
-    msg::TypeSupport,
+    msg::{Stamped, TryClone, TypeSupport},
     node::Node,
-    qos,
+    qos::{self, PublisherEventKind, QosEventStatus},
     rcl::{self, MT_UNSAFE_FN},
     signal_handler::Signaled,
-    topic::publisher_loaned_message::PublisherLoanedMessage,
+    topic::{LoanCounters, LoanStats, publisher_loaned_message::PublisherLoanedMessage},
+};
+use oxidros_core::{
+    NetworkFlowEndpoint, TypeDescription, TypeInfo,
+    stats::{EntityKind, EntityToken},
+    targets,
+    time::{StampFn, stamp_if_unset},
+};
+use std::{
+    borrow::Cow,
+    ffi::CString,
+    fmt,
+    marker::PhantomData,
+    ptr::null_mut,
+    sync::{Arc, Weak},
+    time::Duration,
 };
-use oxidros_core::targets;
-use std::{borrow::Cow, ffi::CString, marker::PhantomData, ptr::null_mut, sync::Arc};
+
+#[cfg(feature = "custom_alloc")]
+use crate::allocator::PoolAllocator;
 
 /// Publisher.
 ///
@@ -83,18 +122,23 @@ use std::{borrow::Cow, ffi::CString, marker::PhantomData, ptr::null_mut, sync::A
 /// publisher.send(&msg).unwrap();
 /// ```
 pub struct Publisher<T> {
-    publisher: Arc<rcl::rcl_publisher_t>,
+    publisher: Arc<RCLPublisher>,
     _phantom: PhantomData<T>,
-    node: Arc<Node>,
+    /// Set by [`Publisher::set_auto_stamp`].
+    auto_stamp: Option<StampFn<T>>,
+    loans: LoanCounters,
+    _entity: EntityToken,
+    #[cfg(feature = "custom_alloc")]
+    message_pool: Option<&'static PoolAllocator>,
 }
 
 impl<T: TypeSupport> Publisher<T> {
-    #[allow(clippy::arc_with_non_send_sync)]
     pub(crate) fn new(
         node: Arc<Node>,
         topic_name: &str,
         qos: Option<qos::Profile>,
     ) -> Result<Self> {
+        node.validate_topic_name(topic_name)?;
         let mut publisher = rcl::MTSafeFn::rcl_get_zero_initialized_publisher();
 
         let topic_name_c = CString::new(topic_name).unwrap_or_default();
@@ -106,7 +150,8 @@ impl<T: TypeSupport> Publisher<T> {
             guard.rcl_publisher_init(
                 &mut publisher,
                 node.as_ptr(),
-                T::type_support() as *const rcl::rosidl_message_type_support_t,
+                T::try_type_support().map_err(Error::TypeSupport)?
+                    as *const rcl::rosidl_message_type_support_t,
                 topic_name_c.as_ptr(),
                 options.as_ptr(),
             )?;
@@ -119,18 +164,22 @@ impl<T: TypeSupport> Publisher<T> {
         );
 
         Ok(Publisher {
-            publisher: Arc::new(publisher),
-            node,
+            #[cfg(feature = "custom_alloc")]
+            message_pool: node.message_pool,
+            _entity: node.entities.register(EntityKind::Publisher),
+            publisher: Arc::new(RCLPublisher { publisher, node }),
+            loans: Default::default(),
             _phantom: Default::default(),
+            auto_stamp: None,
         })
     }
 
-    #[allow(clippy::arc_with_non_send_sync)]
     pub(crate) fn new_disable_loaned_message(
         node: Arc<Node>,
         topic_name: &str,
         qos: Option<qos::Profile>,
     ) -> Result<Self> {
+        node.validate_topic_name(topic_name)?;
         let mut publisher = rcl::MTSafeFn::rcl_get_zero_initialized_publisher();
 
         let topic_name_c = CString::new(topic_name).unwrap_or_default();
@@ -143,7 +192,8 @@ impl<T: TypeSupport> Publisher<T> {
             guard.rcl_publisher_init(
                 &mut publisher,
                 node.as_ptr(),
-                T::type_support() as *const rcl::rosidl_message_type_support_t,
+                T::try_type_support().map_err(Error::TypeSupport)?
+                    as *const rcl::rosidl_message_type_support_t,
                 topic_name_c.as_ptr(),
                 options.as_ptr(),
             )?;
@@ -156,16 +206,44 @@ impl<T: TypeSupport> Publisher<T> {
         );
 
         Ok(Publisher {
-            publisher: Arc::new(publisher),
-            node,
+            #[cfg(feature = "custom_alloc")]
+            message_pool: node.message_pool,
+            _entity: node.entities.register(EntityKind::Publisher),
+            publisher: Arc::new(RCLPublisher { publisher, node }),
+            loans: Default::default(),
             _phantom: Default::default(),
+            auto_stamp: None,
         })
     }
 
+    /// Allocate the messages built by [`Publisher::with_message_pool`] from `pool`.
+    ///
+    /// Defaults to the pool of the node. See [`crate::allocator`].
+    #[cfg(feature = "custom_alloc")]
+    pub fn set_message_pool(&mut self, pool: Option<&'static PoolAllocator>) {
+        self.message_pool = pool;
+    }
+
+    /// The pool of the messages of this publisher.
+    #[cfg(feature = "custom_alloc")]
+    pub fn message_pool(&self) -> Option<&'static PoolAllocator> {
+        self.message_pool
+    }
+
+    /// Run `f` with the pool of this publisher installed, e.g. to build a message.
+    ///
+    /// ```ignore
+    /// let mut msg = publisher.with_message_pool(JointState::new).unwrap();
+    /// ```
+    #[cfg(feature = "custom_alloc")]
+    pub fn with_message_pool<R>(&self, f: impl FnOnce() -> R) -> R {
+        crate::allocator::with_message_pool(self.message_pool, f)
+    }
+
     /// Get the fully qualified topic name (includes namespace).
     pub fn fully_qualified_topic_name(&self) -> Result<Cow<'_, String>> {
         let guard = MT_UNSAFE_FN.lock();
-        let name = guard.rcl_publisher_get_topic_name(self.publisher.as_ref())?;
+        let name = guard.rcl_publisher_get_topic_name(self.publisher.as_ptr())?;
         Ok(Cow::Owned(name))
     }
 
@@ -177,13 +255,57 @@ impl<T: TypeSupport> Publisher<T> {
         Ok(Cow::Owned(name))
     }
 
+    /// Get the parent node.
+    pub fn node(&self) -> &Arc<Node> {
+        &self.publisher.node
+    }
+
+    /// Whether the middleware can loan messages to this publisher.
+    ///
+    /// This depends on the RMW implementation, its configuration and the
+    /// message type `T`; loaning is disabled for publishers created by
+    /// `create_publisher_disable_loaned_message`.
+    pub fn can_loan(&self) -> bool {
+        rcl::MTSafeFn::rcl_publisher_can_loan_messages(self.publisher.as_ptr())
+    }
+
+    #[deprecated(note = "use can_loan instead")]
     pub fn can_loan_messages(&self) -> bool {
-        rcl::MTSafeFn::rcl_publisher_can_loan_messages(self.publisher.as_ref())
+        self.can_loan()
+    }
+
+    /// Create a handle which sends messages through this publisher without
+    /// keeping it alive.
+    ///
+    /// The publisher is destroyed when it is dropped, even if other threads
+    /// still hold a [`WeakPublisher`].
+    pub fn downgrade(&self) -> WeakPublisher<T> {
+        WeakPublisher {
+            publisher: Arc::downgrade(&self.publisher),
+            _phantom: Default::default(),
+        }
+    }
+
+    /// Number of messages sent through a loan and by copy.
+    pub fn loan_stats(&self) -> LoanStats {
+        self.loans.stats()
     }
 
     /// Borrows a memory chunk from the shared memory.
+    ///
+    /// If the middleware cannot loan messages, see [`Publisher::can_loan`],
+    /// the message is allocated by the application and copied when sent.
+    /// A warning is logged the first time it happens.
     pub fn borrow_loaned_message(&self) -> Result<PublisherLoanedMessage<T>> {
-        PublisherLoanedMessage::new(self.publisher.clone())
+        let msg = PublisherLoanedMessage::new(self.publisher.clone())?;
+        if matches!(msg, PublisherLoanedMessage::Copied(_)) && self.loans.first_fallback() {
+            tracing::warn!(
+                target: targets::PUBLISHER,
+                topic = %self.topic_name().unwrap_or_default(),
+                "The middleware cannot loan messages, they are copied instead"
+            );
+        }
+        Ok(msg)
     }
 
     /// Send a message.
@@ -218,7 +340,14 @@ impl<T: TypeSupport> Publisher<T> {
 
         let start = std::time::Instant::now();
 
-        rcl::MTSafeFn::rcl_publish(self.publisher.as_ref(), msg as *const T as _, null_mut())?;
+        let stamped = match self.auto_stamp {
+            Some(stamp) => stamp(msg, self.publisher.node.get_clock()?.lock().get_now()?)?,
+            None => None,
+        };
+        let msg = stamped.as_ref().unwrap_or(msg);
+
+        rcl::MTSafeFn::rcl_publish(self.publisher.as_ptr(), msg as *const T as _, null_mut())?;
+        self.loans.count(false);
 
         tracing::debug!(
             target: targets::PUBLISHER,
@@ -229,6 +358,26 @@ impl<T: TypeSupport> Publisher<T> {
         Ok(())
     }
 
+    /// Send a message from a realtime loop.
+    ///
+    /// Same as [`Publisher::send`], without tracing, so that the call takes no
+    /// lock and does not allocate on the Rust side once the publisher is
+    /// created. Whether `rcl_publish` allocates depends on the middleware;
+    /// messages with sequences or strings should be built once and reused,
+    /// or taken from a message pool of the `custom_alloc` feature.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Publisher::send`].
+    pub fn publish_rt(&self, msg: &T) -> Result<()> {
+        if crate::is_halt() {
+            return Err(Signaled.into());
+        }
+        rcl::MTSafeFn::rcl_publish(self.publisher.as_ptr(), msg as *const T as _, null_mut())?;
+        self.loans.count(false);
+        Ok(())
+    }
+
     /// Send a loaned message.
     ///
     /// This functions takes the ownership of the loaned message since its chunk should be transferred back to the middleware.
@@ -239,7 +388,9 @@ impl<T: TypeSupport> Publisher<T> {
 
         let start = std::time::Instant::now();
 
+        let loaned = matches!(msg, PublisherLoanedMessage::Loaned(_));
         msg.send()?;
+        self.loans.count(loaned);
 
         tracing::debug!(
             target: targets::PUBLISHER,
@@ -250,6 +401,126 @@ impl<T: TypeSupport> Publisher<T> {
         Ok(())
     }
 
+    /// Block until every matched subscriber has acknowledged the messages
+    /// sent so far, or `timeout` elapses.
+    ///
+    /// A zero `timeout` only checks whether the messages are acknowledged.
+    /// Best effort publishers return immediately.
+    ///
+    /// # Errors
+    ///
+    /// - `RCLError::Timeout` if the messages are not acknowledged in time, or
+    /// - `RCLError::Unsupported` if the middleware does not support it, or
+    /// - `RCLError::PublisherInvalid` if the publisher is invalid.
+    pub fn wait_for_all_acked(&self, timeout: Duration) -> Result<()> {
+        let timeout = timeout.as_nanos().min(i64::MAX as u128) as i64;
+        rcl::MTSafeFn::rcl_publisher_wait_for_all_acked(self.publisher.as_ptr(), timeout)
+    }
+
+    /// Tell the subscribers that this publisher is alive.
+    ///
+    /// Publishers with [`qos::LivelinessPolicy::ManualByTopic`] have to call it,
+    /// or send a message, within [`qos::Profile::liveliness_lease_duration`].
+    ///
+    /// # Errors
+    ///
+    /// - `RCLError::PublisherInvalid` if the publisher is invalid, or
+    /// - `RCLError::Error` if an unspecified error occurs.
+    pub fn assert_liveliness(&self) -> Result<()> {
+        rcl::MTSafeFn::rcl_publisher_assert_liveliness(self.publisher.as_ptr())
+    }
+
+    /// The QoS profile actually used by the middleware.
+    ///
+    /// Policies requested as system default are resolved, e.g. the
+    /// [`qos::Profile::deadline`] offered by this publisher.
+    ///
+    /// # Errors
+    ///
+    /// - `RCLError::PublisherInvalid` if the publisher is invalid.
+    pub fn actual_qos(&self) -> Result<qos::Profile> {
+        let qos = rcl::MTSafeFn::rcl_publisher_get_actual_qos(self.publisher.as_ptr());
+        if qos.is_null() {
+            return Err(RclError::PublisherInvalid.into());
+        }
+        Ok(unsafe { &*qos }.into())
+    }
+
+    /// The local transport addresses used by the middleware to send the
+    /// messages, e.g. the UDP ports of DDS.
+    ///
+    /// # Errors
+    ///
+    /// - `RCLError::Unsupported` if the middleware does not support it, or
+    /// - `RCLError::PublisherInvalid` if the publisher is invalid.
+    pub fn get_network_flow_endpoints(&self) -> Result<Vec<NetworkFlowEndpoint>> {
+        rcl::MTSafeFn::rcl_publisher_get_network_flow_endpoints(self.publisher.as_ptr())
+    }
+
+    /// Create a QoS event of this publisher.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oxidros_rcl::{
+    ///     context::Context,
+    ///     msg::common_interfaces::std_msgs,
+    ///     qos::{Profile, PublisherEventKind},
+    /// };
+    /// use std::time::Duration;
+    ///
+    /// let ctx = Context::new().unwrap();
+    /// let node = ctx
+    ///     .create_node_with_opt("publish_rs_event", None, Default::default())
+    ///     .unwrap();
+    ///
+    /// let mut profile = Profile::default();
+    /// profile.deadline = Duration::from_millis(100);
+    /// let publisher = node
+    ///     .create_publisher::<std_msgs::msg::Empty>("publish_rs_event_topic", Some(profile))
+    ///     .unwrap();
+    ///
+    /// let mut selector = ctx.create_selector().unwrap();
+    /// let event = publisher.event(PublisherEventKind::OfferedDeadlineMissed).unwrap();
+    /// selector.add_publisher_event(
+    ///     event,
+    ///     Box::new(|status| println!("missed {} deadlines", status.total_count)),
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - `RCLError::Unsupported` if the middleware does not support the event, or
+    /// - `RCLError::PublisherInvalid` if the publisher is invalid.
+    pub fn event(&self, kind: PublisherEventKind) -> Result<PublisherEvent> {
+        let event_type = match kind {
+            PublisherEventKind::OfferedDeadlineMissed => {
+                rcl::rcl_publisher_event_type_t::RCL_PUBLISHER_OFFERED_DEADLINE_MISSED
+            }
+            PublisherEventKind::LivelinessLost => {
+                rcl::rcl_publisher_event_type_t::RCL_PUBLISHER_LIVELINESS_LOST
+            }
+        };
+
+        let mut event = Arc::new(RCLPublisherEvent {
+            event: rcl::MTSafeFn::rcl_get_zero_initialized_event(),
+            kind,
+            publisher: self.publisher.clone(),
+        });
+        {
+            // not shared yet
+            let event = Arc::get_mut(&mut event).unwrap();
+            let guard = rcl::MT_UNSAFE_FN.lock();
+            guard.rcl_publisher_event_init(
+                &mut event.event,
+                self.publisher.as_ptr(),
+                event_type,
+            )?;
+        }
+
+        Ok(PublisherEvent { event })
+    }
+
     /// Send a raw message.
     ///
     /// # Safety
@@ -263,7 +534,8 @@ impl<T: TypeSupport> Publisher<T> {
 
         let start = std::time::Instant::now();
 
-        rcl::MTSafeFn::rcl_publish_serialized_message(self.publisher.as_ref(), msg, null_mut())?;
+        rcl::MTSafeFn::rcl_publish_serialized_message(self.publisher.as_ptr(), msg, null_mut())?;
+        self.loans.count(false);
 
         tracing::debug!(
             target: targets::PUBLISHER,
@@ -276,16 +548,186 @@ impl<T: TypeSupport> Publisher<T> {
     }
 }
 
-impl<T> Drop for Publisher<T> {
+/// Publisher which does not keep the underlying publisher alive, see
+/// [`Publisher::downgrade`].
+///
+/// Messages sent through it are not counted in [`Publisher::loan_stats`].
+pub struct WeakPublisher<T> {
+    publisher: Weak<RCLPublisher>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: TypeSupport> WeakPublisher<T> {
+    /// Whether the publisher is still alive.
+    pub fn is_alive(&self) -> bool {
+        self.publisher.strong_count() > 0
+    }
+
+    /// Send a message if the publisher is still alive.
+    ///
+    /// # Errors
+    ///
+    /// - `RCLError::PublisherInvalid` if the publisher was dropped, or
+    /// - the errors of [`Publisher::send`].
+    pub fn send(&self, msg: &T) -> Result<()> {
+        if crate::is_halt() {
+            return Err(Signaled.into());
+        }
+        let publisher = self.publisher.upgrade().ok_or(RclError::PublisherInvalid)?;
+        rcl::MTSafeFn::rcl_publish(publisher.as_ptr(), msg as *const T as _, null_mut())
+    }
+}
+
+impl<T: TypeSupport + Stamped + TryClone> Publisher<T> {
+    /// Fill the stamps left to zero with the time of the clock of the node.
+    ///
+    /// Messages whose stamp is zero are copied and stamped by
+    /// [`Publisher::send`], so that late or missing stamps do not cause
+    /// extrapolation errors in TF. The other sending functions do not stamp
+    /// messages. Disabled by default.
+    pub fn set_auto_stamp(&mut self, enable: bool) {
+        self.auto_stamp = enable.then_some(stamp_if_unset::<T> as StampFn<T>);
+    }
+
+    /// Whether the stamps left to zero are filled, see
+    /// [`Publisher::set_auto_stamp`].
+    pub fn auto_stamp(&self) -> bool {
+        self.auto_stamp.is_some()
+    }
+}
+
+impl<T: TypeSupport + TypeDescription> Publisher<T> {
+    /// Get the type name and hash of the messages, as advertised in the graph.
+    pub fn type_info(&self) -> Result<TypeInfo> {
+        TypeInfo::of_message::<T>()
+    }
+}
+
+impl<T: TypeSupport + TypeDescription> fmt::Debug for Publisher<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("Publisher");
+        if let Ok(topic) = self.fully_qualified_topic_name() {
+            s.field("topic", &topic);
+        }
+        if let Ok(type_info) = self.type_info() {
+            s.field("type_info", &type_info);
+        }
+        s.finish_non_exhaustive()
+    }
+}
+
+impl<T> Clone for WeakPublisher<T> {
+    fn clone(&self) -> Self {
+        Self {
+            publisher: self.publisher.clone(),
+            _phantom: Default::default(),
+        }
+    }
+}
+
+unsafe impl<T> Sync for WeakPublisher<T> {}
+unsafe impl<T> Send for WeakPublisher<T> {}
+
+/// An initialized `rcl_publisher_t`, shared by a publisher, its loaned
+/// messages and its QoS events.
+pub(crate) struct RCLPublisher {
+    publisher: rcl::rcl_publisher_t,
+    node: Arc<Node>,
+}
+
+impl RCLPublisher {
+    pub(crate) fn as_ptr(&self) -> *const rcl::rcl_publisher_t {
+        &self.publisher
+    }
+}
+
+impl Drop for RCLPublisher {
     fn drop(&mut self) {
-        let (node, publisher) = (&mut self.node, &mut self.publisher);
         let guard = rcl::MT_UNSAFE_FN.lock();
-        let _ = guard.rcl_publisher_fini(publisher.as_ref() as *const _ as *mut _, unsafe {
-            node.as_ptr_mut()
-        });
+        let _ = guard.rcl_publisher_fini(&mut self.publisher, unsafe { self.node.as_ptr_mut() });
     }
 }
 
+unsafe impl Sync for RCLPublisher {}
+unsafe impl Send for RCLPublisher {}
+
+/// QoS event of a publisher, see [`Publisher::event`].
+///
+/// Wait for it with [`crate::selector::Selector::add_publisher_event`], or
+/// read its status with [`PublisherEvent::take`].
+pub struct PublisherEvent {
+    pub(crate) event: Arc<RCLPublisherEvent>,
+}
+
+impl PublisherEvent {
+    /// Kind of the event.
+    pub fn kind(&self) -> PublisherEventKind {
+        self.event.kind()
+    }
+
+    /// Read the status of the event and reset its `total_count_change`.
+    ///
+    /// # Errors
+    ///
+    /// - `RCLError::EventTakeFailed` if the status could not be taken.
+    pub fn take(&self) -> Result<QosEventStatus> {
+        self.event.take()
+    }
+}
+
+pub(crate) struct RCLPublisherEvent {
+    pub(crate) event: rcl::rcl_event_t,
+    kind: PublisherEventKind,
+    publisher: Arc<RCLPublisher>,
+}
+
+impl RCLPublisherEvent {
+    pub(crate) fn take(&self) -> Result<QosEventStatus> {
+        match self.kind {
+            PublisherEventKind::OfferedDeadlineMissed => {
+                let mut status = rcl::rmw_offered_deadline_missed_status_t {
+                    total_count: 0,
+                    total_count_change: 0,
+                };
+                rcl::MTSafeFn::rcl_take_event(&self.event, &mut status as *mut _ as _)?;
+                Ok(QosEventStatus {
+                    total_count: status.total_count,
+                    total_count_change: status.total_count_change,
+                })
+            }
+            PublisherEventKind::LivelinessLost => {
+                let mut status = rcl::rmw_liveliness_lost_status_t {
+                    total_count: 0,
+                    total_count_change: 0,
+                };
+                rcl::MTSafeFn::rcl_take_event(&self.event, &mut status as *mut _ as _)?;
+                Ok(QosEventStatus {
+                    total_count: status.total_count,
+                    total_count_change: status.total_count_change,
+                })
+            }
+        }
+    }
+
+    pub(crate) fn kind(&self) -> PublisherEventKind {
+        self.kind
+    }
+
+    pub(crate) fn node(&self) -> &Arc<Node> {
+        &self.publisher.node
+    }
+}
+
+impl Drop for RCLPublisherEvent {
+    fn drop(&mut self) {
+        let guard = rcl::MT_UNSAFE_FN.lock();
+        let _ = guard.rcl_event_fini(&mut self.event);
+    }
+}
+
+unsafe impl Sync for RCLPublisherEvent {}
+unsafe impl Send for RCLPublisherEvent {}
+
 /// Options for publishers.
 struct Options {
     options: rcl::rcl_publisher_options_t,
@@ -1,6 +1,6 @@
 use std::{ptr::null_mut, sync::Arc};
 
-use crate::{error::Result, msg::TypeSupport, rcl};
+use crate::{error::Result, msg::TypeSupport, rcl, topic::publisher::RCLPublisher};
 
 /// A message loaned by a publisher.
 ///
@@ -13,8 +13,8 @@ pub enum PublisherLoanedMessage<T: TypeSupport> {
 unsafe impl<T: Send + TypeSupport> Send for PublisherLoanedMessage<T> {}
 
 impl<T: TypeSupport> PublisherLoanedMessage<T> {
-    pub(crate) fn new(publisher: Arc<rcl::rcl_publisher_t>) -> Result<Self> {
-        if rcl::MTSafeFn::rcl_publisher_can_loan_messages(publisher.as_ref() as *const _) {
+    pub(crate) fn new(publisher: Arc<RCLPublisher>) -> Result<Self> {
+        if rcl::MTSafeFn::rcl_publisher_can_loan_messages(publisher.as_ptr()) {
             Ok(Self::Loaned(Loaned::new(publisher)?))
         } else {
             // Allocate if loaning is not available
@@ -26,14 +26,14 @@ impl<T: TypeSupport> PublisherLoanedMessage<T> {
         match self {
             PublisherLoanedMessage::Copied(msg) => {
                 rcl::MTSafeFn::rcl_publish(
-                    msg.publisher.as_ref(),
+                    msg.publisher.as_ptr(),
                     &msg.value as *const T as _,
                     null_mut(),
                 )?;
             }
             PublisherLoanedMessage::Loaned(mut msg) => {
                 rcl::MTSafeFn::rcl_publish_loaned_message(
-                    msg.publisher.as_ref(),
+                    msg.publisher.as_ptr(),
                     msg.as_mut_ptr() as *const _ as *mut _,
                     null_mut(),
                 )?;
@@ -67,29 +67,29 @@ impl<T: TypeSupport> std::ops::DerefMut for PublisherLoanedMessage<T> {
 }
 
 pub struct Copied<T: TypeSupport> {
-    publisher: Arc<rcl::rcl_publisher_t>,
+    publisher: Arc<RCLPublisher>,
     value: T,
 }
 
 impl<T: TypeSupport> Copied<T> {
-    pub fn new(publisher: Arc<rcl::rcl_publisher_t>) -> Self {
+    pub(crate) fn new(publisher: Arc<RCLPublisher>) -> Self {
         let value: T = unsafe { std::mem::zeroed() };
         Self { publisher, value }
     }
 }
 
 pub struct Loaned<T: TypeSupport> {
-    publisher: Arc<rcl::rcl_publisher_t>,
+    publisher: Arc<RCLPublisher>,
     chunk: *mut T,
     returned: bool,
 }
 
 impl<T: TypeSupport> Loaned<T> {
-    pub(crate) fn new(publisher: Arc<rcl::rcl_publisher_t>) -> Result<Self> {
+    pub(crate) fn new(publisher: Arc<RCLPublisher>) -> Result<Self> {
         let mut chunk = null_mut();
         let guard = rcl::MT_UNSAFE_FN.lock();
         guard.rcl_borrow_loaned_message(
-            publisher.as_ref(),
+            publisher.as_ptr(),
             T::type_support() as *const rcl::rosidl_message_type_support_t,
             &mut chunk,
         )?;
@@ -119,7 +119,7 @@ impl<T: TypeSupport> Drop for Loaned<T> {
 
         let guard = rcl::MT_UNSAFE_FN.lock();
         let _ = guard.rcl_return_loaned_message_from_publisher(
-            self.publisher.as_ref(),
+            self.publisher.as_ptr(),
             self.chunk as *const _ as *mut _,
         );
     }
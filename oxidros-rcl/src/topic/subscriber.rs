@@ -159,13 +159,19 @@ use crate::{
     rcl::{self, MT_UNSAFE_FN},
     selector::async_selector,
     signal_handler::Signaled,
-    topic::subscriber_loaned_message::SubscriberLoanedMessage,
+    topic::{LoanCounters, LoanStats, subscriber_loaned_message::SubscriberLoanedMessage},
 };
 pub use oxidros_core::message::Message;
-use oxidros_core::{Error, MessageInfo, RclError, selector::CallbackResult, targets};
+use oxidros_core::{
+    Error, MessageInfo, NetworkFlowEndpoint, RclError, TypeCheckPolicy, TypeDescription, TypeInfo,
+    selector::CallbackResult,
+    stats::{EntityKind, EntityToken},
+    targets,
+};
 use std::{
     borrow::Cow,
     ffi::CString,
+    fmt,
     future::Future,
     marker::PhantomData,
     os::raw::c_void,
@@ -175,9 +181,13 @@ use std::{
     task::{self, Poll},
 };
 
+#[cfg(feature = "custom_alloc")]
+use crate::allocator::PoolAllocator;
+
 pub(crate) struct RCLSubscription {
     pub subscription: Box<rcl::rcl_subscription_t>,
     pub node: Arc<Node>,
+    pub loans: LoanCounters,
 }
 
 impl Drop for RCLSubscription {
@@ -194,8 +204,12 @@ unsafe impl Send for RCLSubscription {}
 /// Subscriber.
 pub struct Subscriber<T> {
     pub(crate) subscription: Arc<RCLSubscription>,
+    _entity: EntityToken,
+    type_check: TypeCheckPolicy,
     _phantom: PhantomData<T>,
     _unsync: PhantomUnsync,
+    #[cfg(feature = "custom_alloc")]
+    message_pool: Option<&'static PoolAllocator>,
 }
 
 impl<T: TypeSupport> Subscriber<T> {
@@ -204,6 +218,7 @@ impl<T: TypeSupport> Subscriber<T> {
         topic_name: &str,
         qos: Option<qos::Profile>,
     ) -> Result<Self> {
+        node.validate_topic_name(topic_name)?;
         let mut subscription = Box::new(rcl::MTSafeFn::rcl_get_zero_initialized_subscription());
 
         let topic_name_c = CString::new(topic_name).unwrap_or_default();
@@ -216,7 +231,8 @@ impl<T: TypeSupport> Subscriber<T> {
             guard.rcl_subscription_init(
                 subscription.as_mut(),
                 node.as_ptr(),
-                T::type_support() as *const rcl::rosidl_message_type_support_t,
+                T::try_type_support().map_err(Error::TypeSupport)?
+                    as *const rcl::rosidl_message_type_support_t,
                 topic_name_c.as_ptr(),
                 options.as_ptr(),
             )?;
@@ -229,7 +245,15 @@ impl<T: TypeSupport> Subscriber<T> {
         );
 
         Ok(Subscriber {
-            subscription: Arc::new(RCLSubscription { subscription, node }),
+            #[cfg(feature = "custom_alloc")]
+            message_pool: node.message_pool,
+            _entity: node.entities.register(EntityKind::Subscriber),
+            type_check: TypeCheckPolicy::default(),
+            subscription: Arc::new(RCLSubscription {
+                subscription,
+                node,
+                loans: Default::default(),
+            }),
             _phantom: Default::default(),
             _unsync: Default::default(),
         })
@@ -240,6 +264,7 @@ impl<T: TypeSupport> Subscriber<T> {
         topic_name: &str,
         qos: Option<qos::Profile>,
     ) -> Result<Self> {
+        node.validate_topic_name(topic_name)?;
         let mut subscription = Box::new(rcl::MTSafeFn::rcl_get_zero_initialized_subscription());
         let topic_name_c = CString::new(topic_name).unwrap_or_default();
         let mut options = Options::new(&qos.unwrap_or_default());
@@ -250,7 +275,8 @@ impl<T: TypeSupport> Subscriber<T> {
             guard.rcl_subscription_init(
                 subscription.as_mut(),
                 node.as_ptr(),
-                T::type_support() as *const rcl::rosidl_message_type_support_t,
+                T::try_type_support().map_err(Error::TypeSupport)?
+                    as *const rcl::rosidl_message_type_support_t,
                 topic_name_c.as_ptr(),
                 options.as_ptr(),
             )?;
@@ -262,12 +288,49 @@ impl<T: TypeSupport> Subscriber<T> {
         );
 
         Ok(Subscriber {
-            subscription: Arc::new(RCLSubscription { subscription, node }),
+            #[cfg(feature = "custom_alloc")]
+            message_pool: node.message_pool,
+            _entity: node.entities.register(EntityKind::Subscriber),
+            type_check: TypeCheckPolicy::default(),
+            subscription: Arc::new(RCLSubscription {
+                subscription,
+                node,
+                loans: Default::default(),
+            }),
             _phantom: Default::default(),
             _unsync: Default::default(),
         })
     }
 
+    /// Allocate the strings and sequences of the received messages from `pool`.
+    ///
+    /// Defaults to the pool of the node. See [`crate::allocator`].
+    #[cfg(feature = "custom_alloc")]
+    pub fn set_message_pool(&mut self, pool: Option<&'static PoolAllocator>) {
+        self.message_pool = pool;
+    }
+
+    /// The pool of the messages of this subscriber.
+    #[cfg(feature = "custom_alloc")]
+    pub fn message_pool(&self) -> Option<&'static PoolAllocator> {
+        self.message_pool
+    }
+
+    /// Set how the messages of publishers with another type hash are handled.
+    ///
+    /// RCL leaves the matching of publishers and subscriptions to the RMW
+    /// implementation, which compares type names only, so the policy is
+    /// recorded but has no effect.
+    pub fn with_type_check(mut self, policy: TypeCheckPolicy) -> Self {
+        self.type_check = policy;
+        self
+    }
+
+    /// Get how the messages of publishers with another type hash are handled.
+    pub fn type_check(&self) -> TypeCheckPolicy {
+        self.type_check
+    }
+
     /// Get the fully qualified topic name (includes namespace).
     pub fn fully_qualified_topic_name(&self) -> Result<Cow<'_, String>> {
         let guard = MT_UNSAFE_FN.lock();
@@ -284,6 +347,36 @@ impl<T: TypeSupport> Subscriber<T> {
         Ok(Cow::Owned(name))
     }
 
+    /// Get the parent node.
+    pub fn node(&self) -> &Arc<Node> {
+        &self.subscription.node
+    }
+
+    /// Whether the middleware can loan the received messages to this subscriber.
+    ///
+    /// Messages are copied when it cannot, see [`Subscriber::loan_stats`].
+    pub fn can_loan(&self) -> bool {
+        rcl::MTSafeFn::rcl_subscription_can_loan_messages(self.subscription.subscription.as_ref())
+    }
+
+    /// The local transport addresses used by the middleware to receive the
+    /// messages, e.g. the UDP ports of DDS.
+    ///
+    /// # Errors
+    ///
+    /// - `RCLError::Unsupported` if the middleware does not support it, or
+    /// - `RCLError::SubscriptionInvalid` if the subscriber is invalid.
+    pub fn get_network_flow_endpoints(&self) -> Result<Vec<NetworkFlowEndpoint>> {
+        rcl::MTSafeFn::rcl_subscription_get_network_flow_endpoints(
+            self.subscription.subscription.as_ref(),
+        )
+    }
+
+    /// Number of messages taken through a loan and by copy.
+    pub fn loan_stats(&self) -> LoanStats {
+        self.subscription.loans.stats()
+    }
+
     /// Non-blocking receive.
     ///
     /// Because `rcl::rcl_take` is non-blocking,
@@ -318,7 +411,11 @@ impl<T: TypeSupport> Subscriber<T> {
         let start = std::time::Instant::now();
 
         let s = self.subscription.clone();
-        match take::<T>(&s) {
+        #[cfg(feature = "custom_alloc")]
+        let taken = crate::allocator::with_message_pool(self.message_pool, || take::<T>(&s));
+        #[cfg(not(feature = "custom_alloc"))]
+        let taken = take::<T>(&s);
+        match taken {
             Ok(n) => {
                 tracing::debug!(
                     target: targets::SUBSCRIBER,
@@ -340,6 +437,38 @@ impl<T: TypeSupport> Subscriber<T> {
             Err(e) => Err(e),
         }
     }
+
+    /// Take up to `max_n` messages that are already queued, without blocking.
+    ///
+    /// This drains a burst of a high-rate topic after a single wakeup.
+    /// The returned vector is empty if no message is available.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Subscriber::try_recv`], if it fails before any message is
+    /// taken. An error after some messages is logged and stops the batch:
+    /// the messages taken so far are returned.
+    pub fn try_recv_many(&self, max_n: usize) -> Result<Vec<Message<T>>> {
+        let mut messages = Vec::with_capacity(max_n.min(64));
+        while messages.len() < max_n {
+            match self.try_recv() {
+                Ok(Some(msg)) => messages.push(msg),
+                Ok(None) => break,
+                Err(e) if messages.is_empty() => return Err(e),
+                Err(e) => {
+                    tracing::warn!(
+                        target: targets::SUBSCRIBER,
+                        taken = messages.len(),
+                        error = %e,
+                        "Stopped taking a batch of messages"
+                    );
+                    break;
+                }
+            }
+        }
+        Ok(messages)
+    }
+
     /// Blocking receive.
     ///
     /// # Errors
@@ -368,6 +497,15 @@ impl<T: TypeSupport> Subscriber<T> {
     /// use mechanisms provided by asynchronous libraries,
     /// such as `tokio::time::timeout`.
     ///
+    /// Messages already received are returned on the first poll, without
+    /// waiting on the selector. With `DurabilityPolicy::TransientLocal`, this
+    /// includes the messages replayed by the publishers to a late-joining
+    /// subscriber, as soon as the RMW delivered them after discovery.
+    ///
+    /// This is cancel safe: the message is taken by the poll that returns it,
+    /// and dropping a pending future removes its registration from the async
+    /// selector, so it can be used in `tokio::select!` without losing messages.
+    ///
     /// # Example
     ///
     /// ```
@@ -433,6 +571,26 @@ impl<T: TypeSupport> Subscriber<T> {
     }
 }
 
+impl<T: TypeSupport + TypeDescription> Subscriber<T> {
+    /// Get the type name and hash of the messages, as advertised in the graph.
+    pub fn type_info(&self) -> Result<TypeInfo> {
+        TypeInfo::of_message::<T>()
+    }
+}
+
+impl<T: TypeSupport + TypeDescription> fmt::Debug for Subscriber<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("Subscriber");
+        if let Ok(topic) = self.fully_qualified_topic_name() {
+            s.field("topic", &topic);
+        }
+        if let Ok(type_info) = self.type_info() {
+            s.field("type_info", &type_info);
+        }
+        s.finish_non_exhaustive()
+    }
+}
+
 /// Asynchronous receiver of subscribers.
 pub struct AsyncReceiver<'a, T> {
     subscriber: &'a mut Subscriber<T>,
@@ -524,12 +682,16 @@ impl Options {
 }
 
 fn take<T: 'static>(subscription: &Arc<RCLSubscription>) -> Result<Message<T>> {
-    if rcl::MTSafeFn::rcl_subscription_can_loan_messages(subscription.subscription.as_ref()) {
+    let loaned =
+        rcl::MTSafeFn::rcl_subscription_can_loan_messages(subscription.subscription.as_ref());
+    let msg = if loaned {
         take_loaned_message(subscription.clone())
-            .map(move |(x, i)| Message::new_loaned(Box::new(x), i))
+            .map(move |(x, i)| Message::new_loaned(Box::new(x), i))?
     } else {
-        rcl_take(subscription.subscription.as_ref()).map(|(x, i)| Message::new(x, i))
-    }
+        rcl_take(subscription.subscription.as_ref()).map(|(x, i)| Message::new(x, i))?
+    };
+    subscription.loans.count(loaned);
+    Ok(msg)
 }
 
 fn take_loaned_message<T>(
@@ -0,0 +1,134 @@
+//! Type description service.
+//!
+//! Since Jazzy, ROS2 nodes serve `~/get_type_description`
+//! (`type_description_interfaces/srv/GetTypeDescription`), which describes
+//! the types of their publishers, subscribers, clients and servers so that
+//! introspection tools and dynamic subscribers can decode them.
+//!
+//! The descriptions come from the type cache of the rcl node, filled from the
+//! type supports of the messages when the entities are created.
+//!
+//! # Example
+//!
+//! ```
+//! use oxidros_rcl::{compat::rcl_features, context::Context};
+//!
+//! let ctx = Context::new().unwrap();
+//! let node = ctx
+//!     .create_node_with_opt("type_description_ex", None, Default::default())
+//!     .unwrap();
+//!
+//! if rcl_features::has_type_description_service() {
+//!     // Served until `server` is dropped.
+//!     let _server = node.create_type_description_server().unwrap();
+//! }
+//! ```
+
+use crate::{error::Result, node::Node};
+use std::sync::Arc;
+
+#[cfg(any(ros_distro_jazzy, ros_distro_kilted, ros_distro_lyrical))]
+use crate::{
+    msg::interfaces::type_description_interfaces::srv::{
+        GetTypeDescription, GetTypeDescription_Response,
+    },
+    qos::Profile,
+    rcl,
+    selector::guard_condition::GuardCondition,
+};
+#[cfg(any(ros_distro_jazzy, ros_distro_kilted, ros_distro_lyrical))]
+use oxidros_core::selector::CallbackResult;
+#[cfg(any(ros_distro_jazzy, ros_distro_kilted, ros_distro_lyrical))]
+use std::{cell::Cell, rc::Rc};
+
+/// Server of the `~/get_type_description` service of a node.
+///
+/// Requests are served by a dedicated thread until the server is dropped.
+pub struct TypeDescriptionServer {
+    #[cfg(any(ros_distro_jazzy, ros_distro_kilted, ros_distro_lyrical))]
+    handler: Option<std::thread::JoinHandle<Result<()>>>,
+    #[cfg(any(ros_distro_jazzy, ros_distro_kilted, ros_distro_lyrical))]
+    cond_halt: GuardCondition,
+}
+
+impl TypeDescriptionServer {
+    #[cfg(any(ros_distro_jazzy, ros_distro_kilted, ros_distro_lyrical))]
+    pub(crate) fn new(node: Arc<Node>) -> Result<Self> {
+        let cond_halt = GuardCondition::new(node.context.clone())?;
+        let cond_halt_cloned = cond_halt.clone();
+
+        let handler = std::thread::Builder::new()
+            .name("oxidros-type-description".into())
+            .spawn(move || type_description_server(node, cond_halt_cloned))
+            .expect("spawn type description server thread");
+
+        Ok(Self {
+            handler: Some(handler),
+            cond_halt,
+        })
+    }
+
+    #[cfg(not(any(ros_distro_jazzy, ros_distro_kilted, ros_distro_lyrical)))]
+    pub(crate) fn new(_node: Arc<Node>) -> Result<Self> {
+        Err(oxidros_core::Error::NotImplemented {
+            feature: "type_description_service".into(),
+            reason: "rcl provides type descriptions since Jazzy".into(),
+        })
+    }
+}
+
+#[cfg(any(ros_distro_jazzy, ros_distro_kilted, ros_distro_lyrical))]
+impl Drop for TypeDescriptionServer {
+    fn drop(&mut self) {
+        if self.cond_halt.trigger().is_ok()
+            && let Some(handler) = self.handler.take()
+        {
+            let _ = handler.join();
+        }
+    }
+}
+
+#[cfg(any(ros_distro_jazzy, ros_distro_kilted, ros_distro_lyrical))]
+fn type_description_server(node: Arc<Node>, cond_halt: GuardCondition) -> Result<()> {
+    let mut selector = node.context.create_selector()?;
+
+    let name = node.name()?;
+    let server = node.create_server::<GetTypeDescription>(
+        &format!("{name}/get_type_description"),
+        Some(Profile::services_default()),
+    )?;
+    let n = node.clone();
+    selector.add_server(
+        server,
+        Box::new(move |req| {
+            let mut response = GetTypeDescription_Response::new().unwrap();
+            // rcl only reads the request, the header is not used
+            let header: rcl::rmw_request_id_t = unsafe { std::mem::zeroed() };
+            let guard = rcl::MT_UNSAFE_FN.lock();
+            guard.rcl_node_type_description_service_handle_request(
+                unsafe { n.as_ptr_mut() },
+                &header,
+                &*req as *const _ as *const _,
+                &mut response as *mut _ as *mut _,
+            );
+            response
+        }),
+    );
+
+    let is_halt = Rc::new(Cell::new(false));
+    let is_halt_cloned = is_halt.clone();
+    selector.add_guard_condition(
+        &cond_halt,
+        Some(Box::new(move || {
+            is_halt_cloned.set(true);
+            CallbackResult::Remove
+        })),
+        false,
+    );
+
+    while !is_halt.get() {
+        selector.wait()?;
+    }
+
+    Ok(())
+}
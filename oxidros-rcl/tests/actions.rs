@@ -5,7 +5,7 @@ pub mod common;
 use oxidros_rcl::{
     action::{
         GoalStatus,
-        client::Client,
+        client::{Client, ClientQosOption},
         handle::GoalHandle,
         server::{Server, ServerQosOption},
     },
@@ -16,11 +16,19 @@ use oxidros_rcl::{
             Fibonacci, Fibonacci_Feedback, Fibonacci_GetResult_Request, Fibonacci_Goal,
             Fibonacci_Result,
         },
-        interfaces::action_msgs::{msg::GoalInfo, srv::CancelGoal_Request},
+        interfaces::action_msgs::{
+            msg::GoalInfo,
+            srv::{CancelGoal_Request, CancelGoal_Response},
+        },
         unique_identifier_msgs::msg::UUID,
     },
+    qos::{Profile, ReliabilityPolicy},
+};
+use std::{
+    sync::{Arc, mpsc},
+    thread,
+    time::Duration,
 };
-use std::{sync::Arc, thread, time::Duration};
 
 fn create_server(
     ctx: &Arc<Context>,
@@ -226,6 +234,64 @@ fn test_action_cancel() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_action_cancel_all() -> Result<()> {
+    let ctx = Context::new()?;
+
+    let mut client = create_client(
+        &ctx,
+        "test_action_cancel_all_client",
+        "test_action_cancel_all",
+    )?;
+
+    let mut selector = ctx.create_selector()?;
+    let server = create_server(
+        &ctx,
+        "test_action_cancel_all_server",
+        "test_action_cancel_all",
+        None,
+    )?;
+
+    selector.add_action_server(server, |_| true, accept_handler, move |_goal| true);
+
+    // send two goals and wait until both are accepted
+    let uuids: [[u8; 16]; 2] = [rand::random(), rand::random()];
+    for uuid in uuids {
+        let recv = client.send_goal_with_uuid(Fibonacci_Goal { order: 10 }, uuid)?;
+        loop {
+            match recv.recv_timeout(Duration::from_secs(3), &mut selector) {
+                Ok(Some((data, _header))) => {
+                    assert!(data.accepted);
+                    break;
+                }
+                Ok(None) => {}
+                Err(e) => panic!("{}", e),
+            }
+        }
+    }
+
+    let recv = client.cancel_all_goals()?;
+    loop {
+        match recv.recv_timeout(Duration::from_secs(3), &mut selector) {
+            Ok(Some((data, _header))) => {
+                assert_eq!(data.return_code, CancelGoal_Response::ERROR_NONE);
+                let canceling: Vec<_> = data
+                    .goals_canceling
+                    .iter()
+                    .map(|goal| goal.goal_id.uuid)
+                    .collect();
+                assert_eq!(canceling.len(), uuids.len());
+                assert!(uuids.iter().all(|uuid| canceling.contains(uuid)));
+                break;
+            }
+            Ok(None) => println!("retrying"),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_action_status() -> Result<()> {
     let ctx = Context::new()?;
@@ -284,3 +350,126 @@ fn test_action_status() -> Result<()> {
         }
     }
 }
+
+#[test]
+fn test_action_feedback_rate_limit() -> Result<()> {
+    let ctx = Context::new()?;
+
+    let mut client = create_client(
+        &ctx,
+        "test_action_feedback_rate_client",
+        "test_action_feedback_rate",
+    )?;
+
+    let mut selector = ctx.create_selector()?;
+    let server_qos = ServerQosOption {
+        feedback_rate_limit: Some(Duration::from_secs(10)),
+        ..Default::default()
+    };
+    let server = create_server(
+        &ctx,
+        "test_action_feedback_rate_server",
+        "test_action_feedback_rate",
+        Some(server_qos),
+    )?;
+
+    let uuid: [u8; 16] = rand::random();
+    let recv = client.send_goal_with_uuid(Fibonacci_Goal { order: 10 }, uuid)?;
+
+    thread::sleep(Duration::from_millis(100));
+
+    // Send ten feedback messages at once, only the first and the last are published
+    let (tx, rx) = mpsc::channel();
+    selector.add_action_server(
+        server.clone(),
+        |_| true,
+        move |handle: GoalHandle<Fibonacci>| {
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for i in 0..10 {
+                    let feedback = Fibonacci_Feedback {
+                        sequence: vec![i].as_slice().try_into().unwrap(),
+                    };
+                    handle.feedback(feedback).unwrap();
+                }
+                handle
+                    .finish(Fibonacci_Result {
+                        sequence: vec![9].as_slice().try_into().unwrap(),
+                    })
+                    .unwrap();
+                tx.send(handle.feedback_dropped()).unwrap();
+            });
+        },
+        |_| true,
+    );
+
+    loop {
+        match recv.recv_timeout(Duration::from_secs(3), &mut selector) {
+            Ok(Some((data, _header))) => {
+                assert!(data.accepted);
+                break;
+            }
+            Ok(None) => {}
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    let mut received = Vec::new();
+    for _ in 0..20 {
+        if received.last() == Some(&9) {
+            break;
+        }
+        match client.recv_feedback_timeout(Duration::from_secs(1), &mut selector) {
+            Ok(Some(feedback)) => received.push(feedback.feedback.sequence.as_slice()[0]),
+            Ok(None) => {}
+            Err(e) => panic!("{}", e),
+        }
+    }
+    assert_eq!(received, [0, 9]);
+
+    let dropped = rx.recv_timeout(Duration::from_secs(3)).unwrap();
+    assert_eq!(dropped, 8);
+    assert_eq!(server.feedback_dropped(), 8);
+
+    Ok(())
+}
+
+#[test]
+fn test_action_names_and_qos() -> Result<()> {
+    let ctx = Context::new()?;
+
+    // best-effort feedback on both sides
+    let feedback_topic = Profile {
+        reliability: ReliabilityPolicy::BestEffort,
+        ..Profile::default()
+    };
+    let server_qos = ServerQosOption {
+        feedback_topic: feedback_topic.clone(),
+        ..Default::default()
+    };
+    let server = create_server(
+        &ctx,
+        "test_action_names_server",
+        "test_action_names",
+        Some(server_qos),
+    )?;
+
+    let node_client =
+        ctx.create_node_with_opt("test_action_names_client", None, Default::default())?;
+    let client_qos = ClientQosOption {
+        feedback_topic,
+        ..Default::default()
+    };
+    let client = Client::<Fibonacci>::new(node_client, "test_action_names", Some(client_qos))?;
+
+    let names = server.names()?;
+    assert_eq!(names.action, "/test_action_names");
+    assert_eq!(names.send_goal, "/test_action_names/_action/send_goal");
+    assert_eq!(names.cancel_goal, "/test_action_names/_action/cancel_goal");
+    assert_eq!(names.get_result, "/test_action_names/_action/get_result");
+    assert_eq!(names.feedback, "/test_action_names/_action/feedback");
+    assert_eq!(names.status, "/test_action_names/_action/status");
+    assert_eq!(client.names()?, names);
+
+    Ok(())
+}
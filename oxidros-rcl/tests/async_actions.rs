@@ -8,16 +8,22 @@ use oxidros_rcl::{
         GoalStatus,
         client::{Client, ClientGoalRecv, ClientResultRecv},
         handle::GoalHandle,
-        server::{AsyncServer, Server, ServerCancelSend, ServerGoalSend, ServerQosOption},
+        server::{
+            AsyncServer, GoalAcceptancePolicy, Server, ServerCancelSend, ServerGoalSend,
+            ServerQosOption,
+        },
     },
     context::Context,
-    error::Result,
+    error::{ActionError, Error, Result},
     msg::{
         common_interfaces::example_interfaces::action::{
             Fibonacci, Fibonacci_Feedback, Fibonacci_GetResult_Request, Fibonacci_Goal,
             Fibonacci_Result,
         },
-        interfaces::action_msgs::{msg::GoalInfo, srv::CancelGoal_Request},
+        interfaces::{
+            action_msgs::{msg::GoalInfo, srv::CancelGoal_Request},
+            builtin_interfaces::msg::Time,
+        },
         unique_identifier_msgs::msg::UUID,
     },
 };
@@ -264,6 +270,44 @@ async fn run_client_abort(mut client: Client<Fibonacci>) -> Result<()> {
     Ok(())
 }
 
+async fn send_goal(client: &mut Client<Fibonacci>, uuid: [u8; 16]) -> Result<bool> {
+    let receiver = client.send_goal_with_uuid(Fibonacci_Goal { order: 10 }, uuid)?;
+    match tokio::time::timeout(Duration::from_secs(3), receiver.recv()).await {
+        Ok(Ok((response, _header))) => Ok(response.accepted),
+        Ok(Err(e)) => panic!("{e:?}"),
+        Err(_) => panic!("timed out"),
+    }
+}
+
+async fn goal_status(client: &mut Client<Fibonacci>, uuid: [u8; 16]) -> i8 {
+    match tokio::time::timeout(Duration::from_secs(3), client.recv_status()).await {
+        Ok(Ok(status_array)) => status_array
+            .status_list
+            .as_slice()
+            .iter()
+            .find(|s| s.goal_info.goal_id.uuid == uuid)
+            .map(|s| s.status)
+            .unwrap_or(GoalStatus::Unknown as i8),
+        Ok(Err(e)) => panic!("{e:?}"),
+        Err(_) => panic!("timed out"),
+    }
+}
+
+async fn start_server_with_policy(
+    action: &str,
+    policy: GoalAcceptancePolicy,
+) -> Result<Client<Fibonacci>> {
+    let ctx = Context::new()?;
+    let client = create_client(&ctx, &format!("{action}_client"), action)?;
+    let qos = ServerQosOption {
+        goal_acceptance: policy,
+        ..Default::default()
+    };
+    let server = create_server(&ctx, &format!("{action}_server"), action, Some(qos))?;
+    tokio::task::spawn(run_server(server, false));
+    Ok(client)
+}
+
 async fn start_server_client<G>(
     action: &str,
     client_node: &str,
@@ -348,3 +392,130 @@ async fn test_async_action_abort() -> Result<()> {
     .await?;
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_async_action_reject_if_busy() -> Result<()> {
+    let mut client = start_server_with_policy(
+        "test_async_action_reject_if_busy",
+        GoalAcceptancePolicy::RejectIfBusy,
+    )
+    .await?;
+
+    assert!(send_goal(&mut client, rand::random()).await?);
+    assert!(!send_goal(&mut client, rand::random()).await?);
+
+    // the first goal finishes after 6 seconds
+    tokio::time::sleep(Duration::from_secs(8)).await;
+    assert!(send_goal(&mut client, rand::random()).await?);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_async_action_preempt() -> Result<()> {
+    let mut client = start_server_with_policy(
+        "test_async_action_preempt",
+        GoalAcceptancePolicy::PreemptCurrent,
+    )
+    .await?;
+
+    let first: [u8; 16] = rand::random();
+    let second: [u8; 16] = rand::random();
+    assert!(send_goal(&mut client, first).await?);
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(send_goal(&mut client, second).await?);
+
+    // the worker of the first goal notices the cancellation within a second
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert_eq!(
+        goal_status(&mut client, first).await,
+        GoalStatus::Canceled as i8
+    );
+    assert_eq!(
+        goal_status(&mut client, second).await,
+        GoalStatus::Executing as i8
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_async_action_errors() -> Result<()> {
+    let mut client = start_server_with_policy(
+        "test_async_action_errors",
+        GoalAcceptancePolicy::RejectIfBusy,
+    )
+    .await?;
+
+    let first: [u8; 16] = rand::random();
+    assert!(send_goal(&mut client, first).await?);
+
+    // the server is busy with the first goal
+    let receiver = client.send_goal_with_uuid(Fibonacci_Goal { order: 10 }, rand::random())?;
+    let result = tokio::time::timeout(Duration::from_secs(3), receiver.recv_accepted())
+        .await
+        .expect("timed out");
+    assert!(matches!(
+        result,
+        Err(Error::Action(ActionError::GoalRejected))
+    ));
+
+    // no goal has this ID
+    let receiver = client.send_cancel_request(&CancelGoal_Request {
+        goal_info: GoalInfo {
+            goal_id: UUID {
+                uuid: rand::random(),
+            },
+            stamp: Time { sec: 0, nanosec: 0 },
+        },
+    })?;
+    let result = tokio::time::timeout(Duration::from_secs(3), receiver.recv_accepted())
+        .await
+        .expect("timed out");
+    match result {
+        Err(Error::Action(ActionError::CancelRejected | ActionError::UnknownGoalId)) => (),
+        result => panic!("unexpected cancel response: {result:?}"),
+    }
+
+    let receiver = client.send_cancel_request(&CancelGoal_Request {
+        goal_info: GoalInfo {
+            goal_id: UUID { uuid: first },
+            stamp: Time { sec: 0, nanosec: 0 },
+        },
+    })?;
+    let result = tokio::time::timeout(Duration::from_secs(3), receiver.recv_accepted())
+        .await
+        .expect("timed out");
+    assert!(result.is_ok(), "{result:?}");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_async_action_request_result() -> Result<()> {
+    let mut client = start_server_with_policy(
+        "test_async_action_request_result",
+        GoalAcceptancePolicy::AcceptAll,
+    )
+    .await?;
+    client.set_result_cache_duration(Duration::from_secs(60));
+
+    // the response is sent once the goal finishes after 6 seconds
+    let uuid: [u8; 16] = rand::random();
+    assert!(send_goal(&mut client, uuid).await?);
+    let response = tokio::time::timeout(Duration::from_secs(10), client.request_result(uuid))
+        .await
+        .expect("timed out")?;
+    assert_eq!(response.status, GoalStatus::Succeeded as i8);
+    assert_eq!(response.result.sequence.len(), 6);
+
+    let cached = client.cached_result(&uuid).expect("result not cached");
+    assert!(Arc::ptr_eq(&cached, &response));
+    let again = client.request_result(uuid).await?;
+    assert!(Arc::ptr_eq(&again, &response));
+
+    client.set_result_cache_duration(Duration::ZERO);
+    assert!(client.cached_result(&uuid).is_none());
+
+    Ok(())
+}
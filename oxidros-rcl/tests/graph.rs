@@ -0,0 +1,59 @@
+#![cfg(feature = "rcl")]
+
+pub mod common;
+
+use futures_util::StreamExt;
+use oxidros_rcl::{
+    context::Context, error::Error as RclError, graph::GraphEvent,
+    msg::common_interfaces::example_interfaces::msg::Int64,
+};
+use std::{error::Error, time::Duration};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_graph_wait_for_node() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_graph_watcher", None)?;
+    let graph = node.graph();
+
+    let ctx2 = ctx.clone();
+    let th = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        ctx2.create_node("test_graph_late", Some("/graph_ns"))
+            .unwrap()
+    });
+
+    graph
+        .wait_for_node("/graph_ns/test_graph_late", Duration::from_secs(5))
+        .await?;
+    let _late = th.await?;
+    assert!(graph.snapshot()?.contains_node("/graph_ns/test_graph_late"));
+
+    let result = graph
+        .wait_for_node("/test_graph_never", Duration::from_millis(200))
+        .await;
+    assert!(matches!(result, Err(RclError::Timeout)));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_graph_events() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_graph_events", None)?;
+    let mut events = node.graph().events()?;
+
+    let _publisher = node.create_publisher::<Int64>("test_graph_events_topic", None)?;
+
+    let event = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = events.next().await {
+            if let GraphEvent::PublisherAdded(topic) = event? {
+                return Ok::<_, RclError>(topic);
+            }
+        }
+        Err(RclError::ChannelClosed)
+    })
+    .await??;
+    assert_eq!(event, "/test_graph_events_topic");
+
+    Ok(())
+}
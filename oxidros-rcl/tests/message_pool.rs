@@ -0,0 +1,108 @@
+#![cfg(all(feature = "rcl", feature = "custom_alloc"))]
+
+use oxidros_rcl::{
+    allocator::{PoolAllocator, with_message_pool},
+    context::Context,
+    msg::common_interfaces::std_msgs,
+    node::NodeOptions,
+};
+use std::{
+    alloc::{GlobalAlloc, Layout},
+    error::Error,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn test_pool_blocks() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let pool = PoolAllocator::new(60, 4)?;
+    assert_eq!(pool.block_size(), 64);
+    assert_eq!(pool.capacity(), 4);
+
+    let layout = Layout::from_size_align(64, 8)?;
+    let blocks: Vec<_> = (0..4).map(|_| unsafe { pool.alloc(layout) }).collect();
+    assert!(blocks.iter().all(|b| !b.is_null() && pool.contains(*b)));
+    assert_eq!(pool.available(), 0);
+
+    // exhausted, or too large
+    assert!(unsafe { pool.alloc(layout) }.is_null());
+    assert!(unsafe { pool.alloc(Layout::from_size_align(65, 8)?) }.is_null());
+
+    for b in blocks {
+        unsafe { pool.dealloc(b, layout) };
+    }
+    assert_eq!(pool.available(), 4);
+
+    assert!(PoolAllocator::new(0, 4).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_pool_concurrent() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let pool = PoolAllocator::new(32, 64)?;
+    let layout = Layout::from_size_align(32, 8)?;
+
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            std::thread::spawn(move || {
+                for _ in 0..10_000 {
+                    let a = unsafe { pool.alloc(layout) };
+                    let b = unsafe { pool.alloc(layout) };
+                    assert_ne!(a, b);
+                    unsafe {
+                        pool.dealloc(a, layout);
+                        pool.dealloc(b, layout);
+                    }
+                }
+            })
+        })
+        .collect();
+    for th in threads {
+        th.join().unwrap();
+    }
+    assert_eq!(pool.available(), 64);
+
+    Ok(())
+}
+
+#[test]
+fn test_pubsub_message_pool() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let pool = PoolAllocator::new(256, 64)?;
+
+    let ctx = Context::new()?;
+    let node = ctx.create_node_with_opt(
+        "test_message_pool_node",
+        None,
+        NodeOptions::new().message_pool(pool),
+    )?;
+    let publisher = node.create_publisher::<std_msgs::msg::String>("test_message_pool", None)?;
+    let subscriber = node.create_subscriber::<std_msgs::msg::String>("test_message_pool", None)?;
+    assert!(subscriber.message_pool().is_some());
+
+    // the message is built from the pool and returns to it when dropped
+    let mut msg = publisher
+        .with_message_pool(std_msgs::msg::String::new)
+        .unwrap();
+    msg.data.assign("pooled");
+    assert!(pool.available() < 64);
+
+    let deadline = Instant::now() + Duration::from_secs(3);
+    loop {
+        publisher.send(&msg)?;
+        if let Some(received) = subscriber.try_recv()? {
+            assert_eq!(received.data.to_string(), "pooled");
+            break;
+        }
+        assert!(Instant::now() < deadline, "no message received");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    // outside of a scope, messages come from the global allocator
+    with_message_pool(None, || {
+        let before = pool.available();
+        let other = std_msgs::msg::String::new().unwrap();
+        assert_eq!(pool.available(), before);
+        drop(other);
+    });
+
+    Ok(())
+}
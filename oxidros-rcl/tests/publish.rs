@@ -2,8 +2,13 @@
 
 pub mod common;
 
-use oxidros_rcl::{context::Context, msg::common_interfaces::example_interfaces::msg::Int64};
-use std::error::Error;
+use oxidros_rcl::{
+    context::Context,
+    error::RclError,
+    msg::common_interfaces::example_interfaces::msg::Int64,
+    qos::{LivelinessPolicy, Profile, PublisherEventKind},
+};
+use std::{cell::Cell, error::Error, rc::Rc, time::Duration};
 
 #[test]
 fn test_publish() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
@@ -19,3 +24,91 @@ fn test_publish() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
 
     Ok(())
 }
+
+#[test]
+fn test_publisher_qos_events() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let ctx = Context::new()?;
+    let node =
+        ctx.create_node_with_opt("test_publisher_qos_events_node", None, Default::default())?;
+
+    let mut profile = Profile::default();
+    profile.deadline = Duration::from_millis(50);
+    profile.liveliness = LivelinessPolicy::ManualByTopic;
+    profile.liveliness_lease_duration = Duration::from_secs(1);
+    let publisher = node.create_publisher::<Int64>("test_publisher_qos_events", Some(profile))?;
+
+    let qos = publisher.actual_qos()?;
+    assert_eq!(qos.deadline, Duration::from_millis(50));
+    assert_eq!(qos.liveliness, LivelinessPolicy::ManualByTopic);
+    publisher.assert_liveliness()?;
+
+    let event = publisher.event(PublisherEventKind::OfferedDeadlineMissed)?;
+    assert_eq!(event.kind(), PublisherEventKind::OfferedDeadlineMissed);
+
+    let missed = Rc::new(Cell::new(0));
+    let mut selector = ctx.create_selector()?;
+    assert!(selector.add_publisher_event(event, {
+        let missed = missed.clone();
+        Box::new(move |status| missed.set(status.total_count))
+    }));
+
+    publisher.send(&Int64 { data: 1 })?;
+    for _ in 0..20 {
+        if missed.get() > 0 {
+            break;
+        }
+        selector.wait_timeout(Duration::from_millis(100))?;
+    }
+    assert!(missed.get() > 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_network_flow_endpoints() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let ctx = Context::new()?;
+    let node =
+        ctx.create_node_with_opt("test_network_flow_endpoints_node", None, Default::default())?;
+
+    let publisher = node.create_publisher::<Int64>("test_network_flow_endpoints", None)?;
+    let subscriber = node.create_subscriber::<Int64>("test_network_flow_endpoints", None)?;
+
+    // Not every middleware reports them
+    for endpoints in [
+        publisher.get_network_flow_endpoints(),
+        subscriber.get_network_flow_endpoints(),
+    ] {
+        match endpoints {
+            Ok(endpoints) => {
+                for endpoint in endpoints {
+                    assert!(!endpoint.internet_address.is_empty(), "{endpoint}");
+                }
+            }
+            Err(oxidros_rcl::error::Error::Rcl(RclError::Unsupported)) => (),
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_weak_publisher() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node_with_opt("test_weak_publisher_node", None, Default::default())?;
+
+    let publisher = node.create_publisher::<Int64>("test_weak_publisher", None)?;
+    let weak = publisher.downgrade();
+    std::thread::spawn({
+        let weak = weak.clone();
+        move || weak.send(&Int64 { data: 1 })
+    })
+    .join()
+    .unwrap()?;
+
+    drop(publisher);
+    assert!(!weak.is_alive());
+    assert!(weak.send(&Int64 { data: 2 }).is_err());
+
+    Ok(())
+}
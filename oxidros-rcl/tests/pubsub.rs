@@ -4,7 +4,10 @@ pub mod common;
 
 use oxidros_rcl::msg::common_interfaces::example_interfaces::msg::Int64;
 use oxidros_rcl::{context::Context, msg::common_interfaces::std_msgs};
-use std::error::Error;
+use std::{
+    error::Error,
+    time::{Duration, Instant},
+};
 
 const TOPIC_NAME: &str = "test_pubsub";
 
@@ -81,3 +84,38 @@ fn test_pubsub_string() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
 
     Ok(())
 }
+
+#[test]
+fn test_pubsub_recv_many() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    // create a context
+    let ctx = Context::new()?;
+
+    // create a publish node and a subscribe node
+    let node_pub =
+        ctx.create_node_with_opt("test_pubsub_many_pub_node", None, Default::default())?;
+    let node_sub =
+        ctx.create_node_with_opt("test_pubsub_many_sub_node", None, Default::default())?;
+
+    // create a publisher and a subscriber
+    let publisher = common::create_publisher(node_pub, "test_pubsub_many", true)?;
+    let subscriber = common::create_subscriber(node_sub, "test_pubsub_many", true)?;
+
+    // publish a burst of messages
+    for data in 0..5 {
+        publisher.send(&Int64 { data })?;
+    }
+
+    // take them in batches of at most two
+    let mut received = Vec::new();
+    let deadline = Instant::now() + Duration::from_secs(3);
+    while received.len() < 5 && Instant::now() < deadline {
+        let batch = subscriber.try_recv_many(2)?;
+        assert!(batch.len() <= 2);
+        received.extend(batch.into_iter().map(|msg| msg.data));
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(received, vec![0, 1, 2, 3, 4]);
+    assert!(subscriber.try_recv_many(2)?.is_empty());
+
+    Ok(())
+}
@@ -44,3 +44,33 @@ fn test_pubsub_loaned() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
 
     Ok(())
 }
+
+#[test]
+fn test_pubsub_loan_stats() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_pubsub_loan_stats_node", None)?;
+    let topic = "test_pubsub_loan_stats";
+    let publisher = common::create_publisher(node.clone(), topic, false)?;
+    let subscriber = common::create_subscriber(node, topic, false)?;
+
+    let mut loaned = publisher.borrow_loaned_message()?;
+    *loaned = Int64 { data: 1 };
+    publisher.send_loaned(loaned)?;
+    publisher.send(&Int64 { data: 2 })?;
+
+    let stats = publisher.loan_stats();
+    assert_eq!(stats.loaned, publisher.can_loan() as u64);
+    assert_eq!(stats.loaned + stats.copied, 2);
+
+    subscriber.recv_blocking()?;
+    subscriber.recv_blocking()?;
+
+    let stats = subscriber.loan_stats();
+    if subscriber.can_loan() {
+        assert_eq!(stats.loaned, 2);
+    } else {
+        assert_eq!(stats.copied, 2);
+    }
+
+    Ok(())
+}
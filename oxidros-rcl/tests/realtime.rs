@@ -0,0 +1,98 @@
+#![cfg(all(feature = "rcl", not(feature = "custom_alloc")))]
+
+//! Allocation checks of the realtime paths.
+//!
+//! Only the allocations of the Rust side are counted: the C libraries use
+//! `malloc` directly.
+
+use oxidros_rcl::{context::Context, msg::common_interfaces::example_interfaces::msg::Int64};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    error::Error,
+    rc::Rc,
+    time::Duration,
+};
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Run `f` and panic if it allocated on the current thread.
+fn assert_no_alloc<R>(f: impl FnOnce() -> R) -> R {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    let allocations = ALLOCATIONS.with(Cell::get) - before;
+    assert_eq!(allocations, 0, "{allocations} allocations");
+    result
+}
+
+#[test]
+fn test_publish_rt_no_alloc() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_publish_rt_node", None)?;
+    let publisher = node.create_publisher::<Int64>("test_publish_rt", None)?;
+
+    let mut msg = Int64 { data: 0 };
+    publisher.publish_rt(&msg)?;
+
+    assert_no_alloc(|| {
+        for i in 0..100 {
+            msg.data = i;
+            publisher.publish_rt(&msg)?;
+        }
+        Ok::<_, oxidros_rcl::error::Error>(())
+    })?;
+
+    Ok(())
+}
+
+#[test]
+fn test_selector_wait_no_alloc() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_selector_wait_rt_node", None)?;
+    let mut selector = ctx.create_selector()?;
+
+    let count = Rc::new(Cell::new(0));
+    let count_cloned = count.clone();
+    selector.add_timer_with_clock(
+        "test_selector_wait_rt",
+        Duration::from_millis(1),
+        node.get_clock()?,
+        Box::new(move || count_cloned.set(count_cloned.get() + 1)),
+    )?;
+
+    // the first wait sizes the wait set
+    selector.wait()?;
+
+    assert_no_alloc(|| {
+        for _ in 0..10 {
+            selector.wait()?;
+        }
+        Ok::<_, oxidros_rcl::error::Error>(())
+    })?;
+    assert!(count.get() > 1);
+
+    Ok(())
+}
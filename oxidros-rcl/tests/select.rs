@@ -9,6 +9,7 @@ use std::{cell::Cell, error::Error, rc::Rc, sync::Arc, thread, time::Duration};
 const TOPIC_NAME_1: &str = "test_select_1";
 const TOPIC_NAME_2: &str = "test_select_2";
 const TOPIC_NAME_3: &str = "test_select_3";
+const TOPIC_NAME_4: &str = "test_select_4";
 const INIT_1: i64 = 0;
 const INIT_2: i64 = 100;
 const COUNT: i64 = 5;
@@ -122,3 +123,88 @@ fn test_callback() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
 
     Ok(())
 }
+
+#[test]
+fn test_scoped_subscriber() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let ctx = oxidros_rcl::context::Context::new()?;
+
+    let node_pub = ctx.create_node_with_opt("test_scoped_pub_node", None, Default::default())?;
+    let node_sub = ctx.create_node_with_opt("test_scoped_sub_node", None, Default::default())?;
+
+    let publisher = common::create_publisher(node_pub, TOPIC_NAME_4, true)?;
+    let subscriber = common::create_subscriber(node_sub, TOPIC_NAME_4, true)?;
+
+    let cnt = Rc::new(Cell::new(0));
+    let mut selector = ctx.create_selector()?;
+
+    let cnt1 = cnt.clone();
+    let handle = selector
+        .add_scoped_subscriber(subscriber, Box::new(move |_msg| cnt1.set(cnt1.get() + 1)))
+        .unwrap();
+
+    // resend until the subscriber is matched
+    while cnt.get() == 0 {
+        publisher.send(&Int64 { data: 1 })?;
+        selector.wait_timeout(Duration::from_millis(100))?;
+    }
+    let received = cnt.get();
+
+    // unsubscribe from another thread
+    thread::spawn(move || drop(handle)).join().unwrap();
+    selector.wait_timeout(Duration::from_millis(100))?;
+
+    publisher.send(&Int64 { data: 2 })?;
+    for _ in 0..3 {
+        selector.wait_timeout(Duration::from_millis(100))?;
+    }
+    assert_eq!(cnt.get(), received);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_select_fd() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    use oxidros_rcl::selector::FdInterest;
+    use std::io::{Read, Write};
+    use std::os::{fd::AsRawFd, unix::net::UnixStream};
+
+    let ctx = oxidros_rcl::context::Context::new()?;
+    let mut selector = ctx.create_selector()?;
+
+    let (mut tx, mut rx) = UnixStream::pair()?;
+    let received = Rc::new(Cell::new(0));
+    let received_m = received.clone();
+    let id = selector.add_fd(
+        rx.as_raw_fd(),
+        FdInterest::READABLE,
+        Box::new(move |readiness| {
+            assert!(readiness.readable);
+            let mut buf = [0; 16];
+            let n = rx.read(&mut buf).unwrap();
+            received_m.set(received_m.get() + n);
+        }),
+    )?;
+
+    // Not ready
+    assert!(!selector.wait_timeout(Duration::from_millis(50))?);
+
+    tx.write_all(b"ping")?;
+    assert!(selector.wait_timeout(Duration::from_secs(1))?);
+    assert_eq!(received.get(), 4);
+
+    // Removed
+    selector.remove_fd(id);
+    tx.write_all(b"pong")?;
+    assert!(!selector.wait_timeout(Duration::from_millis(50))?);
+    assert_eq!(received.get(), 4);
+
+    // Closed file descriptors are rejected
+    assert!(
+        selector
+            .add_fd(-1, FdInterest::READABLE, Box::new(|_| ()))
+            .is_err()
+    );
+
+    Ok(())
+}
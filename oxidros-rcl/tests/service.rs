@@ -4,12 +4,14 @@ pub mod common;
 
 use oxidros_rcl::{
     context::Context,
-    error::Result,
+    error::{Error, Result},
     msg::common_interfaces::example_interfaces::srv::{AddTwoInts_Request, AddTwoInts_Response},
 };
-use std::time::Duration;
+use std::{cell::Cell, rc::Rc, time::Duration};
 
 const SERVICE_NAME1: &str = "test_service1";
+const SERVICE_NAME2: &str = "test_service_reentrant";
+const SERVICE_NAME3: &str = "test_service_other_context";
 
 #[test]
 fn test_service() -> Result<()> {
@@ -71,3 +73,114 @@ fn test_service() -> Result<()> {
         Err(e) => Err(e),
     }
 }
+
+#[test]
+fn test_service_reentrant() -> Result<()> {
+    let ctx = Context::new()?;
+    let node_server = ctx.create_node_with_opt(
+        "test_service_reentrant_server_node",
+        None,
+        Default::default(),
+    )?;
+    let node_client = ctx.create_node_with_opt(
+        "test_service_reentrant_client_node",
+        None,
+        Default::default(),
+    )?;
+
+    let server = common::create_server(node_server.clone(), SERVICE_NAME2)?;
+    let mut client = common::create_client(node_client, SERVICE_NAME2)?;
+
+    // the handler calls its own service, which cannot answer before it returns
+    let mut nested_client = common::create_client(node_server, SERVICE_NAME2)?;
+    let mut nested_selector = ctx.create_selector()?;
+    let deadlock = Rc::new(Cell::new(false));
+    let deadlock_cloned = deadlock.clone();
+
+    let mut selector = ctx.create_selector()?;
+    selector.add_server(
+        server,
+        Box::new(move |request| {
+            let receiver = nested_client
+                .send(&AddTwoInts_Request { a: 0, b: 0 })
+                .unwrap();
+            let result = receiver.recv_timeout(Duration::from_millis(100), &mut nested_selector);
+            deadlock_cloned.set(matches!(result, Err(Error::WouldDeadlock { .. })));
+            AddTwoInts_Response {
+                sum: request.a + request.b,
+            }
+        }),
+    );
+
+    let receiver = client.send(&AddTwoInts_Request { a: 1, b: 2 })?;
+    for _ in 0..10 {
+        if deadlock.get() {
+            break;
+        }
+        selector.wait_timeout(Duration::from_millis(100))?;
+    }
+    assert!(deadlock.get());
+
+    // outside of callbacks, waiting is allowed
+    let mut client_selector = ctx.create_selector()?;
+    let response = receiver.recv_timeout(Duration::from_millis(500), &mut client_selector)?;
+    assert_eq!(response.map(|r| r.sum), Some(3));
+
+    Ok(())
+}
+
+#[test]
+fn test_service_other_context() -> Result<()> {
+    let ctx = Context::new()?;
+    let node_server = ctx.create_node_with_opt(
+        "test_service_other_context_server_node",
+        None,
+        Default::default(),
+    )?;
+    let node_client = ctx.create_node_with_opt(
+        "test_service_other_context_client_node",
+        None,
+        Default::default(),
+    )?;
+
+    let server = common::create_server(node_server, SERVICE_NAME3)?;
+    let mut client = common::create_client(node_client, SERVICE_NAME3)?;
+
+    // a client of another context only waits for its own servers
+    let other_ctx = Context::new()?;
+    let other_node = other_ctx.create_node_with_opt(
+        "test_service_other_context_nested_node",
+        None,
+        Default::default(),
+    )?;
+    let mut nested_client = common::create_client(other_node, SERVICE_NAME3)?;
+    let mut nested_selector = other_ctx.create_selector()?;
+    let deadlock = Rc::new(Cell::new(None));
+    let deadlock_cloned = deadlock.clone();
+
+    let mut selector = ctx.create_selector()?;
+    selector.add_server(
+        server,
+        Box::new(move |request| {
+            let receiver = nested_client
+                .send(&AddTwoInts_Request { a: 0, b: 0 })
+                .unwrap();
+            let result = receiver.recv_timeout(Duration::from_millis(10), &mut nested_selector);
+            deadlock_cloned.set(Some(matches!(result, Err(Error::WouldDeadlock { .. }))));
+            AddTwoInts_Response {
+                sum: request.a + request.b,
+            }
+        }),
+    );
+
+    let _receiver = client.send(&AddTwoInts_Request { a: 1, b: 2 })?;
+    for _ in 0..10 {
+        if deadlock.get().is_some() {
+            break;
+        }
+        selector.wait_timeout(Duration::from_millis(100))?;
+    }
+    assert_eq!(deadlock.get(), Some(false));
+
+    Ok(())
+}
@@ -2,7 +2,8 @@
 #[cfg(test)]
 mod tests {
     use oxidros_rcl::context::Context;
-    use oxidros_rcl::msg::common_interfaces::std_msgs;
+    use oxidros_rcl::error::Error;
+    use oxidros_rcl::msg::common_interfaces::{std_msgs, std_srvs};
 
     #[test]
     fn test_publisher_topic_names() {
@@ -83,4 +84,73 @@ mod tests {
         );
         assert_eq!(sub3.topic_name().unwrap().as_str(), "simple");
     }
+
+    #[test]
+    fn test_substitution_topic_names() {
+        let ctx = Context::new().unwrap();
+        let node = ctx.create_node("test_node_subst", Some("/my_ns")).unwrap();
+
+        let pub1 = node
+            .create_publisher::<std_msgs::msg::String>("{node}/status", None)
+            .unwrap();
+        assert_eq!(
+            pub1.fully_qualified_topic_name().unwrap().as_str(),
+            "/my_ns/test_node_subst/status"
+        );
+
+        let pub2 = node
+            .create_publisher::<std_msgs::msg::String>("~/private", None)
+            .unwrap();
+        assert_eq!(
+            pub2.fully_qualified_topic_name().unwrap().as_str(),
+            "/my_ns/test_node_subst/private"
+        );
+    }
+
+    #[test]
+    fn test_invalid_topic_names() {
+        let ctx = Context::new().unwrap();
+        let node = ctx.create_node("test_node_invalid", None).unwrap();
+
+        let Err(err) = node.create_publisher::<std_msgs::msg::String>("foo//bar", None) else {
+            panic!("publisher created with an invalid topic name");
+        };
+        assert!(matches!(
+            err,
+            Error::InvalidName { ref name, position: Some(4), .. } if name == "foo//bar"
+        ));
+
+        let Err(err) = node.create_subscriber::<std_msgs::msg::String>("1topic", None) else {
+            panic!("subscriber created with an invalid topic name");
+        };
+        assert!(matches!(
+            err,
+            Error::InvalidName {
+                position: Some(0),
+                ..
+            }
+        ));
+
+        let Err(err) = node.create_server::<std_srvs::srv::Empty>("srv/{robot}", None) else {
+            panic!("server created with an unknown substitution");
+        };
+        assert!(matches!(
+            err,
+            Error::InvalidName {
+                position: Some(4),
+                ..
+            }
+        ));
+
+        let Err(err) = node.create_client::<std_srvs::srv::Empty>("srv/", None) else {
+            panic!("client created with a trailing slash");
+        };
+        assert!(matches!(
+            err,
+            Error::InvalidName {
+                position: Some(3),
+                ..
+            }
+        ));
+    }
 }
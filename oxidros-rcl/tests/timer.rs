@@ -1,7 +1,13 @@
 #![cfg(feature = "rcl")]
 
-use oxidros_rcl::{context::Context, msg::common_interfaces::std_msgs};
-use std::{error::Error, time::Duration};
+use oxidros_core::time::{ClockType, VirtualClock};
+use oxidros_rcl::{context::Context, msg::common_interfaces::std_msgs, selector::MissedTickPolicy};
+use std::{
+    cell::{Cell, RefCell},
+    error::Error,
+    rc::Rc,
+    time::{Duration, SystemTime},
+};
 
 #[test]
 fn test_timer() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
@@ -86,3 +92,160 @@ fn test_wall_timer() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
 
     Ok(())
 }
+
+#[test]
+fn test_cancel_reset_timer() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let ctx = Context::new()?;
+    let mut selector = ctx.create_selector()?;
+
+    let count = Rc::new(Cell::new(0));
+    let count_cloned = count.clone();
+    let id = selector.add_wall_timer(
+        "test_cancel_reset_timer",
+        Duration::from_millis(20),
+        Box::new(move || count_cloned.set(count_cloned.get() + 1)),
+    );
+    assert_eq!(selector.timer_period(id)?, Duration::from_millis(20));
+
+    selector.wait()?;
+    assert_eq!(count.get(), 1);
+
+    selector.cancel_timer(id)?;
+    assert!(selector.is_timer_canceled(id)?);
+    assert!(!selector.wait_timeout(Duration::from_millis(100))?);
+    assert_eq!(count.get(), 1);
+
+    selector.reset_timer(id)?;
+    assert!(!selector.is_timer_canceled(id)?);
+    selector.wait()?;
+    assert_eq!(count.get(), 2);
+
+    selector.remove_timer(id);
+    assert!(selector.cancel_timer(id).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_sim_time_timer() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_sim_time_timer_node", None)?;
+    let mut selector = ctx.create_selector()?;
+
+    let clock = node.get_clock()?;
+    assert_eq!(clock.lock().clock_type(), ClockType::Ros);
+    {
+        let mut clock = clock.lock();
+        clock.set_ros_time_override(Duration::from_secs(100))?;
+        clock.enable_ros_time_override()?;
+    }
+
+    let fired = Rc::new(Cell::new(false));
+    let fired_cloned = fired.clone();
+    selector.add_timer_with_clock(
+        "test_sim_time_timer",
+        Duration::from_secs(1),
+        clock.clone(),
+        Box::new(move || fired_cloned.set(true)),
+    )?;
+
+    // The simulated time is frozen, so the timer does not fire
+    selector.wait_timeout(Duration::from_millis(100))?;
+    assert!(!fired.get());
+
+    clock
+        .lock()
+        .set_ros_time_override(Duration::from_secs(101))?;
+    selector.wait_timeout(Duration::from_secs(1))?;
+    assert!(fired.get());
+
+    Ok(())
+}
+
+#[test]
+fn test_missed_tick_policy() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let ctx = Context::new()?;
+    let mut selector = ctx.create_selector()?;
+
+    let burst = Rc::new(RefCell::new(Vec::new()));
+    let burst_cloned = burst.clone();
+    selector.add_wall_timer_with_policy(
+        "test_burst_timer",
+        Duration::from_millis(20),
+        MissedTickPolicy::Burst,
+        Box::new(move |tick| burst_cloned.borrow_mut().push(tick)),
+    );
+
+    let skip = Rc::new(RefCell::new(Vec::new()));
+    let skip_cloned = skip.clone();
+    selector.add_wall_timer_with_policy(
+        "test_skip_timer",
+        Duration::from_millis(20),
+        MissedTickPolicy::Skip,
+        Box::new(move |tick| skip_cloned.borrow_mut().push(tick)),
+    );
+
+    // Miss a few ticks
+    std::thread::sleep(Duration::from_millis(70));
+    selector.wait()?;
+
+    let burst = burst.borrow();
+    assert!(burst.len() >= 3);
+    assert_eq!(burst.first().unwrap().missed, burst.len() as u64 - 1);
+    assert_eq!(burst.last().unwrap().missed, 0);
+    for pair in burst.windows(2) {
+        assert_eq!(
+            pair[1].expected.duration_since(pair[0].expected)?,
+            Duration::from_millis(20)
+        );
+    }
+
+    let skip = skip.borrow();
+    assert_eq!(skip.len(), 1);
+    assert!(skip[0].missed >= 2);
+    assert!(skip[0].expected <= SystemTime::now());
+
+    Ok(())
+}
+
+#[test]
+fn test_virtual_clock_timer() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let ctx = Context::new()?;
+    let mut selector = ctx.create_selector()?;
+    let clock = VirtualClock::new();
+
+    let one_shot = Rc::new(Cell::new(0));
+    let fired = one_shot.clone();
+    selector.add_timer(
+        Duration::from_secs(10),
+        Box::new(move || fired.set(fired.get() + 1)),
+    );
+
+    // the wall timer added before the clock keeps its remaining time
+    let periodic = Rc::new(Cell::new(0));
+    let fired = periodic.clone();
+    selector.add_wall_timer(
+        "test_virtual_clock_timer",
+        Duration::from_secs(1),
+        Box::new(move || fired.set(fired.get() + 1)),
+    );
+    selector.set_virtual_clock(clock.clone());
+
+    // real time does not fire the timers
+    assert!(!selector.wait_timeout(Duration::from_millis(20))?);
+    assert_eq!((one_shot.get(), periodic.get()), (0, 0));
+
+    clock.advance(Duration::from_secs(1));
+    assert!(selector.wait_timeout(Duration::ZERO)?);
+    assert_eq!((one_shot.get(), periodic.get()), (0, 1));
+
+    clock.advance(Duration::from_secs(9));
+    selector.wait_timeout(Duration::ZERO)?;
+    assert_eq!((one_shot.get(), periodic.get()), (1, 2));
+
+    clock.advance(Duration::from_secs(10));
+    selector.wait_timeout(Duration::ZERO)?;
+    assert_eq!((one_shot.get(), periodic.get()), (1, 3));
+
+    Ok(())
+}
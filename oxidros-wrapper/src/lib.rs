@@ -82,10 +82,14 @@ pub use oxidros_core::{
     // Types
     Message,
     MessageStream,
+    NetworkFlowEndpoint,
+    NodeStats,
     Profile,
+    QosCompatibility,
     ReliabilityPolicy,
     Result,
     // Traits
+    RosActionClient,
     RosClient,
     RosContext,
     RosNode,
@@ -93,13 +97,18 @@ pub use oxidros_core::{
     RosSelector,
     RosServer,
     RosSubscriber,
+    ServerOptions,
     ServiceMsg,
     ServiceRequest as ServiceRequestTrait,
+    SheddingPolicy,
+    TypeCheckPolicy,
+    TypeInfo,
     TypeSupport,
 };
+use oxidros_core::{ErrorContext, Operation, ResultExt, ServiceTypeDescription, TypeDescription};
 
-// Re-export callback result from core
-pub use oxidros_core::selector::CallbackResult;
+// Re-export callback result and file descriptor events from core
+pub use oxidros_core::selector::{CallbackResult, FdInterest, FdReadiness};
 
 // Re-export message types
 pub use oxidros_msg as msg;
@@ -139,6 +148,12 @@ impl Context {
         Ok(Arc::new(Self(inner)))
     }
 
+    /// Create a separate context on the domain `domain_id`.
+    pub fn with_domain_id(domain_id: u32) -> Result<Arc<Self>> {
+        let inner = oxidros_rcl::context::Context::with_domain_id(domain_id)?;
+        Ok(Arc::new(Self(inner)))
+    }
+
     /// Get the inner RCL context.
     pub fn inner(&self) -> &Arc<oxidros_rcl::context::Context> {
         &self.0
@@ -187,9 +202,9 @@ impl Deref for Node {
 /// A ROS2 publisher wrapper implementing [`RosPublisher`].
 pub struct Publisher<T>(pub oxidros_rcl::topic::publisher::Publisher<T>);
 
-impl<T> std::fmt::Debug for Publisher<T> {
+impl<T: TypeSupport + TypeDescription> std::fmt::Debug for Publisher<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Publisher").finish_non_exhaustive()
+        self.0.fmt(f)
     }
 }
 
@@ -208,12 +223,18 @@ impl<T> Deref for Publisher<T> {
     }
 }
 
+impl<T> DerefMut for Publisher<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
 /// A ROS2 subscriber wrapper implementing [`RosSubscriber`].
 pub struct Subscriber<T>(pub oxidros_rcl::topic::subscriber::Subscriber<T>);
 
-impl<T> std::fmt::Debug for Subscriber<T> {
+impl<T: TypeSupport + TypeDescription> std::fmt::Debug for Subscriber<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Subscriber").finish_non_exhaustive()
+        self.0.fmt(f)
     }
 }
 
@@ -222,6 +243,14 @@ impl<T> Subscriber<T> {
     pub fn inner(&self) -> &oxidros_rcl::topic::subscriber::Subscriber<T> {
         &self.0
     }
+
+    /// Set how the messages of publishers with another type hash are handled.
+    ///
+    /// The policy has no effect on RCL, see
+    /// [`with_type_check`](oxidros_rcl::topic::subscriber::Subscriber::with_type_check).
+    pub fn with_type_check(self, policy: TypeCheckPolicy) -> Self {
+        Subscriber(self.0.with_type_check(policy))
+    }
 }
 
 impl<T> Deref for Subscriber<T> {
@@ -241,9 +270,9 @@ impl<T> DerefMut for Subscriber<T> {
 /// A ROS2 service client wrapper implementing [`RosClient`].
 pub struct Client<T>(pub oxidros_rcl::service::client::Client<T>);
 
-impl<T> std::fmt::Debug for Client<T> {
+impl<T: ServiceMsg + ServiceTypeDescription> std::fmt::Debug for Client<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Client").finish_non_exhaustive()
+        self.0.fmt(f)
     }
 }
 
@@ -271,9 +300,9 @@ impl<T> DerefMut for Client<T> {
 /// A ROS2 service server wrapper implementing [`RosServer`].
 pub struct Server<T>(pub oxidros_rcl::service::server::Server<T>);
 
-impl<T> std::fmt::Debug for Server<T> {
+impl<T: ServiceMsg + ServiceTypeDescription> std::fmt::Debug for Server<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Server").finish_non_exhaustive()
+        self.0.fmt(f)
     }
 }
 
@@ -375,7 +404,7 @@ impl<T: TypeSupport + Send + 'static> std::fmt::Debug for SubscriberStream<T> {
 async fn make_subscriber_future<T: TypeSupport + Send + 'static>(
     mut subscriber: Subscriber<T>,
 ) -> (Result<Message<T>>, Subscriber<T>) {
-    let result = subscriber.0.recv().await;
+    let result = RosSubscriber::recv(&mut subscriber).await;
     (result, subscriber)
 }
 
@@ -402,6 +431,60 @@ impl<T: TypeSupport + Send + 'static> Stream for SubscriberStream<T> {
 // Trait Implementations
 // ============================================================================
 
+/// Context of `operation` on the topic or service `entity` of `node`.
+fn entity_context(
+    node: &oxidros_rcl::node::Node,
+    operation: Operation,
+    entity: Result<Cow<'_, String>>,
+) -> ErrorContext {
+    let entity = entity.map(Cow::into_owned).unwrap_or_default();
+    let context = ErrorContext::new(operation, entity);
+    match node.fully_qualified_name() {
+        Ok(node) => context.with_node(node),
+        Err(_) => context,
+    }
+}
+
+impl<T: TypeSupport> Publisher<T> {
+    fn publish_context(&self) -> ErrorContext {
+        entity_context(
+            self.0.node(),
+            Operation::Publish,
+            self.0.fully_qualified_topic_name(),
+        )
+    }
+}
+
+impl<T: TypeSupport> Subscriber<T> {
+    fn take_context(&self) -> ErrorContext {
+        entity_context(
+            self.0.node(),
+            Operation::Take,
+            self.0.fully_qualified_topic_name(),
+        )
+    }
+}
+
+impl<T: ServiceMsg> Client<T> {
+    fn call_context(&self) -> ErrorContext {
+        entity_context(
+            self.0.node(),
+            Operation::Call,
+            self.0.fully_qualified_service_name(),
+        )
+    }
+}
+
+impl<T: ServiceMsg> Server<T> {
+    fn request_context(&self) -> ErrorContext {
+        entity_context(
+            self.0.node(),
+            Operation::TakeRequest,
+            self.0.fully_qualified_service_name(),
+        )
+    }
+}
+
 impl RosContext for Context {
     type Node = Node;
     type Selector = Selector;
@@ -415,17 +498,29 @@ impl RosContext for Context {
         Ok(Arc::new(Node(inner)))
     }
 
+    fn shutdown_token(&self) -> oxidros_core::shutdown::ShutdownToken {
+        oxidros_rcl::shutdown_token()
+    }
+
     fn create_selector(self: &Arc<Self>) -> Result<Self::Selector> {
         let inner = self.0.create_selector()?;
         Ok(Selector(inner))
     }
 
     fn ros_domain_id(&self) -> u32 {
-        // RCL uses the ROS_DOMAIN_ID environment variable, default is 0
-        std::env::var("ROS_DOMAIN_ID")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(0)
+        self.0.domain_id()
+    }
+
+    fn rmw_implementation(&self) -> &str {
+        self.0.rmw_implementation()
+    }
+
+    fn rmw_qos_compatibility_check(
+        &self,
+        publisher: &Profile,
+        subscription: &Profile,
+    ) -> Result<QosCompatibility> {
+        self.0.rmw_qos_compatibility_check(publisher, subscription)
     }
 }
 
@@ -447,6 +542,10 @@ impl RosNode for Node {
         self.0.fully_qualified_name()
     }
 
+    fn stats(&self) -> NodeStats {
+        self.0.stats()
+    }
+
     fn create_publisher<T: TypeSupport>(
         self: &Arc<Self>,
         topic_name: &str,
@@ -482,6 +581,23 @@ impl RosNode for Node {
         let inner = self.0.create_server(service_name, qos)?;
         Ok(Server(inner))
     }
+
+    fn create_server_with_options<T: ServiceMsg>(
+        self: &Arc<Self>,
+        service_name: &str,
+        qos: Option<Profile>,
+        options: ServerOptions,
+    ) -> Result<Self::Server<T>> {
+        if options != ServerOptions::default() {
+            return Err(Error::NotImplemented {
+                feature: "ServerOptions".into(),
+                reason: "RCL servers queue requests in the middleware, bound them with the \
+                         depth of the QoS profile instead"
+                    .into(),
+            });
+        }
+        self.create_server(service_name, qos)
+    }
 }
 
 impl<T: TypeSupport> RosPublisher<T> for Publisher<T> {
@@ -490,12 +606,30 @@ impl<T: TypeSupport> RosPublisher<T> for Publisher<T> {
     }
 
     fn send(&self, msg: &T) -> Result<()> {
-        self.0.send(msg)
+        self.0.send(msg).with_context(|| self.publish_context())
     }
 
     fn send_raw(&self, data: &[u8]) -> Result<()> {
         // SAFETY: The raw bytes are passed directly to RCL
-        unsafe { self.0.send_raw(data) }
+        let result = unsafe { self.0.send_raw(data) };
+        result.with_context(|| self.publish_context())
+    }
+
+    fn network_flow_endpoints(&self) -> Result<Vec<NetworkFlowEndpoint>> {
+        self.0.get_network_flow_endpoints()
+    }
+
+    async fn flush(&self) -> Result<()> {
+        // Poll with a zero timeout not to block the executor
+        loop {
+            match self.0.wait_for_all_acked(Duration::ZERO) {
+                Ok(()) | Err(Error::Rcl(oxidros_core::RclError::Unsupported)) => return Ok(()),
+                Err(Error::Rcl(oxidros_core::RclError::Timeout)) => {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 }
 
@@ -505,19 +639,31 @@ impl<T: TypeSupport + Send + 'static> RosSubscriber<T> for Subscriber<T> {
     }
 
     async fn recv(&mut self) -> Result<Message<T>> {
-        self.0.recv().await
+        let result = self.0.recv().await;
+        result.with_context(|| self.take_context())
     }
 
     fn try_recv(&mut self) -> Result<Option<Message<T>>> {
-        self.0.try_recv()
+        self.0.try_recv().with_context(|| self.take_context())
     }
 
     async fn recv_raw(&mut self) -> Result<(Vec<u8>, oxidros_core::message::MessageInfo)> {
-        self.0.recv_raw().await
+        let result = self.0.recv_raw().await;
+        result.with_context(|| self.take_context())
     }
 
     fn try_recv_raw(&mut self) -> Result<Option<(Vec<u8>, oxidros_core::message::MessageInfo)>> {
-        self.0.try_recv_raw()
+        self.0.try_recv_raw().with_context(|| self.take_context())
+    }
+
+    fn network_flow_endpoints(&self) -> Result<Vec<NetworkFlowEndpoint>> {
+        self.0.get_network_flow_endpoints()
+    }
+
+    fn recv_many(&mut self, limit: usize) -> Result<Vec<Message<T>>> {
+        self.0
+            .try_recv_many(limit)
+            .with_context(|| self.take_context())
     }
 
     fn into_stream(self) -> MessageStream<T>
@@ -528,6 +674,33 @@ impl<T: TypeSupport + Send + 'static> RosSubscriber<T> for Subscriber<T> {
     }
 }
 
+impl<T: ActionMsg> RosActionClient<T> for action::client::Client<T> {
+    type CancelRecv<'a>
+        = action::client::ClientCancelRecv<'a, T>
+    where
+        Self: 'a;
+
+    fn cancel_all_goals(&mut self) -> Result<Self::CancelRecv<'_>> {
+        action::client::Client::cancel_all_goals(self)
+    }
+
+    fn cancel_goals_before(&mut self, stamp: Duration) -> Result<Self::CancelRecv<'_>> {
+        action::client::Client::cancel_goals_before(self, stamp_to_time(stamp))
+    }
+}
+
+/// Convert a time since the epoch to a `Time` message.
+///
+/// Stamps beyond the range of `Time` are clamped to its maximum: they are
+/// after every goal, so all the goals are canceled either way.
+fn stamp_to_time(stamp: Duration) -> oxidros_msg::interfaces::builtin_interfaces::msg::Time {
+    let (sec, nanosec) = match i32::try_from(stamp.as_secs()) {
+        Ok(sec) => (sec, stamp.subsec_nanos()),
+        Err(_) => (i32::MAX, 999_999_999),
+    };
+    oxidros_msg::interfaces::builtin_interfaces::msg::Time { sec, nanosec }
+}
+
 impl<T: ServiceMsg> RosClient<T> for Client<T> {
     fn service_name(&self) -> Result<Cow<'_, String>> {
         self.0.service_name()
@@ -538,7 +711,8 @@ impl<T: ServiceMsg> RosClient<T> for Client<T> {
     }
 
     async fn call(&mut self, request: &T::Request) -> Result<Message<T::Response>> {
-        self.0.call(request).await
+        let result = self.0.call(request).await;
+        result.with_context(|| self.call_context())
     }
 
     async fn call_with_retry(
@@ -557,7 +731,7 @@ impl<T: ServiceMsg> RosClient<T> for Client<T> {
         loop {
             match time::timeout(timeout, self.0.call(request)).await {
                 Ok(Ok(response)) => return Ok(response),
-                Ok(Err(e)) => return Err(e),
+                Ok(Err(e)) => return Err(e.with_context(self.call_context())),
                 Err(_) => {
                     tracing::warn!("Service {} call timeout, retrying...", T::type_name());
                 }
@@ -574,6 +748,10 @@ where
         self.inner.request()
     }
 
+    fn info(&self) -> oxidros_core::RequestInfo {
+        self.inner.info()
+    }
+
     fn respond(self, response: &T::Response) -> Result<()> {
         self.inner.send(response)
     }
@@ -590,12 +768,13 @@ where
     }
 
     async fn recv(&mut self) -> Result<Self::Request> {
-        let inner = self.0.recv().await?;
+        let result = self.0.recv().await;
+        let inner = result.with_context(|| self.request_context())?;
         Ok(ServiceRequest::new(inner))
     }
 
     fn try_recv(&mut self) -> Result<Option<Self::Request>> {
-        match self.0.try_recv()? {
+        match self.0.try_recv().with_context(|| self.request_context())? {
             Some(inner) => Ok(Some(ServiceRequest::new(inner))),
             None => Ok(None),
         }
@@ -616,6 +795,7 @@ where
                     }
                 }
                 Err(e) => {
+                    let e = e.with_context(self.request_context());
                     tracing::error!("Error receiving request: {:?}", e);
                     return Err(e);
                 }
@@ -639,6 +819,7 @@ where
                     }
                 }
                 Err(e) => {
+                    let e = e.with_context(self.request_context());
                     tracing::error!("Error receiving request: {:?}", e);
                     return Err(e);
                 }
@@ -691,6 +872,15 @@ impl RosSelector for Selector {
         self.0.remove_timer(id)
     }
 
+    fn set_virtual_clock(&mut self, clock: oxidros_core::time::VirtualClock) -> Result<()> {
+        self.0.set_virtual_clock(clock);
+        Ok(())
+    }
+
+    fn set_panic_policy(&mut self, policy: oxidros_core::selector::PanicPolicy) {
+        self.0.set_panic_policy(policy)
+    }
+
     fn add_action_server<T, GR, A, CR>(
         &mut self,
         server: Self::ActionServer<T>,
@@ -733,6 +923,21 @@ impl RosSelector for Selector {
     fn wait_timeout(&mut self, timeout: Duration) -> Result<bool> {
         self.0.wait_timeout(timeout)
     }
+
+    #[cfg(unix)]
+    fn add_fd(
+        &mut self,
+        fd: std::os::fd::RawFd,
+        interest: FdInterest,
+        handler: Box<dyn FnMut(FdReadiness)>,
+    ) -> Result<u64> {
+        self.0.add_fd(fd, interest, handler)
+    }
+
+    #[cfg(unix)]
+    fn remove_fd(&mut self, id: u64) {
+        self.0.remove_fd(id)
+    }
 }
 
 // ============================================================================
@@ -757,6 +962,7 @@ pub mod prelude {
         Node,
         Profile,
         Publisher,
+        QosCompatibility,
         ReliabilityPolicy,
         Result,
         // Core traits
@@ -777,3 +983,24 @@ pub mod prelude {
     pub use futures_util::StreamExt;
     pub use std::sync::Arc;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stamp_to_time_clamps() {
+        let max = Duration::new(i32::MAX as u64, 999_999_999);
+        let time = stamp_to_time(max);
+        assert_eq!((time.sec, time.nanosec), (i32::MAX, 999_999_999));
+
+        let time = stamp_to_time(Duration::new(i32::MAX as u64 + 1, 5));
+        assert_eq!((time.sec, time.nanosec), (i32::MAX, 999_999_999));
+
+        let time = stamp_to_time(Duration::MAX);
+        assert_eq!((time.sec, time.nanosec), (i32::MAX, 999_999_999));
+
+        let time = stamp_to_time(Duration::new(12, 34));
+        assert_eq!((time.sec, time.nanosec), (12, 34));
+    }
+}
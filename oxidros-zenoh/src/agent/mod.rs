@@ -0,0 +1,422 @@
+//! Micro-agent bridging microcontrollers into the ROS2 graph.
+//!
+//! Devices without a Zenoh stack, e.g. `no_std` firmwares using the code of
+//! the `no_std` mode of the `ros2msg` generator, exchange CDR messages with
+//! the agent over a serial link or UDP, with the small frame protocol of
+//! [`protocol`]. The agent creates a publisher or a subscriber on its node for
+//! every stream opened by a device, so the device topics are seen by the rest
+//! of the graph as the ones of the agent node.
+//!
+//! A device opens a stream with a `CREATE_PUBLISHER` or `CREATE_SUBSCRIBER`
+//! frame giving the topic, the type name and the QoS of the stream, and the
+//! agent replies with a `STATUS` frame. `DATA` frames then carry the messages
+//! of the stream in both directions, without being decoded by the agent.
+//!
+//! Type descriptions are resolved from, in order:
+//! 1. types registered with [`MicroAgent::register`],
+//! 2. types used by entities of the agent node,
+//! 3. a `z_get_type_description` query to the nodes of the domain.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros_zenoh::{Context, agent::MicroAgent};
+//!
+//! let ctx = Context::new()?;
+//! let node = ctx.create_node("micro_agent", None)?;
+//! let agent = MicroAgent::new(node).register::<sensor_msgs::msg::Imu>();
+//!
+//! // Serial link opened with e.g. `tokio-serial`
+//! // agent.serve_stream(serial).await?;
+//! agent.serve_udp(("0.0.0.0", oxidros_zenoh::agent::DEFAULT_PORT)).await?;
+//! ```
+
+pub mod protocol;
+
+use crate::{
+    error::{Error, Result},
+    node::Node,
+    task,
+    topic::GenericPublisher,
+    type_description::TypeCache,
+};
+use oxidros_core::{TypeDescription, targets, types::TypeDescriptionMsg};
+use protocol::{Frame, StatusCode, StreamDecoder, encode_stream_frame};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::{ToSocketAddrs, UdpSocket},
+    sync::mpsc,
+    task::JoinHandle,
+};
+
+/// Default UDP port of the agent.
+pub const DEFAULT_PORT: u16 = 8888;
+
+/// Time after which a UDP device that sent nothing is forgotten.
+pub const DEFAULT_SESSION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest UDP datagram received.
+const MAX_DATAGRAM_LEN: usize = 64 * 1024;
+
+/// Agent bridging devices into the graph.
+///
+/// Each device gets its own session, whose publishers and subscribers are
+/// created on the agent node and dropped when the session ends.
+pub struct MicroAgent {
+    shared: Arc<Shared>,
+    session_timeout: Duration,
+}
+
+/// State shared by all sessions.
+struct Shared {
+    node: Arc<Node>,
+    /// Type descriptions by ROS2 type name.
+    types: TypeCache,
+}
+
+impl MicroAgent {
+    /// Create an agent bridging devices into the graph of `node`.
+    pub fn new(node: Arc<Node>) -> Self {
+        MicroAgent {
+            shared: Arc::new(Shared {
+                node,
+                types: TypeCache::default(),
+            }),
+            session_timeout: DEFAULT_SESSION_TIMEOUT,
+        }
+    }
+
+    /// Make a type available to devices without querying the graph.
+    pub fn register<T: TypeDescription>(self) -> Self {
+        self.shared.types.insert(T::type_description());
+        self
+    }
+
+    /// Set the time after which a UDP device that sent nothing is forgotten,
+    /// [`DEFAULT_SESSION_TIMEOUT`] by default.
+    ///
+    /// Devices are expected to send a `PING` frame when they have nothing else
+    /// to send.
+    pub fn session_timeout(mut self, timeout: Duration) -> Self {
+        self.session_timeout = timeout;
+        self
+    }
+
+    /// Serve the devices sending datagrams to `addr`, one session per source
+    /// address, until a socket error occurs.
+    pub async fn serve_udp(self, addr: impl ToSocketAddrs) -> Result<()> {
+        let socket = UdpSocket::bind(addr)
+            .await
+            .map_err(|e| Error::Other(format!("Failed to bind micro-agent socket: {e}")))?;
+        let socket = Arc::new(socket);
+        tracing::info!(
+            target: targets::ZENOH,
+            addr = ?socket.local_addr().ok(),
+            "Micro-agent listening"
+        );
+
+        let mut sessions: HashMap<SocketAddr, (Session, Instant)> = HashMap::new();
+        let mut buf = vec![0; MAX_DATAGRAM_LEN];
+        let mut sweep = tokio::time::interval(self.session_timeout);
+        loop {
+            let (len, peer) = tokio::select! {
+                received = socket.recv_from(&mut buf) => received
+                    .map_err(|e| Error::Other(format!("Failed to receive datagram: {e}")))?,
+                _ = sweep.tick() => {
+                    sessions.retain(|peer, (_, last_seen)| {
+                        let alive = last_seen.elapsed() < self.session_timeout;
+                        if !alive {
+                            tracing::debug!(target: targets::ZENOH, %peer, "Micro-agent session timed out");
+                        }
+                        alive
+                    });
+                    continue;
+                }
+            };
+
+            let (session, last_seen) = sessions.entry(peer).or_insert_with(|| {
+                tracing::debug!(target: targets::ZENOH, %peer, "Micro-agent session opened");
+                let (frames, mut rx) = mpsc::unbounded_channel::<Frame>();
+                let socket = socket.clone();
+                let writer = task::spawn_named("agent-udp-writer", async move {
+                    while let Some(frame) = rx.recv().await {
+                        if socket.send_to(&frame.encode(), peer).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+                (
+                    Session::new(self.shared.clone(), frames, writer),
+                    Instant::now(),
+                )
+            });
+            *last_seen = Instant::now();
+
+            match Frame::decode(&buf[..len]) {
+                Ok(frame) => session.handle(frame).await,
+                Err(e) => {
+                    tracing::debug!(target: targets::ZENOH, %peer, error = %e, "Invalid micro-agent frame");
+                    session.reply(0, StatusCode::InvalidFrame);
+                }
+            }
+        }
+    }
+
+    /// Serve a device connected through a byte stream, e.g. a serial port,
+    /// until the stream ends.
+    pub async fn serve_stream<S>(self, stream: S) -> Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let (mut reader, mut writer) = tokio::io::split(stream);
+        let (frames, mut rx) = mpsc::unbounded_channel::<Frame>();
+        let writer = task::spawn_named("agent-stream-writer", async move {
+            while let Some(frame) = rx.recv().await {
+                if writer
+                    .write_all(&encode_stream_frame(&frame))
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        let mut session = Session::new(self.shared.clone(), frames, writer);
+        let mut decoder = StreamDecoder::new();
+        let mut buf = [0; 1024];
+        loop {
+            let len = reader
+                .read(&mut buf)
+                .await
+                .map_err(|e| Error::Other(format!("Failed to read micro-agent stream: {e}")))?;
+            if len == 0 {
+                return Ok(());
+            }
+            for &byte in &buf[..len] {
+                match decoder.push(byte) {
+                    Some(Ok(frame)) => session.handle(frame).await,
+                    Some(Err(e)) => {
+                        // Noise on the line, e.g. boot messages of the device
+                        tracing::debug!(target: targets::ZENOH, error = %e, "Invalid micro-agent frame");
+                    }
+                    None => (),
+                }
+            }
+        }
+    }
+}
+
+/// Streams of a device.
+struct Session {
+    shared: Arc<Shared>,
+    frames: mpsc::UnboundedSender<Frame>,
+    writer: JoinHandle<()>,
+    publishers: HashMap<u16, GenericPublisher>,
+    subscriptions: HashMap<u16, JoinHandle<()>>,
+}
+
+impl Session {
+    fn new(
+        shared: Arc<Shared>,
+        frames: mpsc::UnboundedSender<Frame>,
+        writer: JoinHandle<()>,
+    ) -> Self {
+        Session {
+            shared,
+            frames,
+            writer,
+            publishers: HashMap::new(),
+            subscriptions: HashMap::new(),
+        }
+    }
+
+    fn reply(&self, stream: u16, code: StatusCode) {
+        let _ = self.frames.send(Frame::Status { stream, code });
+    }
+
+    async fn handle(&mut self, frame: Frame) {
+        match frame {
+            Frame::CreatePublisher {
+                stream,
+                qos,
+                topic,
+                type_name,
+            } => {
+                self.delete(stream);
+                let code = match self.create_publisher(&topic, &type_name, qos).await {
+                    Ok(publisher) => {
+                        self.publishers.insert(stream, publisher);
+                        StatusCode::Ok
+                    }
+                    Err(code) => code,
+                };
+                self.reply(stream, code);
+            }
+            Frame::CreateSubscriber {
+                stream,
+                qos,
+                topic,
+                type_name,
+            } => {
+                self.delete(stream);
+                let code = match self
+                    .create_subscriber(stream, &topic, &type_name, qos)
+                    .await
+                {
+                    Ok(task) => {
+                        self.subscriptions.insert(stream, task);
+                        StatusCode::Ok
+                    }
+                    Err(code) => code,
+                };
+                self.reply(stream, code);
+            }
+            Frame::Data { stream, payload } => {
+                let Some(publisher) = self.publishers.get(&stream) else {
+                    self.reply(stream, StatusCode::UnknownStream);
+                    return;
+                };
+                // Best effort: data frames are not acknowledged
+                if let Err(e) = publisher.publish_raw(payload) {
+                    tracing::debug!(target: targets::ZENOH, stream, error = %e, "Failed to publish device message");
+                    self.reply(stream, StatusCode::Failed);
+                }
+            }
+            Frame::Delete { stream } => {
+                let code = if self.delete(stream) {
+                    StatusCode::Ok
+                } else {
+                    StatusCode::UnknownStream
+                };
+                self.reply(stream, code);
+            }
+            Frame::Ping => {
+                let _ = self.frames.send(Frame::Pong);
+            }
+            // Sent by the agent only
+            Frame::Status { .. } | Frame::Pong => (),
+        }
+    }
+
+    /// Delete a stream, returning whether it existed.
+    fn delete(&mut self, stream: u16) -> bool {
+        let publisher = self.publishers.remove(&stream);
+        let subscription = self.subscriptions.remove(&stream);
+        if let Some(task) = &subscription {
+            task.abort();
+        }
+        publisher.is_some() || subscription.is_some()
+    }
+
+    async fn create_publisher(
+        &self,
+        topic: &str,
+        type_name: &str,
+        qos: protocol::StreamQos,
+    ) -> std::result::Result<GenericPublisher, StatusCode> {
+        let desc = self.resolve(type_name).await?;
+        self.shared
+            .node
+            .create_generic_publisher(topic, desc, Some(qos.to_profile()))
+            .map_err(|e| {
+                tracing::debug!(target: targets::ZENOH, topic, error = %e, "Failed to create device publisher");
+                StatusCode::Failed
+            })
+    }
+
+    async fn create_subscriber(
+        &self,
+        stream: u16,
+        topic: &str,
+        type_name: &str,
+        qos: protocol::StreamQos,
+    ) -> std::result::Result<JoinHandle<()>, StatusCode> {
+        let desc = self.resolve(type_name).await?;
+        let mut subscriber = self
+            .shared
+            .node
+            .create_generic_subscriber(topic, desc, Some(qos.to_profile()))
+            .map_err(|e| {
+                tracing::debug!(target: targets::ZENOH, topic, error = %e, "Failed to create device subscriber");
+                StatusCode::Failed
+            })?;
+        let frames = self.frames.clone();
+        Ok(task::spawn_named(topic, async move {
+            loop {
+                let payload = match subscriber.recv_raw().await {
+                    Ok((payload, _)) => payload,
+                    Err(Error::ChannelClosed) => break,
+                    Err(_) => continue,
+                };
+                if frames.send(Frame::Data { stream, payload }).is_err() {
+                    break;
+                }
+            }
+        }))
+    }
+
+    /// Resolve the description of the type of a stream.
+    async fn resolve(
+        &self,
+        type_name: &str,
+    ) -> std::result::Result<TypeDescriptionMsg, StatusCode> {
+        let Some(type_name) = message_type(type_name) else {
+            return Err(StatusCode::UnknownType);
+        };
+        self.shared
+            .types
+            .resolve(&self.shared.node, &type_name)
+            .await
+            .map_err(|e| {
+                tracing::debug!(target: targets::ZENOH, type_name, error = %e, "Unknown device type");
+                StatusCode::UnknownType
+            })
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        for (_, task) in self.subscriptions.drain() {
+            task.abort();
+        }
+        self.writer.abort();
+    }
+}
+
+/// Normalize a message type name sent by a device to its fully qualified
+/// ROS2 form: `"std_msgs/String"` becomes `"std_msgs/msg/String"`.
+fn message_type(type_name: &str) -> Option<String> {
+    let parts: Vec<&str> = type_name.split('/').collect();
+    match parts.as_slice() {
+        [pkg, name] if !pkg.is_empty() && !name.is_empty() => Some(format!("{pkg}/msg/{name}")),
+        [pkg, "msg", name] if !pkg.is_empty() && !name.is_empty() => Some(type_name.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_type() {
+        assert_eq!(
+            message_type("std_msgs/String").as_deref(),
+            Some("std_msgs/msg/String")
+        );
+        assert_eq!(
+            message_type("std_msgs/msg/String").as_deref(),
+            Some("std_msgs/msg/String")
+        );
+        assert_eq!(message_type("std_srvs/srv/Empty"), None);
+        assert_eq!(message_type("String"), None);
+        assert_eq!(message_type("/String"), None);
+    }
+}
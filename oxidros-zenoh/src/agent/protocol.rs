@@ -0,0 +1,549 @@
+//! Micro-agent frame protocol.
+//!
+//! A frame starts with its kind and the id of the stream it applies to,
+//! followed by a body depending on the kind. Integers are little endian.
+//!
+//! | Kind                | Body                                         |
+//! |---------------------|----------------------------------------------|
+//! | `CREATE_PUBLISHER`  | QoS (4 bytes), topic name, type name         |
+//! | `CREATE_SUBSCRIBER` | QoS (4 bytes), topic name, type name         |
+//! | `DATA`              | CDR payload, encapsulation header included   |
+//! | `DELETE`            | empty                                        |
+//! | `STATUS`            | status code (1 byte)                         |
+//! | `PING`, `PONG`      | empty                                        |
+//!
+//! Names are a `u16` length followed by UTF-8 bytes. The QoS is a flag byte
+//! ([`StreamQos::RELIABLE`], [`StreamQos::TRANSIENT_LOCAL`]), a reserved byte
+//! and the `u16` history depth.
+//!
+//! A UDP datagram holds one frame. On byte streams such as serial links,
+//! frames are followed by a CRC-16/CCITT-FALSE, COBS-encoded and terminated
+//! by a zero byte, see [`encode_stream_frame`] and [`StreamDecoder`].
+
+use oxidros_core::qos::{DurabilityPolicy, HistoryPolicy, Profile, ReliabilityPolicy};
+
+/// Kind of a frame creating a publisher of the device.
+pub const CREATE_PUBLISHER: u8 = 0x01;
+/// Kind of a frame creating a subscriber of the device.
+pub const CREATE_SUBSCRIBER: u8 = 0x02;
+/// Kind of a frame carrying a message.
+pub const DATA: u8 = 0x03;
+/// Kind of a frame deleting a stream.
+pub const DELETE: u8 = 0x04;
+/// Kind of a frame reporting the result of a request.
+pub const STATUS: u8 = 0x05;
+/// Kind of a keep-alive request.
+pub const PING: u8 = 0x06;
+/// Kind of a keep-alive reply.
+pub const PONG: u8 = 0x07;
+
+/// Largest frame accepted on byte streams, in bytes.
+pub const MAX_FRAME_LEN: usize = 64 * 1024;
+
+/// Error decoding a frame.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FrameError {
+    /// The frame is shorter than its content.
+    #[error("truncated frame")]
+    Truncated,
+    /// The kind of the frame is unknown.
+    #[error("unknown frame kind 0x{0:02x}")]
+    UnknownKind(u8),
+    /// A name is not UTF-8.
+    #[error("invalid name")]
+    InvalidName,
+    /// The status code is unknown.
+    #[error("unknown status code {0}")]
+    UnknownStatus(u8),
+    /// The COBS encoding of a stream frame is invalid.
+    #[error("invalid COBS encoding")]
+    InvalidEncoding,
+    /// The CRC of a stream frame does not match.
+    #[error("bad checksum")]
+    BadChecksum,
+    /// A stream frame exceeds [`MAX_FRAME_LEN`].
+    #[error("frame too long")]
+    TooLong,
+}
+
+/// QoS of a stream, mapped to a [`Profile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamQos {
+    /// [`StreamQos::RELIABLE`] and [`StreamQos::TRANSIENT_LOCAL`] flags.
+    pub flags: u8,
+    /// History depth, `0` for keep all.
+    pub depth: u16,
+}
+
+impl StreamQos {
+    /// Reliable instead of best effort.
+    pub const RELIABLE: u8 = 0x01;
+    /// Transient local instead of volatile.
+    pub const TRANSIENT_LOCAL: u8 = 0x02;
+
+    /// QoS profile of the publisher or subscriber of the stream.
+    pub fn to_profile(self) -> Profile {
+        let default = Profile::default();
+        let reliability = if self.flags & Self::RELIABLE != 0 {
+            ReliabilityPolicy::Reliable
+        } else {
+            ReliabilityPolicy::BestEffort
+        };
+        let durability = if self.flags & Self::TRANSIENT_LOCAL != 0 {
+            DurabilityPolicy::TransientLocal
+        } else {
+            DurabilityPolicy::Volatile
+        };
+        let (history, depth) = if self.depth == 0 {
+            (HistoryPolicy::KeepAll, default.depth)
+        } else {
+            (HistoryPolicy::KeepLast, self.depth.into())
+        };
+        Profile {
+            history,
+            depth,
+            reliability,
+            durability,
+            ..default
+        }
+    }
+}
+
+impl Default for StreamQos {
+    /// Reliable, volatile, depth 10, as [`Profile::default`].
+    fn default() -> Self {
+        StreamQos {
+            flags: Self::RELIABLE,
+            depth: 10,
+        }
+    }
+}
+
+/// Result of a request, reported in a [`Frame::Status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum StatusCode {
+    /// The request succeeded.
+    Ok = 0,
+    /// The type of the stream is unknown to the agent.
+    UnknownType = 1,
+    /// The stream does not exist.
+    UnknownStream = 2,
+    /// The frame cannot be decoded.
+    InvalidFrame = 3,
+    /// The publisher or subscriber cannot be created, or the message not
+    /// published.
+    Failed = 4,
+}
+
+impl TryFrom<u8> for StatusCode {
+    type Error = FrameError;
+
+    fn try_from(code: u8) -> Result<Self, FrameError> {
+        match code {
+            0 => Ok(StatusCode::Ok),
+            1 => Ok(StatusCode::UnknownType),
+            2 => Ok(StatusCode::UnknownStream),
+            3 => Ok(StatusCode::InvalidFrame),
+            4 => Ok(StatusCode::Failed),
+            code => Err(FrameError::UnknownStatus(code)),
+        }
+    }
+}
+
+/// Frame exchanged between a device and the agent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+    /// Publish the [`Frame::Data`] of the device on a topic.
+    CreatePublisher {
+        /// Stream id, chosen by the device.
+        stream: u16,
+        /// QoS of the publisher.
+        qos: StreamQos,
+        /// Topic name.
+        topic: String,
+        /// Message type (`pkg/msg/Name` or `pkg/Name`).
+        type_name: String,
+    },
+    /// Forward the messages of a topic to the device.
+    CreateSubscriber {
+        /// Stream id, chosen by the device.
+        stream: u16,
+        /// QoS of the subscriber.
+        qos: StreamQos,
+        /// Topic name.
+        topic: String,
+        /// Message type (`pkg/msg/Name` or `pkg/Name`).
+        type_name: String,
+    },
+    /// Message of a stream.
+    Data {
+        /// Stream id.
+        stream: u16,
+        /// CDR payload, encapsulation header included.
+        payload: Vec<u8>,
+    },
+    /// Delete a stream.
+    Delete {
+        /// Stream id.
+        stream: u16,
+    },
+    /// Result of a request on a stream.
+    Status {
+        /// Stream id.
+        stream: u16,
+        /// Result.
+        code: StatusCode,
+    },
+    /// Keep-alive request.
+    Ping,
+    /// Keep-alive reply.
+    Pong,
+}
+
+impl Frame {
+    /// Encode the frame.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            Frame::CreatePublisher {
+                stream,
+                qos,
+                topic,
+                type_name,
+            }
+            | Frame::CreateSubscriber {
+                stream,
+                qos,
+                topic,
+                type_name,
+            } => {
+                let kind = if matches!(self, Frame::CreatePublisher { .. }) {
+                    CREATE_PUBLISHER
+                } else {
+                    CREATE_SUBSCRIBER
+                };
+                put_header(&mut buf, kind, *stream);
+                buf.extend_from_slice(&[qos.flags, 0]);
+                buf.extend_from_slice(&qos.depth.to_le_bytes());
+                put_name(&mut buf, topic);
+                put_name(&mut buf, type_name);
+            }
+            Frame::Data { stream, payload } => {
+                put_header(&mut buf, DATA, *stream);
+                buf.extend_from_slice(payload);
+            }
+            Frame::Delete { stream } => put_header(&mut buf, DELETE, *stream),
+            Frame::Status { stream, code } => {
+                put_header(&mut buf, STATUS, *stream);
+                buf.push(*code as u8);
+            }
+            Frame::Ping => put_header(&mut buf, PING, 0),
+            Frame::Pong => put_header(&mut buf, PONG, 0),
+        }
+        buf
+    }
+
+    /// Decode a frame.
+    pub fn decode(bytes: &[u8]) -> Result<Self, FrameError> {
+        let mut reader = Reader(bytes);
+        let kind = reader.u8()?;
+        let stream = reader.u16()?;
+        let frame = match kind {
+            CREATE_PUBLISHER | CREATE_SUBSCRIBER => {
+                let flags = reader.u8()?;
+                let _reserved = reader.u8()?;
+                let qos = StreamQos {
+                    flags,
+                    depth: reader.u16()?,
+                };
+                let topic = reader.name()?;
+                let type_name = reader.name()?;
+                if kind == CREATE_PUBLISHER {
+                    Frame::CreatePublisher {
+                        stream,
+                        qos,
+                        topic,
+                        type_name,
+                    }
+                } else {
+                    Frame::CreateSubscriber {
+                        stream,
+                        qos,
+                        topic,
+                        type_name,
+                    }
+                }
+            }
+            DATA => Frame::Data {
+                stream,
+                payload: reader.0.to_vec(),
+            },
+            DELETE => Frame::Delete { stream },
+            STATUS => Frame::Status {
+                stream,
+                code: reader.u8()?.try_into()?,
+            },
+            PING => Frame::Ping,
+            PONG => Frame::Pong,
+            kind => return Err(FrameError::UnknownKind(kind)),
+        };
+        Ok(frame)
+    }
+}
+
+fn put_header(buf: &mut Vec<u8>, kind: u8, stream: u16) {
+    buf.push(kind);
+    buf.extend_from_slice(&stream.to_le_bytes());
+}
+
+fn put_name(buf: &mut Vec<u8>, name: &str) {
+    let len = u16::try_from(name.len()).unwrap_or(u16::MAX);
+    buf.extend_from_slice(&len.to_le_bytes());
+    buf.extend_from_slice(&name.as_bytes()[..len.into()]);
+}
+
+/// Cursor over the bytes of a frame.
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], FrameError> {
+        if self.0.len() < len {
+            return Err(FrameError::Truncated);
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, FrameError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, FrameError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn name(&mut self) -> Result<String, FrameError> {
+        let len = self.u16()?.into();
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| FrameError::InvalidName)
+    }
+}
+
+/// CRC-16/CCITT-FALSE of `bytes`.
+pub fn crc16(bytes: &[u8]) -> u16 {
+    let mut crc: u16 = 0xffff;
+    for &byte in bytes {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Encode a frame for a byte stream: CRC, COBS and zero terminator.
+pub fn encode_stream_frame(frame: &Frame) -> Vec<u8> {
+    let mut bytes = frame.encode();
+    let crc = crc16(&bytes);
+    bytes.extend_from_slice(&crc.to_le_bytes());
+
+    // COBS: every zero is replaced by the distance to the next one
+    let mut out = Vec::with_capacity(bytes.len() + bytes.len() / 254 + 2);
+    let mut code_pos = 0;
+    out.push(0);
+    let mut code = 1u8;
+    for &byte in &bytes {
+        if byte == 0 {
+            out[code_pos] = code;
+            code_pos = out.len();
+            out.push(0);
+            code = 1;
+        } else {
+            out.push(byte);
+            code += 1;
+            if code == 0xff {
+                out[code_pos] = code;
+                code_pos = out.len();
+                out.push(0);
+                code = 1;
+            }
+        }
+    }
+    out[code_pos] = code;
+    out.push(0);
+    out
+}
+
+/// Decoder of the frames of a byte stream, fed with the received bytes.
+#[derive(Default)]
+pub struct StreamDecoder {
+    buf: Vec<u8>,
+    overflow: bool,
+}
+
+impl StreamDecoder {
+    /// Create a decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a received byte, returning a frame when it terminates one.
+    ///
+    /// Bytes received before the first terminator, e.g. when the device
+    /// started sending before the agent opened the link, yield an error
+    /// which can be ignored.
+    pub fn push(&mut self, byte: u8) -> Option<Result<Frame, FrameError>> {
+        if byte != 0 {
+            if self.buf.len() < MAX_FRAME_LEN {
+                self.buf.push(byte);
+            } else {
+                self.overflow = true;
+            }
+            return None;
+        }
+
+        let encoded = std::mem::take(&mut self.buf);
+        if std::mem::take(&mut self.overflow) {
+            return Some(Err(FrameError::TooLong));
+        }
+        if encoded.is_empty() {
+            return None;
+        }
+        Some(decode_stream_frame(&encoded))
+    }
+}
+
+/// Decode a COBS-encoded frame, without its zero terminator.
+fn decode_stream_frame(encoded: &[u8]) -> Result<Frame, FrameError> {
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+    while i < encoded.len() {
+        let code = encoded[i] as usize;
+        if code == 0 || i + code > encoded.len() {
+            return Err(FrameError::InvalidEncoding);
+        }
+        bytes.extend_from_slice(&encoded[i + 1..i + code]);
+        i += code;
+        if code < 0xff && i < encoded.len() {
+            bytes.push(0);
+        }
+    }
+
+    let Some(body_len) = bytes.len().checked_sub(2) else {
+        return Err(FrameError::Truncated);
+    };
+    let (body, crc) = bytes.split_at(body_len);
+    if crc16(body).to_le_bytes() != crc {
+        return Err(FrameError::BadChecksum);
+    }
+    Frame::decode(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames() {
+        let frames = [
+            Frame::CreatePublisher {
+                stream: 1,
+                qos: StreamQos::default(),
+                topic: "imu".to_string(),
+                type_name: "sensor_msgs/msg/Imu".to_string(),
+            },
+            Frame::CreateSubscriber {
+                stream: 2,
+                qos: StreamQos {
+                    flags: StreamQos::TRANSIENT_LOCAL,
+                    depth: 1,
+                },
+                topic: "/cmd_vel".to_string(),
+                type_name: "geometry_msgs/Twist".to_string(),
+            },
+            Frame::Data {
+                stream: 1,
+                payload: vec![0, 1, 0, 0, 42, 0, 0, 0],
+            },
+            Frame::Delete { stream: 2 },
+            Frame::Status {
+                stream: 1,
+                code: StatusCode::UnknownType,
+            },
+            Frame::Ping,
+            Frame::Pong,
+        ];
+        for frame in frames {
+            assert_eq!(Frame::decode(&frame.encode()), Ok(frame.clone()));
+
+            let mut decoder = StreamDecoder::new();
+            let encoded = encode_stream_frame(&frame);
+            assert_eq!(encoded.iter().filter(|&&b| b == 0).count(), 1);
+            let decoded: Vec<_> = encoded.iter().filter_map(|&b| decoder.push(b)).collect();
+            assert_eq!(decoded, [Ok(frame)]);
+        }
+
+        assert_eq!(Frame::decode(&[DATA]), Err(FrameError::Truncated));
+        assert_eq!(
+            Frame::decode(&[0x42, 0, 0]),
+            Err(FrameError::UnknownKind(0x42))
+        );
+        assert_eq!(
+            Frame::decode(&[STATUS, 0, 0, 9]),
+            Err(FrameError::UnknownStatus(9))
+        );
+    }
+
+    #[test]
+    fn test_stream_decoder() {
+        // Long payloads need several COBS blocks
+        let frame = Frame::Data {
+            stream: 7,
+            payload: (0..600).map(|i| (i % 7) as u8).collect(),
+        };
+        let mut encoded = vec![0x13, 0x37, 0];
+        encoded.extend(encode_stream_frame(&frame));
+        let mut corrupted = encode_stream_frame(&Frame::Ping);
+        corrupted[1] ^= 0x10;
+        encoded.extend(corrupted);
+
+        let mut decoder = StreamDecoder::new();
+        let decoded: Vec<_> = encoded.iter().filter_map(|&b| decoder.push(b)).collect();
+        assert_eq!(decoded.len(), 3);
+        assert!(decoded[0].is_err());
+        assert_eq!(decoded[1], Ok(frame));
+        assert_eq!(decoded[2], Err(FrameError::BadChecksum));
+    }
+
+    #[test]
+    fn test_qos() {
+        let profile = StreamQos {
+            flags: StreamQos::TRANSIENT_LOCAL,
+            depth: 0,
+        }
+        .to_profile();
+        assert_eq!(profile.reliability, ReliabilityPolicy::BestEffort);
+        assert_eq!(profile.durability, DurabilityPolicy::TransientLocal);
+        assert_eq!(profile.history, HistoryPolicy::KeepAll);
+
+        let profile = StreamQos::default().to_profile();
+        let default = Profile::default();
+        assert_eq!(profile.reliability, default.reliability);
+        assert_eq!(profile.durability, default.durability);
+        assert_eq!(profile.history, default.history);
+        assert_eq!(profile.depth, default.depth);
+    }
+
+    #[test]
+    fn test_crc16() {
+        // Check value of CRC-16/CCITT-FALSE
+        assert_eq!(crc16(b"123456789"), 0x29b1);
+    }
+}
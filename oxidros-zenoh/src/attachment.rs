@@ -17,8 +17,25 @@
 //! | 17 | 16 | Publisher/Client GID (16 bytes) |
 //!
 //! Total: 33 bytes
+//!
+//! A [`TraceContext`] may follow the fixed header when distributed tracing is
+//! in use; see [`crate::trace_context`] for its encoding.
+//!
+//! The chunks of a message split by a publisher (see
+//! [`ChunkOptions`](crate::topic::ChunkOptions)) share its sequence number and
+//! end with a [`ChunkInfo`], after the trace context (encoded even if empty):
+//!
+//! | Size | Content |
+//! |------|---------|
+//! | 1 | Marker (`0xC7`) |
+//! | 4 | Index of the chunk (u32 LE) |
+//! | 4 | Number of chunks (u32 LE) |
+//! | 8 | Size of the whole payload (u64 LE) |
 
-use crate::error::{Error, Result};
+use crate::{
+    error::{Error, Result},
+    trace_context::TraceContext,
+};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Size of the attachment in bytes.
@@ -27,6 +44,23 @@ pub const ATTACHMENT_SIZE: usize = 33;
 /// Size of the GID (Global Identifier).
 pub const GID_SIZE: usize = 16;
 
+/// Marker of the [`ChunkInfo`] of an attachment.
+const CHUNK_MARKER: u8 = 0xC7;
+
+/// Size of the encoded [`ChunkInfo`], marker included.
+const CHUNK_INFO_SIZE: usize = 17;
+
+/// Position of a chunk in a message split by its publisher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkInfo {
+    /// Index of the chunk, from 0.
+    pub index: u32,
+    /// Number of chunks of the message.
+    pub count: u32,
+    /// Size of the payload of the whole message.
+    pub total_size: u64,
+}
+
 /// Attachment data for messages.
 #[derive(Debug, Clone)]
 pub struct Attachment {
@@ -36,6 +70,10 @@ pub struct Attachment {
     pub timestamp_ns: i64,
     /// Global identifier (publisher or client GID).
     pub gid: [u8; GID_SIZE],
+    /// Propagated trace context (empty unless tracing is in use).
+    pub trace_context: TraceContext,
+    /// Position of the chunk, if the message was split by its publisher.
+    pub chunk: Option<ChunkInfo>,
 }
 
 impl Default for Attachment {
@@ -56,9 +94,19 @@ impl Attachment {
             sequence_number,
             timestamp_ns,
             gid,
+            trace_context: TraceContext::default(),
+            chunk: None,
         }
     }
 
+    /// Attach the trace context of the current span.
+    ///
+    /// Leaves the attachment unchanged unless the `otel` feature is enabled.
+    pub fn with_current_context(mut self) -> Self {
+        self.trace_context = TraceContext::current();
+        self
+    }
+
     /// Encode the attachment to bytes.
     ///
     /// # Format
@@ -67,8 +115,10 @@ impl Attachment {
     /// - 8 bytes: timestamp (i64 LE)
     /// - 1 byte: GID length (16)
     /// - 16 bytes: GID
-    pub fn to_bytes(&self) -> [u8; ATTACHMENT_SIZE] {
-        let mut bytes = [0u8; ATTACHMENT_SIZE];
+    /// - trace context entries, only if non-empty or followed by chunk info
+    /// - chunk info, only for chunks
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; ATTACHMENT_SIZE];
 
         // Sequence number (i64 LE)
         bytes[0..8].copy_from_slice(&self.sequence_number.to_le_bytes());
@@ -82,6 +132,19 @@ impl Attachment {
         // GID
         bytes[17..33].copy_from_slice(&self.gid);
 
+        // Trace context
+        if !self.trace_context.is_empty() || self.chunk.is_some() {
+            self.trace_context.encode(&mut bytes);
+        }
+
+        // Chunk info
+        if let Some(chunk) = &self.chunk {
+            bytes.push(CHUNK_MARKER);
+            bytes.extend_from_slice(&chunk.index.to_le_bytes());
+            bytes.extend_from_slice(&chunk.count.to_le_bytes());
+            bytes.extend_from_slice(&chunk.total_size.to_le_bytes());
+        }
+
         bytes
     }
 
@@ -121,14 +184,45 @@ impl Attachment {
         let mut gid = [0u8; GID_SIZE];
         gid.copy_from_slice(&bytes[17..33]);
 
+        let (trace_context, rest) = TraceContext::decode_prefix(&bytes[ATTACHMENT_SIZE..])?;
+        let chunk = decode_chunk_info(rest)?;
+
         Ok(Self {
             sequence_number,
             timestamp_ns,
             gid,
+            trace_context,
+            chunk,
         })
     }
 }
 
+/// Decode the [`ChunkInfo`] following the trace context, if any.
+fn decode_chunk_info(bytes: &[u8]) -> Result<Option<ChunkInfo>> {
+    if bytes.first() != Some(&CHUNK_MARKER) {
+        return Ok(None);
+    }
+    if bytes.len() < CHUNK_INFO_SIZE {
+        return Err(Error::InvalidAttachment("truncated chunk info".into()));
+    }
+    let u32_at =
+        |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+    let mut total_size = [0u8; 8];
+    total_size.copy_from_slice(&bytes[9..17]);
+    let chunk = ChunkInfo {
+        index: u32_at(1),
+        count: u32_at(5),
+        total_size: u64::from_le_bytes(total_size),
+    };
+    if chunk.index >= chunk.count {
+        return Err(Error::InvalidAttachment(format!(
+            "invalid chunk index: {} of {}",
+            chunk.index, chunk.count
+        )));
+    }
+    Ok(Some(chunk))
+}
+
 // ============================================================================
 // MessageInfo conversion
 // ============================================================================
@@ -175,6 +269,54 @@ mod tests {
         assert_eq!(decoded.gid, gid);
     }
 
+    #[test]
+    fn test_attachment_with_trace_context() {
+        let mut attachment = Attachment::new(7, generate_gid());
+        attachment.trace_context.insert(
+            "traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        );
+
+        let bytes = attachment.to_bytes();
+        assert!(bytes.len() > ATTACHMENT_SIZE);
+
+        let decoded = Attachment::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.sequence_number, 7);
+        assert_eq!(decoded.trace_context, attachment.trace_context);
+    }
+
+    #[test]
+    fn test_attachment_with_chunk_info() {
+        let mut attachment = Attachment::new(3, generate_gid());
+        let chunk = ChunkInfo {
+            index: 1,
+            count: 4,
+            total_size: 10_000_000,
+        };
+        attachment.chunk = Some(chunk);
+
+        let bytes = attachment.to_bytes();
+        assert_eq!(bytes.len(), ATTACHMENT_SIZE + 1 + CHUNK_INFO_SIZE);
+
+        let decoded = Attachment::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.sequence_number, 3);
+        assert!(decoded.trace_context.is_empty());
+        assert_eq!(decoded.chunk, Some(chunk));
+
+        // With a trace context before the chunk info
+        attachment
+            .trace_context
+            .insert("tracestate", "vendor=value");
+        let decoded = Attachment::from_bytes(&attachment.to_bytes()).unwrap();
+        assert_eq!(decoded.trace_context, attachment.trace_context);
+        assert_eq!(decoded.chunk, Some(chunk));
+
+        // Unchunked attachments keep the rmw_zenoh size
+        attachment.chunk = None;
+        attachment.trace_context = TraceContext::default();
+        assert_eq!(attachment.to_bytes().len(), ATTACHMENT_SIZE);
+    }
+
     #[test]
     fn test_attachment_from_short_bytes() {
         let short_bytes = [0u8; 10];
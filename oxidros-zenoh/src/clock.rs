@@ -1,23 +1,445 @@
 //! Fake clock implementation for oxidros-zenoh
 //!
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+//! Time jumps are emulated to mirror the `rcl` clock: overriding the ROS time
+//! invokes the registered jump callbacks, and steps of the system time (e.g.,
+//! by NTP) are detected by comparing it against a monotonic clock whenever
+//! [`Clock::get_now`] is called. Slewing of the system time by NTP is not
+//! reported as a jump.
+use std::{
+    sync::{
+        Arc, OnceLock,
+        mpsc::{self, Sender},
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use oxidros_core::{
+    targets,
+    time::{ClockChange, JumpHandle, JumpPostCallback, JumpPreCallback, JumpThreshold, TimeJump},
+};
+use parking_lot::Mutex;
 
 use crate::error::Result;
 
+/// Drift between the system and monotonic clocks that is not reported as a
+/// jump by default, so that reading jitter does not invoke callbacks.
+pub const DEFAULT_SYSTEM_TIME_STEP_TOLERANCE: Duration = Duration::from_millis(100);
+
+/// Maximum rate, in parts per million, at which NTP slews the system time.
+/// The drift it accumulates between two readings is not reported as a jump.
+const MAX_SLEW_RATE_PPM: u128 = 500;
+
 /// A clock. For now only SystemTime/ROSTime is implemented.
-#[derive(Debug)]
-pub struct Clock;
+pub struct Clock {
+    /// Time set by [`Clock::set_ros_time_override`].
+    ros_time: Duration,
+    /// Whether `ros_time` is used instead of the system time.
+    ros_time_override: bool,
+    /// Last system time reading and when it was taken, to detect steps.
+    last_system_time: Option<(Duration, Instant)>,
+    /// Drift of the system time not reported as a jump, besides slewing.
+    step_tolerance: Duration,
+    jump_callbacks: Vec<JumpCallback>,
+    next_jump_id: u64,
+}
+
+struct JumpCallback {
+    id: u64,
+    threshold: JumpThreshold,
+    callbacks: Arc<Mutex<JumpCallbacks>>,
+}
+
+struct JumpCallbacks {
+    pre: Option<JumpPreCallback>,
+    post: Option<JumpPostCallback>,
+}
+
+/// Change of the time of a clock.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum TimeChange {
+    EnableOverride,
+    DisableOverride,
+    RosTime(Duration),
+}
+
+/// Jump callbacks selected for a change of the time, invoked without
+/// borrowing the clock so that they may read it.
+struct PendingJump {
+    jump: TimeJump,
+    callbacks: Vec<Arc<Mutex<JumpCallbacks>>>,
+}
+
+impl PendingJump {
+    fn pre(&self) {
+        for callbacks in &self.callbacks {
+            if let Some(pre) = callbacks.lock().pre.as_mut() {
+                pre();
+            }
+        }
+    }
+
+    fn post(&self) {
+        for callbacks in &self.callbacks {
+            if let Some(post) = callbacks.lock().post.as_mut() {
+                post(&self.jump);
+            }
+        }
+    }
+}
 
 impl Clock {
     /// Create a clock.
     pub fn new() -> Result<Self> {
-        Ok(Self)
+        Ok(Self {
+            ros_time: Duration::ZERO,
+            ros_time_override: false,
+            last_system_time: None,
+            step_tolerance: DEFAULT_SYSTEM_TIME_STEP_TOLERANCE,
+            jump_callbacks: Vec::new(),
+            next_jump_id: 0,
+        })
     }
 
     /// Get duration since epoc
     pub fn get_now(&mut self) -> Result<Duration> {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| oxidros_core::Error::Other(format!("{e}")))
+        if self.ros_time_override {
+            return Ok(self.ros_time);
+        }
+
+        let now = system_time()?;
+        let instant = Instant::now();
+        if let Some((last, last_instant)) = self.last_system_time {
+            let elapsed = instant.duration_since(last_instant);
+            let delta = delta_nanos(last + elapsed, now);
+            let tolerance =
+                self.step_tolerance.as_nanos() + elapsed.as_nanos() * MAX_SLEW_RATE_PPM / 1_000_000;
+            if u128::from(delta.unsigned_abs()) > tolerance {
+                let pending = self.pending_jump(ClockChange::SystemTimeNoChange, delta);
+                if !pending.callbacks.is_empty() {
+                    notify_step(pending);
+                }
+            }
+        }
+        self.last_system_time = Some((now, instant));
+        Ok(now)
+    }
+
+    /// Set the drift of the system time that is not reported as a jump,
+    /// [`DEFAULT_SYSTEM_TIME_STEP_TOLERANCE`] by default.
+    ///
+    /// The drift accumulated by NTP slewing between two readings of the clock
+    /// is tolerated on top of it.
+    pub fn set_system_time_step_tolerance(&mut self, tolerance: Duration) {
+        self.step_tolerance = tolerance;
+    }
+
+    /// Register callbacks invoked before and after a time jump that exceeds
+    /// `threshold`.
+    ///
+    /// The callbacks run on the thread that changes the time. Since a step of
+    /// the system time is only noticed afterwards by [`Clock::get_now`], both
+    /// callbacks are invoked after the step in that case, from a notifier
+    /// thread so that they may read the clock.
+    pub fn create_jump_callback(
+        &mut self,
+        threshold: JumpThreshold,
+        pre_cb: Option<JumpPreCallback>,
+        post_cb: Option<JumpPostCallback>,
+    ) -> Result<JumpHandle> {
+        let id = self.next_jump_id;
+        self.next_jump_id += 1;
+        self.jump_callbacks.push(JumpCallback {
+            id,
+            threshold,
+            callbacks: Arc::new(Mutex::new(JumpCallbacks {
+                pre: pre_cb,
+                post: post_cb,
+            })),
+        });
+        Ok(JumpHandle::from_raw(id))
+    }
+
+    /// Unregister callbacks added by [`Clock::create_jump_callback`].
+    pub fn remove_jump_callback(&mut self, handle: JumpHandle) -> Result<()> {
+        let len = self.jump_callbacks.len();
+        self.jump_callbacks.retain(|cb| cb.id != handle.as_raw());
+        if self.jump_callbacks.len() == len {
+            return Err(oxidros_core::Error::InvalidConfig(format!(
+                "no jump callback with handle {}",
+                handle.as_raw()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Use the time set by [`Clock::set_ros_time_override`] instead of the
+    /// system time.
+    pub fn enable_ros_time_override(&mut self) -> Result<()> {
+        self.change_time(TimeChange::EnableOverride)
+    }
+
+    /// Go back to the system time.
+    pub fn disable_ros_time_override(&mut self) -> Result<()> {
+        self.change_time(TimeChange::DisableOverride)
+    }
+
+    /// Set the ROS time, e.g. from a `/clock` message.
+    pub fn set_ros_time_override(&mut self, time: Duration) -> Result<()> {
+        self.change_time(TimeChange::RosTime(time))
+    }
+
+    /// Apply `change` between the pre- and post-jump callbacks.
+    fn change_time(&mut self, change: TimeChange) -> Result<()> {
+        let pending = self.prepare(change)?;
+        pending.pre();
+        self.apply(change);
+        pending.post();
+        Ok(())
+    }
+
+    /// Select the jump callbacks of `change`.
+    fn prepare(&self, change: TimeChange) -> Result<PendingJump> {
+        Ok(match change {
+            TimeChange::EnableOverride if !self.ros_time_override => self.pending_jump(
+                ClockChange::RosTimeActivated,
+                delta_nanos(system_time()?, self.ros_time),
+            ),
+            TimeChange::DisableOverride if self.ros_time_override => self.pending_jump(
+                ClockChange::RosTimeDeactivated,
+                delta_nanos(self.ros_time, system_time()?),
+            ),
+            TimeChange::RosTime(time) if self.ros_time_override => self.pending_jump(
+                ClockChange::RosTimeNoChange,
+                delta_nanos(self.ros_time, time),
+            ),
+            _ => PendingJump {
+                jump: TimeJump {
+                    clock_change: ClockChange::RosTimeNoChange,
+                    delta_nanos: 0,
+                },
+                callbacks: Vec::new(),
+            },
+        })
+    }
+
+    fn apply(&mut self, change: TimeChange) {
+        match change {
+            TimeChange::EnableOverride => self.ros_time_override = true,
+            TimeChange::DisableOverride => {
+                if self.ros_time_override {
+                    self.ros_time_override = false;
+                    self.last_system_time = None;
+                }
+            }
+            TimeChange::RosTime(time) => self.ros_time = time,
+        }
+    }
+
+    fn pending_jump(&self, clock_change: ClockChange, delta_nanos: i64) -> PendingJump {
+        let jump = TimeJump {
+            clock_change,
+            delta_nanos,
+        };
+        let callbacks = self
+            .jump_callbacks
+            .iter()
+            .filter(|cb| cb.threshold.is_exceeded(&jump))
+            .map(|cb| cb.callbacks.clone())
+            .collect();
+        PendingJump { jump, callbacks }
+    }
+}
+
+/// Apply `change` to a shared clock, invoking the jump callbacks without
+/// holding its lock so that they may read the clock, e.g. through
+/// [`Node::get_clock`](crate::node::Node::get_clock).
+pub(crate) fn change_unlocked(clock: &Mutex<Clock>, change: TimeChange) -> Result<()> {
+    let pending = clock.lock().prepare(change)?;
+    pending.pre();
+    clock.lock().apply(change);
+    pending.post();
+    Ok(())
+}
+
+/// Invoke the callbacks of a step of the system time on a notifier thread
+/// shared by all clocks, as the clock is borrowed, and possibly locked, by
+/// [`Clock::get_now`].
+///
+/// The step is only noticed after it happened, so the pre-jump callbacks also
+/// run after it, right before the post-jump ones.
+fn notify_step(pending: PendingJump) {
+    static NOTIFIER: OnceLock<Option<Sender<PendingJump>>> = OnceLock::new();
+
+    let notifier = NOTIFIER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<PendingJump>();
+        let result = std::thread::Builder::new()
+            .name("oxidros-clock-jump".into())
+            .spawn(move || {
+                for pending in receiver {
+                    pending.pre();
+                    pending.post();
+                }
+            });
+        match result {
+            Ok(_) => Some(sender),
+            Err(e) => {
+                tracing::warn!(
+                    target: targets::CLOCK,
+                    error = %e,
+                    "Failed to start the notifier of system time steps"
+                );
+                None
+            }
+        }
+    });
+    if notifier
+        .as_ref()
+        .is_none_or(|sender| sender.send(pending).is_err())
+    {
+        tracing::warn!(
+            target: targets::CLOCK,
+            "Failed to notify a step of the system time"
+        );
+    }
+}
+
+impl std::fmt::Debug for Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Clock")
+            .field("ros_time", &self.ros_time)
+            .field("ros_time_override", &self.ros_time_override)
+            .field("jump_callbacks", &self.jump_callbacks.len())
+            .finish()
+    }
+}
+
+fn system_time() -> Result<Duration> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| oxidros_core::Error::Other(format!("{e}")))
+}
+
+/// Signed difference `to - from` in nanoseconds, saturating at `i64` bounds.
+fn delta_nanos(from: Duration, to: Duration) -> i64 {
+    let delta = to.as_nanos() as i128 - from.as_nanos() as i128;
+    delta.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicI64, AtomicUsize, Ordering},
+    };
+
+    #[test]
+    fn test_ros_time_jump_callbacks() {
+        let mut clock = Clock::new().unwrap();
+        let pre_calls = Arc::new(AtomicUsize::new(0));
+        let last_delta = Arc::new(AtomicI64::new(0));
+
+        let pre = pre_calls.clone();
+        let delta = last_delta.clone();
+        let handle = clock
+            .create_jump_callback(
+                JumpThreshold {
+                    on_clock_change: false,
+                    min_forward: None,
+                    min_backward: Some(Duration::from_nanos(1)),
+                },
+                Some(Box::new(move || {
+                    pre.fetch_add(1, Ordering::SeqCst);
+                })),
+                Some(Box::new(move |jump| {
+                    delta.store(jump.delta_nanos, Ordering::SeqCst);
+                })),
+            )
+            .unwrap();
+
+        clock
+            .set_ros_time_override(Duration::from_secs(10))
+            .unwrap();
+        clock.enable_ros_time_override().unwrap();
+        assert_eq!(clock.get_now().unwrap(), Duration::from_secs(10));
+        assert_eq!(pre_calls.load(Ordering::SeqCst), 0);
+
+        // Forward jumps are below the threshold
+        clock
+            .set_ros_time_override(Duration::from_secs(20))
+            .unwrap();
+        assert_eq!(pre_calls.load(Ordering::SeqCst), 0);
+
+        clock
+            .set_ros_time_override(Duration::from_secs(15))
+            .unwrap();
+        assert_eq!(pre_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(last_delta.load(Ordering::SeqCst), -5_000_000_000);
+
+        clock.remove_jump_callback(handle).unwrap();
+        clock.set_ros_time_override(Duration::from_secs(1)).unwrap();
+        assert_eq!(pre_calls.load(Ordering::SeqCst), 1);
+        assert!(clock.remove_jump_callback(handle).is_err());
+    }
+
+    #[test]
+    fn test_jump_callback_reads_shared_clock() {
+        let clock = Arc::new(Mutex::new(Clock::new().unwrap()));
+        let seen = Arc::new(AtomicI64::new(0));
+
+        let clock_cloned = clock.clone();
+        let seen_cloned = seen.clone();
+        clock
+            .lock()
+            .create_jump_callback(
+                JumpThreshold {
+                    on_clock_change: true,
+                    min_forward: Some(Duration::from_nanos(1)),
+                    min_backward: None,
+                },
+                None,
+                Some(Box::new(move |_| {
+                    let now = clock_cloned.lock().get_now().unwrap();
+                    seen_cloned.store(now.as_secs() as i64, Ordering::SeqCst);
+                })),
+            )
+            .unwrap();
+
+        change_unlocked(&clock, TimeChange::EnableOverride).unwrap();
+        change_unlocked(&clock, TimeChange::RosTime(Duration::from_secs(42))).unwrap();
+        assert_eq!(seen.load(Ordering::SeqCst), 42);
+    }
+
+    #[test]
+    fn test_system_time_steps_share_notifier_thread() {
+        let (sender, receiver) = mpsc::channel();
+        let pre_sender = sender.clone();
+        let callbacks = Arc::new(Mutex::new(JumpCallbacks {
+            pre: Some(Box::new(move || {
+                pre_sender
+                    .send(("pre", std::thread::current().id()))
+                    .unwrap();
+            })),
+            post: Some(Box::new(move |_| {
+                sender.send(("post", std::thread::current().id())).unwrap();
+            })),
+        }));
+        let step = || PendingJump {
+            jump: TimeJump {
+                clock_change: ClockChange::SystemTimeNoChange,
+                delta_nanos: 1_000_000_000,
+            },
+            callbacks: vec![callbacks.clone()],
+        };
+
+        notify_step(step());
+        notify_step(step());
+        let calls: Vec<_> = (0..4)
+            .map(|_| receiver.recv_timeout(Duration::from_secs(2)).unwrap())
+            .collect();
+        let order: Vec<_> = calls.iter().map(|(name, _)| *name).collect();
+        assert_eq!(order, ["pre", "post", "pre", "post"]);
+        assert!(calls.iter().all(|(_, id)| *id == calls[0].1));
+        assert_ne!(calls[0].1, std::thread::current().id());
     }
 }
@@ -9,30 +9,54 @@
 
 use crate::{
     error::{Error, Result, Ros2ArgsResultExt},
+    graph,
     graph_cache::GraphCache,
     node::Node,
+    transport::TransportMonitor,
+};
+use oxidros_core::{
+    NetworkFlowEndpoint,
+    graph::GraphSnapshot,
+    qos::{Profile, QosCompatibility},
+    targets,
 };
-use oxidros_core::targets;
 use parking_lot::Mutex;
-use ros2args::Ros2Args;
+use ros2args::{Ros2Args, RosEnv};
 use std::{
     env,
     sync::{
         Arc,
         atomic::{AtomicU32, Ordering},
     },
+    time::{Duration, Instant},
 };
-use zenoh::{Session, Wait};
+use zenoh::{Session, Wait, sample::SampleKind};
 
 /// Environment variable for custom Zenoh session config.
 pub const ZENOH_SESSION_CONFIG_URI: &str = "ZENOH_SESSION_CONFIG_URI";
 
-/// Environment variable for ROS domain ID.
-pub const ROS_DOMAIN_ID: &str = "ROS_DOMAIN_ID";
-
 /// Default Zenoh router endpoint.
 pub const DEFAULT_ROUTER_ENDPOINT: &str = "tcp/localhost:7447";
 
+/// Listen endpoint used when `ROS_LOCALHOST_ONLY=1`.
+pub const LOCALHOST_LISTEN_ENDPOINT: &str = "tcp/127.0.0.1:0";
+
+/// Read the ROS2 environment variables.
+fn read_ros_env() -> Result<RosEnv> {
+    RosEnv::from_env().map_err(|e| Error::InvalidConfig(e.to_string()))
+}
+
+/// Participants awaited by [`Context::wait_for_discovery`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiscoveryTarget {
+    /// At least this many other sessions with a live node.
+    Peers(usize),
+    /// The node with this fully qualified name.
+    ///
+    /// A name without a leading `/` is looked up in the root namespace.
+    Node(String),
+}
+
 /// Inner context data.
 struct ContextInner {
     /// Zenoh session.
@@ -45,10 +69,18 @@ struct ContextInner {
     next_node_id: AtomicU32,
     /// Graph cache for entity discovery.
     graph_cache: Arc<Mutex<GraphCache>>,
+    /// Channels notified when the graph cache changes.
+    graph_listeners: Arc<Mutex<Vec<flume::Sender<()>>>>,
     /// Parsed ROS2 command-line arguments.
     ros2_args: Ros2Args,
+    /// ROS2 environment variables.
+    ros_env: RosEnv,
     /// Liveliness subscriber for graph discovery (must be kept alive).
     _liveliness_subscriber: Mutex<Option<zenoh::pubsub::Subscriber<()>>>,
+    /// Recorder of the transport events.
+    transport: Arc<TransportMonitor>,
+    /// Admin space subscriber for transport events (must be kept alive).
+    _transport_subscriber: Mutex<Option<zenoh::pubsub::Subscriber<()>>>,
 }
 
 /// ROS2 context wrapping a Zenoh session.
@@ -56,6 +88,16 @@ struct ContextInner {
 /// A context represents a single Zenoh session and can contain multiple nodes.
 /// All nodes within a context share the same session for communication.
 ///
+/// # Domain Isolation
+///
+/// As with `rmw_zenoh_cpp`, the ROS domain ID prefixes every key expression
+/// of the context: `<domain_id>/<name>/<type>/<hash>` for topics and
+/// services, and `@ros2_lv/<domain_id>/...` for the liveliness tokens of the
+/// graph. Contexts on different domains may share Zenoh sessions and routers
+/// but neither exchange messages nor see each other's nodes. The domain ID
+/// is read from `ROS_DOMAIN_ID` by [`Context::new`], or given explicitly to
+/// [`Context::with_domain_id`].
+///
 /// # Example
 ///
 /// ```ignore
@@ -74,12 +116,15 @@ impl Context {
     /// 1. Parse ROS2 command-line arguments from `std::env::args()`
     /// 2. Read `ROS_DOMAIN_ID` from environment (default: 0)
     /// 3. Read `ZENOH_SESSION_CONFIG_URI` for custom config (optional)
-    /// 4. Open a Zenoh session in peer mode connecting to localhost:7447
+    /// 4. Open a Zenoh session in peer mode connecting to localhost:7447,
+    ///    listening on the loopback interface only if `ROS_LOCALHOST_ONLY=1`
+    ///
+    /// Nodes created without a namespace use `ROS_NAMESPACE` if it is set.
     ///
     /// # Errors
     ///
     /// Returns an error if:
-    /// - ROS2 arguments are malformed
+    /// - ROS2 arguments or environment variables are malformed
     /// - The Zenoh session cannot be opened
     pub fn new() -> Result<Arc<Self>> {
         // Parse ROS2 arguments from environment
@@ -94,16 +139,19 @@ impl Context {
     /// provide custom arguments programmatically.
     pub fn with_args(ros2_args: Ros2Args) -> Result<Arc<Self>> {
         // Get domain ID from environment
-        let domain_id = env::var(ROS_DOMAIN_ID)
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(0);
+        let ros_env = read_ros_env()?;
+        let domain_id = ros_env.domain_id.unwrap_or(0);
 
-        Self::with_args_and_domain_id(ros2_args, domain_id)
+        Self::open(ros2_args, domain_id, ros_env)
     }
 
     /// Create a new context with pre-parsed ROS2 arguments and specific domain ID.
     pub fn with_args_and_domain_id(ros2_args: Ros2Args, domain_id: u32) -> Result<Arc<Self>> {
+        Self::open(ros2_args, domain_id, read_ros_env()?)
+    }
+
+    /// Open a session with the default configuration for `ros_env`.
+    fn open(ros2_args: Ros2Args, domain_id: u32, ros_env: RosEnv) -> Result<Arc<Self>> {
         // Build Zenoh config
         let mut config = zenoh::Config::default();
 
@@ -118,6 +166,18 @@ impl Context {
                 .endpoints
                 .set(vec![DEFAULT_ROUTER_ENDPOINT.parse().unwrap()])
                 .map_err(|e| Error::InvalidConfig(format!("Failed to set endpoints: {:?}", e)))?;
+
+            // ROS_LOCALHOST_ONLY: neither listen nor scout outside of the host
+            if ros_env.localhost_only {
+                config
+                    .listen
+                    .endpoints
+                    .set(vec![LOCALHOST_LISTEN_ENDPOINT.parse().unwrap()])
+                    .map_err(|e| {
+                        Error::InvalidConfig(format!("Failed to set endpoints: {:?}", e))
+                    })?;
+                config.insert_json5("scouting/multicast/enabled", "false")?;
+            }
         }
 
         // Enable timestamping for AdvancedPublisher with Sequencing::Timestamp
@@ -126,10 +186,11 @@ impl Context {
             &serde_json::json!({"router": true, "peer": true, "client": true}).to_string(),
         )?;
 
-        Self::with_full_config(ros2_args, domain_id, config)
+        Self::open_with_config(ros2_args, domain_id, config, ros_env)
     }
 
-    /// Create a new context with a specific domain ID (legacy API).
+    /// Create a new context on the ROS domain `domain_id`, ignoring
+    /// `ROS_DOMAIN_ID`.
     ///
     /// This parses ROS2 arguments from `std::env::args()`.
     pub fn with_domain_id(domain_id: u32) -> Result<Arc<Self>> {
@@ -152,6 +213,17 @@ impl Context {
         ros2_args: Ros2Args,
         domain_id: u32,
         config: zenoh::Config,
+    ) -> Result<Arc<Self>> {
+        Self::open_with_config(ros2_args, domain_id, config, read_ros_env()?)
+    }
+
+    /// Open a session with `config`, keeping the ROS2 environment variables
+    /// read by the caller.
+    fn open_with_config(
+        ros2_args: Ros2Args,
+        domain_id: u32,
+        config: zenoh::Config,
+        ros_env: RosEnv,
     ) -> Result<Arc<Self>> {
         // Open Zenoh session
         let session = zenoh::open(config).wait()?;
@@ -168,14 +240,19 @@ impl Context {
             session_id,
             next_node_id: AtomicU32::new(0),
             graph_cache: Arc::new(Mutex::new(graph_cache)),
+            graph_listeners: Arc::new(Mutex::new(Vec::new())),
             ros2_args,
+            ros_env,
             _liveliness_subscriber: Mutex::new(None),
+            transport: Arc::new(TransportMonitor::default()),
+            _transport_subscriber: Mutex::new(None),
         });
 
         let ctx = Arc::new(Context { inner });
 
         // Start liveliness subscription for graph discovery
         ctx.start_graph_discovery()?;
+        ctx.start_transport_monitor()?;
 
         tracing::debug!(
             target: targets::ZENOH,
@@ -192,11 +269,77 @@ impl Context {
         self.inner.domain_id
     }
 
+    /// Get the ROS domain ID, the prefix of the key expressions of the context.
+    ///
+    /// Same as [`Context::domain_id`], under the name of
+    /// [`RosContext::ros_domain_id`](oxidros_core::api::RosContext::ros_domain_id).
+    pub fn ros_domain_id(&self) -> u32 {
+        self.inner.domain_id
+    }
+
+    /// Get the identifier of the RMW implementation this backend is
+    /// interoperable with, `rmw_zenoh_cpp`.
+    pub fn rmw_implementation(&self) -> &'static str {
+        "rmw_zenoh_cpp"
+    }
+
+    /// Check whether a publisher and a subscription with the given QoS
+    /// profiles match.
+    ///
+    /// See [`QosMapping::check_compatible`](crate::qos::QosMapping::check_compatible).
+    /// This never fails, the `Result` keeps the signature of the RCL backend.
+    pub fn rmw_qos_compatibility_check(
+        &self,
+        publisher: &Profile,
+        subscription: &Profile,
+    ) -> Result<QosCompatibility> {
+        Ok(crate::qos::QosMapping::check_compatible(
+            publisher,
+            subscription,
+        ))
+    }
+
     /// Get the Zenoh session ID as a hex string.
     pub fn session_id(&self) -> &str {
         &self.inner.session_id
     }
 
+    /// Get the recorder of the transport events of the session.
+    ///
+    /// See the [`transport`](crate::transport) module.
+    pub fn transport_monitor(&self) -> &Arc<TransportMonitor> {
+        &self.inner.transport
+    }
+
+    /// Get the local addresses of the links of the session with its peers
+    /// and routers.
+    ///
+    /// Zenoh sends the messages of every publisher and subscriber of the
+    /// session over these links. Links without an IP address, e.g. shared
+    /// memory, are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the admin space query fails.
+    pub fn network_flow_endpoints(&self) -> Result<Vec<NetworkFlowEndpoint>> {
+        let key = format!(
+            "@/{}/session/transport/unicast/*/link/*",
+            self.inner.session_id
+        );
+        let replies = self.inner.session.get(&key).wait()?;
+
+        let mut endpoints = Vec::new();
+        while let Ok(reply) = replies.recv() {
+            if let Ok(sample) = reply.result()
+                && let Some(endpoint) = link_source(sample)
+                && !endpoints.contains(&endpoint)
+            {
+                endpoints.push(endpoint);
+            }
+        }
+        Ok(endpoints)
+    }
+
     /// Get a reference to the Zenoh session.
     pub fn session(&self) -> &Session {
         &self.inner.session
@@ -207,6 +350,11 @@ impl Context {
         &self.inner.ros2_args
     }
 
+    /// Get the ROS2 environment variables read when the context was created.
+    pub fn ros_env(&self) -> &RosEnv {
+        &self.inner.ros_env
+    }
+
     /// Get the enclave from ROS2 arguments (for SROS2 security).
     pub fn enclave(&self) -> Option<&str> {
         self.inner.ros2_args.enclave.as_deref()
@@ -217,7 +365,10 @@ impl Context {
     /// # Arguments
     ///
     /// * `name` - Node name (must be a valid ROS2 node name)
-    /// * `namespace` - Optional namespace (must be a valid ROS2 namespace)
+    /// * `namespace` - Optional namespace (must be a valid ROS2 namespace),
+    ///   `ROS_NAMESPACE` if `None` or empty
+    ///
+    /// `__node:=` and `__ns:=` remapping rules override the name and namespace.
     ///
     /// # Errors
     ///
@@ -237,7 +388,7 @@ impl Context {
             Arc::clone(self),
             node_id,
             name,
-            namespace.unwrap_or(""),
+            self.inner.ros_env.resolve_namespace(namespace),
             enclave,
         )
     }
@@ -247,6 +398,79 @@ impl Context {
         self.inner.graph_cache.lock().clone()
     }
 
+    /// Take a snapshot of the nodes, publishers and subscribers in the graph cache.
+    pub(crate) fn graph_snapshot(&self) -> GraphSnapshot {
+        self.inner.graph_cache.lock().snapshot()
+    }
+
+    /// Subscribe to changes of the graph cache.
+    ///
+    /// The receiver gets a notification after each change; notifications
+    /// that have not been received yet are coalesced into one.
+    pub(crate) fn graph_listener(&self) -> flume::Receiver<()> {
+        let (tx, rx) = flume::bounded(1);
+        self.inner.graph_listeners.lock().push(tx);
+        rx
+    }
+
+    /// Wait until the graph cache has seen the liveliness of `target`.
+    ///
+    /// Liveliness tokens take a moment to propagate, so a publisher and a
+    /// subscriber started at the same time can miss each other's first
+    /// messages. Waiting for the expected participants before publishing
+    /// closes this window.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let ctx = Context::new()?;
+    /// let node = ctx.create_node("talker", None)?;
+    /// let publisher = node.create_publisher::<String>("chatter", None)?;
+    ///
+    /// ctx.wait_for_discovery(DiscoveryTarget::Node("/listener".into()), Duration::from_secs(5))
+    ///     .await?;
+    /// publisher.send(&msg)?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if `target` is not seen within `timeout`.
+    pub async fn wait_for_discovery(
+        &self,
+        target: DiscoveryTarget,
+        timeout: Duration,
+    ) -> Result<()> {
+        // Listen before checking so that no change is missed in between
+        let listener = self.graph_listener();
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.is_discovered(&target) {
+                tracing::debug!(
+                    target: targets::ZENOH,
+                    discovery = ?target,
+                    "Discovery complete"
+                );
+                return Ok(());
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::Timeout);
+            }
+            graph::wait(&listener, Some(deadline - now)).await?;
+        }
+    }
+
+    /// Check whether the graph cache has seen `target`.
+    fn is_discovered(&self, target: &DiscoveryTarget) -> bool {
+        let cache = self.inner.graph_cache.lock();
+        match target {
+            DiscoveryTarget::Peers(min_peers) => {
+                cache.count_peers(&self.inner.session_id) >= *min_peers
+            }
+            DiscoveryTarget::Node(name) => cache.snapshot().contains_node(name),
+        }
+    }
+
     /// Create a new selector.
     ///
     /// The selector is used to wait on events and invoke callbacks
@@ -257,11 +481,10 @@ impl Context {
 
     /// Start graph discovery by subscribing to liveliness tokens.
     fn start_graph_discovery(&self) -> Result<()> {
-        use crate::keyexpr::LIVELINESS_PREFIX;
-
-        // Include domain_id in the key pattern to match rmw_zenoh behavior
-        let key = format!("{}/{}/**", LIVELINESS_PREFIX, self.inner.domain_id);
+        // Only the tokens of the domain, to match rmw_zenoh behavior
+        let key = crate::keyexpr::liveliness_domain_keyexpr(self.inner.domain_id);
         let graph_cache = Arc::clone(&self.inner.graph_cache);
+        let graph_listeners = Arc::clone(&self.inner.graph_listeners);
 
         // Subscribe to liveliness tokens
         let subscriber = self
@@ -271,8 +494,14 @@ impl Context {
             .declare_subscriber(&key)
             .callback(move |sample| {
                 let key_expr = sample.key_expr().as_str();
-                let mut cache = graph_cache.lock();
-                cache.handle_liveliness_token(key_expr, sample.kind());
+                graph_cache
+                    .lock()
+                    .handle_liveliness_token(key_expr, sample.kind());
+
+                // Notify listeners, dropping the ones that went away
+                graph_listeners.lock().retain(|tx| {
+                    !matches!(tx.try_send(()), Err(flume::TrySendError::Disconnected(_)))
+                });
             })
             .wait()?;
 
@@ -291,6 +520,58 @@ impl Context {
 
         Ok(())
     }
+
+    /// Subscribe to the sessions with peers and routers in the admin space.
+    ///
+    /// Zenoh publishes `@/<zid>/session/transport/unicast/<peer zid>` locally
+    /// when a session opens, with the kind of the peer in a JSON payload, and
+    /// deletes it when the session closes.
+    fn start_transport_monitor(&self) -> Result<()> {
+        let key = format!("@/{}/session/transport/unicast/*", self.inner.session_id);
+        let monitor = Arc::clone(&self.inner.transport);
+
+        let subscriber = self
+            .inner
+            .session
+            .declare_subscriber(&key)
+            .callback(move |sample| {
+                let Some(zid) = sample.key_expr().as_str().rsplit('/').next() else {
+                    return;
+                };
+                match sample.kind() {
+                    SampleKind::Put => monitor.connected(zid, &peer_kind(&sample)),
+                    SampleKind::Delete => monitor.disconnected(zid),
+                }
+            })
+            .wait()?;
+        *self.inner._transport_subscriber.lock() = Some(subscriber);
+
+        // Sessions opened with the Zenoh session
+        let replies = self.inner.session.get(&key).wait()?;
+        while let Ok(reply) = replies.recv() {
+            if let Ok(sample) = reply.result()
+                && let Some(zid) = sample.key_expr().as_str().rsplit('/').next()
+            {
+                self.inner.transport.connected(zid, &peer_kind(sample));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Kind of the peer of an admin space transport sample, `unknown` if missing.
+fn peer_kind(sample: &zenoh::sample::Sample) -> String {
+    serde_json::from_slice::<serde_json::Value>(&sample.payload().to_bytes())
+        .ok()
+        .and_then(|info| info.get("whatami")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Local endpoint of an admin space link sample, from its `src` locator.
+fn link_source(sample: &zenoh::sample::Sample) -> Option<NetworkFlowEndpoint> {
+    let link = serde_json::from_slice::<serde_json::Value>(&sample.payload().to_bytes()).ok()?;
+    NetworkFlowEndpoint::from_locator(link.get("src")?.as_str()?)
 }
 
 // ============================================================================
@@ -316,4 +597,16 @@ impl oxidros_core::api::RosContext for Context {
     fn ros_domain_id(&self) -> u32 {
         self.domain_id()
     }
+
+    fn rmw_implementation(&self) -> &str {
+        Context::rmw_implementation(self)
+    }
+
+    fn rmw_qos_compatibility_check(
+        &self,
+        publisher: &Profile,
+        subscription: &Profile,
+    ) -> crate::error::Result<QosCompatibility> {
+        Context::rmw_qos_compatibility_check(self, publisher, subscription)
+    }
 }
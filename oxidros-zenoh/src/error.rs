@@ -6,6 +6,18 @@
 // Re-export the unified error types from oxidros-core
 pub use oxidros_core::error::{Error, Result};
 
+use crate::node::Node;
+use oxidros_core::error::{ErrorContext, Operation};
+
+/// Context of `operation` on the topic or service `entity` of `node`.
+pub(crate) fn entity_context(node: &Node, operation: Operation, entity: &str) -> ErrorContext {
+    let context = ErrorContext::new(operation, entity);
+    match node.z_fully_qualified_name() {
+        Ok(node) => context.with_node(node),
+        Err(_) => context,
+    }
+}
+
 // ============================================================================
 // Zenoh-specific conversion helpers
 // ============================================================================
@@ -18,6 +30,14 @@ pub trait Ros2ArgsResultExt<T> {
 
 impl<T> Ros2ArgsResultExt<T> for std::result::Result<T, ros2args::Ros2ArgsError> {
     fn map_name_err(self) -> Result<T> {
-        self.map_err(|e| Error::InvalidName(e.to_string()))
+        self.map_err(|e| match e {
+            ros2args::Ros2ArgsError::InvalidName {
+                name,
+                position,
+                reason,
+                ..
+            } => Error::invalid_name(name, position, reason),
+            e => Error::InvalidConfig(e.to_string()),
+        })
     }
 }
@@ -0,0 +1,378 @@
+//! Foxglove WebSocket server.
+//!
+//! Lets Foxglove Studio connect directly to an oxidros process
+//! (`ws://<host>:8765`) for live visualization. Every topic of the graph
+//! whose type description can be resolved is advertised as a channel; the
+//! CDR payloads of subscribed channels are forwarded as received, without
+//! decoding.
+//!
+//! Type descriptions are resolved like for the rosbridge server: registered
+//! types first, then types used by the bridge node, then a
+//! `z_get_type_description` query to the nodes of the domain. The schema of a
+//! channel is the `ros2msg` definition built from the description, and the
+//! bridge subscribes with the type hash of the description.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros_zenoh::{Context, foxglove::FoxgloveServer};
+//!
+//! let ctx = Context::new()?;
+//! let node = ctx.create_node("foxglove_bridge", None)?;
+//! FoxgloveServer::new(node)
+//!     .register::<sensor_msgs::msg::LaserScan>()
+//!     .serve(("0.0.0.0", oxidros_zenoh::foxglove::DEFAULT_PORT))
+//!     .await?;
+//! ```
+
+pub mod protocol;
+
+use crate::{
+    error::{Error, Result},
+    node::Node,
+    task,
+    topic::GenericSubscriber,
+    type_description::{TypeCache, dds_to_ros_type_name},
+};
+use futures_util::{SinkExt, StreamExt};
+use oxidros_core::{TypeDescription, targets, types::TypeDescriptionMsg};
+use protocol::{Channel, ClientMessage, STATUS_WARNING, SUBPROTOCOL, ServerMessage, Subscription};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc,
+    task::JoinHandle,
+};
+use tokio_tungstenite::tungstenite::{
+    Message as WsMessage,
+    handshake::server::{ErrorResponse, Request, Response},
+    http::{HeaderValue, header::SEC_WEBSOCKET_PROTOCOL},
+};
+
+/// Default Foxglove WebSocket port.
+pub const DEFAULT_PORT: u16 = 8765;
+
+/// Period of the graph scans advertising new topics.
+const DISCOVERY_PERIOD: Duration = Duration::from_secs(1);
+
+/// Delay before accepting again after an accept error, e.g. when the process
+/// runs out of file descriptors.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Foxglove WebSocket server.
+///
+/// Each connection gets its own channels and subscriptions, created on the
+/// bridge node and dropped when the connection closes.
+pub struct FoxgloveServer {
+    shared: Arc<Shared>,
+}
+
+/// State shared by all connections.
+struct Shared {
+    node: Arc<Node>,
+    types: TypeCache,
+}
+
+impl FoxgloveServer {
+    /// Create a server exposing the graph seen by `node`.
+    pub fn new(node: Arc<Node>) -> Self {
+        FoxgloveServer {
+            shared: Arc::new(Shared {
+                node,
+                types: TypeCache::default(),
+            }),
+        }
+    }
+
+    /// Make a type available to clients without querying the graph.
+    pub fn register<T: TypeDescription>(self) -> Self {
+        self.shared.types.insert(T::type_description());
+        self
+    }
+
+    /// Listen on `addr` and serve connections.
+    pub async fn serve(self, addr: impl ToSocketAddrs) -> Result<()> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(|e| Error::Other(format!("Failed to bind Foxglove server: {e}")))?;
+        self.serve_listener(listener).await
+    }
+
+    /// Serve connections accepted on `listener`.
+    ///
+    /// Accept errors are logged and accepting is retried after a short delay.
+    pub async fn serve_listener(self, listener: TcpListener) -> Result<()> {
+        tracing::info!(
+            target: targets::ZENOH,
+            addr = ?listener.local_addr().ok(),
+            "Foxglove server listening"
+        );
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    tracing::warn!(target: targets::ZENOH, error = %e, "Failed to accept a Foxglove connection");
+                    tokio::time::sleep(ACCEPT_BACKOFF).await;
+                    continue;
+                }
+            };
+            let shared = self.shared.clone();
+            task::spawn_named(&format!("foxglove-{peer}"), async move {
+                tracing::debug!(target: targets::ZENOH, %peer, "Foxglove client connected");
+                if let Err(e) = Connection::run(shared, stream).await {
+                    tracing::debug!(target: targets::ZENOH, %peer, error = %e, "Foxglove connection failed");
+                }
+                tracing::debug!(target: targets::ZENOH, %peer, "Foxglove client disconnected");
+            });
+        }
+    }
+}
+
+/// Advertised channel.
+struct ChannelState {
+    topic: String,
+    desc: TypeDescriptionMsg,
+}
+
+/// Per-connection state.
+struct Connection {
+    shared: Arc<Shared>,
+    frames: mpsc::UnboundedSender<WsMessage>,
+    channels: HashMap<u32, ChannelState>,
+    next_channel_id: u32,
+    /// Topics whose type could not be resolved, not retried.
+    unresolved: HashSet<String>,
+    /// Subscription tasks with their channel.
+    subscriptions: HashMap<u32, (u32, JoinHandle<()>)>,
+}
+
+impl Connection {
+    async fn run(shared: Arc<Shared>, stream: TcpStream) -> Result<()> {
+        let ws = tokio_tungstenite::accept_hdr_async(stream, select_subprotocol)
+            .await
+            .map_err(|e| Error::Other(format!("WebSocket handshake failed: {e}")))?;
+        let (mut sink, mut source) = ws.split();
+
+        // Frames are produced by subscription tasks, a single writer task
+        // sends them in order.
+        let (frames, mut rx) = mpsc::unbounded_channel::<WsMessage>();
+        let writer = task::spawn_named("foxglove-writer", async move {
+            while let Some(frame) = rx.recv().await {
+                if sink.send(frame).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut conn = Connection {
+            shared,
+            frames,
+            channels: HashMap::new(),
+            next_channel_id: 1,
+            unresolved: HashSet::new(),
+            subscriptions: HashMap::new(),
+        };
+        conn.send(&ServerMessage::ServerInfo {
+            name: conn.shared.node.z_fully_qualified_name()?,
+            capabilities: Vec::new(),
+            supported_encodings: vec!["cdr".into()],
+            metadata: HashMap::new(),
+        });
+
+        let mut discovery = tokio::time::interval(DISCOVERY_PERIOD);
+        loop {
+            tokio::select! {
+                frame = source.next() => {
+                    let text = match frame {
+                        Some(Ok(WsMessage::Text(text))) => text,
+                        Some(Ok(WsMessage::Close(_)) | Err(_)) | None => break,
+                        Some(Ok(_)) => continue,
+                    };
+                    match serde_json::from_str::<ClientMessage>(&text) {
+                        Ok(msg) => conn.handle(msg),
+                        Err(e) => conn.send(&ServerMessage::status(
+                            STATUS_WARNING,
+                            format!("Unsupported message: {e}"),
+                        )),
+                    }
+                }
+                _ = discovery.tick() => conn.discover().await,
+            }
+        }
+
+        for (_, (_, task)) in conn.subscriptions.drain() {
+            task.abort();
+        }
+        writer.abort();
+        Ok(())
+    }
+
+    fn send(&self, msg: &ServerMessage) {
+        if let Ok(text) = serde_json::to_string(msg) {
+            let _ = self.frames.send(WsMessage::Text(text.into()));
+        }
+    }
+
+    /// Advertise new topics of the graph and unadvertise removed ones.
+    async fn discover(&mut self) {
+        let topics = self
+            .shared
+            .node
+            .context()
+            .graph_cache()
+            .get_topic_names_and_types();
+
+        let removed: Vec<u32> = self
+            .channels
+            .iter()
+            .filter(|(_, ch)| !topics.iter().any(|(topic, _)| *topic == ch.topic))
+            .map(|(id, _)| *id)
+            .collect();
+        if !removed.is_empty() {
+            for id in &removed {
+                self.channels.remove(id);
+            }
+            self.subscriptions.retain(|_, (channel_id, task)| {
+                let keep = !removed.contains(channel_id);
+                if !keep {
+                    task.abort();
+                }
+                keep
+            });
+            self.send(&ServerMessage::Unadvertise {
+                channel_ids: removed,
+            });
+        }
+
+        let mut added = Vec::new();
+        for (topic, dds_type_name) in topics {
+            if self.unresolved.contains(&topic)
+                || self.channels.values().any(|ch| ch.topic == topic)
+            {
+                continue;
+            }
+            let desc = match dds_to_ros_type_name(&dds_type_name) {
+                Some(type_name) => {
+                    self.shared
+                        .types
+                        .resolve(&self.shared.node, &type_name)
+                        .await
+                }
+                None => Err(Error::Other(format!("Invalid type '{dds_type_name}'"))),
+            };
+            let desc = match desc {
+                Ok(desc) => desc,
+                Err(e) => {
+                    tracing::debug!(target: targets::ZENOH, %topic, error = %e, "Topic not advertised");
+                    self.unresolved.insert(topic);
+                    continue;
+                }
+            };
+            let id = self.next_channel_id;
+            self.next_channel_id += 1;
+            added.push(Channel {
+                id,
+                topic: topic.clone(),
+                encoding: "cdr",
+                schema_name: desc.type_description.type_name.clone(),
+                schema: desc.to_msg_definition(),
+                schema_encoding: "ros2msg",
+            });
+            self.channels.insert(id, ChannelState { topic, desc });
+        }
+        if !added.is_empty() {
+            self.send(&ServerMessage::Advertise { channels: added });
+        }
+    }
+
+    fn handle(&mut self, msg: ClientMessage) {
+        match msg {
+            ClientMessage::Subscribe { subscriptions } => {
+                for subscription in subscriptions {
+                    if let Err(e) = self.subscribe(subscription) {
+                        self.send(&ServerMessage::status(STATUS_WARNING, e));
+                    }
+                }
+            }
+            ClientMessage::Unsubscribe { subscription_ids } => {
+                for id in subscription_ids {
+                    if let Some((_, task)) = self.subscriptions.remove(&id) {
+                        task.abort();
+                    }
+                }
+            }
+        }
+    }
+
+    fn subscribe(&mut self, subscription: Subscription) -> Result<()> {
+        let channel = self
+            .channels
+            .get(&subscription.channel_id)
+            .ok_or_else(|| Error::Other(format!("Unknown channel {}", subscription.channel_id)))?;
+        let subscriber = self.shared.node.create_generic_subscriber(
+            &channel.topic,
+            channel.desc.clone(),
+            None,
+        )?;
+        let task = task::spawn_named(
+            &channel.topic,
+            forward(subscriber, subscription.id, self.frames.clone()),
+        );
+        if let Some((_, previous)) = self
+            .subscriptions
+            .insert(subscription.id, (subscription.channel_id, task))
+        {
+            previous.abort();
+        }
+        Ok(())
+    }
+}
+
+/// Accept the Foxglove subprotocol during the WebSocket handshake.
+// The signature is the handshake callback of tungstenite
+#[allow(clippy::result_large_err)]
+fn select_subprotocol(
+    request: &Request,
+    mut response: Response,
+) -> std::result::Result<Response, ErrorResponse> {
+    let offered = request
+        .headers()
+        .get_all(SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|protocol| protocol.trim() == SUBPROTOCOL);
+    if offered {
+        response.headers_mut().insert(
+            SEC_WEBSOCKET_PROTOCOL,
+            HeaderValue::from_static(SUBPROTOCOL),
+        );
+    }
+    Ok(response)
+}
+
+/// Forward the CDR payloads of a subscription to the client.
+async fn forward(
+    mut subscriber: GenericSubscriber,
+    subscription_id: u32,
+    frames: mpsc::UnboundedSender<WsMessage>,
+) {
+    loop {
+        let payload = match subscriber.recv_raw().await {
+            Ok((payload, _info)) => payload,
+            Err(Error::ChannelClosed) => break,
+            Err(_) => continue,
+        };
+        let timestamp_ns = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        let frame = protocol::message_data(subscription_id, timestamp_ns, &payload);
+        if frames.send(WsMessage::Binary(frame.into())).is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Foxglove WebSocket protocol messages.
+//!
+//! The server advertises one channel per topic, with the `ros2msg` definition
+//! of its type as schema, and streams the CDR payloads of the subscribed
+//! channels in binary frames.
+//!
+//! # Reference
+//!
+//! See the [Foxglove WebSocket protocol](https://github.com/foxglove/ws-protocol/blob/main/docs/spec.md)
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// WebSocket subprotocol negotiated with clients.
+pub const SUBPROTOCOL: &str = "foxglove.websocket.v1";
+
+/// Opcode of binary message data frames.
+pub const MESSAGE_DATA: u8 = 0x01;
+
+/// Status level of informational messages.
+pub const STATUS_INFO: u8 = 0;
+/// Status level of warnings.
+pub const STATUS_WARNING: u8 = 1;
+/// Status level of errors.
+pub const STATUS_ERROR: u8 = 2;
+
+/// Channel advertised to clients.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Channel {
+    /// Channel id, unique per connection.
+    pub id: u32,
+    /// Topic name.
+    pub topic: String,
+    /// Message encoding, always `"cdr"`.
+    pub encoding: &'static str,
+    /// ROS2 type name.
+    pub schema_name: String,
+    /// Message definition of the type.
+    pub schema: String,
+    /// Schema encoding, always `"ros2msg"`.
+    pub schema_encoding: &'static str,
+}
+
+/// JSON message sent by the server.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ServerMessage {
+    /// First message of a connection.
+    ServerInfo {
+        /// Server name.
+        name: String,
+        /// Optional features supported by the server.
+        capabilities: Vec<String>,
+        /// Encodings of the messages.
+        supported_encodings: Vec<String>,
+        /// Free-form server metadata.
+        metadata: HashMap<String, String>,
+    },
+    /// New channels.
+    Advertise {
+        /// Advertised channels.
+        channels: Vec<Channel>,
+    },
+    /// Removed channels.
+    Unadvertise {
+        /// Ids of the removed channels.
+        channel_ids: Vec<u32>,
+    },
+    /// Information, warning or error.
+    Status {
+        /// One of [`STATUS_INFO`], [`STATUS_WARNING`] or [`STATUS_ERROR`].
+        level: u8,
+        /// Description.
+        message: String,
+    },
+}
+
+impl ServerMessage {
+    /// Build a status message.
+    pub fn status(level: u8, message: impl std::fmt::Display) -> Self {
+        ServerMessage::Status {
+            level,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Subscription of a client to a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subscription {
+    /// Subscription id chosen by the client.
+    pub id: u32,
+    /// Subscribed channel.
+    pub channel_id: u32,
+}
+
+/// JSON message sent by a client.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ClientMessage {
+    /// Subscribe to channels.
+    Subscribe {
+        /// New subscriptions.
+        subscriptions: Vec<Subscription>,
+    },
+    /// Cancel subscriptions.
+    Unsubscribe {
+        /// Ids of the cancelled subscriptions.
+        subscription_ids: Vec<u32>,
+    },
+}
+
+/// Encode a message data frame.
+///
+/// Layout: opcode, subscription id (u32 LE), receive timestamp in nanoseconds
+/// (u64 LE), then the CDR payload including its encapsulation header.
+pub fn message_data(subscription_id: u32, timestamp_ns: u64, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(1 + 4 + 8 + payload.len());
+    frame.push(MESSAGE_DATA);
+    frame.extend_from_slice(&subscription_id.to_le_bytes());
+    frame.extend_from_slice(&timestamp_ns.to_le_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_server_messages() {
+        let advertise = ServerMessage::Advertise {
+            channels: vec![Channel {
+                id: 1,
+                topic: "/chatter".into(),
+                encoding: "cdr",
+                schema_name: "std_msgs/msg/String".into(),
+                schema: "string data\n".into(),
+                schema_encoding: "ros2msg",
+            }],
+        };
+        assert_eq!(
+            serde_json::to_value(&advertise).unwrap(),
+            json!({
+                "op": "advertise",
+                "channels": [{
+                    "id": 1,
+                    "topic": "/chatter",
+                    "encoding": "cdr",
+                    "schemaName": "std_msgs/msg/String",
+                    "schema": "string data\n",
+                    "schemaEncoding": "ros2msg"
+                }]
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(ServerMessage::Unadvertise {
+                channel_ids: vec![1]
+            })
+            .unwrap(),
+            json!({"op": "unadvertise", "channelIds": [1]})
+        );
+        assert_eq!(
+            serde_json::to_value(ServerMessage::status(STATUS_ERROR, "unknown channel")).unwrap(),
+            json!({"op": "status", "level": 2, "message": "unknown channel"})
+        );
+    }
+
+    #[test]
+    fn test_client_messages() {
+        let msg: ClientMessage = serde_json::from_value(json!({
+            "op": "subscribe",
+            "subscriptions": [{"id": 0, "channelId": 3}]
+        }))
+        .unwrap();
+        assert_eq!(
+            msg,
+            ClientMessage::Subscribe {
+                subscriptions: vec![Subscription {
+                    id: 0,
+                    channel_id: 3
+                }]
+            }
+        );
+        let msg: ClientMessage = serde_json::from_value(json!({
+            "op": "unsubscribe",
+            "subscriptionIds": [0]
+        }))
+        .unwrap();
+        assert_eq!(
+            msg,
+            ClientMessage::Unsubscribe {
+                subscription_ids: vec![0]
+            }
+        );
+        assert!(serde_json::from_value::<ClientMessage>(json!({"op": "getParameters"})).is_err());
+    }
+
+    #[test]
+    fn test_message_data() {
+        let frame = message_data(7, 42, &[0, 1, 0, 0, 5]);
+        assert_eq!(frame[0], MESSAGE_DATA);
+        assert_eq!(&frame[1..5], &7u32.to_le_bytes());
+        assert_eq!(&frame[5..13], &42u64.to_le_bytes());
+        assert_eq!(&frame[13..], &[0, 1, 0, 0, 5]);
+    }
+}
@@ -0,0 +1,170 @@
+//! Graph of ROS2 as seen by a node.
+//!
+//! The graph reports the nodes, publishers and subscribers found in the graph
+//! cache, and lets tasks wait for changes notified by the liveliness
+//! subscriber of the context instead of polling.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use futures_util::StreamExt;
+//! use oxidros_zenoh::{Context, GraphEvent};
+//! use std::time::Duration;
+//!
+//! let ctx = Context::new()?;
+//! let node = ctx.create_node("graph_rs", None)?;
+//! let graph = node.graph();
+//!
+//! // Wait until `/talker` joins the graph.
+//! graph.wait_for_node("/talker", Duration::from_secs(5)).await?;
+//!
+//! // Watch the graph.
+//! let mut events = graph.events()?;
+//! while let Some(event) = events.next().await {
+//!     if let GraphEvent::NodeRemoved(name) = event? {
+//!         println!("{name} left");
+//!     }
+//! }
+//! ```
+
+use crate::{
+    context::Context,
+    error::{Error, Result},
+};
+use futures_core::Stream;
+use std::{
+    collections::{BTreeSet, VecDeque},
+    pin::Pin,
+    sync::Arc,
+    task::{Context as TaskContext, Poll},
+    time::{Duration, Instant},
+};
+
+pub use oxidros_core::graph::{GraphEvent, GraphSnapshot};
+
+/// Graph of ROS2 as seen by a node.
+#[derive(Clone)]
+pub struct Graph {
+    context: Arc<Context>,
+}
+
+impl Graph {
+    pub(crate) fn new(context: Arc<Context>) -> Self {
+        Graph { context }
+    }
+
+    /// Get the fully qualified names of the nodes in the graph.
+    pub fn node_names(&self) -> Result<Vec<String>> {
+        Ok(self.context.graph_cache().get_node_names())
+    }
+
+    /// Take a snapshot of the nodes, publishers and subscribers in the graph.
+    pub fn snapshot(&self) -> Result<GraphSnapshot> {
+        Ok(self.context.graph_snapshot())
+    }
+
+    /// Get the DDS type names and type hashes announced by the publishers of
+    /// the fully qualified topic `topic`, without duplicates.
+    ///
+    /// Several entries for one type name mean that the publishers disagree on
+    /// the definition of the type, e.g. because they were built against
+    /// different ROS 2 distributions.
+    pub fn topic_types(&self, topic: &str) -> Result<Vec<(String, String)>> {
+        let cache = self.context.graph_cache();
+        let types: BTreeSet<_> = cache
+            .get_publishers_info(topic)
+            .into_iter()
+            .filter_map(|info| Some((info.type_name.clone()?, info.type_hash.clone()?)))
+            .collect();
+        Ok(types.into_iter().collect())
+    }
+
+    /// Wait until the graph changes or `timeout` elapses.
+    ///
+    /// Spurious wakeups are possible, so the graph must be checked again
+    /// after this returns.
+    pub async fn wait_for_change(&self, timeout: Option<Duration>) -> Result<()> {
+        let listener = self.context.graph_listener();
+        wait(&listener, timeout).await
+    }
+
+    /// Wait until a node with the fully qualified name `name` is in the graph.
+    ///
+    /// A name without a leading `/` is looked up in the root namespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if the node does not appear within `timeout`.
+    pub async fn wait_for_node(&self, name: &str, timeout: Duration) -> Result<()> {
+        // Listen before checking so that no change is missed in between
+        let listener = self.context.graph_listener();
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.snapshot()?.contains_node(name) {
+                return Ok(());
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::Timeout);
+            }
+            wait(&listener, Some(deadline - now)).await?;
+        }
+    }
+
+    /// Stream of the changes of the graph from now on.
+    pub fn events(&self) -> Result<GraphEvents> {
+        let listener = self.context.graph_listener();
+        Ok(GraphEvents {
+            context: self.context.clone(),
+            listener: listener.into_stream(),
+            last: self.snapshot()?,
+            pending: VecDeque::new(),
+        })
+    }
+}
+
+/// Wait for a notification of `listener`, or until `timeout` elapses.
+pub(crate) async fn wait(listener: &flume::Receiver<()>, timeout: Option<Duration>) -> Result<()> {
+    let changed = match timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, listener.recv_async()).await {
+            Ok(changed) => changed,
+            Err(_) => return Ok(()),
+        },
+        None => listener.recv_async().await,
+    };
+    changed.map_err(|_| Error::ChannelClosed)
+}
+
+/// Stream of graph changes returned by `Graph::events`.
+///
+/// Changes are found by comparing snapshots of the graph, so an entity that
+/// appears and disappears between two notifications is not reported.
+pub struct GraphEvents {
+    context: Arc<Context>,
+    listener: flume::r#async::RecvStream<'static, ()>,
+    last: GraphSnapshot,
+    pending: VecDeque<GraphEvent>,
+}
+
+impl Stream for GraphEvents {
+    type Item = Result<GraphEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(event) = this.pending.pop_front() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+
+            match Pin::new(&mut this.listener).poll_next(cx) {
+                Poll::Ready(Some(())) => {
+                    let snapshot = this.context.graph_snapshot();
+                    this.pending.extend(this.last.diff(&snapshot));
+                    this.last = snapshot;
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
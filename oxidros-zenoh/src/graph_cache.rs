@@ -7,8 +7,10 @@
 //!
 //! See [rmw_zenoh design - Graph Cache](https://github.com/ros2/rmw_zenoh/blob/rolling/docs/design.md#graph-cache)
 
-use crate::keyexpr::{EntityKind, LIVELINESS_PREFIX, unmangle_name};
-use std::collections::HashMap;
+use crate::keyexpr::{EntityKind, LIVELINESS_PREFIX, keyexpr_to_qos, unmangle_name};
+use oxidros_core::Profile;
+use oxidros_core::graph::GraphSnapshot;
+use std::collections::{HashMap, HashSet};
 use zenoh::sample::SampleKind;
 
 /// Information about a discovered entity.
@@ -36,6 +38,8 @@ pub struct EntityInfo {
     pub type_name: Option<String>,
     /// Type hash (empty for nodes)
     pub type_hash: Option<String>,
+    /// Offered/requested QoS profile (empty for nodes)
+    pub qos: Option<Profile>,
 }
 
 /// Graph cache storing discovered entities.
@@ -111,6 +115,10 @@ impl GraphCache {
         } else {
             (None, None, None)
         };
+        let qos = match parts.get(12) {
+            Some(qos) if kind != EntityKind::Node => keyexpr_to_qos(qos),
+            _ => None,
+        };
 
         Some(EntityInfo {
             domain_id,
@@ -124,6 +132,7 @@ impl GraphCache {
             topic_name,
             type_name,
             type_hash,
+            qos,
         })
     }
 
@@ -142,6 +151,16 @@ impl GraphCache {
             .collect()
     }
 
+    /// Count the sessions other than `own_session_id` with a live node.
+    pub fn count_peers(&self, own_session_id: &str) -> usize {
+        self.entities
+            .values()
+            .filter(|e| e.kind == EntityKind::Node && e.session_id != own_session_id)
+            .map(|e| e.session_id.as_str())
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
     /// Count publishers for a topic.
     pub fn count_publishers(&self, topic: &str) -> usize {
         self.entities
@@ -221,11 +240,31 @@ impl GraphCache {
     pub fn get_all_entities(&self) -> Vec<&EntityInfo> {
         self.entities.values().collect()
     }
+
+    /// Take a snapshot of the nodes, publishers and subscribers.
+    pub fn snapshot(&self) -> GraphSnapshot {
+        let mut snapshot = GraphSnapshot {
+            nodes: self.get_node_names().into_iter().collect(),
+            ..Default::default()
+        };
+        for e in self.entities.values() {
+            let counts = match e.kind {
+                EntityKind::Publisher => &mut snapshot.publishers,
+                EntityKind::Subscriber => &mut snapshot.subscribers,
+                _ => continue,
+            };
+            if let Some(topic) = &e.topic_name {
+                *counts.entry(topic.clone()).or_insert(0) += 1;
+            }
+        }
+        snapshot
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use oxidros_core::graph::GraphEvent;
 
     // =========================================================================
     // Parse Token Tests - Nodes
@@ -327,6 +366,7 @@ mod tests {
                     .to_string()
             )
         );
+        assert_eq!(info.qos.map(|qos| qos.depth), Some(7));
     }
 
     // =========================================================================
@@ -459,6 +499,26 @@ mod tests {
         assert_eq!(cache.get_node_names().len(), 0);
     }
 
+    #[test]
+    fn test_count_peers() {
+        let mut cache = GraphCache::new();
+        cache.handle_liveliness_token("@ros2_lv/0/own/0/0/NN/%/%/me", SampleKind::Put);
+        cache.handle_liveliness_token("@ros2_lv/0/sess1/0/0/NN/%/%/node1", SampleKind::Put);
+        cache.handle_liveliness_token("@ros2_lv/0/sess1/1/1/NN/%/%/node2", SampleKind::Put);
+        assert_eq!(cache.count_peers("own"), 1);
+
+        // A session is only a peer once it has a node
+        let publisher = "@ros2_lv/0/sess2/0/10/MP/%/%/node3/%chatter/std_msgs::msg::dds_::String_/RIHS01_abc/qos";
+        cache.handle_liveliness_token(publisher, SampleKind::Put);
+        assert_eq!(cache.count_peers("own"), 1);
+        cache.handle_liveliness_token("@ros2_lv/0/sess2/0/0/NN/%/%/node3", SampleKind::Put);
+        assert_eq!(cache.count_peers("own"), 2);
+
+        cache.handle_liveliness_token("@ros2_lv/0/sess1/0/0/NN/%/%/node1", SampleKind::Delete);
+        cache.handle_liveliness_token("@ros2_lv/0/sess1/1/1/NN/%/%/node2", SampleKind::Delete);
+        assert_eq!(cache.count_peers("own"), 1);
+    }
+
     #[test]
     fn test_count_publishers() {
         let mut cache = GraphCache::new();
@@ -534,4 +594,27 @@ mod tests {
         assert!(names.contains(&"/robot1/node2".to_string()));
         assert!(names.contains(&"/robot1/arm/node3".to_string()));
     }
+
+    #[test]
+    fn test_snapshot() {
+        let mut cache = GraphCache::new();
+        let node = "@ros2_lv/0/sess/0/0/NN/%/%robot1/talker";
+        let publisher = "@ros2_lv/0/sess/0/10/MP/%/%robot1/talker/%chatter/std_msgs::msg::dds_::String_/RIHS01_abc/qos";
+        let subscriber = "@ros2_lv/0/sess/0/11/MS/%/%robot1/talker/%cmd/std_msgs::msg::dds_::String_/RIHS01_abc/qos";
+
+        cache.handle_liveliness_token(node, SampleKind::Put);
+        cache.handle_liveliness_token(publisher, SampleKind::Put);
+        cache.handle_liveliness_token(subscriber, SampleKind::Put);
+
+        let before = cache.snapshot();
+        assert!(before.contains_node("/robot1/talker"));
+        assert_eq!(before.count_publishers("/chatter"), 1);
+        assert_eq!(before.count_subscribers("/cmd"), 1);
+
+        cache.handle_liveliness_token(publisher, SampleKind::Delete);
+        assert_eq!(
+            before.diff(&cache.snapshot()),
+            vec![GraphEvent::PublisherRemoved("/chatter".to_string())]
+        );
+    }
 }
@@ -100,6 +100,13 @@ pub fn liveliness_node_keyexpr(
     )
 }
 
+/// Build the key expression matching all liveliness tokens of a domain.
+///
+/// Format: `@ros2_lv/<domain_id>/**`
+pub fn liveliness_domain_keyexpr(domain_id: u32) -> String {
+    format!("{LIVELINESS_PREFIX}/{domain_id}/**")
+}
+
 /// Build a liveliness token key expression for an entity (publisher, subscriber, service, client).
 ///
 /// Format: `@ros2_lv/<domain_id>/<session_id>/<node_id>/<entity_id>/<entity_kind>/<mangled_enclave>/<mangled_namespace>/<node_name>/<mangled_qualified_name>/<type_name>/<type_hash>/<qos>`
@@ -185,6 +192,15 @@ pub fn unmangle_name(mangled: &str) -> String {
     }
 }
 
+// Default QoS values as defined in rmw_zenoh_cpp/src/detail/qos.cpp
+const DEFAULT_RELIABILITY: u8 = 1; // RMW_QOS_POLICY_RELIABILITY_RELIABLE
+const DEFAULT_DURABILITY: u8 = 2; // RMW_QOS_POLICY_DURABILITY_VOLATILE
+const DEFAULT_HISTORY: u8 = 1; // RMW_QOS_POLICY_HISTORY_KEEP_LAST
+const DEFAULT_DEPTH: usize = 42;
+const DEFAULT_LIVELINESS: u8 = 1; // RMW_QOS_POLICY_LIVELINESS_AUTOMATIC
+// Default deadline, lifespan, liveliness_lease_duration are all "infinite"
+// which is represented as max u64 values. We treat Duration::ZERO as unset/default.
+
 /// Encode QoS profile to a compact key expression string.
 ///
 /// Format: `<Reliability>:<Durability>:<History>,<Depth>:<DeadlineSec>,<DeadlineNSec>:<LifespanSec>,<LifespanNSec>:<Liveliness>,<LivelinessSec>,<LivelinessNSec>`
@@ -210,15 +226,6 @@ pub fn unmangle_name(mangled: &str) -> String {
 pub fn qos_to_keyexpr(qos: &Profile) -> String {
     use oxidros_core::qos::{DurabilityPolicy, HistoryPolicy, LivelinessPolicy, ReliabilityPolicy};
 
-    // Default QoS values as defined in rmw_zenoh_cpp/src/detail/qos.cpp
-    const DEFAULT_RELIABILITY: u8 = 1; // RMW_QOS_POLICY_RELIABILITY_RELIABLE
-    const DEFAULT_DURABILITY: u8 = 2; // RMW_QOS_POLICY_DURABILITY_VOLATILE
-    const DEFAULT_HISTORY: u8 = 1; // RMW_QOS_POLICY_HISTORY_KEEP_LAST
-    const DEFAULT_DEPTH: usize = 42;
-    const DEFAULT_LIVELINESS: u8 = 1; // RMW_QOS_POLICY_LIVELINESS_AUTOMATIC
-    // Default deadline, lifespan, liveliness_lease_duration are all "infinite"
-    // which is represented as max u64 values. We treat Duration::ZERO as unset/default.
-
     let mut keyexpr = String::new();
 
     // Reliability (enum values: 0=SystemDefault, 1=Reliable, 2=BestEffort, 3=Unknown, 4=BestAvailable)
@@ -310,6 +317,79 @@ pub fn qos_to_keyexpr(qos: &Profile) -> String {
     keyexpr
 }
 
+/// Decode a QoS profile from its key expression string.
+///
+/// This is the inverse of [`qos_to_keyexpr`]: empty values are the RMW
+/// defaults. Returns `None` if the string is malformed.
+pub fn keyexpr_to_qos(keyexpr: &str) -> Option<Profile> {
+    use oxidros_core::qos::{DurabilityPolicy, HistoryPolicy, LivelinessPolicy, ReliabilityPolicy};
+    use std::time::Duration;
+
+    fn value<T: std::str::FromStr>(s: &str, default: T) -> Option<T> {
+        if s.is_empty() {
+            Some(default)
+        } else {
+            s.parse().ok()
+        }
+    }
+    fn duration(sec: &str, nsec: &str) -> Option<Duration> {
+        Some(Duration::new(value(sec, 0)?, value(nsec, 0)?))
+    }
+
+    let parts: Vec<&str> = keyexpr.split(':').collect();
+    let [
+        reliability,
+        durability,
+        history,
+        deadline,
+        lifespan,
+        liveliness,
+    ] = parts[..]
+    else {
+        return None;
+    };
+    let (history, depth) = history.split_once(',')?;
+    let (deadline_sec, deadline_nsec) = deadline.split_once(',')?;
+    let (lifespan_sec, lifespan_nsec) = lifespan.split_once(',')?;
+    let (liveliness, lease) = liveliness.split_once(',')?;
+    let (lease_sec, lease_nsec) = lease.split_once(',')?;
+
+    Some(Profile {
+        reliability: match value(reliability, DEFAULT_RELIABILITY)? {
+            0 => ReliabilityPolicy::SystemDefault,
+            1 => ReliabilityPolicy::Reliable,
+            2 => ReliabilityPolicy::BestEffort,
+            4 => ReliabilityPolicy::BestAvailable,
+            _ => ReliabilityPolicy::Unknown,
+        },
+        durability: match value(durability, DEFAULT_DURABILITY)? {
+            0 => DurabilityPolicy::SystemDefault,
+            1 => DurabilityPolicy::TransientLocal,
+            2 => DurabilityPolicy::Volatile,
+            4 => DurabilityPolicy::BestAvailable,
+            _ => DurabilityPolicy::Unknown,
+        },
+        history: match value(history, DEFAULT_HISTORY)? {
+            0 => HistoryPolicy::SystemDefault,
+            1 => HistoryPolicy::KeepLast,
+            2 => HistoryPolicy::KeepAll,
+            _ => HistoryPolicy::Unknown,
+        },
+        depth: value(depth, DEFAULT_DEPTH)?,
+        deadline: duration(deadline_sec, deadline_nsec)?,
+        lifespan: duration(lifespan_sec, lifespan_nsec)?,
+        liveliness: match value(liveliness, DEFAULT_LIVELINESS)? {
+            0 => LivelinessPolicy::SystemDefault,
+            1 => LivelinessPolicy::Automatic,
+            2 => LivelinessPolicy::ManualByTopic,
+            4 => LivelinessPolicy::BestAvailable,
+            _ => LivelinessPolicy::Unknown,
+        },
+        liveliness_lease_duration: duration(lease_sec, lease_nsec)?,
+        ..Default::default()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -465,6 +545,16 @@ mod tests {
     // Liveliness Token Tests - Publishers/Subscribers
     // =========================================================================
 
+    #[test]
+    fn test_liveliness_domain_keyexpr() {
+        assert_eq!(liveliness_domain_keyexpr(0), "@ros2_lv/0/**");
+        assert_eq!(liveliness_domain_keyexpr(42), "@ros2_lv/42/**");
+
+        let token = liveliness_node_keyexpr(42, "abc", 0, "", "", "talker");
+        assert!(token.starts_with("@ros2_lv/42/"));
+        assert!(!token.starts_with("@ros2_lv/4/"));
+    }
+
     #[test]
     fn test_liveliness_subscriber_chatter() {
         // Example from design doc:
@@ -711,6 +801,46 @@ mod tests {
         assert_eq!(keyexpr, "::,:5,123456789:,:,,");
     }
 
+    #[test]
+    fn test_keyexpr_to_qos_roundtrip() {
+        use oxidros_core::qos::LivelinessPolicy;
+        use std::time::Duration;
+        let qos = Profile {
+            reliability: ReliabilityPolicy::BestEffort,
+            durability: DurabilityPolicy::TransientLocal,
+            history: HistoryPolicy::KeepLast,
+            depth: 5,
+            deadline: Duration::new(5, 123456789),
+            liveliness: LivelinessPolicy::ManualByTopic,
+            liveliness_lease_duration: Duration::from_secs(2),
+            ..Default::default()
+        };
+        let decoded = keyexpr_to_qos(&qos_to_keyexpr(&qos)).unwrap();
+        assert_eq!(decoded.reliability, qos.reliability);
+        assert_eq!(decoded.durability, qos.durability);
+        assert_eq!(decoded.history, qos.history);
+        assert_eq!(decoded.depth, 5);
+        assert_eq!(decoded.deadline, qos.deadline);
+        assert!(decoded.lifespan.is_zero());
+        assert_eq!(decoded.liveliness, qos.liveliness);
+        assert_eq!(
+            decoded.liveliness_lease_duration,
+            qos.liveliness_lease_duration
+        );
+    }
+
+    #[test]
+    fn test_keyexpr_to_qos_defaults() {
+        let qos = keyexpr_to_qos("::,:,:,:,,").unwrap();
+        assert_eq!(qos.reliability, ReliabilityPolicy::Reliable);
+        assert_eq!(qos.durability, DurabilityPolicy::Volatile);
+        assert_eq!(qos.history, HistoryPolicy::KeepLast);
+        assert_eq!(qos.depth, 42);
+
+        assert!(keyexpr_to_qos("").is_none());
+        assert!(keyexpr_to_qos("x::,:,:,:,,").is_none());
+    }
+
     // =========================================================================
     // Integration Tests - Full Key Expression Parsing
     // =========================================================================
@@ -13,6 +13,12 @@
 //! - [`Publisher`]/[`Subscriber`] use Zenoh pub/sub
 //! - [`Client`]/[`Server`] use Zenoh queryables
 //! - Graph discovery via Zenoh liveliness tokens
+//! - Transport events (lost sessions, congestion, drops) are published as
+//!   diagnostics by [`TransportDiagnostics`]
+//! - Web clients can connect through the rosbridge server (`rosbridge` feature)
+//!   or, for Foxglove Studio, the Foxglove WebSocket server (`foxglove` feature)
+//! - Microcontrollers can join the graph over serial or UDP through the
+//!   micro-agent (`agent` feature)
 //!
 //! # Example
 //!
@@ -35,35 +41,56 @@
 mod attachment;
 mod context;
 mod error;
+mod graph;
 mod graph_cache;
 mod keyexpr;
 mod node;
 mod qos;
 mod selector;
+#[cfg(any(feature = "agent", feature = "foxglove", feature = "rosbridge"))]
+mod task;
+mod time_source;
+mod trace_context;
 mod type_description;
 
 pub mod clock;
 pub mod logger;
 pub mod service;
 pub mod topic;
+pub mod transport;
 
+#[cfg(feature = "agent")]
+pub mod agent;
+#[cfg(feature = "foxglove")]
+pub mod foxglove;
 pub mod parameter;
+#[cfg(feature = "rosbridge")]
+pub mod rosbridge;
 
 // Re-exports
-pub use attachment::{Attachment, generate_gid};
-pub use context::Context;
+pub use attachment::{Attachment, ChunkInfo, generate_gid};
+pub use context::{Context, DiscoveryTarget};
 pub use error::{Error, Result};
+pub use graph::{Graph, GraphEvent, GraphEvents, GraphSnapshot};
 pub use graph_cache::{EntityInfo, GraphCache};
 pub use keyexpr::EntityKind;
 pub use node::Node;
 pub use qos::QosMapping;
 pub use selector::Selector;
+#[cfg(feature = "dynamic")]
+pub use service::GenericClient;
 pub use service::ServiceRequest;
+#[cfg(feature = "dynamic")]
+pub use topic::{GenericPublisher, GenericSubscriber};
+pub use trace_context::TraceContext;
+pub use transport::{TransportDiagnostics, TransportEvent, TransportMonitor, TransportStats};
+pub use type_description::TypeDescriptionServer;
 
 // Re-export core types
 pub use oxidros_core::{
     Descriptor, DurabilityPolicy, FloatingPointRange, HistoryPolicy, IntegerRange,
-    LivelinessPolicy, Parameter, Profile, ReliabilityPolicy, TypeSupport, Value,
+    LivelinessPolicy, NodeStats, Parameter, Profile, QosCompatibility, QosOverridingOptions,
+    QosPolicyKind, ReliabilityPolicy, TypeCheckPolicy, TypeSupport, Value,
 };
 
 // Re-export error types for compatibility
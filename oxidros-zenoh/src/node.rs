@@ -5,19 +5,33 @@
 
 use crate::{
     attachment::generate_gid,
+    clock::Clock,
     context::Context,
     error::{Result, Ros2ArgsResultExt},
+    graph::Graph,
     keyexpr::{EntityKind, liveliness_node_keyexpr},
-    service::{client::Client, server::Server},
-    topic::{publisher::Publisher, subscriber::Subscriber},
+    service::{
+        client::Client,
+        server::{RequestQueueProbe, Server, ServerOptions},
+    },
+    topic::{
+        publisher::Publisher,
+        subscriber::{BufferOptions, QueueProbe, Subscriber},
+    },
     type_description::TypeRegistry,
 };
-use oxidros_core::{TypeSupport, qos::Profile, targets};
+use oxidros_core::{
+    TypeSupport,
+    parameter::Value,
+    qos::{Profile, QosEntityKind, QosOverridingOptions},
+    stats::{EntityCounters, NodeStats},
+    targets,
+};
 use parking_lot::Mutex;
 use ros2args::names::NameKind;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{
-    Arc,
+    Arc, Weak,
     atomic::{AtomicU32, Ordering},
 };
 use zenoh::Wait;
@@ -39,12 +53,20 @@ struct NodeInner {
     gid: [u8; 16],
     /// Next entity ID counter.
     next_entity_id: AtomicU32,
+    /// Number of live publishers, subscribers, servers and clients.
+    entities: Arc<EntityCounters>,
+    /// Receive buffers of the subscribers.
+    subscriber_queues: Mutex<Vec<Weak<QueueProbe>>>,
+    /// Request queues of the servers.
+    server_queues: Mutex<Vec<Weak<RequestQueueProbe>>>,
     /// Liveliness token for this node.
     _liveliness_token: Mutex<Option<LivelinessToken>>,
     /// Type descriptions registered by publishers/subscribers/services.
     type_registry: TypeRegistry,
     /// Queryable for z_get_type_description (kept alive).
     _type_desc_queryable: zenoh::query::Queryable<()>,
+    /// ROS time clock, created by [`Node::get_clock`].
+    clock: Mutex<Option<Arc<Mutex<Clock>>>>,
 }
 
 /// ROS2 Node.
@@ -172,8 +194,12 @@ impl Node {
             enclave: enclave.to_string(),
             gid,
             next_entity_id: AtomicU32::new(10), // Start at 10 to match rmw_zenoh
+            entities: EntityCounters::new(),
+            subscriber_queues: Mutex::new(Vec::new()),
+            server_queues: Mutex::new(Vec::new()),
             _liveliness_token: Mutex::new(Some(token)),
             type_registry,
+            clock: Mutex::new(None),
             _type_desc_queryable: type_desc_queryable,
         });
 
@@ -223,11 +249,30 @@ impl Node {
         &self.inner.gid
     }
 
+    /// Get the ROS time clock of this node.
+    ///
+    /// The clock is created on the first call and shared afterwards, so that
+    /// overriding its time applies to every user of the clock.
+    pub fn get_clock(&self) -> Result<Arc<Mutex<Clock>>> {
+        let mut guard = self.inner.clock.lock();
+        if let Some(clock) = guard.as_ref() {
+            return Ok(clock.clone());
+        }
+        let clock = Arc::new(Mutex::new(Clock::new()?));
+        *guard = Some(clock.clone());
+        Ok(clock)
+    }
+
     /// Get the parent context.
     pub fn context(&self) -> &Arc<Context> {
         &self.inner.context
     }
 
+    /// Get the graph of ROS2 as seen by this node.
+    pub fn graph(&self) -> Graph {
+        Graph::new(self.inner.context.clone())
+    }
+
     /// Get the node ID.
     pub fn node_id(&self) -> u32 {
         self.inner.node_id
@@ -250,6 +295,97 @@ impl Node {
         self.inner.next_entity_id.fetch_add(1, Ordering::SeqCst)
     }
 
+    /// Live entity counters of this node.
+    pub(crate) fn entities(&self) -> &Arc<EntityCounters> {
+        &self.inner.entities
+    }
+
+    /// Report the receive buffer of a subscriber in [`Node::z_stats`].
+    pub(crate) fn register_queue(&self, queue: &Arc<QueueProbe>) {
+        self.inner
+            .subscriber_queues
+            .lock()
+            .push(Arc::downgrade(queue));
+    }
+
+    /// Report the request queue of a server in [`Node::z_stats`].
+    pub(crate) fn register_request_queue(&self, queue: &Arc<RequestQueueProbe>) {
+        self.inner.server_queues.lock().push(Arc::downgrade(queue));
+    }
+
+    /// Get the number of entities of this node, the messages waiting in the
+    /// buffers of its subscribers, the messages they dropped because
+    /// their buffer was full, and the same for the requests of its servers.
+    pub fn z_stats(&self) -> NodeStats {
+        let mut stats = self.inner.entities.stats();
+        let mut queued = 0;
+        let mut dropped = 0;
+
+        let mut queues = self.inner.subscriber_queues.lock();
+        queues.retain(|queue| match queue.upgrade() {
+            Some(queue) => {
+                queued += queue.len();
+                dropped += queue.lagged();
+                true
+            }
+            None => false,
+        });
+
+        stats.queued_messages = Some(queued);
+        stats.dropped_messages = Some(dropped);
+
+        let mut pending = 0;
+        let mut shed = 0;
+        let mut queues = self.inner.server_queues.lock();
+        queues.retain(|queue| match queue.upgrade() {
+            Some(queue) => {
+                pending += queue.len();
+                shed += queue.shed();
+                true
+            }
+            None => false,
+        });
+
+        stats.pending_requests = Some(pending);
+        stats.shed_requests = Some(shed);
+        stats
+    }
+
+    /// Parameters given for this node on the command line and in parameter files.
+    ///
+    /// Node-specific rules are matched against both the original name and the
+    /// fully qualified name; the latter take precedence.
+    pub(crate) fn parameter_overrides(&self) -> Result<BTreeMap<String, Value>> {
+        let ros2_args = self.inner.context.ros2_args();
+        let fqn = self.z_fully_qualified_name()?;
+
+        let mut params = BTreeMap::new();
+        for node_name in [self.original_name(), fqn.as_str()] {
+            if let Ok(param_assignments) = ros2_args.get_params_for_node(node_name) {
+                for param in param_assignments {
+                    if let Ok(value) = Value::try_from(&param.value) {
+                        params.insert(param.name, value);
+                    }
+                }
+            }
+        }
+        Ok(params)
+    }
+
+    /// Apply the `qos_overrides` parameters of an entity on `fq_topic_name`.
+    fn override_qos(
+        &self,
+        fq_topic_name: &str,
+        entity: QosEntityKind,
+        qos: Option<Profile>,
+        options: &QosOverridingOptions,
+    ) -> Result<Profile> {
+        let overrides = self.parameter_overrides()?;
+        options.apply(fq_topic_name, entity, qos.unwrap_or_default(), |name| {
+            overrides.get(name).cloned()
+        })
+    }
+
     /// Register a type description so it can be served via `get_type_description`.
     fn register_type_description<T: oxidros_core::TypeDescription>(&self) {
         let desc = T::type_description();
@@ -257,6 +393,17 @@ impl Node {
         self.inner.type_registry.lock().entry(key).or_insert(desc);
     }
 
+    /// Register a runtime type description so it can be served via `get_type_description`.
+    #[cfg(feature = "dynamic")]
+    fn register_dynamic_type_description(&self, desc: &oxidros_core::types::TypeDescriptionMsg) {
+        let key = desc.type_description.type_name.clone();
+        self.inner
+            .type_registry
+            .lock()
+            .entry(key)
+            .or_insert_with(|| desc.clone());
+    }
+
     /// Expand a topic/service name to its fully qualified form and apply remapping rules.
     ///
     /// This function:
@@ -389,6 +536,41 @@ impl Node {
         )
     }
 
+    /// Create a publisher whose QoS can be overridden by parameters.
+    ///
+    /// The policies allowed by `options` are read from the
+    /// `qos_overrides.<topic>.publisher.<policy>` parameters given on the
+    /// command line or in parameter files, where `<topic>` is the fully
+    /// qualified topic name after remapping. The profile is then validated by
+    /// the callback of `options`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // ros2 run ... --ros-args -p 'qos_overrides./scan.publisher.depth:=1'
+    /// let options = QosOverridingOptions::with_default_policies();
+    /// let publisher =
+    ///     node.create_publisher_with_qos_overrides::<LaserScan>("scan", None, &options)?;
+    /// ```
+    pub fn create_publisher_with_qos_overrides<T: TypeSupport + oxidros_core::TypeDescription>(
+        self: &Arc<Self>,
+        topic_name: &str,
+        qos: Option<Profile>,
+        options: &QosOverridingOptions,
+    ) -> Result<Publisher<T>> {
+        self.register_type_description::<T>();
+        let fq_topic_name = self.expand_and_remap_name(topic_name, NameKind::Topic)?;
+        let qos = self.override_qos(&fq_topic_name, QosEntityKind::Publisher, qos, options)?;
+
+        Publisher::new(
+            self.clone(),
+            topic_name,
+            &fq_topic_name,
+            qos,
+            EntityKind::Publisher,
+        )
+    }
+
     /// Create a subscriber.
     ///
     /// # Arguments
@@ -407,6 +589,21 @@ impl Node {
         self: &Arc<Self>,
         topic_name: &str,
         qos: Option<Profile>,
+    ) -> Result<Subscriber<T>> {
+        self.z_create_subscriber_with_buffer(topic_name, qos, BufferOptions::default())
+    }
+
+    /// Create a subscriber with an explicit receive buffer configuration.
+    ///
+    /// By default the buffer holds the QoS depth and drops the oldest message
+    /// when full. Use [`Subscriber::lagged`] to observe dropped messages.
+    /// With [`BufferOptions::paused`], no message is buffered until
+    /// [`Subscriber::resume`].
+    pub fn z_create_subscriber_with_buffer<T: TypeSupport + oxidros_core::TypeDescription>(
+        self: &Arc<Self>,
+        topic_name: &str,
+        qos: Option<Profile>,
+        buffer: BufferOptions,
     ) -> Result<Subscriber<T>> {
         self.register_type_description::<T>();
         // Expand and remap the topic name
@@ -418,6 +615,32 @@ impl Node {
             &fq_topic_name,
             qos.unwrap_or_default(),
             EntityKind::Subscriber,
+            buffer,
+        )
+    }
+
+    /// Create a subscriber whose QoS can be overridden by parameters.
+    ///
+    /// The policies allowed by `options` are read from the
+    /// `qos_overrides.<topic>.subscription.<policy>` parameters (see
+    /// `create_publisher_with_qos_overrides`).
+    pub fn create_subscriber_with_qos_overrides<T: TypeSupport + oxidros_core::TypeDescription>(
+        self: &Arc<Self>,
+        topic_name: &str,
+        qos: Option<Profile>,
+        options: &QosOverridingOptions,
+    ) -> Result<Subscriber<T>> {
+        self.register_type_description::<T>();
+        let fq_topic_name = self.expand_and_remap_name(topic_name, NameKind::Topic)?;
+        let qos = self.override_qos(&fq_topic_name, QosEntityKind::Subscription, qos, options)?;
+
+        Subscriber::new(
+            self.clone(),
+            topic_name,
+            &fq_topic_name,
+            qos,
+            EntityKind::Subscriber,
+            BufferOptions::default(),
         )
     }
 
@@ -476,6 +699,36 @@ impl Node {
         service_name: &str,
         qos: Option<Profile>,
     ) -> Result<Server<T>>
+    where
+        T::Request: TypeSupport + oxidros_core::TypeDescription,
+        T::Response: TypeSupport + oxidros_core::TypeDescription,
+    {
+        self.z_create_server_with_options(service_name, qos, ServerOptions::default())
+    }
+
+    /// Create a service server with an explicit request queue configuration.
+    ///
+    /// By default at most 32 requests wait to be received, and the requests
+    /// arriving when the queue is full are answered with an error. Use
+    /// [`Server::shed`] to observe the refused requests.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use oxidros_zenoh::service::{ServerOptions, SheddingPolicy};
+    ///
+    /// let options = ServerOptions {
+    ///     max_pending: Some(4),
+    ///     shedding: SheddingPolicy::DropOldest,
+    /// };
+    /// let server = node.z_create_server_with_options::<AddTwoInts>("add", None, options)?;
+    /// ```
+    pub fn z_create_server_with_options<T: oxidros_core::ServiceMsg>(
+        self: &Arc<Self>,
+        service_name: &str,
+        qos: Option<Profile>,
+        options: ServerOptions,
+    ) -> Result<Server<T>>
     where
         T::Request: TypeSupport + oxidros_core::TypeDescription,
         T::Response: TypeSupport + oxidros_core::TypeDescription,
@@ -490,9 +743,149 @@ impl Node {
             service_name,
             &fq_service_name,
             qos.unwrap_or_else(Profile::services_default),
+            options,
+        )
+    }
+
+    /// Create a service client whose type is only known at runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_name` - Service name (can be absolute, relative, or private `~`)
+    /// * `service_type` - ROS2 service type name (e.g. `example_interfaces/srv/AddTwoInts`)
+    /// * `request_desc` - Type description of the request
+    /// * `response_desc` - Type description of the response
+    /// * `qos` - Optional QoS profile (uses default if None)
+    ///
+    /// # Name Resolution
+    ///
+    /// The service name is expanded and remapped (see `create_publisher`).
+    #[cfg(feature = "dynamic")]
+    pub fn create_generic_client(
+        self: &Arc<Self>,
+        service_name: &str,
+        service_type: &str,
+        request_desc: oxidros_core::types::TypeDescriptionMsg,
+        response_desc: oxidros_core::types::TypeDescriptionMsg,
+        qos: Option<Profile>,
+    ) -> Result<crate::service::GenericClient> {
+        // Make the descriptions available via z_get_type_description
+        self.register_dynamic_type_description(&request_desc);
+        self.register_dynamic_type_description(&response_desc);
+        // Expand and remap the service name (services use Topic naming rules)
+        let fq_service_name = self.expand_and_remap_name(service_name, NameKind::Topic)?;
+
+        crate::service::GenericClient::new(
+            self.clone(),
+            service_name,
+            &fq_service_name,
+            service_type,
+            request_desc,
+            response_desc,
+            qos.unwrap_or_else(Profile::services_default),
+        )
+    }
+
+    /// Create a publisher whose message type is only known at runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic_name` - Topic name (can be absolute, relative, or private `~`)
+    /// * `type_desc` - Type description of the message (e.g. `std_msgs/msg/String`)
+    /// * `qos` - Optional QoS profile (uses default if None)
+    ///
+    /// # Name Resolution
+    ///
+    /// The topic name is expanded and remapped (see `create_publisher`).
+    #[cfg(feature = "dynamic")]
+    pub fn create_generic_publisher(
+        self: &Arc<Self>,
+        topic_name: &str,
+        type_desc: oxidros_core::types::TypeDescriptionMsg,
+        qos: Option<Profile>,
+    ) -> Result<crate::topic::GenericPublisher> {
+        self.register_dynamic_type_description(&type_desc);
+        let fq_topic_name = self.expand_and_remap_name(topic_name, NameKind::Topic)?;
+
+        crate::topic::GenericPublisher::new(
+            self.clone(),
+            topic_name,
+            &fq_topic_name,
+            type_desc,
+            qos.unwrap_or_default(),
+        )
+    }
+
+    /// Create a subscriber whose message type is only known at runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic_name` - Topic name (can be absolute, relative, or private `~`)
+    /// * `type_desc` - Type description of the message (e.g. `std_msgs/msg/String`)
+    /// * `qos` - Optional QoS profile (uses default if None)
+    ///
+    /// # Name Resolution
+    ///
+    /// The topic name is expanded and remapped (see `create_publisher`).
+    #[cfg(feature = "dynamic")]
+    pub fn create_generic_subscriber(
+        self: &Arc<Self>,
+        topic_name: &str,
+        type_desc: oxidros_core::types::TypeDescriptionMsg,
+        qos: Option<Profile>,
+    ) -> Result<crate::topic::GenericSubscriber> {
+        self.register_dynamic_type_description(&type_desc);
+        let fq_topic_name = self.expand_and_remap_name(topic_name, NameKind::Topic)?;
+
+        crate::topic::GenericSubscriber::new(
+            self.clone(),
+            topic_name,
+            &fq_topic_name,
+            type_desc,
+            qos.unwrap_or_default(),
         )
     }
 
+    /// Look up a type description registered on this node by ROS2 type name.
+    ///
+    /// Types are registered when entities using them are created.
+    pub fn registered_type_description(
+        &self,
+        type_name: &str,
+    ) -> Option<oxidros_core::types::TypeDescriptionMsg> {
+        self.inner.type_registry.lock().get(type_name).cloned()
+    }
+
+    /// Create the `~/get_type_description` service of this node
+    /// (`type_description_interfaces/srv/GetTypeDescription`).
+    ///
+    /// The service describes the types of the publishers, subscribers,
+    /// clients and servers of the node, so that the tools and dynamic
+    /// subscribers of other ROS2 implementations can decode them. It is
+    /// served until the returned server is dropped.
+    pub fn create_type_description_server(
+        self: &Arc<Self>,
+    ) -> Result<crate::type_description::TypeDescriptionServer> {
+        use oxidros_msg::interfaces::type_description_interfaces::srv::{
+            GetTypeDescription, GetTypeDescription_Request, GetTypeDescription_Response,
+        };
+
+        const SERVICE_NAME: &str = "~/get_type_description";
+        self.register_type_description::<GetTypeDescription_Request>();
+        self.register_type_description::<GetTypeDescription_Response>();
+        let fq_service_name = self.expand_and_remap_name(SERVICE_NAME, NameKind::Topic)?;
+
+        let registry = self.inner.type_registry.clone();
+        let server = Server::<GetTypeDescription>::with_handler(
+            self.clone(),
+            SERVICE_NAME,
+            &fq_service_name,
+            Profile::services_default(),
+            move |request| crate::type_description::describe(&registry, request),
+        )?;
+        Ok(crate::type_description::TypeDescriptionServer::new(server))
+    }
+
     /// Create a parameter server for this node.
     ///
     /// The parameter server provides the standard ROS2 parameter services:
@@ -536,6 +929,10 @@ impl oxidros_core::api::RosNode for Node {
         self.z_fully_qualified_name()
     }
 
+    fn stats(&self) -> NodeStats {
+        self.z_stats()
+    }
+
     fn create_publisher<T: TypeSupport + oxidros_core::TypeDescription>(
         self: &Arc<Self>,
         topic_name: &str,
@@ -570,6 +967,15 @@ impl oxidros_core::api::RosNode for Node {
         self: &Arc<Self>,
         service_name: &str,
         qos: Option<Profile>,
+    ) -> Result<Self::Server<T>> {
+        self.create_server_with_options(service_name, qos, ServerOptions::default())
+    }
+
+    fn create_server_with_options<T: oxidros_core::ServiceMsg>(
+        self: &Arc<Self>,
+        service_name: &str,
+        qos: Option<Profile>,
+        options: ServerOptions,
     ) -> Result<Self::Server<T>> {
         let fq_service_name = self.expand_and_remap_name(service_name, NameKind::Topic)?;
         Server::new(
@@ -577,6 +983,7 @@ impl oxidros_core::api::RosNode for Node {
             service_name,
             &fq_service_name,
             qos.unwrap_or_else(Profile::services_default),
+            options,
         )
     }
 }
@@ -4,6 +4,15 @@
 //! standard ROS2 parameter services. It allows nodes to have parameters that can
 //! be get/set/listed/described through ROS2 service calls.
 //!
+//! Requests are validated and applied by the service helpers of
+//! [`Parameters`], such as [`Parameters::list`] and [`Parameters::update`], so
+//! the services behave like the ones of the RCL backend and of `rclcpp`.
+//!
+//! The `use_sim_time` parameter is declared on every parameter server. While
+//! it is `true`, the clock of the node, returned by [`Node::get_clock`],
+//! follows the `/clock` topic. It can be switched at runtime through the
+//! parameter services or [`ParameterServer::set_use_sim_time`].
+//!
 //! # Example
 //!
 //! ```ignore
@@ -25,18 +34,32 @@
 //! // Process parameter requests in event loop
 //! param_server.process_once().await?;
 //! ```
-
-use crate::{error::Result, node::Node, service::server::Server};
-pub use oxidros_core::parameter::{Parameters, Value};
+//!
+//! # Persistence
+//!
+//! Parameters can be saved to and restored from a file in the `ros2 param dump`
+//! YAML format with [`ParameterServer::dump_yaml`] and
+//! [`ParameterServer::load_yaml`]. With [`ParameterServer::set_auto_save`], the
+//! file is rewritten every time a parameter is changed through the parameter
+//! services, so values tuned at runtime survive a restart.
+
+use crate::{error::Result, node::Node, service::server::Server, time_source::TimeSource};
+pub use oxidros_core::parameter::{ParameterSnapshot, ParameterType, Parameters, Value};
 use oxidros_core::qos::Profile;
+use oxidros_core::targets;
+use oxidros_core::time::USE_SIM_TIME;
 use parking_lot::RwLock;
-use std::{collections::BTreeSet, sync::Arc};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 // Import rcl_interfaces types when the feature is enabled
 use oxidros_msg::interfaces::rcl_interfaces::{
     msg::{
-        ParameterDescriptor, ParameterDescriptorSeq, ParameterValue, ParameterValueSeq,
-        SetParametersResult, SetParametersResultSeq,
+        FloatingPointRangeSeq, IntegerRangeSeq, ParameterDescriptor, ParameterDescriptorSeq,
+        ParameterValue, ParameterValueSeq, SetParametersResult, SetParametersResultSeq,
     },
     srv::{
         describe_parameters::{DescribeParameters, DescribeParameters_Response},
@@ -72,6 +95,10 @@ pub struct ParameterServer {
     srv_get_types: Server<GetParameterTypes>,
     /// Parameters updated by external service calls (not local app changes).
     service_updated: BTreeSet<String>,
+    /// File rewritten after parameters are updated by service calls.
+    auto_save: Option<PathBuf>,
+    /// Subscriber of `/clock` driven by `use_sim_time`.
+    time_source: TimeSource,
 }
 
 impl ParameterServer {
@@ -83,31 +110,21 @@ impl ParameterServer {
     pub fn new(node: Arc<Node>) -> Result<Self> {
         // Initialize parameters from ROS2 arguments
         let mut params = Parameters::new();
-
-        // Load parameters from command-line assignments
-        // Use original name for matching node-specific parameter rules
-        let ros2_args = node.context().ros2_args();
-        let original_name = node.original_name();
-        let fqn = node.z_fully_qualified_name()?;
-
-        // Get parameters that apply to this node (using original name for matching)
-        if let Ok(param_assignments) = ros2_args.get_params_for_node(original_name) {
-            for param in param_assignments {
-                if let Ok(value) = Value::try_from(&param.value) {
-                    let _ = params.set_parameter(param.name.clone(), value, false, None);
-                }
-            }
+        for (name, value) in node.parameter_overrides()? {
+            let _ = params.set_parameter(name, value, false, None);
         }
-
-        // Also check with FQN (some params might be specified with full path)
-        if let Ok(param_assignments) = ros2_args.get_params_for_node(&fqn) {
-            for param in param_assignments {
-                if let Ok(value) = Value::try_from(&param.value) {
-                    let _ = params.set_parameter(param.name.clone(), value, false, None);
-                }
-            }
+        if params.get_parameter(USE_SIM_TIME).is_none() {
+            params.set_parameter(
+                USE_SIM_TIME.to_string(),
+                Value::Bool(false),
+                false,
+                Some("Use the simulated time of the /clock topic".to_string()),
+            )?;
         }
 
+        let mut time_source = TimeSource::new(node.clone())?;
+        apply_use_sim_time(&params, &mut time_source);
+
         // Clear the updated set - initial parameters shouldn't be considered "updated"
         let _ = params.take_updated();
 
@@ -148,14 +165,62 @@ impl ParameterServer {
             srv_describe,
             srv_get_types,
             service_updated: BTreeSet::new(),
+            auto_save: None,
+            time_source,
         })
     }
 
+    /// Set the `use_sim_time` parameter, and switch the clock of the node to
+    /// the simulated time or back to the system time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameter is read-only or not a boolean.
+    pub fn set_use_sim_time(&mut self, enable: bool) -> Result<()> {
+        let mut params = self.params.write();
+        params.update(USE_SIM_TIME, Value::Bool(enable))?;
+        apply_use_sim_time(&params, &mut self.time_source);
+        Ok(())
+    }
+
+    /// Whether the clock of the node follows the simulated time.
+    pub fn use_sim_time(&self) -> bool {
+        self.time_source.use_sim_time()
+    }
+
     /// Get a reference to the parent node.
     pub fn node(&self) -> &Arc<Node> {
         &self.node
     }
 
+    /// Take an immutable copy of the parameters, see [`Parameters::snapshot`].
+    pub fn snapshot(&self) -> ParameterSnapshot {
+        self.params.read().snapshot()
+    }
+
+    /// Write all parameters to a YAML file in the `ros2 param dump` format.
+    pub fn dump_yaml(&self, path: impl AsRef<Path>) -> Result<()> {
+        let fqn = self.node.z_fully_qualified_name()?;
+        self.params.read().dump_yaml(&fqn, path)
+    }
+
+    /// Load parameters from a YAML parameter file.
+    ///
+    /// Only the entries whose node key matches this node (exactly or through a
+    /// wildcard such as `/**`) are applied. Returns the names of the parameters
+    /// that were set.
+    pub fn load_yaml(&self, path: impl AsRef<Path>) -> Result<BTreeSet<String>> {
+        let fqn = self.node.z_fully_qualified_name()?;
+        self.params.write().load_yaml(&fqn, path)
+    }
+
+    /// Rewrite `path` whenever parameters are set through the parameter services.
+    ///
+    /// Pass `None` to disable auto-saving.
+    pub fn set_auto_save(&mut self, path: Option<PathBuf>) {
+        self.auto_save = path;
+    }
+
     /// Wait for parameter updates asynchronously.
     ///
     /// This method returns a future that completes when parameters are updated
@@ -294,27 +359,37 @@ impl ParameterServer {
         processed
     }
 
+    /// Write the parameters to the auto-save file, if one is configured.
+    fn save_if_enabled(&self, params: &Parameters) {
+        let Some(path) = &self.auto_save else {
+            return;
+        };
+        let result = self
+            .node
+            .z_fully_qualified_name()
+            .and_then(|fqn| params.dump_yaml(&fqn, path));
+        if let Err(e) = result {
+            tracing::warn!(
+                target: targets::PARAMETER,
+                path = %path.display(),
+                error = %e,
+                "Failed to auto-save parameters"
+            );
+        }
+    }
+
     // --- Service handlers ---
 
     fn handle_list_parameters(
         &self,
-        _request: &<ListParameters as oxidros_core::ServiceMsg>::Request,
+        request: &<ListParameters as oxidros_core::ServiceMsg>::Request,
     ) -> <ListParameters as oxidros_core::ServiceMsg>::Response {
-        let guard = self.params.read();
+        let prefixes: Vec<String> = request.prefixes.iter().map(|p| p.to_string()).collect();
+        let list = self.params.read().list(&prefixes, request.depth);
 
         let mut response = ListParameters_Response::new().unwrap_or_default();
-
-        // Collect all parameter names
-        let names: Vec<_> = guard.params.keys().cloned().collect();
-        if let Some(mut name_seq) = RosStringSeq::<0, 0>::new(names.len()) {
-            for (i, name) in names.iter().enumerate() {
-                if let Some(ros_str) = RosString::<0>::new(name) {
-                    name_seq.as_mut_slice()[i] = ros_str;
-                }
-            }
-            response.result.names = name_seq;
-        }
-
+        response.result.names = to_string_seq(&list.names);
+        response.result.prefixes = to_string_seq(&list.prefixes);
         response
     }
 
@@ -330,7 +405,7 @@ impl ParameterServer {
         if let Some(mut values) = ParameterValueSeq::<0>::new(names.len()) {
             for (i, name) in names.iter().enumerate() {
                 let name_str = name.to_string();
-                if let Some(param) = guard.params.get(&name_str) {
+                if let Some(param) = guard.get_parameter(&name_str) {
                     values.as_mut_slice()[i] = value_to_parameter_value(&param.value);
                 }
             }
@@ -354,14 +429,14 @@ impl ParameterServer {
 
                 let mut result = SetParametersResult::new().unwrap_or_default();
 
-                match guard.set_parameter(name.clone(), value, false, None) {
+                match guard.update(&name, value) {
                     Ok(()) => {
                         result.successful = true;
                         self.service_updated.insert(name);
                     }
-                    Err(e) => {
+                    Err(reason) => {
                         result.successful = false;
-                        if let Some(reason) = RosString::new(&e.to_string()) {
+                        if let Some(reason) = RosString::new(&reason) {
                             result.reason = reason;
                         }
                     }
@@ -372,6 +447,8 @@ impl ParameterServer {
             response.results = results;
         }
 
+        apply_use_sim_time(&guard, &mut self.time_source);
+        self.save_if_enabled(&guard);
         response
     }
 
@@ -384,42 +461,33 @@ impl ParameterServer {
         let mut response = SetParametersAtomically_Response::new().unwrap_or_default();
         response.result = SetParametersResult::new().unwrap_or_default();
 
-        // First, validate all parameters
-        let mut validated: Vec<(String, Value)> = Vec::new();
-
-        for param in request.parameters.iter() {
-            let name = param.name.to_string();
-            let value = parameter_value_to_value(&param.value);
-
-            // Check if it's a valid update
-            if let Some(existing) = guard.params.get(&name) {
-                if existing.descriptor.read_only {
-                    response.result.successful = false;
-                    if let Some(reason) = RosString::new(&format!("{} is read only", name)) {
-                        response.result.reason = reason;
-                    }
-                    return response;
-                }
-                if !existing.descriptor.dynamic_typing && !existing.value.type_check(&value) {
-                    response.result.successful = false;
-                    if let Some(reason) = RosString::new(&format!("Type mismatch for {}", name)) {
-                        response.result.reason = reason;
-                    }
-                    return response;
+        let parameters: Vec<(String, Value)> = request
+            .parameters
+            .iter()
+            .map(|param| {
+                (
+                    param.name.to_string(),
+                    parameter_value_to_value(&param.value),
+                )
+            })
+            .collect();
+        let names: Vec<String> = parameters.iter().map(|(name, _)| name.clone()).collect();
+
+        match guard.update_atomically(parameters) {
+            Ok(()) => {
+                response.result.successful = true;
+                self.service_updated.extend(names);
+                apply_use_sim_time(&guard, &mut self.time_source);
+                self.save_if_enabled(&guard);
+            }
+            Err(reason) => {
+                response.result.successful = false;
+                if let Some(reason) = RosString::new(&reason) {
+                    response.result.reason = reason;
                 }
             }
-
-            validated.push((name, value));
-        }
-
-        // All validated, now apply atomically
-        for (name, value) in validated {
-            let _ = guard.set_parameter(name.clone(), value, false, None);
-            self.service_updated.insert(name);
         }
 
-        response.result.successful = true;
-
         response
     }
 
@@ -440,13 +508,30 @@ impl ParameterServer {
                     desc.name = ros_name;
                 }
 
-                if let Some(param) = guard.params.get(&name_str) {
-                    desc.r#type = value_type_id(&param.value);
+                if let Some(param) = guard.get_parameter(&name_str) {
+                    desc.r#type = param.value.parameter_type().into();
                     if let Some(ros_desc) = RosString::new(&param.descriptor.description) {
                         desc.description = ros_desc;
                     }
+                    if let Some(constraints) =
+                        RosString::new(&param.descriptor.additional_constraints)
+                    {
+                        desc.additional_constraints = constraints;
+                    }
                     desc.read_only = param.descriptor.read_only;
                     desc.dynamic_typing = param.descriptor.dynamic_typing;
+                    if let Some(range) = &param.descriptor.integer_range
+                        && let Some(mut seq) = IntegerRangeSeq::new(1)
+                    {
+                        seq.as_mut_slice()[0] = range.into();
+                        desc.integer_range = seq;
+                    }
+                    if let Some(range) = &param.descriptor.floating_point_range
+                        && let Some(mut seq) = FloatingPointRangeSeq::new(1)
+                    {
+                        seq.as_mut_slice()[0] = range.into();
+                        desc.floating_point_range = seq;
+                    }
                 }
 
                 descriptors.as_mut_slice()[i] = desc;
@@ -468,8 +553,8 @@ impl ParameterServer {
         if let Some(mut types) = oxidros_msg::msg::U8Seq::<0>::new(request.names.len()) {
             for (i, name) in request.names.iter().enumerate() {
                 let name_str = name.to_string();
-                if let Some(param) = guard.params.get(&name_str) {
-                    types.as_mut_slice()[i] = value_type_id(&param.value);
+                if let Some(param) = guard.get_parameter(&name_str) {
+                    types.as_mut_slice()[i] = param.value.parameter_type().into();
                 }
             }
             response.types = types;
@@ -481,62 +566,76 @@ impl ParameterServer {
 
 // --- Helper functions ---
 
+/// Convert strings to a string sequence.
+/// Switch the time source to the value of the `use_sim_time` parameter.
+fn apply_use_sim_time(params: &Parameters, time_source: &mut TimeSource) {
+    let enable = params
+        .get_parameter(USE_SIM_TIME)
+        .is_some_and(|param| matches!(param.value, Value::Bool(true)));
+    if let Err(e) = time_source.set_use_sim_time(enable) {
+        tracing::error!(
+            target: targets::PARAMETER,
+            use_sim_time = enable,
+            error = %e,
+            "Failed to switch the time source"
+        );
+    }
+}
+
+fn to_string_seq(strings: &[String]) -> RosStringSeq<0, 0> {
+    let mut seq = RosStringSeq::<0, 0>::new(strings.len()).unwrap_or_default();
+    for (dst, src) in seq.as_mut_slice().iter_mut().zip(strings) {
+        dst.assign(src);
+    }
+    seq
+}
+
 /// Convert oxidros_core Value to ParameterValue message.
 fn value_to_parameter_value(value: &Value) -> ParameterValue {
     let mut pv = ParameterValue::new().unwrap_or_default();
+    pv.r#type = value.parameter_type().into();
 
     match value {
-        Value::NotSet => {
-            pv.r#type = 0;
-        }
+        Value::NotSet => {}
         Value::Bool(b) => {
-            pv.r#type = 1;
             pv.bool_value = *b;
         }
         Value::I64(i) => {
-            pv.r#type = 2;
             pv.integer_value = *i;
         }
         Value::F64(f) => {
-            pv.r#type = 3;
             pv.double_value = *f;
         }
         Value::String(s) => {
-            pv.r#type = 4;
             if let Some(ros_str) = RosString::new(s) {
                 pv.string_value = ros_str;
             }
         }
         Value::VecU8(bytes) => {
-            pv.r#type = 5;
             if let Some(mut seq) = oxidros_msg::msg::ByteSeq::<0>::new(bytes.len()) {
                 seq.as_mut_slice().copy_from_slice(bytes);
                 pv.byte_array_value = seq;
             }
         }
         Value::VecBool(bools) => {
-            pv.r#type = 6;
             if let Some(mut seq) = oxidros_msg::msg::BoolSeq::<0>::new(bools.len()) {
                 seq.as_mut_slice().copy_from_slice(bools);
                 pv.bool_array_value = seq;
             }
         }
         Value::VecI64(ints) => {
-            pv.r#type = 7;
             if let Some(mut seq) = oxidros_msg::msg::I64Seq::<0>::new(ints.len()) {
                 seq.as_mut_slice().copy_from_slice(ints);
                 pv.integer_array_value = seq;
             }
         }
         Value::VecF64(floats) => {
-            pv.r#type = 8;
             if let Some(mut seq) = oxidros_msg::msg::F64Seq::<0>::new(floats.len()) {
                 seq.as_mut_slice().copy_from_slice(floats);
                 pv.double_array_value = seq;
             }
         }
         Value::VecString(strings) => {
-            pv.r#type = 9;
             if let Some(mut seq) = RosStringSeq::<0, 0>::new(strings.len()) {
                 for (i, s) in strings.iter().enumerate() {
                     if let Some(ros_str) = RosString::new(s) {
@@ -553,16 +652,18 @@ fn value_to_parameter_value(value: &Value) -> ParameterValue {
 
 /// Convert ParameterValue message to oxidros_core Value.
 fn parameter_value_to_value(pv: &ParameterValue) -> Value {
-    match pv.r#type {
-        1 => Value::Bool(pv.bool_value),
-        2 => Value::I64(pv.integer_value),
-        3 => Value::F64(pv.double_value),
-        4 => Value::String(pv.string_value.to_string()),
-        5 => Value::VecU8(pv.byte_array_value.as_slice().to_vec()),
-        6 => Value::VecBool(pv.bool_array_value.as_slice().to_vec()),
-        7 => Value::VecI64(pv.integer_array_value.as_slice().to_vec()),
-        8 => Value::VecF64(pv.double_array_value.as_slice().to_vec()),
-        9 => Value::VecString(
+    match ParameterType::try_from(pv.r#type) {
+        Ok(ParameterType::Bool) => Value::Bool(pv.bool_value),
+        Ok(ParameterType::Integer) => Value::I64(pv.integer_value),
+        Ok(ParameterType::Double) => Value::F64(pv.double_value),
+        Ok(ParameterType::String) => Value::String(pv.string_value.to_string()),
+        Ok(ParameterType::ByteArray) => Value::VecU8(pv.byte_array_value.as_slice().to_vec()),
+        Ok(ParameterType::BoolArray) => Value::VecBool(pv.bool_array_value.as_slice().to_vec()),
+        Ok(ParameterType::IntegerArray) => {
+            Value::VecI64(pv.integer_array_value.as_slice().to_vec())
+        }
+        Ok(ParameterType::DoubleArray) => Value::VecF64(pv.double_array_value.as_slice().to_vec()),
+        Ok(ParameterType::StringArray) => Value::VecString(
             pv.string_array_value
                 .iter()
                 .map(|s| s.to_string())
@@ -571,19 +672,3 @@ fn parameter_value_to_value(pv: &ParameterValue) -> Value {
         _ => Value::NotSet,
     }
 }
-
-/// Get the ROS2 parameter type ID for a value.
-fn value_type_id(value: &Value) -> u8 {
-    match value {
-        Value::NotSet => 0,
-        Value::Bool(_) => 1,
-        Value::I64(_) => 2,
-        Value::F64(_) => 3,
-        Value::String(_) => 4,
-        Value::VecU8(_) => 5,
-        Value::VecBool(_) => 6,
-        Value::VecI64(_) => 7,
-        Value::VecF64(_) => 8,
-        Value::VecString(_) => 9,
-    }
-}
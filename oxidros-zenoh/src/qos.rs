@@ -11,14 +11,17 @@
 //! |----------|----------------|
 //! | `Reliable` | Data delivery via reliable transport (tcp/quic) if configured |
 //! | `BestEffort` | May use unreliable transport (udp) if configured, otherwise reliable |
-//! | `SystemDefault` | Same as `BestEffort` |
+//! | `SystemDefault` | Same as `Reliable` |
+//!
+//! The reliability is set on publishers; subscriptions receive whatever the
+//! transport delivers.
 //!
 //! ## History Mapping
 //!
 //! | ROS2 QoS | Zenoh Behavior |
 //! |----------|----------------|
-//! | `KeepLast(n)` | For subscriptions: queue depth = n. For publishers with TRANSIENT_LOCAL: cache size = n |
-//! | `KeepAll` | For publishers with Reliable: `CongestionControl::Block` mode |
+//! | `KeepLast(n)` | For subscriptions: queue depth = n, oldest messages dropped when full. For publishers with TRANSIENT_LOCAL: cache size = n |
+//! | `KeepAll` | For subscriptions: unbounded queue. For publishers with Reliable: `CongestionControl::Block` mode |
 //! | `SystemDefault` | Same as `KeepLast` |
 //!
 //! ## Depth Mapping
@@ -26,6 +29,9 @@
 //! | ROS2 QoS | Zenoh Behavior |
 //! |----------|----------------|
 //! | `0` | Defaults to `42` (per rmw_zenoh specification) |
+//!
+//! Messages dropped by a full subscription queue are counted by
+//! `Subscriber::lagged`.
 //! | `n > 0` | Uses specified depth |
 //!
 //! ## Durability Mapping
@@ -55,8 +61,10 @@
 //! any subscription as long as their key expressions match. Type safety is enforced
 //! through the type name and hash in the key expression.
 
-use oxidros_core::qos::{DurabilityPolicy, HistoryPolicy, Profile, ReliabilityPolicy};
-use zenoh::qos::CongestionControl;
+use oxidros_core::qos::{
+    DurabilityPolicy, HistoryPolicy, Profile, QosCompatibility, ReliabilityPolicy,
+};
+use zenoh::qos::{CongestionControl, Reliability};
 
 /// Default depth when QoS depth is 0 (per rmw_zenoh specification).
 pub const DEFAULT_DEPTH: usize = 42;
@@ -67,18 +75,14 @@ pub struct QosMapping;
 impl QosMapping {
     /// Get the effective queue/cache depth.
     ///
-    /// Returns `DEFAULT_DEPTH` (42) if the profile depth is 0,
-    /// otherwise returns the specified depth.
+    /// Returns `usize::MAX` for `KeepAll`, `DEFAULT_DEPTH` (42) if the
+    /// profile depth is 0, otherwise returns the specified depth.
     pub fn effective_depth(profile: &Profile) -> usize {
         let depth = match profile.history {
             HistoryPolicy::KeepAll => usize::MAX,
             _ => profile.depth,
         };
-        if depth == 0 {
-            DEFAULT_DEPTH
-        } else {
-            profile.depth
-        }
+        if depth == 0 { DEFAULT_DEPTH } else { depth }
     }
 
     /// Check if the profile requires transient local durability.
@@ -98,6 +102,18 @@ impl QosMapping {
         )
     }
 
+    /// Get the Zenoh reliability of a publisher.
+    ///
+    /// Returns `BestEffort` only if reliability is `BestEffort`, so that such
+    /// publishers may use an unreliable transport (udp) if configured.
+    pub fn reliability(profile: &Profile) -> Reliability {
+        if Self::is_reliable(profile) {
+            Reliability::Reliable
+        } else {
+            Reliability::BestEffort
+        }
+    }
+
     /// Get the Zenoh congestion control mode for a publisher.
     ///
     /// Returns `Block` if history is `KeepAll` and reliability is `Reliable`,
@@ -139,6 +155,26 @@ impl QosMapping {
             tracing::warn!("QoS lifespan is not implemented in rmw_zenoh, ignoring");
         }
     }
+
+    /// Check whether a publisher and a subscription with the given profiles match.
+    ///
+    /// The endpoints always match, see the compatibility note of this module.
+    /// A warning is returned when the subscription asks for a guarantee the
+    /// publisher does not provide.
+    pub fn check_compatible(publisher: &Profile, subscription: &Profile) -> QosCompatibility {
+        let mut reasons = Vec::new();
+        if !Self::is_reliable(publisher) && Self::is_reliable(subscription) {
+            reasons.push("best effort publisher and reliable subscription");
+        }
+        if !Self::is_transient_local(publisher) && Self::is_transient_local(subscription) {
+            reasons.push("volatile publisher and transient local subscription");
+        }
+        if reasons.is_empty() {
+            QosCompatibility::Ok
+        } else {
+            QosCompatibility::Warning(reasons.join("; "))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -157,6 +193,23 @@ mod tests {
         // Non-zero depth should be returned as-is
         profile.depth = 10;
         assert_eq!(QosMapping::effective_depth(&profile), 10);
+
+        // KeepAll ignores the depth
+        profile.history = HistoryPolicy::KeepAll;
+        assert_eq!(QosMapping::effective_depth(&profile), usize::MAX);
+    }
+
+    #[test]
+    fn test_reliability() {
+        let mut profile = Profile {
+            reliability: ReliabilityPolicy::Reliable,
+            ..Default::default()
+        };
+        assert_eq!(QosMapping::reliability(&profile), Reliability::Reliable);
+        profile.reliability = ReliabilityPolicy::SystemDefault;
+        assert_eq!(QosMapping::reliability(&profile), Reliability::Reliable);
+        profile.reliability = ReliabilityPolicy::BestEffort;
+        assert_eq!(QosMapping::reliability(&profile), Reliability::BestEffort);
     }
 
     #[test]
@@ -195,4 +248,31 @@ mod tests {
             CongestionControl::Drop
         );
     }
+
+    #[test]
+    fn test_check_compatible() {
+        let reliable = Profile::default();
+        let best_effort = Profile::sensor_data();
+        let transient_local = Profile {
+            durability: DurabilityPolicy::TransientLocal,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            QosMapping::check_compatible(&reliable, &best_effort),
+            QosCompatibility::Ok
+        );
+        assert_eq!(
+            QosMapping::check_compatible(&transient_local, &reliable),
+            QosCompatibility::Ok
+        );
+
+        // Never an error, the endpoints always match
+        let compatibility = QosMapping::check_compatible(&best_effort, &reliable);
+        assert!(matches!(compatibility, QosCompatibility::Warning(_)));
+        assert!(compatibility.is_compatible());
+
+        let compatibility = QosMapping::check_compatible(&reliable, &transient_local);
+        assert!(matches!(compatibility, QosCompatibility::Warning(_)));
+    }
 }
@@ -0,0 +1,370 @@
+//! rosbridge v2 WebSocket server.
+//!
+//! Exposes the ROS2 graph to web clients (roslibjs, Foxglove, ...) speaking the
+//! rosbridge JSON protocol. Messages are converted between JSON and CDR at
+//! runtime with [`DynamicMessage`](oxidros_dynamic::DynamicMessage), so no
+//! message type has to be known at compile time.
+//!
+//! Type descriptions are resolved from, in order:
+//! 1. types registered with [`RosBridgeServer::register`],
+//! 2. types used by entities of the bridge node,
+//! 3. a `z_get_type_description` query to the nodes of the domain.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros_zenoh::{Context, rosbridge::RosBridgeServer};
+//!
+//! let ctx = Context::new()?;
+//! let node = ctx.create_node("rosbridge", None)?;
+//! RosBridgeServer::new(node)
+//!     .register::<std_msgs::msg::String>()
+//!     .serve(("0.0.0.0", oxidros_zenoh::rosbridge::DEFAULT_PORT))
+//!     .await?;
+//! ```
+
+pub mod protocol;
+
+use crate::{
+    error::{Error, Result},
+    node::Node,
+    service::GenericClient,
+    task,
+    topic::{GenericPublisher, GenericSubscriber},
+    type_description::{TypeCache, dds_to_ros_type_name},
+};
+use futures_util::{SinkExt, StreamExt};
+use oxidros_core::{TypeDescription, targets, types::TypeDescriptionMsg};
+use protocol::{Reply, Request, normalize_type_name};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc,
+    task::JoinHandle,
+};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+/// Default rosbridge port.
+pub const DEFAULT_PORT: u16 = 9090;
+
+/// Delay before accepting again after an accept error, e.g. when the process
+/// runs out of file descriptors.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// rosbridge v2 WebSocket server.
+///
+/// Each connection gets its own publishers, subscriptions and service clients,
+/// all created on the bridge node and dropped when the connection closes.
+pub struct RosBridgeServer {
+    shared: Arc<Shared>,
+}
+
+/// State shared by all connections.
+struct Shared {
+    node: Arc<Node>,
+    /// Type descriptions by ROS2 type name.
+    types: TypeCache,
+}
+
+impl RosBridgeServer {
+    /// Create a server bridging the graph seen by `node`.
+    pub fn new(node: Arc<Node>) -> Self {
+        RosBridgeServer {
+            shared: Arc::new(Shared {
+                node,
+                types: TypeCache::default(),
+            }),
+        }
+    }
+
+    /// Make a type available to clients without querying the graph.
+    pub fn register<T: TypeDescription>(self) -> Self {
+        self.shared.types.insert(T::type_description());
+        self
+    }
+
+    /// Listen on `addr` and serve connections.
+    pub async fn serve(self, addr: impl ToSocketAddrs) -> Result<()> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(|e| Error::Other(format!("Failed to bind rosbridge server: {e}")))?;
+        self.serve_listener(listener).await
+    }
+
+    /// Serve connections accepted on `listener`.
+    ///
+    /// Accept errors are logged and accepting is retried after a short delay.
+    pub async fn serve_listener(self, listener: TcpListener) -> Result<()> {
+        tracing::info!(
+            target: targets::ZENOH,
+            addr = ?listener.local_addr().ok(),
+            "rosbridge server listening"
+        );
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    tracing::warn!(target: targets::ZENOH, error = %e, "Failed to accept a rosbridge connection");
+                    tokio::time::sleep(ACCEPT_BACKOFF).await;
+                    continue;
+                }
+            };
+            let shared = self.shared.clone();
+            task::spawn_named(&format!("rosbridge-{peer}"), async move {
+                tracing::debug!(target: targets::ZENOH, %peer, "rosbridge client connected");
+                if let Err(e) = Connection::run(shared, stream).await {
+                    tracing::debug!(target: targets::ZENOH, %peer, error = %e, "rosbridge connection failed");
+                }
+                tracing::debug!(target: targets::ZENOH, %peer, "rosbridge client disconnected");
+            });
+        }
+    }
+}
+
+impl Shared {
+    /// Resolve the description of a fully qualified ROS2 type name.
+    async fn resolve(&self, type_name: &str) -> Result<TypeDescriptionMsg> {
+        self.types.resolve(&self.node, type_name).await
+    }
+
+    /// Find the ROS2 type of an entity in the graph.
+    fn graph_type(&self, name: &str, services: bool) -> Result<String> {
+        let graph = self.node.context().graph_cache();
+        let types = if services {
+            graph.get_service_names_and_types()
+        } else {
+            graph.get_topic_names_and_types()
+        };
+        types
+            .into_iter()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, dds)| dds_to_ros_type_name(&dds))
+            .ok_or_else(|| Error::Other(format!("Cannot find the type of '{name}' in the graph")))
+    }
+}
+
+/// Per-connection state.
+struct Connection {
+    shared: Arc<Shared>,
+    replies: mpsc::UnboundedSender<Reply>,
+    publishers: HashMap<String, GenericPublisher>,
+    subscriptions: HashMap<String, JoinHandle<()>>,
+    clients: HashMap<String, Arc<GenericClient>>,
+}
+
+impl Connection {
+    async fn run(shared: Arc<Shared>, stream: TcpStream) -> Result<()> {
+        let ws = tokio_tungstenite::accept_async(stream)
+            .await
+            .map_err(|e| Error::Other(format!("WebSocket handshake failed: {e}")))?;
+        let (mut sink, mut source) = ws.split();
+
+        // Replies are produced by subscription and service tasks, a single
+        // writer task sends them in order.
+        let (replies, mut rx) = mpsc::unbounded_channel::<Reply>();
+        let writer = task::spawn_named("rosbridge-writer", async move {
+            while let Some(reply) = rx.recv().await {
+                let Ok(text) = serde_json::to_string(&reply) else {
+                    continue;
+                };
+                if sink.send(WsMessage::Text(text.into())).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut conn = Connection {
+            shared,
+            replies,
+            publishers: HashMap::new(),
+            subscriptions: HashMap::new(),
+            clients: HashMap::new(),
+        };
+        while let Some(frame) = source.next().await {
+            let text = match frame {
+                Ok(WsMessage::Text(text)) => text,
+                Ok(WsMessage::Close(_)) | Err(_) => break,
+                Ok(_) => continue,
+            };
+            let request = match serde_json::from_str::<Request>(&text) {
+                Ok(request) => request,
+                Err(e) => {
+                    let _ = conn.replies.send(Reply::error(None, e));
+                    continue;
+                }
+            };
+            let id = request.id().map(str::to_string);
+            if let Err(e) = conn.handle(request).await {
+                let _ = conn.replies.send(Reply::error(id.as_deref(), e));
+            }
+        }
+
+        for (_, task) in conn.subscriptions.drain() {
+            task.abort();
+        }
+        writer.abort();
+        Ok(())
+    }
+
+    async fn handle(&mut self, request: Request) -> Result<()> {
+        match request {
+            Request::Advertise {
+                topic, type_name, ..
+            } => {
+                let type_name = message_type(&type_name)?;
+                if let Some(publisher) = self.publishers.get(&topic)
+                    && publisher.type_description().type_description.type_name == type_name
+                {
+                    return Ok(());
+                }
+                let desc = self.shared.resolve(&type_name).await?;
+                let publisher = self
+                    .shared
+                    .node
+                    .create_generic_publisher(&topic, desc, None)?;
+                self.publishers.insert(topic, publisher);
+            }
+            Request::Unadvertise { topic, .. } => {
+                self.publishers.remove(&topic);
+            }
+            Request::Publish { topic, msg, .. } => {
+                let publisher = self
+                    .publishers
+                    .get(&topic)
+                    .ok_or_else(|| Error::Other(format!("Topic '{topic}' is not advertised")))?;
+                publisher.publish(&publisher.new_message(msg))?;
+            }
+            Request::Subscribe {
+                topic,
+                type_name,
+                throttle_rate,
+                ..
+            } => {
+                let type_name = match type_name {
+                    Some(type_name) => message_type(&type_name)?,
+                    None => self.shared.graph_type(&topic, false)?,
+                };
+                let desc = self.shared.resolve(&type_name).await?;
+                let subscriber = self
+                    .shared
+                    .node
+                    .create_generic_subscriber(&topic, desc, None)?;
+                let task = task::spawn_named(
+                    &topic,
+                    forward(
+                        subscriber,
+                        topic.clone(),
+                        Duration::from_millis(throttle_rate),
+                        self.replies.clone(),
+                    ),
+                );
+                if let Some(previous) = self.subscriptions.insert(topic, task) {
+                    previous.abort();
+                }
+            }
+            Request::Unsubscribe { topic, .. } => {
+                if let Some(task) = self.subscriptions.remove(&topic) {
+                    task.abort();
+                }
+            }
+            Request::CallService {
+                id,
+                service,
+                type_name,
+                args,
+            } => {
+                let client = self.client(&service, type_name.as_deref()).await?;
+                let replies = self.replies.clone();
+                // Calls may take long, do not block the connection
+                task::spawn_named(&format!("rosbridge-call-{service}"), async move {
+                    let request = client.new_request(args.unwrap_or_else(|| serde_json::json!({})));
+                    let (values, result) = match client.call(&request).await {
+                        Ok(response) => (response.value().clone(), true),
+                        Err(e) => (serde_json::Value::String(e.to_string()), false),
+                    };
+                    let _ = replies.send(Reply::ServiceResponse {
+                        id,
+                        service,
+                        values,
+                        result,
+                    });
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Get or create the client of a service.
+    async fn client(
+        &mut self,
+        service: &str,
+        type_name: Option<&str>,
+    ) -> Result<Arc<GenericClient>> {
+        if let Some(client) = self.clients.get(service) {
+            return Ok(client.clone());
+        }
+        let service_type = match type_name {
+            Some(type_name) => normalize_type_name(type_name, "srv")
+                .ok_or_else(|| Error::Other(format!("Invalid service type '{type_name}'")))?,
+            None => self.shared.graph_type(service, true)?,
+        };
+        let request_desc = self
+            .shared
+            .resolve(&format!("{service_type}_Request"))
+            .await?;
+        let response_desc = self
+            .shared
+            .resolve(&format!("{service_type}_Response"))
+            .await?;
+        let client = Arc::new(self.shared.node.create_generic_client(
+            service,
+            &service_type,
+            request_desc,
+            response_desc,
+            None,
+        )?);
+        self.clients.insert(service.to_string(), client.clone());
+        Ok(client)
+    }
+}
+
+/// Normalize a message type name sent by a client.
+fn message_type(type_name: &str) -> Result<String> {
+    normalize_type_name(type_name, "msg")
+        .ok_or_else(|| Error::Other(format!("Invalid message type '{type_name}'")))
+}
+
+/// Forward the messages of a subscription to the client.
+async fn forward(
+    mut subscriber: GenericSubscriber,
+    topic: String,
+    throttle: Duration,
+    replies: mpsc::UnboundedSender<Reply>,
+) {
+    let mut last: Option<Instant> = None;
+    loop {
+        let msg = match subscriber.recv().await {
+            Ok(msg) => msg,
+            Err(Error::ChannelClosed) => break,
+            Err(e) => {
+                let _ = replies.send(Reply::error(None, e));
+                continue;
+            }
+        };
+        if last.is_some_and(|t| t.elapsed() < throttle) {
+            continue;
+        }
+        last = Some(Instant::now());
+        let reply = Reply::Publish {
+            topic: topic.clone(),
+            msg: msg.value().clone(),
+        };
+        if replies.send(reply).is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! rosbridge v2 protocol messages.
+//!
+//! Only the operations needed by web clients (roslibjs, Foxglove's rosbridge
+//! connection, ...) are supported: `advertise`, `unadvertise`, `publish`,
+//! `subscribe`, `unsubscribe` and `call_service`.
+//!
+//! # Reference
+//!
+//! See the [rosbridge v2 protocol](https://github.com/RobotWebTools/rosbridge_suite/blob/ros2/ROSBRIDGE_PROTOCOL.md)
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Operation sent by a client.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Request {
+    /// Declare a publisher on a topic.
+    Advertise {
+        /// Optional request id.
+        id: Option<String>,
+        /// Topic name.
+        topic: String,
+        /// Message type (`pkg/msg/Name` or `pkg/Name`).
+        #[serde(rename = "type")]
+        type_name: String,
+    },
+    /// Remove a publisher declared with `advertise`.
+    Unadvertise {
+        /// Optional request id.
+        id: Option<String>,
+        /// Topic name.
+        topic: String,
+    },
+    /// Publish a message on an advertised topic.
+    Publish {
+        /// Optional request id.
+        id: Option<String>,
+        /// Topic name.
+        topic: String,
+        /// Message as JSON.
+        msg: Value,
+    },
+    /// Subscribe to a topic.
+    Subscribe {
+        /// Optional request id.
+        id: Option<String>,
+        /// Topic name.
+        topic: String,
+        /// Message type, looked up in the graph when missing.
+        #[serde(rename = "type")]
+        type_name: Option<String>,
+        /// Minimum time between two forwarded messages, in milliseconds.
+        #[serde(default)]
+        throttle_rate: u64,
+    },
+    /// Cancel a subscription.
+    Unsubscribe {
+        /// Optional request id.
+        id: Option<String>,
+        /// Topic name.
+        topic: String,
+    },
+    /// Call a service.
+    CallService {
+        /// Optional request id, echoed in the response.
+        id: Option<String>,
+        /// Service name.
+        service: String,
+        /// Service type, looked up in the graph when missing.
+        #[serde(rename = "type")]
+        type_name: Option<String>,
+        /// Request as JSON.
+        #[serde(default)]
+        args: Option<Value>,
+    },
+}
+
+impl Request {
+    /// Id of the request, if any.
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Request::Advertise { id, .. }
+            | Request::Unadvertise { id, .. }
+            | Request::Publish { id, .. }
+            | Request::Subscribe { id, .. }
+            | Request::Unsubscribe { id, .. }
+            | Request::CallService { id, .. } => id.as_deref(),
+        }
+    }
+}
+
+/// Operation sent to a client.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Reply {
+    /// Message received on a subscribed topic.
+    Publish {
+        /// Topic name.
+        topic: String,
+        /// Message as JSON.
+        msg: Value,
+    },
+    /// Response to a `call_service`.
+    ServiceResponse {
+        /// Id of the `call_service` request.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        /// Service name.
+        service: String,
+        /// Response as JSON, or the error message when `result` is false.
+        values: Value,
+        /// Whether the call succeeded.
+        result: bool,
+    },
+    /// Error report.
+    Status {
+        /// Id of the failed request.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        /// Status level, always `"error"`.
+        level: &'static str,
+        /// Description of the error.
+        msg: String,
+    },
+}
+
+impl Reply {
+    /// Build an error status.
+    pub fn error(id: Option<&str>, msg: impl std::fmt::Display) -> Self {
+        Reply::Status {
+            id: id.map(str::to_string),
+            level: "error",
+            msg: msg.to_string(),
+        }
+    }
+}
+
+/// Normalize a rosbridge type name to its fully qualified ROS2 form.
+///
+/// rosbridge clients often omit the interface kind: `"std_msgs/String"`
+/// becomes `"std_msgs/msg/String"` for `interface = "msg"`.
+pub fn normalize_type_name(type_name: &str, interface: &str) -> Option<String> {
+    let parts: Vec<&str> = type_name.split('/').collect();
+    match parts.as_slice() {
+        [pkg, name] if !pkg.is_empty() && !name.is_empty() => {
+            Some(format!("{pkg}/{interface}/{name}"))
+        }
+        [pkg, iface, name] if !pkg.is_empty() && *iface == interface && !name.is_empty() => {
+            Some(type_name.to_string())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_requests() {
+        let req: Request = serde_json::from_value(json!({
+            "op": "subscribe",
+            "id": "sub1",
+            "topic": "/chatter",
+            "type": "std_msgs/String",
+            "throttle_rate": 100
+        }))
+        .unwrap();
+        assert_eq!(
+            req,
+            Request::Subscribe {
+                id: Some("sub1".into()),
+                topic: "/chatter".into(),
+                type_name: Some("std_msgs/String".into()),
+                throttle_rate: 100,
+            }
+        );
+        assert_eq!(req.id(), Some("sub1"));
+
+        let req: Request =
+            serde_json::from_value(json!({"op": "call_service", "service": "/add_two_ints"}))
+                .unwrap();
+        assert_eq!(
+            req,
+            Request::CallService {
+                id: None,
+                service: "/add_two_ints".into(),
+                type_name: None,
+                args: None,
+            }
+        );
+
+        assert!(serde_json::from_value::<Request>(json!({"op": "fragment"})).is_err());
+        assert!(serde_json::from_value::<Request>(json!({"op": "publish"})).is_err());
+    }
+
+    #[test]
+    fn test_serialize_replies() {
+        let reply = Reply::Publish {
+            topic: "/chatter".into(),
+            msg: json!({"data": "hello"}),
+        };
+        assert_eq!(
+            serde_json::to_value(&reply).unwrap(),
+            json!({"op": "publish", "topic": "/chatter", "msg": {"data": "hello"}})
+        );
+        assert_eq!(
+            serde_json::to_value(Reply::error(None, "unknown topic")).unwrap(),
+            json!({"op": "status", "level": "error", "msg": "unknown topic"})
+        );
+    }
+
+    #[test]
+    fn test_normalize_type_name() {
+        assert_eq!(
+            normalize_type_name("std_msgs/String", "msg").as_deref(),
+            Some("std_msgs/msg/String")
+        );
+        assert_eq!(
+            normalize_type_name("std_msgs/msg/String", "msg").as_deref(),
+            Some("std_msgs/msg/String")
+        );
+        assert_eq!(normalize_type_name("std_msgs/msg/String", "srv"), None);
+        assert_eq!(normalize_type_name("String", "msg"), None);
+    }
+}
@@ -2,12 +2,29 @@
 //!
 //! Provides a unified way to wait on multiple ROS2 entities (subscribers, servers, timers)
 //! and dispatch callbacks when events occur.
+//!
+//! On Unix, file descriptors such as serial ports or sockets can be waited on
+//! with [`Selector::z_add_fd`]: the selector sleeps in `poll(2)` on them
+//! between two polls of the entities.
+//!
+//! In tests, the timers can be driven by a [`VirtualClock`] with
+//! [`Selector::z_set_virtual_clock`].
 
 use crate::{
     error::Result, parameter::ParameterServer as ZenohParameterServer, service::server::Server,
     topic::subscriber::Subscriber,
 };
-use oxidros_core::{Message, TypeSupport, parameter::Parameters};
+#[cfg(unix)]
+use oxidros_core::selector::{FdInterest, FdReadiness, check_fd, poll_fds};
+use oxidros_core::{
+    Message, TypeSupport,
+    parameter::Parameters,
+    selector::{PanicContainment, PanicPolicy},
+    spans, targets,
+    time::VirtualClock,
+};
+#[cfg(unix)]
+use std::{collections::BTreeMap, os::fd::RawFd};
 use std::{
     collections::{BTreeSet, HashMap},
     sync::atomic::{AtomicU64, Ordering},
@@ -17,15 +34,56 @@ use std::{
 /// Timer ID counter.
 static TIMER_ID_COUNTER: AtomicU64 = AtomicU64::new(1);
 
+/// File descriptor ID counter.
+#[cfg(unix)]
+static FD_ID_COUNTER: AtomicU64 = AtomicU64::new(1);
+
 /// A timer entry.
 struct Timer {
+    /// Name reported in tracing spans.
+    name: String,
     period: Duration,
-    next_fire: Instant,
+    /// Time of the timer clock at which the timer fires next.
+    next_fire: Duration,
     handler: Box<dyn FnMut()>,
     /// If true, the timer fires once and is removed.
     one_shot: bool,
 }
 
+/// A file descriptor entry.
+#[cfg(unix)]
+struct FdHandler {
+    fd: RawFd,
+    interest: FdInterest,
+    handler: Box<dyn FnMut(FdReadiness)>,
+}
+
+/// Clock driving the timers.
+enum TimerClock {
+    /// Monotonic time elapsed since the given instant.
+    Monotonic(Instant),
+    /// Manually advanced time.
+    Virtual(VirtualClock),
+}
+
+impl TimerClock {
+    fn now(&self) -> Duration {
+        match self {
+            TimerClock::Monotonic(start) => start.elapsed(),
+            TimerClock::Virtual(clock) => clock.now(),
+        }
+    }
+
+    /// Real time to sleep until the clock reaches `time`, `None` if it does
+    /// not advance by itself.
+    fn sleep_time(&self, time: Duration) -> Option<Duration> {
+        match self {
+            TimerClock::Monotonic(_) => Some(time.saturating_sub(self.now())),
+            TimerClock::Virtual(_) => None,
+        }
+    }
+}
+
 /// Callback type for parameter server updates.
 type ParameterServerCallback = Box<dyn FnMut(&mut Parameters, BTreeSet<String>)>;
 
@@ -58,14 +116,23 @@ type ParameterServerCallback = Box<dyn FnMut(&mut Parameters, BTreeSet<String>)>
 /// }
 /// ```
 pub struct Selector {
-    /// Subscriber handlers that poll and process messages.
-    subscriber_handlers: Vec<Box<dyn FnMut() -> bool>>,
-    /// Server handlers that poll and process requests.
-    server_handlers: Vec<Box<dyn FnMut() -> bool>>,
+    /// Subscriber handlers that poll and process messages, with the name of
+    /// their entity.
+    subscriber_handlers: Vec<(String, Box<dyn FnMut() -> bool>)>,
+    /// Server handlers that poll and process requests, with the name of their
+    /// entity.
+    server_handlers: Vec<(String, Box<dyn FnMut() -> bool>)>,
     /// Parameter server handler (only one per Selector).
     parameter_server_handler: Option<Box<dyn FnMut() -> bool>>,
     /// Timers with their next fire time.
     timers: HashMap<u64, Timer>,
+    /// Clock of the fire times of the timers.
+    clock: TimerClock,
+    /// File descriptors waited on.
+    #[cfg(unix)]
+    fds: BTreeMap<u64, FdHandler>,
+    /// Panics caught in the callbacks.
+    panics: PanicContainment,
 }
 
 impl Selector {
@@ -76,6 +143,10 @@ impl Selector {
             server_handlers: Vec::new(),
             parameter_server_handler: None,
             timers: HashMap::new(),
+            clock: TimerClock::Monotonic(Instant::now()),
+            #[cfg(unix)]
+            fds: BTreeMap::new(),
+            panics: PanicContainment::default(),
         }
     }
 
@@ -87,17 +158,27 @@ impl Selector {
         subscriber: Subscriber<T>,
         mut handler: Box<dyn FnMut(Message<T>)>,
     ) -> bool {
+        let entity = format!("subscriber {}", subscriber.fully_qualified_topic_name());
+
         // Create a closure that tries to receive and call the handler
         let poll_fn = Box::new(move || -> bool {
-            match subscriber.z_try_recv() {
-                Ok(Some(msg)) => {
+            match subscriber.z_try_recv_with_context() {
+                Ok(Some((msg, trace_context))) => {
+                    let span = tracing::debug_span!(
+                        target: targets::ZENOH_SUBSCRIBER,
+                        spans::SUBSCRIBER_CALLBACK,
+                        topic = %subscriber.fully_qualified_topic_name(),
+                        seq = msg.info.sequence_number,
+                    );
+                    trace_context.link_span(&span);
+                    let _enter = span.enter();
                     handler(msg);
                     true
                 }
                 _ => false,
             }
         });
-        self.subscriber_handlers.push(poll_fn);
+        self.subscriber_handlers.push((entity, poll_fn));
         true
     }
 
@@ -116,25 +197,49 @@ impl Selector {
         T::Request: oxidros_core::TypeSupport,
         T::Response: oxidros_core::TypeSupport,
     {
+        let service_name: String = server
+            .fully_qualified_service_name()
+            .map(|name| name.into_owned())
+            .unwrap_or_default();
+        let label = format!("server {service_name}");
+
         // Create a closure that tries to receive and call the handler
         let poll_fn = Box::new(move || -> bool {
             match server.z_try_recv() {
                 Ok(Some(service_req)) => {
+                    let span = tracing::debug_span!(
+                        target: targets::ZENOH_SERVICE,
+                        spans::SERVICE_CALLBACK,
+                        service = %service_name,
+                        seq = service_req.request.info.sequence_number,
+                    );
+                    service_req.trace_context().link_span(&span);
+                    let _enter = span.enter();
                     let (sender, request) = service_req.split();
                     let response = handler(request);
                     if let Err(e) = sender.send(&response) {
-                        eprintln!("Failed to send service response: {e}");
+                        tracing::warn!(
+                            target: targets::ZENOH_SERVICE,
+                            service = %service_name,
+                            error = %e,
+                            "Failed to send service response"
+                        );
                     }
                     true
                 }
                 Ok(None) => false,
                 Err(e) => {
-                    eprintln!("Failed to receive service request: {e}");
+                    tracing::warn!(
+                        target: targets::ZENOH_SERVICE,
+                        service = %service_name,
+                        error = %e,
+                        "Failed to receive service request"
+                    );
                     false
                 }
             }
         });
-        self.server_handlers.push(poll_fn);
+        self.server_handlers.push((label, poll_fn));
         true
     }
 
@@ -160,6 +265,9 @@ impl Selector {
             let mut guard = params.write();
             let updated = guard.take_updated();
             if !updated.is_empty() {
+                let _enter =
+                    tracing::debug_span!(target: targets::ZENOH, spans::PARAMETER_CALLBACK)
+                        .entered();
                 handler(&mut guard, updated);
             }
 
@@ -175,8 +283,9 @@ impl Selector {
     pub fn z_add_timer(&mut self, duration: Duration, handler: Box<dyn FnMut()>) -> u64 {
         let id = TIMER_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
         let timer = Timer {
+            name: "one_shot".to_string(),
             period: duration,
-            next_fire: Instant::now() + duration,
+            next_fire: self.clock.now() + duration,
             handler,
             one_shot: true,
         };
@@ -191,14 +300,15 @@ impl Selector {
     /// Returns a timer ID that can be used to remove the timer.
     pub fn z_add_wall_timer(
         &mut self,
-        _name: &str,
+        name: &str,
         period: Duration,
         handler: Box<dyn FnMut()>,
     ) -> u64 {
         let id = TIMER_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
         let timer = Timer {
+            name: name.to_string(),
             period,
-            next_fire: Instant::now() + period,
+            next_fire: self.clock.now() + period,
             handler,
             one_shot: false,
         };
@@ -211,7 +321,91 @@ impl Selector {
         self.timers.remove(&id);
     }
 
+    /// Drive the timers by `clock` instead of the monotonic clock.
+    ///
+    /// The timers already added keep their remaining time. A timer fires in
+    /// [`z_wait`](Self::z_wait) once `clock` has been advanced past its
+    /// deadline, while the timeout of [`z_wait_timeout`](Self::z_wait_timeout)
+    /// stays in real time.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use oxidros_core::time::VirtualClock;
+    ///
+    /// let clock = VirtualClock::new();
+    /// selector.z_set_virtual_clock(clock.clone());
+    /// selector.z_add_timer(Duration::from_secs(30), Box::new(|| println!("timeout")));
+    ///
+    /// clock.advance(Duration::from_secs(30));
+    /// selector.z_wait_timeout(Duration::ZERO)?; // prints "timeout"
+    /// ```
+    pub fn z_set_virtual_clock(&mut self, clock: VirtualClock) {
+        let now = self.clock.now();
+        let virtual_now = clock.now();
+        for timer in self.timers.values_mut() {
+            timer.next_fire = virtual_now + timer.next_fire.saturating_sub(now);
+        }
+        self.clock = TimerClock::Virtual(clock);
+    }
+
+    /// Set what happens when a callback panics.
+    ///
+    /// The panic is always caught and the entity of the callback removed;
+    /// see [`PanicPolicy`] for what the following waits do.
+    pub fn z_set_panic_policy(&mut self, policy: PanicPolicy) {
+        self.panics.set_policy(policy);
+    }
+
+    /// What happens when a callback panics.
+    pub fn panic_policy(&self) -> PanicPolicy {
+        self.panics.policy()
+    }
+
+    /// Wait on a file descriptor, e.g. of a serial port or a CAN socket.
+    ///
+    /// The handler is called with the readiness of `fd` when one of the events
+    /// of `interest` occurs, as long as it stays ready. The selector does not
+    /// take ownership of `fd`, which must stay open until it is removed.
+    ///
+    /// Returns an ID that can be used to remove the file descriptor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `fd` is not open.
+    #[cfg(unix)]
+    pub fn z_add_fd(
+        &mut self,
+        fd: RawFd,
+        interest: FdInterest,
+        handler: Box<dyn FnMut(FdReadiness)>,
+    ) -> Result<u64> {
+        check_fd(fd)?;
+        let id = FD_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+        self.fds.insert(
+            id,
+            FdHandler {
+                fd,
+                interest,
+                handler,
+            },
+        );
+        tracing::debug!(target: targets::ZENOH, fd, id, "Added file descriptor");
+        Ok(id)
+    }
+
+    /// Remove a file descriptor by ID.
+    #[cfg(unix)]
+    pub fn z_remove_fd(&mut self, id: u64) {
+        self.fds.remove(&id);
+    }
+
     /// Wait for events indefinitely.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CallbackPanicked`](crate::error::Error::CallbackPanicked)
+    /// once a callback panicked with [`PanicPolicy::Shutdown`].
     pub fn z_wait(&mut self) -> Result<()> {
         self.wait_timeout_internal(None)
     }
@@ -224,34 +418,45 @@ impl Selector {
     }
 
     fn wait_timeout_internal(&mut self, timeout: Option<Duration>) -> Result<()> {
+        self.panics.check()?;
         let start = Instant::now();
         let deadline = timeout.map(|t| start + t);
         let poll_interval = Duration::from_millis(10);
 
         loop {
-            // Poll all subscribers
-            for handler in &mut self.subscriber_handlers {
-                handler();
-            }
+            let _enter =
+                tracing::debug_span!(target: targets::ZENOH, spans::SELECTOR_WAIT).entered();
 
-            // Poll all service servers
-            for handler in &mut self.server_handlers {
-                handler();
-            }
+            // Poll all subscribers and service servers, removing those whose
+            // callback panicked
+            let panics = &self.panics;
+            self.subscriber_handlers
+                .retain_mut(|(entity, handler)| panics.call(entity, handler).is_some());
+            self.server_handlers
+                .retain_mut(|(entity, handler)| panics.call(entity, handler).is_some());
 
             // Poll parameter server
-            if let Some(ref mut handler) = self.parameter_server_handler {
-                handler();
+            if let Some(ref mut handler) = self.parameter_server_handler
+                && panics.call("parameter server", handler).is_none()
+            {
+                self.parameter_server_handler = None;
             }
 
             // Process expired timers
-            let now = Instant::now();
+            let now = self.clock.now();
             let mut timers_to_remove = Vec::new();
             for (&id, timer) in self.timers.iter_mut() {
                 if now >= timer.next_fire {
-                    (timer.handler)();
-                    if timer.one_shot {
-                        // One-shot timer: mark for removal
+                    let span = tracing::debug_span!(
+                        target: targets::ZENOH,
+                        spans::TIMER_CALLBACK,
+                        timer = %timer.name,
+                    );
+                    let fired = self.panics.call(&format!("timer {}", timer.name), || {
+                        span.in_scope(|| (timer.handler)())
+                    });
+                    if timer.one_shot || fired.is_none() {
+                        // One-shot or panicked timer: mark for removal
                         timers_to_remove.push(id);
                     } else {
                         // Periodic timer: reschedule
@@ -263,6 +468,7 @@ impl Selector {
             for id in timers_to_remove {
                 self.timers.remove(&id);
             }
+            self.panics.check()?;
 
             // Check if we've exceeded the timeout
             if let Some(d) = deadline
@@ -272,20 +478,26 @@ impl Selector {
             }
 
             // Calculate sleep time
-            let next_timer = self.timers.values().map(|t| t.next_fire).min();
-            let sleep_until = match (deadline, next_timer) {
-                (Some(d), Some(t)) => Some(d.min(t)),
-                (Some(d), None) => Some(d),
-                (None, Some(t)) => Some(t),
-                (None, None) => None,
-            };
-
-            let sleep_time = sleep_until
-                .map(|s| s.saturating_duration_since(Instant::now()))
-                .map(|d| d.min(poll_interval))
-                .unwrap_or(poll_interval);
-
-            if sleep_time > Duration::ZERO {
+            let until_timer = self
+                .timers
+                .values()
+                .map(|t| t.next_fire)
+                .min()
+                .and_then(|t| self.clock.sleep_time(t));
+            let until_deadline = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+            let sleep_time = until_timer
+                .into_iter()
+                .chain(until_deadline)
+                .min()
+                .map_or(poll_interval, |d| d.min(poll_interval));
+
+            // Sleep in poll(2) if file descriptors are waited on
+            #[cfg(unix)]
+            let slept = self.wait_fds(sleep_time)?;
+            #[cfg(not(unix))]
+            let slept = false;
+
+            if !slept && sleep_time > Duration::ZERO {
                 std::thread::sleep(sleep_time);
             }
 
@@ -296,7 +508,43 @@ impl Selector {
             }
         }
 
-        Ok(())
+        self.panics.check()
+    }
+
+    /// Wait up to `timeout` for the file descriptors and call the handlers of
+    /// the ready ones.
+    ///
+    /// Returns `false` without waiting if there is no file descriptor.
+    #[cfg(unix)]
+    fn wait_fds(&mut self, timeout: Duration) -> Result<bool> {
+        if self.fds.is_empty() {
+            return Ok(false);
+        }
+        let fds: Vec<_> = self.fds.values().map(|h| (h.fd, h.interest)).collect();
+        let ready = poll_fds(&fds, Some(timeout))?;
+        let mut panicked = Vec::new();
+        for ((&id, fd), readiness) in self.fds.iter_mut().zip(ready) {
+            if readiness.is_ready() {
+                let _enter = tracing::debug_span!(
+                    target: targets::ZENOH,
+                    spans::FD_CALLBACK,
+                    fd = fd.fd,
+                )
+                .entered();
+                let entity = format!("fd {}", fd.fd);
+                if self
+                    .panics
+                    .call(&entity, || (fd.handler)(readiness))
+                    .is_none()
+                {
+                    panicked.push(id);
+                }
+            }
+        }
+        for id in panicked {
+            self.fds.remove(&id);
+        }
+        Ok(true)
     }
 }
 
@@ -314,6 +562,27 @@ pub struct ActionClient<T>(std::marker::PhantomData<T>);
 /// Stub action goal handle (not supported in Zenoh).
 pub struct ActionGoalHandle<T>(std::marker::PhantomData<T>);
 
+impl<T: oxidros_core::ActionMsg> oxidros_core::api::RosActionClient<T> for ActionClient<T> {
+    type CancelRecv<'a>
+        = std::convert::Infallible
+    where
+        Self: 'a;
+
+    fn cancel_all_goals(&mut self) -> oxidros_core::Result<Self::CancelRecv<'_>> {
+        Err(oxidros_core::Error::NotImplemented {
+            feature: "action_client".into(),
+            reason: "Zenoh backend does not support actions yet".into(),
+        })
+    }
+
+    fn cancel_goals_before(
+        &mut self,
+        _stamp: Duration,
+    ) -> oxidros_core::Result<Self::CancelRecv<'_>> {
+        self.cancel_all_goals()
+    }
+}
+
 impl oxidros_core::api::RosSelector for Selector {
     type Subscriber<T: TypeSupport + 'static> = Subscriber<T>;
     type Server<T: oxidros_core::ServiceMsg + 'static> = Server<T>;
@@ -362,6 +631,15 @@ impl oxidros_core::api::RosSelector for Selector {
         self.remove_timer(id)
     }
 
+    fn set_virtual_clock(&mut self, clock: VirtualClock) -> oxidros_core::Result<()> {
+        self.z_set_virtual_clock(clock);
+        Ok(())
+    }
+
+    fn set_panic_policy(&mut self, policy: PanicPolicy) {
+        self.z_set_panic_policy(policy)
+    }
+
     fn add_action_server<T, GR, A, CR>(
         &mut self,
         _server: Self::ActionServer<T>,
@@ -399,4 +677,102 @@ impl oxidros_core::api::RosSelector for Selector {
     fn wait_timeout(&mut self, timeout: Duration) -> oxidros_core::Result<bool> {
         self.z_wait_timeout(timeout)
     }
+
+    #[cfg(unix)]
+    fn add_fd(
+        &mut self,
+        fd: RawFd,
+        interest: FdInterest,
+        handler: Box<dyn FnMut(FdReadiness)>,
+    ) -> oxidros_core::Result<u64> {
+        self.z_add_fd(fd, interest, handler)
+    }
+
+    #[cfg(unix)]
+    fn remove_fd(&mut self, id: u64) {
+        self.z_remove_fd(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn test_virtual_clock_timers() {
+        let mut selector = Selector::new();
+        let clock = VirtualClock::new();
+
+        let one_shot = Rc::new(Cell::new(0));
+        let fired = one_shot.clone();
+        selector.z_add_timer(
+            Duration::from_secs(10),
+            Box::new(move || fired.set(fired.get() + 1)),
+        );
+        selector.z_set_virtual_clock(clock.clone());
+
+        let periodic = Rc::new(Cell::new(0));
+        let fired = periodic.clone();
+        selector.z_add_wall_timer(
+            "periodic",
+            Duration::from_secs(1),
+            Box::new(move || fired.set(fired.get() + 1)),
+        );
+
+        // Real time does not fire the timers
+        selector.z_wait_timeout(Duration::from_millis(20)).unwrap();
+        assert_eq!((one_shot.get(), periodic.get()), (0, 0));
+
+        clock.advance(Duration::from_secs(1));
+        selector.z_wait_timeout(Duration::ZERO).unwrap();
+        assert_eq!((one_shot.get(), periodic.get()), (0, 1));
+
+        clock.advance(Duration::from_secs(9));
+        selector.z_wait_timeout(Duration::ZERO).unwrap();
+        assert_eq!((one_shot.get(), periodic.get()), (1, 2));
+
+        clock.advance(Duration::from_secs(10));
+        selector.z_wait_timeout(Duration::ZERO).unwrap();
+        assert_eq!((one_shot.get(), periodic.get()), (1, 3));
+    }
+    #[test]
+    fn test_panicking_timer() {
+        let mut selector = Selector::new();
+        let clock = VirtualClock::new();
+        selector.z_set_virtual_clock(clock.clone());
+
+        let fired = Rc::new(Cell::new(0));
+        let count = fired.clone();
+        selector.z_add_wall_timer(
+            "counter",
+            Duration::from_secs(1),
+            Box::new(move || count.set(count.get() + 1)),
+        );
+        selector.z_add_wall_timer(
+            "panicking",
+            Duration::from_secs(1),
+            Box::new(|| panic!("timer failed")),
+        );
+
+        // The panicking timer is removed, the other one keeps firing
+        assert_eq!(selector.panic_policy(), PanicPolicy::RemoveEntity);
+        clock.advance(Duration::from_secs(1));
+        selector.z_wait_timeout(Duration::ZERO).unwrap();
+        assert_eq!((fired.get(), selector.timers.len()), (1, 1));
+
+        selector.z_set_panic_policy(PanicPolicy::Shutdown);
+        selector.z_add_wall_timer(
+            "panicking",
+            Duration::from_secs(1),
+            Box::new(|| panic!("timer failed")),
+        );
+        clock.advance(Duration::from_secs(1));
+        assert!(matches!(
+            selector.z_wait_timeout(Duration::ZERO),
+            Err(oxidros_core::Error::CallbackPanicked { .. })
+        ));
+        assert!(selector.z_wait_timeout(Duration::ZERO).is_err());
+        assert_eq!(fired.get(), 2);
+    }
 }
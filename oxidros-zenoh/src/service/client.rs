@@ -6,13 +6,20 @@
 
 use crate::{
     attachment::{Attachment, GID_SIZE, generate_gid},
-    error::{Error, Result},
+    error::{Error, Result, entity_context},
     keyexpr::{EntityKind, liveliness_entity_keyexpr, topic_keyexpr},
     node::Node,
 };
-use oxidros_core::{Message, TypeSupport, qos::Profile, targets};
+use oxidros_core::{
+    Message, TypeInfo, TypeSupport,
+    error::{Operation, ResultExt},
+    qos::Profile,
+    stats::{self, EntityToken},
+    targets,
+};
 use std::{
     borrow::Cow,
+    fmt,
     marker::PhantomData,
     sync::{Arc, atomic::AtomicI64},
 };
@@ -42,10 +49,14 @@ pub struct Client<T: oxidros_core::ServiceMsg> {
     key_expr: String,
     /// Client GID.
     gid: [u8; GID_SIZE],
+    /// Advertised type name and hash.
+    type_info: TypeInfo,
     /// Sequence number counter.
     sequence_number: AtomicI64,
     /// Liveliness token.
     _liveliness_token: zenoh::liveliness::LivelinessToken,
+    /// Keeps the client counted by the node.
+    _entity: EntityToken,
     /// Phantom data for service type.
     _phantom: PhantomData<T>,
 }
@@ -116,11 +127,13 @@ where
         );
 
         Ok(Client {
+            _entity: node.entities().register(stats::EntityKind::Client),
             node,
             service_name: service_name.to_string(),
             fq_service_name: fq_service_name.to_string(),
             key_expr,
             gid,
+            type_info: TypeInfo::new(type_name, type_hash),
             sequence_number: AtomicI64::new(0),
             _liveliness_token: liveliness_token,
             _phantom: PhantomData,
@@ -148,6 +161,12 @@ where
         &self.gid
     }
 
+    /// Get the type name and hash advertised in the key expression and the
+    /// liveliness token of the client.
+    pub fn type_info(&self) -> &TypeInfo {
+        &self.type_info
+    }
+
     /// Check if the service is available.
     pub fn z_is_service_available(&self) -> bool {
         self.node
@@ -158,6 +177,9 @@ where
 
     /// Send a request and wait for a response.
     ///
+    /// Each call receives the replies on its own channel, so dropping the
+    /// future abandons the request without affecting later calls.
+    ///
     /// # Arguments
     ///
     /// * `request` - The request message
@@ -177,7 +199,7 @@ where
             .sequence_number
             .fetch_add(1, std::sync::atomic::Ordering::AcqRel);
         // Create attachment
-        let attachment = Attachment::new(seq, self.gid);
+        let attachment = Attachment::new(seq, self.gid).with_current_context();
         let attachment_bytes = attachment.to_bytes();
         // Send query
         let replies = self
@@ -192,7 +214,17 @@ where
 
         // Wait for reply with matching sequence number
         loop {
-            let reply = replies.recv_async().await?;
+            let reply = match replies.recv_async().await {
+                Ok(reply) => reply,
+                Err(e) => {
+                    // The query timed out before a matching reply
+                    self.node
+                        .context()
+                        .transport_monitor()
+                        .query_timeout(&self.fq_service_name);
+                    return Err(e.into());
+                }
+            };
             let sample = reply.result().map_err(|e| Error::Zenoh(format!("{e}")))?;
             // Parse response attachment (required by protocol)
             let attachment_bytes = sample.attachment().ok_or(Error::MissingAttachment)?;
@@ -221,6 +253,15 @@ where
     }
 }
 
+impl<T: oxidros_core::ServiceMsg> fmt::Debug for Client<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("service", &self.fq_service_name)
+            .field("type_info", &self.type_info)
+            .finish_non_exhaustive()
+    }
+}
+
 // ============================================================================
 // RosClient trait implementation
 // ============================================================================
@@ -237,7 +278,8 @@ where
         self.z_is_service_available()
     }
     async fn call(&mut self, request: &T::Request) -> Result<Message<T::Response>> {
-        self.z_call(request).await
+        let result = self.z_call(request).await;
+        result.with_context(|| entity_context(&self.node, Operation::Call, &self.fq_service_name))
     }
     async fn call_with_retry(
         &mut self,
@@ -255,8 +297,18 @@ where
         loop {
             match time::timeout(timeout, Self::z_call(self, request)).await {
                 Ok(Ok(response)) => return Ok(response),
-                Ok(Err(e)) => return Err(e),
+                Ok(Err(e)) => {
+                    return Err(e.with_context(entity_context(
+                        &self.node,
+                        Operation::Call,
+                        &self.fq_service_name,
+                    )));
+                }
                 Err(_) => {
+                    self.node
+                        .context()
+                        .transport_monitor()
+                        .query_timeout(&self.fq_service_name);
                     tracing::warn!("Service {} call timeout, retrying...", T::type_name());
                 }
             }
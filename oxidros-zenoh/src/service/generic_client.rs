@@ -0,0 +1,378 @@
+//! Service client with runtime type information.
+//!
+//! A [`GenericClient`] calls a service whose type is only known at runtime,
+//! e.g. from a service type string typed by a user. Requests and responses
+//! are [`DynamicMessage`]s encoded/decoded from their [`TypeDescriptionMsg`].
+//!
+//! The service type hash is not known up front, so it is taken from the
+//! server's liveliness token in the graph cache on the first call. The
+//! client's own liveliness token is declared at that point.
+
+use crate::{
+    attachment::{Attachment, GID_SIZE, generate_gid},
+    error::{Error, Result},
+    keyexpr::{EntityKind, liveliness_entity_keyexpr, topic_keyexpr},
+    node::Node,
+};
+use oxidros_core::{
+    Message,
+    qos::Profile,
+    stats::{self, EntityToken},
+    targets,
+    types::TypeDescriptionMsg,
+};
+use oxidros_dynamic::DynamicMessage;
+use parking_lot::Mutex;
+use std::sync::{
+    Arc,
+    atomic::{AtomicI64, Ordering},
+};
+use zenoh::query::QueryTarget;
+use zenoh::{Wait, bytes::ZBytes};
+
+/// Key expression and liveliness token, available once the server is discovered.
+struct ResolvedService {
+    /// Key expression for queries.
+    key_expr: String,
+    /// Liveliness token.
+    _liveliness_token: zenoh::liveliness::LivelinessToken,
+}
+
+/// Service client using runtime type information.
+///
+/// # Example
+///
+/// ```ignore
+/// let client = node.create_generic_client(
+///     "add_two_ints",
+///     "example_interfaces/srv/AddTwoInts",
+///     request_desc,
+///     response_desc,
+///     None,
+/// )?;
+///
+/// let request = client.new_request(serde_json::json!({"a": 1, "b": 2}));
+/// let response = client.call(&request).await?;
+/// println!("{}", response.value());
+/// ```
+pub struct GenericClient {
+    /// Parent node.
+    node: Arc<Node>,
+    /// Service name.
+    service_name: String,
+    /// Fully qualified service name.
+    fq_service_name: String,
+    /// ROS2 service type name (e.g. `example_interfaces/srv/AddTwoInts`).
+    service_type: String,
+    /// DDS service type name (e.g. `example_interfaces::srv::dds_::AddTwoInts_`).
+    dds_type_name: String,
+    /// Request type description.
+    request_desc: Arc<TypeDescriptionMsg>,
+    /// Response type description.
+    response_desc: Arc<TypeDescriptionMsg>,
+    /// QoS profile.
+    qos: Profile,
+    /// Client GID.
+    gid: [u8; GID_SIZE],
+    /// Entity ID within the node.
+    entity_id: u32,
+    /// Sequence number counter.
+    sequence_number: AtomicI64,
+    /// Resolved key expression and liveliness token.
+    resolved: Mutex<Option<ResolvedService>>,
+    /// Keeps the client counted by the node.
+    _entity: EntityToken,
+}
+
+impl GenericClient {
+    /// Create a new generic service client.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - Parent node
+    /// * `service_name` - Original service name (for display)
+    /// * `fq_service_name` - Fully qualified service name (already expanded and remapped)
+    /// * `service_type` - ROS2 service type name (e.g. `example_interfaces/srv/AddTwoInts`)
+    /// * `request_desc` - Type description of the request
+    /// * `response_desc` - Type description of the response
+    /// * `qos` - QoS profile
+    pub(crate) fn new(
+        node: Arc<Node>,
+        service_name: &str,
+        fq_service_name: &str,
+        service_type: &str,
+        request_desc: TypeDescriptionMsg,
+        response_desc: TypeDescriptionMsg,
+        qos: Profile,
+    ) -> Result<Self> {
+        let dds_type_name = ros_to_dds_type_name(service_type).ok_or_else(|| {
+            Error::InvalidConfig(format!(
+                "Invalid service type '{service_type}', expected '<package>/srv/<Name>'"
+            ))
+        })?;
+
+        tracing::debug!(
+            target: targets::ZENOH,
+            service = %service_name,
+            fq_service = %fq_service_name,
+            service_type = %service_type,
+            "Generic service client created"
+        );
+
+        Ok(GenericClient {
+            service_name: service_name.to_string(),
+            fq_service_name: fq_service_name.to_string(),
+            service_type: service_type.to_string(),
+            dds_type_name,
+            request_desc: Arc::new(request_desc),
+            response_desc: Arc::new(response_desc),
+            qos,
+            gid: generate_gid(),
+            entity_id: node.allocate_entity_id(),
+            sequence_number: AtomicI64::new(0),
+            resolved: Mutex::new(None),
+            _entity: node.entities().register(stats::EntityKind::Client),
+            node,
+        })
+    }
+
+    /// Get the service name.
+    pub fn service_name(&self) -> &str {
+        &self.service_name
+    }
+
+    /// Get the fully qualified service name.
+    pub fn fully_qualified_service_name(&self) -> &str {
+        &self.fq_service_name
+    }
+
+    /// Get the ROS2 service type name.
+    pub fn service_type(&self) -> &str {
+        &self.service_type
+    }
+
+    /// Get the request type description.
+    pub fn request_description(&self) -> &Arc<TypeDescriptionMsg> {
+        &self.request_desc
+    }
+
+    /// Get the response type description.
+    pub fn response_description(&self) -> &Arc<TypeDescriptionMsg> {
+        &self.response_desc
+    }
+
+    /// Get the client GID.
+    pub fn gid(&self) -> &[u8; GID_SIZE] {
+        &self.gid
+    }
+
+    /// Get the parent node.
+    pub fn node(&self) -> &Arc<Node> {
+        &self.node
+    }
+
+    /// Check if the service is available.
+    pub fn is_service_available(&self) -> bool {
+        self.node
+            .context()
+            .graph_cache()
+            .is_service_available(&self.fq_service_name)
+    }
+
+    /// Build a request message of this client's request type.
+    pub fn new_request(&self, value: serde_json::Value) -> DynamicMessage {
+        DynamicMessage::new(self.request_desc.clone(), value)
+    }
+
+    /// Send a request and wait for a response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The request is not of this client's request type
+    /// - No server is discovered, or the server has a different service type
+    /// - Encoding, the query or decoding fails
+    pub async fn call(&self, request: &DynamicMessage) -> Result<Message<DynamicMessage>> {
+        if request.type_name() != self.request_desc.type_description.type_name {
+            return Err(Error::InvalidConfig(format!(
+                "Request type '{}' does not match '{}'",
+                request.type_name(),
+                self.request_desc.type_description.type_name
+            )));
+        }
+        let key_expr = self.resolve()?;
+        // Serialize request
+        let payload = request
+            .to_cdr()
+            .map_err(|e| Error::Other(format!("Failed to encode request: {e}")))?;
+        // Increment sequence number
+        let seq = self.sequence_number.fetch_add(1, Ordering::AcqRel);
+        // Create attachment
+        let attachment = Attachment::new(seq, self.gid).with_current_context();
+        let attachment_bytes = attachment.to_bytes();
+        // Send query
+        let replies = self
+            .node
+            .context()
+            .session()
+            .get(&key_expr)
+            .payload(payload)
+            .attachment(ZBytes::from(attachment_bytes))
+            .target(QueryTarget::All) // ALL_COMPLETE equivalent
+            .await?;
+
+        // Wait for reply with matching sequence number
+        loop {
+            let reply = replies.recv_async().await?;
+            let sample = reply.result().map_err(|e| Error::Zenoh(format!("{e}")))?;
+            // Parse response attachment (required by protocol)
+            let attachment_bytes = sample.attachment().ok_or(Error::MissingAttachment)?;
+            let attachment = Attachment::from_bytes(&attachment_bytes.to_bytes())?;
+            if attachment.sequence_number != seq {
+                continue;
+            }
+            let response_bytes = sample.payload().to_bytes();
+            let response = DynamicMessage::from_cdr(&response_bytes, self.response_desc.clone())
+                .map_err(|e| Error::Other(format!("Failed to decode response: {e}")))?;
+            tracing::debug!(
+                target: targets::ZENOH,
+                service = %self.service_name,
+                seq = seq,
+                "Generic service call completed"
+            );
+            return Ok(Message::new(response, attachment.into()));
+        }
+    }
+
+    /// Send a request and wait for a response, retrying on timeout.
+    ///
+    /// Waits for the service to become available, then retries the call
+    /// each time `timeout` elapses without a response.
+    pub async fn call_with_retry(
+        &self,
+        request: &DynamicMessage,
+        timeout: std::time::Duration,
+    ) -> Result<Message<DynamicMessage>> {
+        use tokio::time;
+
+        // Wait for service availability
+        while !self.is_service_available() {
+            time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+
+        // Retry loop with timeout
+        loop {
+            match time::timeout(timeout, self.call(request)).await {
+                Ok(result) => return result,
+                Err(_) => {
+                    tracing::warn!(
+                        target: targets::ZENOH_SERVICE,
+                        service = %self.fq_service_name,
+                        service_type = %self.service_type,
+                        "Generic service call timeout, retrying"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Look up the server's type hash and declare the client liveliness token.
+    ///
+    /// Returns the key expression to query. The result is cached after the
+    /// first successful lookup.
+    fn resolve(&self) -> Result<String> {
+        let mut resolved = self.resolved.lock();
+        if let Some(resolved) = resolved.as_ref() {
+            return Ok(resolved.key_expr.clone());
+        }
+
+        let graph = self.node.context().graph_cache();
+        let servers: Vec<_> = graph
+            .get_all_entities()
+            .into_iter()
+            .filter(|e| {
+                e.kind == EntityKind::ServiceServer
+                    && e.topic_name.as_deref() == Some(self.fq_service_name.as_str())
+            })
+            .collect();
+        let server = servers
+            .iter()
+            .find(|e| e.type_name.as_deref() == Some(self.dds_type_name.as_str()))
+            .ok_or_else(
+                || match servers.first().and_then(|e| e.type_name.as_deref()) {
+                    Some(found) => Error::InvalidConfig(format!(
+                        "Service '{}' has type '{}', expected '{}'",
+                        self.fq_service_name, found, self.dds_type_name
+                    )),
+                    None => Error::service_not_found(&self.fq_service_name),
+                },
+            )?;
+        let type_hash = server
+            .type_hash
+            .clone()
+            .ok_or_else(|| Error::service_not_found(&self.fq_service_name))?;
+
+        let key_expr = topic_keyexpr(
+            self.node.context().domain_id(),
+            &self.fq_service_name,
+            &self.dds_type_name,
+            &type_hash,
+        );
+
+        let token_key = liveliness_entity_keyexpr(
+            self.node.context().domain_id(),
+            self.node.context().session_id(),
+            self.node.node_id(),
+            self.entity_id,
+            EntityKind::ServiceClient,
+            self.node.enclave(),
+            &self.node.z_namespace()?,
+            &self.node.z_name()?,
+            &self.fq_service_name,
+            &self.dds_type_name,
+            &type_hash,
+            &self.qos,
+        );
+        let liveliness_token = self
+            .node
+            .context()
+            .session()
+            .liveliness()
+            .declare_token(&token_key)
+            .wait()?;
+
+        *resolved = Some(ResolvedService {
+            key_expr: key_expr.clone(),
+            _liveliness_token: liveliness_token,
+        });
+        Ok(key_expr)
+    }
+}
+
+/// Convert a ROS2 type name to its DDS form.
+///
+/// `"my_pkg/srv/MyType"` → `"my_pkg::srv::dds_::MyType_"`
+pub(crate) fn ros_to_dds_type_name(ros_name: &str) -> Option<String> {
+    let mut parts = ros_name.split('/');
+    let (pkg, iface, name) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() || pkg.is_empty() || iface.is_empty() || name.is_empty() {
+        return None;
+    }
+    Some(format!("{pkg}::{iface}::dds_::{name}_"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ros_to_dds_type_name() {
+        assert_eq!(
+            ros_to_dds_type_name("example_interfaces/srv/AddTwoInts").as_deref(),
+            Some("example_interfaces::srv::dds_::AddTwoInts_")
+        );
+        assert_eq!(ros_to_dds_type_name("AddTwoInts"), None);
+        assert_eq!(ros_to_dds_type_name("pkg//AddTwoInts"), None);
+        assert_eq!(ros_to_dds_type_name("a/b/c/d"), None);
+    }
+}
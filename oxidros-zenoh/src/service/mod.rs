@@ -1,7 +1,11 @@
 //! Service-based request/response (client/server).
 
 pub mod client;
+#[cfg(feature = "dynamic")]
+pub mod generic_client;
 pub mod server;
 
 pub use client::Client;
-pub use server::{Server, ServiceRequest};
+#[cfg(feature = "dynamic")]
+pub use generic_client::GenericClient;
+pub use server::{Server, ServerOptions, ServiceRequest, SheddingPolicy};
@@ -6,20 +6,51 @@
 
 use crate::{
     attachment::{Attachment, GID_SIZE, generate_gid},
-    error::{Error, Result},
+    error::{Error, Result, entity_context},
     keyexpr::{EntityKind, liveliness_entity_keyexpr, topic_keyexpr},
     node::Node,
+    trace_context::TraceContext,
 };
-use oxidros_core::{Message, TypeSupport, qos::Profile, targets};
-use std::{borrow::Cow, marker::PhantomData, sync::Arc};
+use oxidros_core::{
+    Message, RequestInfo, TypeInfo, TypeSupport,
+    error::{ErrorContext, Operation, ResultExt},
+    qos::Profile,
+    spans,
+    stats::{self, EntityToken},
+    targets,
+};
+use std::{
+    borrow::Cow,
+    fmt,
+    marker::PhantomData,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::Instrument;
 use zenoh::{Wait, bytes::ZBytes, query::Query};
 
+/// Default maximum number of requests waiting to be received by a server.
+const DEFAULT_MAX_PENDING: usize = 32;
+
+/// Request waiting to be received: the query, its payload, its attachment and
+/// the time at which it was received, in nanoseconds since UNIX epoch.
+type PendingRequest = (Query, Vec<u8>, Option<Vec<u8>>, i64);
+
+pub use oxidros_core::service::{ServerOptions, SheddingPolicy};
+
 /// Incoming service request with sender for response.
 pub struct ServiceRequest<T: oxidros_core::ServiceMsg> {
     /// Request data.
     pub request: Message<T::Request>,
+    /// Client GID, sequence number and timestamps.
+    info: RequestInfo,
     /// Sender for response.
     sender: RequestSender<T>,
+    /// Trace context propagated by the client.
+    trace_context: TraceContext,
 }
 
 impl<T: oxidros_core::ServiceMsg> ServiceRequest<T>
@@ -35,11 +66,25 @@ where
     pub fn split(self) -> (RequestSender<T>, Message<T::Request>) {
         (self.sender, self.request)
     }
+
+    /// Get the metadata of the request: client GID, sequence number, and the
+    /// times at which it was sent and received.
+    pub fn info(&self) -> RequestInfo {
+        self.info
+    }
+
+    /// Trace context propagated by the client.
+    ///
+    /// Use [`TraceContext::link_span`] to continue the client's trace.
+    pub fn trace_context(&self) -> &TraceContext {
+        &self.trace_context
+    }
 }
 
 /// Sender for service response.
 pub struct RequestSender<T: oxidros_core::ServiceMsg> {
     query: Query,
+    context: Arc<ErrorContext>,
     client_gid: [u8; GID_SIZE],
     sequence_number: i64,
     _phantom: PhantomData<T>,
@@ -51,15 +96,21 @@ where
 {
     /// Send a response
     pub fn send(self, response: &T::Response) -> Result<()> {
+        self.reply(response)
+            .with_context(|| ErrorContext::clone(&self.context))
+    }
+
+    fn reply(&self, response: &T::Response) -> Result<()> {
         // Serialize response
         let payload = response.to_bytes()?;
         // Create response attachment (echo back client's seq and gid)
-        let attachment = Attachment::new(self.sequence_number, self.client_gid);
+        let attachment =
+            Attachment::new(self.sequence_number, self.client_gid).with_current_context();
         let attachment_bytes = attachment.to_bytes();
         // Reply to query
         self.query
             .reply(self.query.key_expr().clone(), payload)
-            .attachment(ZBytes::from(attachment_bytes.to_vec()))
+            .attachment(ZBytes::from(attachment_bytes))
             .wait()
             .map_err(|e| Error::Zenoh(e.to_string()))?;
 
@@ -67,6 +118,122 @@ where
     }
 }
 
+/// Metadata of a request from its attachment and its receive time.
+fn request_info(attachment: &Attachment, received_ns: i64) -> RequestInfo {
+    RequestInfo {
+        client_gid: attachment.gid,
+        sequence_number: attachment.sequence_number,
+        source_timestamp_ns: attachment.timestamp_ns,
+        received_timestamp_ns: received_ns,
+    }
+}
+
+/// Decode a request, answer it with `handler` and send the response.
+fn reply_with<T, F>(query: Query, handler: &F, context: &Arc<ErrorContext>) -> Result<()>
+where
+    T: oxidros_core::ServiceMsg,
+    T::Request: TypeSupport,
+    T::Response: TypeSupport,
+    F: Fn(&T::Request) -> T::Response,
+{
+    let payload = query
+        .payload()
+        .map(|p| p.to_bytes().to_vec())
+        .unwrap_or_default();
+    let request = T::Request::from_bytes(&payload)?;
+
+    // Parse attachment (required by protocol)
+    let attachment_bytes = query
+        .attachment()
+        .map(|a| a.to_bytes().to_vec())
+        .ok_or(Error::MissingAttachment)?;
+    let attachment = Attachment::from_bytes(&attachment_bytes)?;
+
+    let sender = RequestSender::<T> {
+        query,
+        context: context.clone(),
+        client_gid: attachment.gid,
+        sequence_number: attachment.sequence_number,
+        _phantom: PhantomData,
+    };
+    sender.send(&handler(&request))
+}
+
+/// Request queue of a server, observed by [`Node::z_stats`].
+pub(crate) struct RequestQueueProbe {
+    receiver: flume::Receiver<PendingRequest>,
+    shed: Arc<AtomicU64>,
+}
+
+impl RequestQueueProbe {
+    /// Number of requests waiting to be received.
+    pub(crate) fn len(&self) -> usize {
+        self.receiver.len()
+    }
+
+    /// Number of requests rejected or dropped because the queue was full.
+    pub(crate) fn shed(&self) -> u64 {
+        self.shed.load(Ordering::Relaxed)
+    }
+}
+
+/// Build the Zenoh callback filling the request queue according to
+/// `shedding`; `reject` answers the requests refused by
+/// [`SheddingPolicy::RejectWithError`].
+fn shedding_callback<S, R>(
+    shedding: SheddingPolicy,
+    sender: flume::Sender<S>,
+    receiver: &flume::Receiver<S>,
+    shed: Arc<AtomicU64>,
+    reject: R,
+) -> Box<dyn Fn(S) + Send + Sync>
+where
+    S: Send + 'static,
+    R: Fn(S) + Send + Sync + 'static,
+{
+    match shedding {
+        SheddingPolicy::RejectWithError => Box::new(move |request| {
+            if let Err(flume::TrySendError::Full(request)) = sender.try_send(request) {
+                shed.fetch_add(1, Ordering::Relaxed);
+                reject(request);
+            }
+        }),
+        SheddingPolicy::DropOldest => {
+            let drain_receiver = receiver.clone();
+            Box::new(move |request| {
+                if sender.is_full() && drain_receiver.try_recv().is_ok() {
+                    shed.fetch_add(1, Ordering::Relaxed);
+                }
+                if sender.try_send(request).is_err() {
+                    shed.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+        }
+    }
+}
+
+/// Answer a request refused because the queue of the server is full.
+fn reject(query: Query, service: &str) {
+    tracing::warn!(
+        target: targets::ZENOH_SERVICE,
+        service = %service,
+        "Request rejected, too many pending requests"
+    );
+    if let Err(e) = query
+        .reply_err(ZBytes::from(format!(
+            "service {service} is overloaded, request rejected"
+        )))
+        .wait()
+    {
+        tracing::debug!(
+            target: targets::ZENOH_SERVICE,
+            service = %service,
+            error = %e,
+            "Failed to reject service request"
+        );
+    }
+}
+
 /// Service server.
 ///
 /// Receives requests and sends responses.
@@ -89,14 +256,24 @@ pub struct Server<T: oxidros_core::ServiceMsg> {
     service_name: String,
     /// Fully qualified service name.
     fq_service_name: String,
+    /// Context of the errors of responses.
+    response_context: Arc<ErrorContext>,
     /// Server GID.
     gid: [u8; GID_SIZE],
+    /// Advertised type name and hash.
+    type_info: TypeInfo,
     /// Request receiver channel.
-    receiver: flume::Receiver<(Query, Vec<u8>, Option<Vec<u8>>)>,
+    receiver: flume::Receiver<PendingRequest>,
+    /// Number of requests rejected or dropped because the queue was full.
+    shed: Arc<AtomicU64>,
+    /// Request queue as reported by the node statistics.
+    _queue: Arc<RequestQueueProbe>,
     /// Liveliness token.
     _liveliness_token: zenoh::liveliness::LivelinessToken,
     /// Zenoh queryable (kept alive).
     _queryable: zenoh::query::Queryable<()>,
+    /// Keeps the server counted by the node.
+    _entity: EntityToken,
     /// Phantom data for service type.
     _phantom: PhantomData<T>,
 }
@@ -114,12 +291,111 @@ where
     /// * `service_name` - Original service name (for display)
     /// * `fq_service_name` - Fully qualified service name (already expanded and remapped)
     /// * `qos` - QoS profile
+    /// * `options` - Capacity and shedding policy of the request queue
     pub(crate) fn new(
         node: Arc<Node>,
         service_name: &str,
         fq_service_name: &str,
         qos: Profile,
+        options: ServerOptions,
     ) -> Result<Self> {
+        // Create channel for incoming requests
+        let capacity = options.max_pending.unwrap_or(DEFAULT_MAX_PENDING).max(1);
+        let (sender, receiver) = flume::bounded(capacity);
+        let shed = Arc::new(AtomicU64::new(0));
+        let name = fq_service_name.to_string();
+        let on_request = shedding_callback(
+            options.shedding,
+            sender,
+            &receiver,
+            shed.clone(),
+            move |(query, ..): PendingRequest| reject(query, &name),
+        );
+
+        Self::declare(
+            node,
+            service_name,
+            fq_service_name,
+            qos,
+            receiver,
+            shed,
+            move |query: Query| {
+                let received_ns = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_nanos() as i64)
+                    .unwrap_or(0);
+
+                // Extract payload
+                let payload = query
+                    .payload()
+                    .map(|p| p.to_bytes().to_vec())
+                    .unwrap_or_default();
+
+                // Extract attachment
+                let attachment = query.attachment().map(|a| a.to_bytes().to_vec());
+
+                // Send to channel
+                on_request((query, payload, attachment, received_ns));
+            },
+        )
+    }
+
+    /// Create a service server answering each request with `handler` from the
+    /// Zenoh callback, so that it does not need to be spun.
+    ///
+    /// Requests never reach the receive queue of the server.
+    pub(crate) fn with_handler<F>(
+        node: Arc<Node>,
+        service_name: &str,
+        fq_service_name: &str,
+        qos: Profile,
+        handler: F,
+    ) -> Result<Self>
+    where
+        T: 'static,
+        F: Fn(&T::Request) -> T::Response + Send + Sync + 'static,
+    {
+        let (_, receiver) = flume::bounded(0);
+        let name = fq_service_name.to_string();
+        let context = Arc::new(entity_context(
+            &node,
+            Operation::SendResponse,
+            fq_service_name,
+        ));
+
+        Self::declare(
+            node,
+            service_name,
+            fq_service_name,
+            qos,
+            receiver,
+            Arc::new(AtomicU64::new(0)),
+            move |query: Query| {
+                if let Err(e) = reply_with::<T, F>(query, &handler, &context) {
+                    tracing::warn!(
+                        target: targets::ZENOH,
+                        service = %name,
+                        error = %e,
+                        "Failed to answer service request"
+                    );
+                }
+            },
+        )
+    }
+
+    /// Declare the queryable and the liveliness token of the server.
+    fn declare<F>(
+        node: Arc<Node>,
+        service_name: &str,
+        fq_service_name: &str,
+        qos: Profile,
+        receiver: flume::Receiver<PendingRequest>,
+        shed: Arc<AtomicU64>,
+        callback: F,
+    ) -> Result<Self>
+    where
+        F: Fn(Query) + Send + Sync + 'static,
+    {
         // Get type info
         let type_name = T::type_name();
         let type_hash = T::type_hash()?;
@@ -132,28 +408,13 @@ where
             &type_hash,
         );
 
-        // Create channel for incoming requests
-        let (sender, receiver) = flume::bounded(32);
-
         // Create Zenoh queryable
         let queryable = node
             .context()
             .session()
             .declare_queryable(&key_expr)
             .complete(true) // Service can answer all queries
-            .callback(move |query| {
-                // Extract payload
-                let payload = query
-                    .payload()
-                    .map(|p| p.to_bytes().to_vec())
-                    .unwrap_or_default();
-
-                // Extract attachment
-                let attachment = query.attachment().map(|a| a.to_bytes().to_vec());
-
-                // Send to channel
-                let _ = sender.try_send((query, payload, attachment));
-            })
+            .callback(callback)
             .wait()?;
 
         // Generate server GID
@@ -190,12 +451,27 @@ where
             "Service server created"
         );
 
+        let queue = Arc::new(RequestQueueProbe {
+            receiver: receiver.clone(),
+            shed: shed.clone(),
+        });
+        node.register_request_queue(&queue);
+
         Ok(Server {
+            _entity: node.entities().register(stats::EntityKind::Server),
+            _queue: queue,
+            response_context: Arc::new(entity_context(
+                &node,
+                Operation::SendResponse,
+                fq_service_name,
+            )),
             node,
             service_name: service_name.to_string(),
             fq_service_name: fq_service_name.to_string(),
             gid,
+            type_info: TypeInfo::new(type_name, type_hash),
             receiver,
+            shed,
             _liveliness_token: liveliness_token,
             _queryable: queryable,
             _phantom: PhantomData,
@@ -223,9 +499,30 @@ where
         &self.gid
     }
 
+    /// Get the type name and hash advertised in the key expression and the
+    /// liveliness token of the server.
+    pub fn type_info(&self) -> &TypeInfo {
+        &self.type_info
+    }
+
+    /// Number of requests waiting to be received.
+    pub fn pending(&self) -> usize {
+        self.receiver.len()
+    }
+
+    /// Number of requests rejected or dropped so far because the request
+    /// queue was full.
+    ///
+    /// A growing value means the clients send requests faster than the
+    /// server answers them.
+    pub fn shed(&self) -> u64 {
+        self.shed.load(Ordering::Relaxed)
+    }
+
     /// Receive a request asynchronously.
     ///
     /// Returns a `ServiceRequest` that can be used to send a response.
+    /// This is cancel safe: dropping the future loses no request.
     ///
     /// # Errors
     ///
@@ -235,7 +532,7 @@ where
     /// - Request is missing attachment (protocol violation)
     /// - Request attachment is invalid
     pub async fn z_recv(&mut self) -> Result<ServiceRequest<T>> {
-        let (query, payload, attachment_bytes) = self
+        let (query, payload, attachment_bytes, received_ns) = self
             .receiver
             .recv_async()
             .await
@@ -246,10 +543,11 @@ where
 
         // Parse attachment (required by protocol)
         let attachment_bytes = attachment_bytes.ok_or(Error::MissingAttachment)?;
-        let attachment = Attachment::from_bytes(&attachment_bytes)?;
+        let mut attachment = Attachment::from_bytes(&attachment_bytes)?;
 
         let sender = RequestSender {
             query,
+            context: self.response_context.clone(),
             client_gid: attachment.gid,
             sequence_number: attachment.sequence_number,
             _phantom: PhantomData,
@@ -263,6 +561,8 @@ where
         );
 
         Ok(ServiceRequest {
+            trace_context: std::mem::take(&mut attachment.trace_context),
+            info: request_info(&attachment, received_ns),
             request: Message::new(request, attachment.into()),
             sender,
         })
@@ -273,6 +573,23 @@ where
         &self.node
     }
 
+    /// Context of the errors of taking requests.
+    fn request_context(&self) -> ErrorContext {
+        entity_context(&self.node, Operation::TakeRequest, &self.fq_service_name)
+    }
+
+    /// Span wrapping the handler invocation for a received request.
+    fn callback_span(&self, service_req: &ServiceRequest<T>) -> tracing::Span {
+        let span = tracing::debug_span!(
+            target: targets::ZENOH_SERVICE,
+            spans::SERVICE_CALLBACK,
+            service = %self.fq_service_name,
+            seq = service_req.request.info.sequence_number,
+        );
+        service_req.trace_context.link_span(&span);
+        span
+    }
+
     /// Try to receive a request without blocking.
     ///
     /// Returns `Ok(None)` if no request is currently available.
@@ -288,22 +605,25 @@ where
         T::Request: TypeSupport,
     {
         match self.receiver.try_recv() {
-            Ok((query, payload, attachment_bytes)) => {
+            Ok((query, payload, attachment_bytes, received_ns)) => {
                 // Deserialize request
                 let request = T::Request::from_bytes(&payload)?;
 
                 // Parse attachment (required by protocol)
                 let attachment_bytes = attachment_bytes.ok_or(Error::MissingAttachment)?;
-                let attachment = Attachment::from_bytes(&attachment_bytes)?;
+                let mut attachment = Attachment::from_bytes(&attachment_bytes)?;
 
                 let sender = RequestSender {
                     query,
+                    context: self.response_context.clone(),
                     client_gid: attachment.gid,
                     sequence_number: attachment.sequence_number,
                     _phantom: PhantomData,
                 };
 
                 Ok(Some(ServiceRequest {
+                    trace_context: std::mem::take(&mut attachment.trace_context),
+                    info: request_info(&attachment, received_ns),
                     request: Message::new(request, attachment.into()),
                     sender,
                 }))
@@ -314,6 +634,15 @@ where
     }
 }
 
+impl<T: oxidros_core::ServiceMsg> fmt::Debug for Server<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Server")
+            .field("service", &self.fq_service_name)
+            .field("type_info", &self.type_info)
+            .finish_non_exhaustive()
+    }
+}
+
 // ============================================================================
 // ServiceRequest trait implementation
 // ============================================================================
@@ -326,6 +655,10 @@ where
         &self.request.sample
     }
 
+    fn info(&self) -> RequestInfo {
+        self.info
+    }
+
     fn respond(self, response: &T::Response) -> Result<()> {
         self.send(response)
     }
@@ -347,11 +680,12 @@ where
     }
 
     async fn recv(&mut self) -> Result<Self::Request> {
-        self.z_recv().await
+        let result = self.z_recv().await;
+        result.with_context(|| self.request_context())
     }
 
     fn try_recv(&mut self) -> Result<Option<Self::Request>> {
-        self.z_try_recv()
+        self.z_try_recv().with_context(|| self.request_context())
     }
 
     async fn serve<F>(mut self, mut handler: F) -> Result<()>
@@ -362,13 +696,15 @@ where
         loop {
             match self.z_recv().await {
                 Ok(service_req) => {
+                    let span = self.callback_span(&service_req);
                     let (sender, request) = service_req.split();
-                    let response = handler(request);
+                    let response = span.in_scope(|| handler(request));
                     if let Err(e) = sender.send(&response) {
                         tracing::error!("Failed to send response: {:?}", e);
                     }
                 }
                 Err(e) => {
+                    let e = e.with_context(self.request_context());
                     tracing::error!("Error receiving request: {:?}", e);
                     return Err(e);
                 }
@@ -385,13 +721,15 @@ where
         loop {
             match self.z_recv().await {
                 Ok(service_req) => {
+                    let span = self.callback_span(&service_req);
                     let (sender, request) = service_req.split();
-                    let response = handler(request).await;
+                    let response = handler(request).instrument(span).await;
                     if let Err(e) = sender.send(&response) {
                         tracing::error!("Failed to send response: {:?}", e);
                     }
                 }
                 Err(e) => {
+                    let e = e.with_context(self.request_context());
                     tracing::error!("Error receiving request: {:?}", e);
                     return Err(e);
                 }
@@ -399,3 +737,35 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(shedding: SheddingPolicy) -> (Vec<u32>, u64, Vec<u32>) {
+        let (sender, receiver) = flume::bounded(2);
+        let shed = Arc::new(AtomicU64::new(0));
+        let rejected = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let rejected_cloned = rejected.clone();
+        let callback = shedding_callback(shedding, sender, &receiver, shed.clone(), move |i| {
+            rejected_cloned.lock().push(i)
+        });
+        for i in 0..5 {
+            callback(i);
+        }
+        (
+            receiver.try_iter().collect(),
+            shed.load(Ordering::Relaxed),
+            rejected.lock().clone(),
+        )
+    }
+
+    #[test]
+    fn test_shedding_policies() {
+        assert_eq!(
+            fill(SheddingPolicy::RejectWithError),
+            (vec![0, 1], 3, vec![2, 3, 4])
+        );
+        assert_eq!(fill(SheddingPolicy::DropOldest), (vec![3, 4], 3, vec![]));
+    }
+}
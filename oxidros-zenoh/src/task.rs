@@ -0,0 +1,32 @@
+//! Named async tasks.
+
+use oxidros_core::{spans, targets};
+use tokio::task::JoinHandle;
+use tracing::Instrument;
+
+/// Spawn `future` on Tokio as the task `name`.
+///
+/// The task runs in a [`spans::TASK`] span carrying its name. When built with
+/// `--cfg tokio_unstable`, the task is also named for `tokio-console`.
+///
+/// # Panics
+///
+/// Panics if called outside of a Tokio runtime.
+pub(crate) fn spawn_named<F>(name: &str, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let future = future.instrument(tracing::debug_span!(
+        target: targets::TASK,
+        spans::TASK,
+        task = %name
+    ));
+    #[cfg(tokio_unstable)]
+    return tokio::task::Builder::new()
+        .name(name)
+        .spawn(future)
+        .expect("failed to spawn a task");
+    #[cfg(not(tokio_unstable))]
+    tokio::spawn(future)
+}
@@ -0,0 +1,97 @@
+//! Simulated time of the clock of a node.
+//!
+//! As the `TimeSource` of `rclcpp`, [`TimeSource`] overrides the ROS time of
+//! the clock of a node with the messages of the `/clock` topic while the
+//! `use_sim_time` parameter is `true`. The messages are decoded in the
+//! callback of a Zenoh subscriber, so the clock follows `/clock` without
+//! polling the [`ParameterServer`].
+//!
+//! [`ParameterServer`]: crate::parameter::ParameterServer
+
+use crate::{
+    clock::{self, Clock, TimeChange},
+    error::Result,
+    keyexpr::topic_keyexpr,
+    node::Node,
+};
+use oxidros_core::{TypeSupport, targets, time::CLOCK_TOPIC};
+use oxidros_msg::interfaces::rosgraph_msgs::msg::Clock as ClockMsg;
+use parking_lot::Mutex;
+use ros2args::names::NameKind;
+use std::{sync::Arc, time::Duration};
+use zenoh::Wait;
+
+/// Subscriber of `/clock` overriding the ROS time of the clock of a node.
+pub(crate) struct TimeSource {
+    node: Arc<Node>,
+    clock: Arc<Mutex<Clock>>,
+    /// Subscriber of `/clock`, while the simulated time is used.
+    subscriber: Option<zenoh::pubsub::Subscriber<()>>,
+}
+
+impl TimeSource {
+    pub(crate) fn new(node: Arc<Node>) -> Result<Self> {
+        let clock = node.get_clock()?;
+        Ok(Self {
+            node,
+            clock,
+            subscriber: None,
+        })
+    }
+
+    /// Whether the clock follows the simulated time.
+    pub(crate) fn use_sim_time(&self) -> bool {
+        self.subscriber.is_some()
+    }
+
+    /// Switch the clock to the simulated time, or back to the system time.
+    ///
+    /// The simulated time is zero until the first message of `/clock`.
+    pub(crate) fn set_use_sim_time(&mut self, enable: bool) -> Result<()> {
+        if enable == self.use_sim_time() {
+            return Ok(());
+        }
+
+        if enable {
+            clock::change_unlocked(&self.clock, TimeChange::EnableOverride)?;
+
+            let topic = self
+                .node
+                .expand_and_remap_name(CLOCK_TOPIC, NameKind::Topic)?;
+            // Any type hash, as the subscribers of the topic
+            let key = topic_keyexpr(
+                self.node.context().domain_id(),
+                &topic,
+                ClockMsg::type_name(),
+                "*",
+            );
+            let clock = self.clock.clone();
+            let subscriber = self
+                .node
+                .context()
+                .session()
+                .declare_subscriber(&key)
+                .callback(move |sample| {
+                    let msg = match ClockMsg::from_bytes(&sample.payload().to_bytes()) {
+                        Ok(msg) => msg,
+                        Err(e) => {
+                            tracing::warn!(target: targets::CLOCK, error = %e, "Failed to decode /clock");
+                            return;
+                        }
+                    };
+                    let time = Duration::new(msg.clock.sec.max(0) as u64, msg.clock.nanosec);
+                    if let Err(e) = clock::change_unlocked(&clock, TimeChange::RosTime(time)) {
+                        tracing::error!(target: targets::CLOCK, error = %e, "Failed to set the simulated time");
+                    }
+                })
+                .wait()?;
+            self.subscriber = Some(subscriber);
+        } else {
+            self.subscriber = None;
+            clock::change_unlocked(&self.clock, TimeChange::DisableOverride)?;
+        }
+
+        tracing::debug!(target: targets::CLOCK, use_sim_time = enable, "Time source switched");
+        Ok(())
+    }
+}
@@ -0,0 +1,487 @@
+//! Chunked publication of large messages.
+//!
+//! Multi-megabyte messages, e.g. maps and point clouds, may exceed the
+//! message size limit of a Zenoh transport or router, or stall other traffic
+//! of the session while they are sent. A publisher with [`ChunkOptions`]
+//! splits the payloads above a threshold into chunks published one by one;
+//! subscribers reassemble them before buffering the message.
+//!
+//! The chunks of a message share its sequence number, and their attachment
+//! ends with a [`ChunkInfo`]. Chunking is opt-in on the publisher: every
+//! oxidros subscriber reassembles chunks, but subscribers of `rmw_zenoh_cpp`
+//! do not, and would receive each chunk as a malformed message.
+//!
+//! A message whose chunks do not all arrive within the reassembly timeout of
+//! the subscriber (see [`BufferOptions::reassembly_timeout`]) is dropped, so
+//! chunked topics should use reliable QoS. So are the messages larger than
+//! [`BufferOptions::max_reassembled_size`], chunks whose header does not
+//! match their size, and the oldest message once [`MAX_PARTIALS`] messages
+//! are being reassembled. With transient local durability,
+//! the cache of the publisher holds chunks, so its depth should cover the
+//! chunks of the messages kept for late joiners.
+//!
+//! [`BufferOptions::reassembly_timeout`]: super::BufferOptions::reassembly_timeout
+//! [`BufferOptions::max_reassembled_size`]: super::BufferOptions::max_reassembled_size
+
+use crate::{
+    attachment::{Attachment, ChunkInfo, GID_SIZE},
+    error::Result,
+};
+use bytes::Bytes;
+use oxidros_core::targets;
+use parking_lot::Mutex;
+use std::{
+    collections::{BTreeMap, HashMap, btree_map},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use zenoh::{bytes::ZBytes, key_expr::KeyExpr, sample::Sample};
+
+/// Reassembly timeout of subscribers by default.
+pub const DEFAULT_REASSEMBLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Maximum size in bytes of a reassembled message by default.
+pub const DEFAULT_MAX_REASSEMBLED_SIZE: usize = 1 << 28;
+
+/// Maximum number of messages a subscriber reassembles at once.
+pub const MAX_PARTIALS: usize = 16;
+
+/// Splitting of the large messages of a publisher, see
+/// [`Publisher::set_chunking`](super::Publisher::set_chunking).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkOptions {
+    /// Payloads larger than this size in bytes are split.
+    pub threshold: usize,
+    /// Size of the chunks in bytes, the last one being smaller.
+    pub chunk_size: usize,
+}
+
+impl Default for ChunkOptions {
+    /// Split the messages larger than 1 MiB into chunks of 1 MiB.
+    fn default() -> Self {
+        Self::with_threshold(1 << 20)
+    }
+}
+
+impl ChunkOptions {
+    /// Split the messages larger than `threshold` into chunks of `threshold`.
+    pub fn with_threshold(threshold: usize) -> Self {
+        ChunkOptions {
+            threshold,
+            chunk_size: threshold,
+        }
+    }
+
+    /// Split `payload` into `(chunk info, chunk)` pairs, or return `None` if
+    /// it is not larger than the threshold.
+    pub(crate) fn split<'a>(
+        &self,
+        payload: &'a [u8],
+    ) -> Option<impl Iterator<Item = (ChunkInfo, &'a [u8])>> {
+        if payload.len() <= self.threshold {
+            return None;
+        }
+        let chunk_size = self.chunk_size.max(1);
+        let count = payload.len().div_ceil(chunk_size) as u32;
+        let total_size = payload.len() as u64;
+        Some(
+            payload
+                .chunks(chunk_size)
+                .enumerate()
+                .map(move |(index, chunk)| {
+                    let info = ChunkInfo {
+                        index: index as u32,
+                        count,
+                        total_size,
+                    };
+                    (info, chunk)
+                }),
+        )
+    }
+}
+
+/// Sample buffered by a subscriber, reassembled if it was chunked.
+#[derive(Debug, Clone)]
+pub(crate) struct ReceivedSample {
+    key_expr: KeyExpr<'static>,
+    payload: ZBytes,
+    attachment: Option<ZBytes>,
+}
+
+impl ReceivedSample {
+    /// Key expression of the publisher.
+    pub(crate) fn key_expr(&self) -> &KeyExpr<'static> {
+        &self.key_expr
+    }
+
+    /// CDR payload, encapsulation header included.
+    pub(crate) fn payload(&self) -> &ZBytes {
+        &self.payload
+    }
+
+    /// Attachment of the message, without chunk info.
+    pub(crate) fn attachment(&self) -> Option<&ZBytes> {
+        self.attachment.as_ref()
+    }
+}
+
+impl From<Sample> for ReceivedSample {
+    fn from(sample: Sample) -> Self {
+        ReceivedSample {
+            key_expr: sample.key_expr().clone(),
+            payload: sample.payload().clone(),
+            attachment: sample.attachment().cloned(),
+        }
+    }
+}
+
+/// Chunks received so far of a message.
+struct Partial {
+    /// Chunks by index, only holding the received ones.
+    chunks: BTreeMap<u32, ZBytes>,
+    count: u32,
+    total_size: u64,
+    /// Size of the chunks received so far.
+    received: u64,
+    attachment: Attachment,
+    started: Instant,
+}
+
+type PartialId = ([u8; GID_SIZE], i64);
+
+/// Reassembly of the chunked messages received by a subscriber.
+pub(crate) struct Reassembler {
+    fq_topic_name: String,
+    timeout: Duration,
+    max_size: u64,
+    partials: Mutex<HashMap<PartialId, Partial>>,
+    /// Number of messages dropped because they were incomplete at the timeout
+    /// or evicted.
+    incomplete: Arc<AtomicU64>,
+}
+
+impl Reassembler {
+    pub(crate) fn new(
+        fq_topic_name: &str,
+        timeout: Duration,
+        max_size: usize,
+        incomplete: Arc<AtomicU64>,
+    ) -> Self {
+        Reassembler {
+            fq_topic_name: fq_topic_name.to_string(),
+            timeout,
+            max_size: max_size as u64,
+            partials: Mutex::new(HashMap::new()),
+            incomplete,
+        }
+    }
+
+    /// Handle a sample, returning the message once all its chunks arrived.
+    ///
+    /// Samples which are not chunks are returned as they are.
+    pub(crate) fn push(&self, sample: Sample) -> Option<ReceivedSample> {
+        self.reassemble(sample.into())
+    }
+
+    fn reassemble(&self, sample: ReceivedSample) -> Option<ReceivedSample> {
+        let Some((attachment, chunk)) = sample
+            .attachment()
+            .and_then(|bytes| Attachment::from_bytes(&bytes.to_bytes()).ok())
+            .and_then(|mut attachment| attachment.chunk.take().map(|chunk| (attachment, chunk)))
+        else {
+            return Some(sample);
+        };
+        let id = (attachment.gid, attachment.sequence_number);
+
+        let size = sample.payload().len() as u64;
+        if !self.is_valid(&chunk, size) {
+            tracing::warn!(
+                target: targets::ZENOH_SUBSCRIBER,
+                topic = %self.fq_topic_name,
+                seq = id.1,
+                index = chunk.index,
+                count = chunk.count,
+                total_size = chunk.total_size,
+                size,
+                "Ignoring malformed chunk"
+            );
+            return None;
+        }
+
+        let mut partials = self.partials.lock();
+        self.prune(&mut partials);
+        if !partials.contains_key(&id) && partials.len() >= MAX_PARTIALS {
+            self.evict_oldest(&mut partials);
+        }
+        let partial = partials.entry(id).or_insert_with(|| Partial {
+            chunks: BTreeMap::new(),
+            count: chunk.count,
+            total_size: chunk.total_size,
+            received: 0,
+            attachment,
+            started: Instant::now(),
+        });
+        if partial.count != chunk.count
+            || partial.total_size != chunk.total_size
+            || partial.received + size > partial.total_size
+        {
+            tracing::debug!(
+                target: targets::ZENOH_SUBSCRIBER,
+                topic = %self.fq_topic_name,
+                seq = id.1,
+                "Ignoring chunk inconsistent with the previous ones"
+            );
+            return None;
+        }
+        if let btree_map::Entry::Vacant(slot) = partial.chunks.entry(chunk.index) {
+            slot.insert(sample.payload().clone());
+            partial.received += size;
+        }
+        if partial.chunks.len() < partial.count as usize {
+            return None;
+        }
+
+        let partial = partials.remove(&id)?;
+        drop(partials);
+        if partial.received != partial.total_size {
+            self.incomplete.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(
+                target: targets::ZENOH_SUBSCRIBER,
+                topic = %self.fq_topic_name,
+                seq = id.1,
+                size = partial.received,
+                total_size = partial.total_size,
+                "Dropping chunked message of a size different from its header"
+            );
+            return None;
+        }
+        let mut payload = Vec::with_capacity(partial.received as usize);
+        for chunk in partial.chunks.into_values() {
+            payload.extend_from_slice(&chunk.to_bytes());
+        }
+        tracing::debug!(
+            target: targets::ZENOH_SUBSCRIBER,
+            topic = %self.fq_topic_name,
+            seq = id.1,
+            size = payload.len(),
+            "Reassembled chunked message"
+        );
+        Some(ReceivedSample {
+            key_expr: sample.key_expr().clone(),
+            payload: ZBytes::from(payload),
+            attachment: Some(ZBytes::from(partial.attachment.to_bytes())),
+        })
+    }
+
+    /// Check the header of a chunk of `size` bytes.
+    ///
+    /// All the chunks but the last one have the chunk size of the publisher,
+    /// so they give the number of chunks of the message.
+    fn is_valid(&self, chunk: &ChunkInfo, size: u64) -> bool {
+        if chunk.index >= chunk.count
+            || chunk.total_size > self.max_size
+            || size == 0
+            || size > chunk.total_size
+        {
+            return false;
+        }
+        if chunk.index + 1 < chunk.count {
+            chunk.total_size.div_ceil(size) == u64::from(chunk.count)
+        } else {
+            u64::from(chunk.count) <= chunk.total_size
+        }
+    }
+
+    /// Drop the message which started to be reassembled first.
+    fn evict_oldest(&self, partials: &mut HashMap<PartialId, Partial>) {
+        let Some((&id, partial)) = partials.iter().min_by_key(|(_, partial)| partial.started)
+        else {
+            return;
+        };
+        self.incomplete.fetch_add(1, Ordering::Relaxed);
+        tracing::warn!(
+            target: targets::ZENOH_SUBSCRIBER,
+            topic = %self.fq_topic_name,
+            seq = id.1,
+            missing = partial.count as usize - partial.chunks.len(),
+            "Dropping the oldest chunked message, too many are being reassembled"
+        );
+        partials.remove(&id);
+    }
+
+    /// Drop the messages whose chunks did not all arrive within the timeout.
+    fn prune(&self, partials: &mut HashMap<PartialId, Partial>) {
+        partials.retain(|(_, seq), partial| {
+            let expired = partial.started.elapsed() > self.timeout;
+            if expired {
+                self.incomplete.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!(
+                    target: targets::ZENOH_SUBSCRIBER,
+                    topic = %self.fq_topic_name,
+                    seq = *seq,
+                    missing = partial.count as usize - partial.chunks.len(),
+                    "Dropping chunked message not reassembled in time"
+                );
+            }
+            !expired
+        });
+    }
+}
+
+/// Publish `payload` with `put`, split into chunks if `options` say so.
+pub(crate) fn put_chunked(
+    options: Option<&ChunkOptions>,
+    payload: Bytes,
+    attachment: Attachment,
+    mut put: impl FnMut(ZBytes, Vec<u8>) -> Result<()>,
+) -> Result<()> {
+    let Some(chunks) = options.and_then(|options| options.split(&payload)) else {
+        return put(ZBytes::from(payload), attachment.to_bytes());
+    };
+    let mut attachment = attachment;
+    for (info, chunk) in chunks {
+        attachment.chunk = Some(info);
+        put(
+            ZBytes::from(payload.slice_ref(chunk)),
+            attachment.to_bytes(),
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let options = ChunkOptions {
+            threshold: 10,
+            chunk_size: 4,
+        };
+        assert!(options.split(&[0; 10]).is_none());
+
+        let payload: Vec<u8> = (0..11).collect();
+        let chunks: Vec<_> = options.split(&payload).unwrap().collect();
+        assert_eq!(chunks.len(), 3);
+        for (i, (info, _)) in chunks.iter().enumerate() {
+            assert_eq!(info.index, i as u32);
+            assert_eq!(info.count, 3);
+            assert_eq!(info.total_size, 11);
+        }
+        let joined: Vec<u8> = chunks.iter().flat_map(|(_, c)| c.iter().copied()).collect();
+        assert_eq!(joined, payload);
+        assert_eq!(chunks[2].1, &[8, 9, 10]);
+    }
+
+    fn chunk_sample(seq: i64, info: ChunkInfo, payload: &[u8]) -> ReceivedSample {
+        let mut attachment = Attachment::new(seq, [1; GID_SIZE]);
+        attachment.chunk = Some(info);
+        ReceivedSample {
+            key_expr: KeyExpr::try_from("chunked").unwrap(),
+            payload: ZBytes::from(payload.to_vec()),
+            attachment: Some(ZBytes::from(attachment.to_bytes())),
+        }
+    }
+
+    fn reassembler() -> Reassembler {
+        Reassembler::new(
+            "/chunked",
+            DEFAULT_REASSEMBLY_TIMEOUT,
+            1000,
+            Default::default(),
+        )
+    }
+
+    #[test]
+    fn test_reassemble() {
+        let options = ChunkOptions {
+            threshold: 10,
+            chunk_size: 4,
+        };
+        let payload: Vec<u8> = (0..11).collect();
+        let reassembler = reassembler();
+
+        let mut chunks: Vec<_> = options.split(&payload).unwrap().collect();
+        chunks.reverse();
+        let mut received = None;
+        for (info, chunk) in chunks {
+            assert!(received.is_none());
+            received = reassembler.reassemble(chunk_sample(1, info, chunk));
+        }
+        assert_eq!(received.unwrap().payload().to_bytes(), payload);
+        assert!(reassembler.partials.lock().is_empty());
+    }
+
+    #[test]
+    fn test_reassemble_malformed_header() {
+        let reassembler = reassembler();
+        let malformed = [
+            // Chunk count from the network, not matching the size
+            (0, u32::MAX, 100),
+            // Larger than the maximum size
+            (0, 2, 2000),
+            // Fewer bytes than chunks
+            (0, 3, 2),
+        ];
+        for (index, count, total_size) in malformed {
+            let info = ChunkInfo {
+                index,
+                count,
+                total_size,
+            };
+            assert!(
+                reassembler
+                    .reassemble(chunk_sample(1, info, &[0; 4]))
+                    .is_none()
+            );
+        }
+        assert!(reassembler.partials.lock().is_empty());
+
+        // A last chunk larger than announced
+        let info = |index| ChunkInfo {
+            index,
+            count: 2,
+            total_size: 6,
+        };
+        assert!(
+            reassembler
+                .reassemble(chunk_sample(2, info(0), &[0; 4]))
+                .is_none()
+        );
+        assert!(
+            reassembler
+                .reassemble(chunk_sample(2, info(1), &[0; 4]))
+                .is_none()
+        );
+        assert_eq!(reassembler.partials.lock().len(), 1);
+    }
+
+    #[test]
+    fn test_reassemble_evicts_oldest() {
+        let reassembler = reassembler();
+        let info = ChunkInfo {
+            index: 0,
+            count: 2,
+            total_size: 8,
+        };
+        for seq in 0..=MAX_PARTIALS as i64 {
+            assert!(
+                reassembler
+                    .reassemble(chunk_sample(seq, info, &[0; 4]))
+                    .is_none()
+            );
+            if seq == 0 {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        }
+
+        let partials = reassembler.partials.lock();
+        assert_eq!(partials.len(), MAX_PARTIALS);
+        assert!(!partials.contains_key(&([1; GID_SIZE], 0)));
+        assert_eq!(reassembler.incomplete.load(Ordering::Relaxed), 1);
+    }
+}
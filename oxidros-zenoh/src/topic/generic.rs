@@ -0,0 +1,193 @@
+//! Publisher and subscriber with runtime type information.
+//!
+//! A [`GenericPublisher`] and a [`GenericSubscriber`] exchange
+//! [`DynamicMessage`]s on a topic whose type is only known at runtime, e.g.
+//! from a type name sent by a web client. The type hash of the key
+//! expressions and liveliness tokens is computed from the type description.
+
+use crate::{
+    error::{Error, Result},
+    keyexpr::EntityKind,
+    node::Node,
+    service::generic_client::ros_to_dds_type_name,
+    topic::{BufferOptions, Publisher, Subscriber},
+};
+use oxidros_core::{Message, MessageInfo, qos::Profile, types::TypeDescriptionMsg};
+use oxidros_dynamic::DynamicMessage;
+use std::sync::Arc;
+
+/// DDS type name and type hash of a runtime type.
+pub(crate) fn type_info(type_desc: &TypeDescriptionMsg) -> Result<(String, String)> {
+    let type_name = &type_desc.type_description.type_name;
+    let dds_type_name = ros_to_dds_type_name(type_name).ok_or_else(|| {
+        Error::InvalidConfig(format!(
+            "Invalid message type '{type_name}', expected '<package>/msg/<Name>'"
+        ))
+    })?;
+    let type_hash = oxidros_core::calculate_type_hash(type_desc)?;
+    Ok((dds_type_name, type_hash))
+}
+
+/// Publisher using runtime type information.
+///
+/// # Example
+///
+/// ```ignore
+/// let publisher = node.create_generic_publisher("chatter", string_desc, None)?;
+/// publisher.publish(&publisher.new_message(serde_json::json!({"data": "Hello!"})))?;
+/// ```
+pub struct GenericPublisher {
+    inner: Publisher<DynamicMessage>,
+    type_desc: Arc<TypeDescriptionMsg>,
+}
+
+impl GenericPublisher {
+    pub(crate) fn new(
+        node: Arc<Node>,
+        topic_name: &str,
+        fq_topic_name: &str,
+        type_desc: TypeDescriptionMsg,
+        qos: Profile,
+    ) -> Result<Self> {
+        let (dds_type_name, type_hash) = type_info(&type_desc)?;
+        let inner = Publisher::with_type(
+            node,
+            topic_name,
+            fq_topic_name,
+            &dds_type_name,
+            &type_hash,
+            qos,
+            EntityKind::Publisher,
+        )?;
+        Ok(GenericPublisher {
+            inner,
+            type_desc: Arc::new(type_desc),
+        })
+    }
+
+    /// Get the fully qualified topic name.
+    pub fn fully_qualified_topic_name(&self) -> &str {
+        self.inner.fully_qualified_topic_name()
+    }
+
+    /// Get the message type description.
+    pub fn type_description(&self) -> &Arc<TypeDescriptionMsg> {
+        &self.type_desc
+    }
+
+    /// Build a message of this publisher's type.
+    pub fn new_message(&self, value: serde_json::Value) -> DynamicMessage {
+        DynamicMessage::new(self.type_desc.clone(), value)
+    }
+
+    /// Publish a message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message is not of this publisher's type, if
+    /// its value does not match the type, or if the Zenoh put fails.
+    pub fn publish(&self, msg: &DynamicMessage) -> Result<()> {
+        if msg.type_name() != self.type_desc.type_description.type_name {
+            return Err(Error::InvalidConfig(format!(
+                "Message type '{}' does not match '{}'",
+                msg.type_name(),
+                self.type_desc.type_description.type_name
+            )));
+        }
+        let payload = msg
+            .to_cdr()
+            .map_err(|e| Error::Other(format!("Failed to encode message: {e}")))?;
+        self.inner.send_internal(payload.into())
+    }
+
+    /// Publish a message already encoded in CDR, e.g. forwarded from a
+    /// device, without checking it against the type.
+    ///
+    /// The bytes must include the 4-byte encapsulation header.
+    pub fn publish_raw(&self, cdr: Vec<u8>) -> Result<()> {
+        self.inner.send_internal(cdr.into())
+    }
+}
+
+/// Subscriber using runtime type information.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut subscriber = node.create_generic_subscriber("chatter", string_desc, None)?;
+/// let msg = subscriber.recv().await?;
+/// println!("{}", msg.value());
+/// ```
+pub struct GenericSubscriber {
+    inner: Subscriber<DynamicMessage>,
+    type_desc: Arc<TypeDescriptionMsg>,
+}
+
+impl GenericSubscriber {
+    pub(crate) fn new(
+        node: Arc<Node>,
+        topic_name: &str,
+        fq_topic_name: &str,
+        type_desc: TypeDescriptionMsg,
+        qos: Profile,
+    ) -> Result<Self> {
+        let (dds_type_name, type_hash) = type_info(&type_desc)?;
+        let inner = Subscriber::with_type(
+            node,
+            topic_name,
+            fq_topic_name,
+            &dds_type_name,
+            &type_hash,
+            qos,
+            EntityKind::Subscriber,
+            BufferOptions::default(),
+        )?;
+        Ok(GenericSubscriber {
+            inner,
+            type_desc: Arc::new(type_desc),
+        })
+    }
+
+    /// Get the fully qualified topic name.
+    pub fn fully_qualified_topic_name(&self) -> &str {
+        self.inner.fully_qualified_topic_name()
+    }
+
+    /// Get the message type description.
+    pub fn type_description(&self) -> &Arc<TypeDescriptionMsg> {
+        &self.type_desc
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<DynamicMessage> {
+        DynamicMessage::from_cdr(bytes, self.type_desc.clone())
+            .map_err(|e| Error::Other(format!("Failed to decode message: {e}")))
+    }
+
+    /// Receive a message asynchronously.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decoding fails, the channel is closed, or the
+    /// message has a missing/invalid attachment.
+    pub async fn recv(&mut self) -> Result<Message<DynamicMessage>> {
+        let (bytes, info) = self.inner.z_recv_raw().await?;
+        Ok(Message::new(self.decode(&bytes)?, info))
+    }
+
+    /// Receive the CDR bytes of a message asynchronously, without decoding them.
+    ///
+    /// The bytes include the 4-byte encapsulation header.
+    pub async fn recv_raw(&mut self) -> Result<(Vec<u8>, MessageInfo)> {
+        self.inner.z_recv_raw().await
+    }
+
+    /// Try to receive a message without blocking.
+    ///
+    /// Returns `None` if no message is available.
+    pub fn try_recv(&self) -> Result<Option<Message<DynamicMessage>>> {
+        match self.inner.z_try_recv_raw()? {
+            Some((bytes, info)) => Ok(Some(Message::new(self.decode(&bytes)?, info))),
+            None => Ok(None),
+        }
+    }
+}
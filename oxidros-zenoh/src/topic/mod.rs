@@ -1,7 +1,13 @@
 //! Topic-based publish/subscribe.
 
+pub mod chunk;
+#[cfg(feature = "dynamic")]
+pub mod generic;
 pub mod publisher;
 pub mod subscriber;
 
+pub use chunk::ChunkOptions;
+#[cfg(feature = "dynamic")]
+pub use generic::{GenericPublisher, GenericSubscriber};
 pub use publisher::Publisher;
-pub use subscriber::Subscriber;
+pub use subscriber::{BufferOptions, OverflowPolicy, Subscriber};
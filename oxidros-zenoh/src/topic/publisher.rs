@@ -6,19 +6,32 @@
 
 use crate::{
     attachment::{Attachment, GID_SIZE, generate_gid},
-    error::Result,
+    error::{Result, entity_context},
     keyexpr::{EntityKind, liveliness_entity_keyexpr, topic_keyexpr},
     node::Node,
     qos::QosMapping,
+    topic::chunk::{ChunkOptions, put_chunked},
 };
-use oxidros_core::{TypeSupport, qos::Profile, targets};
+use bytes::{Bytes, BytesMut};
+use oxidros_core::{
+    NetworkFlowEndpoint, TypeInfo, TypeSupport,
+    error::{Operation, ResultExt},
+    msg::{Stamped, TryClone},
+    qos::Profile,
+    stats::{self, EntityToken},
+    targets,
+    time::{StampFn, stamp_if_unset},
+};
+use parking_lot::Mutex;
 use std::{
     borrow::Cow,
+    fmt,
     marker::PhantomData,
     sync::{
         Arc,
         atomic::{AtomicI64, Ordering},
     },
+    time::Instant,
 };
 use zenoh::{Wait, bytes::ZBytes};
 use zenoh_ext::AdvancedPublisherBuilderExt;
@@ -44,12 +57,23 @@ pub struct Publisher<T> {
     fq_topic_name: String,
     /// Zenoh advanced publisher (supports cache for TRANSIENT_LOCAL durability).
     zenoh_publisher: zenoh_ext::AdvancedPublisher<'static>,
+    /// Advertised type name and hash.
+    type_info: TypeInfo,
     /// Publisher GID.
     gid: [u8; GID_SIZE],
-    /// Sequence number counter.
+    /// Sequence number of the next message, starting at 1 as in rmw.
     sequence_number: AtomicI64,
+    /// Buffer the messages are serialized into. Payloads share its memory,
+    /// which is reused once Zenoh has sent them.
+    payload_buffer: Mutex<BytesMut>,
     /// Liveliness token.
     _liveliness_token: zenoh::liveliness::LivelinessToken,
+    /// Keeps the publisher counted by the node.
+    _entity: EntityToken,
+    /// Set by [`Publisher::set_auto_stamp`].
+    auto_stamp: Option<StampFn<T>>,
+    /// Set by [`Publisher::set_chunking`].
+    chunking: Option<ChunkOptions>,
     /// Phantom data for type.
     _phantom: PhantomData<T>,
 }
@@ -70,20 +94,42 @@ impl<T: TypeSupport> Publisher<T> {
         fq_topic_name: &str,
         qos: Profile,
         entity_kind: EntityKind,
+    ) -> Result<Self> {
+        let type_hash = T::type_hash()?;
+        Self::with_type(
+            node,
+            topic_name,
+            fq_topic_name,
+            T::type_name(),
+            &type_hash,
+            qos,
+            entity_kind,
+        )
+    }
+}
+
+impl<T> Publisher<T> {
+    /// Create a new publisher of the given DDS type name and type hash.
+    ///
+    /// `T` is only a marker; runtime-typed publishers serialize the messages themselves.
+    pub(crate) fn with_type(
+        node: Arc<Node>,
+        topic_name: &str,
+        fq_topic_name: &str,
+        type_name: &str,
+        type_hash: &str,
+        qos: Profile,
+        entity_kind: EntityKind,
     ) -> Result<Self> {
         // Validate QoS
         QosMapping::validate(&qos);
 
-        // Get type info
-        let type_name = T::type_name();
-        let type_hash = T::type_hash()?;
-
         // Build key expression
         let key_expr_str = topic_keyexpr(
             node.context().domain_id(),
             fq_topic_name,
             type_name,
-            &type_hash,
+            type_hash,
         );
 
         // Create Zenoh publisher
@@ -104,6 +150,7 @@ impl<T: TypeSupport> Publisher<T> {
         let zenoh_publisher = session
             .declare_publisher(key_expr)
             .congestion_control(QosMapping::congestion_control(&qos))
+            .reliability(QosMapping::reliability(&qos))
             .cache(zenoh_ext::CacheConfig::default().max_samples(cache_depth))
             .wait()?;
 
@@ -123,7 +170,7 @@ impl<T: TypeSupport> Publisher<T> {
             &node.z_name()?,
             fq_topic_name,
             type_name,
-            &type_hash,
+            type_hash,
             &qos,
         );
 
@@ -137,19 +184,55 @@ impl<T: TypeSupport> Publisher<T> {
         );
 
         Ok(Publisher {
+            _entity: node.entities().register(stats::EntityKind::Publisher),
             node,
             topic_name: topic_name.to_string(),
             fq_topic_name: fq_topic_name.to_string(),
             zenoh_publisher,
+            type_info: TypeInfo::new(type_name, type_hash),
             gid,
-            sequence_number: AtomicI64::new(0),
+            sequence_number: AtomicI64::new(1),
+            payload_buffer: Mutex::new(BytesMut::new()),
             _liveliness_token: liveliness_token,
+            auto_stamp: None,
+            chunking: None,
             _phantom: PhantomData,
         })
     }
+
+    /// Publish CDR bytes, including the encapsulation header.
+    pub(crate) fn send_internal(&self, payload: Bytes) -> Result<()> {
+        // Increment sequence number
+        let seq = self.sequence_number.fetch_add(1, Ordering::Relaxed);
+        // Create attachment
+        let attachment = Attachment::new(seq, self.gid).with_current_context();
+        // Publish with attachment, in chunks if the payload is large
+        put_chunked(
+            self.chunking.as_ref(),
+            payload,
+            attachment,
+            |payload, attachment_bytes| {
+                // Blocks while congested if reliable
+                let start = Instant::now();
+                let result = self
+                    .zenoh_publisher
+                    .put(payload)
+                    .attachment(ZBytes::from(attachment_bytes))
+                    .wait();
+                let transport = self.node.context().transport_monitor();
+                if let Err(e) = &result {
+                    transport.put_failed(&self.fq_topic_name, e);
+                } else {
+                    transport.put_blocked(&self.fq_topic_name, start.elapsed());
+                }
+                result?;
+                Ok(())
+            },
+        )
+    }
 }
 
-impl<T: TypeSupport> Publisher<T> {
+impl<T> Publisher<T> {
     /// Get the topic name.
     pub fn z_topic_name(&self) -> Result<Cow<'_, String>> {
         Ok(Cow::Borrowed(&self.topic_name))
@@ -165,20 +248,65 @@ impl<T: TypeSupport> Publisher<T> {
         &self.gid
     }
 
-    fn send_internal(&self, payload: Vec<u8>) -> Result<()> {
-        // Increment sequence number
-        let seq = self.sequence_number.fetch_add(1, Ordering::Relaxed);
-        // Create attachment
-        let attachment = Attachment::new(seq, self.gid);
-        let attachment_bytes = attachment.to_bytes();
-        // Publish with attachment
-        self.zenoh_publisher
-            .put(payload)
-            .attachment(ZBytes::from(attachment_bytes.to_vec()))
-            .wait()?;
-        Ok(())
+    /// Get the type name and hash advertised in the key expression and the
+    /// liveliness token of the publisher.
+    pub fn type_info(&self) -> &TypeInfo {
+        &self.type_info
+    }
+
+    /// Get the sequence number of the last published message, `0` if none.
+    ///
+    /// Subscribers receive it in `MessageInfo::sequence_number`.
+    pub fn sequence_number(&self) -> i64 {
+        self.sequence_number.load(Ordering::Relaxed) - 1
+    }
+
+    /// Get the parent node.
+    pub fn node(&self) -> &Arc<Node> {
+        &self.node
+    }
+
+    /// Split the messages larger than the threshold of `options` into chunks,
+    /// reassembled by the subscribers. Disabled (`None`) by default.
+    ///
+    /// Only oxidros subscribers reassemble chunks, see [`crate::topic::chunk`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use oxidros_zenoh::topic::ChunkOptions;
+    ///
+    /// let mut publisher = node.z_create_publisher::<nav_msgs::msg::OccupancyGrid>("map", None)?;
+    /// publisher.set_chunking(Some(ChunkOptions::with_threshold(512 * 1024)));
+    /// publisher.z_send(&map)?;
+    /// ```
+    pub fn set_chunking(&mut self, options: Option<ChunkOptions>) {
+        self.chunking = options;
+    }
+
+    /// Get how large messages are split, see [`Publisher::set_chunking`].
+    pub fn chunking(&self) -> Option<ChunkOptions> {
+        self.chunking
+    }
+
+    /// Get the local addresses of the links the messages are sent over.
+    ///
+    /// See [`Context::network_flow_endpoints`](crate::Context::network_flow_endpoints).
+    pub fn get_network_flow_endpoints(&self) -> Result<Vec<NetworkFlowEndpoint>> {
+        self.node.context().network_flow_endpoints()
+    }
+}
+
+impl<T> fmt::Debug for Publisher<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Publisher")
+            .field("topic", &self.fq_topic_name)
+            .field("type_info", &self.type_info)
+            .finish_non_exhaustive()
     }
+}
 
+impl<T: TypeSupport> Publisher<T> {
     /// Publish a message.
     ///
     /// # Errors
@@ -186,8 +314,19 @@ impl<T: TypeSupport> Publisher<T> {
     /// Returns an error if serialization fails or the Zenoh put fails.
     pub fn z_send(&self, msg: &T) -> Result<()> {
         let start = std::time::Instant::now();
-        // Serialize message to CDR
-        let payload = msg.to_bytes()?;
+        let stamped = match self.auto_stamp {
+            Some(stamp) => stamp(msg, self.node.get_clock()?.lock().get_now()?)?,
+            None => None,
+        };
+        let msg = stamped.as_ref().unwrap_or(msg);
+        // Serialize message to CDR in the reused buffer
+        let payload = {
+            let mut buffer = self.payload_buffer.lock();
+            buffer.resize(msg.serialized_size(), 0);
+            let len = msg.to_bytes_into(&mut buffer)?;
+            buffer.truncate(len);
+            buffer.split().freeze()
+        };
         let result = self.send_internal(payload);
 
         tracing::debug!(
@@ -212,11 +351,24 @@ impl<T: TypeSupport> Publisher<T> {
         // Serialize message to CDR
         use oxidros_core::CdrSerde;
         let payload = msg.to_vec().serialize()?;
-        self.send_internal(payload)
+        self.send_internal(payload.into())
     }
-    /// Get the parent node.
-    pub fn node(&self) -> &Arc<Node> {
-        &self.node
+}
+
+impl<T: TypeSupport + Stamped + TryClone> Publisher<T> {
+    /// Fill the stamps left to zero with the time of the clock of the node.
+    ///
+    /// Messages whose stamp is zero are copied and stamped by
+    /// [`Publisher::z_send`]. Raw messages are not stamped. Disabled by
+    /// default.
+    pub fn set_auto_stamp(&mut self, enable: bool) {
+        self.auto_stamp = enable.then_some(stamp_if_unset::<T> as StampFn<T>);
+    }
+
+    /// Whether the stamps left to zero are filled, see
+    /// [`Publisher::set_auto_stamp`].
+    pub fn auto_stamp(&self) -> bool {
+        self.auto_stamp.is_some()
     }
 }
 
@@ -231,12 +383,16 @@ impl<T: TypeSupport> oxidros_core::api::RosPublisher<T> for Publisher<T> {
 
     fn send(&self, msg: &T) -> crate::error::Result<()> {
         self.z_send(msg)
+            .with_context(|| entity_context(&self.node, Operation::Publish, &self.fq_topic_name))
+    }
+
+    fn network_flow_endpoints(&self) -> Result<Vec<NetworkFlowEndpoint>> {
+        self.get_network_flow_endpoints()
     }
 
     fn send_raw(&self, data: &[u8]) -> crate::error::Result<()> {
-        // Add CDR serialization header to the raw data and send
-        use oxidros_core::CdrSerde;
-        let payload = data.to_vec().serialize()?;
-        self.send_internal(payload)
+        // `data` is a whole CDR message, as returned by `recv_raw`
+        self.send_internal(data.to_vec().into())
+            .with_context(|| entity_context(&self.node, Operation::Publish, &self.fq_topic_name))
     }
 }
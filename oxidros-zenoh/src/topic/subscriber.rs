@@ -6,16 +6,75 @@
 
 use crate::{
     attachment::{Attachment, GID_SIZE, generate_gid},
-    error::{Error, Result},
+    error::{Error, Result, entity_context},
     keyexpr::{EntityKind, liveliness_entity_keyexpr, topic_keyexpr},
     node::Node,
     qos::QosMapping,
+    topic::chunk::{
+        DEFAULT_MAX_REASSEMBLED_SIZE, DEFAULT_REASSEMBLY_TIMEOUT, Reassembler, ReceivedSample,
+    },
+    trace_context::TraceContext,
+    transport::DropReporter,
+};
+pub use oxidros_core::{
+    Message, TypeCheckPolicy, TypeSupport, TypeSupportBorrowed, qos::Profile, targets,
+};
+use oxidros_core::{NetworkFlowEndpoint, TypeInfo};
+use oxidros_core::{
+    error::{ErrorContext, Operation, ResultExt},
+    stats::{self, EntityToken},
+};
+use parking_lot::Mutex;
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    fmt,
+    marker::PhantomData,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering},
+    },
+    time::Duration,
 };
-pub use oxidros_core::{Message, TypeSupport, qos::Profile, targets};
-use std::{borrow::Cow, marker::PhantomData, sync::Arc};
 use zenoh::Wait;
+use zenoh::key_expr::KeyExpr;
+use zenoh::sample::Sample;
 use zenoh_ext::AdvancedSubscriberBuilderExt;
 
+/// What a subscriber does with an incoming message when its buffer is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Drop the oldest buffered message (`KeepLast` semantics).
+    #[default]
+    DropOldest,
+    /// Drop the incoming message.
+    DropNewest,
+    /// Wait until the consumer makes room.
+    ///
+    /// This blocks the Zenoh callback thread, delaying the delivery of other
+    /// samples of the session, and should only be used with consumers that
+    /// keep up on average.
+    Block,
+}
+
+/// Configuration of the buffer between Zenoh and a subscriber.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BufferOptions {
+    /// Maximum number of buffered messages (the QoS depth if `None`).
+    pub capacity: Option<usize>,
+    /// Policy applied when the buffer is full.
+    pub overflow: OverflowPolicy,
+    /// Create the subscriber paused, see [`Subscriber::resume`].
+    pub paused: bool,
+    /// How long the chunks of a message split by its publisher are kept
+    /// until the message is complete (5 seconds if `None`), see
+    /// [`ChunkOptions`](super::ChunkOptions).
+    pub reassembly_timeout: Option<Duration>,
+    /// Maximum size in bytes of a message split by its publisher (256 MiB if
+    /// `None`); the chunks of larger messages are dropped.
+    pub max_reassembled_size: Option<usize>,
+}
+
 /// Topic subscriber.
 ///
 /// Receives messages from a topic using Zenoh.
@@ -43,8 +102,22 @@ pub struct Subscriber<T> {
     fq_topic_name: String,
     /// Subscriber GID.
     gid: [u8; GID_SIZE],
+    /// Advertised type name and hash.
+    type_info: TypeInfo,
     /// Message receiver channel.
-    receiver: flume::Receiver<zenoh::sample::Sample>,
+    receiver: flume::Receiver<ReceivedSample>,
+    /// Number of messages dropped because the buffer was full.
+    lagged: Arc<AtomicU64>,
+    /// Number of chunked messages dropped because they were not reassembled in time.
+    incomplete: Arc<AtomicU64>,
+    /// Whether incoming messages are discarded instead of buffered.
+    paused: Arc<AtomicBool>,
+    /// Filter of the messages by type hash.
+    type_check: Arc<TypeCheck>,
+    /// Receive buffer as reported by the node statistics.
+    _queue: Arc<QueueProbe>,
+    /// Keeps the subscriber counted by the node.
+    _entity: EntityToken,
     /// Liveliness token.
     _liveliness_token: zenoh::liveliness::LivelinessToken,
     /// Zenoh advanced subscriber (supports history query for TRANSIENT_LOCAL durability).
@@ -63,35 +136,80 @@ impl<T: TypeSupport> Subscriber<T> {
     /// * `fq_topic_name` - Fully qualified topic name (already expanded and remapped)
     /// * `qos` - QoS profile
     /// * `entity_kind` - Entity kind for liveliness
+    /// * `buffer` - Capacity, overflow policy and initial state of the receive buffer
     pub(crate) fn new(
         node: Arc<Node>,
         topic_name: &str,
         fq_topic_name: &str,
         qos: Profile,
         entity_kind: EntityKind,
+        buffer: BufferOptions,
+    ) -> Result<Self> {
+        let type_hash = T::type_hash()?;
+        Self::with_type(
+            node,
+            topic_name,
+            fq_topic_name,
+            T::type_name(),
+            &type_hash,
+            qos,
+            entity_kind,
+            buffer,
+        )
+    }
+}
+
+impl<T> Subscriber<T> {
+    /// Create a new subscriber of the given DDS type name and type hash.
+    ///
+    /// `T` is only a marker; runtime-typed subscribers deserialize the messages themselves.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn with_type(
+        node: Arc<Node>,
+        topic_name: &str,
+        fq_topic_name: &str,
+        type_name: &str,
+        type_hash: &str,
+        qos: Profile,
+        entity_kind: EntityKind,
+        buffer: BufferOptions,
     ) -> Result<Self> {
         // Validate QoS
         QosMapping::validate(&qos);
 
-        // Get type info
-        let type_name = T::type_name();
-        let type_hash = T::type_hash()?;
-
         // Build key expression with wildcard for type hash
-        // This allows receiving from publishers with different (compatible) type hashes
-        let key_expr = topic_keyexpr(
-            node.context().domain_id(),
-            fq_topic_name,
-            type_name,
-            &type_hash,
-        );
+        // Publishers with another type hash are filtered according to the type check policy
+        let key_expr = topic_keyexpr(node.context().domain_id(), fq_topic_name, type_name, "*");
 
-        // Create channel for received messages
+        // Create channel for received messages, holding the last `depth`
+        // messages for KeepLast, and unbounded for KeepAll (usize::MAX)
         let depth = QosMapping::effective_depth(&qos);
-        let (sender, receiver) = flume::bounded(depth);
-
-        // Clone receiver for use in callback (to implement KeepLast drop-oldest semantics)
-        let drain_receiver = receiver.clone();
+        let capacity = buffer.capacity.unwrap_or(depth).max(1);
+        let (sender, receiver) = flume::bounded(capacity);
+        let lagged = Arc::new(AtomicU64::new(0));
+        let drops = DropReporter::new(node.context().transport_monitor(), fq_topic_name);
+        let on_sample = overflow_callback(
+            buffer.overflow,
+            sender,
+            &receiver,
+            lagged.clone(),
+            Some(drops),
+        );
+        let type_check = Arc::new(TypeCheck::new(fq_topic_name, type_hash));
+        let filter = type_check.clone();
+        let paused = Arc::new(AtomicBool::new(buffer.paused));
+        let gate = paused.clone();
+        let incomplete = Arc::new(AtomicU64::new(0));
+        let reassembler = Reassembler::new(
+            fq_topic_name,
+            buffer
+                .reassembly_timeout
+                .unwrap_or(DEFAULT_REASSEMBLY_TIMEOUT),
+            buffer
+                .max_reassembled_size
+                .unwrap_or(DEFAULT_MAX_REASSEMBLED_SIZE),
+            incomplete.clone(),
+        );
 
         // Create Zenoh subscriber
         let session = node.context().session();
@@ -106,14 +224,13 @@ impl<T: TypeSupport> Subscriber<T> {
         };
         let zenoh_subscriber = session
             .declare_subscriber(&key_expr)
-            .callback(move |sample: zenoh::sample::Sample| {
-                // KeepLast(n) semantics: if channel is full, drop oldest message first
-                if sender.is_full() {
-                    // Drain one message to make room (drop oldest)
-                    let _ = drain_receiver.try_recv();
+            .callback(move |sample: Sample| {
+                if !gate.load(Ordering::Relaxed)
+                    && filter.accept(sample.key_expr())
+                    && let Some(sample) = reassembler.push(sample)
+                {
+                    on_sample(sample);
                 }
-                // Now there's room - this should always succeed
-                let _ = sender.try_send(sample);
             })
             .history(zenoh_ext::HistoryConfig::default().max_samples(history_depth))
             .wait()?;
@@ -133,7 +250,7 @@ impl<T: TypeSupport> Subscriber<T> {
             &node.z_name()?,
             fq_topic_name,
             type_name,
-            &type_hash,
+            type_hash,
             &qos,
         );
 
@@ -146,130 +263,560 @@ impl<T: TypeSupport> Subscriber<T> {
             "Subscriber created"
         );
 
+        let queue = Arc::new(QueueProbe {
+            receiver: receiver.clone(),
+            lagged: lagged.clone(),
+        });
+        node.register_queue(&queue);
+
         Ok(Subscriber {
+            _entity: node.entities().register(stats::EntityKind::Subscriber),
+            _queue: queue,
             node,
             topic_name: topic_name.to_string(),
             fq_topic_name: fq_topic_name.to_string(),
             gid,
+            type_info: TypeInfo::new(type_name, type_hash),
             receiver,
+            lagged,
+            incomplete,
+            paused,
+            type_check,
             _liveliness_token: liveliness_token,
             _zenoh_subscriber: zenoh_subscriber,
             _phantom: PhantomData,
         })
     }
-}
 
-impl<T: TypeSupport> Subscriber<T> {
-    /// Get the topic name.
-    pub fn z_topic_name(&self) -> Result<Cow<'_, String>> {
-        Ok(Cow::Borrowed(&self.topic_name))
-    }
+    /// Receive raw CDR bytes asynchronously without deserializing.
+    ///
+    /// Returns the raw CDR payload and message metadata.
+    pub async fn z_recv_raw(&mut self) -> Result<(Vec<u8>, oxidros_core::message::MessageInfo)> {
+        let sample = self
+            .receiver
+            .recv_async()
+            .await
+            .map_err(|_| Error::ChannelClosed)?;
+        let raw_bytes = sample.payload().to_bytes().to_vec();
+        let attachment_bytes = sample.attachment().ok_or(Error::MissingAttachment)?;
+        let attachment = Attachment::from_bytes(&attachment_bytes.to_bytes())?;
 
-    /// Get the fully qualified topic name.
-    pub fn fully_qualified_topic_name(&self) -> &str {
-        &self.fq_topic_name
+        tracing::debug!(
+            target: targets::ZENOH_SUBSCRIBER,
+            topic = %self.fq_topic_name,
+            seq = attachment.sequence_number,
+            "Received raw message"
+        );
+
+        Ok((raw_bytes, attachment.into()))
     }
 
-    /// Get the subscriber GID.
-    pub fn gid(&self) -> &[u8; GID_SIZE] {
-        &self.gid
+    /// Try to receive raw CDR bytes without blocking or deserializing.
+    pub fn z_try_recv_raw(&self) -> Result<Option<(Vec<u8>, oxidros_core::message::MessageInfo)>> {
+        match self.receiver.try_recv() {
+            Ok(sample) => {
+                let raw_bytes = sample.payload().to_bytes().to_vec();
+                let attachment_bytes = sample.attachment().ok_or(Error::MissingAttachment)?;
+                let info = Attachment::from_bytes(&attachment_bytes.to_bytes())?.into();
+                Ok(Some((raw_bytes, info)))
+            }
+            Err(flume::TryRecvError::Empty) => Ok(None),
+            Err(flume::TryRecvError::Disconnected) => Err(Error::ChannelClosed),
+        }
     }
 
-    /// Receive a message asynchronously.
+    /// Receive a message asynchronously without deserializing it.
+    ///
+    /// The returned [`BorrowedMessage`] keeps the received payload, and
+    /// deserializes views borrowing its strings and byte sequences with
+    /// [`BorrowedMessage::view`].
     ///
     /// # Errors
     ///
-    /// Returns an error if deserialization fails, the channel is closed,
-    /// or the message has a missing/invalid attachment.
-    pub async fn z_recv(&mut self) -> Result<Message<T>> {
+    /// Returns an error if the channel is closed, or the message has a
+    /// missing/invalid attachment.
+    pub async fn z_recv_borrowed(&mut self) -> Result<BorrowedMessage> {
         let sample = self
             .receiver
             .recv_async()
             .await
             .map_err(|_| Error::ChannelClosed)?;
-        let data = T::from_bytes(&sample.payload().to_bytes())?;
-        let attachment_bytes = sample.attachment().ok_or(Error::MissingAttachment)?;
-        let attachment = Attachment::from_bytes(&attachment_bytes.to_bytes())?;
+        let msg = BorrowedMessage::new(sample)?;
 
         tracing::debug!(
             target: targets::ZENOH_SUBSCRIBER,
             topic = %self.fq_topic_name,
-            seq = attachment.sequence_number,
+            seq = msg.info.sequence_number,
             "Received message"
         );
 
-        Ok(Message::new(data, attachment.into()))
+        Ok(msg)
     }
 
-    /// Try to receive a message without blocking.
+    /// Try to receive a message without blocking or deserializing it.
     ///
     /// Returns `None` if no message is available.
     ///
     /// # Errors
     ///
-    /// Returns an error if deserialization fails or the message has a
-    /// missing/invalid attachment.
-    pub fn z_try_recv(&self) -> Result<Option<Message<T>>> {
+    /// Returns an error if the message has a missing/invalid attachment.
+    pub fn z_try_recv_borrowed(&self) -> Result<Option<BorrowedMessage>> {
         match self.receiver.try_recv() {
-            Ok(sample) => {
-                let data = T::from_bytes(&sample.payload().to_bytes())?;
-                let attachment_bytes = sample.attachment().ok_or(Error::MissingAttachment)?;
-                let info = Attachment::from_bytes(&attachment_bytes.to_bytes())?.into();
-                Ok(Some(Message::new(data, info)))
-            }
+            Ok(sample) => BorrowedMessage::new(sample).map(Some),
             Err(flume::TryRecvError::Empty) => Ok(None),
             Err(flume::TryRecvError::Disconnected) => Err(Error::ChannelClosed),
         }
     }
+}
 
-    /// Receive a message, blocking until one is available.
+/// A received message kept as CDR bytes, see [`Subscriber::z_recv_borrowed`].
+///
+/// For large messages of which only a few fields are inspected, a view whose
+/// `&str` and `&[u8]` fields borrow from the payload avoids copying them:
+///
+/// ```ignore
+/// #[derive(serde::Deserialize)]
+/// struct ImageView<'a> {
+///     #[serde(borrow)]
+///     header: HeaderView<'a>,
+///     height: u32,
+///     width: u32,
+///     encoding: &'a str,
+///     is_bigendian: u8,
+///     step: u32,
+///     data: &'a [u8],
+/// }
+///
+/// let msg = subscriber.z_recv_borrowed().await?;
+/// let image: ImageView = msg.view()?;
+/// ```
+pub struct BorrowedMessage {
+    sample: ReceivedSample,
+    /// Copy of the payload when Zenoh delivered it in several slices.
+    contiguous: Option<Vec<u8>>,
+    /// Metadata of the message.
+    pub info: oxidros_core::message::MessageInfo,
+}
+
+impl BorrowedMessage {
+    fn new(sample: ReceivedSample) -> Result<Self> {
+        let attachment_bytes = sample.attachment().ok_or(Error::MissingAttachment)?;
+        let info = Attachment::from_bytes(&attachment_bytes.to_bytes())?.into();
+        let payload = sample.payload();
+        let contiguous = (payload.slices().count() > 1).then(|| payload.to_bytes().into_owned());
+        Ok(Self {
+            sample,
+            contiguous,
+            info,
+        })
+    }
+
+    /// Get the CDR payload, encapsulation header included.
+    pub fn bytes(&self) -> &[u8] {
+        match &self.contiguous {
+            Some(bytes) => bytes,
+            None => self.sample.payload().slices().next().unwrap_or_default(),
+        }
+    }
+
+    /// Deserialize a view borrowing from the payload.
     ///
     /// # Errors
     ///
-    /// Returns an error if deserialization fails, the channel is closed,
-    /// or the message has a missing/invalid attachment.
-    pub fn recv_blocking(&self) -> Result<Message<T>> {
-        let sample = self.receiver.recv().map_err(|_| Error::ChannelClosed)?;
-        let data = T::from_bytes(&sample.payload().to_bytes())?;
-        let attachment_bytes = sample.attachment().ok_or(Error::MissingAttachment)?;
-        let info = Attachment::from_bytes(&attachment_bytes.to_bytes())?.into();
-        Ok(Message::new(data, info))
+    /// Returns an error if deserialization fails.
+    pub fn view<'a, V: TypeSupportBorrowed<'a>>(&'a self) -> Result<V> {
+        Ok(V::from_bytes_borrowed(self.bytes())?)
     }
 
-    /// Receive raw CDR bytes asynchronously without deserializing.
+    /// Deserialize the whole message.
     ///
-    /// Returns the raw CDR payload and message metadata.
-    pub async fn z_recv_raw(&mut self) -> Result<(Vec<u8>, oxidros_core::message::MessageInfo)> {
-        let sample = self
-            .receiver
-            .recv_async()
-            .await
-            .map_err(|_| Error::ChannelClosed)?;
-        let raw_bytes = sample.payload().to_bytes().to_vec();
+    /// # Errors
+    ///
+    /// Returns an error if deserialization fails.
+    pub fn to_message<T: TypeSupport>(&self) -> Result<Message<T>> {
+        let data = T::from_bytes(self.bytes())?;
+        Ok(Message::new(data, self.info))
+    }
+}
+
+/// Receive buffer of a subscriber, observed by [`Node::z_stats`].
+pub(crate) struct QueueProbe {
+    receiver: flume::Receiver<ReceivedSample>,
+    lagged: Arc<AtomicU64>,
+}
+
+impl QueueProbe {
+    /// Number of messages waiting in the buffer.
+    pub(crate) fn len(&self) -> usize {
+        self.receiver.len()
+    }
+
+    /// Number of messages dropped because the buffer was full.
+    pub(crate) fn lagged(&self) -> u64 {
+        self.lagged.load(Ordering::Relaxed)
+    }
+}
+
+/// Filter of the samples of a subscriber by the type hash of their publisher.
+pub(crate) struct TypeCheck {
+    fq_topic_name: String,
+    type_hash: String,
+    policy: AtomicU8,
+    /// Type hashes of publishers already reported.
+    reported: Mutex<HashSet<String>>,
+}
+
+impl TypeCheck {
+    fn new(fq_topic_name: &str, type_hash: &str) -> Self {
+        TypeCheck {
+            fq_topic_name: fq_topic_name.to_string(),
+            type_hash: type_hash.to_string(),
+            policy: AtomicU8::new(TypeCheckPolicy::Strict as u8),
+            reported: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn policy(&self) -> TypeCheckPolicy {
+        match self.policy.load(Ordering::Relaxed) {
+            1 => TypeCheckPolicy::WarnOnly,
+            2 => TypeCheckPolicy::BestEffortDecode,
+            _ => TypeCheckPolicy::Strict,
+        }
+    }
+
+    fn set_policy(&self, policy: TypeCheckPolicy) {
+        self.policy.store(policy as u8, Ordering::Relaxed);
+    }
+
+    /// Type hash of the publisher of the samples on `key_expr`, if it differs from ours.
+    fn mismatch<'a>(&self, key_expr: &'a KeyExpr<'_>) -> Option<&'a str> {
+        let type_hash = key_expr.as_str().rsplit('/').next()?;
+        (type_hash != self.type_hash).then_some(type_hash)
+    }
+
+    /// Whether the samples on `key_expr` are delivered to the subscriber.
+    ///
+    /// The first sample of each mismatching type hash is logged.
+    fn accept(&self, key_expr: &KeyExpr<'_>) -> bool {
+        let Some(type_hash) = self.mismatch(key_expr) else {
+            return true;
+        };
+        let policy = self.policy();
+        if self.reported.lock().insert(type_hash.to_string()) {
+            if policy == TypeCheckPolicy::Strict {
+                tracing::debug!(
+                    target: targets::ZENOH_SUBSCRIBER,
+                    topic = %self.fq_topic_name,
+                    expected = %self.type_hash,
+                    received = %type_hash,
+                    "Ignoring messages with another type hash"
+                );
+            } else {
+                tracing::warn!(
+                    target: targets::ZENOH_SUBSCRIBER,
+                    topic = %self.fq_topic_name,
+                    expected = %self.type_hash,
+                    received = %type_hash,
+                    ?policy,
+                    "Receiving messages with another type hash"
+                );
+            }
+        }
+        policy != TypeCheckPolicy::Strict
+    }
+
+    /// Deserialize `sample`, or return `None` if it is skipped.
+    fn decode<T: TypeSupport>(
+        &self,
+        sample: &ReceivedSample,
+    ) -> Result<Option<(Message<T>, TraceContext)>> {
+        let data = match T::from_bytes(&sample.payload().to_bytes()) {
+            Ok(data) => data,
+            Err(e)
+                if self.policy() == TypeCheckPolicy::BestEffortDecode
+                    && self.mismatch(sample.key_expr()).is_some() =>
+            {
+                tracing::debug!(
+                    target: targets::ZENOH_SUBSCRIBER,
+                    topic = %self.fq_topic_name,
+                    error = %e,
+                    "Skipping message with another type hash"
+                );
+                return Ok(None);
+            }
+            Err(e) => return Err(e.into()),
+        };
         let attachment_bytes = sample.attachment().ok_or(Error::MissingAttachment)?;
-        let attachment = Attachment::from_bytes(&attachment_bytes.to_bytes())?;
+        let mut attachment = Attachment::from_bytes(&attachment_bytes.to_bytes())?;
+        let trace_context = std::mem::take(&mut attachment.trace_context);
+        Ok(Some((Message::new(data, attachment.into()), trace_context)))
+    }
+}
+
+/// Build the Zenoh callback filling the receive buffer according to `overflow`.
+///
+/// Dropped messages are counted in `lagged` and reported to `drops`.
+fn overflow_callback<S: Send + 'static>(
+    overflow: OverflowPolicy,
+    sender: flume::Sender<S>,
+    receiver: &flume::Receiver<S>,
+    lagged: Arc<AtomicU64>,
+    drops: Option<DropReporter>,
+) -> Box<dyn Fn(S) + Send + Sync> {
+    let dropped = move || {
+        lagged.fetch_add(1, Ordering::Relaxed);
+        if let Some(drops) = &drops {
+            drops.report();
+        }
+    };
+    match overflow {
+        OverflowPolicy::DropOldest => {
+            // Clone receiver for use in callback (to implement KeepLast drop-oldest semantics)
+            let drain_receiver = receiver.clone();
+            Box::new(move |sample| {
+                if sender.is_full() && drain_receiver.try_recv().is_ok() {
+                    dropped();
+                }
+                if sender.try_send(sample).is_err() {
+                    dropped();
+                }
+            })
+        }
+        OverflowPolicy::DropNewest => Box::new(move |sample| {
+            if let Err(flume::TrySendError::Full(_)) = sender.try_send(sample) {
+                dropped();
+            }
+        }),
+        // No receiver is kept here, so dropping the subscriber disconnects the
+        // channel and releases a blocked callback.
+        OverflowPolicy::Block => Box::new(move |sample| {
+            let _ = sender.send(sample);
+        }),
+    }
+}
+
+impl<T> Subscriber<T> {
+    /// Get the topic name.
+    pub fn z_topic_name(&self) -> Result<Cow<'_, String>> {
+        Ok(Cow::Borrowed(&self.topic_name))
+    }
+
+    /// Get the fully qualified topic name.
+    pub fn fully_qualified_topic_name(&self) -> &str {
+        &self.fq_topic_name
+    }
+
+    /// Context of the errors of taking messages.
+    fn take_context(&self) -> ErrorContext {
+        entity_context(&self.node, Operation::Take, &self.fq_topic_name)
+    }
+
+    /// Get the subscriber GID.
+    pub fn gid(&self) -> &[u8; GID_SIZE] {
+        &self.gid
+    }
+
+    /// Get the type name and hash advertised in the liveliness token of the
+    /// subscriber, the hash being the one expected from the publishers.
+    pub fn type_info(&self) -> &TypeInfo {
+        &self.type_info
+    }
 
+    /// Number of messages dropped so far because the receive buffer was full.
+    ///
+    /// A growing value means the consumer is slower than the publishers.
+    pub fn lagged(&self) -> u64 {
+        self.lagged.load(Ordering::Relaxed)
+    }
+
+    /// Number of messages split by their publisher and dropped because their
+    /// chunks did not all arrive within [`BufferOptions::reassembly_timeout`],
+    /// or because too many messages were being reassembled.
+    pub fn incomplete(&self) -> u64 {
+        self.incomplete.load(Ordering::Relaxed)
+    }
+
+    /// Number of messages waiting in the receive buffer.
+    pub fn buffered(&self) -> usize {
+        self.receiver.len()
+    }
+
+    /// Stop taking messages, e.g. while the processing of an expensive topic
+    /// is disabled by a mode switch.
+    ///
+    /// The buffered messages are dropped, and the incoming ones are discarded
+    /// without being buffered or counted as [`lagged`](Self::lagged) until
+    /// [`resume`](Self::resume). The subscriber stays matched with the
+    /// publishers, so resuming delivers the next message without discovery
+    /// delay.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+        self.receiver.drain().for_each(drop);
         tracing::debug!(
             target: targets::ZENOH_SUBSCRIBER,
             topic = %self.fq_topic_name,
-            seq = attachment.sequence_number,
-            "Received raw message"
+            "Subscriber paused"
         );
+    }
 
-        Ok((raw_bytes, attachment.into()))
+    /// Start taking messages again, after [`pause`](Self::pause) or the
+    /// creation of a subscriber with [`BufferOptions::paused`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use oxidros_zenoh::topic::BufferOptions;
+    ///
+    /// let buffer = BufferOptions {
+    ///     paused: true,
+    ///     ..Default::default()
+    /// };
+    /// let mut subscriber =
+    ///     node.z_create_subscriber_with_buffer::<sensor_msgs::msg::PointCloud2>("points", None, buffer)?;
+    ///
+    /// // Once the mapping mode is enabled
+    /// subscriber.resume();
+    /// let cloud = subscriber.z_recv().await?;
+    /// ```
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+        tracing::debug!(
+            target: targets::ZENOH_SUBSCRIBER,
+            topic = %self.fq_topic_name,
+            "Subscriber resumed"
+        );
     }
 
-    /// Try to receive raw CDR bytes without blocking or deserializing.
-    pub fn z_try_recv_raw(&self) -> Result<Option<(Vec<u8>, oxidros_core::message::MessageInfo)>> {
-        match self.receiver.try_recv() {
-            Ok(sample) => {
-                let raw_bytes = sample.payload().to_bytes().to_vec();
-                let attachment_bytes = sample.attachment().ok_or(Error::MissingAttachment)?;
-                let info = Attachment::from_bytes(&attachment_bytes.to_bytes())?.into();
-                Ok(Some((raw_bytes, info)))
+    /// Whether incoming messages are discarded, see [`pause`](Self::pause).
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Set how the messages of publishers with another type hash are handled.
+    ///
+    /// Subscribers are [`TypeCheckPolicy::Strict`] by default. Publishers with
+    /// another type hash can be found with
+    /// [`Graph::topic_types`](crate::graph::Graph::topic_types).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use oxidros_zenoh::TypeCheckPolicy;
+    ///
+    /// let subscriber = node
+    ///     .z_create_subscriber::<std_msgs::msg::String>("chatter", None)?
+    ///     .with_type_check(TypeCheckPolicy::WarnOnly);
+    /// ```
+    pub fn with_type_check(self, policy: TypeCheckPolicy) -> Self {
+        self.type_check.set_policy(policy);
+        self
+    }
+
+    /// Get how the messages of publishers with another type hash are handled.
+    pub fn type_check(&self) -> TypeCheckPolicy {
+        self.type_check.policy()
+    }
+}
+
+impl<T> fmt::Debug for Subscriber<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscriber")
+            .field("topic", &self.fq_topic_name)
+            .field("type_info", &self.type_info)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: TypeSupport> Subscriber<T> {
+    /// Receive a message asynchronously.
+    ///
+    /// This is cancel safe: samples stay in the queue of the subscriber until
+    /// the poll that returns them, so dropping the future loses no message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deserialization fails, the channel is closed,
+    /// or the message has a missing/invalid attachment.
+    pub async fn z_recv(&mut self) -> Result<Message<T>> {
+        self.z_recv_with_context()
+            .await
+            .map(|(msg, _trace_context)| msg)
+    }
+
+    /// Receive a message asynchronously, along with the trace context of the sender.
+    ///
+    /// Use [`TraceContext::link_span`] to continue the sender's trace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deserialization fails, the channel is closed,
+    /// or the message has a missing/invalid attachment.
+    pub async fn z_recv_with_context(&mut self) -> Result<(Message<T>, TraceContext)> {
+        loop {
+            let sample = self
+                .receiver
+                .recv_async()
+                .await
+                .map_err(|_| Error::ChannelClosed)?;
+            if let Some((msg, trace_context)) = self.type_check.decode(&sample)? {
+                tracing::debug!(
+                    target: targets::ZENOH_SUBSCRIBER,
+                    topic = %self.fq_topic_name,
+                    seq = msg.info.sequence_number,
+                    "Received message"
+                );
+                return Ok((msg, trace_context));
+            }
+        }
+    }
+
+    /// Try to receive a message without blocking.
+    ///
+    /// Returns `None` if no message is available.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deserialization fails or the message has a
+    /// missing/invalid attachment.
+    pub fn z_try_recv(&self) -> Result<Option<Message<T>>> {
+        self.z_try_recv_with_context()
+            .map(|received| received.map(|(msg, _trace_context)| msg))
+    }
+
+    /// Try to receive a message without blocking, along with the trace context of the sender.
+    ///
+    /// Returns `None` if no message is available.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deserialization fails or the message has a
+    /// missing/invalid attachment.
+    pub fn z_try_recv_with_context(&self) -> Result<Option<(Message<T>, TraceContext)>> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(sample) => {
+                    if let Some(received) = self.type_check.decode(&sample)? {
+                        return Ok(Some(received));
+                    }
+                }
+                Err(flume::TryRecvError::Empty) => return Ok(None),
+                Err(flume::TryRecvError::Disconnected) => return Err(Error::ChannelClosed),
+            }
+        }
+    }
+
+    /// Receive a message, blocking until one is available.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deserialization fails, the channel is closed,
+    /// or the message has a missing/invalid attachment.
+    pub fn recv_blocking(&self) -> Result<Message<T>> {
+        loop {
+            let sample = self.receiver.recv().map_err(|_| Error::ChannelClosed)?;
+            if let Some((msg, _trace_context)) = self.type_check.decode(&sample)? {
+                return Ok(msg);
             }
-            Err(flume::TryRecvError::Empty) => Ok(None),
-            Err(flume::TryRecvError::Disconnected) => Err(Error::ChannelClosed),
         }
     }
 
@@ -277,6 +824,13 @@ impl<T: TypeSupport> Subscriber<T> {
     pub fn node(&self) -> &Arc<Node> {
         &self.node
     }
+
+    /// Get the local addresses of the links the messages are received over.
+    ///
+    /// See [`Context::network_flow_endpoints`](crate::Context::network_flow_endpoints).
+    pub fn get_network_flow_endpoints(&self) -> Result<Vec<NetworkFlowEndpoint>> {
+        self.node.context().network_flow_endpoints()
+    }
 }
 
 // ============================================================================
@@ -289,19 +843,25 @@ impl<T: TypeSupport + Send + 'static> oxidros_core::api::RosSubscriber<T> for Su
     }
 
     async fn recv(&mut self) -> Result<Message<T>> {
-        self.z_recv().await
+        let result = self.z_recv().await;
+        result.with_context(|| self.take_context())
     }
 
     fn try_recv(&mut self) -> Result<Option<Message<T>>> {
-        self.z_try_recv()
+        self.z_try_recv().with_context(|| self.take_context())
     }
 
     async fn recv_raw(&mut self) -> Result<(Vec<u8>, oxidros_core::message::MessageInfo)> {
-        self.z_recv_raw().await
+        let result = self.z_recv_raw().await;
+        result.with_context(|| self.take_context())
     }
 
     fn try_recv_raw(&mut self) -> Result<Option<(Vec<u8>, oxidros_core::message::MessageInfo)>> {
-        self.z_try_recv_raw()
+        self.z_try_recv_raw().with_context(|| self.take_context())
+    }
+
+    fn network_flow_endpoints(&self) -> Result<Vec<NetworkFlowEndpoint>> {
+        self.get_network_flow_endpoints()
     }
 
     fn into_stream(self) -> oxidros_core::MessageStream<T>
@@ -315,12 +875,16 @@ impl<T: TypeSupport + Send + 'static> oxidros_core::api::RosSubscriber<T> for Su
 /// A stream wrapper for async subscription using flume's native async support.
 pub struct SubscriberStream<T: TypeSupport + Send + 'static> {
     /// Flume's async stream for receiving samples
-    inner: flume::r#async::RecvStream<'static, zenoh::sample::Sample>,
-    /// Phantom for the message type
-    _phantom: PhantomData<T>,
+    inner: flume::r#async::RecvStream<'static, ReceivedSample>,
+    /// Filter of the messages by type hash
+    type_check: Arc<TypeCheck>,
+    /// Context of the errors of taking messages
+    context: ErrorContext,
+    /// Keeps the Zenoh subscriber, and so the sender of the channel, alive
+    _subscriber: Subscriber<T>,
 }
 
-// SAFETY: RecvStream is Unpin, PhantomData is Unpin
+// The fields are never pinned: the stream polls `inner` through `Pin::new`
 impl<T: TypeSupport + Send + 'static> Unpin for SubscriberStream<T> {}
 
 impl<T: TypeSupport + Send + 'static> SubscriberStream<T> {
@@ -328,10 +892,14 @@ impl<T: TypeSupport + Send + 'static> SubscriberStream<T> {
     pub fn new(subscriber: Subscriber<T>) -> Self {
         // Convert the receiver into an owned stream
         // We use into_stream() which gives us a 'static lifetime stream
-        let inner = subscriber.receiver.into_stream();
+        let type_check = subscriber.type_check.clone();
+        let context = subscriber.take_context();
+        let inner = subscriber.receiver.clone().into_stream();
         Self {
             inner,
-            _phantom: PhantomData,
+            type_check,
+            context,
+            _subscriber: subscriber,
         }
     }
 }
@@ -347,20 +915,67 @@ impl<T: TypeSupport + Send + 'static> futures_core::Stream for SubscriberStream<
 
         let this = self.get_mut();
 
-        // Poll the inner flume stream
-        match std::pin::Pin::new(&mut this.inner).poll_next(cx) {
-            Poll::Ready(Some(sample)) => {
-                // Deserialize the message
-                let result = (|| {
-                    let data = T::from_bytes(&sample.payload().to_bytes())?;
-                    let attachment_bytes = sample.attachment().ok_or(Error::MissingAttachment)?;
-                    let info = Attachment::from_bytes(&attachment_bytes.to_bytes())?.into();
-                    Ok(Message::new(data, info))
-                })();
-                Poll::Ready(Some(result))
+        // Poll the inner flume stream until a message is decoded
+        loop {
+            match std::pin::Pin::new(&mut this.inner).poll_next(cx) {
+                Poll::Ready(Some(sample)) => match this.type_check.decode(&sample) {
+                    Ok(Some((msg, _trace_context))) => return Poll::Ready(Some(Ok(msg))),
+                    Ok(None) => continue,
+                    Err(e) => {
+                        return Poll::Ready(Some(Err(e.with_context(this.context.clone()))));
+                    }
+                },
+                Poll::Ready(None) => return Poll::Ready(None), // Channel closed
+                Poll::Pending => return Poll::Pending,
             }
-            Poll::Ready(None) => Poll::Ready(None), // Channel closed
-            Poll::Pending => Poll::Pending,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(overflow: OverflowPolicy) -> (Vec<u32>, u64) {
+        let (sender, receiver) = flume::bounded(2);
+        let lagged = Arc::new(AtomicU64::new(0));
+        let callback = overflow_callback(overflow, sender, &receiver, lagged.clone(), None);
+        for i in 0..5 {
+            callback(i);
+        }
+        (
+            receiver.try_iter().collect(),
+            lagged.load(Ordering::Relaxed),
+        )
+    }
+
+    #[test]
+    fn test_overflow_policies() {
+        assert_eq!(fill(OverflowPolicy::DropOldest), (vec![3, 4], 3));
+        assert_eq!(fill(OverflowPolicy::DropNewest), (vec![0, 1], 3));
+    }
+
+    #[test]
+    fn test_overflow_block() {
+        let (sender, receiver) = flume::bounded(1);
+        let lagged = Arc::new(AtomicU64::new(0));
+        let callback = overflow_callback(
+            OverflowPolicy::Block,
+            sender,
+            &receiver,
+            lagged.clone(),
+            None,
+        );
+
+        let producer = std::thread::spawn(move || {
+            for i in 0..3 {
+                callback(i);
+            }
+        });
+        let received: Vec<u32> = (0..3).map(|_| receiver.recv().unwrap()).collect();
+        producer.join().unwrap();
+
+        assert_eq!(received, vec![0, 1, 2]);
+        assert_eq!(lagged.load(Ordering::Relaxed), 0);
+    }
+}
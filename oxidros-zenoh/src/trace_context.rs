@@ -0,0 +1,244 @@
+//! Distributed trace context carried in message attachments.
+//!
+//! When the `otel` feature is enabled, the W3C trace context (`traceparent`,
+//! `tracestate`) of the current [`tracing::Span`] is injected into the
+//! attachment of every published message, service request and service
+//! response. Receivers extract it and make their callback spans children of
+//! the remote span, so a single trace follows a request across nodes.
+//!
+//! The context is written through the globally registered OpenTelemetry
+//! text map propagator, so the application must install one, e.g.:
+//!
+//! ```ignore
+//! opentelemetry::global::set_text_map_propagator(
+//!     opentelemetry_sdk::propagation::TraceContextPropagator::new(),
+//! );
+//! ```
+//!
+//! The RCL backend has no equivalent of the attachment, and no header field
+//! can be reserved in messages without breaking other ROS 2 nodes, so trace
+//! context is only propagated between nodes using the Zenoh backend. The
+//! `otel` feature of the `oxidros` crate selects this backend, and conflicts
+//! with `rcl`.
+//!
+//! # Wire Format
+//!
+//! The entries are appended after the fixed 33-byte attachment header and are
+//! only present when the context is non-empty, so messages stay byte-for-byte
+//! compatible with `rmw_zenoh_cpp` when tracing is not in use.
+//!
+//! | Size | Content |
+//! |------|---------|
+//! | 1 | Number of entries (N) |
+//! | N × (1 + K + 2 + V) | Key length (u8), key, value length (u16 LE), value |
+
+use crate::error::{Error, Result};
+
+/// Maximum number of entries encoded in an attachment.
+const MAX_ENTRIES: usize = u8::MAX as usize;
+
+/// Propagated trace context as key/value pairs (e.g. `traceparent`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceContext {
+    entries: Vec<(String, String)>,
+}
+
+impl TraceContext {
+    /// Capture the context of the current span.
+    ///
+    /// Returns an empty context when the `otel` feature is disabled.
+    pub fn current() -> Self {
+        Self {
+            entries: otel::inject_current(),
+        }
+    }
+
+    /// Make `span` a child of the remote span described by this context.
+    ///
+    /// Does nothing when the context is empty or the `otel` feature is disabled.
+    pub fn link_span(&self, span: &tracing::Span) {
+        if !self.entries.is_empty() {
+            otel::set_parent(span, &self.entries);
+        }
+    }
+
+    /// Insert or replace an entry.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        let key = key.into();
+        let value = value.into();
+        match self.entries.iter_mut().find(|(k, _)| *k == key) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key, value)),
+        }
+    }
+
+    /// Get the value of an entry.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Iterate over all entries.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns `true` if no context is present.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Append the encoded entries to `out`.
+    ///
+    /// Entries whose key or value do not fit the length prefixes are skipped.
+    pub(crate) fn encode(&self, out: &mut Vec<u8>) {
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|(k, v)| k.len() <= u8::MAX as usize && v.len() <= u16::MAX as usize)
+            .take(MAX_ENTRIES)
+            .collect();
+
+        out.push(entries.len() as u8);
+        for (key, value) in entries {
+            out.push(key.len() as u8);
+            out.extend_from_slice(key.as_bytes());
+            out.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            out.extend_from_slice(value.as_bytes());
+        }
+    }
+
+    /// Decode entries written by [`TraceContext::encode`] at the start of
+    /// `bytes`, returning the bytes following them.
+    ///
+    /// An empty slice decodes to an empty context.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidAttachment` if the entries are truncated or not UTF-8.
+    pub(crate) fn decode_prefix(bytes: &[u8]) -> Result<(Self, &[u8])> {
+        let Some((&count, mut rest)) = bytes.split_first() else {
+            return Ok((Self::default(), bytes));
+        };
+
+        let mut entries = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let (&key_len, tail) = rest
+                .split_first()
+                .ok_or_else(|| Error::InvalidAttachment("truncated trace context".into()))?;
+            let (key, tail) = split_at_checked(tail, key_len as usize)?;
+            let (value_len, tail) = split_at_checked(tail, 2)?;
+            let value_len = u16::from_le_bytes([value_len[0], value_len[1]]) as usize;
+            let (value, tail) = split_at_checked(tail, value_len)?;
+            entries.push((utf8(key)?, utf8(value)?));
+            rest = tail;
+        }
+
+        Ok((Self { entries }, rest))
+    }
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use opentelemetry::propagation::{Extractor, Injector};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    struct InjectCarrier<'a>(&'a mut Vec<(String, String)>);
+
+    impl Injector for InjectCarrier<'_> {
+        fn set(&mut self, key: &str, value: String) {
+            self.0.push((key.to_owned(), value));
+        }
+    }
+
+    struct ExtractCarrier<'a>(&'a [(String, String)]);
+
+    impl Extractor for ExtractCarrier<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v.as_str())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.iter().map(|(k, _)| k.as_str()).collect()
+        }
+    }
+
+    pub(super) fn inject_current() -> Vec<(String, String)> {
+        let cx = tracing::Span::current().context();
+        let mut entries = Vec::new();
+        opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&cx, &mut InjectCarrier(&mut entries))
+        });
+        entries
+    }
+
+    pub(super) fn set_parent(span: &tracing::Span, entries: &[(String, String)]) {
+        let cx = opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.extract(&ExtractCarrier(entries))
+        });
+        span.set_parent(cx);
+    }
+}
+
+#[cfg(not(feature = "otel"))]
+mod otel {
+    pub(super) fn inject_current() -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    pub(super) fn set_parent(_span: &tracing::Span, _entries: &[(String, String)]) {}
+}
+
+fn split_at_checked(bytes: &[u8], mid: usize) -> Result<(&[u8], &[u8])> {
+    bytes
+        .split_at_checked(mid)
+        .ok_or_else(|| Error::InvalidAttachment("truncated trace context".into()))
+}
+
+fn utf8(bytes: &[u8]) -> Result<String> {
+    String::from_utf8(bytes.to_vec())
+        .map_err(|_| Error::InvalidAttachment("trace context is not valid UTF-8".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_context_roundtrip() {
+        let mut ctx = TraceContext::default();
+        ctx.insert(
+            "traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        );
+        ctx.insert("tracestate", "vendor=value");
+
+        let mut bytes = Vec::new();
+        ctx.encode(&mut bytes);
+
+        let (decoded, rest) = TraceContext::decode_prefix(&bytes).unwrap();
+        assert_eq!(decoded, ctx);
+        assert!(rest.is_empty());
+        assert_eq!(decoded.get("tracestate"), Some("vendor=value"));
+    }
+
+    #[test]
+    fn test_trace_context_truncated() {
+        let mut ctx = TraceContext::default();
+        ctx.insert("traceparent", "00-abc");
+
+        let mut bytes = Vec::new();
+        ctx.encode(&mut bytes);
+        bytes.truncate(bytes.len() - 2);
+
+        assert!(matches!(
+            TraceContext::decode_prefix(&bytes),
+            Err(Error::InvalidAttachment(_))
+        ));
+    }
+}
@@ -0,0 +1,490 @@
+//! Transport diagnostics of the Zenoh session.
+//!
+//! The [`TransportMonitor`] of a [`Context`](crate::Context) records the
+//! events degrading the delivery of messages:
+//!
+//! - sessions with peers and routers closed, and opened again after a loss;
+//! - publications failing, or blocked by congestion for longer than
+//!   [`CONGESTION_THRESHOLD`] (reliable publishers only, the others drop);
+//! - messages dropped by full subscriber buffers;
+//! - service calls left without reply when their query timed out.
+//!
+//! The events are counted in [`TransportStats`] and can be received with
+//! [`TransportMonitor::events`]. [`TransportDiagnostics`] publishes them as
+//! `diagnostic_msgs/msg/DiagnosticArray` messages on `/diagnostics`, so that
+//! link degradation shows in `rqt_robot_monitor` or with
+//! `ros2 topic echo /diagnostics`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros_zenoh::transport::{TransportDiagnostics, TransportEvent};
+//!
+//! tokio::spawn(TransportDiagnostics::new(&node).run());
+//!
+//! let events = ctx.transport_monitor().events();
+//! while let Ok(event) = events.recv_async().await {
+//!     if let TransportEvent::Disconnected { zid } = event {
+//!         tracing::warn!("Lost the session with {zid}");
+//!     }
+//! }
+//! ```
+
+use crate::{error::Result, node::Node};
+use oxidros_core::{RclError, targets};
+use oxidros_msg::common_interfaces::diagnostic_msgs::msg::{
+    DiagnosticArray, DiagnosticStatus, DiagnosticStatusSeq, KeyValueSeq,
+};
+use parking_lot::Mutex;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::{self, Display},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Blocking time of a publication above which it is reported as congested.
+pub const CONGESTION_THRESHOLD: Duration = Duration::from_millis(100);
+
+/// Minimum interval between two reports of the messages dropped on a topic.
+pub const DROP_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default topic of [`TransportDiagnostics`].
+pub const DEFAULT_TOPIC: &str = "/diagnostics";
+
+/// Capacity of the channels returned by [`TransportMonitor::events`].
+const EVENT_CAPACITY: usize = 256;
+
+/// Event of the transport of a session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransportEvent {
+    /// A session with a peer or router was opened.
+    Connected {
+        /// Zenoh ID of the remote session.
+        zid: String,
+        /// Kind of the remote session: `router`, `peer` or `client`.
+        whatami: String,
+        /// Whether a session with it was closed before.
+        reconnect: bool,
+    },
+    /// A session with a peer or router was closed.
+    Disconnected {
+        /// Zenoh ID of the remote session.
+        zid: String,
+    },
+    /// A publication failed.
+    PutFailed {
+        /// Fully qualified name of the topic.
+        topic: String,
+        /// Error of Zenoh.
+        error: String,
+    },
+    /// A publication was blocked by congestion.
+    Congested {
+        /// Fully qualified name of the topic.
+        topic: String,
+        /// Time the publication was blocked.
+        blocked: Duration,
+    },
+    /// Messages were dropped by full subscriber buffers since the last report.
+    MessagesDropped {
+        /// Fully qualified name of the topic.
+        topic: String,
+        /// Number of messages dropped.
+        count: u64,
+    },
+    /// A service call got no reply before its query timed out.
+    QueryTimeout {
+        /// Fully qualified name of the service.
+        service: String,
+    },
+}
+
+impl TransportEvent {
+    /// Level of the event, as in `diagnostic_msgs/msg/DiagnosticStatus`.
+    pub fn level(&self) -> u8 {
+        match self {
+            TransportEvent::Connected {
+                reconnect: false, ..
+            } => DiagnosticStatus::OK,
+            TransportEvent::PutFailed { .. } => DiagnosticStatus::ERROR,
+            _ => DiagnosticStatus::WARN,
+        }
+    }
+}
+
+impl Display for TransportEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportEvent::Connected {
+                zid,
+                whatami,
+                reconnect: false,
+            } => write!(f, "session opened with {whatami} {zid}"),
+            TransportEvent::Connected { zid, whatami, .. } => {
+                write!(f, "session reopened with {whatami} {zid}")
+            }
+            TransportEvent::Disconnected { zid } => write!(f, "session closed with {zid}"),
+            TransportEvent::PutFailed { topic, error } => {
+                write!(f, "publication on {topic} failed: {error}")
+            }
+            TransportEvent::Congested { topic, blocked } => {
+                write!(f, "publication on {topic} blocked for {blocked:?}")
+            }
+            TransportEvent::MessagesDropped { topic, count } => {
+                write!(f, "{count} messages dropped on {topic}")
+            }
+            TransportEvent::QueryTimeout { service } => {
+                write!(f, "call of {service} timed out")
+            }
+        }
+    }
+}
+
+/// Counters of the transport events of a session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransportStats {
+    /// Open sessions with peers and routers.
+    pub sessions: usize,
+    /// Sessions opened.
+    pub connections: u64,
+    /// Sessions closed.
+    pub disconnections: u64,
+    /// Sessions opened again with a peer or router after a loss.
+    pub reconnections: u64,
+    /// Failed publications.
+    pub failed_puts: u64,
+    /// Publications blocked by congestion.
+    pub congested_puts: u64,
+    /// Messages dropped by full subscriber buffers.
+    pub dropped_messages: u64,
+    /// Service calls that timed out.
+    pub query_timeouts: u64,
+}
+
+impl TransportStats {
+    fn key_values(&self) -> [(&'static str, String); 8] {
+        [
+            ("sessions", self.sessions.to_string()),
+            ("connections", self.connections.to_string()),
+            ("disconnections", self.disconnections.to_string()),
+            ("reconnections", self.reconnections.to_string()),
+            ("failed_puts", self.failed_puts.to_string()),
+            ("congested_puts", self.congested_puts.to_string()),
+            ("dropped_messages", self.dropped_messages.to_string()),
+            ("query_timeouts", self.query_timeouts.to_string()),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct MonitorState {
+    stats: TransportStats,
+    /// Remote sessions currently open.
+    sessions: HashSet<String>,
+    /// Remote sessions seen closed.
+    lost: HashSet<String>,
+    /// Messages dropped per topic since the last report.
+    pending_drops: BTreeMap<String, u64>,
+    last_drop_report: Option<Instant>,
+}
+
+/// Recorder of the transport events of a session.
+#[derive(Default)]
+pub struct TransportMonitor {
+    state: Mutex<MonitorState>,
+    listeners: Mutex<Vec<flume::Sender<TransportEvent>>>,
+}
+
+impl TransportMonitor {
+    /// Get the counters of the events recorded so far.
+    pub fn stats(&self) -> TransportStats {
+        self.state.lock().stats
+    }
+
+    /// Receive the events recorded from now on.
+    ///
+    /// Dropped messages are reported at most once per [`DROP_REPORT_INTERVAL`]
+    /// and topic, when more are dropped or by [`TransportDiagnostics`]. Events
+    /// are discarded while the channel holds more than 256 of them.
+    pub fn events(&self) -> flume::Receiver<TransportEvent> {
+        let (sender, receiver) = flume::bounded(EVENT_CAPACITY);
+        self.listeners.lock().push(sender);
+        receiver
+    }
+
+    pub(crate) fn connected(&self, zid: &str, whatami: &str) {
+        let reconnect = {
+            let mut state = self.state.lock();
+            if !state.sessions.insert(zid.to_string()) {
+                return;
+            }
+            let reconnect = state.lost.remove(zid);
+            state.stats.sessions = state.sessions.len();
+            state.stats.connections += 1;
+            if reconnect {
+                state.stats.reconnections += 1;
+            }
+            reconnect
+        };
+        self.emit(TransportEvent::Connected {
+            zid: zid.to_string(),
+            whatami: whatami.to_string(),
+            reconnect,
+        });
+    }
+
+    pub(crate) fn disconnected(&self, zid: &str) {
+        {
+            let mut state = self.state.lock();
+            if !state.sessions.remove(zid) {
+                return;
+            }
+            state.lost.insert(zid.to_string());
+            state.stats.sessions = state.sessions.len();
+            state.stats.disconnections += 1;
+        }
+        self.emit(TransportEvent::Disconnected {
+            zid: zid.to_string(),
+        });
+    }
+
+    pub(crate) fn put_failed(&self, topic: &str, error: &dyn Display) {
+        self.state.lock().stats.failed_puts += 1;
+        self.emit(TransportEvent::PutFailed {
+            topic: topic.to_string(),
+            error: error.to_string(),
+        });
+    }
+
+    pub(crate) fn put_blocked(&self, topic: &str, blocked: Duration) {
+        if blocked < CONGESTION_THRESHOLD {
+            return;
+        }
+        self.state.lock().stats.congested_puts += 1;
+        self.emit(TransportEvent::Congested {
+            topic: topic.to_string(),
+            blocked,
+        });
+    }
+
+    pub(crate) fn message_dropped(&self, topic: &str) {
+        let mut state = self.state.lock();
+        state.stats.dropped_messages += 1;
+        *state.pending_drops.entry(topic.to_string()).or_default() += 1;
+        let due = state
+            .last_drop_report
+            .is_none_or(|last| last.elapsed() >= DROP_REPORT_INTERVAL);
+        if due {
+            self.report_drops(state);
+        }
+    }
+
+    /// Report the messages dropped since the last report.
+    pub(crate) fn flush_drops(&self) {
+        let state = self.state.lock();
+        if !state.pending_drops.is_empty() {
+            self.report_drops(state);
+        }
+    }
+
+    fn report_drops(&self, mut state: parking_lot::MutexGuard<'_, MonitorState>) {
+        state.last_drop_report = Some(Instant::now());
+        let drops = std::mem::take(&mut state.pending_drops);
+        drop(state);
+        for (topic, count) in drops {
+            self.emit(TransportEvent::MessagesDropped { topic, count });
+        }
+    }
+
+    pub(crate) fn query_timeout(&self, service: &str) {
+        self.state.lock().stats.query_timeouts += 1;
+        self.emit(TransportEvent::QueryTimeout {
+            service: service.to_string(),
+        });
+    }
+
+    fn emit(&self, event: TransportEvent) {
+        if event.level() == DiagnosticStatus::OK {
+            tracing::debug!(target: targets::ZENOH_TRANSPORT, "{event}");
+        } else {
+            tracing::warn!(target: targets::ZENOH_TRANSPORT, "{event}");
+        }
+        self.listeners.lock().retain(|tx| {
+            !matches!(
+                tx.try_send(event.clone()),
+                Err(flume::TrySendError::Disconnected(_))
+            )
+        });
+    }
+}
+
+/// Reporter of the messages dropped by a subscriber.
+pub(crate) struct DropReporter {
+    monitor: Arc<TransportMonitor>,
+    topic: String,
+}
+
+impl DropReporter {
+    pub(crate) fn new(monitor: &Arc<TransportMonitor>, topic: &str) -> Self {
+        DropReporter {
+            monitor: monitor.clone(),
+            topic: topic.to_string(),
+        }
+    }
+
+    pub(crate) fn report(&self) {
+        self.monitor.message_dropped(&self.topic);
+    }
+}
+
+/// Publishes the transport events of the session of a node as diagnostics.
+///
+/// Each event is published as a `diagnostic_msgs/msg/DiagnosticArray` with
+/// one status named `<node>: zenoh transport`, the Zenoh ID of the session as
+/// `hardware_id`, and the [`TransportStats`] as key-value pairs.
+pub struct TransportDiagnostics {
+    node: Arc<Node>,
+    topic: String,
+}
+
+impl TransportDiagnostics {
+    /// Create a publisher of the transport events of `node` on
+    /// [`DEFAULT_TOPIC`].
+    pub fn new(node: &Arc<Node>) -> Self {
+        TransportDiagnostics {
+            node: node.clone(),
+            topic: DEFAULT_TOPIC.to_string(),
+        }
+    }
+
+    /// Topic of the diagnostics.
+    pub fn topic(mut self, topic: &str) -> Self {
+        self.topic = topic.to_string();
+        self
+    }
+
+    /// Publish the events until an error occurs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the publisher fails.
+    pub async fn run(self) -> Result<()> {
+        let monitor = self.node.context().transport_monitor().clone();
+        let events = monitor.events();
+        let publisher = self
+            .node
+            .z_create_publisher::<DiagnosticArray>(&self.topic, None)?;
+        let own_topic = publisher.fully_qualified_topic_name().to_string();
+        let name = format!("{}: zenoh transport", self.node.z_fully_qualified_name()?);
+        let hardware_id = self.node.context().session_id().to_string();
+
+        loop {
+            match tokio::time::timeout(DROP_REPORT_INTERVAL, events.recv_async()).await {
+                Ok(Ok(event)) => {
+                    // Failures of this publisher would feed themselves
+                    if matches!(&event, TransportEvent::PutFailed { topic, .. } if *topic == own_topic)
+                    {
+                        continue;
+                    }
+                    let array = diagnostic_array(&name, &hardware_id, &event, &monitor.stats())?;
+                    publisher.z_send(&array)?;
+                }
+                Ok(Err(_)) => return Ok(()),
+                Err(_) => monitor.flush_drops(),
+            }
+        }
+    }
+}
+
+fn diagnostic_array(
+    name: &str,
+    hardware_id: &str,
+    event: &TransportEvent,
+    stats: &TransportStats,
+) -> Result<DiagnosticArray> {
+    let values = stats.key_values();
+    let mut array = DiagnosticArray::new().ok_or(RclError::BadAlloc)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    array.header.stamp.sec = now.as_secs() as i32;
+    array.header.stamp.nanosec = now.subsec_nanos();
+    array.status = DiagnosticStatusSeq::<0>::new(1).ok_or(RclError::BadAlloc)?;
+
+    let status = &mut array.status.as_mut_slice()[0];
+    status.level = event.level();
+    status.name.assign(name);
+    status.message.assign(&event.to_string());
+    status.hardware_id.assign(hardware_id);
+    status.values = KeyValueSeq::<0>::new(values.len()).ok_or(RclError::BadAlloc)?;
+    for (kv, (key, value)) in status.values.as_mut_slice().iter_mut().zip(&values) {
+        kv.key.assign(key);
+        kv.value.assign(value);
+    }
+    Ok(array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnection() {
+        let monitor = TransportMonitor::default();
+        let events = monitor.events();
+
+        monitor.connected("a1", "router");
+        monitor.connected("a1", "router");
+        monitor.disconnected("a1");
+        monitor.connected("a1", "router");
+
+        let stats = monitor.stats();
+        assert_eq!(stats.sessions, 1);
+        assert_eq!(stats.connections, 2);
+        assert_eq!(stats.disconnections, 1);
+        assert_eq!(stats.reconnections, 1);
+
+        let events: Vec<_> = events.drain().collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].level(), DiagnosticStatus::OK);
+        assert_eq!(events[1], TransportEvent::Disconnected { zid: "a1".into() });
+        assert_eq!(events[2].to_string(), "session reopened with router a1");
+        assert_eq!(events[2].level(), DiagnosticStatus::WARN);
+    }
+
+    #[test]
+    fn test_dropped_messages_are_coalesced() {
+        let monitor = TransportMonitor::default();
+        let events = monitor.events();
+
+        for _ in 0..5 {
+            monitor.message_dropped("/chatter");
+        }
+        assert_eq!(
+            events.drain().collect::<Vec<_>>(),
+            [TransportEvent::MessagesDropped {
+                topic: "/chatter".into(),
+                count: 1
+            }]
+        );
+
+        monitor.flush_drops();
+        assert_eq!(
+            events.drain().collect::<Vec<_>>(),
+            [TransportEvent::MessagesDropped {
+                topic: "/chatter".into(),
+                count: 4
+            }]
+        );
+        assert_eq!(monitor.stats().dropped_messages, 5);
+    }
+
+    #[test]
+    fn test_short_blocking_is_not_congestion() {
+        let monitor = TransportMonitor::default();
+        monitor.put_blocked("/chatter", Duration::from_millis(1));
+        monitor.put_blocked("/chatter", CONGESTION_THRESHOLD);
+        assert_eq!(monitor.stats().congested_puts, 1);
+    }
+}
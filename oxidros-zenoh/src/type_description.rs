@@ -1,13 +1,32 @@
-//! Zenoh-specific type description service (`z_get_type_description`).
+//! Type description services.
 //!
-//! Registers a Zenoh queryable that serves `TypeDescriptionMsg` for types
-//! registered on the node. The protocol is Zenoh-specific (not ROS2 service):
+//! Nodes serve the descriptions of the types registered by their publishers,
+//! subscribers, clients and servers in two ways:
 //!
-//! - Key: `<domain_id>/<node_fqn>/z_get_type_description`
-//! - Request payload: UTF-8 ROS type name (e.g. `"std_msgs/msg/String"`)
-//! - Response payload: JSON-encoded `TypeDescriptionMsg`
+//! - the Zenoh-specific `z_get_type_description` queryable, always declared:
+//!   - Key: `<domain_id>/<node_fqn>/z_get_type_description`
+//!   - Request payload: UTF-8 ROS type name (e.g. `"std_msgs/msg/String"`)
+//!   - Response payload: JSON-encoded `TypeDescriptionMsg`
+//! - the standard `~/get_type_description` service
+//!   (`type_description_interfaces/srv/GetTypeDescription`), created with
+//!   [`Node::create_type_description_server`](crate::Node::create_type_description_server),
+//!   which ROS2 tools of other vendors query.
 
-use oxidros_core::types::TypeDescriptionMsg;
+use crate::service::server::Server;
+use oxidros_core::{
+    calculate_type_hash,
+    types::{IndividualTypeDescription, TypeDescriptionMsg},
+};
+use oxidros_msg::interfaces::type_description_interfaces::{
+    msg::{
+        field::FieldSeq,
+        individual_type_description::{
+            IndividualTypeDescription as IndividualTypeDescriptionMsg, IndividualTypeDescriptionSeq,
+        },
+        type_description::TypeDescription,
+    },
+    srv::{GetTypeDescription, GetTypeDescription_Request, GetTypeDescription_Response},
+};
 use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -60,3 +79,185 @@ fn handle_query(query: &zenoh::query::Query, registry: &TypeRegistry) {
         let _ = query.reply(query.key_expr().clone(), json).wait();
     }
 }
+
+/// Server of the `~/get_type_description` service of a node.
+///
+/// Requests are answered from the Zenoh callback, so the server does not need
+/// to be spun. The service is removed when the server is dropped.
+pub struct TypeDescriptionServer {
+    _server: Server<GetTypeDescription>,
+}
+
+impl TypeDescriptionServer {
+    pub(crate) fn new(server: Server<GetTypeDescription>) -> Self {
+        TypeDescriptionServer { _server: server }
+    }
+}
+
+/// Answer a `GetTypeDescription` request from the registry of the node.
+pub(crate) fn describe(
+    registry: &TypeRegistry,
+    request: &GetTypeDescription_Request,
+) -> GetTypeDescription_Response {
+    let mut response = GetTypeDescription_Response::new().expect("GetTypeDescription_Response");
+    let type_name = request.type_name.get_string();
+    let type_hash = request.type_hash.get_string();
+    let desc = registry.lock().get(&type_name).cloned();
+    let result = match desc {
+        Some(desc) => match calculate_type_hash(&desc) {
+            Ok(hash) if type_hash.is_empty() || type_hash == hash => to_type_description_msg(&desc)
+                .ok_or_else(|| {
+                    format!("Type description of '{type_name}' exceeds the message bounds")
+                }),
+            Ok(hash) => Err(format!("Type '{type_name}' has hash {hash}")),
+            Err(e) => Err(e.to_string()),
+        },
+        None => Err(format!(
+            "Type '{type_name}' not currently in use by this node"
+        )),
+    };
+    match result {
+        Ok(description) => {
+            response.successful = true;
+            response.type_description = description;
+        }
+        Err(reason) => {
+            response.failure_reason.assign(&reason);
+        }
+    }
+    response
+}
+
+fn to_type_description_msg(desc: &TypeDescriptionMsg) -> Option<TypeDescription> {
+    let mut msg = TypeDescription::new()?;
+    msg.type_description = to_individual_msg(&desc.type_description)?;
+    let mut referenced =
+        IndividualTypeDescriptionSeq::<0>::new(desc.referenced_type_descriptions.len())?;
+    for (dst, src) in referenced
+        .as_mut_slice()
+        .iter_mut()
+        .zip(&desc.referenced_type_descriptions)
+    {
+        *dst = to_individual_msg(src)?;
+    }
+    msg.referenced_type_descriptions = referenced;
+    Some(msg)
+}
+
+fn to_individual_msg(desc: &IndividualTypeDescription) -> Option<IndividualTypeDescriptionMsg> {
+    let mut msg = IndividualTypeDescriptionMsg::new()?;
+    if !msg.type_name.assign(&desc.type_name) {
+        return None;
+    }
+    let mut fields = FieldSeq::<0>::new(desc.fields.len())?;
+    for (dst, src) in fields.as_mut_slice().iter_mut().zip(&desc.fields) {
+        dst.name.assign(&src.name);
+        dst.default_value.assign(&src.default_value);
+        dst.r#type.type_id = src.field_type.type_id;
+        dst.r#type.capacity = src.field_type.capacity;
+        dst.r#type.string_capacity = src.field_type.string_capacity;
+        if !dst
+            .r#type
+            .nested_type_name
+            .assign(&src.field_type.nested_type_name)
+        {
+            return None;
+        }
+    }
+    msg.fields = fields;
+    Some(msg)
+}
+
+/// Cache of type descriptions resolved for runtime-typed bridges.
+///
+/// Descriptions are looked up, in order, in the cache, in the registry of the
+/// node and with a `z_get_type_description` query to the nodes of the domain.
+#[cfg(any(feature = "rosbridge", feature = "foxglove", feature = "agent"))]
+#[derive(Default)]
+pub(crate) struct TypeCache {
+    types: Mutex<HashMap<String, TypeDescriptionMsg>>,
+}
+
+#[cfg(any(feature = "rosbridge", feature = "foxglove", feature = "agent"))]
+impl TypeCache {
+    /// Timeout of `z_get_type_description` queries.
+    const QUERY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+    /// Add a description to the cache.
+    pub(crate) fn insert(&self, desc: TypeDescriptionMsg) {
+        self.types
+            .lock()
+            .insert(desc.type_description.type_name.clone(), desc);
+    }
+
+    /// Resolve the description of a fully qualified ROS2 type name.
+    pub(crate) async fn resolve(
+        &self,
+        node: &crate::node::Node,
+        type_name: &str,
+    ) -> crate::error::Result<TypeDescriptionMsg> {
+        let cached = self.types.lock().get(type_name).cloned();
+        if let Some(desc) = cached {
+            return Ok(desc);
+        }
+        let desc = match node.registered_type_description(type_name) {
+            Some(desc) => desc,
+            None => Self::query(node.context(), type_name).await?,
+        };
+        self.insert(desc.clone());
+        Ok(desc)
+    }
+
+    /// Query `z_get_type_description` on all nodes of the domain.
+    async fn query(
+        ctx: &crate::context::Context,
+        type_name: &str,
+    ) -> crate::error::Result<TypeDescriptionMsg> {
+        let key_expr = format!("{}/**/z_get_type_description", ctx.domain_id());
+        let replies = ctx
+            .session()
+            .get(&key_expr)
+            .payload(type_name.as_bytes())
+            .timeout(Self::QUERY_TIMEOUT)
+            .await?;
+        while let Ok(reply) = replies.recv_async().await {
+            if let Ok(sample) = reply.result()
+                && let Ok(desc) = serde_json::from_slice(&sample.payload().to_bytes())
+            {
+                return Ok(desc);
+            }
+        }
+        Err(crate::error::Error::Other(format!(
+            "Unknown type '{type_name}'"
+        )))
+    }
+}
+
+/// Convert a DDS type name found in the graph to its ROS2 form.
+///
+/// `"my_pkg::srv::dds_::MyType_"` → `"my_pkg/srv/MyType"`
+#[cfg(any(feature = "rosbridge", feature = "foxglove"))]
+pub(crate) fn dds_to_ros_type_name(dds_name: &str) -> Option<String> {
+    let mut parts = dds_name.split("::");
+    let (pkg, iface, dds, name) = (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() || dds != "dds_" || pkg.is_empty() || iface.is_empty() {
+        return None;
+    }
+    let name = name.strip_suffix('_').filter(|n| !n.is_empty())?;
+    Some(format!("{pkg}/{iface}/{name}"))
+}
+
+#[cfg(all(test, any(feature = "rosbridge", feature = "foxglove")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dds_to_ros_type_name() {
+        assert_eq!(
+            dds_to_ros_type_name("example_interfaces::srv::dds_::AddTwoInts_").as_deref(),
+            Some("example_interfaces/srv/AddTwoInts")
+        );
+        assert_eq!(dds_to_ros_type_name("std_msgs::msg::String_"), None);
+        assert_eq!(dds_to_ros_type_name("std_msgs::msg::dds_::_"), None);
+    }
+}
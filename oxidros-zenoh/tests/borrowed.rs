@@ -0,0 +1,82 @@
+//! Integration tests for borrowed deserialization of received messages.
+
+use oxidros_msg::common_interfaces::sensor_msgs::msg::Image;
+use oxidros_msg::msg::{RosString, U8Seq};
+use oxidros_zenoh::Context;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Deserialize)]
+struct TimeView {
+    sec: i32,
+    nanosec: u32,
+}
+
+#[derive(Deserialize)]
+struct HeaderView<'a> {
+    stamp: TimeView,
+    frame_id: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ImageView<'a> {
+    #[serde(borrow)]
+    header: HeaderView<'a>,
+    height: u32,
+    width: u32,
+    encoding: &'a str,
+    is_bigendian: u8,
+    step: u32,
+    data: &'a [u8],
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_recv_borrowed_image() {
+    let ctx = Arc::new(Context::new().expect("Failed to create context"));
+    let node = ctx
+        .z_create_node("test_borrowed_node", None)
+        .expect("Failed to create node");
+
+    let mut subscriber = node
+        .z_create_subscriber::<Image>("borrowed_image", None)
+        .expect("Failed to create subscriber");
+    let publisher = node
+        .z_create_publisher::<Image>("borrowed_image", None)
+        .expect("Failed to create publisher");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let mut image = Image::new().expect("Failed to create message");
+    image.header.stamp.sec = 7;
+    image.header.stamp.nanosec = 42;
+    image.header.frame_id = RosString::new("camera").unwrap();
+    image.height = 2;
+    image.width = 3;
+    image.encoding = RosString::new("mono8").unwrap();
+    image.step = 3;
+    image.data = U8Seq::new(6).unwrap();
+    image
+        .data
+        .as_mut_slice()
+        .copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+    publisher.z_send(&image).expect("Failed to publish");
+
+    let msg = tokio::time::timeout(Duration::from_secs(2), subscriber.z_recv_borrowed())
+        .await
+        .expect("Timeout")
+        .expect("Failed to receive");
+    assert_eq!(msg.info.sequence_number, 1);
+
+    let view: ImageView = msg.view().expect("Failed to deserialize view");
+    assert_eq!((view.header.stamp.sec, view.header.stamp.nanosec), (7, 42));
+    assert_eq!(view.header.frame_id, "camera");
+    assert_eq!((view.height, view.width, view.step), (2, 3, 3));
+    assert_eq!(view.encoding, "mono8");
+    assert_eq!(view.is_bigendian, 0);
+    assert_eq!(view.data, &[1, 2, 3, 4, 5, 6]);
+    assert!(msg.bytes().as_ptr_range().contains(&view.data.as_ptr()));
+
+    // The same payload still decodes as the owned message
+    let owned = msg.to_message::<Image>().expect("Failed to deserialize");
+    assert_eq!(owned.encoding.get_string(), "mono8");
+}
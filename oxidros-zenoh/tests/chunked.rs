@@ -0,0 +1,77 @@
+//! Integration tests for the chunked publication of large messages.
+
+use oxidros_msg::common_interfaces::sensor_msgs::msg::Image;
+use oxidros_msg::msg::{RosString, U8Seq};
+use oxidros_zenoh::Context;
+use oxidros_zenoh::topic::ChunkOptions;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_chunked_image() {
+    let ctx = Arc::new(Context::new().expect("Failed to create context"));
+    let node = ctx
+        .z_create_node("test_chunked_node", None)
+        .expect("Failed to create node");
+
+    let mut subscriber = node
+        .z_create_subscriber::<Image>("chunked_image", None)
+        .expect("Failed to create subscriber");
+    let mut publisher = node
+        .z_create_publisher::<Image>("chunked_image", None)
+        .expect("Failed to create publisher");
+    publisher.set_chunking(Some(ChunkOptions::with_threshold(64 * 1024)));
+    // Every chunk is a sample on the key expression of the topic
+    let raw = ctx
+        .session()
+        .declare_subscriber("*/chunked_image/**")
+        .await
+        .expect("Failed to create raw subscriber");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let mut image = Image::new().expect("Failed to create message");
+    image.header.frame_id = RosString::new("camera").unwrap();
+    image.height = 512;
+    image.width = 1024;
+    image.encoding = RosString::new("mono8").unwrap();
+    image.step = 1024;
+    image.data = U8Seq::new(512 * 1024).unwrap();
+    for (i, byte) in image.data.as_mut_slice().iter_mut().enumerate() {
+        *byte = (i % 251) as u8;
+    }
+    publisher.z_send(&image).expect("Failed to send");
+
+    let msg = tokio::time::timeout(Duration::from_secs(5), subscriber.z_recv())
+        .await
+        .expect("Timeout")
+        .expect("Failed to receive");
+    assert_eq!(msg.header.frame_id.get_string(), "camera");
+    assert_eq!((msg.height, msg.width), (512, 1024));
+    assert_eq!(msg.data.as_slice(), image.data.as_slice());
+    assert_eq!(msg.info.sequence_number, 1);
+
+    let mut chunks = 0;
+    while let Ok(Ok(_)) = tokio::time::timeout(Duration::from_millis(500), raw.recv_async()).await {
+        chunks += 1;
+    }
+    assert!(chunks > 1, "message not chunked");
+
+    // Small messages are not chunked
+    image.data = U8Seq::new(16).unwrap();
+    publisher.z_send(&image).expect("Failed to send");
+    let msg = tokio::time::timeout(Duration::from_secs(2), subscriber.z_recv())
+        .await
+        .expect("Timeout")
+        .expect("Failed to receive");
+    assert_eq!(msg.data.len(), 16);
+    tokio::time::timeout(Duration::from_secs(2), raw.recv_async())
+        .await
+        .expect("Timeout")
+        .expect("Failed to receive raw sample");
+    assert!(
+        tokio::time::timeout(Duration::from_millis(300), raw.recv_async())
+            .await
+            .is_err()
+    );
+    assert_eq!(subscriber.incomplete(), 0);
+}
@@ -0,0 +1,51 @@
+//! Integration tests for the discovery readiness barrier.
+
+use oxidros_zenoh::{Context, DiscoveryTarget, Error};
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wait_for_discovery_node() {
+    let ctx = Context::new().expect("Failed to create context");
+    let peer_ctx = Context::new().expect("Failed to create peer context");
+    let _peer = peer_ctx
+        .z_create_node("discovery_peer_node", Some("/discovery_test"))
+        .expect("Failed to create node");
+
+    ctx.wait_for_discovery(
+        DiscoveryTarget::Node("/discovery_test/discovery_peer_node".to_string()),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("peer node not discovered");
+    assert!(
+        ctx.graph_cache()
+            .get_node_names()
+            .contains(&"/discovery_test/discovery_peer_node".to_string())
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wait_for_discovery_peers() {
+    let ctx = Context::new().expect("Failed to create context");
+    let peer_ctx = Context::new().expect("Failed to create peer context");
+    let _peer = peer_ctx
+        .z_create_node("discovery_peers_node", None)
+        .expect("Failed to create node");
+
+    ctx.wait_for_discovery(DiscoveryTarget::Peers(1), Duration::from_secs(5))
+        .await
+        .expect("peer session not discovered");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wait_for_discovery_timeout() {
+    let ctx = Context::new().expect("Failed to create context");
+
+    let result = ctx
+        .wait_for_discovery(
+            DiscoveryTarget::Node("/discovery_test/missing_node".to_string()),
+            Duration::from_millis(200),
+        )
+        .await;
+    assert!(matches!(result, Err(Error::Timeout)));
+}
@@ -0,0 +1,80 @@
+//! Integration tests for the isolation of ROS domains.
+
+use oxidros_msg::common_interfaces::std_msgs::msg::Int32;
+use oxidros_zenoh::{Context, DiscoveryTarget, Error};
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_domain_isolation() {
+    let ctx = Context::with_domain_id(71).expect("Failed to create context");
+    let same_ctx = Context::with_domain_id(71).expect("Failed to create context");
+    let other_ctx = Context::with_domain_id(72).expect("Failed to create context");
+    assert_eq!(ctx.ros_domain_id(), 71);
+    assert_eq!(other_ctx.ros_domain_id(), 72);
+
+    let node = ctx
+        .z_create_node("domain_node", None)
+        .expect("Failed to create node");
+    let same_node = same_ctx
+        .z_create_node("domain_same_node", None)
+        .expect("Failed to create node");
+    let other_node = other_ctx
+        .z_create_node("domain_other_node", None)
+        .expect("Failed to create node");
+
+    // Nodes are only discovered on their domain
+    ctx.wait_for_discovery(
+        DiscoveryTarget::Node("/domain_same_node".to_string()),
+        Duration::from_secs(5),
+    )
+    .await
+    .expect("node of the same domain not discovered");
+    let result = other_ctx
+        .wait_for_discovery(
+            DiscoveryTarget::Node("/domain_node".to_string()),
+            Duration::from_millis(500),
+        )
+        .await;
+    assert!(matches!(result, Err(Error::Timeout)));
+    assert!(
+        !ctx.graph_cache()
+            .get_node_names()
+            .contains(&"/domain_other_node".to_string())
+    );
+
+    // Messages are only delivered on their domain
+    let publisher = node
+        .z_create_publisher::<Int32>("domain_topic", None)
+        .expect("Failed to create publisher");
+    let mut same_subscriber = same_node
+        .z_create_subscriber::<Int32>("domain_topic", None)
+        .expect("Failed to create subscriber");
+    let mut other_subscriber = other_node
+        .z_create_subscriber::<Int32>("domain_topic", None)
+        .expect("Failed to create subscriber");
+    // Key expression of rmw_zenoh_cpp: <domain_id>/<topic>/<type>/<hash>
+    let raw = ctx
+        .session()
+        .declare_subscriber("71/domain_topic/std_msgs::msg::dds_::Int32_/*")
+        .await
+        .expect("Failed to create raw subscriber");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    publisher
+        .z_send(&Int32 { data: 7 })
+        .expect("Failed to send");
+    let msg = tokio::time::timeout(Duration::from_secs(2), same_subscriber.z_recv())
+        .await
+        .expect("Timeout")
+        .expect("Failed to receive");
+    assert_eq!(msg.data, 7);
+    tokio::time::timeout(Duration::from_secs(2), raw.recv_async())
+        .await
+        .expect("Timeout")
+        .expect("Failed to receive raw sample");
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), other_subscriber.z_recv())
+            .await
+            .is_err()
+    );
+}
@@ -0,0 +1,69 @@
+//! Integration tests for the request queue of service servers.
+
+use oxidros_msg::common_interfaces::example_interfaces::srv::{
+    AddTwoInts, AddTwoInts_Request, AddTwoInts_Response,
+};
+use oxidros_zenoh::Context;
+use oxidros_zenoh::service::{ServerOptions, SheddingPolicy};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_server_reject_with_error() {
+    let ctx = Arc::new(Context::new().expect("Failed to create context"));
+    let node = ctx
+        .z_create_node("test_load_shedding_node", None)
+        .expect("Failed to create node");
+
+    let options = ServerOptions {
+        max_pending: Some(1),
+        shedding: SheddingPolicy::RejectWithError,
+    };
+    let mut server = node
+        .z_create_server_with_options::<AddTwoInts>("load_shedding", None, options)
+        .expect("Failed to create server");
+    let mut first = node
+        .z_create_client::<AddTwoInts>("load_shedding", None)
+        .expect("Failed to create client");
+    let mut second = node
+        .z_create_client::<AddTwoInts>("load_shedding", None)
+        .expect("Failed to create client");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // The first request fills the queue
+    let first = tokio::spawn(async move {
+        first
+            .z_call(&AddTwoInts_Request { a: 1, b: 2 })
+            .await
+            .map(|response| response.sum)
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(server.pending(), 1);
+
+    // The second one is rejected without waiting for the server
+    let rejected = tokio::time::timeout(
+        Duration::from_secs(2),
+        second.z_call(&AddTwoInts_Request { a: 3, b: 4 }),
+    )
+    .await
+    .expect("Timeout");
+    assert!(rejected.is_err());
+    assert_eq!(server.shed(), 1);
+
+    let stats = node.z_stats();
+    assert_eq!(stats.pending_requests, Some(1));
+    assert_eq!(stats.shed_requests, Some(1));
+
+    // The queued request is still served
+    let request = server.z_try_recv().expect("Failed to receive").unwrap();
+    let sum = request.request.a + request.request.b;
+    request
+        .send(&AddTwoInts_Response { sum })
+        .expect("Failed to respond");
+    let sum = tokio::time::timeout(Duration::from_secs(2), first)
+        .await
+        .expect("Timeout")
+        .expect("Failed to join")
+        .expect("Failed to call");
+    assert_eq!(sum, 3);
+}
@@ -406,3 +406,135 @@ async fn test_parameter_service_get_set() {
         assert_eq!(param.value, Value::I64(42));
     }
 }
+
+/// Test that describe_parameters reports the full descriptor, including
+/// constraints updated after declaration.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_parameter_service_describe() {
+    use oxidros_core::parameter::IntegerRange;
+    use oxidros_msg::interfaces::rcl_interfaces::srv::describe_parameters::DescribeParameters;
+    use oxidros_msg::msg::{RosString, RosStringSeq};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tokio::time::timeout;
+
+    let ctx = Context::new().expect("Failed to create context");
+    let node = Arc::new(
+        ctx.z_create_node("param_describe_node", None)
+            .expect("Failed to create node"),
+    );
+
+    let mut param_server = node
+        .create_parameter_server()
+        .expect("Failed to create parameter server");
+    {
+        let mut params = param_server.params.write();
+        params
+            .set_parameter(
+                "rate".to_string(),
+                Value::I64(10),
+                false,
+                Some("Publishing rate".to_string()),
+            )
+            .expect("Failed to set parameter");
+        params
+            .update_descriptor("rate", |desc| {
+                desc.additional_constraints = "Multiple of 5".to_string();
+                desc.integer_range = Some(IntegerRange {
+                    min: 0,
+                    max: 100,
+                    step: 5,
+                });
+            })
+            .expect("Failed to update descriptor");
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_server = stop.clone();
+    let server_handle = tokio::spawn(async move {
+        while !stop_server.load(Ordering::SeqCst) {
+            let _ = timeout(Duration::from_millis(10), param_server.process_once()).await;
+        }
+    });
+
+    let mut client = node
+        .z_create_client::<DescribeParameters>("/param_describe_node/describe_parameters", None)
+        .expect("Failed to create describe_parameters client");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let mut request =
+        <DescribeParameters as oxidros_core::ServiceMsg>::Request::new().unwrap_or_default();
+    let mut names = RosStringSeq::<0, 0>::new(1).unwrap();
+    names.as_mut_slice()[0] = RosString::new("rate").unwrap();
+    request.names = names;
+
+    let response = timeout(Duration::from_secs(3), client.z_call(&request))
+        .await
+        .expect("describe_parameters timed out")
+        .expect("describe_parameters failed");
+    stop.store(true, Ordering::SeqCst);
+    server_handle.await.expect("Server task panicked");
+
+    let descriptors = response.sample.descriptors.as_slice();
+    assert_eq!(descriptors.len(), 1);
+    let desc = &descriptors[0];
+    assert_eq!(desc.name.to_string(), "rate");
+    assert_eq!(desc.description.to_string(), "Publishing rate");
+    assert_eq!(desc.additional_constraints.to_string(), "Multiple of 5");
+    assert!(!desc.read_only);
+    let ranges = desc.integer_range.as_slice();
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(
+        (ranges[0].from_value, ranges[0].to_value, ranges[0].step),
+        (0, 100, 5)
+    );
+    assert!(desc.floating_point_range.as_slice().is_empty());
+}
+
+/// Test that `use_sim_time` is declared and drives the clock of the node.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_use_sim_time() {
+    use oxidros_msg::interfaces::rosgraph_msgs::msg::Clock;
+
+    let ctx = Context::new().expect("Failed to create context");
+    let node = ctx
+        .z_create_node("param_sim_time_node", None)
+        .expect("Failed to create node");
+
+    let mut param_server = node
+        .create_parameter_server()
+        .expect("Failed to create parameter server");
+    assert_eq!(
+        param_server
+            .params
+            .read()
+            .get_parameter("use_sim_time")
+            .expect("use_sim_time not declared")
+            .value,
+        Value::Bool(false)
+    );
+    assert!(!param_server.use_sim_time());
+
+    let publisher = node
+        .z_create_publisher::<Clock>("/clock", None)
+        .expect("Failed to create publisher");
+    param_server
+        .set_use_sim_time(true)
+        .expect("Failed to enable use_sim_time");
+    assert!(param_server.use_sim_time());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let mut msg = Clock::new().unwrap();
+    msg.clock.sec = 42;
+    msg.clock.nanosec = 500;
+    publisher.z_send(&msg).expect("Failed to send");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let clock = node.get_clock().expect("Failed to get clock");
+    assert_eq!(clock.lock().get_now().unwrap(), Duration::new(42, 500));
+
+    // Back to the system time
+    param_server
+        .set_use_sim_time(false)
+        .expect("Failed to disable use_sim_time");
+    assert!(clock.lock().get_now().unwrap() > Duration::from_secs(1_000_000));
+}
@@ -0,0 +1,62 @@
+//! Integration tests for paused subscribers.
+
+use oxidros_msg::common_interfaces::std_msgs::msg::Int32;
+use oxidros_zenoh::Context;
+use oxidros_zenoh::topic::BufferOptions;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_paused_subscriber() {
+    let ctx = Arc::new(Context::new().expect("Failed to create context"));
+    let node = ctx
+        .z_create_node("test_paused_subscriber_node", None)
+        .expect("Failed to create node");
+
+    let buffer = BufferOptions {
+        paused: true,
+        ..Default::default()
+    };
+    let mut subscriber = node
+        .z_create_subscriber_with_buffer::<Int32>("paused_topic", None, buffer)
+        .expect("Failed to create subscriber");
+    assert!(subscriber.is_paused());
+    let publisher = node
+        .z_create_publisher::<Int32>("paused_topic", None)
+        .expect("Failed to create publisher");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Messages published while paused are neither buffered nor counted as lagged
+    for data in 0..3 {
+        publisher.z_send(&Int32 { data }).expect("Failed to send");
+    }
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(subscriber.buffered(), 0);
+    assert_eq!(subscriber.lagged(), 0);
+
+    subscriber.resume();
+    assert!(!subscriber.is_paused());
+    publisher
+        .z_send(&Int32 { data: 3 })
+        .expect("Failed to send");
+    let msg = tokio::time::timeout(Duration::from_secs(2), subscriber.z_recv())
+        .await
+        .expect("Timeout")
+        .expect("Failed to receive");
+    assert_eq!(msg.data, 3);
+
+    // Pausing drops the buffered messages
+    publisher
+        .z_send(&Int32 { data: 4 })
+        .expect("Failed to send");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(subscriber.buffered(), 1);
+    subscriber.pause();
+    assert_eq!(subscriber.buffered(), 0);
+    assert!(
+        subscriber
+            .z_try_recv()
+            .expect("Failed to receive")
+            .is_none()
+    );
+}
@@ -0,0 +1,55 @@
+//! Integration tests for batched receives.
+
+#![cfg(feature = "dynamic")]
+
+use oxidros_core::{TypeDescription, api::RosSubscriber};
+use oxidros_msg::common_interfaces::std_msgs::msg::Int32;
+use oxidros_zenoh::Context;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Encapsulation header without the int32: fails to decode.
+const MALFORMED: [u8; 4] = [0, 1, 0, 0];
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_recv_many_keeps_messages_before_error() {
+    let ctx = Arc::new(Context::new().expect("Failed to create context"));
+    let node = ctx
+        .z_create_node("test_recv_many_node", None)
+        .expect("Failed to create node");
+
+    let mut subscriber = node
+        .z_create_subscriber::<Int32>("recv_many_topic", None)
+        .expect("Failed to create subscriber");
+    let publisher = node
+        .z_create_publisher::<Int32>("recv_many_topic", None)
+        .expect("Failed to create publisher");
+    let raw = node
+        .create_generic_publisher("recv_many_topic", Int32::type_description(), None)
+        .expect("Failed to create publisher");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    raw.publish_raw(MALFORMED.to_vec())
+        .expect("Failed to publish");
+    for data in 1..=2 {
+        publisher.z_send(&Int32 { data }).expect("Failed to send");
+    }
+    raw.publish_raw(MALFORMED.to_vec())
+        .expect("Failed to publish");
+    publisher
+        .z_send(&Int32 { data: 3 })
+        .expect("Failed to send");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // An error before any message fails the batch
+    assert!(subscriber.recv_many(10).is_err());
+
+    // An error after some messages ends the batch
+    let batch = subscriber.recv_many(10).expect("Failed to receive");
+    let data: Vec<_> = batch.iter().map(|msg| msg.data).collect();
+    assert_eq!(data, [1, 2]);
+
+    let batch = subscriber.recv_many(10).expect("Failed to receive");
+    let data: Vec<_> = batch.iter().map(|msg| msg.data).collect();
+    assert_eq!(data, [3]);
+}
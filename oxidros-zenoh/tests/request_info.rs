@@ -0,0 +1,64 @@
+//! Integration tests for the metadata of service requests.
+
+use oxidros_msg::common_interfaces::example_interfaces::srv::{
+    AddTwoInts, AddTwoInts_Request, AddTwoInts_Response,
+};
+use oxidros_zenoh::Context;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_request_info() {
+    let ctx = Arc::new(Context::new().expect("Failed to create context"));
+    let node = ctx
+        .z_create_node("test_request_info_node", None)
+        .expect("Failed to create node");
+
+    let mut server = node
+        .z_create_server::<AddTwoInts>("request_info", None)
+        .expect("Failed to create server");
+    let mut client = node
+        .z_create_client::<AddTwoInts>("request_info", None)
+        .expect("Failed to create client");
+    let mut other = node
+        .z_create_client::<AddTwoInts>("request_info", None)
+        .expect("Failed to create client");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let calls = tokio::spawn(async move {
+        for a in 0..2 {
+            client
+                .z_call(&AddTwoInts_Request { a, b: 0 })
+                .await
+                .expect("Failed to call");
+        }
+        other
+            .z_call(&AddTwoInts_Request { a: 2, b: 0 })
+            .await
+            .expect("Failed to call");
+    });
+
+    let mut infos = Vec::new();
+    for _ in 0..3 {
+        let request = tokio::time::timeout(Duration::from_secs(2), server.z_recv())
+            .await
+            .expect("Timeout")
+            .expect("Failed to receive");
+        infos.push(request.info());
+        request
+            .send(&AddTwoInts_Response { sum: 0 })
+            .expect("Failed to respond");
+    }
+    calls.await.expect("Failed to join");
+
+    // Requests of a client share its GID and are numbered in order
+    assert_eq!(infos[0].client_gid, infos[1].client_gid);
+    assert_ne!(infos[0].client_gid, infos[2].client_gid);
+    assert!(infos[0].sequence_number < infos[1].sequence_number);
+    assert_ne!(infos[0].request_id(), infos[1].request_id());
+
+    for info in &infos {
+        assert!(info.source_timestamp_ns > 0);
+        assert!(info.received_timestamp_ns >= info.source_timestamp_ns);
+    }
+}
@@ -0,0 +1,86 @@
+//! Integration tests for the transport diagnostics.
+
+use oxidros_msg::common_interfaces::diagnostic_msgs::msg::{DiagnosticArray, DiagnosticStatus};
+use oxidros_msg::common_interfaces::std_msgs::msg::Int32;
+use oxidros_zenoh::topic::{BufferOptions, OverflowPolicy};
+use oxidros_zenoh::{Context, TransportDiagnostics, TransportEvent};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_dropped_messages_diagnostics() {
+    let ctx = Arc::new(Context::new().expect("Failed to create context"));
+    let node = ctx
+        .z_create_node("test_transport_node", None)
+        .expect("Failed to create node");
+
+    let mut diagnostics = node
+        .z_create_subscriber::<DiagnosticArray>("transport_diagnostics", None)
+        .expect("Failed to create subscriber");
+    let events = ctx.transport_monitor().events();
+    tokio::spawn(
+        TransportDiagnostics::new(&node)
+            .topic("transport_diagnostics")
+            .run(),
+    );
+
+    let buffer = BufferOptions {
+        capacity: Some(1),
+        overflow: OverflowPolicy::DropNewest,
+        ..Default::default()
+    };
+    let _subscriber = node
+        .z_create_subscriber_with_buffer::<Int32>("transport_drops", None, buffer)
+        .expect("Failed to create subscriber");
+    let publisher = node
+        .z_create_publisher::<Int32>("transport_drops", None)
+        .expect("Failed to create publisher");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // The subscriber holds the first message and drops the others
+    for data in 0..3 {
+        publisher.z_send(&Int32 { data }).expect("Failed to send");
+    }
+
+    let event = tokio::time::timeout(Duration::from_secs(2), async {
+        loop {
+            match events.recv_async().await.expect("Monitor dropped") {
+                event @ TransportEvent::MessagesDropped { .. } => return event,
+                _ => continue,
+            }
+        }
+    })
+    .await
+    .expect("Timeout");
+    assert_eq!(
+        event,
+        TransportEvent::MessagesDropped {
+            topic: "/transport_drops".to_string(),
+            count: 1
+        }
+    );
+
+    // The second drop is reported with the next flush
+    let array = tokio::time::timeout(Duration::from_secs(3), async {
+        loop {
+            let array = diagnostics.z_recv().await.expect("Failed to receive");
+            let status = &array.status.as_slice()[0];
+            if status.message.get_string().contains("/transport_drops") {
+                return array;
+            }
+        }
+    })
+    .await
+    .expect("Timeout");
+    let status = &array.status.as_slice()[0];
+    assert_eq!(status.level, DiagnosticStatus::WARN);
+    assert_eq!(status.hardware_id.get_string(), ctx.session_id());
+    assert!(
+        status
+            .values
+            .as_slice()
+            .iter()
+            .any(|kv| kv.key.get_string() == "dropped_messages")
+    );
+    assert_eq!(ctx.transport_monitor().stats().dropped_messages, 2);
+}
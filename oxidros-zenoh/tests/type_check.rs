@@ -0,0 +1,102 @@
+//! Integration tests for the type check policies of subscribers.
+
+#![cfg(feature = "dynamic")]
+
+use oxidros_core::{
+    TypeDescription, TypeSupport,
+    types::{FIELD_TYPE_INT32, TypeDescriptionMsg},
+};
+use oxidros_msg::common_interfaces::std_msgs::msg::String as StdString;
+use oxidros_zenoh::{Context, TypeCheckPolicy};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// `std_msgs/msg/String` with its field renamed: another type hash, same layout.
+fn renamed_string() -> TypeDescriptionMsg {
+    let mut desc = StdString::type_description();
+    desc.type_description.fields[0].name = "text".to_string();
+    desc
+}
+
+/// `std_msgs/msg/String` with an `int32` field: another type hash and layout.
+fn int_string() -> TypeDescriptionMsg {
+    let mut desc = StdString::type_description();
+    desc.type_description.fields[0].field_type.type_id = FIELD_TYPE_INT32;
+    desc
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_type_check_strict_and_warn_only() {
+    let ctx = Arc::new(Context::new().expect("Failed to create context"));
+    let node = ctx
+        .z_create_node("test_type_check_node", None)
+        .expect("Failed to create node");
+
+    let strict = node
+        .z_create_subscriber::<StdString>("type_check_renamed", None)
+        .expect("Failed to create subscriber");
+    assert_eq!(strict.type_check(), TypeCheckPolicy::Strict);
+    let mut warn_only = node
+        .z_create_subscriber::<StdString>("type_check_renamed", None)
+        .expect("Failed to create subscriber")
+        .with_type_check(TypeCheckPolicy::WarnOnly);
+
+    let publisher = node
+        .create_generic_publisher("type_check_renamed", renamed_string(), None)
+        .expect("Failed to create publisher");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // The mismatching type hash is visible in the graph
+    let types = node
+        .graph()
+        .topic_types("/type_check_renamed")
+        .expect("Failed to get topic types");
+    assert_eq!(types.len(), 1);
+    assert_ne!(types[0].1, StdString::type_hash().unwrap());
+
+    publisher
+        .publish(&publisher.new_message(json!({"text": "hello"})))
+        .expect("Failed to publish");
+
+    let msg = tokio::time::timeout(Duration::from_secs(2), warn_only.z_recv())
+        .await
+        .expect("Timeout")
+        .expect("Failed to receive");
+    assert_eq!(msg.data.get_string(), "hello");
+    assert!(strict.z_try_recv().expect("Failed to receive").is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_type_check_best_effort_decode() {
+    let ctx = Arc::new(Context::new().expect("Failed to create context"));
+    let node = ctx
+        .z_create_node("test_type_check_decode_node", None)
+        .expect("Failed to create node");
+
+    let mut subscriber = node
+        .z_create_subscriber::<StdString>("type_check_decode", None)
+        .expect("Failed to create subscriber")
+        .with_type_check(TypeCheckPolicy::BestEffortDecode);
+
+    let renamed = node
+        .create_generic_publisher("type_check_decode", renamed_string(), None)
+        .expect("Failed to create publisher");
+    let int = node
+        .create_generic_publisher("type_check_decode", int_string(), None)
+        .expect("Failed to create publisher");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Does not decode as a string: skipped
+    int.publish(&int.new_message(json!({"data": 1_000_000})))
+        .expect("Failed to publish");
+    renamed
+        .publish(&renamed.new_message(json!({"text": "decoded"})))
+        .expect("Failed to publish");
+
+    let msg = tokio::time::timeout(Duration::from_secs(2), subscriber.z_recv())
+        .await
+        .expect("Timeout")
+        .expect("Failed to receive");
+    assert_eq!(msg.data.get_string(), "decoded");
+}
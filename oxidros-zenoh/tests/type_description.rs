@@ -118,3 +118,103 @@ async fn test_ros_node_trait_registers_type() {
 
     assert_eq!(desc.type_description.type_name, "std_msgs/msg/String");
 }
+
+/// Test the standard `~/get_type_description` service.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_type_description_service() {
+    use oxidros_core::TypeDescription;
+    use oxidros_msg::interfaces::type_description_interfaces::srv::{
+        GetTypeDescription, GetTypeDescription_Request,
+    };
+
+    let ctx = Arc::new(Context::new().expect("Failed to create context"));
+    let node = ctx
+        .z_create_node("test_type_desc_srv_node", None)
+        .expect("Failed to create node");
+    let _pub = node
+        .z_create_publisher::<StdString>("chatter", None)
+        .expect("Failed to create publisher");
+    let _server = node
+        .create_type_description_server()
+        .expect("Failed to create type description server");
+
+    let mut client = node
+        .z_create_client::<GetTypeDescription>(
+            "/test_type_desc_srv_node/get_type_description",
+            None,
+        )
+        .expect("Failed to create client");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let mut request = GetTypeDescription_Request::new().expect("request");
+    request.type_name.assign("std_msgs/msg/String");
+    request
+        .type_hash
+        .assign(&StdString::compute_hash().expect("hash"));
+    let response = tokio::time::timeout(Duration::from_secs(2), client.z_call(&request))
+        .await
+        .expect("call timed out")
+        .expect("call failed");
+    assert!(response.successful);
+    assert_eq!(
+        response
+            .type_description
+            .type_description
+            .type_name
+            .get_string(),
+        "std_msgs/msg/String"
+    );
+    assert_eq!(
+        response.type_description.type_description.fields.as_slice()[0]
+            .name
+            .get_string(),
+        "data"
+    );
+
+    // Types not used by the node are reported as failures
+    request.type_name.assign("std_msgs/msg/Bool");
+    request.type_hash.assign("");
+    let response = tokio::time::timeout(Duration::from_secs(2), client.z_call(&request))
+        .await
+        .expect("call timed out")
+        .expect("call failed");
+    assert!(!response.successful);
+    assert!(!response.failure_reason.get_string().is_empty());
+}
+
+/// Test that entities expose the type name and hash they advertise.
+#[test]
+fn test_type_info() {
+    use oxidros_core::{TypeDescription, TypeInfo};
+    use oxidros_msg::common_interfaces::std_srvs::srv::Empty;
+
+    let ctx = Arc::new(Context::new().expect("Failed to create context"));
+    let node = ctx
+        .z_create_node("test_type_info_node", None)
+        .expect("Failed to create node");
+
+    let publisher = node
+        .z_create_publisher::<StdString>("type_info_topic", None)
+        .expect("Failed to create publisher");
+    let subscriber = node
+        .z_create_subscriber::<StdString>("type_info_topic", None)
+        .expect("Failed to create subscriber");
+    let expected = TypeInfo::new(
+        "std_msgs::msg::dds_::String_",
+        StdString::compute_hash().unwrap(),
+    );
+    assert_eq!(publisher.type_info(), &expected);
+    assert_eq!(subscriber.type_info(), &expected);
+    assert_eq!(publisher.type_info().serialization, "cdr");
+    assert!(format!("{publisher:?}").contains(&expected.rihs01_hash));
+
+    let server = node
+        .z_create_server::<Empty>("type_info_service", None)
+        .expect("Failed to create server");
+    let client = node
+        .z_create_client::<Empty>("type_info_service", None)
+        .expect("Failed to create client");
+    assert_eq!(server.type_info(), client.type_info());
+    assert_eq!(server.type_info().type_name, "std_srvs::srv::dds_::Empty_");
+    assert!(format!("{client:?}").contains("/type_info_service"));
+}
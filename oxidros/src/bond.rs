@@ -0,0 +1,415 @@
+//! Heartbeat bonds between nodes and supervision of lifecycle nodes.
+//!
+//! A [`Bond`] works like `bondcpp`: both ends publish heartbeats on a shared
+//! topic and consider the bond broken when the other end stops sending them,
+//! or when it breaks the bond explicitly. The heartbeats are
+//! `diagnostic_msgs/msg/DiagnosticStatus` messages, with the bond id as
+//! `name` and the instance id of the sender as `hardware_id`; the `bond`
+//! package messages are not available, so the bonds do not interoperate with
+//! `bondcpp`.
+//!
+//! [`Supervisor`] keeps a bond with each managed node and, when one breaks,
+//! restarts the node or requests lifecycle transitions through the
+//! `change_state` services.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::bond::{Bond, BondAction, Supervisor};
+//! use oxidros::msg::interfaces::lifecycle_msgs::msg::Transition;
+//! use oxidros::prelude::*;
+//!
+//! // In the managed node
+//! let bond = Bond::new(&node, "camera_driver");
+//! tokio::spawn(bond.run());
+//!
+//! // In the lifecycle manager
+//! let supervisor = Supervisor::new(&manager_node)
+//!     .supervise("camera_driver", [BondAction::restart(|name| respawn(name))])
+//!     .supervise(
+//!         "detector",
+//!         [BondAction::transition("detector", [Transition::TRANSITION_DEACTIVATE])],
+//!     );
+//! supervisor.run().await?;
+//! ```
+
+use futures_util::future::try_join_all;
+use oxidros_core::{
+    Error, RclError, Result,
+    api::{RosClient, RosNode, RosPublisher, RosSubscriber},
+    shutdown::ShutdownToken,
+    targets,
+};
+use oxidros_msg::{
+    common_interfaces::diagnostic_msgs::msg::DiagnosticStatus,
+    interfaces::lifecycle_msgs::srv::{ChangeState, ChangeState_Request},
+};
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::time::{Instant, MissedTickBehavior};
+
+/// Default topic of the heartbeats.
+pub const DEFAULT_TOPIC: &str = "bond";
+
+/// Default interval between two heartbeats.
+pub const DEFAULT_HEARTBEAT_PERIOD: Duration = Duration::from_secs(1);
+
+/// Default time without heartbeat after which a formed bond is broken.
+pub const DEFAULT_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// Default time to wait for the first heartbeat of the other end.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a bond was broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrokenReason {
+    /// No heartbeat of the other end was received within the connect timeout.
+    ConnectTimeout,
+    /// The other end stopped sending heartbeats.
+    HeartbeatTimeout,
+    /// The other end broke the bond.
+    Peer,
+    /// The bond was broken locally with the token of [`Bond::breaker`].
+    Local,
+}
+
+type Callback<A> = Box<dyn FnMut(A) + Send>;
+
+/// One end of a bond.
+pub struct Bond<N> {
+    node: Arc<N>,
+    topic: String,
+    id: String,
+    instance_id: String,
+    heartbeat_period: Duration,
+    heartbeat_timeout: Duration,
+    connect_timeout: Duration,
+    breaker: ShutdownToken,
+    on_formed: Option<Callback<()>>,
+    on_broken: Option<Callback<BrokenReason>>,
+}
+
+impl<N> Bond<N>
+where
+    N: RosNode,
+    N::Subscriber<DiagnosticStatus>: RosSubscriber<DiagnosticStatus>,
+{
+    /// Create a bond named `id` on [`DEFAULT_TOPIC`].
+    ///
+    /// Both ends must use the same topic and id.
+    pub fn new(node: &Arc<N>, id: &str) -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        Bond {
+            node: node.clone(),
+            topic: DEFAULT_TOPIC.to_string(),
+            id: id.to_string(),
+            instance_id: format!("{}-{nanos:x}", std::process::id()),
+            heartbeat_period: DEFAULT_HEARTBEAT_PERIOD,
+            heartbeat_timeout: DEFAULT_HEARTBEAT_TIMEOUT,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            breaker: ShutdownToken::new(),
+            on_formed: None,
+            on_broken: None,
+        }
+    }
+
+    /// Topic of the heartbeats.
+    pub fn topic(mut self, topic: &str) -> Self {
+        self.topic = topic.to_string();
+        self
+    }
+
+    /// Interval between two heartbeats.
+    pub fn heartbeat_period(mut self, period: Duration) -> Self {
+        self.heartbeat_period = period;
+        self
+    }
+
+    /// Time without heartbeat after which a formed bond is broken.
+    pub fn heartbeat_timeout(mut self, timeout: Duration) -> Self {
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
+    /// Time to wait for the first heartbeat of the other end.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Call `callback` when the first heartbeat of the other end is received.
+    pub fn on_formed<F: FnMut() + Send + 'static>(mut self, mut callback: F) -> Self {
+        self.on_formed = Some(Box::new(move |()| callback()));
+        self
+    }
+
+    /// Call `callback` when the bond breaks.
+    pub fn on_broken<F: FnMut(BrokenReason) + Send + 'static>(mut self, callback: F) -> Self {
+        self.on_broken = Some(Box::new(callback));
+        self
+    }
+
+    /// Get the bond id.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Get the id of this end of the bond.
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// Get a token breaking the bond when triggered.
+    ///
+    /// The other end is told, instead of having to wait for the heartbeat
+    /// timeout.
+    pub fn breaker(&self) -> ShutdownToken {
+        self.breaker.clone()
+    }
+
+    /// Exchange heartbeats until the bond breaks, and return why.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the publisher or the subscriber fails.
+    pub async fn run(mut self) -> Result<BrokenReason> {
+        let publisher = self
+            .node
+            .create_publisher::<DiagnosticStatus>(&self.topic, None)?;
+        let mut subscriber = self
+            .node
+            .create_subscriber::<DiagnosticStatus>(&self.topic, None)?;
+
+        let mut heartbeat = tokio::time::interval(self.heartbeat_period);
+        heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut deadline = Instant::now() + self.connect_timeout;
+        let mut formed = false;
+
+        let reason = loop {
+            tokio::select! {
+                _ = heartbeat.tick() => {
+                    publisher.send(&self.status(DiagnosticStatus::OK)?)?;
+                }
+                msg = subscriber.recv() => {
+                    let msg = msg?;
+                    if msg.name.get_string() != self.id
+                        || msg.hardware_id.get_string() == self.instance_id
+                    {
+                        continue;
+                    }
+                    if msg.level == DiagnosticStatus::STALE {
+                        break BrokenReason::Peer;
+                    }
+                    deadline = Instant::now() + self.heartbeat_timeout;
+                    if !formed {
+                        formed = true;
+                        tracing::debug!(target: targets::BOND, bond = %self.id, "Bond formed");
+                        if let Some(callback) = self.on_formed.as_mut() {
+                            callback(());
+                        }
+                    }
+                }
+                _ = tokio::time::sleep_until(deadline) => {
+                    break if formed {
+                        BrokenReason::HeartbeatTimeout
+                    } else {
+                        BrokenReason::ConnectTimeout
+                    };
+                }
+                _ = self.breaker.wait() => break BrokenReason::Local,
+            }
+        };
+
+        tracing::debug!(target: targets::BOND, bond = %self.id, ?reason, "Bond broken");
+        if reason != BrokenReason::Peer {
+            publisher.send(&self.status(DiagnosticStatus::STALE)?)?;
+        }
+        if let Some(callback) = self.on_broken.as_mut() {
+            callback(reason);
+        }
+        Ok(reason)
+    }
+
+    fn status(&self, level: u8) -> Result<DiagnosticStatus> {
+        let mut status = DiagnosticStatus::new().ok_or(RclError::BadAlloc)?;
+        status.level = level;
+        status.name.assign(&self.id);
+        status.hardware_id.assign(&self.instance_id);
+        Ok(status)
+    }
+}
+
+type Restart = Box<dyn FnMut(&str) -> Result<()> + Send>;
+
+/// What a [`Supervisor`] does when the bond with a node breaks.
+pub enum BondAction {
+    /// Request lifecycle transitions of a node, in order, through its
+    /// `<node>/change_state` service.
+    Transition {
+        /// Name of the lifecycle node.
+        node: String,
+        /// `lifecycle_msgs/msg/Transition` ids.
+        transitions: Vec<u8>,
+    },
+    /// Call a closure with the name of the node, e.g. to spawn its process
+    /// again.
+    Restart(Restart),
+}
+
+impl BondAction {
+    /// Request `transitions` of the lifecycle node `node`.
+    pub fn transition(node: &str, transitions: impl IntoIterator<Item = u8>) -> Self {
+        BondAction::Transition {
+            node: node.to_string(),
+            transitions: transitions.into_iter().collect(),
+        }
+    }
+
+    /// Call `restart` with the name of the node.
+    pub fn restart<F>(restart: F) -> Self
+    where
+        F: FnMut(&str) -> Result<()> + Send + 'static,
+    {
+        BondAction::Restart(Box::new(restart))
+    }
+}
+
+/// Keeps a bond with each managed node and acts when one breaks.
+///
+/// The bond id of a node is its name. A node which does not form its bond
+/// within the connect timeout is handled as if the bond broke. After the
+/// actions, the supervisor waits for the node to form the bond again.
+pub struct Supervisor<N> {
+    node: Arc<N>,
+    topic: String,
+    heartbeat_timeout: Duration,
+    nodes: Vec<(String, Vec<BondAction>)>,
+}
+
+impl<N> Supervisor<N>
+where
+    N: RosNode,
+    N::Subscriber<DiagnosticStatus>: RosSubscriber<DiagnosticStatus>,
+{
+    /// Create a supervisor without managed node.
+    pub fn new(node: &Arc<N>) -> Self {
+        Supervisor {
+            node: node.clone(),
+            topic: DEFAULT_TOPIC.to_string(),
+            heartbeat_timeout: DEFAULT_HEARTBEAT_TIMEOUT,
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Topic of the heartbeats.
+    pub fn topic(mut self, topic: &str) -> Self {
+        self.topic = topic.to_string();
+        self
+    }
+
+    /// Time without heartbeat after which a bond is broken.
+    pub fn heartbeat_timeout(mut self, timeout: Duration) -> Self {
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
+    /// Supervise the node `name`, running `actions` in order when its bond
+    /// breaks.
+    pub fn supervise(mut self, name: &str, actions: impl IntoIterator<Item = BondAction>) -> Self {
+        self.nodes
+            .push((name.to_string(), actions.into_iter().collect()));
+        self
+    }
+
+    /// Supervise the nodes until an error occurs.
+    ///
+    /// Failed actions are logged and do not stop the supervision.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the publisher or the subscriber of a bond fails.
+    pub async fn run(self) -> Result<()> {
+        let Supervisor {
+            node,
+            topic,
+            heartbeat_timeout,
+            nodes,
+        } = self;
+        try_join_all(nodes.into_iter().map(|(name, actions)| {
+            supervise(
+                node.clone(),
+                topic.clone(),
+                heartbeat_timeout,
+                name,
+                actions,
+            )
+        }))
+        .await?;
+        Ok(())
+    }
+}
+
+async fn supervise<N>(
+    node: Arc<N>,
+    topic: String,
+    heartbeat_timeout: Duration,
+    name: String,
+    mut actions: Vec<BondAction>,
+) -> Result<()>
+where
+    N: RosNode,
+    N::Subscriber<DiagnosticStatus>: RosSubscriber<DiagnosticStatus>,
+{
+    loop {
+        let reason = Bond::new(&node, &name)
+            .topic(&topic)
+            .heartbeat_timeout(heartbeat_timeout)
+            .run()
+            .await?;
+        tracing::warn!(
+            target: targets::BOND,
+            node = %name,
+            ?reason,
+            "Bond with supervised node broken"
+        );
+
+        for action in actions.iter_mut() {
+            let result = match action {
+                BondAction::Transition {
+                    node: target,
+                    transitions,
+                } => change_state(&node, target, transitions).await,
+                BondAction::Restart(restart) => restart(&name),
+            };
+            if let Err(e) = result {
+                tracing::error!(
+                    target: targets::BOND,
+                    node = %name,
+                    error = %e,
+                    "Supervisor action failed"
+                );
+            }
+        }
+    }
+}
+
+/// Request `transitions` of the lifecycle node `target`, in order.
+async fn change_state<N: RosNode>(node: &Arc<N>, target: &str, transitions: &[u8]) -> Result<()> {
+    let mut client = node.create_client::<ChangeState>(&format!("{target}/change_state"), None)?;
+    for id in transitions {
+        let mut request = ChangeState_Request::new().ok_or(RclError::BadAlloc)?;
+        request.transition.id = *id;
+        let response = client.call(&request).await?;
+        if !response.success {
+            return Err(Error::Other(format!(
+                "lifecycle node {target} rejected transition {id}"
+            )));
+        }
+    }
+    Ok(())
+}
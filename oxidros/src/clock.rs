@@ -5,3 +5,8 @@ pub use oxidros_wrapper::Clock;
 
 #[cfg(feature = "zenoh")]
 pub use oxidros_zenoh::clock::Clock;
+
+pub use oxidros_core::time::{
+    ClockChange, JumpHandle, JumpPostCallback, JumpPreCallback, JumpThreshold, TimeJump,
+    VirtualClock, VirtualSleep,
+};
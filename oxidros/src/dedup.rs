@@ -0,0 +1,203 @@
+//! Detection of duplicated and lost messages.
+//!
+//! Every publisher numbers its messages (see
+//! [`MessageInfo::sequence_number`]). Over lossy or redundant links, such as a
+//! bridged network or a reliable transport retrying after a reconnection, a
+//! subscriber may see the same message twice or miss some of them.
+//! [`DeduplicatingSubscriber`] drops the duplicates and counts the gaps, per
+//! publisher.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::dedup::DeduplicatingSubscriber;
+//! use oxidros::prelude::*;
+//!
+//! let subscriber = node.create_subscriber::<Int64>("counter", None)?;
+//! let mut subscriber = DeduplicatingSubscriber::new(subscriber);
+//! loop {
+//!     let msg = subscriber.recv().await?;
+//!     let stats = subscriber.stats();
+//!     println!("{} (lost {} so far)", msg.data, stats.lost);
+//! }
+//! ```
+
+use oxidros_core::{Message, MessageInfo, Result, RosSubscriber, TypeSupport};
+use std::{borrow::Cow, collections::HashMap, marker::PhantomData};
+
+/// Result of [`SequenceTracker::check`] for one message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceStatus {
+    /// First message seen from its publisher.
+    First,
+    /// The message follows the previous one of its publisher.
+    InOrder,
+    /// `missing` messages of the publisher were not received before this one.
+    Gap {
+        /// Number of messages missing.
+        missing: u64,
+    },
+    /// The message is not newer than the last one of its publisher.
+    ///
+    /// This includes messages arriving late, after a gap was reported.
+    Duplicate,
+    /// The middleware does not provide sequence numbers.
+    Unsequenced,
+}
+
+impl SequenceStatus {
+    /// Return `true` if the message should be handed to the application.
+    pub fn is_new(&self) -> bool {
+        !matches!(self, SequenceStatus::Duplicate)
+    }
+}
+
+/// Counters of a [`SequenceTracker`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DedupStats {
+    /// Messages accepted, including the unsequenced ones.
+    pub received: u64,
+    /// Messages dropped as duplicates.
+    pub duplicates: u64,
+    /// Messages missing in the sequence of their publisher.
+    pub lost: u64,
+    /// Number of gaps, each covering one or more lost messages.
+    pub gaps: u64,
+    /// Publishers seen so far.
+    pub publishers: usize,
+}
+
+/// Sequence numbers seen per publisher.
+#[derive(Debug, Default)]
+pub struct SequenceTracker {
+    last: HashMap<[u8; 16], i64>,
+    stats: DedupStats,
+}
+
+impl SequenceTracker {
+    /// Create an empty tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the message described by `info` and classify it.
+    pub fn check(&mut self, info: &MessageInfo) -> SequenceStatus {
+        let seq = info.sequence_number;
+        if seq <= 0 {
+            self.stats.received += 1;
+            return SequenceStatus::Unsequenced;
+        }
+
+        let status = match self.last.get(&info.writer_gid) {
+            None => SequenceStatus::First,
+            Some(&last) if seq <= last => SequenceStatus::Duplicate,
+            Some(&last) if seq == last + 1 => SequenceStatus::InOrder,
+            Some(&last) => SequenceStatus::Gap {
+                missing: (seq - last - 1) as u64,
+            },
+        };
+
+        match status {
+            SequenceStatus::Duplicate => self.stats.duplicates += 1,
+            SequenceStatus::Gap { missing } => {
+                self.stats.lost += missing;
+                self.stats.gaps += 1;
+            }
+            _ => (),
+        }
+        if status.is_new() {
+            self.stats.received += 1;
+            self.last.insert(info.writer_gid, seq);
+            self.stats.publishers = self.last.len();
+        }
+        status
+    }
+
+    /// Get the counters.
+    pub fn stats(&self) -> DedupStats {
+        self.stats
+    }
+
+    /// Forget the publishers seen so far and clear the counters.
+    pub fn reset(&mut self) {
+        self.last.clear();
+        self.stats = DedupStats::default();
+    }
+}
+
+/// Subscriber dropping duplicated messages and counting lost ones.
+///
+/// Wraps any [`RosSubscriber`]; the publishers are told apart by their GID.
+pub struct DeduplicatingSubscriber<T, S> {
+    inner: S,
+    tracker: SequenceTracker,
+    last_status: Option<SequenceStatus>,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: TypeSupport, S: RosSubscriber<T>> DeduplicatingSubscriber<T, S> {
+    /// Wrap `inner`.
+    pub fn new(inner: S) -> Self {
+        DeduplicatingSubscriber {
+            inner,
+            tracker: SequenceTracker::new(),
+            last_status: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Get the topic name.
+    pub fn topic_name(&self) -> Result<Cow<'_, String>> {
+        self.inner.topic_name()
+    }
+
+    /// Receive the next message that is not a duplicate.
+    pub async fn recv(&mut self) -> Result<Message<T>> {
+        loop {
+            let msg = self.inner.recv().await?;
+            if self.accept(&msg.info) {
+                return Ok(msg);
+            }
+        }
+    }
+
+    /// Try to receive a message that is not a duplicate, without blocking.
+    ///
+    /// Returns `Ok(None)` if no such message is currently available.
+    pub fn try_recv(&mut self) -> Result<Option<Message<T>>> {
+        while let Some(msg) = self.inner.try_recv()? {
+            if self.accept(&msg.info) {
+                return Ok(Some(msg));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get the status of the last message returned, e.g. to react to a gap.
+    pub fn last_status(&self) -> Option<SequenceStatus> {
+        self.last_status
+    }
+
+    /// Get the counters.
+    pub fn stats(&self) -> DedupStats {
+        self.tracker.stats()
+    }
+
+    /// Get the sequence tracker, e.g. to reset it.
+    pub fn tracker_mut(&mut self) -> &mut SequenceTracker {
+        &mut self.tracker
+    }
+
+    /// Get the wrapped subscriber back.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn accept(&mut self, info: &MessageInfo) -> bool {
+        let status = self.tracker.check(info);
+        if status.is_new() {
+            self.last_status = Some(status);
+        }
+        status.is_new()
+    }
+}
@@ -0,0 +1,149 @@
+//! Relay of topics between ROS2 domains.
+//!
+//! A [`DomainBridge`] creates a node in each of two contexts and forwards the
+//! messages of selected topics from one domain to the other, replacing the
+//! external `domain_bridge` tool. Messages are forwarded as raw CDR bytes, so
+//! no deserialization takes place.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::domain_bridge::{DomainBridge, TopicBridgeOptions};
+//! use oxidros::msg::common_interfaces::std_msgs;
+//! use oxidros::prelude::*;
+//! use std::time::Duration;
+//!
+//! let robot = Context::with_domain_id(1)?;
+//! let fleet = Context::with_domain_id(2)?;
+//!
+//! let mut bridge = DomainBridge::new("domain_bridge", &robot, &fleet)?;
+//! bridge.bridge_topic::<std_msgs::msg::String>("chatter", TopicBridgeOptions::default())?;
+//! bridge.bridge_topic::<std_msgs::msg::Float64>(
+//!     "temperature",
+//!     TopicBridgeOptions::default()
+//!         .remap("/robot1/temperature")
+//!         .min_interval(Duration::from_secs(1)),
+//! )?;
+//!
+//! // Relay until an error occurs
+//! bridge.run().await?;
+//! ```
+
+use futures_util::future::try_join_all;
+use oxidros_core::{
+    Profile, Result, TypeDescription, TypeSupport,
+    api::{RosContext, RosNode, RosSubscriber},
+};
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+type Relay = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+/// Options of a topic relayed by a [`DomainBridge`].
+#[derive(Debug, Clone, Default)]
+pub struct TopicBridgeOptions {
+    remap: Option<String>,
+    subscriber_qos: Option<Profile>,
+    publisher_qos: Option<Profile>,
+    min_interval: Option<Duration>,
+}
+
+impl TopicBridgeOptions {
+    /// Publish on `topic_name` in the target domain instead of the source
+    /// topic name.
+    pub fn remap(mut self, topic_name: impl Into<String>) -> Self {
+        self.remap = Some(topic_name.into());
+        self
+    }
+
+    /// QoS of the subscriber in the source domain.
+    pub fn subscriber_qos(mut self, qos: Profile) -> Self {
+        self.subscriber_qos = Some(qos);
+        self
+    }
+
+    /// QoS of the publisher in the target domain.
+    ///
+    /// Defaults to the QoS of the subscriber.
+    pub fn publisher_qos(mut self, qos: Profile) -> Self {
+        self.publisher_qos = Some(qos);
+        self
+    }
+
+    /// Drop messages received less than `interval` after the last relayed
+    /// one.
+    pub fn min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = Some(interval);
+        self
+    }
+
+    /// Relay at most `hz` messages per second.
+    ///
+    /// Equivalent to [`TopicBridgeOptions::min_interval`] with `1 / hz`.
+    /// A non-positive rate is ignored.
+    pub fn max_rate(self, hz: f64) -> Self {
+        if hz > 0.0 {
+            self.min_interval(Duration::from_secs_f64(1.0 / hz))
+        } else {
+            self
+        }
+    }
+}
+
+/// Relay of topics from the domain of one context to the domain of another.
+pub struct DomainBridge<C: RosContext> {
+    from: Arc<C::Node>,
+    to: Arc<C::Node>,
+    relays: Vec<Relay>,
+}
+
+impl<C: RosContext> DomainBridge<C> {
+    /// Create a bridge from the domain of `from` to the domain of `to`.
+    ///
+    /// A node named `name` is created in both contexts.
+    pub fn new(name: &str, from: &Arc<C>, to: &Arc<C>) -> Result<Self> {
+        Ok(DomainBridge {
+            from: from.create_node(name, None)?,
+            to: to.create_node(name, None)?,
+            relays: Vec::new(),
+        })
+    }
+
+    /// Relay the messages of `topic_name` to the target domain.
+    pub fn bridge_topic<T>(&mut self, topic_name: &str, options: TopicBridgeOptions) -> Result<()>
+    where
+        T: TypeSupport + TypeDescription + 'static,
+        <C::Node as RosNode>::Subscriber<T>: RosSubscriber<T> + 'static,
+        <C::Node as RosNode>::Publisher<T>: 'static,
+    {
+        let subscriber = self
+            .from
+            .create_subscriber::<T>(topic_name, options.subscriber_qos.clone())?;
+        let publisher = self.to.create_publisher::<T>(
+            options.remap.as_deref().unwrap_or(topic_name),
+            options.publisher_qos.or(options.subscriber_qos),
+        )?;
+        self.relays
+            .push(Box::pin(crate::nodes::forward_raw::<T, _, _>(
+                subscriber,
+                publisher,
+                options.min_interval,
+            )));
+        Ok(())
+    }
+
+    /// Get the node in the source domain.
+    pub fn source_node(&self) -> &Arc<C::Node> {
+        &self.from
+    }
+
+    /// Get the node in the target domain.
+    pub fn target_node(&self) -> &Arc<C::Node> {
+        &self.to
+    }
+
+    /// Relay the bridged topics until one of them fails.
+    pub async fn run(self) -> Result<()> {
+        try_join_all(self.relays).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+//! Multi-threaded executor with pinned worker threads.
+//!
+//! [`Executor`] builds a multi-threaded Tokio runtime whose threads are
+//! placed with [`ThreadOptions`] when they start: pinned to CPU cores and
+//! given a `SCHED_FIFO` priority where the process is permitted to.
+//! Unprivileged processes keep running with the settings they could apply,
+//! the others being logged as warnings.
+//!
+//! Tokio calls the same start hook for the threads of its blocking pool, which
+//! are therefore placed like the workers.
+//!
+//! For the single-threaded selectors, see
+//! [`RosSelector::set_thread_options`](oxidros_core::api::RosSelector::set_thread_options).
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::executor::Executor;
+//! use oxidros::prelude::*;
+//!
+//! let runtime = Executor::new()
+//!     .worker_threads(2)
+//!     .thread_options(ThreadOptions::new().cpus([2, 3]).pin_per_worker(true).fifo_priority(80))
+//!     .build()?;
+//!
+//! runtime.block_on(async {
+//!     let ctx = Context::new()?;
+//!     let node = ctx.create_node("controller", None)?;
+//!     // ...
+//! });
+//! ```
+
+use oxidros_core::thread::ThreadOptions;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+/// Builder of a multi-threaded Tokio runtime with placed threads.
+#[derive(Debug, Clone, Default)]
+pub struct Executor {
+    worker_threads: Option<usize>,
+    thread_options: ThreadOptions,
+}
+
+impl Executor {
+    /// Executor with one worker per core and the inherited placement.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of worker threads.
+    pub fn worker_threads(mut self, count: usize) -> Self {
+        self.worker_threads = Some(count);
+        self
+    }
+
+    /// Set the placement of the threads.
+    ///
+    /// With [`ThreadOptions::pin_per_worker`], the threads are pinned to the
+    /// cores in turn, in their start order.
+    pub fn thread_options(mut self, options: ThreadOptions) -> Self {
+        self.thread_options = options;
+        self
+    }
+
+    /// Build the runtime.
+    pub fn build(self) -> std::io::Result<tokio::runtime::Runtime> {
+        let mut builder = tokio::runtime::Builder::new_multi_thread();
+        builder.enable_all().thread_name("oxidros-worker");
+        if let Some(count) = self.worker_threads {
+            builder.worker_threads(count);
+        }
+        if !self.thread_options.is_default() {
+            let options = Arc::new(self.thread_options);
+            let index = AtomicUsize::new(0);
+            builder.on_thread_start(move || {
+                options.apply_to_worker(index.fetch_add(1, Ordering::Relaxed));
+            });
+        }
+        builder.build()
+    }
+}
@@ -0,0 +1,225 @@
+//! Instance-aware subscriptions for keyed messages.
+//!
+//! Some topics carry many logical instances, e.g. the objects of a tracker,
+//! each with its ID. DDS calls them instances and tells them apart by the key
+//! fields of the message. Messages declare these fields with `#[ros2(key)]`
+//! (see [`Keyed`]).
+//!
+//! Neither rmw nor Zenoh expose instances, so [`InstanceSubscriber`] tracks
+//! them on the subscriber side: it keeps the last sample of every instance,
+//! and takes the samples of one instance at a time.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::instance::InstanceSubscriber;
+//! use oxidros::prelude::*;
+//!
+//! let subscriber = node.create_subscriber::<TrackedObject>("objects", None)?;
+//! let mut objects = InstanceSubscriber::new(subscriber);
+//! loop {
+//!     let msg = objects.recv().await?;
+//!     println!("{} objects tracked", objects.len());
+//!     if let Some(object) = objects.last(&msg.key()) {
+//!         println!("last position: {:?}", object.position);
+//!     }
+//! }
+//! ```
+//!
+//! Messages without key fields are keyed by a function instead:
+//!
+//! ```ignore
+//! let mut objects = InstanceSubscriber::with_key(subscriber, |msg: &Int64| msg.data % 10);
+//! ```
+
+use oxidros_core::{
+    Keyed, Message, MessageInfo, Result, RosSubscriber, TryClone, TypeSupport, targets,
+};
+use std::{borrow::Cow, collections::BTreeMap, marker::PhantomData};
+
+/// State of an instance in an [`InstanceSubscriber`].
+#[derive(Debug)]
+pub struct Instance<T> {
+    /// Last sample of the instance.
+    pub sample: T,
+    /// Metadata of the last sample.
+    pub info: MessageInfo,
+    /// Samples received for the instance.
+    pub count: u64,
+    /// Whether the last sample was not taken yet, see
+    /// [`InstanceSubscriber::take_instance`].
+    pub unread: bool,
+}
+
+/// Subscriber keeping the last sample of every instance of a topic.
+///
+/// Wraps any [`RosSubscriber`]; the instances are told apart by the key of
+/// the messages, given by [`Keyed`] or by a function.
+pub struct InstanceSubscriber<T, S, K> {
+    inner: S,
+    key: fn(&T) -> K,
+    instances: BTreeMap<K, Instance<T>>,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: TypeSupport + TryClone + Keyed, S: RosSubscriber<T>> InstanceSubscriber<T, S, T::Key> {
+    /// Wrap `inner`, telling instances apart by the key fields of `T`.
+    pub fn new(inner: S) -> Self {
+        Self::with_key(inner, T::key)
+    }
+}
+
+impl<T: TypeSupport + TryClone, S: RosSubscriber<T>, K: Ord> InstanceSubscriber<T, S, K> {
+    /// Wrap `inner`, telling instances apart by `key`.
+    pub fn with_key(inner: S, key: fn(&T) -> K) -> Self {
+        InstanceSubscriber {
+            inner,
+            key,
+            instances: BTreeMap::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Get the topic name.
+    pub fn topic_name(&self) -> Result<Cow<'_, String>> {
+        self.inner.topic_name()
+    }
+
+    /// Receive the next message, of any instance.
+    ///
+    /// The message becomes the last sample of its instance, and stays unread
+    /// until taken by [`InstanceSubscriber::take_instance`].
+    pub async fn recv(&mut self) -> Result<Message<T>> {
+        let msg = self.inner.recv().await?;
+        self.update(&msg);
+        Ok(msg)
+    }
+
+    /// Try to receive a message, of any instance, without blocking.
+    ///
+    /// Returns `Ok(None)` if no message is currently available.
+    pub fn try_recv(&mut self) -> Result<Option<Message<T>>> {
+        let msg = self.inner.try_recv()?;
+        if let Some(msg) = &msg {
+            self.update(msg);
+        }
+        Ok(msg)
+    }
+
+    /// Receive the messages currently available and update the instances.
+    ///
+    /// Returns the number of messages received.
+    pub fn poll(&mut self) -> Result<usize> {
+        let mut count = 0;
+        while self.try_recv()?.is_some() {
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Receive the next message of the instance `key`.
+    ///
+    /// Messages of other instances received meanwhile update their instance.
+    pub async fn recv_instance(&mut self, key: &K) -> Result<Message<T>> {
+        loop {
+            let msg = self.recv().await?;
+            if (self.key)(&msg) == *key {
+                self.mark_read(key);
+                return Ok(msg);
+            }
+        }
+    }
+
+    /// Take the unread last sample of the instance `key`, after receiving
+    /// the messages currently available.
+    ///
+    /// Returns `Ok(None)` if the instance has no sample which was not taken
+    /// yet. Older samples of the instance are not kept.
+    pub fn take_instance(&mut self, key: &K) -> Result<Option<T>> {
+        self.poll()?;
+        let Some(instance) = self.instances.get_mut(key) else {
+            return Ok(None);
+        };
+        if !instance.unread {
+            return Ok(None);
+        }
+        instance.unread = false;
+        Ok(instance.sample.try_clone())
+    }
+
+    /// Get the last sample of the instance `key`.
+    pub fn last(&self, key: &K) -> Option<&T> {
+        self.instances.get(key).map(|instance| &instance.sample)
+    }
+
+    /// Get the state of the instance `key`.
+    pub fn instance(&self, key: &K) -> Option<&Instance<T>> {
+        self.instances.get(key)
+    }
+
+    /// Iterate over the instances, ordered by key.
+    pub fn instances(&self) -> impl Iterator<Item = (&K, &Instance<T>)> {
+        self.instances.iter()
+    }
+
+    /// Get the number of instances seen.
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Return `true` if no instance was seen.
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+
+    /// Forget the instance `key`, e.g. an object which left the scene.
+    ///
+    /// Returns its state, if it was known.
+    pub fn remove(&mut self, key: &K) -> Option<Instance<T>> {
+        self.instances.remove(key)
+    }
+
+    /// Forget all the instances.
+    pub fn clear(&mut self) {
+        self.instances.clear();
+    }
+
+    /// Get the wrapped subscriber back.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn mark_read(&mut self, key: &K) {
+        if let Some(instance) = self.instances.get_mut(key) {
+            instance.unread = false;
+        }
+    }
+
+    fn update(&mut self, msg: &Message<T>) {
+        // Loaned samples are copied, as they must be returned to the middleware
+        let Some(sample) = msg.try_clone() else {
+            tracing::warn!(target: targets::SUBSCRIBER, "Failed to copy the sample of an instance");
+            return;
+        };
+        let key = (self.key)(&sample);
+        match self.instances.get_mut(&key) {
+            Some(instance) => {
+                instance.sample = sample;
+                instance.info = msg.info;
+                instance.count += 1;
+                instance.unread = true;
+            }
+            None => {
+                self.instances.insert(
+                    key,
+                    Instance {
+                        sample,
+                        info: msg.info,
+                        count: 1,
+                        unread: true,
+                    },
+                );
+            }
+        }
+    }
+}
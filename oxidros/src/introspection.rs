@@ -0,0 +1,55 @@
+//! Rendering of messages as JSON.
+//!
+//! Debugging tools and dashboards can display any message without code
+//! specific to its type: [`to_json`] converts a typed message, and
+//! [`cdr_to_json`] converts the CDR bytes of a message whose type is only
+//! known at runtime from its type description, e.g. one received with
+//! `recv_raw` or obtained from the `type_description` service.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::introspection;
+//! use oxidros::prelude::*;
+//!
+//! let msg = subscriber.recv().await?;
+//! println!("{}", introspection::to_json(&*msg)?);
+//!
+//! let (bytes, _info) = raw_subscriber.recv_raw().await?;
+//! println!("{:#}", introspection::cdr_to_json(&type_description, &bytes)?);
+//! ```
+
+use oxidros_core::{Error, Result, TypeDescription, TypeSupport, types::TypeDescriptionMsg};
+
+pub use serde_json::Value;
+
+/// Convert a message to JSON.
+///
+/// Fields become the members of an object, in the order of the message
+/// definition; arrays and sequences become JSON arrays.
+///
+/// # Errors
+///
+/// Returns the serialization error of the message, or `Error::Other` if it
+/// cannot be decoded with its own type description.
+pub fn to_json<T: TypeSupport + TypeDescription>(msg: &T) -> Result<Value> {
+    let bytes = msg.to_bytes()?;
+    cdr_to_json(&T::type_description(), &bytes)
+}
+
+/// Convert CDR bytes to JSON, using the type description of the message.
+///
+/// `bytes` starts with the 4-byte encapsulation header, as published on the
+/// wire.
+///
+/// # Errors
+///
+/// Returns `Error::Other` if `bytes` does not match `type_description`.
+pub fn cdr_to_json(type_description: &TypeDescriptionMsg, bytes: &[u8]) -> Result<Value> {
+    oxidros_dynamic::decode_cdr(bytes, type_description).map_err(|e| {
+        Error::Other(format!(
+            "failed to decode {}: {e}",
+            type_description.type_description.type_name
+        ))
+    })
+}
@@ -0,0 +1,401 @@
+//! Node templates for sensor drivers and controllers.
+//!
+//! The templates give nodes of the same kind the same structure, while the
+//! application only provides closures:
+//!
+//! | Node | Behavior | Parameters |
+//! |------|----------|------------|
+//! | [`SensorDriverNode`] | Polls a sensor `rate` times per second, publishes the readings on `topic` and reports its health on `/diagnostics` | `topic`, `rate` |
+//! | [`ControllerNode`] | Runs a control closure `rate` times per second on the latest message of `input_topic` and publishes the output on `output_topic`, with an optional watchdog on the input | `input_topic`, `output_topic`, `rate`, `watchdog_timeout` (seconds, optional) |
+//!
+//! Like the nodes of [`crate::nodes`], each template is built either from
+//! explicit values or from the parameters of a parameter server.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::kit::{ControllerNode, SensorDriverNode};
+//! use oxidros::msg::common_interfaces::{geometry_msgs::msg::Twist, sensor_msgs::msg::Imu};
+//! use oxidros::prelude::*;
+//! use std::time::Duration;
+//!
+//! let driver = SensorDriverNode::new(&node, "imu", 100.0, move || imu.read())?
+//!     .hardware_id("imu0");
+//!
+//! let controller = ControllerNode::new(&node, "cmd_vel", "wheels", 50.0, |cmd: &Twist| {
+//!     Some(wheel_speeds(cmd))
+//! })?
+//! // Stop the wheels when no command is received for half a second
+//! .watchdog(Duration::from_millis(500), || Some(wheel_speeds(&Twist::new().unwrap())));
+//!
+//! tokio::try_join!(driver.run(), controller.run())?;
+//! ```
+
+use crate::nodes::{number_parameter, string_parameter};
+use oxidros_core::{
+    Error, Profile, RclError, Result, TypeDescription, TypeSupport,
+    api::{RosNode, RosPublisher, RosSubscriber},
+    message::Message,
+    parameter::Parameters,
+    targets,
+};
+use oxidros_msg::common_interfaces::diagnostic_msgs::msg::{
+    DiagnosticArray, DiagnosticStatus, DiagnosticStatusSeq, KeyValueSeq,
+};
+use std::{sync::Arc, time::Duration};
+use tokio::time::{Instant, MissedTickBehavior};
+
+/// Topic of the diagnostics of [`SensorDriverNode`].
+pub const DIAGNOSTICS_TOPIC: &str = "/diagnostics";
+
+/// Default interval between two diagnostics of a [`SensorDriverNode`].
+pub const DEFAULT_DIAGNOSTICS_PERIOD: Duration = Duration::from_secs(1);
+
+type Read<T> = Box<dyn FnMut() -> Result<Option<T>> + Send>;
+
+/// Polls a sensor at a fixed rate and publishes its readings.
+///
+/// The read closure returns `Ok(None)` when no reading is available yet.
+/// Every [`DEFAULT_DIAGNOSTICS_PERIOD`], a `diagnostic_msgs/DiagnosticArray`
+/// reports the measured rate and the read errors on [`DIAGNOSTICS_TOPIC`]:
+/// the level is `ERROR` when every read failed, `WARN` when some failed or
+/// none returned a reading, and `OK` otherwise.
+pub struct SensorDriverNode<N, T> {
+    node: Arc<N>,
+    topic: String,
+    rate: f64,
+    qos: Option<Profile>,
+    hardware_id: String,
+    diagnostics_period: Duration,
+    read: Read<T>,
+}
+
+impl<N, T> SensorDriverNode<N, T>
+where
+    N: RosNode,
+    T: TypeSupport + TypeDescription + 'static,
+{
+    /// Create a driver calling `read` `rate` times per second and publishing
+    /// the readings on `topic`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if `rate` is not positive.
+    pub fn new<F>(node: &Arc<N>, topic: &str, rate: f64, read: F) -> Result<Self>
+    where
+        F: FnMut() -> Result<Option<T>> + Send + 'static,
+    {
+        check_rate(rate)?;
+        Ok(SensorDriverNode {
+            node: node.clone(),
+            topic: topic.to_string(),
+            rate,
+            qos: None,
+            hardware_id: String::new(),
+            diagnostics_period: DEFAULT_DIAGNOSTICS_PERIOD,
+            read: Box::new(read),
+        })
+    }
+
+    /// Create a driver configured by the `topic` and `rate` parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if a parameter is missing or has the
+    /// wrong type, or if `rate` is not positive.
+    pub fn from_parameters<F>(node: &Arc<N>, params: &Parameters, read: F) -> Result<Self>
+    where
+        F: FnMut() -> Result<Option<T>> + Send + 'static,
+    {
+        let rate = number_parameter(params, "rate")?
+            .ok_or_else(|| Error::InvalidConfig("parameter rate is not set".into()))?;
+        Self::new(node, &string_parameter(params, "topic")?, rate, read)
+    }
+
+    /// QoS of the publisher of the readings.
+    pub fn qos(mut self, qos: Profile) -> Self {
+        self.qos = Some(qos);
+        self
+    }
+
+    /// Hardware id reported in the diagnostics.
+    pub fn hardware_id(mut self, hardware_id: &str) -> Self {
+        self.hardware_id = hardware_id.to_string();
+        self
+    }
+
+    /// Interval between two diagnostics.
+    pub fn diagnostics_period(mut self, period: Duration) -> Self {
+        self.diagnostics_period = period;
+        self
+    }
+
+    /// Poll the sensor until publishing fails.
+    ///
+    /// Read errors are logged and reported in the diagnostics, they do not
+    /// stop the driver.
+    pub async fn run(mut self) -> Result<()> {
+        let publisher = self
+            .node
+            .create_publisher::<T>(&self.topic, self.qos.take())?;
+        let diagnostics = self
+            .node
+            .create_publisher::<DiagnosticArray>(DIAGNOSTICS_TOPIC, None)?;
+        let name = format!("{}: {}", self.node.fully_qualified_name()?, self.topic);
+
+        let mut poll = tokio::time::interval(Duration::from_secs_f64(1.0 / self.rate));
+        poll.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let start = Instant::now() + self.diagnostics_period;
+        let mut report = tokio::time::interval_at(start, self.diagnostics_period);
+        report.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let mut health = Health::default();
+        let mut since = Instant::now();
+        loop {
+            tokio::select! {
+                _ = poll.tick() => match (self.read)() {
+                    Ok(Some(msg)) => {
+                        publisher.send(&msg)?;
+                        health.published += 1;
+                    }
+                    Ok(None) => health.empty += 1,
+                    Err(e) => {
+                        tracing::warn!(target: targets::KIT, topic = %self.topic, error = %e, "Sensor read failed");
+                        health.errors += 1;
+                        health.last_error = Some(e.to_string());
+                    }
+                },
+                _ = report.tick() => {
+                    let elapsed = since.elapsed().as_secs_f64();
+                    diagnostics.send(&health.report(&name, &self.hardware_id, self.rate, elapsed)?)?;
+                    health = Health::default();
+                    since = Instant::now();
+                }
+            }
+        }
+    }
+}
+
+/// Outcome of the reads of a [`SensorDriverNode`] since the last diagnostics.
+#[derive(Default)]
+struct Health {
+    published: u64,
+    empty: u64,
+    errors: u64,
+    last_error: Option<String>,
+}
+
+impl Health {
+    fn report(
+        &self,
+        name: &str,
+        hardware_id: &str,
+        expected_rate: f64,
+        elapsed: f64,
+    ) -> Result<DiagnosticArray> {
+        let (level, message) = match (self.published, self.errors, &self.last_error) {
+            (0, 1.., Some(error)) => (DiagnosticStatus::ERROR, error.clone()),
+            (_, 1.., Some(error)) => (
+                DiagnosticStatus::WARN,
+                format!("{} read errors, last: {error}", self.errors),
+            ),
+            (0, _, _) => (DiagnosticStatus::WARN, "no reading".to_string()),
+            _ => (DiagnosticStatus::OK, "OK".to_string()),
+        };
+        let rate = if elapsed > 0.0 {
+            self.published as f64 / elapsed
+        } else {
+            0.0
+        };
+        let values = [
+            ("rate", format!("{rate:.2}")),
+            ("expected_rate", format!("{expected_rate:.2}")),
+            ("published", self.published.to_string()),
+            ("empty_reads", self.empty.to_string()),
+            ("errors", self.errors.to_string()),
+        ];
+
+        let mut statuses = DiagnosticStatusSeq::<0>::new(1).ok_or(RclError::BadAlloc)?;
+        let status = &mut statuses.as_mut_slice()[0];
+        status.level = level;
+        status.name.assign(name);
+        status.message.assign(&message);
+        status.hardware_id.assign(hardware_id);
+        let mut seq = KeyValueSeq::<0>::new(values.len()).ok_or(RclError::BadAlloc)?;
+        for (kv, (key, value)) in seq.as_mut_slice().iter_mut().zip(&values) {
+            kv.key.assign(key);
+            kv.value.assign(value);
+        }
+        status.values = seq;
+
+        let mut array = DiagnosticArray::new().ok_or(RclError::BadAlloc)?;
+        array.status = statuses;
+        Ok(array)
+    }
+}
+
+type Control<I, O> = Box<dyn FnMut(&I) -> Option<O> + Send>;
+type Fallback<O> = Box<dyn FnMut() -> Option<O> + Send>;
+
+/// Runs a control closure at a fixed rate on the latest input message.
+///
+/// The closure returns `None` to publish nothing at this tick. Nothing is
+/// published before the first input, unless a watchdog is set.
+pub struct ControllerNode<N, I, O> {
+    node: Arc<N>,
+    input_topic: String,
+    output_topic: String,
+    rate: f64,
+    qos: Option<Profile>,
+    control: Control<I, O>,
+    watchdog: Option<(Duration, Fallback<O>)>,
+}
+
+impl<N, I, O> ControllerNode<N, I, O>
+where
+    N: RosNode,
+    I: TypeSupport + TypeDescription + Send + 'static,
+    O: TypeSupport + TypeDescription + 'static,
+    N::Subscriber<I>: RosSubscriber<I>,
+{
+    /// Create a controller calling `control` `rate` times per second with the
+    /// latest message of `input_topic`, and publishing its output on
+    /// `output_topic`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if `rate` is not positive.
+    pub fn new<F>(
+        node: &Arc<N>,
+        input_topic: &str,
+        output_topic: &str,
+        rate: f64,
+        control: F,
+    ) -> Result<Self>
+    where
+        F: FnMut(&I) -> Option<O> + Send + 'static,
+    {
+        check_rate(rate)?;
+        Ok(ControllerNode {
+            node: node.clone(),
+            input_topic: input_topic.to_string(),
+            output_topic: output_topic.to_string(),
+            rate,
+            qos: None,
+            control: Box::new(control),
+            watchdog: None,
+        })
+    }
+
+    /// Create a controller configured by the `input_topic`, `output_topic`
+    /// and `rate` parameters.
+    ///
+    /// The watchdog is enabled by the `watchdog_timeout` parameter, in
+    /// seconds; its fallback publishes nothing until
+    /// [`ControllerNode::watchdog`] replaces it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if a parameter is missing or has the
+    /// wrong type, or if `rate` or `watchdog_timeout` is not positive.
+    pub fn from_parameters<F>(node: &Arc<N>, params: &Parameters, control: F) -> Result<Self>
+    where
+        F: FnMut(&I) -> Option<O> + Send + 'static,
+    {
+        let rate = number_parameter(params, "rate")?
+            .ok_or_else(|| Error::InvalidConfig("parameter rate is not set".into()))?;
+        let mut controller = Self::new(
+            node,
+            &string_parameter(params, "input_topic")?,
+            &string_parameter(params, "output_topic")?,
+            rate,
+            control,
+        )?;
+        if let Some(timeout) = number_parameter(params, "watchdog_timeout")? {
+            let timeout = Duration::try_from_secs_f64(timeout)
+                .ok()
+                .filter(|timeout| !timeout.is_zero())
+                .ok_or_else(|| {
+                    Error::InvalidConfig(format!(
+                        "watchdog timeout must be positive, got {timeout}"
+                    ))
+                })?;
+            controller = controller.watchdog(timeout, || None);
+        }
+        Ok(controller)
+    }
+
+    /// QoS of the subscriber and of the publisher.
+    pub fn qos(mut self, qos: Profile) -> Self {
+        self.qos = Some(qos);
+        self
+    }
+
+    /// Call `fallback` instead of the control closure while no input was
+    /// received for `timeout`, including before the first input.
+    ///
+    /// The fallback typically returns a command stopping the actuators.
+    pub fn watchdog<F>(mut self, timeout: Duration, fallback: F) -> Self
+    where
+        F: FnMut() -> Option<O> + Send + 'static,
+    {
+        self.watchdog = Some((timeout, Box::new(fallback)));
+        self
+    }
+
+    /// Run the control loop until an error occurs.
+    pub async fn run(mut self) -> Result<()> {
+        let mut subscriber = self
+            .node
+            .create_subscriber::<I>(&self.input_topic, self.qos.clone())?;
+        let publisher = self
+            .node
+            .create_publisher::<O>(&self.output_topic, self.qos.take())?;
+
+        let mut tick = tokio::time::interval(Duration::from_secs_f64(1.0 / self.rate));
+        tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        let mut latest: Option<(Message<I>, Instant)> = None;
+        let mut expired = false;
+        loop {
+            tokio::select! {
+                msg = subscriber.recv() => latest = Some((msg?, Instant::now())),
+                _ = tick.tick() => {
+                    let output = match (&latest, self.watchdog.as_mut()) {
+                        (Some((msg, received)), Some((timeout, _)))
+                            if received.elapsed() <= *timeout =>
+                        {
+                            if expired {
+                                expired = false;
+                                tracing::info!(target: targets::KIT, topic = %self.input_topic, "Controller input resumed");
+                            }
+                            (self.control)(&**msg)
+                        }
+                        (_, Some((timeout, fallback))) => {
+                            if !expired {
+                                expired = true;
+                                tracing::warn!(target: targets::KIT, topic = %self.input_topic, ?timeout, "Controller input timed out");
+                            }
+                            fallback()
+                        }
+                        (Some((msg, _)), None) => (self.control)(&**msg),
+                        (None, None) => None,
+                    };
+                    if let Some(output) = output {
+                        publisher.send(&output)?;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn check_rate(rate: f64) -> Result<()> {
+    if rate > 0.0 && rate.is_finite() {
+        Ok(())
+    } else {
+        Err(Error::InvalidConfig(format!(
+            "rate must be positive, got {rate}"
+        )))
+    }
+}
@@ -0,0 +1,508 @@
+//! Programmatic startup of a set of nodes.
+//!
+//! A [`Launch`] is a typed alternative to Python launch files for pure-Rust
+//! systems. It declares nodes with their parameters and remappings, and
+//! starts them once their [`StartCondition`]s hold. Each [`LaunchNode`] runs
+//! either in-process, on a node created from the context of the launch, or
+//! as a child process started with the matching `--ros-args`.
+//!
+//! The backends read remapping rules from the command line of the process
+//! only, so in-process nodes resolve their topic names with
+//! [`NodeConfig::remap`] and get their parameters from
+//! [`NodeConfig::parameters`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::launch::{Launch, LaunchNode, StartCondition};
+//! use oxidros::prelude::*;
+//!
+//! let ctx = Context::new()?;
+//! Launch::new(&ctx)
+//!     // started first, as a child process
+//!     .node(
+//!         LaunchNode::process("camera", "camera_driver")
+//!             .namespace("/robot")
+//!             .parameter("fps", 30i64)
+//!             .remap("image_raw", "camera/image"),
+//!     )
+//!     // started once the lifecycle node is active
+//!     .node(
+//!         LaunchNode::in_process("detector", |node, config| async move {
+//!             let mut images = node.create_subscriber::<Image>(config.remap("image"), None)?;
+//!             loop {
+//!                 detect(images.recv().await?);
+//!             }
+//!         })
+//!         .namespace("/robot")
+//!         .remap("image", "camera/image")
+//!         .after(StartCondition::LifecycleActive("/robot/camera".into())),
+//!     )
+//!     .run()
+//!     .await?;
+//! ```
+
+use futures_util::future::{BoxFuture, try_join_all};
+use oxidros_core::{
+    Error, RclError, Result,
+    api::{RosClient, RosContext, RosNode},
+    parameter::{Parameters, Value},
+    targets,
+};
+use oxidros_msg::interfaces::lifecycle_msgs::{
+    msg::State,
+    srv::{GetState, GetState_Request},
+};
+use ros2args::Ros2ArgsBuilder;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    future::Future,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{process::Command, sync::watch, time::Instant};
+
+/// Default time to wait for a lifecycle node to become active.
+pub const DEFAULT_CONDITION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Interval between two queries of the state of a lifecycle node.
+const POLL_PERIOD: Duration = Duration::from_millis(100);
+
+/// What a node waits for before being started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartCondition {
+    /// The node of the launch with this name was started.
+    Started(String),
+    /// The lifecycle node with this fully qualified name reports the active
+    /// state through its `get_state` service.
+    LifecycleActive(String),
+}
+
+/// Name, parameters and remapping rules of a launched node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeConfig {
+    /// Node name.
+    pub name: String,
+    /// Node namespace, `/` if `None`.
+    pub namespace: Option<String>,
+    /// Parameters, in declaration order.
+    pub parameters: Vec<(String, Value)>,
+    /// Remapping rules `(from, to)`, in declaration order.
+    pub remaps: Vec<(String, String)>,
+}
+
+impl NodeConfig {
+    /// Fully qualified name of the node.
+    pub fn fully_qualified_name(&self) -> String {
+        match self.namespace.as_deref().map(|ns| ns.trim_end_matches('/')) {
+            None | Some("") => format!("/{}", self.name),
+            Some(ns) if ns.starts_with('/') => format!("{ns}/{}", self.name),
+            Some(ns) => format!("/{ns}/{}", self.name),
+        }
+    }
+
+    /// Apply the remapping rules to a topic or service name.
+    ///
+    /// The first rule whose `from` is `name` wins. Other names are returned
+    /// unchanged.
+    pub fn remap<'a>(&'a self, name: &'a str) -> &'a str {
+        self.remaps
+            .iter()
+            .find(|(from, _)| from == name)
+            .map_or(name, |(_, to)| to)
+    }
+
+    /// Build the parameters of the node.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a parameter is set twice or is [`Value::NotSet`].
+    pub fn parameters(&self) -> Result<Parameters> {
+        let mut params = Parameters::new();
+        for (name, value) in &self.parameters {
+            if params.get_parameter(name).is_some() {
+                return Err(Error::InvalidConfig(format!(
+                    "parameter {name} of node {} is set twice",
+                    self.name
+                )));
+            }
+            params.set_parameter(name.clone(), value.clone(), false, None)?;
+        }
+        params.take_updated();
+        Ok(params)
+    }
+
+    /// Command line arguments of a child process, starting with
+    /// `--ros-args`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if a parameter is [`Value::NotSet`] or
+    /// if a name cannot be written on the command line.
+    pub fn ros_args(&self) -> Result<Vec<String>> {
+        let mut builder = Ros2ArgsBuilder::new().remap("__node", &self.name);
+        if let Some(ns) = &self.namespace {
+            builder = builder.remap("__ns", ns);
+        }
+        for (from, to) in &self.remaps {
+            builder = builder.remap(from, to);
+        }
+        for (name, value) in &self.parameters {
+            builder = match value.clone() {
+                Value::NotSet => {
+                    return Err(Error::InvalidConfig(format!(
+                        "parameter {name} of node {} is not set",
+                        self.name
+                    )));
+                }
+                Value::Bool(v) => builder.param(name, v),
+                Value::I64(v) => builder.param(name, v),
+                Value::F64(v) => builder.param(name, v),
+                Value::String(v) => builder.param(name, v),
+                Value::VecBool(v) => builder.param(name, v),
+                Value::VecI64(v) => builder.param(name, v),
+                Value::VecU8(v) => builder.param(name, v),
+                Value::VecF64(v) => builder.param(name, v),
+                Value::VecString(v) => builder.param(name, v),
+            };
+        }
+        builder
+            .build_args()
+            .map_err(|e| Error::InvalidConfig(e.to_string()))
+    }
+}
+
+type Start<N> = Box<dyn FnOnce(Arc<N>, NodeConfig) -> BoxFuture<'static, Result<()>> + Send>;
+
+enum Executable<N> {
+    InProcess(Start<N>),
+    Process { program: PathBuf, args: Vec<String> },
+}
+
+/// A node of a [`Launch`].
+pub struct LaunchNode<N> {
+    config: NodeConfig,
+    executable: Executable<N>,
+    conditions: Vec<StartCondition>,
+}
+
+impl<N: RosNode> LaunchNode<N> {
+    /// A node created from the context of the launch, running `start`.
+    ///
+    /// The node is stopped when the future returned by `start` completes.
+    pub fn in_process<F, Fut>(name: &str, start: F) -> Self
+    where
+        F: FnOnce(Arc<N>, NodeConfig) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        Self::with_executable(
+            name,
+            Executable::InProcess(Box::new(move |node, config| Box::pin(start(node, config)))),
+        )
+    }
+
+    /// A node running as a child process of `program`.
+    ///
+    /// The process is killed when the launch stops, and fails the launch if it
+    /// exits with an error.
+    pub fn process(name: &str, program: impl Into<PathBuf>) -> Self {
+        Self::with_executable(
+            name,
+            Executable::Process {
+                program: program.into(),
+                args: Vec::new(),
+            },
+        )
+    }
+
+    fn with_executable(name: &str, executable: Executable<N>) -> Self {
+        LaunchNode {
+            config: NodeConfig {
+                name: name.to_string(),
+                namespace: None,
+                parameters: Vec::new(),
+                remaps: Vec::new(),
+            },
+            executable,
+            conditions: Vec::new(),
+        }
+    }
+
+    /// Namespace of the node.
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        self.config.namespace = Some(namespace.to_string());
+        self
+    }
+
+    /// Set the parameter `name`.
+    pub fn parameter(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.config
+            .parameters
+            .push((name.to_string(), value.into()));
+        self
+    }
+
+    /// Remap the topic or service `from` to `to`.
+    pub fn remap(mut self, from: &str, to: &str) -> Self {
+        self.config.remaps.push((from.to_string(), to.to_string()));
+        self
+    }
+
+    /// Argument of the child process, before `--ros-args`.
+    ///
+    /// Ignored by in-process nodes.
+    pub fn arg(mut self, arg: &str) -> Self {
+        if let Executable::Process { args, .. } = &mut self.executable {
+            args.push(arg.to_string());
+        }
+        self
+    }
+
+    /// Start the node once `condition` holds.
+    pub fn after(mut self, condition: StartCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Name, parameters and remapping rules of the node.
+    pub fn config(&self) -> &NodeConfig {
+        &self.config
+    }
+}
+
+/// A set of nodes started in dependency order.
+pub struct Launch<C: RosContext> {
+    ctx: Arc<C>,
+    nodes: Vec<LaunchNode<C::Node>>,
+    condition_timeout: Duration,
+}
+
+impl<C> Launch<C>
+where
+    C: RosContext,
+    C::Node: 'static,
+{
+    /// Create a launch without node.
+    pub fn new(ctx: &Arc<C>) -> Self {
+        Launch {
+            ctx: ctx.clone(),
+            nodes: Vec::new(),
+            condition_timeout: DEFAULT_CONDITION_TIMEOUT,
+        }
+    }
+
+    /// Add a node.
+    pub fn node(mut self, node: LaunchNode<C::Node>) -> Self {
+        self.nodes.push(node);
+        self
+    }
+
+    /// Time to wait for a [`StartCondition::LifecycleActive`] condition.
+    pub fn condition_timeout(mut self, timeout: Duration) -> Self {
+        self.condition_timeout = timeout;
+        self
+    }
+
+    /// Names of the nodes, in an order compatible with their
+    /// [`StartCondition::Started`] conditions.
+    ///
+    /// Nodes without dependency between them keep their declaration order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if two nodes have the same name, if a
+    /// condition refers to an unknown node, or if the conditions form a
+    /// cycle.
+    pub fn startup_order(&self) -> Result<Vec<&str>> {
+        let mut indices = BTreeMap::new();
+        for (i, node) in self.nodes.iter().enumerate() {
+            if indices.insert(node.config.name.as_str(), i).is_some() {
+                return Err(Error::InvalidConfig(format!(
+                    "node {} is launched twice",
+                    node.config.name
+                )));
+            }
+        }
+
+        let mut pending = vec![0usize; self.nodes.len()];
+        let mut dependents = vec![Vec::new(); self.nodes.len()];
+        for (i, node) in self.nodes.iter().enumerate() {
+            for condition in &node.conditions {
+                let StartCondition::Started(name) = condition else {
+                    continue;
+                };
+                let Some(&j) = indices.get(name.as_str()) else {
+                    return Err(Error::InvalidConfig(format!(
+                        "node {} waits for unknown node {name}",
+                        node.config.name
+                    )));
+                };
+                pending[i] += 1;
+                dependents[j].push(i);
+            }
+        }
+
+        let mut ready: VecDeque<usize> =
+            (0..self.nodes.len()).filter(|&i| pending[i] == 0).collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(i) = ready.pop_front() {
+            order.push(self.nodes[i].config.name.as_str());
+            for &j in &dependents[i] {
+                pending[j] -= 1;
+                if pending[j] == 0 {
+                    ready.push_back(j);
+                }
+            }
+        }
+
+        if order.len() != self.nodes.len() {
+            let cycle: Vec<&str> = (0..self.nodes.len())
+                .filter(|&i| pending[i] > 0)
+                .map(|i| self.nodes[i].config.name.as_str())
+                .collect();
+            return Err(Error::InvalidConfig(format!(
+                "start conditions form a cycle between {}",
+                cycle.join(", ")
+            )));
+        }
+        Ok(order)
+    }
+
+    /// Start the nodes and run them until they all stop, one of them fails,
+    /// or the process receives a termination signal.
+    ///
+    /// Child processes still running when the launch stops are killed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the launch is invalid (see
+    /// [`Launch::startup_order`]), if a node cannot be started, if a
+    /// condition times out, or if a node fails.
+    pub async fn run(self) -> Result<()> {
+        self.startup_order()?;
+
+        let launcher = if self.nodes.iter().any(|node| {
+            node.conditions
+                .iter()
+                .any(|c| matches!(c, StartCondition::LifecycleActive(_)))
+        }) {
+            let name = format!("launch_{}", std::process::id());
+            Some(self.ctx.create_node(&name, None)?)
+        } else {
+            None
+        };
+
+        let mut started = BTreeMap::new();
+        let mut senders = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let (tx, rx) = watch::channel(false);
+            started.insert(node.config.name.clone(), rx);
+            senders.push(tx);
+        }
+
+        let shutdown = self.ctx.shutdown_token();
+        let Launch {
+            ctx,
+            nodes,
+            condition_timeout,
+        } = self;
+
+        let tasks = nodes.into_iter().zip(senders).map(|(node, tx)| {
+            let ctx = ctx.clone();
+            let launcher = launcher.clone();
+            let started = &started;
+            async move {
+                for condition in &node.conditions {
+                    match condition {
+                        StartCondition::Started(name) => {
+                            let mut rx = started[name].clone();
+                            rx.wait_for(|started| *started)
+                                .await
+                                .map_err(|_| Error::ChannelClosed)?;
+                        }
+                        StartCondition::LifecycleActive(target) => {
+                            let launcher =
+                                launcher.as_ref().expect("created for lifecycle conditions");
+                            wait_active(launcher, target, condition_timeout).await?;
+                        }
+                    }
+                }
+                start(&ctx, node, tx).await
+            }
+        });
+
+        tokio::select! {
+            result = try_join_all(tasks) => result.map(|_| ()),
+            _ = shutdown.wait() => Ok(()),
+        }
+    }
+}
+
+/// Start `node`, notify `started`, and run it until it stops.
+async fn start<C: RosContext>(
+    ctx: &Arc<C>,
+    node: LaunchNode<C::Node>,
+    started: watch::Sender<bool>,
+) -> Result<()> {
+    let LaunchNode {
+        config, executable, ..
+    } = node;
+    let fqn = config.fully_qualified_name();
+
+    match executable {
+        Executable::InProcess(start) => {
+            let node = ctx.create_node(&config.name, config.namespace.as_deref())?;
+            tracing::info!(target: targets::LAUNCH, node = %fqn, "Started in-process node");
+            let run = start(node, config);
+            started.send_replace(true);
+            run.await
+        }
+        Executable::Process { program, args } => {
+            let mut child = Command::new(&program)
+                .args(&args)
+                .args(config.ros_args()?)
+                .kill_on_drop(true)
+                .spawn()?;
+            tracing::info!(
+                target: targets::LAUNCH,
+                node = %fqn,
+                program = %program.display(),
+                pid = child.id(),
+                "Started node process"
+            );
+            started.send_replace(true);
+
+            let status = child.wait().await?;
+            if status.success() {
+                tracing::info!(target: targets::LAUNCH, node = %fqn, "Node process exited");
+                Ok(())
+            } else {
+                Err(Error::Other(format!("node {fqn} exited with {status}")))
+            }
+        }
+    }
+}
+
+/// Wait until the lifecycle node `target` reports the active state.
+async fn wait_active<N: RosNode>(node: &Arc<N>, target: &str, timeout: Duration) -> Result<()> {
+    let mut client = node.create_client::<GetState>(&format!("{target}/get_state"), None)?;
+    let request = GetState_Request::new().ok_or(RclError::BadAlloc)?;
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        if client.is_service_available() {
+            let response = tokio::time::timeout_at(deadline, client.call(&request))
+                .await
+                .map_err(|_| Error::Timeout)??;
+            if response.current_state.id == State::PRIMARY_STATE_ACTIVE {
+                tracing::debug!(target: targets::LAUNCH, node = %target, "Lifecycle node active");
+                return Ok(());
+            }
+        }
+        if Instant::now() >= deadline {
+            return Err(Error::Timeout);
+        }
+        tokio::time::sleep(POLL_PERIOD).await;
+    }
+}
@@ -155,6 +155,10 @@
 //!             false,
 //!             Some("Update rate".to_string()),
 //!         )?;
+//!
+//!         // Or through serde, from and to any compatible Rust type
+//!         params.set_from("frames", &["base_link", "odom"])?;
+//!         let rate: f32 = params.get_as("rate")?;
 //!     }
 //!
 //!     // Add to selector with update callback
@@ -169,6 +173,338 @@
 //! }
 //! ```
 //!
+//! # Node Structs
+//!
+//! `#[derive(RosNodeStruct)]` generates the wiring for a struct with handler
+//! methods:
+//!
+//! ```ignore
+//! use oxidros::prelude::*;
+//! use oxidros::msg::common_interfaces::std_msgs;
+//!
+//! #[derive(RosNodeStruct)]
+//! #[subscriber(topic = "chatter", msg = std_msgs::msg::String, handler = on_chatter)]
+//! #[timer(period = "1s", handler = on_tick)]
+//! struct Counter {
+//!     #[publisher(topic = "count")]
+//!     count_pub: Publisher<std_msgs::msg::Int64>,
+//!     #[parameter(default = 1i64, description = "Increment per message")]
+//!     step: i64,
+//!     count: i64,
+//! }
+//!
+//! impl Counter {
+//!     fn on_chatter(&mut self, _msg: Message<std_msgs::msg::String>) {
+//!         self.count += self.step;
+//!     }
+//!
+//!     fn on_tick(&mut self) {
+//!         let mut msg = std_msgs::msg::Int64::new().unwrap();
+//!         msg.data = self.count;
+//!         let _ = self.count_pub.publish(&msg);
+//!     }
+//! }
+//!
+//! let counter = Counter::register(&node, &mut selector)?;
+//! loop { selector.wait()?; }
+//! ```
+//!
+//! # Primitive Types
+//!
+//! Numbers, booleans and strings can be published without building a
+//! `std_msgs` message by hand:
+//!
+//! ```ignore
+//! use oxidros::prelude::*;
+//!
+//! let publisher = node.create_primitive_publisher::<f64>("temperature", None)?;
+//! publisher.send(&21.5)?;
+//! ```
+//!
+//! # Multiple Domains
+//!
+//! `Context::with_domain_id` creates a context on a given domain, and several
+//! of them can live in one process. A
+//! [`DomainBridge`](domain_bridge::DomainBridge) relays topics between them:
+//!
+//! ```ignore
+//! use oxidros::domain_bridge::{DomainBridge, TopicBridgeOptions};
+//! use oxidros::prelude::*;
+//!
+//! let robot = Context::with_domain_id(1)?;
+//! let fleet = Context::with_domain_id(2)?;
+//! let mut bridge = DomainBridge::new("domain_bridge", &robot, &fleet)?;
+//! bridge.bridge_topic::<MyMessage>("status", TopicBridgeOptions::default().max_rate(1.0))?;
+//! bridge.run().await?;
+//! ```
+//!
+//! # Relay, Throttle, Transform and Mux Nodes
+//!
+//! The [`nodes`] module provides generic equivalents of `topic_tools` and
+//! `twist_mux`, configured by topic names or by parameters:
+//!
+//! ```ignore
+//! use oxidros::nodes::TransformNode;
+//!
+//! let doubler = TransformNode::new(&node, &["input"], "output", |msg: &Int64| {
+//!     Some(Int64 { data: msg.data * 2 })
+//! })?;
+//! doubler.run().await?;
+//! ```
+//!
+//! # Runtime Reconfiguration
+//!
+//! A [`Reconfigurable`](reconfigure::Reconfigurable) rebuilds a publisher,
+//! a timer period or any other value when the parameters it depends on are
+//! set, and notifies the tasks using it:
+//!
+//! ```ignore
+//! use oxidros::reconfigure::Reconfigurable;
+//!
+//! let publisher = Reconfigurable::new(&params.read(), &["output_topic"], build_publisher)?;
+//! let updated = param_server.wait().await?;
+//! publisher.update(&params.read(), &updated)?;
+//! publisher.get().send(&msg)?;
+//! ```
+//!
+//! # Graceful Shutdown
+//!
+//! `ctx.shutdown_token()` returns a [`ShutdownToken`](shutdown::ShutdownToken)
+//! triggered by SIGINT and SIGTERM. Async loops wait on it next to their work,
+//! then flush their publishers so the last messages are delivered:
+//!
+//! ```ignore
+//! let shutdown = ctx.shutdown_token();
+//! loop {
+//!     tokio::select! {
+//!         _ = shutdown.wait() => break,
+//!         _ = interval.tick() => publisher.send(&state)?,
+//!     }
+//! }
+//! publisher.flush().await?;
+//! ```
+//!
+//! # Duplicate and Loss Detection
+//!
+//! Messages carry the sequence number given by their publisher. A
+//! [`DeduplicatingSubscriber`](dedup::DeduplicatingSubscriber) drops the
+//! duplicates and counts the lost messages, which helps over lossy links:
+//!
+//! ```ignore
+//! use oxidros::dedup::DeduplicatingSubscriber;
+//!
+//! let mut subscriber = DeduplicatingSubscriber::new(node.create_subscriber::<Int64>("counter", None)?);
+//! let msg = subscriber.recv().await?;
+//! println!("lost so far: {}", subscriber.stats().lost);
+//! ```
+//!
+//! # Keyed Topics
+//!
+//! Messages declare key fields with `#[ros2(key)]`, to tell apart the
+//! instances published on a topic, e.g. tracked objects. An
+//! [`InstanceSubscriber`](instance::InstanceSubscriber) keeps the last sample
+//! of every instance:
+//!
+//! ```ignore
+//! use oxidros::instance::InstanceSubscriber;
+//!
+//! let mut objects = InstanceSubscriber::new(node.create_subscriber::<TrackedObject>("objects", None)?);
+//! objects.recv().await?;
+//! let object = objects.take_instance(&key)?;
+//! ```
+//!
+//! # Message Introspection
+//!
+//! The [`introspection`] module renders any message as JSON, from a typed
+//! message or from CDR bytes and a runtime type description:
+//!
+//! ```ignore
+//! use oxidros::introspection;
+//!
+//! let msg = subscriber.recv().await?;
+//! println!("{}", introspection::to_json(&*msg)?);
+//! ```
+//!
+//! Other tools discover the types of a node through its
+//! `~/get_type_description` service, served on both backends (Jazzy or newer
+//! with RCL) until the returned server is dropped:
+//!
+//! ```ignore
+//! let _type_descriptions = node.create_type_description_server()?;
+//! ```
+//!
+//! # Recording
+//!
+//! With the `mcap` feature, the [`mcap`] module writes messages to MCAP
+//! files, readable by Foxglove Studio and `ros2 bag`, and replays them:
+//!
+//! ```ignore
+//! use oxidros::mcap::McapWriter;
+//!
+//! let mut writer = McapWriter::create("run.mcap")?;
+//! writer.write_message("/scan", &subscriber.recv().await?)?;
+//! writer.finish()?;
+//! ```
+//!
+//! # Bonds
+//!
+//! With the `bond` feature, the [`bond`] module detects crashed peers with
+//! heartbeats, and supervises lifecycle nodes:
+//!
+//! ```ignore
+//! use oxidros::bond::Bond;
+//!
+//! let bond = Bond::new(&node, "camera_driver")
+//!     .on_broken(|reason| tracing::warn!("camera_driver lost: {reason:?}"));
+//! tokio::spawn(bond.run());
+//! ```
+//!
+//! # Launching Nodes
+//!
+//! With the `launch` feature, the [`launch`] module starts a set of nodes,
+//! in-process or as child processes, with their parameters, remappings and
+//! startup dependencies:
+//!
+//! ```ignore
+//! use oxidros::launch::{Launch, LaunchNode, StartCondition};
+//!
+//! Launch::new(&ctx)
+//!     .node(LaunchNode::process("camera", "camera_driver").parameter("fps", 30i64))
+//!     .node(
+//!         LaunchNode::in_process("detector", run_detector)
+//!             .after(StartCondition::LifecycleActive("/camera".into())),
+//!     )
+//!     .run()
+//!     .await?;
+//! ```
+//!
+//! # Images
+//!
+//! With the `image` feature, [`msg::images`] converts `sensor_msgs/Image`
+//! messages to and from the `image` crate and compresses them for
+//! `sensor_msgs/CompressedImage`:
+//!
+//! ```ignore
+//! use oxidros::msg::images::{self, Compression};
+//!
+//! let frame = images::to_dynamic_image(&msg)?;
+//! let compressed = images::compress(&msg, Compression::Jpeg { quality: 90 })?;
+//! ```
+//!
+//! # Node Templates
+//!
+//! With the `kit` feature, the [`kit`] module provides a sensor driver
+//! template, publishing readings and diagnostics at a fixed rate, and a
+//! controller template with a watchdog on its input:
+//!
+//! ```ignore
+//! use oxidros::kit::{ControllerNode, SensorDriverNode};
+//!
+//! let driver = SensorDriverNode::new(&node, "range", 10.0, move || sensor.read())?;
+//! let controller = ControllerNode::new(&node, "range", "cmd_vel", 20.0, avoid)?
+//!     .watchdog(Duration::from_millis(500), || Some(Twist::new().unwrap()));
+//! tokio::try_join!(driver.run(), controller.run())?;
+//! ```
+//!
+//! # Node Statistics
+//!
+//! [`RosNode::stats`](crate::core::api::RosNode::stats) counts the entities
+//! of a node and, on Zenoh, the messages waiting in or dropped from its
+//! subscriber queues. With the `stats` feature, the `stats` module publishes
+//! them periodically on `~/_events`:
+//!
+//! ```ignore
+//! use oxidros::stats::StatsPublisher;
+//!
+//! println!("{:?}", node.stats());
+//! tokio::spawn(StatsPublisher::new(&node).run());
+//! ```
+//!
+//! # Slow Callbacks
+//!
+//! With the `watchdog` feature, [`spawn_ros_task`](crate::watchdog::spawn_ros_task)
+//! spawns a Tokio task which warns when one of its polls blocks for longer
+//! than a threshold:
+//!
+//! ```ignore
+//! use oxidros::watchdog::spawn_ros_task;
+//!
+//! spawn_ros_task("/scan", async move {
+//!     while let Ok(msg) = subscriber.recv().await {
+//!         process(msg);
+//!     }
+//! });
+//! ```
+//!
+//! # Thread Placement
+//!
+//! With the `executor` feature, [`Executor`](crate::executor::Executor)
+//! builds a multi-threaded Tokio runtime whose workers are pinned to CPU cores
+//! and run with a `SCHED_FIFO` priority where permitted. Selectors place the
+//! thread spinning them with
+//! [`RosSelector::set_thread_options`](oxidros_core::api::RosSelector::set_thread_options):
+//!
+//! ```ignore
+//! use oxidros::executor::Executor;
+//!
+//! let options = ThreadOptions::new().cpus([3]).fifo_priority(80);
+//! let runtime = Executor::new().thread_options(options.clone()).build()?;
+//!
+//! let mut selector = ctx.create_selector()?;
+//! selector.set_thread_options(&options);
+//! ```
+//!
+//! # Missing Interface Packages
+//!
+//! The RCL backend links the rosidl libraries of every message package, so a
+//! binary does not start without all of them installed. With the
+//! `lazy-typesupport` feature, they are loaded from `AMENT_PREFIX_PATH` when
+//! first used instead, and creating an entity of a missing interface fails with
+//! a clear error:
+//!
+//! ```ignore
+//! match node.create_publisher::<nav_msgs::msg::Odometry>("odom", None) {
+//!     // Type support error: package nav_msgs typesupport not found in AMENT_PREFIX_PATH (...)
+//!     Err(Error::TypeSupport(e)) => tracing::warn!("odometry disabled: {e}"),
+//!     result => publishers.push(result?),
+//! }
+//! ```
+//!
+//! # Web Clients
+//!
+//! With the `rosbridge` feature, the Zenoh backend serves the rosbridge v2
+//! protocol over WebSocket so that roslibjs or Foxglove can subscribe,
+//! publish and call services:
+//!
+//! ```ignore
+//! use oxidros::rosbridge::{DEFAULT_PORT, RosBridgeServer};
+//!
+//! let node = ctx.create_node("rosbridge", None)?;
+//! RosBridgeServer::new(node).serve(("0.0.0.0", DEFAULT_PORT)).await?;
+//! ```
+//!
+//! With the `foxglove` feature, Foxglove Studio can connect directly to the
+//! process and visualize every topic of the graph:
+//!
+//! ```ignore
+//! use oxidros::foxglove::{DEFAULT_PORT, FoxgloveServer};
+//!
+//! FoxgloveServer::new(node).serve(("0.0.0.0", DEFAULT_PORT)).await?;
+//! ```
+//!
+//! # Microcontrollers
+//!
+//! With the `agent` feature, `no_std` devices exchange CDR messages with a
+//! micro-agent over a serial link or UDP, and their topics are published and
+//! subscribed by the agent node:
+//!
+//! ```ignore
+//! use oxidros::agent::{DEFAULT_PORT, MicroAgent};
+//!
+//! let node = ctx.create_node("micro_agent", None)?;
+//! MicroAgent::new(node).serve_udp(("0.0.0.0", DEFAULT_PORT)).await?;
+//! ```
+//!
 //! # Logging
 //!
 //! Oxidros uses the `tracing` ecosystem for logging:
@@ -194,6 +530,7 @@
 //! - `oxidros-core` - Shared types and traits
 //! - `oxidros-msg` - ROS2 message type generation
 //! - `ros2-types` - CDR serialization and type traits
+//! - `oxidros-derive` - `RosNodeStruct` derive macro
 
 // Compile-time check: ensure not both backends are explicitly selected
 #[cfg(all(feature = "rcl", feature = "zenoh"))]
@@ -203,11 +540,39 @@ compile_error!("Features `rcl` and `zenoh` are mutually exclusive. Choose one ba
 pub mod prelude;
 
 // Explicit modules that re-export types uniformly regardless of backend
+#[cfg(feature = "bond")]
+pub mod bond;
 pub mod clock;
+pub mod dedup;
+pub mod domain_bridge;
+#[cfg(feature = "executor")]
+pub mod executor;
+pub mod instance;
+pub mod introspection;
+#[cfg(feature = "kit")]
+pub mod kit;
+#[cfg(feature = "launch")]
+pub mod launch;
 pub mod logger;
+#[cfg(feature = "mcap")]
+pub mod mcap;
+pub mod nodes;
 pub mod parameter;
+pub mod primitive;
+pub mod reconfigure;
 pub mod service;
+#[cfg(feature = "stats")]
+pub mod stats;
 pub mod topic;
+#[cfg(feature = "watchdog")]
+pub mod watchdog;
+
+#[cfg(feature = "agent")]
+pub use oxidros_zenoh::agent;
+#[cfg(feature = "foxglove")]
+pub use oxidros_zenoh::foxglove;
+#[cfg(feature = "rosbridge")]
+pub use oxidros_zenoh::rosbridge;
 
 // Re-export core types and traits (common to both backends)
 pub use oxidros_core as core;
@@ -216,6 +581,13 @@ pub use oxidros_core::error;
 // Re-export message types (common to both backends)
 pub use oxidros_msg as msg;
 
+// Re-export derive macros
+pub use oxidros_derive::RosNodeStruct;
+
 pub mod qos {
     pub use oxidros_core::qos::*;
 }
+
+pub mod shutdown {
+    pub use oxidros_core::shutdown::*;
+}
@@ -0,0 +1,471 @@
+//! Recording and replay of messages in MCAP files.
+//!
+//! An [`McapWriter`] stores CDR messages with the channel metadata expected
+//! by ROS2 tools: one channel per topic, the `ros2msg` definition of its type
+//! as schema, and its type hash in the channel metadata. Files can be opened
+//! with Foxglove Studio, `ros2 bag` (MCAP storage) or `oxidros bag play`.
+//!
+//! An [`McapReader`] lists the channels of a file and iterates its messages;
+//! a [`Player`] yields them at the recorded pace for replay.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::mcap::{McapReader, McapWriter};
+//! use oxidros::prelude::*;
+//!
+//! // Record
+//! let mut writer = McapWriter::create("scan.mcap")?;
+//! for _ in 0..100 {
+//!     let msg = subscriber.recv().await?;
+//!     writer.write_message("/scan", &msg)?;
+//! }
+//! writer.finish()?;
+//!
+//! // Replay
+//! let mut player = McapReader::open("scan.mcap")?.player(1.0)?;
+//! while let Some(recorded) = player.next().await {
+//!     publisher.send(&recorded?.decode::<LaserScan>()?)?;
+//! }
+//! ```
+
+use ::mcap::{
+    records::Record,
+    sans_io::{LinearReadEvent, LinearReader},
+};
+use oxidros_core::{
+    Error, Message, MessageInfo, Result, TypeDescription, TypeSupport, types::TypeDescriptionMsg,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufReader, BufWriter, Cursor, Read, Seek, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Channel metadata key holding the DDS type name (read by `oxidros bag play`).
+pub const DDS_TYPE_KEY: &str = "dds_type";
+/// Channel metadata key holding the RIHS01 type hash (read by `oxidros bag play`).
+pub const TYPE_HASH_KEY: &str = "type_hash";
+/// Channel metadata key holding the type hash in rosbag2 files.
+pub const TOPIC_TYPE_HASH_KEY: &str = "topic_type_hash";
+
+fn mcap_error(e: ::mcap::McapError) -> Error {
+    Error::Other(format!("MCAP error: {e}"))
+}
+
+fn now_ns() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+}
+
+/// Writer of MCAP files with the `ros2` profile.
+pub struct McapWriter<W: Write + Seek> {
+    writer: ::mcap::Writer<W>,
+    /// Channel ids by topic name.
+    channels: HashMap<String, u16>,
+    /// Schema ids by ROS2 type name.
+    schemas: HashMap<String, u16>,
+    sequence: u32,
+}
+
+impl McapWriter<BufWriter<File>> {
+    /// Create a file, compressed with zstd.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write + Seek> McapWriter<W> {
+    /// Write to `writer`, compressed with zstd.
+    pub fn new(writer: W) -> Result<Self> {
+        Self::with_options(writer, ::mcap::WriteOptions::new())
+    }
+
+    /// Write to `writer` with the given options (compression, chunk size, ...).
+    ///
+    /// The profile and library of the options are overwritten.
+    pub fn with_options(writer: W, options: ::mcap::WriteOptions) -> Result<Self> {
+        let writer = options
+            .profile("ros2".to_string())
+            .library(format!("oxidros {}", env!("CARGO_PKG_VERSION")))
+            .create(writer)
+            .map_err(mcap_error)?;
+        Ok(McapWriter {
+            writer,
+            channels: HashMap::new(),
+            schemas: HashMap::new(),
+            sequence: 0,
+        })
+    }
+
+    /// Add the channel of a topic, or get its id if it already exists.
+    pub fn add_channel<T: TypeSupport + TypeDescription>(&mut self, topic: &str) -> Result<u16> {
+        if let Some(id) = self.channels.get(topic) {
+            return Ok(*id);
+        }
+        self.add_channel_with_description(topic, &T::type_description())
+    }
+
+    /// Add the channel of a topic whose type is only known at runtime, or
+    /// get its id if it already exists.
+    pub fn add_channel_with_description(
+        &mut self,
+        topic: &str,
+        type_description: &TypeDescriptionMsg,
+    ) -> Result<u16> {
+        if let Some(id) = self.channels.get(topic) {
+            return Ok(*id);
+        }
+        let type_name = &type_description.type_description.type_name;
+        let schema_id = match self.schemas.get(type_name) {
+            Some(id) => *id,
+            None => {
+                let definition = type_description.to_msg_definition();
+                let id = self
+                    .writer
+                    .add_schema(type_name, "ros2msg", definition.as_bytes())
+                    .map_err(mcap_error)?;
+                self.schemas.insert(type_name.clone(), id);
+                id
+            }
+        };
+
+        let type_hash = oxidros_core::calculate_type_hash(type_description)?;
+        let mut metadata = BTreeMap::new();
+        if let Some(dds_type) = dds_type_name(type_name) {
+            metadata.insert(DDS_TYPE_KEY.to_string(), dds_type);
+        }
+        metadata.insert(TYPE_HASH_KEY.to_string(), type_hash.clone());
+        metadata.insert(TOPIC_TYPE_HASH_KEY.to_string(), type_hash);
+
+        let id = self
+            .writer
+            .add_channel(schema_id, topic, "cdr", &metadata)
+            .map_err(mcap_error)?;
+        self.channels.insert(topic.to_string(), id);
+        Ok(id)
+    }
+
+    /// Write a message, stamped with the current time.
+    pub fn write<T: TypeSupport + TypeDescription>(&mut self, topic: &str, msg: &T) -> Result<()> {
+        let channel_id = self.add_channel::<T>(topic)?;
+        let now = now_ns();
+        self.write_raw(channel_id, &msg.to_bytes()?, now, now)
+    }
+
+    /// Write a received message.
+    ///
+    /// The publish time is the source timestamp of the message, the log time
+    /// is the current time.
+    pub fn write_message<T: TypeSupport + TypeDescription>(
+        &mut self,
+        topic: &str,
+        msg: &Message<T>,
+    ) -> Result<()> {
+        let channel_id = self.add_channel::<T>(topic)?;
+        let (log_time, publish_time) = timestamps(&msg.info);
+        self.write_raw(channel_id, &msg.to_bytes()?, log_time, publish_time)
+    }
+
+    /// Write CDR bytes received with `recv_raw` on a channel.
+    pub fn write_received(
+        &mut self,
+        channel_id: u16,
+        cdr: &[u8],
+        info: &MessageInfo,
+    ) -> Result<()> {
+        let (log_time, publish_time) = timestamps(info);
+        self.write_raw(channel_id, cdr, log_time, publish_time)
+    }
+
+    /// Write CDR bytes (including the encapsulation header) on a channel.
+    ///
+    /// Times are in nanoseconds since the UNIX epoch.
+    pub fn write_raw(
+        &mut self,
+        channel_id: u16,
+        cdr: &[u8],
+        log_time: u64,
+        publish_time: u64,
+    ) -> Result<()> {
+        let header = ::mcap::records::MessageHeader {
+            channel_id,
+            sequence: self.sequence,
+            log_time,
+            publish_time,
+        };
+        self.writer
+            .write_to_known_channel(&header, cdr)
+            .map_err(mcap_error)?;
+        self.sequence = self.sequence.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Write the summary and flush the file.
+    pub fn finish(mut self) -> Result<()> {
+        self.writer.finish().map_err(mcap_error)?;
+        Ok(())
+    }
+}
+
+/// Log and publish times of a received message.
+fn timestamps(info: &MessageInfo) -> (u64, u64) {
+    let log_time = now_ns();
+    let publish_time = u64::try_from(info.source_timestamp_ns)
+        .ok()
+        .filter(|t| *t > 0)
+        .unwrap_or(log_time);
+    (log_time, publish_time)
+}
+
+/// `"pkg/msg/Name"` → `"pkg::msg::dds_::Name_"`
+fn dds_type_name(type_name: &str) -> Option<String> {
+    let mut parts = type_name.split('/');
+    let (pkg, iface, name) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(format!("{pkg}::{iface}::dds_::{name}_"))
+}
+
+/// Channel of an MCAP file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelInfo {
+    /// Channel id.
+    pub id: u16,
+    /// Topic name.
+    pub topic: String,
+    /// ROS2 type name, empty if the channel has no schema.
+    pub type_name: String,
+    /// RIHS01 type hash, if recorded.
+    pub type_hash: Option<String>,
+    /// Message definition (`ros2msg` schema).
+    pub message_definition: String,
+}
+
+impl ChannelInfo {
+    fn new(channel: ::mcap::records::Channel, schema: Option<&Schema>) -> Self {
+        let (type_name, message_definition) = match schema {
+            Some(schema) => (
+                schema.name.clone(),
+                String::from_utf8_lossy(&schema.data).into_owned(),
+            ),
+            None => Default::default(),
+        };
+        let type_hash = channel
+            .metadata
+            .get(TYPE_HASH_KEY)
+            .or_else(|| channel.metadata.get(TOPIC_TYPE_HASH_KEY))
+            .cloned();
+        ChannelInfo {
+            id: channel.id,
+            topic: channel.topic,
+            type_name,
+            type_hash,
+            message_definition,
+        }
+    }
+}
+
+/// Message read from an MCAP file.
+#[derive(Debug, Clone)]
+pub struct RecordedMessage {
+    /// Topic name.
+    pub topic: String,
+    /// Channel id.
+    pub channel_id: u16,
+    /// Time the message was recorded, in nanoseconds since the UNIX epoch.
+    pub log_time: u64,
+    /// Time the message was published, in nanoseconds since the UNIX epoch.
+    pub publish_time: u64,
+    /// CDR bytes, including the encapsulation header.
+    pub data: Vec<u8>,
+}
+
+impl RecordedMessage {
+    /// Decode the message.
+    pub fn decode<T: TypeSupport>(&self) -> Result<T> {
+        Ok(T::from_bytes(&self.data)?)
+    }
+}
+
+/// Schema record of an MCAP file.
+struct Schema {
+    name: String,
+    data: Vec<u8>,
+}
+
+/// Content of an MCAP file.
+enum Source {
+    File(PathBuf),
+    Bytes(Arc<[u8]>),
+}
+
+/// Reader of MCAP files.
+///
+/// Files are streamed chunk by chunk, so memory does not grow with their
+/// size. Each call to [`channels`](Self::channels), [`messages`](Self::messages)
+/// or [`player`](Self::player) reads the file again from the start.
+pub struct McapReader {
+    source: Source,
+}
+
+impl McapReader {
+    /// Read a file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        // Fail now if the file cannot be read
+        File::open(&path)?;
+        Ok(McapReader {
+            source: Source::File(path),
+        })
+    }
+
+    /// Read the content of a file.
+    pub fn from_bytes(data: Vec<u8>) -> Self {
+        McapReader {
+            source: Source::Bytes(data.into()),
+        }
+    }
+
+    fn records(&self) -> Result<Records> {
+        let input: Box<dyn Read + Send> = match &self.source {
+            Source::File(path) => Box::new(BufReader::new(File::open(path)?)),
+            Source::Bytes(data) => Box::new(Cursor::new(data.clone())),
+        };
+        Ok(Records {
+            input,
+            reader: LinearReader::new(),
+            schemas: HashMap::new(),
+            channels: HashMap::new(),
+        })
+    }
+
+    /// Channels of the file, sorted by id.
+    pub fn channels(&self) -> Result<Vec<ChannelInfo>> {
+        let mut records = self.records()?;
+        while records.next_message()?.is_some() {}
+        let mut channels: Vec<_> = records.channels.into_values().collect();
+        channels.sort_by_key(|channel| channel.id);
+        Ok(channels)
+    }
+
+    /// Iterate over the messages in file order.
+    pub fn messages(&self) -> Result<impl Iterator<Item = Result<RecordedMessage>> + use<>> {
+        let mut records = self.records()?;
+        Ok(std::iter::from_fn(move || {
+            records.next_message().transpose()
+        }))
+    }
+
+    /// Replay the messages, in file order, at `rate` times the recorded pace.
+    pub fn player(&self, rate: f64) -> Result<Player> {
+        if !(rate > 0.0 && rate.is_finite()) {
+            return Err(Error::InvalidConfig(format!(
+                "Replay rate must be positive, got {rate}"
+            )));
+        }
+        Ok(Player {
+            records: self.records()?,
+            pending: None,
+            rate,
+            start: None,
+        })
+    }
+}
+
+/// Records of an MCAP file, read with decompressed chunks.
+struct Records {
+    input: Box<dyn Read + Send>,
+    reader: LinearReader,
+    /// Schemas by id.
+    schemas: HashMap<u16, Schema>,
+    /// Channels by id.
+    channels: HashMap<u16, ChannelInfo>,
+}
+
+impl Records {
+    /// Read up to the next message, recording the schemas and channels on the way.
+    fn next_message(&mut self) -> Result<Option<RecordedMessage>> {
+        while let Some(event) = self.reader.next_event() {
+            match event.map_err(mcap_error)? {
+                LinearReadEvent::ReadRequest(need) => {
+                    let read = self.input.read(self.reader.insert(need))?;
+                    self.reader.notify_read(read);
+                }
+                LinearReadEvent::Record { opcode, data } => {
+                    match ::mcap::parse_record(opcode, data).map_err(mcap_error)? {
+                        Record::Schema { header, data } => {
+                            self.schemas.insert(
+                                header.id,
+                                Schema {
+                                    name: header.name,
+                                    data: data.into_owned(),
+                                },
+                            );
+                        }
+                        Record::Channel(channel) => {
+                            let schema = self.schemas.get(&channel.schema_id);
+                            self.channels
+                                .entry(channel.id)
+                                .or_insert_with(|| ChannelInfo::new(channel, schema));
+                        }
+                        Record::Message { header, data } => {
+                            let channel =
+                                self.channels.get(&header.channel_id).ok_or_else(|| {
+                                    Error::Other(format!(
+                                        "MCAP error: message on unknown channel {}",
+                                        header.channel_id
+                                    ))
+                                })?;
+                            return Ok(Some(RecordedMessage {
+                                topic: channel.topic.clone(),
+                                channel_id: header.channel_id,
+                                log_time: header.log_time,
+                                publish_time: header.publish_time,
+                                data: data.into_owned(),
+                            }));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Replay of recorded messages at their recorded pace.
+pub struct Player {
+    records: Records,
+    /// Next message, read but not yet due.
+    pending: Option<RecordedMessage>,
+    rate: f64,
+    /// Wall clock and log time of the first message.
+    start: Option<(Instant, u64)>,
+}
+
+impl Player {
+    /// Wait until the next message is due and return it.
+    ///
+    /// Returns `None` when all messages have been replayed. Cancelling the
+    /// wait, e.g. in a `select!`, does not lose the message.
+    pub async fn next(&mut self) -> Option<Result<RecordedMessage>> {
+        if self.pending.is_none() {
+            match self.records.next_message() {
+                Ok(msg) => self.pending = msg,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        let log_time = self.pending.as_ref()?.log_time;
+        let (start, first_log_time) = *self.start.get_or_insert((Instant::now(), log_time));
+        let offset = log_time.saturating_sub(first_log_time) as f64 / self.rate;
+        let due = start + Duration::from_nanos(offset as u64);
+        tokio::time::sleep_until(due.into()).await;
+        self.pending.take().map(Ok)
+    }
+}
@@ -0,0 +1,638 @@
+//! Generic relay, throttle, transform and mux nodes.
+//!
+//! Equivalents of the `topic_tools` nodes that applications often
+//! reimplement:
+//!
+//! | Node | Behavior | Parameters |
+//! |------|----------|------------|
+//! | [`Relay`] | Republishes `input_topic` on `output_topic` | `input_topic`, `output_topic` |
+//! | [`Throttle`] | Same, at most `rate` messages per second | `input_topic`, `output_topic`, `rate` |
+//! | [`TransformNode`] | Publishes the results of a closure applied to the messages of `input_topics` on `output_topic` | `input_topics` (or `input_topic`), `output_topic` |
+//! | [`Mux`] | Republishes the highest priority active input of `input_topics` on `output_topic`, like `twist_mux` | `input_topics`, `priorities`, `timeouts` (seconds), `output_topic`, `lock_priority` (optional) |
+//!
+//! Each node is built either from explicit topic names or from the
+//! parameters of a parameter server, e.g. set with
+//! `--ros-args -p input_topic:=/chatter`. `Relay`, `Throttle` and `Mux`
+//! forward raw CDR bytes without deserializing the messages.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::msg::common_interfaces::std_msgs::msg::{Float64, String as StringMsg};
+//! use oxidros::nodes::{Throttle, TransformNode};
+//! use oxidros::prelude::*;
+//!
+//! let ctx = Context::new()?;
+//! let node = ctx.create_node("temperature_tools", None)?;
+//!
+//! // Configured from the command line
+//! let params = node.create_parameter_server()?;
+//! let throttle = Throttle::<_, Float64>::from_parameters(&node, &params.params.read())?;
+//!
+//! // Chained closures: keep high temperatures and format them
+//! let alerts = TransformNode::new(&node, &["temperature"], "alerts", |msg: &Float64| {
+//!     Some(msg.data)
+//! })?
+//! .filter(|celsius| *celsius > 80.0)
+//! .map(|celsius| {
+//!     let mut msg = StringMsg::new().unwrap();
+//!     msg.data.set_string(&format!("overheat: {celsius}"));
+//!     msg
+//! });
+//!
+//! tokio::try_join!(throttle.run(), alerts.run())?;
+//! ```
+
+use futures_util::{StreamExt, future::try_join, stream::select_all};
+use oxidros_core::{
+    Error, Profile, Result, TypeDescription, TypeSupport,
+    api::{RosNode, RosPublisher, RosServer, RosSubscriber},
+    parameter::{Parameters, Value},
+    targets,
+};
+use oxidros_msg::common_interfaces::std_srvs::srv::{SetBool, SetBool_Response};
+use std::{
+    marker::PhantomData,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// Republishes the messages of one topic on another.
+pub struct Relay<N, T> {
+    node: Arc<N>,
+    input_topic: String,
+    output_topic: String,
+    qos: Option<Profile>,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<N, T> Relay<N, T>
+where
+    N: RosNode,
+    T: TypeSupport + TypeDescription + 'static,
+    N::Subscriber<T>: RosSubscriber<T>,
+{
+    /// Create a relay from `input_topic` to `output_topic`.
+    pub fn new(node: &Arc<N>, input_topic: &str, output_topic: &str) -> Self {
+        Relay {
+            node: node.clone(),
+            input_topic: input_topic.to_string(),
+            output_topic: output_topic.to_string(),
+            qos: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Create a relay configured by the `input_topic` and `output_topic`
+    /// parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if a parameter is missing or is not a
+    /// string.
+    pub fn from_parameters(node: &Arc<N>, params: &Parameters) -> Result<Self> {
+        Ok(Self::new(
+            node,
+            &string_parameter(params, "input_topic")?,
+            &string_parameter(params, "output_topic")?,
+        ))
+    }
+
+    /// QoS of the subscriber and of the publisher.
+    pub fn qos(mut self, qos: Profile) -> Self {
+        self.qos = Some(qos);
+        self
+    }
+
+    /// Relay messages until an error occurs.
+    pub async fn run(self) -> Result<()> {
+        self.forward(None).await
+    }
+
+    async fn forward(self, min_interval: Option<Duration>) -> Result<()> {
+        let subscriber = self
+            .node
+            .create_subscriber::<T>(&self.input_topic, self.qos.clone())?;
+        let publisher = self
+            .node
+            .create_publisher::<T>(&self.output_topic, self.qos)?;
+        forward_raw::<T, _, _>(subscriber, publisher, min_interval).await
+    }
+}
+
+/// Republishes the messages of one topic on another at a limited rate.
+///
+/// Messages received less than `1 / rate` seconds after the last relayed one
+/// are dropped.
+pub struct Throttle<N, T> {
+    relay: Relay<N, T>,
+    min_interval: Duration,
+}
+
+impl<N, T> Throttle<N, T>
+where
+    N: RosNode,
+    T: TypeSupport + TypeDescription + 'static,
+    N::Subscriber<T>: RosSubscriber<T>,
+{
+    /// Create a throttle from `input_topic` to `output_topic` relaying at most
+    /// `rate` messages per second.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if `rate` is not positive.
+    pub fn new(node: &Arc<N>, input_topic: &str, output_topic: &str, rate: f64) -> Result<Self> {
+        if !(rate > 0.0 && rate.is_finite()) {
+            return Err(Error::InvalidConfig(format!(
+                "throttle rate must be positive, got {rate}"
+            )));
+        }
+        Ok(Throttle {
+            relay: Relay::new(node, input_topic, output_topic),
+            min_interval: Duration::from_secs_f64(1.0 / rate),
+        })
+    }
+
+    /// Create a throttle configured by the `input_topic`, `output_topic` and
+    /// `rate` parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if a parameter is missing or has the
+    /// wrong type, or if `rate` is not positive.
+    pub fn from_parameters(node: &Arc<N>, params: &Parameters) -> Result<Self> {
+        let rate = number_parameter(params, "rate")?
+            .ok_or_else(|| Error::InvalidConfig("parameter rate is not set".into()))?;
+        Self::new(
+            node,
+            &string_parameter(params, "input_topic")?,
+            &string_parameter(params, "output_topic")?,
+            rate,
+        )
+    }
+
+    /// QoS of the subscriber and of the publisher.
+    pub fn qos(mut self, qos: Profile) -> Self {
+        self.relay = self.relay.qos(qos);
+        self
+    }
+
+    /// Relay messages until an error occurs.
+    pub async fn run(self) -> Result<()> {
+        self.relay.forward(Some(self.min_interval)).await
+    }
+}
+
+type Transform<I, O> = Box<dyn FnMut(&I) -> Option<O> + Send>;
+
+/// Publishes the results of a closure applied to the messages of one or more
+/// topics.
+///
+/// The closure returns `None` to drop a message. More steps are chained with
+/// [`TransformNode::map`], [`TransformNode::filter`] and
+/// [`TransformNode::filter_map`]; only the type of the last step must be a
+/// message.
+pub struct TransformNode<N, I, O> {
+    node: Arc<N>,
+    input_topics: Vec<String>,
+    output_topic: String,
+    qos: Option<Profile>,
+    transform: Transform<I, O>,
+}
+
+impl<N: RosNode, I: 'static, O: 'static> TransformNode<N, I, O> {
+    /// Create a node applying `transform` to the messages of `input_topics`
+    /// and publishing the results on `output_topic`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if `input_topics` is empty.
+    pub fn new<F>(
+        node: &Arc<N>,
+        input_topics: &[&str],
+        output_topic: &str,
+        transform: F,
+    ) -> Result<Self>
+    where
+        F: FnMut(&I) -> Option<O> + Send + 'static,
+    {
+        if input_topics.is_empty() {
+            return Err(Error::InvalidConfig(
+                "transform node needs at least one input topic".into(),
+            ));
+        }
+        Ok(TransformNode {
+            node: node.clone(),
+            input_topics: input_topics.iter().map(|topic| topic.to_string()).collect(),
+            output_topic: output_topic.to_string(),
+            qos: None,
+            transform: Box::new(transform),
+        })
+    }
+
+    /// Create a node configured by the `input_topics` (or `input_topic`) and
+    /// `output_topic` parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if a parameter is missing or has the
+    /// wrong type.
+    pub fn from_parameters<F>(node: &Arc<N>, params: &Parameters, transform: F) -> Result<Self>
+    where
+        F: FnMut(&I) -> Option<O> + Send + 'static,
+    {
+        let input_topics = match params
+            .get_parameter("input_topics")
+            .map(|param| &param.value)
+        {
+            Some(Value::VecString(topics)) => topics.clone(),
+            Some(value) => {
+                return Err(Error::InvalidConfig(format!(
+                    "parameter input_topics must be a string array, got {}",
+                    value.type_name()
+                )));
+            }
+            None => vec![string_parameter(params, "input_topic")?],
+        };
+        let input_topics: Vec<&str> = input_topics.iter().map(String::as_str).collect();
+        Self::new(
+            node,
+            &input_topics,
+            &string_parameter(params, "output_topic")?,
+            transform,
+        )
+    }
+
+    /// QoS of the subscribers and of the publisher.
+    pub fn qos(mut self, qos: Profile) -> Self {
+        self.qos = Some(qos);
+        self
+    }
+
+    /// Apply `f` to the results.
+    pub fn map<P: 'static, F>(self, mut f: F) -> TransformNode<N, I, P>
+    where
+        F: FnMut(O) -> P + Send + 'static,
+    {
+        self.then(move |output| Some(f(output)))
+    }
+
+    /// Drop the results for which `f` returns `false`.
+    pub fn filter<F>(self, mut f: F) -> Self
+    where
+        F: FnMut(&O) -> bool + Send + 'static,
+    {
+        self.then(move |output| f(&output).then_some(output))
+    }
+
+    /// Apply `f` to the results, dropping those for which it returns `None`.
+    pub fn filter_map<P: 'static, F>(self, f: F) -> TransformNode<N, I, P>
+    where
+        F: FnMut(O) -> Option<P> + Send + 'static,
+    {
+        self.then(f)
+    }
+
+    fn then<P: 'static, F>(self, mut f: F) -> TransformNode<N, I, P>
+    where
+        F: FnMut(O) -> Option<P> + Send + 'static,
+    {
+        let mut transform = self.transform;
+        TransformNode {
+            node: self.node,
+            input_topics: self.input_topics,
+            output_topic: self.output_topic,
+            qos: self.qos,
+            transform: Box::new(move |input| transform(input).and_then(&mut f)),
+        }
+    }
+}
+
+impl<N, I, O> TransformNode<N, I, O>
+where
+    N: RosNode,
+    I: TypeSupport + TypeDescription + 'static,
+    O: TypeSupport + TypeDescription + 'static,
+    N::Subscriber<I>: RosSubscriber<I> + 'static,
+{
+    /// Transform messages until an error occurs.
+    pub async fn run(mut self) -> Result<()> {
+        let mut inputs = Vec::with_capacity(self.input_topics.len());
+        for topic in &self.input_topics {
+            let subscriber = self.node.create_subscriber::<I>(topic, self.qos.clone())?;
+            inputs.push(subscriber.into_stream());
+        }
+        let publisher = self
+            .node
+            .create_publisher::<O>(&self.output_topic, self.qos)?;
+
+        let mut messages = select_all(inputs);
+        while let Some(msg) = messages.next().await {
+            let msg = msg?;
+            if let Some(output) = (self.transform)(&msg) {
+                publisher.send(&output)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Default name of the lock service of a [`Mux`].
+pub const DEFAULT_LOCK_SERVICE: &str = "~/lock";
+
+/// Input topic of a [`Mux`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MuxInput {
+    /// Topic name.
+    pub topic: String,
+    /// Priority, the highest active input is forwarded.
+    pub priority: u8,
+    /// Time after its last message when the input stops being active.
+    pub timeout: Duration,
+}
+
+/// Republishes the messages of the highest priority active input.
+///
+/// An input is active until `timeout` after its last message, so that e.g. a
+/// joystick overrides the `cmd_vel` of a planner while it is used, and the
+/// planner takes over again when the joystick is released. Inputs of the same
+/// priority are all forwarded.
+///
+/// The mux is locked and unlocked with a `std_srvs/SetBool` service,
+/// [`DEFAULT_LOCK_SERVICE`] by default: while locked, only the inputs with a
+/// priority above the lock priority are forwarded, none by default.
+///
+/// # Example
+///
+/// ```ignore
+/// use oxidros::nodes::Mux;
+/// use std::time::Duration;
+///
+/// let mux = Mux::<_, Twist>::new(&node, "cmd_vel")
+///     .input("cmd_vel/nav", 10, Duration::from_millis(500))
+///     .input("cmd_vel/joy", 100, Duration::from_millis(500))
+///     // The lock stops the planner but not the joystick
+///     .lock_priority(50);
+/// mux.run().await?;
+/// ```
+pub struct Mux<N, T> {
+    node: Arc<N>,
+    inputs: Vec<MuxInput>,
+    output_topic: String,
+    qos: Option<Profile>,
+    lock_service: String,
+    lock_priority: u8,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<N, T> Mux<N, T>
+where
+    N: RosNode,
+    T: TypeSupport + TypeDescription + 'static,
+    N::Subscriber<T>: RosSubscriber<T> + 'static,
+{
+    /// Create a mux publishing on `output_topic`, without inputs.
+    pub fn new(node: &Arc<N>, output_topic: &str) -> Self {
+        Mux {
+            node: node.clone(),
+            inputs: Vec::new(),
+            output_topic: output_topic.to_string(),
+            qos: None,
+            lock_service: DEFAULT_LOCK_SERVICE.to_string(),
+            lock_priority: u8::MAX,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Create a mux configured by the `input_topics`, `priorities`,
+    /// `timeouts` (seconds) and `output_topic` parameters, and the optional
+    /// `lock_priority` parameter.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if a parameter is missing or has the
+    /// wrong type, if the arrays do not have the same length, or if a
+    /// priority or a timeout is out of range.
+    pub fn from_parameters(node: &Arc<N>, params: &Parameters) -> Result<Self> {
+        let topics = match params.get_parameter("input_topics").map(|p| &p.value) {
+            Some(Value::VecString(topics)) => topics.clone(),
+            _ => return Err(array_parameter_error(params, "input_topics", "string")),
+        };
+        let priorities = match params.get_parameter("priorities").map(|p| &p.value) {
+            Some(Value::VecI64(priorities)) => priorities.clone(),
+            _ => return Err(array_parameter_error(params, "priorities", "integer")),
+        };
+        let timeouts = match params.get_parameter("timeouts").map(|p| &p.value) {
+            Some(Value::VecF64(timeouts)) => timeouts.clone(),
+            _ => return Err(array_parameter_error(params, "timeouts", "double")),
+        };
+        if priorities.len() != topics.len() || timeouts.len() != topics.len() {
+            return Err(Error::InvalidConfig(
+                "parameters input_topics, priorities and timeouts must have the same length".into(),
+            ));
+        }
+
+        let mut mux = Self::new(node, &string_parameter(params, "output_topic")?);
+        for ((topic, priority), timeout) in topics.iter().zip(priorities).zip(timeouts) {
+            let priority = u8::try_from(priority).map_err(|_| {
+                Error::InvalidConfig(format!("mux priority must be in 0..=255, got {priority}"))
+            })?;
+            let timeout = Duration::try_from_secs_f64(timeout).map_err(|_| {
+                Error::InvalidConfig(format!("mux timeout must be positive, got {timeout}"))
+            })?;
+            mux = mux.input(topic, priority, timeout);
+        }
+        if let Some(priority) = number_parameter(params, "lock_priority")? {
+            let priority = u8::try_from(priority as i64).map_err(|_| {
+                Error::InvalidConfig(format!(
+                    "mux lock priority must be in 0..=255, got {priority}"
+                ))
+            })?;
+            mux = mux.lock_priority(priority);
+        }
+        Ok(mux)
+    }
+
+    /// Add an input topic.
+    pub fn input(mut self, topic: &str, priority: u8, timeout: Duration) -> Self {
+        self.inputs.push(MuxInput {
+            topic: topic.to_string(),
+            priority,
+            timeout,
+        });
+        self
+    }
+
+    /// QoS of the subscribers and of the publisher.
+    pub fn qos(mut self, qos: Profile) -> Self {
+        self.qos = Some(qos);
+        self
+    }
+
+    /// Name of the lock service, [`DEFAULT_LOCK_SERVICE`] by default.
+    pub fn lock_service(mut self, name: &str) -> Self {
+        self.lock_service = name.to_string();
+        self
+    }
+
+    /// Priority up to which the inputs are blocked while the mux is locked,
+    /// all of them by default.
+    pub fn lock_priority(mut self, priority: u8) -> Self {
+        self.lock_priority = priority;
+        self
+    }
+
+    /// Forward messages and serve the lock service until an error occurs.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if the mux has no input, or the error
+    /// of the subscribers, of the publisher or of the lock service.
+    pub async fn run(self) -> Result<()> {
+        if self.inputs.is_empty() {
+            return Err(Error::InvalidConfig("mux needs at least one input".into()));
+        }
+
+        let mut streams = Vec::with_capacity(self.inputs.len());
+        for (index, input) in self.inputs.iter().enumerate() {
+            let subscriber = self
+                .node
+                .create_subscriber::<T>(&input.topic, self.qos.clone())?;
+            let stream = futures_util::stream::unfold(subscriber, |mut subscriber| async move {
+                let received = subscriber.recv_raw().await;
+                Some((received, subscriber))
+            })
+            .map(move |received| (index, received));
+            streams.push(stream.boxed());
+        }
+        let publisher = self
+            .node
+            .create_publisher::<T>(&self.output_topic, self.qos)?;
+        let server = self
+            .node
+            .create_server::<SetBool>(&self.lock_service, None)?;
+
+        let locked = Arc::new(AtomicBool::new(false));
+        let serve = server.serve({
+            let locked = locked.clone();
+            move |request| {
+                locked.store(request.data, Ordering::Relaxed);
+                tracing::info!(target: targets::ROOT, locked = request.data, "Mux lock changed");
+                SetBool_Response {
+                    success: true,
+                    ..Default::default()
+                }
+            }
+        });
+
+        let mut state = MuxState::new(self.inputs, self.lock_priority);
+        let forward = async move {
+            let mut messages = select_all(streams);
+            while let Some((index, received)) = messages.next().await {
+                let (data, _info) = received?;
+                if state.accept(index, Instant::now(), locked.load(Ordering::Relaxed)) {
+                    publisher.send_raw(&data)?;
+                }
+            }
+            Ok::<_, Error>(())
+        };
+        try_join(serve, forward).await.map(|((), ())| ())
+    }
+}
+
+/// Activity of the inputs of a [`Mux`].
+struct MuxState {
+    inputs: Vec<MuxInput>,
+    lock_priority: u8,
+    /// Reception time of the last message of each input.
+    last: Vec<Option<Instant>>,
+}
+
+impl MuxState {
+    fn new(inputs: Vec<MuxInput>, lock_priority: u8) -> Self {
+        let last = vec![None; inputs.len()];
+        MuxState {
+            inputs,
+            lock_priority,
+            last,
+        }
+    }
+
+    /// Record a message of input `index` and return whether it is forwarded.
+    fn accept(&mut self, index: usize, now: Instant, locked: bool) -> bool {
+        self.last[index] = Some(now);
+        let allowed = |priority: u8| !locked || priority > self.lock_priority;
+        let priority = self.inputs[index].priority;
+        if !allowed(priority) {
+            return false;
+        }
+        // Dropped while an allowed input of higher priority is active
+        !self.inputs.iter().zip(&self.last).any(|(input, last)| {
+            input.priority > priority
+                && allowed(input.priority)
+                && last.is_some_and(|last| now.duration_since(last) < input.timeout)
+        })
+    }
+}
+
+fn array_parameter_error(params: &Parameters, name: &str, element: &str) -> Error {
+    match params.get_parameter(name) {
+        Some(param) => Error::InvalidConfig(format!(
+            "parameter {name} must be a {element} array, got {}",
+            param.value.type_name()
+        )),
+        None => Error::InvalidConfig(format!("parameter {name} is not set")),
+    }
+}
+
+/// Forward raw messages from `subscriber` to `publisher`, dropping those
+/// received less than `min_interval` after the last forwarded one.
+pub(crate) async fn forward_raw<T, S, P>(
+    mut subscriber: S,
+    publisher: P,
+    min_interval: Option<Duration>,
+) -> Result<()>
+where
+    T: TypeSupport,
+    S: RosSubscriber<T>,
+    P: RosPublisher<T>,
+{
+    let mut last: Option<Instant> = None;
+    loop {
+        let (data, _info) = subscriber.recv_raw().await?;
+        if let (Some(interval), Some(last)) = (min_interval, last)
+            && last.elapsed() < interval
+        {
+            continue;
+        }
+        publisher.send_raw(&data)?;
+        last = Some(Instant::now());
+    }
+}
+
+pub(crate) fn string_parameter(params: &Parameters, name: &str) -> Result<String> {
+    match params.get_parameter(name).map(|param| &param.value) {
+        Some(Value::String(value)) => Ok(value.clone()),
+        Some(value) => Err(Error::InvalidConfig(format!(
+            "parameter {name} must be a string, got {}",
+            value.type_name()
+        ))),
+        None => Err(Error::InvalidConfig(format!("parameter {name} is not set"))),
+    }
+}
+
+/// Value of an integer or double parameter, `None` if it is not set.
+pub(crate) fn number_parameter(params: &Parameters, name: &str) -> Result<Option<f64>> {
+    match params.get_parameter(name).map(|param| &param.value) {
+        Some(Value::F64(value)) => Ok(Some(*value)),
+        Some(Value::I64(value)) => Ok(Some(*value as f64)),
+        Some(value) => Err(Error::InvalidConfig(format!(
+            "parameter {name} must be a number, got {}",
+            value.type_name()
+        ))),
+        None => Ok(None),
+    }
+}
@@ -1,7 +1,104 @@
 //! Parameter types.
+//!
+//! Groups of parameters declared with
+//! [`Parameters::declare_group`] can be read as a struct with
+//! [`ParametersExt`]:
+//!
+//! ```ignore
+//! use oxidros::parameter::ParametersExt;
+//!
+//! #[derive(serde::Deserialize)]
+//! struct ControllerCfg {
+//!     kp: f64,
+//!     ki: f64,
+//! }
+//!
+//! let param_server = node.create_parameter_server()?;
+//! param_server.params.write().declare_group("controller", |g| {
+//!     g.declare("kp", 1.0);
+//!     g.declare("ki", 0.0);
+//! })?;
+//!
+//! // Rebuilt when `controller.kp` or `controller.ki` is set
+//! let cfg = param_server.params.read().load_struct::<ControllerCfg>("controller")?;
+//! ```
 
 #[cfg(feature = "rcl")]
 pub use oxidros_wrapper::ParameterServer;
 
 #[cfg(feature = "zenoh")]
 pub use oxidros_zenoh::parameter::ParameterServer;
+
+pub use oxidros_core::parameter::{ParameterGroup, ParameterType, Parameters, Value};
+
+use crate::reconfigure::Reconfigurable;
+use oxidros_core::Result;
+use serde::de::DeserializeOwned;
+use serde_json::Map;
+
+/// Typed access to the groups of [`Parameters`].
+pub trait ParametersExt {
+    /// Deserialize the parameters of the group `prefix` into `T`.
+    ///
+    /// The names relative to the group are the fields of `T`, and nested
+    /// groups such as `controller.limits.max_output` are nested structs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameters do not match the fields of `T`.
+    fn get_struct<T: DeserializeOwned>(&self, prefix: &str) -> Result<T>;
+
+    /// Deserialize the parameters of the group `prefix` into a
+    /// [`Reconfigurable`], rebuilt when one of them changes.
+    ///
+    /// As for other reconfigurable values, pass the updates of the parameter
+    /// server to [`Reconfigurable::update`]. Only the parameters of the group
+    /// declared at this point are watched.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameters do not match the fields of `T`.
+    fn load_struct<T: DeserializeOwned + 'static>(&self, prefix: &str)
+    -> Result<Reconfigurable<T>>;
+}
+
+impl ParametersExt for Parameters {
+    fn get_struct<T: DeserializeOwned>(&self, prefix: &str) -> Result<T> {
+        let mut root = Map::new();
+        for (name, param) in self.group(prefix) {
+            let mut object = &mut root;
+            let mut parts = name.split('.').peekable();
+            while let Some(part) = parts.next() {
+                if parts.peek().is_none() {
+                    object.insert(part.to_string(), param.value.to_json());
+                    break;
+                }
+                let entry = object
+                    .entry(part.to_string())
+                    .or_insert_with(|| serde_json::Value::Object(Map::new()));
+                if !entry.is_object() {
+                    *entry = serde_json::Value::Object(Map::new());
+                }
+                object = entry.as_object_mut().expect("replaced by an object");
+            }
+        }
+        serde_json::from_value(serde_json::Value::Object(root)).map_err(|e| {
+            let msg = format!("failed to deserialize parameter group {prefix}: {e}");
+            msg.into()
+        })
+    }
+
+    fn load_struct<T: DeserializeOwned + 'static>(
+        &self,
+        prefix: &str,
+    ) -> Result<Reconfigurable<T>> {
+        let watched: Vec<String> = self
+            .group(prefix)
+            .keys()
+            .map(|name| format!("{prefix}.{name}"))
+            .collect();
+        let watched: Vec<&str> = watched.iter().map(String::as_str).collect();
+        let prefix = prefix.to_string();
+        Reconfigurable::new(self, &watched, move |params| params.get_struct(&prefix))
+    }
+}
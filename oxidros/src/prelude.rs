@@ -3,6 +3,10 @@
 //! This module re-exports the most commonly used types and traits
 //! for ROS2 development with oxidros.
 //!
+//! The backend types are exported under the same names whichever backend
+//! feature is enabled, so code written against the prelude compiles with
+//! both `rcl` and `zenoh`.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -17,26 +21,54 @@
 // Re-export error types
 pub use oxidros_core::error::Error;
 
+/// Result of the oxidros APIs.
+///
+/// The error type defaults to [`Error`] and can be overridden, so the alias
+/// does not get in the way of `Result<T, E>` when the prelude is glob-imported.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
 // Re-export API traits
 pub use oxidros_core::api::{
-    RosClient, RosContext, RosNode, RosPublisher, RosSelector, RosServer, RosSubscriber,
-    ServiceRequest,
+    NodeHandle, RosActionClient, RosClient, RosContext, RosNode, RosPublisher, RosSelector,
+    RosServer, RosSubscriber, ServiceRequest,
 };
 
 // Re-export message traits
-pub use oxidros_core::{ActionGoal, ActionMsg, ActionResult, ServiceMsg, TypeSupport};
+pub use oxidros_core::{
+    ActionGoal, ActionMsg, ActionResult, ServiceMsg, Stamped, TypeCheckPolicy, TypeInfo,
+    TypeSupport, TypeSupportBorrowed,
+};
 
 // Re-export QoS types
-pub use oxidros_core::qos::Profile;
+pub use oxidros_core::qos::{
+    DurabilityPolicy, HistoryPolicy, LivelinessPolicy, Profile, QosCompatibility, ReliabilityPolicy,
+};
 
 // Re-export message utilities
-pub use oxidros_core::message::Message;
+pub use oxidros_core::MessageStream;
+pub use oxidros_core::message::{Message, RequestInfo};
+
+// Re-export network flow endpoints
+pub use oxidros_core::NetworkFlowEndpoint;
+
+// Re-export selector callback result, panic policy and file descriptor events
+pub use oxidros_core::selector::{CallbackResult, FdInterest, FdReadiness, PanicPolicy};
 
-// Re-export selector callback result
-pub use oxidros_core::selector::CallbackResult;
+// Re-export shutdown token
+pub use oxidros_core::shutdown::ShutdownToken;
+
+// Re-export thread placement
+pub use oxidros_core::thread::{ThreadOptions, ThreadPlacement};
 
 // Re-export parameter types
-pub use oxidros_core::{Parameter, Value};
+pub use crate::parameter::ParametersExt;
+pub use oxidros_core::{Parameter, ParameterType, Value};
+
+// Re-export primitive type publishers and subscribers
+pub use crate::primitive::{PrimitiveNodeExt, StdPrimitive};
+
+// Re-export derive macros
+pub use crate::RosNodeStruct;
 
 // Backend-specific types from our own modules
 #[cfg(any(feature = "rcl", feature = "zenoh"))]
@@ -53,7 +85,7 @@ pub use crate::service::server::Server;
 #[cfg(any(feature = "rcl", feature = "zenoh"))]
 pub use crate::topic::publisher::Publisher;
 #[cfg(any(feature = "rcl", feature = "zenoh"))]
-pub use crate::topic::subscriber::Subscriber;
+pub use crate::topic::subscriber::{Subscriber, SubscriberStream};
 
 // Context, Node, Selector come from the backend
 #[cfg(feature = "rcl")]
@@ -61,3 +93,11 @@ pub use oxidros_wrapper::{Context, Node, Selector};
 
 #[cfg(feature = "zenoh")]
 pub use oxidros_zenoh::{Context, Node, Selector};
+
+// Backend-specific extras
+#[cfg(feature = "zenoh")]
+pub use oxidros_zenoh::DiscoveryTarget;
+
+// Async helpers
+pub use futures_util::{Stream, StreamExt};
+pub use std::sync::Arc;
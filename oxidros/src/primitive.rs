@@ -0,0 +1,130 @@
+//! Publishers and subscribers of Rust primitive types.
+//!
+//! Simple telemetry nodes often publish a single number or string. Instead of
+//! building a `std_msgs` message by hand, [`PrimitiveNodeExt`] creates
+//! publishers and subscribers of the Rust type itself; the value is carried by
+//! the `data` field of the matching `std_msgs` message (see [`StdPrimitive`]),
+//! so other ROS2 nodes see an ordinary `std_msgs/msg/Float64`, `String`, etc.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::prelude::*;
+//!
+//! let ctx = Context::new()?;
+//! let node = ctx.create_node("thermometer", None)?;
+//!
+//! // Publishes std_msgs/msg/Float64
+//! let publisher = node.create_primitive_publisher::<f64>("temperature", None)?;
+//! publisher.send(&21.5)?;
+//!
+//! // Subscribes to std_msgs/msg/String
+//! let mut subscriber = node.create_primitive_subscriber::<String>("status", None)?;
+//! let status: String = subscriber.recv().await?.into_owned().unwrap();
+//! ```
+
+use oxidros_core::{Message, Profile, Result, RosNode, RosPublisher, RosSubscriber};
+use std::{borrow::Cow, marker::PhantomData, sync::Arc};
+
+pub use oxidros_msg::std_primitive::StdPrimitive;
+
+/// Publisher of a Rust primitive type, created by
+/// [`PrimitiveNodeExt::create_primitive_publisher`].
+pub struct PrimitivePublisher<T, P> {
+    inner: P,
+    _phantom: PhantomData<fn(&T)>,
+}
+
+impl<T: StdPrimitive, P: RosPublisher<T::Msg>> PrimitivePublisher<T, P> {
+    /// Get the topic name.
+    pub fn topic_name(&self) -> Result<Cow<'_, String>> {
+        self.inner.topic_name()
+    }
+
+    /// Publish a value.
+    pub fn send(&self, value: &T) -> Result<()> {
+        self.inner.send(&value.to_msg()?)
+    }
+
+    /// Wait until the values sent so far have been delivered.
+    ///
+    /// See [`RosPublisher::flush`].
+    pub async fn flush(&self) -> Result<()> {
+        self.inner.flush().await
+    }
+
+    /// Get the publisher of the underlying message.
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+}
+
+/// Subscriber of a Rust primitive type, created by
+/// [`PrimitiveNodeExt::create_primitive_subscriber`].
+pub struct PrimitiveSubscriber<T, S> {
+    inner: S,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: StdPrimitive, S: RosSubscriber<T::Msg>> PrimitiveSubscriber<T, S> {
+    /// Get the topic name.
+    pub fn topic_name(&self) -> Result<Cow<'_, String>> {
+        self.inner.topic_name()
+    }
+
+    /// Receive a value asynchronously.
+    pub async fn recv(&mut self) -> Result<Message<T>> {
+        let msg = self.inner.recv().await?;
+        Ok(Message::new(T::from_msg(&msg), msg.info))
+    }
+
+    /// Try to receive a value without blocking.
+    ///
+    /// Returns `Ok(None)` if no message is currently available.
+    pub fn try_recv(&mut self) -> Result<Option<Message<T>>> {
+        Ok(self
+            .inner
+            .try_recv()?
+            .map(|msg| Message::new(T::from_msg(&msg), msg.info)))
+    }
+
+    /// Get the subscriber of the underlying message, e.g. to add it to a
+    /// selector.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+/// Creation of publishers and subscribers of Rust primitive types.
+///
+/// Implemented for every [`RosNode`].
+pub trait PrimitiveNodeExt: RosNode {
+    /// Create a publisher of `T`, sent as the matching `std_msgs` message.
+    fn create_primitive_publisher<T: StdPrimitive>(
+        self: &Arc<Self>,
+        topic_name: &str,
+        qos: Option<Profile>,
+    ) -> Result<PrimitivePublisher<T, Self::Publisher<T::Msg>>> {
+        Ok(PrimitivePublisher {
+            inner: self.create_publisher::<T::Msg>(topic_name, qos)?,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Create a subscriber of `T`, received as the matching `std_msgs` message.
+    fn create_primitive_subscriber<T: StdPrimitive>(
+        self: &Arc<Self>,
+        topic_name: &str,
+        qos: Option<Profile>,
+    ) -> Result<PrimitiveSubscriber<T, Self::Subscriber<T::Msg>>>
+    where
+        Self::Subscriber<T::Msg>: RosSubscriber<T::Msg>,
+    {
+        Ok(PrimitiveSubscriber {
+            inner: self.create_subscriber::<T::Msg>(topic_name, qos)?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<N: RosNode> PrimitiveNodeExt for N {}
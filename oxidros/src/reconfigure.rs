@@ -0,0 +1,215 @@
+//! Entities rebuilt when the parameters they depend on change.
+//!
+//! A [`Reconfigurable`] holds a value, typically a publisher or a timer
+//! period, built from parameters such as `output_topic` or `publish_rate`.
+//! When the parameter server reports that one of them was updated, the value
+//! is rebuilt and swapped in one step, and the tasks waiting on
+//! [`Reconfigurable::changed`] are woken up to pick the new one.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::prelude::*;
+//! use oxidros::reconfigure::Reconfigurable;
+//! use std::time::Duration;
+//!
+//! let mut param_server = node.create_parameter_server()?;
+//! let params = param_server.params.clone();
+//!
+//! let publisher = Reconfigurable::new(&params.read(), &["output_topic"], {
+//!     let node = node.clone();
+//!     move |params| match params.get_parameter("output_topic").map(|p| &p.value) {
+//!         Some(Value::String(topic)) => node.create_publisher::<MyMessage>(topic, None),
+//!         _ => node.create_publisher::<MyMessage>("out", None),
+//!     }
+//! })?;
+//! let mut period = Reconfigurable::new(&params.read(), &["publish_rate"], |params| {
+//!     match params.get_parameter("publish_rate").map(|p| &p.value) {
+//!         Some(Value::F64(hz)) if *hz > 0.0 => Ok(Duration::from_secs_f64(1.0 / hz)),
+//!         _ => Ok(Duration::from_secs(1)),
+//!     }
+//! })?;
+//!
+//! // Rebuild the values when their parameters are set
+//! let (publisher_cfg, period_cfg) = (publisher.clone(), period.clone());
+//! tokio::spawn(async move {
+//!     loop {
+//!         let updated = param_server.wait().await?;
+//!         let params = param_server.params.read();
+//!         publisher_cfg.update(&params, &updated)?;
+//!         period_cfg.update(&params, &updated)?;
+//!     }
+//! });
+//!
+//! let mut interval = tokio::time::interval(*period.get());
+//! loop {
+//!     tokio::select! {
+//!         _ = period.changed() => interval = tokio::time::interval(*period.get()),
+//!         _ = interval.tick() => publisher.get().send(&msg)?,
+//!     }
+//! }
+//! ```
+
+use oxidros_core::{Result, parameter::Parameters};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::BTreeSet,
+    future::Future,
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    task::{Context, Poll, Waker},
+};
+
+type Build<T> = Box<dyn FnMut(&Parameters) -> Result<T> + Send>;
+
+struct Shared<T> {
+    watched: Vec<String>,
+    build: Mutex<Build<T>>,
+    value: RwLock<Arc<T>>,
+    version: AtomicU64,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+/// A value rebuilt from parameters whenever one of them changes.
+///
+/// Clones share the value; each clone tracks the changes it has seen with
+/// [`Reconfigurable::changed`].
+pub struct Reconfigurable<T> {
+    shared: Arc<Shared<T>>,
+    seen: u64,
+}
+
+impl<T> Clone for Reconfigurable<T> {
+    fn clone(&self) -> Self {
+        Reconfigurable {
+            shared: self.shared.clone(),
+            seen: self.seen,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Reconfigurable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Reconfigurable")
+            .field("watched", &self.shared.watched)
+            .field("version", &self.shared.version.load(Ordering::Acquire))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Reconfigurable<T> {
+    /// Build the value from `params` and watch the parameters named in `watched`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `build`.
+    pub fn new<F>(params: &Parameters, watched: &[&str], mut build: F) -> Result<Self>
+    where
+        F: FnMut(&Parameters) -> Result<T> + Send + 'static,
+    {
+        let value = build(params)?;
+        Ok(Reconfigurable {
+            shared: Arc::new(Shared {
+                watched: watched.iter().map(|name| name.to_string()).collect(),
+                build: Mutex::new(Box::new(build)),
+                value: RwLock::new(Arc::new(value)),
+                version: AtomicU64::new(0),
+                wakers: Mutex::new(Vec::new()),
+            }),
+            seen: 0,
+        })
+    }
+
+    /// Get the current value.
+    ///
+    /// The returned value stays valid after a swap; call `get` again to use
+    /// the new one.
+    pub fn get(&self) -> Arc<T> {
+        self.shared.value.read().clone()
+    }
+
+    /// Names of the watched parameters.
+    pub fn watched(&self) -> &[String] {
+        &self.shared.watched
+    }
+
+    /// Rebuild the value if one of the `updated` parameters is watched.
+    ///
+    /// `updated` is the set returned by `ParameterServer::wait`. Returns
+    /// `true` if the value was swapped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the build function; the previous value is kept.
+    pub fn update(&self, params: &Parameters, updated: &BTreeSet<String>) -> Result<bool> {
+        if !self
+            .shared
+            .watched
+            .iter()
+            .any(|name| updated.contains(name))
+        {
+            return Ok(false);
+        }
+        self.reload(params)?;
+        Ok(true)
+    }
+
+    /// Rebuild the value unconditionally.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the build function; the previous value is kept.
+    pub fn reload(&self, params: &Parameters) -> Result<()> {
+        let value = {
+            let mut build = self.shared.build.lock();
+            build(params)?
+        };
+        *self.shared.value.write() = Arc::new(value);
+        self.shared.version.fetch_add(1, Ordering::AcqRel);
+
+        let wakers = std::mem::take(&mut *self.shared.wakers.lock());
+        for waker in wakers {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Wait until the value is swapped.
+    ///
+    /// Returns immediately if it was swapped since the last call on this
+    /// handle. The future is cancel-safe and can be used in `select!`.
+    pub fn changed(&mut self) -> Changed<'_, T> {
+        Changed { reconf: self }
+    }
+}
+
+/// Future returned by [`Reconfigurable::changed`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Changed<'a, T> {
+    reconf: &'a mut Reconfigurable<T>,
+}
+
+impl<T> Future for Changed<'_, T> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let reconf = &mut *self.get_mut().reconf;
+        let shared = &reconf.shared;
+
+        let mut wakers = shared.wakers.lock();
+        // Checked under the lock, as `reload` takes the wakers after bumping it
+        let version = shared.version.load(Ordering::Acquire);
+        if version != reconf.seen {
+            drop(wakers);
+            reconf.seen = version;
+            return Poll::Ready(());
+        }
+        if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
@@ -11,6 +11,8 @@ pub mod client {
 
 /// Server module.
 pub mod server {
+    pub use oxidros_core::service::{ServerOptions, SheddingPolicy};
+
     #[cfg(feature = "rcl")]
     pub use oxidros_wrapper::{Server, ServiceRequest};
 
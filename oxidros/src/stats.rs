@@ -0,0 +1,122 @@
+//! Periodic publication of node statistics.
+//!
+//! [`StatsPublisher`] publishes the [`NodeStats`] of a node on a hidden topic,
+//! `~/_events` by default, so that fleet monitoring tools can spot overloaded
+//! nodes. The statistics are `diagnostic_msgs/msg/DiagnosticStatus` messages
+//! with the fully qualified name of the node as `name` and one key-value pair
+//! per counter: `publishers`, `subscribers`, `servers`, `clients` and, when
+//! the backend exposes its queues, `queued_messages`, `dropped_messages`,
+//! `pending_requests` and `shed_requests`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::prelude::*;
+//! use oxidros::stats::StatsPublisher;
+//!
+//! let stats = node.stats();
+//! println!("{} subscribers", stats.subscribers);
+//!
+//! tokio::spawn(StatsPublisher::new(&node).run());
+//! ```
+
+use oxidros_core::{
+    RclError, Result,
+    api::{RosNode, RosPublisher},
+};
+use oxidros_msg::common_interfaces::diagnostic_msgs::msg::{DiagnosticStatus, KeyValueSeq};
+use std::{sync::Arc, time::Duration};
+use tokio::time::MissedTickBehavior;
+
+pub use oxidros_core::stats::NodeStats;
+
+/// Default topic of the statistics, relative to the node.
+pub const DEFAULT_TOPIC: &str = "~/_events";
+
+/// Default interval between two publications.
+pub const DEFAULT_PERIOD: Duration = Duration::from_secs(1);
+
+/// Publishes the statistics of a node periodically.
+pub struct StatsPublisher<N> {
+    node: Arc<N>,
+    topic: String,
+    period: Duration,
+}
+
+impl<N: RosNode> StatsPublisher<N> {
+    /// Create a publisher of the statistics of `node` on [`DEFAULT_TOPIC`].
+    pub fn new(node: &Arc<N>) -> Self {
+        StatsPublisher {
+            node: node.clone(),
+            topic: DEFAULT_TOPIC.to_string(),
+            period: DEFAULT_PERIOD,
+        }
+    }
+
+    /// Topic of the statistics.
+    pub fn topic(mut self, topic: &str) -> Self {
+        self.topic = topic.to_string();
+        self
+    }
+
+    /// Interval between two publications.
+    pub fn period(mut self, period: Duration) -> Self {
+        self.period = period;
+        self
+    }
+
+    /// Publish the statistics until an error occurs.
+    ///
+    /// The publisher of the statistics is not counted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the publisher fails.
+    pub async fn run(self) -> Result<()> {
+        let publisher = self
+            .node
+            .create_publisher::<DiagnosticStatus>(&self.topic, None)?;
+        let name = self.node.fully_qualified_name()?;
+
+        let mut interval = tokio::time::interval(self.period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let mut stats = self.node.stats();
+            stats.publishers = stats.publishers.saturating_sub(1);
+            publisher.send(&status(&name, &stats)?)?;
+        }
+    }
+}
+
+fn status(name: &str, stats: &NodeStats) -> Result<DiagnosticStatus> {
+    let mut values = vec![
+        ("publishers", stats.publishers.to_string()),
+        ("subscribers", stats.subscribers.to_string()),
+        ("servers", stats.servers.to_string()),
+        ("clients", stats.clients.to_string()),
+    ];
+    if let Some(queued) = stats.queued_messages {
+        values.push(("queued_messages", queued.to_string()));
+    }
+    if let Some(dropped) = stats.dropped_messages {
+        values.push(("dropped_messages", dropped.to_string()));
+    }
+    if let Some(pending) = stats.pending_requests {
+        values.push(("pending_requests", pending.to_string()));
+    }
+    if let Some(shed) = stats.shed_requests {
+        values.push(("shed_requests", shed.to_string()));
+    }
+
+    let mut status = DiagnosticStatus::new().ok_or(RclError::BadAlloc)?;
+    status.level = DiagnosticStatus::OK;
+    status.name.assign(name);
+    let mut seq = KeyValueSeq::<0>::new(values.len()).ok_or(RclError::BadAlloc)?;
+    for (kv, (key, value)) in seq.as_mut_slice().iter_mut().zip(&values) {
+        kv.key.assign(key);
+        kv.value.assign(value);
+    }
+    status.values = seq;
+    Ok(status)
+}
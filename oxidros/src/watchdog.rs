@@ -0,0 +1,141 @@
+//! Detection of slow polls in async tasks.
+//!
+//! A blocking call inside an async handler, e.g. `std::thread::sleep` or a
+//! synchronous service call, stalls every other task of the runtime thread.
+//! [`spawn_ros_task`] spawns a task on Tokio and measures how long each poll
+//! of its future takes: a poll longer than the threshold is logged as a
+//! warning on the [`TASK`](oxidros_core::targets::TASK) target, with the name
+//! of the task.
+//!
+//! Each poll is also wrapped in a [`ROS_TASK`](oxidros_core::spans::ROS_TASK)
+//! span carrying the task name, so that `tokio-console` or other tracing
+//! subscribers can attribute the time spent to the task.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxidros::watchdog::{spawn_ros_task, set_slow_poll_threshold};
+//! use std::time::Duration;
+//!
+//! set_slow_poll_threshold(Duration::from_millis(5));
+//!
+//! spawn_ros_task("/chatter", async move {
+//!     while let Ok(msg) = subscriber.recv().await {
+//!         handle(msg);
+//!     }
+//! });
+//! ```
+
+use oxidros_core::{spans, targets};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::task::JoinHandle;
+
+/// Default duration of a poll above which a warning is logged.
+pub const DEFAULT_SLOW_POLL_THRESHOLD: Duration = Duration::from_millis(10);
+
+static SLOW_POLL_THRESHOLD_NANOS: AtomicU64 =
+    AtomicU64::new(DEFAULT_SLOW_POLL_THRESHOLD.as_nanos() as u64);
+
+/// Set the threshold of the tasks created from now on.
+pub fn set_slow_poll_threshold(threshold: Duration) {
+    let nanos = u64::try_from(threshold.as_nanos()).unwrap_or(u64::MAX);
+    SLOW_POLL_THRESHOLD_NANOS.store(nanos, Ordering::Relaxed);
+}
+
+/// Get the threshold given to new tasks.
+pub fn slow_poll_threshold() -> Duration {
+    Duration::from_nanos(SLOW_POLL_THRESHOLD_NANOS.load(Ordering::Relaxed))
+}
+
+/// Spawn `future` on Tokio, warning about polls longer than
+/// [`slow_poll_threshold`].
+///
+/// `name` identifies the task in the logs, e.g. the topic it handles. When
+/// built with `--cfg tokio_unstable`, it also names the task for
+/// `tokio-console`.
+///
+/// # Panics
+///
+/// Panics if called outside of a Tokio runtime.
+pub fn spawn_ros_task<F>(name: &str, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    #[cfg(tokio_unstable)]
+    return tokio::task::Builder::new()
+        .name(name)
+        .spawn(Watched::new(name, future))
+        .expect("failed to spawn a task");
+    #[cfg(not(tokio_unstable))]
+    tokio::spawn(Watched::new(name, future))
+}
+
+/// Future measuring the duration of the polls of an inner future.
+pub struct Watched<F> {
+    name: String,
+    threshold: Duration,
+    slow_polls: u64,
+    span: tracing::Span,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Watched<F> {
+    /// Watch `future` under `name` with the current [`slow_poll_threshold`].
+    pub fn new(name: &str, future: F) -> Self {
+        Watched {
+            name: name.to_string(),
+            threshold: slow_poll_threshold(),
+            slow_polls: 0,
+            span: tracing::debug_span!(target: targets::TASK, spans::ROS_TASK, task = %name),
+            future: Box::pin(future),
+        }
+    }
+
+    /// Duration of a poll above which a warning is logged.
+    pub fn threshold(mut self, threshold: Duration) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Get the name of the task.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Number of polls which exceeded the threshold so far.
+    pub fn slow_polls(&self) -> u64 {
+        self.slow_polls
+    }
+}
+
+impl<F: Future> Future for Watched<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let start = Instant::now();
+        let poll = {
+            let _enter = this.span.enter();
+            this.future.as_mut().poll(cx)
+        };
+        let elapsed = start.elapsed();
+        if elapsed > this.threshold {
+            this.slow_polls += 1;
+            tracing::warn!(
+                target: targets::TASK,
+                task = %this.name,
+                ?elapsed,
+                threshold = ?this.threshold,
+                "Slow poll, a blocking call may be stalling the runtime"
+            );
+        }
+        poll
+    }
+}
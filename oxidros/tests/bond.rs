@@ -0,0 +1,75 @@
+//! Bond integration tests.
+
+#![cfg(feature = "bond")]
+
+use oxidros::bond::{Bond, BrokenReason};
+use oxidros::prelude::*;
+use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_bond_broken_by_peer() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node_a = ctx.create_node("test_bond_a", None)?;
+    let node_b = ctx.create_node("test_bond_b", None)?;
+
+    let formed = Arc::new(AtomicBool::new(false));
+    let bond_a = Bond::new(&node_a, "test_bond_peer")
+        .topic("test_bond_peer")
+        .heartbeat_period(Duration::from_millis(100))
+        .on_formed({
+            let formed = formed.clone();
+            move || formed.store(true, Ordering::SeqCst)
+        });
+    let bond_b = Bond::new(&node_b, "test_bond_peer")
+        .topic("test_bond_peer")
+        .heartbeat_period(Duration::from_millis(100));
+    let breaker = bond_b.breaker();
+
+    let a = tokio::spawn(bond_a.run());
+    let b = tokio::spawn(bond_b.run());
+
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert!(formed.load(Ordering::SeqCst));
+
+    breaker.shutdown();
+    assert_eq!(b.await??, BrokenReason::Local);
+    let reason = tokio::time::timeout(Duration::from_secs(3), a).await???;
+    assert_eq!(reason, BrokenReason::Peer);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_bond_timeouts() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node_a = ctx.create_node("test_bond_timeout_a", None)?;
+    let node_b = ctx.create_node("test_bond_timeout_b", None)?;
+
+    // nobody on the other end
+    let reason = Bond::new(&node_a, "test_bond_alone")
+        .topic("test_bond_timeout")
+        .connect_timeout(Duration::from_millis(500))
+        .run()
+        .await?;
+    assert_eq!(reason, BrokenReason::ConnectTimeout);
+
+    // the other end stops without breaking the bond
+    let bond_a = Bond::new(&node_a, "test_bond_timeout")
+        .topic("test_bond_timeout")
+        .heartbeat_period(Duration::from_millis(100))
+        .heartbeat_timeout(Duration::from_millis(500));
+    let bond_b = Bond::new(&node_b, "test_bond_timeout")
+        .topic("test_bond_timeout")
+        .heartbeat_period(Duration::from_millis(100));
+    let a = tokio::spawn(bond_a.run());
+    let b = tokio::spawn(bond_b.run());
+
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    b.abort();
+    let reason = tokio::time::timeout(Duration::from_secs(3), a).await???;
+    assert_eq!(reason, BrokenReason::HeartbeatTimeout);
+
+    Ok(())
+}
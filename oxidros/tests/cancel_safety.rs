@@ -0,0 +1,139 @@
+//! Cancel safety tests.
+//!
+//! Drives `recv` and `call` from `tokio::select!` loops, whose losing branch
+//! drops the pending future.
+//! Works with both RCL and Zenoh backends.
+
+mod common;
+
+use oxidros::prelude::*;
+use oxidros_msg::common_interfaces::example_interfaces::{
+    msg::Int64,
+    srv::{AddTwoInts_Request, AddTwoInts_Response},
+};
+use std::error::Error;
+use std::ops::Deref;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_subscriber_select() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_cancel_subscriber", None)?;
+
+    let publisher = common::create_publisher(node.clone(), "test_cancel_subscriber_topic")?;
+    let mut subscriber = common::create_subscriber(node.clone(), "test_cancel_subscriber_topic")?;
+
+    // Let the publisher and the subscriber discover each other
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let publish = tokio::spawn(async move {
+        for data in 0..5 {
+            publisher.send(&Int64 { data })?;
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        Ok::<_, oxidros_core::Error>(())
+    });
+
+    // The tick cancels many pending receptions
+    let mut interval = tokio::time::interval(Duration::from_millis(1));
+    let mut received = Vec::new();
+    let mut ticks = 0;
+    tokio::time::timeout(Duration::from_secs(3), async {
+        while received.len() < 5 {
+            tokio::select! {
+                msg = subscriber.recv() => received.push(msg?.deref().data),
+                _ = interval.tick() => ticks += 1,
+            }
+        }
+        Ok::<_, oxidros_core::Error>(())
+    })
+    .await??;
+    publish.await??;
+
+    assert_eq!(received, vec![0, 1, 2, 3, 4]);
+    assert!(ticks > 0);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_server_select() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node_server = ctx.create_node("test_cancel_server", None)?;
+    let node_client = ctx.create_node("test_cancel_server_client", None)?;
+
+    let mut server = common::create_server(node_server, "test_cancel_server_service")?;
+    let mut client = common::create_client(node_client, "test_cancel_server_service")?;
+
+    let server = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(1));
+        let mut served = 0;
+        while served < 3 {
+            tokio::select! {
+                request = server.recv() => {
+                    let request = request?;
+                    let req = request.request();
+                    let response = AddTwoInts_Response { sum: req.a + req.b };
+                    request.respond(&response)?;
+                    served += 1;
+                }
+                _ = interval.tick() => {}
+            }
+        }
+        Ok::<_, oxidros_core::Error>(())
+    });
+
+    while !client.is_service_available() {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    for n in 0..3i64 {
+        let request = AddTwoInts_Request { a: n, b: 100 };
+        let response =
+            tokio::time::timeout(Duration::from_secs(3), client.call(&request)).await??;
+        assert_eq!(response.sum, n + 100);
+    }
+
+    tokio::time::timeout(Duration::from_secs(1), server).await???;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_cancelled_call() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node_server = ctx.create_node("test_cancel_call_server", None)?;
+    let node_client = ctx.create_node("test_cancel_call_client", None)?;
+
+    let mut server = common::create_server(node_server, "test_cancel_call_service")?;
+    let mut client = common::create_client(node_client, "test_cancel_call_service")?;
+
+    // The first request is answered late, after the client gave up on it
+    let server = tokio::spawn(async move {
+        for delay in [300, 0] {
+            let request = server.recv().await?;
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            let req = request.request();
+            let response = AddTwoInts_Response { sum: req.a + req.b };
+            request.respond(&response)?;
+        }
+        Ok::<_, oxidros_core::Error>(())
+    });
+
+    while !client.is_service_available() {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let request = AddTwoInts_Request { a: 1, b: 1 };
+    tokio::select! {
+        _ = client.call(&request) => panic!("the server answers late"),
+        _ = tokio::time::sleep(Duration::from_millis(50)) => {}
+    }
+
+    // The late response to the cancelled call must not be returned
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    let request = AddTwoInts_Request { a: 20, b: 22 };
+    let response = tokio::time::timeout(Duration::from_secs(3), client.call(&request)).await??;
+    assert_eq!(response.sum, 42);
+
+    tokio::time::timeout(Duration::from_secs(1), server).await???;
+    Ok(())
+}
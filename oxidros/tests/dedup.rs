@@ -0,0 +1,99 @@
+//! Duplicate and loss detection integration test.
+//!
+//! Tests the sequence tracker on crafted message info, and that messages of a
+//! real publisher arrive numbered and in order. Works with both RCL and Zenoh
+//! backends.
+
+mod common;
+
+use oxidros::dedup::{DedupStats, DeduplicatingSubscriber, SequenceStatus, SequenceTracker};
+use oxidros::prelude::*;
+use oxidros_core::MessageInfo;
+use oxidros_msg::common_interfaces::example_interfaces::msg::Int64;
+use std::error::Error;
+use std::ops::Deref;
+use std::time::Duration;
+
+fn info(gid: u8, sequence_number: i64) -> MessageInfo {
+    MessageInfo {
+        sequence_number,
+        writer_gid: [gid; 16],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_sequence_tracker() {
+    let mut tracker = SequenceTracker::new();
+
+    assert_eq!(tracker.check(&info(1, 1)), SequenceStatus::First);
+    assert_eq!(tracker.check(&info(1, 2)), SequenceStatus::InOrder);
+    assert_eq!(tracker.check(&info(1, 2)), SequenceStatus::Duplicate);
+    assert_eq!(
+        tracker.check(&info(1, 5)),
+        SequenceStatus::Gap { missing: 2 }
+    );
+    // Late message, already counted as lost
+    assert_eq!(tracker.check(&info(1, 4)), SequenceStatus::Duplicate);
+
+    // Publishers are tracked separately
+    assert_eq!(tracker.check(&info(2, 7)), SequenceStatus::First);
+    assert_eq!(tracker.check(&info(2, 8)), SequenceStatus::InOrder);
+
+    assert_eq!(tracker.check(&info(3, 0)), SequenceStatus::Unsequenced);
+    assert_eq!(tracker.check(&info(3, -1)), SequenceStatus::Unsequenced);
+
+    assert_eq!(
+        tracker.stats(),
+        DedupStats {
+            received: 7,
+            duplicates: 2,
+            lost: 2,
+            gaps: 1,
+            publishers: 2,
+        }
+    );
+
+    tracker.reset();
+    assert_eq!(tracker.stats(), DedupStats::default());
+    assert_eq!(tracker.check(&info(1, 1)), SequenceStatus::First);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_deduplicating_subscriber() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_dedup", None)?;
+
+    let publisher = common::create_publisher(node.clone(), "test_dedup_topic")?;
+    let subscriber = common::create_subscriber(node.clone(), "test_dedup_topic")?;
+    let mut subscriber = DeduplicatingSubscriber::new(subscriber);
+
+    // Let the publisher and subscriber discover each other
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    for data in 0..5 {
+        publisher.send(&Int64 { data })?;
+    }
+
+    let mut sequence_numbers = Vec::new();
+    for data in 0..5 {
+        let msg = tokio::time::timeout(Duration::from_secs(2), subscriber.recv()).await??;
+        assert_eq!(msg.deref().data, data);
+        sequence_numbers.push(msg.info.sequence_number);
+    }
+
+    // Numbered by the publisher, one by one
+    assert!(
+        sequence_numbers.windows(2).all(|w| w[1] == w[0] + 1),
+        "{sequence_numbers:?}"
+    );
+    assert_eq!(subscriber.last_status(), Some(SequenceStatus::InOrder));
+
+    let stats = subscriber.stats();
+    assert_eq!(stats.received, 5);
+    assert_eq!(stats.duplicates, 0);
+    assert_eq!(stats.lost, 0);
+    assert_eq!(stats.publishers, 1);
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+//! Thread placement tests.
+
+#![cfg(feature = "executor")]
+
+use oxidros::executor::Executor;
+use oxidros::prelude::*;
+use std::error::Error;
+
+#[test]
+fn test_executor_runs_unprivileged() -> Result<(), Box<dyn Error + Send + Sync>> {
+    // A priority out of reach of unprivileged processes must not fail the build
+    let options = ThreadOptions::new().cpus([0]).fifo_priority(99);
+    let runtime = Executor::new()
+        .worker_threads(2)
+        .thread_options(options)
+        .build()?;
+
+    let value = runtime.block_on(async { tokio::spawn(async { 42 }).await })?;
+    assert_eq!(value, 42);
+    Ok(())
+}
+
+#[test]
+fn test_selector_thread_options() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let mut selector = ctx.create_selector()?;
+
+    let placement = selector.set_thread_options(&ThreadOptions::new());
+    assert_eq!(placement, ThreadPlacement::default());
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! Instance-aware subscription integration test.
+//!
+//! Tests that the last sample of every instance is kept and taken once.
+//! Works with both RCL and Zenoh backends.
+
+mod common;
+
+use oxidros::instance::InstanceSubscriber;
+use oxidros::prelude::*;
+use oxidros_msg::common_interfaces::example_interfaces::msg::Int64;
+use std::error::Error;
+use std::ops::Deref;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_instance_subscriber() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_instance", None)?;
+
+    let publisher = common::create_publisher(node.clone(), "test_instance_topic")?;
+    let subscriber = common::create_subscriber(node.clone(), "test_instance_topic")?;
+    // Instances by the last digit of the data
+    let mut subscriber = InstanceSubscriber::with_key(subscriber, |msg: &Int64| msg.data % 10);
+
+    // Let the publisher and subscriber discover each other
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    for data in [1, 2, 11, 3, 21] {
+        publisher.send(&Int64 { data })?;
+    }
+
+    // Messages of other instances update them while waiting for instance 3
+    let msg = tokio::time::timeout(Duration::from_secs(2), subscriber.recv_instance(&3)).await??;
+    assert_eq!(msg.deref().data, 3);
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+    while subscriber.instance(&1).map(|instance| instance.count) != Some(3) {
+        assert!(tokio::time::Instant::now() < deadline, "timed out");
+        subscriber.poll()?;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    assert_eq!(subscriber.len(), 3);
+    let keys: Vec<_> = subscriber.instances().map(|(key, _)| *key).collect();
+    assert_eq!(keys, [1, 2, 3]);
+    assert_eq!(subscriber.last(&1).map(|msg| msg.data), Some(21));
+    assert_eq!(subscriber.last(&2).map(|msg| msg.data), Some(2));
+
+    // The last sample of an instance is taken once
+    assert_eq!(subscriber.take_instance(&1)?.map(|msg| msg.data), Some(21));
+    assert!(subscriber.take_instance(&1)?.is_none());
+    // Already returned by recv_instance
+    assert!(subscriber.take_instance(&3)?.is_none());
+    assert!(subscriber.take_instance(&4)?.is_none());
+
+    assert_eq!(
+        subscriber.remove(&2).map(|instance| instance.count),
+        Some(1)
+    );
+    assert_eq!(subscriber.len(), 2);
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+//! Message to JSON conversion tests.
+
+use oxidros::introspection::{cdr_to_json, to_json};
+use oxidros_core::{TypeDescription, TypeSupport};
+use oxidros_msg::common_interfaces::{
+    example_interfaces::msg::Int64, geometry_msgs::msg::PoseWithCovariance,
+    std_msgs::msg::String as StringMsg,
+};
+use serde_json::json;
+
+#[test]
+fn test_to_json() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(to_json(&Int64 { data: 42 })?, json!({ "data": 42 }));
+
+    let mut msg = StringMsg::new().unwrap();
+    msg.data.assign("hello");
+    assert_eq!(to_json(&msg)?, json!({ "data": "hello" }));
+
+    let mut pose = PoseWithCovariance::new().unwrap();
+    pose.pose.position.x = 1.5;
+    pose.pose.orientation.w = 1.0;
+    pose.covariance[35] = 2.0;
+    let value = to_json(&pose)?;
+    assert_eq!(value["pose"]["position"]["x"], json!(1.5));
+    assert_eq!(value["pose"]["orientation"]["w"], json!(1.0));
+    assert_eq!(value["covariance"].as_array().map(Vec::len), Some(36));
+    assert_eq!(value["covariance"][35], json!(2.0));
+
+    Ok(())
+}
+
+#[test]
+fn test_cdr_to_json() -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = Int64 { data: -7 }.to_bytes()?;
+    let description = Int64::type_description();
+    assert_eq!(cdr_to_json(&description, &bytes)?, json!({ "data": -7 }));
+
+    // truncated payload
+    assert!(cdr_to_json(&description, &bytes[..6]).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+//! Node template tests.
+
+#![cfg(feature = "kit")]
+
+mod common;
+
+use oxidros::kit::{ControllerNode, DIAGNOSTICS_TOPIC, SensorDriverNode};
+use oxidros::prelude::*;
+use oxidros_core::parameter::Parameters;
+use oxidros_msg::common_interfaces::{
+    diagnostic_msgs::msg::DiagnosticArray, example_interfaces::msg::Int64,
+};
+use std::error::Error;
+use std::ops::Deref;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sensor_driver() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_sensor_driver", None)?;
+
+    let mut subscriber = common::create_subscriber(node.clone(), "test_sensor_driver_data")?;
+    let mut diagnostics = node.create_subscriber::<DiagnosticArray>(DIAGNOSTICS_TOPIC, None)?;
+
+    // Every third read fails
+    let mut count = 0;
+    let driver = SensorDriverNode::new(&node, "test_sensor_driver_data", 50.0, move || {
+        count += 1;
+        if count % 3 == 0 {
+            Err(oxidros_core::Error::Other("sensor timeout".into()))
+        } else {
+            Ok(Some(Int64 { data: count }))
+        }
+    })?
+    .hardware_id("test_sensor")
+    .diagnostics_period(Duration::from_millis(300));
+    let driver = tokio::spawn(driver.run());
+
+    let msg = tokio::time::timeout(Duration::from_secs(2), subscriber.recv()).await??;
+    assert_ne!(msg.deref().data % 3, 0);
+
+    let msg = tokio::time::timeout(Duration::from_secs(2), diagnostics.recv()).await??;
+    let status = &msg.deref().status.as_slice()[0];
+    assert_eq!(status.hardware_id.get_string(), "test_sensor");
+    assert!(
+        status
+            .values
+            .as_slice()
+            .iter()
+            .any(|kv| kv.key.get_string() == "errors")
+    );
+
+    driver.abort();
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_controller_watchdog() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_controller_watchdog", None)?;
+
+    let publisher = common::create_publisher(node.clone(), "test_controller_in")?;
+    let mut subscriber = common::create_subscriber(node.clone(), "test_controller_out")?;
+
+    let controller = ControllerNode::new(
+        &node,
+        "test_controller_in",
+        "test_controller_out",
+        50.0,
+        |msg: &Int64| Some(Int64 { data: msg.data * 2 }),
+    )?
+    .watchdog(Duration::from_millis(200), || Some(Int64 { data: -1 }));
+    let controller = tokio::spawn(controller.run());
+
+    // No input yet: the fallback is published
+    let msg = tokio::time::timeout(Duration::from_secs(2), subscriber.recv()).await??;
+    assert_eq!(msg.deref().data, -1);
+
+    // Let the publishers and subscribers discover each other
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    publisher.send(&Int64 { data: 21 })?;
+
+    let output = tokio::time::timeout(Duration::from_secs(2), async {
+        loop {
+            let msg = subscriber.recv().await?;
+            if msg.deref().data != -1 {
+                return Ok::<_, oxidros_core::Error>(msg.deref().data);
+            }
+        }
+    })
+    .await??;
+    assert_eq!(output, 42);
+
+    controller.abort();
+    Ok(())
+}
+
+#[test]
+fn test_kit_from_parameters() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_kit_from_parameters", None)?;
+
+    let mut params = Parameters::new();
+    params.set_parameter("topic".into(), "data".into(), false, None)?;
+    params.set_parameter("rate".into(), Value::I64(10), false, None)?;
+    assert!(
+        SensorDriverNode::from_parameters(&node, &params, || Ok(Some(Int64 { data: 0 }))).is_ok()
+    );
+
+    params.set_parameter("input_topic".into(), "in".into(), false, None)?;
+    params.set_parameter("output_topic".into(), "out".into(), false, None)?;
+    params.set_parameter("watchdog_timeout".into(), Value::F64(-1.0), false, None)?;
+    assert!(matches!(
+        ControllerNode::from_parameters(&node, &params, |msg: &Int64| Some(Int64 {
+            data: msg.data
+        })),
+        Err(oxidros_core::Error::InvalidConfig(_))
+    ));
+
+    Ok(())
+}
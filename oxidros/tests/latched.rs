@@ -0,0 +1,101 @@
+//! Transient-local (latched) topics integration test.
+//!
+//! Tests that late-joining subscribers receive the history kept by
+//! transient-local publishers through `recv()`, as for `/map` topics.
+//! Works with both RCL and Zenoh backends.
+
+use oxidros::prelude::*;
+use oxidros_msg::common_interfaces::{example_interfaces::msg::Int64, nav_msgs::msg::MapMetaData};
+use std::error::Error;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+fn latched(depth: usize) -> Profile {
+    Profile {
+        depth,
+        reliability: ReliabilityPolicy::Reliable,
+        durability: DurabilityPolicy::TransientLocal,
+        ..Default::default()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_latched_late_subscriber() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node_pub = ctx.create_node("test_latched_map_pub", None)?;
+    let node_sub = ctx.create_node("test_latched_map_sub", None)?;
+
+    let publisher =
+        node_pub.create_publisher::<MapMetaData>("test_latched_map", Some(latched(1)))?;
+    let mut map = MapMetaData::new().unwrap();
+    map.width = 640;
+    map.height = 480;
+    map.resolution = 0.05;
+    publisher.send(&map)?;
+
+    // The subscriber joins after the map was published
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let mut subscriber =
+        node_sub.create_subscriber::<MapMetaData>("test_latched_map", Some(latched(1)))?;
+
+    let msg = tokio::time::timeout(TIMEOUT, subscriber.recv()).await??;
+    assert_eq!(msg.width, 640);
+    assert_eq!(msg.height, 480);
+    assert_eq!(msg.resolution, 0.05);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_latched_history_replay() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node_pub = ctx.create_node("test_latched_history_pub", None)?;
+    let node_sub = ctx.create_node("test_latched_history_sub", None)?;
+
+    let publisher = node_pub.create_publisher::<Int64>("test_latched_history", Some(latched(3)))?;
+    for data in 0..5 {
+        publisher.send(&Int64 { data })?;
+    }
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let mut subscriber =
+        node_sub.create_subscriber::<Int64>("test_latched_history", Some(latched(3)))?;
+
+    // Only the last `depth` messages are kept, and replayed in order
+    for expected in 2..5 {
+        let msg = tokio::time::timeout(TIMEOUT, subscriber.recv()).await??;
+        assert_eq!(msg.data, expected);
+    }
+
+    // Live messages follow the history
+    publisher.send(&Int64 { data: 5 })?;
+    let msg = tokio::time::timeout(TIMEOUT, subscriber.recv()).await??;
+    assert_eq!(msg.data, 5);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_volatile_late_subscriber() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node_pub = ctx.create_node("test_latched_volatile_pub", None)?;
+    let node_sub = ctx.create_node("test_latched_volatile_sub", None)?;
+
+    let publisher =
+        node_pub.create_publisher::<Int64>("test_latched_volatile", Some(latched(1)))?;
+    publisher.send(&Int64 { data: 1 })?;
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let mut subscriber = node_sub.create_subscriber::<Int64>("test_latched_volatile", None)?;
+
+    // A volatile subscriber does not get the history
+    let result = tokio::time::timeout(Duration::from_millis(500), subscriber.recv()).await;
+    assert!(result.is_err(), "received a latched message");
+
+    publisher.send(&Int64 { data: 2 })?;
+    let msg = tokio::time::timeout(TIMEOUT, subscriber.recv()).await??;
+    assert_eq!(msg.data, 2);
+
+    Ok(())
+}
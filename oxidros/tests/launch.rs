@@ -0,0 +1,111 @@
+//! Launch tests.
+
+#![cfg(feature = "launch")]
+
+use oxidros::launch::{Launch, LaunchNode, StartCondition};
+use oxidros::prelude::*;
+use parking_lot::Mutex;
+use std::error::Error;
+
+#[test]
+fn test_startup_order() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let launch = Launch::new(&ctx)
+        .node(LaunchNode::process("c", "c").after(StartCondition::Started("b".into())))
+        .node(LaunchNode::process("a", "a"))
+        .node(LaunchNode::process("b", "b").after(StartCondition::Started("a".into())))
+        .node(LaunchNode::process("d", "d"));
+    assert_eq!(launch.startup_order()?, ["a", "d", "b", "c"]);
+
+    let cycle = Launch::new(&ctx)
+        .node(LaunchNode::process("a", "a").after(StartCondition::Started("b".into())))
+        .node(LaunchNode::process("b", "b").after(StartCondition::Started("a".into())));
+    assert!(cycle.startup_order().is_err());
+
+    let unknown = Launch::new(&ctx)
+        .node(LaunchNode::process("a", "a").after(StartCondition::Started("z".into())));
+    assert!(unknown.startup_order().is_err());
+
+    let twice = Launch::new(&ctx)
+        .node(LaunchNode::process("a", "a"))
+        .node(LaunchNode::process("a", "b"));
+    assert!(twice.startup_order().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_node_config() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let node: LaunchNode<Node> = LaunchNode::process("camera", "camera_driver")
+        .namespace("/robot")
+        .parameter("fps", 30i64)
+        .parameter("frame_id", "it's")
+        .remap("image_raw", "camera/image");
+    let config = node.config();
+
+    assert_eq!(config.fully_qualified_name(), "/robot/camera");
+    assert_eq!(config.remap("image_raw"), "camera/image");
+    assert_eq!(config.remap("info"), "info");
+    assert_eq!(
+        config.ros_args()?,
+        [
+            "--ros-args",
+            "-r",
+            "__node:=camera",
+            "-r",
+            "__ns:=/robot",
+            "-r",
+            "image_raw:=camera/image",
+            "-p",
+            "fps:=30",
+            "-p",
+            "frame_id:=it's",
+        ]
+    );
+
+    let params = config.parameters()?;
+    assert_eq!(
+        params.get_parameter("fps").map(|p| &p.value),
+        Some(&Value::I64(30))
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_in_process() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let log = Arc::new(Mutex::new(Vec::new()));
+
+    let launch = Launch::new(&ctx)
+        .node(
+            LaunchNode::in_process("test_launch_second", {
+                let log = log.clone();
+                move |node: Arc<Node>, config| async move {
+                    log.lock()
+                        .push((node.name()?, config.remap("input").to_string()));
+                    Ok(())
+                }
+            })
+            .remap("input", "first/output")
+            .after(StartCondition::Started("test_launch_first".into())),
+        )
+        .node(LaunchNode::in_process("test_launch_first", {
+            let log = log.clone();
+            move |node: Arc<Node>, _config| async move {
+                log.lock().push((node.name()?, String::new()));
+                Ok(())
+            }
+        }));
+    launch.run().await?;
+
+    assert_eq!(
+        *log.lock(),
+        [
+            ("test_launch_first".to_string(), String::new()),
+            ("test_launch_second".to_string(), "first/output".to_string()),
+        ]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+//! MCAP recording and replay tests.
+
+#![cfg(feature = "mcap")]
+
+use oxidros::mcap::{McapReader, McapWriter};
+use oxidros_core::{MessageInfo, TypeDescription, calculate_type_hash};
+use oxidros_msg::common_interfaces::{
+    example_interfaces::msg::Int64, std_msgs::msg::String as StringMsg,
+};
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("oxidros_{name}_{}.mcap", std::process::id()))
+}
+
+#[test]
+fn test_write_read() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let path = temp_path("write_read");
+    let mut writer = McapWriter::create(&path)?;
+    for i in 0..3 {
+        writer.write("/counter", &Int64 { data: i })?;
+    }
+    let mut msg = StringMsg::new().unwrap();
+    msg.data.assign("hello");
+    writer.write("/chatter", &msg)?;
+    writer.finish()?;
+
+    let reader = McapReader::open(&path)?;
+
+    let channels = reader.channels()?;
+    assert_eq!(channels.len(), 2);
+    assert_eq!(channels[0].topic, "/counter");
+    assert_eq!(channels[0].type_name, "example_interfaces/msg/Int64");
+    assert_eq!(
+        channels[0].type_hash,
+        Some(calculate_type_hash(&Int64::type_description())?)
+    );
+    assert_eq!(channels[0].message_definition.trim(), "int64 data");
+    assert_eq!(channels[1].type_name, "std_msgs/msg/String");
+
+    let messages = reader.messages()?.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(messages.len(), 4);
+    let counter: Vec<i64> = messages
+        .iter()
+        .filter(|m| m.topic == "/counter")
+        .map(|m| m.decode::<Int64>().map(|msg| msg.data))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(counter, [0, 1, 2]);
+    assert_eq!(messages[3].decode::<StringMsg>()?.data.to_string(), "hello");
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_player() -> Result<(), Box<dyn Error + Sync + Send + 'static>> {
+    let path = temp_path("player");
+    let mut writer = McapWriter::create(&path)?;
+    let channel = writer.add_channel::<Int64>("/counter")?;
+    // 100 ms between messages
+    for i in 0..3u64 {
+        let time = 1_000_000_000 + i * 100_000_000;
+        let cdr = oxidros_core::TypeSupport::to_bytes(&Int64 { data: i as i64 })?;
+        writer.write_raw(channel, &cdr, time, time)?;
+    }
+    // received messages without a source timestamp are stamped on receipt
+    let cdr = oxidros_core::TypeSupport::to_bytes(&Int64 { data: 3 })?;
+    writer.write_received(channel, &cdr, &MessageInfo::default())?;
+    writer.finish()?;
+
+    let reader = McapReader::open(&path)?;
+    assert!(reader.player(0.0).is_err());
+    assert_eq!(reader.messages()?.count(), 4);
+
+    // replay the first three messages at twice the recorded pace
+    let mut player = reader.player(2.0)?;
+    let start = Instant::now();
+    for i in 0..2 {
+        let msg = player.next().await.unwrap()?;
+        assert_eq!(msg.decode::<Int64>()?.data, i);
+    }
+    // a cancelled wait keeps the message
+    let cancelled = tokio::time::timeout(Duration::from_millis(10), player.next()).await;
+    assert!(cancelled.is_err());
+    let msg = player.next().await.unwrap()?;
+    assert_eq!(msg.decode::<Int64>()?.data, 2);
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(95), "{elapsed:?}");
+    assert!(elapsed < Duration::from_secs(1), "{elapsed:?}");
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
@@ -0,0 +1,38 @@
+//! Middleware information integration test.
+//!
+//! Works with both RCL and Zenoh backends.
+
+use oxidros::prelude::*;
+use std::error::Error;
+
+#[test]
+fn test_rmw_implementation() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let implementation = ctx.rmw_implementation();
+    assert!(implementation.starts_with("rmw_"), "{implementation}");
+
+    if let Ok(expected) = std::env::var("RMW_IMPLEMENTATION") {
+        assert_eq!(implementation, expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_rmw_qos_compatibility_check() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+
+    let reliable = Profile::default();
+    let compatibility = ctx.rmw_qos_compatibility_check(&reliable, &reliable)?;
+    assert_eq!(compatibility, QosCompatibility::Ok);
+
+    let best_effort = Profile::sensor_data();
+    let compatibility = ctx.rmw_qos_compatibility_check(&reliable, &best_effort)?;
+    assert_eq!(compatibility, QosCompatibility::Ok);
+
+    // DDS does not match these endpoints, Zenoh warns about them
+    let compatibility = ctx.rmw_qos_compatibility_check(&best_effort, &reliable)?;
+    assert_ne!(compatibility, QosCompatibility::Ok);
+
+    Ok(())
+}
@@ -0,0 +1,10 @@
+//! Compile tests of the `RosNodeStruct` derive.
+//!
+//! Works with both RCL and Zenoh backends.
+
+#[test]
+fn test_node_struct_ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/node_struct/pass_*.rs");
+    t.compile_fail("tests/ui/node_struct/fail_*.rs");
+}
@@ -0,0 +1,172 @@
+//! Relay, throttle, transform and mux nodes integration test.
+//!
+//! Works with both RCL and Zenoh backends.
+
+mod common;
+
+use oxidros::nodes::{Mux, Relay, Throttle, TransformNode};
+use oxidros::prelude::*;
+use oxidros_core::parameter::Parameters;
+use oxidros_msg::common_interfaces::example_interfaces::msg::Int64;
+use oxidros_msg::common_interfaces::std_srvs::srv::{SetBool, SetBool_Request};
+use std::error::Error;
+use std::ops::Deref;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_transform_node() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_transform_node", None)?;
+
+    let publisher = common::create_publisher(node.clone(), "test_transform_in")?;
+    let mut subscriber = common::create_subscriber(node.clone(), "test_transform_out")?;
+
+    // Keep even values and double them
+    let transform = TransformNode::new(
+        &node,
+        &["test_transform_in"],
+        "test_transform_out",
+        |msg: &Int64| Some(msg.data),
+    )?
+    .filter(|data| data % 2 == 0)
+    .map(|data| Int64 { data: data * 2 });
+    let relay = Relay::<_, Int64>::new(&node, "test_transform_out", "test_transform_relayed");
+    let mut relayed = common::create_subscriber(node.clone(), "test_transform_relayed")?;
+
+    let transform = tokio::spawn(transform.run());
+    let relay = tokio::spawn(relay.run());
+
+    // Let the publishers and subscribers discover each other
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    publisher.send(&Int64 { data: 1 })?;
+    publisher.send(&Int64 { data: 2 })?;
+
+    let msg = tokio::time::timeout(Duration::from_secs(2), subscriber.recv()).await??;
+    assert_eq!(msg.deref().data, 4);
+    let msg = tokio::time::timeout(Duration::from_secs(2), relayed.recv()).await??;
+    assert_eq!(msg.deref().data, 4);
+
+    transform.abort();
+    relay.abort();
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_mux() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_mux", None)?;
+
+    let low = common::create_publisher(node.clone(), "test_mux_low")?;
+    let high = common::create_publisher(node.clone(), "test_mux_high")?;
+    let mut output = common::create_subscriber(node.clone(), "test_mux_out")?;
+    let mut lock = node.create_client::<SetBool>("test_mux_lock", None)?;
+
+    let timeout = Duration::from_millis(300);
+    let mux = Mux::<_, Int64>::new(&node, "test_mux_out")
+        .input("test_mux_low", 1, timeout)
+        .input("test_mux_high", 2, timeout)
+        .lock_service("test_mux_lock");
+    let mux = tokio::spawn(mux.run());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let mut recv = async || -> Result<i64, Box<dyn Error + Send + Sync>> {
+        let msg = tokio::time::timeout(Duration::from_secs(2), output.recv()).await??;
+        Ok(msg.deref().data)
+    };
+
+    low.send(&Int64 { data: 1 })?;
+    assert_eq!(recv().await?, 1);
+
+    // The high priority input masks the low priority one until its timeout
+    high.send(&Int64 { data: 2 })?;
+    assert_eq!(recv().await?, 2);
+    low.send(&Int64 { data: 3 })?;
+    high.send(&Int64 { data: 4 })?;
+    assert_eq!(recv().await?, 4);
+    tokio::time::sleep(timeout + Duration::from_millis(100)).await;
+    low.send(&Int64 { data: 5 })?;
+    assert_eq!(recv().await?, 5);
+
+    // Nothing is forwarded while locked
+    let response = tokio::time::timeout(
+        Duration::from_secs(2),
+        lock.call(&SetBool_Request { data: true }),
+    )
+    .await??;
+    assert!(response.success);
+    low.send(&Int64 { data: 6 })?;
+    high.send(&Int64 { data: 7 })?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    tokio::time::timeout(
+        Duration::from_secs(2),
+        lock.call(&SetBool_Request { data: false }),
+    )
+    .await??;
+    tokio::time::sleep(timeout).await;
+    low.send(&Int64 { data: 8 })?;
+    assert_eq!(recv().await?, 8);
+
+    mux.abort();
+    Ok(())
+}
+
+#[test]
+fn test_nodes_from_parameters() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_nodes_from_parameters", None)?;
+
+    let mut params = Parameters::new();
+    params.set_parameter("input_topic".into(), "in".into(), false, None)?;
+    params.set_parameter("output_topic".into(), "out".into(), false, None)?;
+    assert!(Relay::<_, Int64>::from_parameters(&node, &params).is_ok());
+
+    // The rate is missing, then not positive
+    assert!(matches!(
+        Throttle::<_, Int64>::from_parameters(&node, &params),
+        Err(oxidros_core::Error::InvalidConfig(_))
+    ));
+    params.set_parameter("rate".into(), Value::F64(0.0), false, None)?;
+    assert!(matches!(
+        Throttle::<_, Int64>::from_parameters(&node, &params),
+        Err(oxidros_core::Error::InvalidConfig(_))
+    ));
+
+    // The mux arrays must have the same length
+    params.set_parameter(
+        "input_topics".into(),
+        Value::VecString(vec!["nav".into(), "joy".into()]),
+        false,
+        None,
+    )?;
+    params.set_parameter(
+        "priorities".into(),
+        Value::VecI64(vec![10, 100]),
+        false,
+        None,
+    )?;
+    params.set_parameter("timeouts".into(), Value::VecF64(vec![0.5]), false, None)?;
+    assert!(matches!(
+        Mux::<_, Int64>::from_parameters(&node, &params),
+        Err(oxidros_core::Error::InvalidConfig(_))
+    ));
+    params.set_parameter(
+        "timeouts".into(),
+        Value::VecF64(vec![0.5, 0.5]),
+        false,
+        None,
+    )?;
+    assert!(Mux::<_, Int64>::from_parameters(&node, &params).is_ok());
+    params.set_parameter(
+        "priorities".into(),
+        Value::VecI64(vec![10, 1000]),
+        false,
+        None,
+    )?;
+    assert!(matches!(
+        Mux::<_, Int64>::from_parameters(&node, &params),
+        Err(oxidros_core::Error::InvalidConfig(_))
+    ));
+
+    Ok(())
+}
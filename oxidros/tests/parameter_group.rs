@@ -0,0 +1,78 @@
+//! Parameter group mapping tests.
+
+use oxidros::parameter::{Parameters, ParametersExt};
+use serde::Deserialize;
+use std::error::Error;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Limits {
+    max_output: f64,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct ControllerCfg {
+    kp: f64,
+    ki: f64,
+    enabled: bool,
+    limits: Limits,
+}
+
+fn declare(params: &mut Parameters) -> Result<(), Box<dyn Error + Send + Sync>> {
+    params.declare_group("controller", |g| {
+        g.declare("kp", 1.0)
+            .declare("ki", 0.0)
+            .declare("enabled", true);
+        g.group("limits", |g| {
+            g.declare("max_output", 10.0);
+        });
+    })?;
+    Ok(())
+}
+
+#[test]
+fn test_get_struct() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut params = Parameters::new();
+    declare(&mut params)?;
+
+    let cfg: ControllerCfg = params.get_struct("controller")?;
+    assert_eq!(
+        cfg,
+        ControllerCfg {
+            kp: 1.0,
+            ki: 0.0,
+            enabled: true,
+            limits: Limits { max_output: 10.0 },
+        }
+    );
+
+    // missing fields
+    assert!(params.get_struct::<ControllerCfg>("other").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_load_struct_updates() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut params = Parameters::new();
+    declare(&mut params)?;
+    params.set_parameter("gain".into(), 3.0.into(), false, None)?;
+    params.take_updated();
+
+    let cfg = params.load_struct::<ControllerCfg>("controller")?;
+    assert_eq!(cfg.get().kp, 1.0);
+
+    // a parameter outside of the group does not rebuild the struct
+    params.set_parameter("gain".into(), 4.0.into(), false, None)?;
+    let updated = params.take_updated();
+    assert!(!cfg.update(&params, &updated)?);
+
+    params.set_parameter(
+        "controller.limits.max_output".into(),
+        5.0.into(),
+        false,
+        None,
+    )?;
+    let updated = params.take_updated();
+    assert!(cfg.update(&params, &updated)?);
+    assert_eq!(cfg.get().limits.max_output, 5.0);
+    Ok(())
+}
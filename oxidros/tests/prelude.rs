@@ -0,0 +1,44 @@
+//! Prelude integration test.
+//!
+//! The code below only uses names from the prelude, and compiles unchanged
+//! with both RCL and Zenoh backends.
+
+use oxidros::prelude::*;
+use oxidros_msg::common_interfaces::example_interfaces::{msg::Int64, srv::AddTwoInts};
+use std::time::Duration;
+
+struct Entities {
+    publisher: Publisher<Int64>,
+    subscriber: Subscriber<Int64>,
+    _client: Client<AddTwoInts>,
+    _server: Server<AddTwoInts>,
+}
+
+fn create_entities(node: &Arc<Node>) -> Result<Entities> {
+    Ok(Entities {
+        publisher: node.create_publisher("test_prelude_topic", None)?,
+        subscriber: node.create_subscriber("test_prelude_topic", None)?,
+        _client: node.create_client("test_prelude_service", None)?,
+        _server: node.create_server("test_prelude_service", None)?,
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_prelude() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_prelude_node", None)?;
+    let _selector: Selector = ctx.create_selector()?;
+
+    let entities = create_entities(&node)?;
+    let mut stream: MessageStream<Int64> = entities.subscriber.into_stream();
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    entities.publisher.send(&Int64 { data: 7 })?;
+
+    let msg = tokio::time::timeout(Duration::from_secs(3), stream.next())
+        .await?
+        .expect("stream closed")?;
+    assert_eq!(msg.data, 7);
+
+    Ok(())
+}
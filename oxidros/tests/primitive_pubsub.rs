@@ -0,0 +1,38 @@
+//! Publish/Subscribe of primitive types integration test.
+//!
+//! Tests publishers and subscribers of Rust primitive types using the unified API.
+//! Works with both RCL and Zenoh backends.
+
+mod common;
+
+use oxidros::prelude::*;
+use std::error::Error;
+use std::ops::Deref;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_primitive_pubsub() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_primitive_pubsub", None)?;
+
+    let publisher = node.create_primitive_publisher::<f64>("test_primitive_f64", None)?;
+    let mut subscriber = node.create_primitive_subscriber::<f64>("test_primitive_f64", None)?;
+
+    let text_publisher = node.create_primitive_publisher::<String>("test_primitive_str", None)?;
+    let mut text_subscriber =
+        node.create_primitive_subscriber::<String>("test_primitive_str", None)?;
+
+    // Let the publishers and subscribers discover each other
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    publisher.send(&21.5)?;
+    text_publisher.send(&"ok".to_string())?;
+
+    let value = tokio::time::timeout(Duration::from_secs(2), subscriber.recv()).await??;
+    assert_eq!(*value.deref(), 21.5);
+
+    let text = tokio::time::timeout(Duration::from_secs(2), text_subscriber.recv()).await??;
+    assert_eq!(text.deref(), "ok");
+
+    Ok(())
+}
@@ -84,3 +84,55 @@ fn test_pubsub_multiple_messages() -> Result<(), Box<dyn Error + Send + Sync>> {
     assert_ne!(COUNT.load(std::sync::atomic::Ordering::Relaxed), 0);
     Ok(())
 }
+
+/// Entities created from `&self` through a node handle stored in a struct.
+struct Relay {
+    node: NodeHandle<Node>,
+    publishers: Vec<Publisher<Int64>>,
+}
+
+impl Relay {
+    fn add_output(&mut self, topic: &str) -> oxidros_core::Result<()> {
+        self.publishers
+            .push(self.node.create_publisher(topic, None)?);
+        Ok(())
+    }
+
+    fn send(&self, data: i64) -> oxidros_core::Result<()> {
+        for publisher in &self.publishers {
+            publisher.send(&Int64 { data })?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_pubsub_node_handle() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_handle_node", None)?;
+
+    let handle = node.handle();
+    assert_eq!(handle.fully_qualified_name()?, "/test_handle_node");
+    let subscriber: Subscriber<Int64> = handle.create_subscriber("test_handle_pubsub", None)?;
+
+    let mut relay = Relay {
+        node: handle,
+        publishers: Vec::new(),
+    };
+    relay.add_output("test_handle_pubsub")?;
+    relay.send(7)?;
+
+    let mut selector = ctx.create_selector()?;
+    static COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    selector.add_subscriber(
+        subscriber,
+        Box::new(|msg: Message<Int64>| {
+            assert_eq!(msg.data, 7);
+            COUNT.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+        }),
+    );
+    selector.wait_timeout(Duration::from_secs(2))?;
+
+    assert_ne!(COUNT.load(std::sync::atomic::Ordering::Relaxed), 0);
+    Ok(())
+}
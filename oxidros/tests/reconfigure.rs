@@ -0,0 +1,65 @@
+//! Reconfigurable entities integration test.
+//!
+//! Works with both RCL and Zenoh backends.
+
+mod common;
+
+use oxidros::prelude::*;
+use oxidros::reconfigure::Reconfigurable;
+use oxidros_core::parameter::Parameters;
+use oxidros_msg::common_interfaces::example_interfaces::msg::Int64;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reconfigurable_publisher() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_reconfigurable_publisher", None)?;
+
+    let mut params = Parameters::new();
+    params.set_parameter("output_topic".into(), "reconf_a".into(), false, None)?;
+
+    let mut publisher = Reconfigurable::new(&params, &["output_topic"], {
+        let node = node.clone();
+        move |params| match params.get_parameter("output_topic").map(|p| &p.value) {
+            Some(Value::String(topic)) if !topic.is_empty() => {
+                node.create_publisher::<Int64>(topic, None)
+            }
+            _ => Err(oxidros_core::Error::InvalidConfig("output_topic".into())),
+        }
+    })?;
+    assert!(publisher.get().topic_name()?.ends_with("reconf_a"));
+
+    // An unrelated parameter does not rebuild the publisher
+    let updated = BTreeSet::from(["publish_rate".to_string()]);
+    assert!(!publisher.update(&params, &updated)?);
+
+    let mut watcher = publisher.clone();
+    let changed = tokio::spawn(async move {
+        watcher.changed().await;
+        watcher.get().topic_name().map(|name| name.into_owned())
+    });
+
+    params.set_parameter("output_topic".into(), "reconf_b".into(), false, None)?;
+    let updated = params.take_updated();
+    assert!(publisher.update(&params, &updated)?);
+    assert!(publisher.get().topic_name()?.ends_with("reconf_b"));
+
+    let topic = tokio::time::timeout(Duration::from_secs(2), changed).await???;
+    assert!(topic.ends_with("reconf_b"));
+
+    // A failed rebuild keeps the previous publisher
+    params.set_parameter("output_topic".into(), "".into(), false, None)?;
+    let updated = params.take_updated();
+    assert!(publisher.update(&params, &updated).is_err());
+    assert!(publisher.get().topic_name()?.ends_with("reconf_b"));
+
+    // Each handle tracks the changes it has seen
+    tokio::time::timeout(Duration::from_millis(10), publisher.changed()).await?;
+    tokio::time::timeout(Duration::from_millis(10), publisher.changed())
+        .await
+        .unwrap_err();
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+//! Graceful shutdown integration test.
+//!
+//! Tests that a publishing loop stops on a shutdown token and that the last
+//! message is delivered after flushing. Works with both RCL and Zenoh backends.
+
+mod common;
+
+use oxidros::prelude::*;
+use oxidros_msg::common_interfaces::example_interfaces::msg::Int64;
+use std::error::Error;
+use std::ops::Deref;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_shutdown_drain() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_shutdown_drain", None)?;
+
+    let publisher = common::create_publisher(node.clone(), "test_shutdown_drain_topic")?;
+    let mut subscriber = common::create_subscriber(node.clone(), "test_shutdown_drain_topic")?;
+
+    // Let the publisher and subscriber discover each other
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let shutdown = ShutdownToken::new();
+    let trigger = shutdown.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        trigger.shutdown();
+    });
+
+    let mut interval = tokio::time::interval(Duration::from_millis(20));
+    loop {
+        tokio::select! {
+            _ = shutdown.wait() => break,
+            _ = interval.tick() => {}
+        }
+    }
+    assert!(shutdown.is_shutdown());
+
+    // Publish the final state and make sure it is delivered
+    publisher.send(&Int64 { data: 42 })?;
+    tokio::time::timeout(Duration::from_secs(2), publisher.flush()).await??;
+
+    let received = tokio::time::timeout(Duration::from_secs(2), subscriber.recv()).await??;
+    assert_eq!(received.deref().data, 42);
+
+    Ok(())
+}
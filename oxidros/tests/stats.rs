@@ -0,0 +1,78 @@
+//! Node statistics integration tests.
+//!
+//! Works with both RCL and Zenoh backends.
+
+mod common;
+
+use oxidros::prelude::*;
+use oxidros_msg::common_interfaces::std_msgs::msg::String as StringMsg;
+use std::error::Error;
+
+/// Publishers, subscribers, servers and clients of `node`.
+fn entity_counts(node: &Node) -> (usize, usize, usize, usize) {
+    let stats = node.stats();
+    (
+        stats.publishers,
+        stats.subscribers,
+        stats.servers,
+        stats.clients,
+    )
+}
+
+#[test]
+fn test_node_stats_counts() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_node_stats", None)?;
+
+    assert_eq!(entity_counts(&node), (0, 0, 0, 0));
+
+    let publisher = node.create_publisher::<StringMsg>("test_node_stats", None)?;
+    let subscriber = node.create_subscriber::<StringMsg>("test_node_stats", None)?;
+    let server = common::create_server(node.clone(), "test_node_stats_service")?;
+    let client = common::create_client(node.clone(), "test_node_stats_service")?;
+
+    assert_eq!(entity_counts(&node), (1, 1, 1, 1));
+
+    drop(publisher);
+    drop(subscriber);
+    drop(server);
+    drop(client);
+
+    assert_eq!(entity_counts(&node), (0, 0, 0, 0));
+
+    Ok(())
+}
+
+#[cfg(feature = "stats")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stats_publisher() -> Result<(), Box<dyn Error + Send + Sync>> {
+    use oxidros::stats::StatsPublisher;
+    use oxidros_msg::common_interfaces::diagnostic_msgs::msg::DiagnosticStatus;
+    use std::time::Duration;
+
+    let ctx = Context::new()?;
+    let node = ctx.create_node("test_stats_publisher", None)?;
+    let monitor = ctx.create_node("test_stats_monitor", None)?;
+    let mut subscriber =
+        monitor.create_subscriber::<DiagnosticStatus>("/test_stats_publisher/_events", None)?;
+    let _publisher = node.create_publisher::<StringMsg>("test_stats_publisher", None)?;
+
+    let task = tokio::spawn(
+        StatsPublisher::new(&node)
+            .period(Duration::from_millis(100))
+            .run(),
+    );
+
+    let msg = tokio::time::timeout(Duration::from_secs(3), subscriber.recv()).await??;
+    assert_eq!(msg.name.get_string(), "/test_stats_publisher");
+    let values: Vec<_> = msg
+        .values
+        .iter()
+        .map(|kv| (kv.key.get_string(), kv.value.get_string()))
+        .collect();
+    assert!(values.contains(&("publishers".to_string(), "1".to_string())));
+    assert!(values.contains(&("subscribers".to_string(), "0".to_string())));
+
+    task.abort();
+    Ok(())
+}
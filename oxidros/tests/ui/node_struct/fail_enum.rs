@@ -0,0 +1,9 @@
+use oxidros::prelude::*;
+
+#[derive(RosNodeStruct)]
+enum Mode {
+    Idle,
+    Running,
+}
+
+fn main() {}
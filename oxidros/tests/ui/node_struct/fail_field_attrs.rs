@@ -0,0 +1,10 @@
+use oxidros::prelude::*;
+
+#[derive(RosNodeStruct)]
+struct Counter {
+    #[publisher(topic = "count")]
+    #[parameter(default = 0)]
+    count: i64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use oxidros::prelude::*;
+
+#[derive(RosNodeStruct)]
+#[timer(period = "1s", handler = Self::on_tick)]
+struct Ticker {
+    ticks: u64,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use oxidros::prelude::*;
+
+#[derive(RosNodeStruct)]
+#[subscriber(msg = Int64, handler = on_msg)]
+struct Listener {
+    #[publisher(qos = Default::default())]
+    out: u64,
+}
+
+fn main() {}
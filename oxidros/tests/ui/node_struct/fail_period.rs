@@ -0,0 +1,11 @@
+use oxidros::prelude::*;
+
+#[derive(RosNodeStruct)]
+#[timer(period = "100", handler = on_tick)]
+#[timer(period = "0ms", handler = on_tick)]
+#[timer(period = "10h", handler = on_tick)]
+struct Ticker {
+    ticks: u64,
+}
+
+fn main() {}
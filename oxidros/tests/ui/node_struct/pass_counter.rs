@@ -0,0 +1,47 @@
+use oxidros::msg::common_interfaces::{example_interfaces, std_msgs};
+use oxidros::prelude::*;
+
+#[derive(RosNodeStruct)]
+#[subscriber(topic = "chatter", msg = std_msgs::msg::String, handler = on_chatter)]
+#[timer(period = "1s", handler = on_tick)]
+struct Counter {
+    #[publisher(topic = "count")]
+    count_pub: Publisher<example_interfaces::msg::Int64>,
+    #[parameter(default = 1i64, description = "Increment per message")]
+    step: i64,
+    count: i64,
+}
+
+impl Counter {
+    fn on_chatter(&mut self, _msg: Message<std_msgs::msg::String>) {
+        self.count += self.step;
+    }
+
+    fn on_tick(&mut self) {
+        let msg = example_interfaces::msg::Int64 { data: self.count };
+        let _ = self.count_pub.send(&msg);
+    }
+}
+
+#[derive(RosNodeStruct)]
+#[timer(period = "250 ms", handler = "on_tick")]
+struct Ticker {
+    #[parameter(name = "tick_limit", read_only)]
+    limit: i64,
+    #[parameter]
+    verbose: bool,
+    ticks: i64,
+}
+
+impl Ticker {
+    fn on_tick(&mut self) {
+        if self.ticks < self.limit {
+            self.ticks += 1;
+        }
+        if self.verbose {
+            println!("tick {}", self.ticks);
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,37 @@
+//! Slow poll detection tests.
+
+#![cfg(feature = "watchdog")]
+
+use oxidros::watchdog::{Watched, spawn_ros_task};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_slow_poll_detected() {
+    let mut task = Watched::new("test_slow_task", async {
+        // blocking call inside an async task
+        std::thread::sleep(Duration::from_millis(50));
+        tokio::task::yield_now().await;
+        42
+    })
+    .threshold(Duration::from_millis(10));
+
+    assert_eq!((&mut task).await, 42);
+    assert_eq!(task.name(), "test_slow_task");
+    assert_eq!(task.slow_polls(), 1);
+}
+
+#[tokio::test]
+async fn test_fast_polls() {
+    let mut task = Watched::new("test_fast_task", async {
+        for _ in 0..3 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .threshold(Duration::from_millis(10));
+
+    (&mut task).await;
+    assert_eq!(task.slow_polls(), 0);
+
+    let handle = spawn_ros_task("test_spawned_task", async { 7 });
+    assert_eq!(handle.await.unwrap(), 7);
+}
@@ -87,6 +87,15 @@ pub struct Ros2FieldOpts {
     /// Default value for the field
     #[darling(default)]
     pub default: Option<String>,
+
+    /// Unit of the field (e.g., "m/s"), queried with `TypeDescription::field_unit`
+    #[darling(default)]
+    pub unit: Option<String>,
+
+    /// Mark this field as part of the key of the message, see `ros2_types::Keyed`.
+    /// Use `#[ros2(key)]` on the field (flag-style).
+    #[darling(default)]
+    pub key: bool,
 }
 
 /// Parse fields from a struct's data
@@ -11,6 +11,15 @@
 //!
 //! - `rcl`: Enable FFI code generation for ROS2 C libraries. When disabled, generates
 //!   pure Rust implementations (Clone, Default, PartialEq, Eq).
+//! - `lazy-typesupport`: With `rcl`, resolve the functions of the rosidl libraries
+//!   at their first call instead of linking them (see `ros2_types::typesupport`).
+//! - `arbitrary`: Implement `arbitrary::Arbitrary` for the sequence types
+//!   generated by `Ros2Msg`, for property tests.
+//!
+//! These are features of the crate using the macros, not of this crate. A crate
+//! which does not define one of them declares it as an expected cfg value, e.g.
+//! `unexpected_cfgs = { level = "warn", check-cfg = ['cfg(feature, values("arbitrary"))'] }`
+//! in its `[lints.rust]` table.
 //!
 //! # Container Attributes
 //!
@@ -22,6 +31,8 @@
 //! - `#[ros2(ros2_type = "byte")]` - Override field type (for byte, char, wstring)
 //! - `#[ros2(capacity = 255)]` - Specify capacity for bounded strings/sequences
 //! - `#[ros2(default = "0")]` - Specify default value
+//! - `#[ros2(unit = "m/s")]` - Specify the unit of the field
+//! - `#[ros2(key)]` - Mark the field as part of the key of the message (`Ros2Msg`)
 //!
 //! # Message Example
 //!
@@ -114,6 +125,7 @@ use syn::{DeriveInput, parse_macro_input};
 /// - `#[ros2(ros2_type = "byte")]` - Override field type (for byte, char, wstring)
 /// - `#[ros2(capacity = 255)]` - Specify capacity for bounded strings/sequences
 /// - `#[ros2(default = "0")]` - Specify default value
+/// - `#[ros2(unit = "m/s")]` - Specify the unit, returned by `TypeDescription::field_unit`
 ///
 /// # Example
 ///
@@ -156,6 +168,7 @@ pub fn derive_type_description(input: TokenStream) -> TokenStream {
 /// # Field Attributes
 ///
 /// - `#[ros2(default = "value")]` - Specify default value for pure Rust Default impl
+/// - `#[ros2(key)]` - Mark the field as part of the key of the message
 ///
 /// # Example
 ///
@@ -181,6 +194,19 @@ pub fn derive_type_description(input: TokenStream) -> TokenStream {
 /// - Default implementation
 /// - Clone implementation
 /// - PartialEq and Eq implementations
+///
+/// With the `arbitrary` feature of the crate using the macro, the sequence
+/// wrapper type also implements `arbitrary::Arbitrary`, for the messages
+/// deriving it.
+///
+/// Messages with a `header` field of type `Header` also implement
+/// `ros2_types::Stamped`, and messages with `#[ros2(key)]` fields implement
+/// `ros2_types::Keyed`, with the tuple of the key fields as key.
+///
+/// With the `lazy-typesupport` feature of the crate using the macro, the C
+/// functions are resolved at their first call by `ros2_types::lazy_ffi!`
+/// instead of being declared `extern "C"`, and `TypeSupport::try_type_support`
+/// reports a missing package as an error.
 #[proc_macro_derive(Ros2Msg, attributes(ros2))]
 pub fn derive_ros2_msg(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -68,6 +68,8 @@ pub fn derive_ros2_msg_impl(input: DeriveInput) -> Result<TokenStream, syn::Erro
     let rcl_impl = generate_rcl_impl(&opts, &field_opts);
     let pure_impl = generate_pure_impl(&opts, &field_opts);
     let common_impl = generate_common_impl(&opts);
+    let stamped_impl = generate_stamped_impl(&opts, &field_opts);
+    let keyed_impl = generate_keyed_impl(&opts, &field_opts);
 
     // Generate service/action wrappers (must be at module level, not inside const _)
     let wrapper_impl = generate_wrapper_impl(&opts);
@@ -75,6 +77,8 @@ pub fn derive_ros2_msg_impl(input: DeriveInput) -> Result<TokenStream, syn::Erro
     let expanded = quote! {
         // Common implementations (always generated)
         #common_impl
+        #stamped_impl
+        #keyed_impl
 
         #[cfg(feature = "rcl")]
         const _: () = {
@@ -121,6 +125,25 @@ fn generate_serialization_ffi_decls() -> TokenStream {
     }
 }
 
+/// Generate the declarations of functions of the rosidl `library` of `package`.
+///
+/// They are `extern "C"` functions linked into the binary, or resolved at their
+/// first call with the `lazy-typesupport` feature (see `ros2_types::typesupport`).
+fn generate_package_ffi_decls(package: &str, library: &str, decls: TokenStream) -> TokenStream {
+    quote! {
+        #[cfg(all(feature = "rcl", not(feature = "lazy-typesupport")))]
+        unsafe extern "C" {
+            #decls
+        }
+
+        #[cfg(all(feature = "rcl", feature = "lazy-typesupport"))]
+        ros2_types::lazy_ffi! {
+            #package, #library;
+            #decls
+        }
+    }
+}
+
 /// Generate the rcl_serialized_message_t struct definition.
 ///
 /// This is the C struct used for serialization/deserialization.
@@ -223,6 +246,56 @@ fn generate_rcl_impl(opts: &Ros2TypeOpts, _field_opts: &[Ros2FieldOpts]) -> Toke
     generate_rcl_base_impl(name, package, interface_type)
 }
 
+/// Generate the `Stamped` implementation of messages with a `header` field of
+/// type `Header`, e.g. `std_msgs/msg/Header`.
+fn generate_stamped_impl(opts: &Ros2TypeOpts, field_opts: &[Ros2FieldOpts]) -> TokenStream {
+    let name = &opts.ident;
+    let has_header = field_opts.iter().any(|f| {
+        f.ident.as_ref().is_some_and(|ident| ident == "header")
+            && matches!(&f.ty, syn::Type::Path(path)
+                if path.path.segments.last().is_some_and(|seg| seg.ident == "Header"))
+    });
+    if !has_header {
+        return quote! {};
+    }
+
+    quote! {
+        impl ros2_types::Stamped for #name {
+            fn stamp(&self) -> ros2_types::UnsafeTime {
+                ros2_types::UnsafeTime::new(self.header.stamp.sec, self.header.stamp.nanosec)
+            }
+
+            fn set_stamp(&mut self, stamp: ros2_types::UnsafeTime) {
+                self.header.stamp.sec = stamp.sec;
+                self.header.stamp.nanosec = stamp.nanosec;
+            }
+        }
+    }
+}
+
+/// Generate the `Keyed` implementation for the messages with `#[ros2(key)]` fields
+fn generate_keyed_impl(opts: &Ros2TypeOpts, field_opts: &[Ros2FieldOpts]) -> TokenStream {
+    let name = &opts.ident;
+    let (names, types): (Vec<_>, Vec<_>) = field_opts
+        .iter()
+        .filter(|f| f.key)
+        .filter_map(|f| f.ident.as_ref().map(|ident| (ident, &f.ty)))
+        .unzip();
+    if names.is_empty() {
+        return quote! {};
+    }
+
+    quote! {
+        impl ros2_types::Keyed for #name {
+            type Key = (#(<#types as ros2_types::KeyField>::Key,)*);
+
+            fn key(&self) -> Self::Key {
+                (#(ros2_types::KeyField::key_value(&self.#names),)*)
+            }
+        }
+    }
+}
+
 /// Generate common implementations that are always needed (regardless of rcl feature)
 fn generate_common_impl(opts: &Ros2TypeOpts) -> TokenStream {
     let name = &opts.ident;
@@ -265,6 +338,25 @@ fn generate_common_impl(opts: &Ros2TypeOpts) -> TokenStream {
         unsafe impl<const N: usize> Send for #seq_type<N> {}
         unsafe impl<const N: usize> Sync for #seq_type<N> {}
 
+        /// Random sequences of at most `N` elements, for property tests.
+        #[cfg(feature = "arbitrary")]
+        impl<'a, const N: usize> arbitrary::Arbitrary<'a> for #seq_type<N>
+        where
+            #name: arbitrary::Arbitrary<'a> + Default,
+        {
+            fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                let mut len = u.arbitrary_len::<#name>()?;
+                if N != 0 {
+                    len = len.min(N);
+                }
+                let mut seq = Self::new(len).ok_or(arbitrary::Error::IncorrectFormat)?;
+                for elem in seq.as_mut_slice() {
+                    *elem = u.arbitrary()?;
+                }
+                Ok(seq)
+            }
+        }
+
         /// TypeDescription for sequence types delegates to the element type.
         /// This enables proper type hash computation when XxxSeq<N> is used in fields.
         impl<const N: usize> ros2_types::TypeDescription for #seq_type<N> {
@@ -328,9 +420,10 @@ fn generate_rcl_base_impl(name: &syn::Ident, package: &str, interface_type: &str
     let serialization_ffi = generate_serialization_ffi_decls();
     let serialized_msg_struct = generate_serialized_message_struct();
 
-    quote! {
-        // FFI function declarations
-        unsafe extern "C" {
+    let generator_ffi = generate_package_ffi_decls(
+        package,
+        "rosidl_generator_c",
+        quote! {
             fn #init_fn(msg: *mut #name) -> bool;
             fn #fini_fn(msg: *mut #name);
             fn #are_equal_fn(lhs: *const #name, rhs: *const #name) -> bool;
@@ -339,7 +432,21 @@ fn generate_rcl_base_impl(name: &syn::Ident, package: &str, interface_type: &str
             fn #seq_fini_fn(msg: *mut #seq_raw_type);
             fn #seq_are_equal_fn(lhs: *const #seq_raw_type, rhs: *const #seq_raw_type) -> bool;
             fn #seq_copy_fn(lhs: *const #seq_raw_type, rhs: *mut #seq_raw_type) -> bool;
+        },
+    );
+    let type_support_ffi = generate_package_ffi_decls(
+        package,
+        "rosidl_typesupport_c",
+        quote! {
             fn #type_support_fn() -> *const std::ffi::c_void;
+        },
+    );
+
+    quote! {
+        // FFI function declarations
+        #generator_ffi
+        #type_support_ffi
+        unsafe extern "C" {
             #serialization_ffi
         }
 
@@ -350,9 +457,12 @@ fn generate_rcl_base_impl(name: &syn::Ident, package: &str, interface_type: &str
         impl #name {
             /// Create a new instance initialized by the ROS2 C library
             pub fn new() -> Option<Self> {
-                let mut msg = unsafe { std::mem::zeroed() };
-                if unsafe { #init_fn(&mut msg) } {
-                    Some(msg)
+                // A failed init has already finalized the members it touched,
+                // so the message must not be dropped again.
+                let mut msg: std::mem::ManuallyDrop<Self> =
+                    std::mem::ManuallyDrop::new(unsafe { std::mem::zeroed() });
+                if unsafe { #init_fn(&mut *msg) } {
+                    Some(std::mem::ManuallyDrop::into_inner(msg))
                 } else {
                     None
                 }
@@ -415,31 +525,37 @@ fn generate_rcl_base_impl(name: &syn::Ident, package: &str, interface_type: &str
                     None
                 }
             }
+
+            /// Whether the C runtime allocated the elements.
+            ///
+            /// Sequences created by `null()`, or whose initialization failed,
+            /// own nothing and are never finalized.
+            pub fn is_initialized(&self) -> bool {
+                !self.0.data.is_null()
+            }
         }
 
         impl<const N: usize> Drop for #seq_type<N> {
             fn drop(&mut self) {
-                unsafe { #seq_fini_fn(std::ops::DerefMut::deref_mut(self)) };
+                if !self.is_initialized() {
+                    return;
+                }
+                unsafe { #seq_fini_fn(&mut self.0) };
             }
         }
 
         impl<const N: usize> PartialEq for #seq_type<N> {
             fn eq(&self, other: &Self) -> bool {
-                unsafe {
-                    let msg1 = #seq_raw_type { data: self.data, size: self.size, capacity: self.capacity };
-                    let msg2 = #seq_raw_type { data: other.data, size: other.size, capacity: other.capacity };
-                    #seq_are_equal_fn(&msg1, &msg2)
-                }
+                unsafe { #seq_are_equal_fn(&self.0, &other.0) }
             }
         }
 
         impl<const N: usize> ros2_types::TryClone for #seq_type<N> {
             fn try_clone(&self) -> Option<Self> {
+                // Copy in place: the copy may reallocate the data, and a failed
+                // copy leaves `result` in a state its Drop can still finalize.
                 let mut result = Self::new(self.size)?;
-                let msg1 = #seq_raw_type { data: self.data, size: self.size, capacity: self.capacity };
-                let mut msg2 = #seq_raw_type { data: result.data, size: result.size, capacity: result.capacity };
-                if unsafe { #seq_copy_fn(&msg1, &mut msg2) } {
-                    result.0 = msg2;
+                if unsafe { #seq_copy_fn(&self.0, &mut result.0) } {
                     Some(result)
                 } else {
                     None
@@ -468,17 +584,23 @@ fn generate_pure_impl(opts: &Ros2TypeOpts, field_opts: &[Ros2FieldOpts]) -> Toke
     let name = &opts.ident;
 
     // Generate Default implementation
-    // Note: The `#[ros2(default = "...")]` attribute is for TypeDescription metadata only,
-    // not for generating Default impl. This matches the rcl behavior where Default calls
-    // the C library's init function (which handles defaults internally).
-    // For pure Rust, we just use Default::default() for all fields.
+    // Fields with a `#[ros2(default = "...")]` attribute are initialized from it, matching
+    // the rcl behavior where Default calls the C library's init function (which applies
+    // the defaults of the interface definition). Other fields use Default::default().
     let default_fields: Vec<_> = field_opts
         .iter()
         .map(|f| {
             let field_name = f.ident.as_ref().unwrap();
+            let ty = &f.ty;
             // Check if the type is a large array (size > 32) which doesn't impl Default
-            let default_expr = get_default_expr_for_type(&f.ty);
-            quote! { #field_name: #default_expr }
+            let default_expr = get_default_expr_for_type(ty);
+            match &f.default {
+                Some(literal) => quote! {
+                    #field_name: <#ty as ros2_types::FromDefaultLiteral>::from_default_literal(#literal)
+                        .unwrap_or_else(|| #default_expr)
+                },
+                None => quote! { #field_name: #default_expr },
+            }
         })
         .collect();
 
@@ -524,6 +646,8 @@ fn generate_pure_impl(opts: &Ros2TypeOpts, field_opts: &[Ros2FieldOpts]) -> Toke
 
         #ts_impl
 
+        // Fields may be #[deprecated] by a comment of the interface definition
+        #[allow(deprecated)]
         impl Default for #name {
             fn default() -> Self {
                 Self {
@@ -532,6 +656,7 @@ fn generate_pure_impl(opts: &Ros2TypeOpts, field_opts: &[Ros2FieldOpts]) -> Toke
             }
         }
 
+        #[allow(deprecated)]
         impl Clone for #name {
             fn clone(&self) -> Self {
                 Self {
@@ -547,6 +672,7 @@ fn generate_pure_impl(opts: &Ros2TypeOpts, field_opts: &[Ros2FieldOpts]) -> Toke
             }
         }
 
+        #[allow(deprecated)]
         impl PartialEq for #name {
             fn eq(&self, other: &Self) -> bool {
                 #eq_body
@@ -674,6 +800,18 @@ fn generate_native_type_support_impl(
                 <Self as ros2_types::CdrSerde>::serialize(self)
             }
 
+            fn serialized_size(&self) -> usize {
+                <Self as ros2_types::CdrSerde>::serialized_size(self).unwrap_or(0)
+            }
+
+            fn to_bytes_into(&self, buf: &mut [u8]) -> ros2_types::Result<usize> {
+                <Self as ros2_types::CdrSerde>::serialize_into_slice(self, buf)
+            }
+
+            fn to_bytes_with(&self, buf: &mut Vec<u8>) -> ros2_types::Result<()> {
+                <Self as ros2_types::CdrSerde>::serialize_into(self, buf)
+            }
+
             fn from_bytes(bytes: &[u8]) -> ros2_types::Result<Self> {
                 <Self as ros2_types::CdrSerde>::deserialize(bytes)
             }
@@ -706,6 +844,18 @@ fn generate_native_type_support_impl_no_hash(
                 <Self as ros2_types::CdrSerde>::serialize(self)
             }
 
+            fn serialized_size(&self) -> usize {
+                <Self as ros2_types::CdrSerde>::serialized_size(self).unwrap_or(0)
+            }
+
+            fn to_bytes_into(&self, buf: &mut [u8]) -> ros2_types::Result<usize> {
+                <Self as ros2_types::CdrSerde>::serialize_into_slice(self, buf)
+            }
+
+            fn to_bytes_with(&self, buf: &mut Vec<u8>) -> ros2_types::Result<()> {
+                <Self as ros2_types::CdrSerde>::serialize_into(self, buf)
+            }
+
             fn from_bytes(bytes: &[u8]) -> ros2_types::Result<Self> {
                 <Self as ros2_types::CdrSerde>::deserialize(bytes)
             }
@@ -732,6 +882,7 @@ fn generate_rcl_type_support_impl(
         name
     );
     let dds_lit = syn::LitStr::new(&dds_type_name, proc_macro2::Span::call_site());
+    let type_support_name = type_support_fn.to_string();
     quote! {
         // TypeSupport implementation
         impl ros2_types::TypeSupport for #name {
@@ -739,6 +890,11 @@ fn generate_rcl_type_support_impl(
                 unsafe { #type_support_fn() }
             }
 
+            #[cfg(feature = "lazy-typesupport")]
+            fn try_type_support() -> ros2_types::Result<*const std::ffi::c_void> {
+                unsafe { ros2_types::typesupport::type_support_handle(#package, #type_support_name) }
+            }
+
             fn to_bytes(&self) -> ros2_types::Result<Vec<u8>> {
                 let ts = Self::type_support();
                 let mut msg_buf: rcl_serialized_message_t = unsafe { rcutils_get_zero_initialized_uint8_array() };
@@ -810,6 +966,15 @@ pub fn generate_service_wrapper(package: &str, service_name: &str) -> TokenStrea
         service_name
     );
 
+    let type_support_name = type_support_fn.to_string();
+    let type_support_ffi = generate_package_ffi_decls(
+        package,
+        "rosidl_typesupport_c",
+        quote! {
+            fn #type_support_fn() -> *const std::ffi::c_void;
+        },
+    );
+
     let service_doc = format!("Service wrapper for {}", service_name);
     let dds_type_name = format!("{}::srv::dds_::{}_", package, service_name);
     quote! {
@@ -818,10 +983,7 @@ pub fn generate_service_wrapper(package: &str, service_name: &str) -> TokenStrea
         #[ros2(package = #package)]
         pub struct #service_ident;
 
-        #[cfg(feature = "rcl")]
-        unsafe extern "C" {
-            fn #type_support_fn() -> *const std::ffi::c_void;
-        }
+        #type_support_ffi
 
         impl ros2_types::ServiceMsg for #service_ident {
             type Request = #request_ident;
@@ -830,6 +992,10 @@ pub fn generate_service_wrapper(package: &str, service_name: &str) -> TokenStrea
             fn type_support() -> *const std::ffi::c_void {
                 unsafe { #type_support_fn() }
             }
+            #[cfg(all(feature = "rcl", feature = "lazy-typesupport"))]
+            fn try_type_support() -> ros2_types::Result<*const std::ffi::c_void> {
+                unsafe { ros2_types::typesupport::type_support_handle(#package, #type_support_name) }
+            }
             #[cfg(not(feature = "rcl"))]
             fn type_hash() -> ros2_types::Result<::std::string::String> {
                 <Self as ros2_types::ServiceTypeDescription>::compute_hash()
@@ -958,6 +1124,22 @@ pub fn generate_action_wrapper(
     let ts_feedback_message_impl_native =
         generate_native_type_support_impl_no_hash(&feedback_message_ident, package, "action");
 
+    let action_type_support_name = action_type_support_fn.to_string();
+    let type_support_ffi = generate_package_ffi_decls(
+        package,
+        "rosidl_typesupport_c",
+        quote! {
+            fn #action_type_support_fn() -> *const std::ffi::c_void;
+            fn #send_goal_type_support_fn() -> *const std::ffi::c_void;
+            fn #get_result_type_support_fn() -> *const std::ffi::c_void;
+            // Message type supports for helper types
+            fn #send_goal_request_type_support_fn() -> *const std::ffi::c_void;
+            fn #send_goal_response_type_support_fn() -> *const std::ffi::c_void;
+            fn #get_result_request_type_support_fn() -> *const std::ffi::c_void;
+            fn #get_result_response_type_support_fn() -> *const std::ffi::c_void;
+            fn #feedback_message_type_support_fn() -> *const std::ffi::c_void;
+        },
+    );
     let serialization_ffi = generate_serialization_ffi_decls();
     let serialized_msg_struct = generate_serialized_message_struct();
 
@@ -966,17 +1148,10 @@ pub fn generate_action_wrapper(
         // FFI declarations
         // =============================================================================
 
+        #type_support_ffi
+
         #[cfg(feature = "rcl")]
         unsafe extern "C" {
-            fn #action_type_support_fn() -> *const std::ffi::c_void;
-            fn #send_goal_type_support_fn() -> *const std::ffi::c_void;
-            fn #get_result_type_support_fn() -> *const std::ffi::c_void;
-            // Message type supports for helper types
-            fn #send_goal_request_type_support_fn() -> *const std::ffi::c_void;
-            fn #send_goal_response_type_support_fn() -> *const std::ffi::c_void;
-            fn #get_result_request_type_support_fn() -> *const std::ffi::c_void;
-            fn #get_result_response_type_support_fn() -> *const std::ffi::c_void;
-            fn #feedback_message_type_support_fn() -> *const std::ffi::c_void;
             #serialization_ffi
         }
 
@@ -1160,6 +1335,13 @@ pub fn generate_action_wrapper(
                 unsafe { #action_type_support_fn() }
             }
 
+            #[cfg(all(feature = "rcl", feature = "lazy-typesupport"))]
+            fn try_type_support() -> ros2_types::Result<*const std::ffi::c_void> {
+                unsafe {
+                    ros2_types::typesupport::type_support_handle(#package, #action_type_support_name)
+                }
+            }
+
             type GoalContent = #goal_ident;
 
             fn new_goal_request(
@@ -1172,6 +1354,14 @@ pub fn generate_action_wrapper(
                 }
             }
 
+            fn new_result_request(
+                uuid: [u8; 16],
+            ) -> <Self::Result as ros2_types::ActionResult>::Request {
+                #get_result_request_ident {
+                    goal_id: #uuid_type { uuid },
+                }
+            }
+
             type ResultContent = #result_ident;
 
             fn new_result_response(
@@ -18,27 +18,42 @@ use crate::attrs::{Ros2FieldOpts, Ros2TypeOpts, parse_fields};
 fn generate_field_type_expr(field_type: &syn::Type, field_opts: &Ros2FieldOpts) -> TokenStream {
     // Handle explicit overrides for types where Rust type differs from ROS2 type
     if let Some(ref ros2_type) = field_opts.ros2_type {
-        // Get the base type_id for the ros2_type
-        let type_id = match ros2_type.as_str() {
-            "byte" | "octet" => quote! { ros2_types::FIELD_TYPE_BYTE },
-            "char" => quote! { ros2_types::FIELD_TYPE_CHAR },
-            "wstring" => quote! { ros2_types::FIELD_TYPE_WSTRING },
-            _ => quote! { ros2_types::FIELD_TYPE_UINT8 }, // fallback
+        let is_array = matches!(field_type, syn::Type::Array(_));
+
+        // Get the element type for the ros2_type; in sequences and arrays, the
+        // bound of wstrings is given by `string_capacity`
+        let element = match ros2_type.as_str() {
+            "byte" | "octet" => {
+                quote! { ros2_types::types::FieldType::primitive(ros2_types::FIELD_TYPE_BYTE) }
+            }
+            "char" => {
+                quote! { ros2_types::types::FieldType::primitive(ros2_types::FIELD_TYPE_CHAR) }
+            }
+            "wstring" => match field_opts.string_capacity {
+                Some(string_capacity) if field_opts.sequence || is_array => {
+                    quote! { ros2_types::types::FieldType::bounded_wstring(#string_capacity) }
+                }
+                _ => {
+                    quote! { ros2_types::types::FieldType::primitive(ros2_types::FIELD_TYPE_WSTRING) }
+                }
+            },
+            // fallback
+            _ => quote! { ros2_types::types::FieldType::primitive(ros2_types::FIELD_TYPE_UINT8) },
         };
 
         // Check if this is also a sequence
         if field_opts.sequence {
             if let Some(capacity) = field_opts.capacity {
-                return quote! { ros2_types::types::FieldType::bounded_sequence(#type_id, #capacity) };
+                return quote! { ros2_types::types::FieldType::bounded_sequence_of(#element, #capacity) };
             } else {
-                return quote! { ros2_types::types::FieldType::sequence(#type_id) };
+                return quote! { ros2_types::types::FieldType::sequence_of(#element) };
             }
         }
 
         // Check if the field type is a fixed-size array [T; N]
         if let syn::Type::Array(array) = field_type {
             let len = &array.len;
-            return quote! { ros2_types::types::FieldType::array(#type_id, #len as u64) };
+            return quote! { ros2_types::types::FieldType::array_of(#element, #len as u64) };
         }
 
         // Not a sequence or array - return primitive or bounded type
@@ -120,6 +135,29 @@ fn generate_field_type_expr(field_type: &syn::Type, field_opts: &Ros2FieldOpts)
         }
     }
 
+    // Handle arrays of bounded strings (e.g., [String; N] with
+    // #[ros2(string, string_capacity = M)] for string<=M[N])
+    if (field_opts.string || field_opts.wstring)
+        && let syn::Type::Array(array) = field_type
+    {
+        let len = &array.len;
+        let element = match (field_opts.string_capacity, field_opts.wstring) {
+            (Some(string_capacity), false) => {
+                quote! { ros2_types::types::FieldType::bounded_string(#string_capacity) }
+            }
+            (Some(string_capacity), true) => {
+                quote! { ros2_types::types::FieldType::bounded_wstring(#string_capacity) }
+            }
+            (None, false) => {
+                quote! { ros2_types::types::FieldType::primitive(ros2_types::FIELD_TYPE_STRING) }
+            }
+            (None, true) => {
+                quote! { ros2_types::types::FieldType::primitive(ros2_types::FIELD_TYPE_WSTRING) }
+            }
+        };
+        return quote! { ros2_types::types::FieldType::array_of(#element, #len as u64) };
+    }
+
     // Handle explicit string attribute with capacity (for bounded strings)
     if field_opts.string
         && let Some(capacity) = field_opts.capacity
@@ -150,14 +188,10 @@ fn generate_field_type_expr(field_type: &syn::Type, field_opts: &Ros2FieldOpts)
         {
             // Got the inner type T from Vec<T>
             return quote! {
-                {
-                    let inner = <#inner_ty as ros2_types::RosFieldType>::ros_field_type();
-                    if inner.type_id == ros2_types::FIELD_TYPE_NESTED_TYPE {
-                        ros2_types::types::FieldType::nested_bounded_sequence(&inner.nested_type_name, #capacity)
-                    } else {
-                        ros2_types::types::FieldType::bounded_sequence(inner.type_id, #capacity)
-                    }
-                }
+                ros2_types::types::FieldType::bounded_sequence_of(
+                    <#inner_ty as ros2_types::RosFieldType>::ros_field_type(),
+                    #capacity
+                )
             };
         }
         // Fallback for non-generic sequence types - shouldn't normally happen
@@ -264,6 +298,26 @@ pub fn derive_type_description_impl(input: DeriveInput) -> Result<TokenStream, s
         })
         .collect();
 
+    // Units are metadata only: they are not part of the type description hash
+    let field_units: Vec<_> = field_opts
+        .iter()
+        .filter_map(|f| {
+            let field_name_raw = f.ident.as_ref().unwrap().to_string();
+            let field_name = field_name_raw.strip_prefix("r#").unwrap_or(&field_name_raw);
+            let unit = f.unit.as_ref()?;
+            Some(quote! { (#field_name, #unit) })
+        })
+        .collect();
+    let field_units_fn = if field_units.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn field_units() -> &'static [(&'static str, &'static str)] {
+                &[#(#field_units),*]
+            }
+        }
+    };
+
     let expanded = quote! {
         impl #impl_generics ros2_types::TypeDescription for #name #ty_generics #where_clause {
             fn type_description() -> ros2_types::types::TypeDescriptionMsg {
@@ -295,6 +349,8 @@ pub fn derive_type_description_impl(input: DeriveInput) -> Result<TokenStream, s
                     stringify!(#name)
                 )
             }
+
+            #field_units_fn
         }
 
         impl #impl_generics ros2_types::RosFieldType for #name #ty_generics #where_clause {
@@ -0,0 +1,512 @@
+//! Borrowed (zero-copy) CDR deserialization
+//!
+//! [`TypeSupportBorrowed`] deserializes a view of a message whose string and
+//! byte-sequence fields borrow from the CDR buffer, instead of allocating a
+//! `String` or a `Vec<u8>` per field. This is useful for large messages such as
+//! `sensor_msgs/Image` or `sensor_msgs/PointCloud2` when only a few fields are
+//! inspected.
+//!
+//! A view is a plain serde struct with the fields of the message in the same
+//! order, using `&'a str` for strings and `&'a [u8]` for `uint8[]`/`byte[]`
+//! sequences:
+//!
+//! ```ignore
+//! use ros2_types::TypeSupportBorrowed;
+//!
+//! #[derive(serde::Deserialize)]
+//! struct TimeView {
+//!     sec: i32,
+//!     nanosec: u32,
+//! }
+//!
+//! #[derive(serde::Deserialize)]
+//! struct HeaderView<'a> {
+//!     stamp: TimeView,
+//!     frame_id: &'a str,
+//! }
+//!
+//! #[derive(serde::Deserialize)]
+//! struct ImageView<'a> {
+//!     #[serde(borrow)]
+//!     header: HeaderView<'a>,
+//!     height: u32,
+//!     width: u32,
+//!     encoding: &'a str,
+//!     is_bigendian: u8,
+//!     step: u32,
+//!     data: &'a [u8],
+//! }
+//!
+//! let image = ImageView::from_bytes_borrowed(&bytes)?;
+//! println!("{} {}x{}", image.header.frame_id, image.width, image.height);
+//! ```
+//!
+//! Fields of other types are decoded as by [`CdrSerde`](crate::CdrSerde), so
+//! owned message types can be deserialized in this mode as well.
+
+use crate::cdr::CdrEncapsulationHeader;
+use crate::error::{Error, Result};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+use std::marker::PhantomData;
+
+/// Trait for message views deserialized from a CDR buffer they borrow from.
+///
+/// Implemented for every type implementing `serde::Deserialize<'a>`, see the
+/// [module documentation](self).
+pub trait TypeSupportBorrowed<'a>: Sized {
+    /// Deserialize a view from CDR-encoded bytes, encapsulation header
+    /// included.
+    ///
+    /// # Errors
+    /// Returns `Error::CdrError` if the encapsulation is not plain CDR or if
+    /// deserialization fails.
+    fn from_bytes_borrowed(bytes: &'a [u8]) -> Result<Self>;
+}
+
+impl<'a, T: serde::Deserialize<'a>> TypeSupportBorrowed<'a> for T {
+    fn from_bytes_borrowed(bytes: &'a [u8]) -> Result<Self> {
+        let header = CdrEncapsulationHeader::from_bytes(bytes)?;
+
+        // Only plain CDR v1 is supported
+        if !header.representation_id.is_supported() {
+            return Err(Error::CdrError(format!(
+                "Unsupported CDR encoding for deserialization: {:?}. Only CdrLE and CdrBE are supported.",
+                header.representation_id
+            )));
+        }
+
+        let payload = &bytes[CdrEncapsulationHeader::SIZE..];
+        let result = if header.representation_id.is_little_endian() {
+            T::deserialize(&mut Deserializer::<LittleEndian>::new(payload))
+        } else {
+            T::deserialize(&mut Deserializer::<BigEndian>::new(payload))
+        };
+        result.map_err(|e| Error::CdrError(e.0))
+    }
+}
+
+/// Error raised by the borrowed deserializer
+#[derive(Debug)]
+struct DeError(String);
+
+impl std::fmt::Display for DeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        DeError(msg.to_string())
+    }
+}
+
+type DeResult<T> = std::result::Result<T, DeError>;
+
+/// Plain CDR v1 deserializer handing out slices of its input
+struct Deserializer<'de, BO> {
+    input: &'de [u8],
+    /// Offset in the payload, used for alignment
+    pos: usize,
+    _byte_order: PhantomData<BO>,
+}
+
+impl<'de, BO: ByteOrder> Deserializer<'de, BO> {
+    fn new(input: &'de [u8]) -> Self {
+        Self {
+            input,
+            pos: 0,
+            _byte_order: PhantomData,
+        }
+    }
+
+    fn take(&mut self, len: usize) -> DeResult<&'de [u8]> {
+        let Some(bytes) = self.input.get(self.pos..self.pos + len) else {
+            return Err(DeError(format!(
+                "unexpected end of CDR payload: {len} bytes needed at offset {}",
+                self.pos
+            )));
+        };
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Skip the padding before a primitive of `size` bytes
+    fn align(&mut self, size: usize) -> DeResult<()> {
+        let padding = (size - self.pos % size) % size;
+        self.take(padding).map(|_| ())
+    }
+
+    fn primitive(&mut self, size: usize) -> DeResult<&'de [u8]> {
+        self.align(size)?;
+        self.take(size)
+    }
+
+    fn read_u32(&mut self) -> DeResult<u32> {
+        self.primitive(4).map(BO::read_u32)
+    }
+
+    fn read_len(&mut self) -> DeResult<usize> {
+        self.read_u32().map(|len| len as usize)
+    }
+
+    fn read_str(&mut self) -> DeResult<&'de str> {
+        let len = self.read_len()?;
+        let bytes = self.take(len)?;
+        // The length includes the null terminator
+        let bytes = match bytes.split_last() {
+            Some((0, content)) => content,
+            _ => bytes,
+        };
+        std::str::from_utf8(bytes).map_err(|e| DeError(format!("invalid UTF-8 string: {e}")))
+    }
+}
+
+impl<'de, BO: ByteOrder> de::Deserializer<'de> for &mut Deserializer<'de, BO> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> DeResult<V::Value> {
+        Err(DeError(
+            "CDR is not self-describing, the type must be known".to_string(),
+        ))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        match self.take(1)?[0] {
+            0 => visitor.visit_bool(false),
+            1 => visitor.visit_bool(true),
+            v => Err(DeError(format!("invalid boolean value: {v}"))),
+        }
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_i8(self.take(1)?[0] as i8)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_i16(BO::read_i16(self.primitive(2)?))
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_i32(BO::read_i32(self.primitive(4)?))
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_i64(BO::read_i64(self.primitive(8)?))
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_u8(self.take(1)?[0])
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_u16(BO::read_u16(self.primitive(2)?))
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_u32(self.read_u32()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_u64(BO::read_u64(self.primitive(8)?))
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_f32(BO::read_f32(self.primitive(4)?))
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_f64(BO::read_f64(self.primitive(8)?))
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        let v = self.read_u32()?;
+        let c = char::from_u32(v).ok_or_else(|| DeError(format!("invalid char: {v:#x}")))?;
+        visitor.visit_char(c)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_borrowed_str(self.read_str()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    /// A `uint8[]` sequence: a length followed by the bytes
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        let len = self.read_len()?;
+        visitor.visit_borrowed_bytes(self.take(len)?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, _visitor: V) -> DeResult<V::Value> {
+        Err(DeError(
+            "optional values are not supported by CDR".to_string(),
+        ))
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> DeResult<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> DeResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        let len = self.read_len()?;
+        visitor.visit_seq(Elements { de: self, len })
+    }
+
+    /// A fixed-size array: the elements without length
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_seq(Elements { de: self, len })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> DeResult<V::Value> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        let len = self.read_len()?;
+        visitor.visit_map(Elements { de: self, len })
+    }
+
+    /// The fields of a struct in order, without names
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> DeResult<V::Value> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> DeResult<V::Value> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> DeResult<V::Value> {
+        Err(DeError("CDR has no field identifiers".to_string()))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> DeResult<V::Value> {
+        Err(DeError(
+            "CDR is not self-describing, fields cannot be skipped".to_string(),
+        ))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Elements of a sequence, array, struct or map
+struct Elements<'a, 'de, BO> {
+    de: &'a mut Deserializer<'de, BO>,
+    len: usize,
+}
+
+impl<'de, BO: ByteOrder> SeqAccess<'de> for Elements<'_, 'de, BO> {
+    type Error = DeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> DeResult<Option<T::Value>> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de, BO: ByteOrder> MapAccess<'de> for Elements<'_, 'de, BO> {
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> DeResult<Option<K::Value>> {
+        self.next_element_seed(seed)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> DeResult<V::Value> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+/// Enums are encoded as a `u32` variant index followed by the variant data
+impl<'de, BO: ByteOrder> EnumAccess<'de> for &mut Deserializer<'de, BO> {
+    type Error = DeError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> DeResult<(V::Value, Self)> {
+        let index = self.read_u32()?;
+        let value = seed.deserialize(IntoDeserializer::<DeError>::into_deserializer(index))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de, BO: ByteOrder> VariantAccess<'de> for &mut Deserializer<'de, BO> {
+    type Error = DeError;
+
+    fn unit_variant(self) -> DeResult<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> DeResult<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> DeResult<V::Value> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> DeResult<V::Value> {
+        de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CdrSerde;
+    use crate::cdr::RepresentationIdentifier;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Header {
+        stamp: (i32, u32),
+        frame_id: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Image {
+        header: Header,
+        height: u32,
+        width: u32,
+        encoding: String,
+        is_bigendian: u8,
+        step: u32,
+        data: Vec<u8>,
+        scale: f64,
+        names: Vec<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct HeaderView<'a> {
+        stamp: (i32, u32),
+        frame_id: &'a str,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct ImageView<'a> {
+        #[serde(borrow)]
+        header: HeaderView<'a>,
+        height: u32,
+        width: u32,
+        encoding: &'a str,
+        is_bigendian: u8,
+        step: u32,
+        data: &'a [u8],
+        scale: f64,
+        #[serde(borrow)]
+        names: Vec<&'a str>,
+    }
+
+    fn image() -> Image {
+        Image {
+            header: Header {
+                stamp: (12, 34),
+                frame_id: "camera".to_string(),
+            },
+            height: 2,
+            width: 3,
+            encoding: "mono8".to_string(),
+            is_bigendian: 0,
+            step: 3,
+            data: vec![1, 2, 3, 4, 5, 6],
+            scale: 0.5,
+            names: vec!["a".to_string(), "bc".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_borrowed_view() {
+        let msg = image();
+        for header in [
+            CdrEncapsulationHeader::default(),
+            CdrEncapsulationHeader::new(RepresentationIdentifier::CdrBE),
+        ] {
+            let bytes = msg.serialize_with_header(header).unwrap();
+            let view = ImageView::from_bytes_borrowed(&bytes).unwrap();
+
+            assert_eq!(view.header.stamp, (12, 34));
+            assert_eq!(view.header.frame_id, "camera");
+            assert_eq!((view.height, view.width, view.step), (2, 3, 3));
+            assert_eq!(view.encoding, "mono8");
+            assert_eq!(view.is_bigendian, 0);
+            assert_eq!(view.data, &[1, 2, 3, 4, 5, 6]);
+            assert_eq!(view.scale, 0.5);
+            assert_eq!(view.names, ["a", "bc"]);
+
+            // The slices point into the buffer
+            let range = bytes.as_ptr_range();
+            assert!(range.contains(&view.data.as_ptr()));
+            assert!(range.contains(&view.encoding.as_ptr()));
+
+            // Owned types decode the same way
+            assert_eq!(Image::from_bytes_borrowed(&bytes).unwrap(), msg);
+        }
+    }
+
+    #[test]
+    fn test_borrowed_truncated() {
+        let bytes = CdrSerde::serialize(&image()).unwrap();
+        let truncated = &bytes[..bytes.len() - 4];
+        assert!(ImageView::from_bytes_borrowed(truncated).is_err());
+    }
+}
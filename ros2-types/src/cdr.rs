@@ -284,6 +284,25 @@ pub trait CdrSerde: Sized {
     /// Serialize with a specific encapsulation header
     fn serialize_with_header(&self, header: CdrEncapsulationHeader) -> Result<Vec<u8>>;
 
+    /// Serialize like [`CdrSerde::serialize`] into `buf`, replacing its content
+    ///
+    /// The capacity of `buf` is kept, so that a buffer can be reused for
+    /// several messages.
+    fn serialize_into(&self, buf: &mut Vec<u8>) -> Result<()>;
+
+    /// Serialize like [`CdrSerde::serialize`] into `buf`, and return the
+    /// number of bytes written
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::CdrError` if `buf` is too small.
+    fn serialize_into_slice(&self, buf: &mut [u8]) -> Result<usize>;
+
+    /// Size of the output of [`CdrSerde::serialize`], header included
+    ///
+    /// The message is serialized without storing the bytes.
+    fn serialized_size(&self) -> Result<usize>;
+
     /// Deserialize from CDR-encoded bytes
     ///
     /// Parses the encapsulation header to determine the encoding format
@@ -298,32 +317,73 @@ pub trait CdrSerde: Sized {
     fn deserialize(bytes: &[u8]) -> Result<Self>;
 }
 
+/// Writer counting the bytes written to it
+struct SizeCounter(usize);
+
+impl std::io::Write for SizeCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Write the encapsulation header and the CDR payload of `value` to `writer`
+fn write_cdr<T, W>(value: &T, header: CdrEncapsulationHeader, mut writer: W) -> Result<()>
+where
+    T: serde::Serialize,
+    W: std::io::Write,
+{
+    // Only plain CDR v1 is supported
+    if !header.representation_id.is_supported() {
+        return Err(Error::CdrError(format!(
+            "Unsupported CDR encoding for serialization: {:?}. Only CdrLE and CdrBE are supported.",
+            header.representation_id
+        )));
+    }
+
+    writer
+        .write_all(&header.to_bytes())
+        .map_err(|e| Error::CdrError(e.to_string()))?;
+
+    if header.representation_id.is_little_endian() {
+        cdr_encoding::to_writer::<T, byteorder::LittleEndian, W>(writer, value)
+    } else {
+        cdr_encoding::to_writer::<T, byteorder::BigEndian, W>(writer, value)
+    }
+    .map_err(|e| Error::CdrError(e.to_string()))
+}
+
 impl<T: serde::Serialize + serde::de::DeserializeOwned> CdrSerde for T {
     fn serialize(&self) -> Result<Vec<u8>> {
         self.serialize_with_header(CdrEncapsulationHeader::default())
     }
 
     fn serialize_with_header(&self, header: CdrEncapsulationHeader) -> Result<Vec<u8>> {
-        // Only plain CDR v1 is supported
-        if !header.representation_id.is_supported() {
-            return Err(Error::CdrError(format!(
-                "Unsupported CDR encoding for serialization: {:?}. Only CdrLE and CdrBE are supported.",
-                header.representation_id
-            )));
-        }
+        let mut result = Vec::new();
+        write_cdr(self, header, &mut result)?;
+        Ok(result)
+    }
 
-        let mut result = header.to_bytes().to_vec();
+    fn serialize_into(&self, buf: &mut Vec<u8>) -> Result<()> {
+        buf.clear();
+        write_cdr(self, CdrEncapsulationHeader::default(), buf)
+    }
 
-        let buffer = if header.representation_id.is_little_endian() {
-            cdr_encoding::to_vec::<T, byteorder::LittleEndian>(self)
-                .map_err(|e| Error::CdrError(e.to_string()))?
-        } else {
-            cdr_encoding::to_vec::<T, byteorder::BigEndian>(self)
-                .map_err(|e| Error::CdrError(e.to_string()))?
-        };
+    fn serialize_into_slice(&self, buf: &mut [u8]) -> Result<usize> {
+        let len = buf.len();
+        let mut rest = buf;
+        write_cdr(self, CdrEncapsulationHeader::default(), &mut rest)?;
+        Ok(len - rest.len())
+    }
 
-        result.extend(buffer);
-        Ok(result)
+    fn serialized_size(&self) -> Result<usize> {
+        let mut counter = SizeCounter(0);
+        write_cdr(self, CdrEncapsulationHeader::default(), &mut counter)?;
+        Ok(counter.0)
     }
 
     fn deserialize(bytes: &[u8]) -> Result<Self> {
@@ -464,4 +524,22 @@ mod tests {
         let result = CdrEncapsulationHeader::from_bytes(&[0x00, 0x01]);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_serialize_into_buffers() {
+        let value = (1u8, 2u32, "hello".to_string());
+        let bytes = value.serialize().unwrap();
+        assert_eq!(value.serialized_size().unwrap(), bytes.len());
+
+        let mut buf = vec![0xFF; 64];
+        value.serialize_into(&mut buf).unwrap();
+        assert_eq!(buf, bytes);
+
+        let mut slice = [0u8; 64];
+        let n = value.serialize_into_slice(&mut slice).unwrap();
+        assert_eq!(&slice[..n], bytes.as_slice());
+
+        let mut small = [0u8; 8];
+        assert!(value.serialize_into_slice(&mut small).is_err());
+    }
 }
@@ -0,0 +1,408 @@
+//! Compatibility checks between type descriptions
+//!
+//! Since Iron, ROS2 installs the type description of every interface next to
+//! its definition, as `share/<package>/<msg|srv|action>/<Name>.json`. Comparing
+//! it with the description compiled into a binary reveals hash drift and
+//! changed fields when the binary runs against another distro.
+
+use crate::{
+    Result, calculate_type_hash,
+    types::{FieldType, IndividualTypeDescription, TypeDescriptionMsg},
+};
+use serde::Deserialize;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// Type description installed by `rosidl_generator_type_description`
+#[derive(Debug, Clone, Deserialize)]
+pub struct InstalledDescription {
+    /// Description of the interface and of the types it references
+    pub type_description_msg: TypeDescriptionMsg,
+    /// Hashes of the interface and of its generated types
+    pub type_hashes: Vec<TypeHash>,
+}
+
+/// Hash entry of an installed type description
+#[derive(Debug, Clone, Deserialize)]
+pub struct TypeHash {
+    /// Fully qualified type name (e.g., "std_msgs/msg/Header")
+    pub type_name: String,
+    /// RIHS01 hash string
+    pub hash_string: String,
+}
+
+impl InstalledDescription {
+    /// Parse the JSON file installed for an interface
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is malformed
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Find the description of `type_name`, either the interface itself or
+    /// one of the types it references
+    pub fn find(&self, type_name: &str) -> Option<&IndividualTypeDescription> {
+        let msg = &self.type_description_msg;
+        std::iter::once(&msg.type_description)
+            .chain(&msg.referenced_type_descriptions)
+            .find(|desc| desc.type_name == type_name)
+    }
+
+    /// Get the installed hash of `type_name`
+    pub fn hash(&self, type_name: &str) -> Option<&str> {
+        self.type_hashes
+            .iter()
+            .find(|hash| hash.type_name == type_name)
+            .map(|hash| hash.hash_string.as_str())
+    }
+}
+
+/// Get the path of the file describing `type_name` under the install prefix
+/// `prefix`
+///
+/// Generated service and action types (e.g., `"example_interfaces/srv/AddTwoInts_Request"`)
+/// are described in the file of their interface.
+///
+/// Returns `None` if `type_name` is not of the form `package/kind/Name`.
+pub fn installed_description_path(prefix: &Path, type_name: &str) -> Option<PathBuf> {
+    let mut parts = type_name.split('/');
+    let (Some(package), Some(kind), Some(name), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+
+    let suffixes: &[&str] = match kind {
+        "srv" => &["_Request", "_Response", "_Event"],
+        "action" => &[
+            "_SendGoal_Request",
+            "_SendGoal_Response",
+            "_SendGoal_Event",
+            "_GetResult_Request",
+            "_GetResult_Response",
+            "_GetResult_Event",
+            "_FeedbackMessage",
+            "_SendGoal",
+            "_GetResult",
+            "_Goal",
+            "_Result",
+            "_Feedback",
+        ],
+        _ => &[],
+    };
+    let interface = suffixes
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .unwrap_or(name);
+
+    Some(
+        prefix
+            .join("share")
+            .join(package)
+            .join(kind)
+            .join(format!("{interface}.json")),
+    )
+}
+
+/// Search `AMENT_PREFIX_PATH` for the file describing `type_name`
+///
+/// Prefixes are searched in order, so overlays take precedence.
+///
+/// Returns `None` if no file is found, e.g. on Humble which does not install
+/// type descriptions.
+pub fn find_installed_description(type_name: &str) -> Option<PathBuf> {
+    let prefixes = std::env::var_os("AMENT_PREFIX_PATH")?;
+    std::env::split_paths(&prefixes)
+        .filter_map(|prefix| installed_description_path(&prefix, type_name))
+        .find(|path| path.is_file())
+}
+
+/// Difference between a compiled-in and an installed type description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    /// A referenced type is not installed
+    MissingType {
+        /// Fully qualified type name
+        type_name: String,
+    },
+    /// A compiled-in field is not installed
+    MissingField {
+        /// Fully qualified type name
+        type_name: String,
+        /// Field name
+        field: String,
+    },
+    /// An installed field is not compiled in
+    ExtraField {
+        /// Fully qualified type name
+        type_name: String,
+        /// Field name
+        field: String,
+    },
+    /// A field has a different type
+    FieldTypeChanged {
+        /// Fully qualified type name
+        type_name: String,
+        /// Field name
+        field: String,
+    },
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difference::MissingType { type_name } => {
+                write!(f, "type {type_name} is not installed")
+            }
+            Difference::MissingField { type_name, field } => {
+                write!(f, "field {type_name}.{field} is not installed")
+            }
+            Difference::ExtraField { type_name, field } => {
+                write!(f, "installed field {type_name}.{field} is not compiled in")
+            }
+            Difference::FieldTypeChanged { type_name, field } => {
+                write!(f, "field {type_name}.{field} has a different type")
+            }
+        }
+    }
+}
+
+/// Result of comparing a compiled-in type description with the installed one
+#[derive(Debug, Clone)]
+pub struct CompatReport {
+    /// Fully qualified type name
+    pub type_name: String,
+    /// Hash of the compiled-in description
+    pub compiled_hash: String,
+    /// Hash of the installed description, if listed
+    pub installed_hash: Option<String>,
+    /// Differences found in the type and the types it references
+    pub differences: Vec<Difference>,
+}
+
+impl CompatReport {
+    /// Whether the compiled-in and installed hashes are equal
+    ///
+    /// Returns `false` if the installed hash is unknown.
+    pub fn hash_matches(&self) -> bool {
+        self.installed_hash.as_deref() == Some(self.compiled_hash.as_str())
+    }
+
+    /// Whether the compiled-in type can talk to the installed one
+    ///
+    /// Falls back to the field comparison when the installed hash is unknown.
+    pub fn is_compatible(&self) -> bool {
+        match &self.installed_hash {
+            Some(_) => self.hash_matches(),
+            None => self.differences.is_empty(),
+        }
+    }
+}
+
+impl fmt::Display for CompatReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = if self.is_compatible() {
+            "compatible"
+        } else {
+            "INCOMPATIBLE"
+        };
+        writeln!(f, "{}: {status}", self.type_name)?;
+        writeln!(f, "  compiled hash:  {}", self.compiled_hash)?;
+        writeln!(
+            f,
+            "  installed hash: {}",
+            self.installed_hash.as_deref().unwrap_or("(unknown)")
+        )?;
+        for difference in &self.differences {
+            writeln!(f, "  - {difference}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare a compiled-in type description with the installed one
+///
+/// Fields of the type and of every type it references are compared by name
+/// and type.
+///
+/// # Errors
+///
+/// Returns an error if the hash of `compiled` cannot be calculated
+pub fn compare(
+    compiled: &TypeDescriptionMsg,
+    installed: &InstalledDescription,
+) -> Result<CompatReport> {
+    let type_name = compiled.type_description.type_name.clone();
+    let mut differences = Vec::new();
+
+    for desc in
+        std::iter::once(&compiled.type_description).chain(&compiled.referenced_type_descriptions)
+    {
+        let Some(other) = installed.find(&desc.type_name) else {
+            differences.push(Difference::MissingType {
+                type_name: desc.type_name.clone(),
+            });
+            continue;
+        };
+        compare_fields(desc, other, &mut differences);
+    }
+
+    Ok(CompatReport {
+        compiled_hash: calculate_type_hash(compiled)?,
+        installed_hash: installed.hash(&type_name).map(str::to_string),
+        type_name,
+        differences,
+    })
+}
+
+fn compare_fields(
+    compiled: &IndividualTypeDescription,
+    installed: &IndividualTypeDescription,
+    differences: &mut Vec<Difference>,
+) {
+    let type_name = &compiled.type_name;
+    for field in &compiled.fields {
+        match installed.fields.iter().find(|f| f.name == field.name) {
+            None => differences.push(Difference::MissingField {
+                type_name: type_name.clone(),
+                field: field.name.clone(),
+            }),
+            Some(other) if !same_type(&field.field_type, &other.field_type) => {
+                differences.push(Difference::FieldTypeChanged {
+                    type_name: type_name.clone(),
+                    field: field.name.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    for field in &installed.fields {
+        if !compiled.fields.iter().any(|f| f.name == field.name) {
+            differences.push(Difference::ExtraField {
+                type_name: type_name.clone(),
+                field: field.name.clone(),
+            });
+        }
+    }
+}
+
+fn same_type(a: &FieldType, b: &FieldType) -> bool {
+    a.type_id == b.type_id
+        && a.capacity == b.capacity
+        && a.string_capacity == b.string_capacity
+        && a.nested_type_name == b.nested_type_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FIELD_TYPE_INT32, FIELD_TYPE_STRING, Field};
+
+    fn installed(fields: Vec<Field>, hash: &str) -> InstalledDescription {
+        InstalledDescription {
+            type_description_msg: TypeDescriptionMsg::new(
+                IndividualTypeDescription::new("test_pkg/msg/TestMsg", fields),
+                vec![],
+            ),
+            type_hashes: vec![TypeHash {
+                type_name: "test_pkg/msg/TestMsg".to_string(),
+                hash_string: hash.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_installed_description_path() {
+        let prefix = Path::new("/opt/ros/jazzy");
+        assert_eq!(
+            installed_description_path(prefix, "std_msgs/msg/String").unwrap(),
+            Path::new("/opt/ros/jazzy/share/std_msgs/msg/String.json")
+        );
+        assert_eq!(
+            installed_description_path(prefix, "example_interfaces/srv/AddTwoInts_Request")
+                .unwrap(),
+            Path::new("/opt/ros/jazzy/share/example_interfaces/srv/AddTwoInts.json")
+        );
+        assert_eq!(
+            installed_description_path(
+                prefix,
+                "example_interfaces/action/Fibonacci_SendGoal_Request"
+            )
+            .unwrap(),
+            Path::new("/opt/ros/jazzy/share/example_interfaces/action/Fibonacci.json")
+        );
+        assert!(installed_description_path(prefix, "String").is_none());
+    }
+
+    #[test]
+    fn test_compare() {
+        let fields = vec![
+            Field::new("a", FieldType::primitive(FIELD_TYPE_INT32)),
+            Field::new("b", FieldType::primitive(FIELD_TYPE_INT32)),
+        ];
+        let compiled = TypeDescriptionMsg::new(
+            IndividualTypeDescription::new("test_pkg/msg/TestMsg", fields.clone()),
+            vec![],
+        );
+        let hash = calculate_type_hash(&compiled).unwrap();
+
+        let report = compare(&compiled, &installed(fields, &hash)).unwrap();
+        assert!(report.hash_matches());
+        assert!(report.is_compatible());
+        assert!(report.differences.is_empty());
+
+        let drifted = vec![
+            Field::new("a", FieldType::primitive(FIELD_TYPE_STRING)),
+            Field::new("c", FieldType::primitive(FIELD_TYPE_INT32)),
+        ];
+        let report = compare(&compiled, &installed(drifted, "RIHS01_other")).unwrap();
+        assert!(!report.is_compatible());
+        assert_eq!(
+            report.differences,
+            vec![
+                Difference::FieldTypeChanged {
+                    type_name: "test_pkg/msg/TestMsg".to_string(),
+                    field: "a".to_string(),
+                },
+                Difference::MissingField {
+                    type_name: "test_pkg/msg/TestMsg".to_string(),
+                    field: "b".to_string(),
+                },
+                Difference::ExtraField {
+                    type_name: "test_pkg/msg/TestMsg".to_string(),
+                    field: "c".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_json() {
+        let json = r#"{
+            "type_description_msg": {
+                "type_description": {
+                    "type_name": "std_msgs/msg/String",
+                    "fields": [{"name": "data", "type": {"type_id": 17, "capacity": 0, "string_capacity": 0, "nested_type_name": ""}, "default_value": ""}]
+                },
+                "referenced_type_descriptions": []
+            },
+            "type_hashes": [{"type_name": "std_msgs/msg/String", "hash_string": "RIHS01_df668c740482bbd48fb39d76a70dfd4bd59db1288021743503259e948f6b1a18"}]
+        }"#;
+        let installed = InstalledDescription::from_json(json).unwrap();
+        assert_eq!(
+            installed.find("std_msgs/msg/String").unwrap().fields.len(),
+            1
+        );
+
+        let compiled = TypeDescriptionMsg::new(
+            installed.find("std_msgs/msg/String").unwrap().clone(),
+            vec![],
+        );
+        let report = compare(&compiled, &installed).unwrap();
+        assert!(report.hash_matches(), "{report}");
+    }
+}
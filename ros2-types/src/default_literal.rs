@@ -0,0 +1,163 @@
+//! Parsing of the default values of message fields.
+//!
+//! Default values come from the interface definitions (e.g. `float64 w 1` in a
+//! `.msg` file) and reach the derive macros as the string of the
+//! `#[ros2(default = "...")]` attribute. The pure Rust `Default` impls parse
+//! them with [`FromDefaultLiteral`], following the rules of `rosidl`:
+//!
+//! - booleans are `true`/`false` (any case) or `1`/`0`
+//! - integers and floats use the Rust syntax; a float may omit its fraction
+//! - strings are taken verbatim; surrounding quotes are removed
+//! - arrays and sequences are lists such as `[1, 2, 3]` or `(1, 2, 3)`
+
+/// A field type whose value can be given by a default literal.
+pub trait FromDefaultLiteral: Sized {
+    /// Parse `literal`, returning `None` if it is not a valid value.
+    fn from_default_literal(literal: &str) -> Option<Self>;
+}
+
+impl FromDefaultLiteral for bool {
+    fn from_default_literal(literal: &str) -> Option<Self> {
+        match literal.trim() {
+            "1" => Some(true),
+            "0" => Some(false),
+            s if s.eq_ignore_ascii_case("true") => Some(true),
+            s if s.eq_ignore_ascii_case("false") => Some(false),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! impl_from_default_literal {
+    ($($ty:ty),*) => {
+        $(
+            impl FromDefaultLiteral for $ty {
+                fn from_default_literal(literal: &str) -> Option<Self> {
+                    literal.trim().parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_default_literal!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64);
+
+impl FromDefaultLiteral for String {
+    fn from_default_literal(literal: &str) -> Option<Self> {
+        Some(unquote(literal).to_string())
+    }
+}
+
+impl<T: FromDefaultLiteral, const N: usize> FromDefaultLiteral for [T; N] {
+    fn from_default_literal(literal: &str) -> Option<Self> {
+        let values = parse_list::<T>(literal)?;
+        values.try_into().ok()
+    }
+}
+
+impl<T: FromDefaultLiteral> FromDefaultLiteral for Vec<T> {
+    fn from_default_literal(literal: &str) -> Option<Self> {
+        parse_list(literal)
+    }
+}
+
+/// Parse a list literal such as `[1, 2, 3]` or `(1, 2, 3)`.
+///
+/// Elements are separated by commas outside of quotes.
+fn parse_list<T: FromDefaultLiteral>(literal: &str) -> Option<Vec<T>> {
+    let literal = literal.trim();
+    let inner = literal
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .or_else(|| literal.strip_prefix('(').and_then(|s| s.strip_suffix(')')))?;
+    if inner.trim().is_empty() {
+        return Some(Vec::new());
+    }
+
+    let mut elements = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in inner.char_indices() {
+        match (quote, c) {
+            (Some(_), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, ',') => {
+                elements.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+        escaped = false;
+    }
+    elements.push(&inner[start..]);
+
+    elements
+        .into_iter()
+        .map(|e| T::from_default_literal(e.trim()))
+        .collect()
+}
+
+/// Remove the quotes around a string literal, if any.
+fn unquote(literal: &str) -> &str {
+    for q in ['"', '\''] {
+        if let Some(s) = literal.strip_prefix(q).and_then(|s| s.strip_suffix(q))
+            && literal.len() >= 2
+        {
+            return s;
+        }
+    }
+    literal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_primitives() {
+        assert_eq!(bool::from_default_literal("True"), Some(true));
+        assert_eq!(bool::from_default_literal("0"), Some(false));
+        assert_eq!(bool::from_default_literal("yes"), None);
+        assert_eq!(i8::from_default_literal("-2"), Some(-2));
+        assert_eq!(u8::from_default_literal("256"), None);
+        assert_eq!(f64::from_default_literal("1"), Some(1.0));
+        assert_eq!(f32::from_default_literal("-1.5e3"), Some(-1500.0));
+    }
+
+    #[test]
+    fn test_strings() {
+        assert_eq!(
+            String::from_default_literal("\"default\"").as_deref(),
+            Some("default")
+        );
+        assert_eq!(
+            String::from_default_literal("plain").as_deref(),
+            Some("plain")
+        );
+        assert_eq!(String::from_default_literal("\"").as_deref(), Some("\""));
+    }
+
+    #[test]
+    fn test_lists() {
+        assert_eq!(
+            <[i32; 3]>::from_default_literal("[1, 2, 3]"),
+            Some([1, 2, 3])
+        );
+        assert_eq!(<[i32; 3]>::from_default_literal("(1, 2)"), None);
+        assert_eq!(
+            Vec::<f64>::from_default_literal("(0, 1.5)"),
+            Some(vec![0.0, 1.5])
+        );
+        assert_eq!(Vec::<u8>::from_default_literal("[]"), Some(vec![]));
+        assert_eq!(
+            Vec::<String>::from_default_literal("['a, b', \"c\"]"),
+            Some(vec!["a, b".to_string(), "c".to_string()])
+        );
+        assert_eq!(Vec::<u8>::from_default_literal("1, 2"), None);
+    }
+}
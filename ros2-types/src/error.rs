@@ -66,4 +66,26 @@ pub enum Error {
     /// CDR serialization error
     #[error("CDR serialization error: {0}")]
     CdrError(String),
+
+    /// A rosidl library of an interface package could not be loaded
+    #[error("package {package} typesupport not found in AMENT_PREFIX_PATH ({library}: {reason})")]
+    TypeSupportNotFound {
+        /// Interface package
+        package: String,
+        /// File name of the library
+        library: String,
+        /// Error of the dynamic loader
+        reason: String,
+    },
+
+    /// A function is missing from the rosidl libraries of an interface package
+    #[error("symbol {symbol} not found in the typesupport of package {package} ({reason})")]
+    TypeSupportSymbolNotFound {
+        /// Interface package
+        package: String,
+        /// Name of the missing function
+        symbol: String,
+        /// Error of the dynamic loader
+        reason: String,
+    },
 }
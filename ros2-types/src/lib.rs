@@ -7,6 +7,9 @@
 //!
 //! - `derive`: Enable derive macros for `TypeDescription` and `Ros2Msg`
 //! - `native`: Enable native CDR serialization (for Zenoh, iceoryx2, etc.)
+//! - `schemars`: Expose the JSON Schema of a type description as `schemars::Schema`
+//! - `lazy-typesupport`: Load the rosidl libraries of the interface packages at
+//!   runtime, see [`typesupport`]
 //!
 //! # Traits
 //!
@@ -18,6 +21,8 @@
 //! - `ActionMsg`: For ROS2 action types (Goal/Result/Feedback)
 //! - `ActionGoal`, `ActionResult`: For action service types
 //! - `GetUUID`, `GoalResponse`, `ResultResponse`: Helper traits for actions
+//! - `FromDefaultLiteral`: For field types initialized from a default value
+//! - `TypeSupportBorrowed`: For message views borrowing strings and bytes from the CDR buffer
 //!
 //! # Native CDR Serialization
 //!
@@ -35,7 +40,10 @@
 //! **Note**: When using `native` feature, message structs must derive
 //! `serde::Serialize` and `serde::Deserialize`.
 
+mod borrowed;
 pub mod cdr;
+pub mod compat;
+mod default_literal;
 mod error;
 mod hash;
 mod ros_field_type;
@@ -43,14 +51,18 @@ mod traits;
 
 mod type_description;
 pub mod types;
+#[cfg(feature = "lazy-typesupport")]
+pub mod typesupport;
 
+pub use borrowed::TypeSupportBorrowed;
 pub use cdr::CdrSerde;
+pub use default_literal::FromDefaultLiteral;
 pub use error::{Error, Result};
 pub use hash::{calculate_type_hash, parse_rihs_string};
 pub use ros_field_type::RosFieldType;
 pub use traits::{
-    ActionGoal, ActionMsg, ActionResult, GetUUID, GoalResponse, ResultResponse, SequenceRaw,
-    ServiceMsg, TryClone, TypeSupport, UnsafeDuration, UnsafeTime,
+    ActionGoal, ActionMsg, ActionResult, GetUUID, GoalResponse, KeyField, Keyed, ResultResponse,
+    SequenceRaw, ServiceMsg, Stamped, TryClone, TypeSupport, UnsafeDuration, UnsafeTime,
 };
 pub use type_description::{
     ActionTypeDescription, MessageTypeName, ServiceTypeDescription, TypeDescription,
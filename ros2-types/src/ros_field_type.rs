@@ -6,9 +6,8 @@
 
 use crate::types::{
     FIELD_TYPE_BOOLEAN, FIELD_TYPE_DOUBLE, FIELD_TYPE_FLOAT, FIELD_TYPE_INT8, FIELD_TYPE_INT16,
-    FIELD_TYPE_INT32, FIELD_TYPE_INT64, FIELD_TYPE_NESTED_TYPE, FIELD_TYPE_STRING,
-    FIELD_TYPE_UINT8, FIELD_TYPE_UINT16, FIELD_TYPE_UINT32, FIELD_TYPE_UINT64, FieldType,
-    IndividualTypeDescription,
+    FIELD_TYPE_INT32, FIELD_TYPE_INT64, FIELD_TYPE_STRING, FIELD_TYPE_UINT8, FIELD_TYPE_UINT16,
+    FIELD_TYPE_UINT32, FIELD_TYPE_UINT64, FieldType, IndividualTypeDescription,
 };
 
 /// Trait for types that can be used as fields in ROS2 messages.
@@ -108,12 +107,7 @@ impl RosFieldType for String {
 
 impl<T: RosFieldType> RosFieldType for Vec<T> {
     fn ros_field_type() -> FieldType {
-        let inner = T::ros_field_type();
-        if inner.type_id == FIELD_TYPE_NESTED_TYPE {
-            FieldType::nested_sequence(&inner.nested_type_name)
-        } else {
-            FieldType::sequence(inner.type_id)
-        }
+        FieldType::sequence_of(T::ros_field_type())
     }
 
     fn referenced_types() -> Vec<IndividualTypeDescription> {
@@ -127,12 +121,7 @@ impl<T: RosFieldType> RosFieldType for Vec<T> {
 
 impl<T: RosFieldType, const N: usize> RosFieldType for [T; N] {
     fn ros_field_type() -> FieldType {
-        let inner = T::ros_field_type();
-        if inner.type_id == FIELD_TYPE_NESTED_TYPE {
-            FieldType::nested_array(&inner.nested_type_name, N as u64)
-        } else {
-            FieldType::array(inner.type_id, N as u64)
-        }
+        FieldType::array_of(T::ros_field_type(), N as u64)
     }
 
     fn referenced_types() -> Vec<IndividualTypeDescription> {
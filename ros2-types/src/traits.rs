@@ -25,6 +25,17 @@ pub trait TypeSupport: 'static + Send + Sync {
         std::ptr::null()
     }
 
+    /// Like [`type_support`](Self::type_support), but returns an error instead
+    /// of panicking if the type support cannot be loaded, see
+    /// `ros2_types::typesupport`.
+    ///
+    /// # Errors
+    /// Returns `Error::TypeSupportNotFound` or `Error::TypeSupportSymbolNotFound`
+    /// with the `lazy-typesupport` feature.
+    fn try_type_support() -> Result<*const c_void> {
+        Ok(Self::type_support())
+    }
+
     /// Serialize this message to CDR-encoded bytes.
     ///
     /// # Implementation
@@ -35,6 +46,47 @@ pub trait TypeSupport: 'static + Send + Sync {
     /// Returns `Error::CdrError` if serialization fails.
     fn to_bytes(&self) -> Result<Vec<u8>>;
 
+    /// Size in bytes of the output of [`TypeSupport::to_bytes`].
+    ///
+    /// The size is exact with the `zenoh` feature. The default implementation
+    /// serializes the message, and returns 0 if serialization fails.
+    fn serialized_size(&self) -> usize {
+        self.to_bytes().map(|bytes| bytes.len()).unwrap_or(0)
+    }
+
+    /// Serialize this message like [`TypeSupport::to_bytes`] into `buf`, and
+    /// return the number of bytes written.
+    ///
+    /// # Errors
+    /// Returns `Error::CdrError` if serialization fails or `buf` is too small.
+    fn to_bytes_into(&self, buf: &mut [u8]) -> Result<usize> {
+        let bytes = self.to_bytes()?;
+        let len = buf.len();
+        let Some(dst) = buf.get_mut(..bytes.len()) else {
+            return Err(crate::Error::CdrError(format!(
+                "buffer of {len} bytes is too small for {} bytes",
+                bytes.len()
+            )));
+        };
+        dst.copy_from_slice(&bytes);
+        Ok(bytes.len())
+    }
+
+    /// Serialize this message like [`TypeSupport::to_bytes`] into `buf`,
+    /// replacing its content.
+    ///
+    /// With the `zenoh` feature, the capacity of `buf` is reused and no
+    /// other buffer is allocated.
+    ///
+    /// # Errors
+    /// Returns `Error::CdrError` if serialization fails.
+    fn to_bytes_with(&self, buf: &mut Vec<u8>) -> Result<()> {
+        let bytes = self.to_bytes()?;
+        buf.clear();
+        buf.extend_from_slice(&bytes);
+        Ok(())
+    }
+
     /// Deserialize a message from CDR-encoded bytes.
     ///
     /// # Implementation
@@ -74,6 +126,85 @@ pub trait TryClone: Sized {
     fn try_clone(&self) -> Option<Self>;
 }
 
+/// Trait for messages with a `std_msgs/msg/Header`.
+///
+/// The `Ros2Msg` derive implements it for the messages whose `header` field
+/// is a `Header`, e.g. `geometry_msgs/msg/PoseStamped`.
+pub trait Stamped {
+    /// Returns the stamp of the header.
+    fn stamp(&self) -> UnsafeTime;
+
+    /// Sets the stamp of the header.
+    fn set_stamp(&mut self, stamp: UnsafeTime);
+}
+
+/// Trait for messages with key fields, which tell apart the logical
+/// instances published on a topic, like DDS keys (e.g. the objects of a
+/// tracker, each with its ID).
+///
+/// The `Ros2Msg` derive implements it for the messages with fields marked
+/// `#[ros2(key)]`. The key is the tuple of the [`KeyField::Key`] of these
+/// fields, in declaration order.
+pub trait Keyed {
+    /// The key of an instance.
+    type Key: Ord + Clone + std::fmt::Debug + Send + Sync + 'static;
+
+    /// Returns the key of the instance the message belongs to.
+    fn key(&self) -> Self::Key;
+}
+
+/// Trait for the types of the fields marked `#[ros2(key)]`, see [`Keyed`].
+///
+/// Floating point fields are not supported, as their values are not ordered.
+pub trait KeyField {
+    /// Owned and ordered value of the field.
+    type Key: Ord + Clone + std::fmt::Debug + Send + Sync + 'static;
+
+    /// Returns the value of the field as a key.
+    fn key_value(&self) -> Self::Key;
+}
+
+macro_rules! impl_key_field {
+    ($($ty:ty),*) => {
+        $(
+            impl KeyField for $ty {
+                type Key = $ty;
+
+                fn key_value(&self) -> Self::Key {
+                    *self
+                }
+            }
+        )*
+    };
+}
+
+impl_key_field!(bool, char, u8, i8, u16, i16, u32, i32, u64, i64);
+
+impl KeyField for String {
+    type Key = String;
+
+    fn key_value(&self) -> Self::Key {
+        self.clone()
+    }
+}
+
+impl<T: KeyField, const N: usize> KeyField for [T; N] {
+    type Key = [T::Key; N];
+
+    fn key_value(&self) -> Self::Key {
+        std::array::from_fn(|i| self[i].key_value())
+    }
+}
+
+/// Nested messages with key fields are keys too.
+impl<T: Keyed> KeyField for T {
+    type Key = T::Key;
+
+    fn key_value(&self) -> Self::Key {
+        self.key()
+    }
+}
+
 /// Trait for ROS2 service message types.
 ///
 /// Services consist of a request and response message pair.
@@ -89,6 +220,17 @@ pub trait ServiceMsg: 'static + Send + Sync {
         std::ptr::null()
     }
 
+    /// Like [`type_support`](Self::type_support), but returns an error instead
+    /// of panicking if the type support cannot be loaded, see
+    /// `ros2_types::typesupport`.
+    ///
+    /// # Errors
+    /// Returns `Error::TypeSupportNotFound` or `Error::TypeSupportSymbolNotFound`
+    /// with the `lazy-typesupport` feature.
+    fn try_type_support() -> Result<*const c_void> {
+        Ok(Self::type_support())
+    }
+
     /// Returns the type name in DDS format.
     ///
     /// Example: `"example_interfaces::srv::dds_::AddTwoInts_"`
@@ -127,6 +269,17 @@ pub trait ActionMsg: 'static + Send + Sync {
         std::ptr::null()
     }
 
+    /// Like [`type_support`](Self::type_support), but returns an error instead
+    /// of panicking if the type support cannot be loaded, see
+    /// `ros2_types::typesupport`.
+    ///
+    /// # Errors
+    /// Returns `Error::TypeSupportNotFound` or `Error::TypeSupportSymbolNotFound`
+    /// with the `lazy-typesupport` feature.
+    fn try_type_support() -> Result<*const c_void> {
+        Ok(Self::type_support())
+    }
+
     /// Returns the type name in DDS format.
     ///
     /// Example: `"example_interfaces::srv::dds_::AddTwoInts_"`
@@ -143,6 +296,9 @@ pub trait ActionMsg: 'static + Send + Sync {
         uuid: [u8; 16],
     ) -> <Self::Goal as ActionGoal>::Request;
 
+    /// Create a new result request for the goal with the given UUID.
+    fn new_result_request(uuid: [u8; 16]) -> <Self::Result as ActionResult>::Request;
+
     /// The result content type (the actual result data).
     type ResultContent: TypeSupport + TryClone;
 
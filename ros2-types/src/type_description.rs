@@ -57,6 +57,29 @@ pub trait TypeDescription {
         let description = Self::type_description();
         calculate_type_hash(&description)
     }
+
+    /// Get the JSON Schema of the JSON representation of this type
+    ///
+    /// See [`TypeDescriptionMsg::to_json_schema`](crate::types::TypeDescriptionMsg::to_json_schema).
+    fn json_schema() -> serde_json::Value {
+        Self::type_description().to_json_schema()
+    }
+
+    /// Get the `(field, unit)` pairs of the fields that declare a unit
+    ///
+    /// Units come from the `@unit` annotations of the interface definition
+    /// (`[m/s]` in a `.msg` comment). They are not part of the type hash.
+    fn field_units() -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    /// Get the unit of a field, if it declares one
+    fn field_unit(field: &str) -> Option<&'static str> {
+        Self::field_units()
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, unit)| *unit)
+    }
 }
 
 /// Trait for ROS2 service types that can provide a type description for hash computation
@@ -35,7 +35,7 @@ pub struct Field {
 }
 
 /// Type information for a field
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FieldType {
     /// Type ID from FieldType.msg constants
     pub type_id: u8,
@@ -91,6 +91,10 @@ pub const FIELD_TYPE_BOOLEAN_ARRAY: u8 = 63;
 pub const FIELD_TYPE_BYTE_ARRAY: u8 = 64;
 pub const FIELD_TYPE_STRING_ARRAY: u8 = 65;
 pub const FIELD_TYPE_WSTRING_ARRAY: u8 = 66;
+pub const FIELD_TYPE_FIXED_STRING_ARRAY: u8 = 67;
+pub const FIELD_TYPE_FIXED_WSTRING_ARRAY: u8 = 68;
+pub const FIELD_TYPE_BOUNDED_STRING_ARRAY: u8 = 69;
+pub const FIELD_TYPE_BOUNDED_WSTRING_ARRAY: u8 = 70;
 
 // Bounded sequences (97-144)
 pub const FIELD_TYPE_NESTED_TYPE_BOUNDED_SEQUENCE: u8 = 97;
@@ -111,6 +115,10 @@ pub const FIELD_TYPE_BOOLEAN_BOUNDED_SEQUENCE: u8 = 111;
 pub const FIELD_TYPE_BYTE_BOUNDED_SEQUENCE: u8 = 112;
 pub const FIELD_TYPE_STRING_BOUNDED_SEQUENCE: u8 = 113;
 pub const FIELD_TYPE_WSTRING_BOUNDED_SEQUENCE: u8 = 114;
+pub const FIELD_TYPE_FIXED_STRING_BOUNDED_SEQUENCE: u8 = 115;
+pub const FIELD_TYPE_FIXED_WSTRING_BOUNDED_SEQUENCE: u8 = 116;
+pub const FIELD_TYPE_BOUNDED_STRING_BOUNDED_SEQUENCE: u8 = 117;
+pub const FIELD_TYPE_BOUNDED_WSTRING_BOUNDED_SEQUENCE: u8 = 118;
 
 // Unbounded sequences (145-192)
 pub const FIELD_TYPE_NESTED_TYPE_UNBOUNDED_SEQUENCE: u8 = 145;
@@ -131,6 +139,10 @@ pub const FIELD_TYPE_BOOLEAN_UNBOUNDED_SEQUENCE: u8 = 159;
 pub const FIELD_TYPE_BYTE_UNBOUNDED_SEQUENCE: u8 = 160;
 pub const FIELD_TYPE_STRING_UNBOUNDED_SEQUENCE: u8 = 161;
 pub const FIELD_TYPE_WSTRING_UNBOUNDED_SEQUENCE: u8 = 162;
+pub const FIELD_TYPE_FIXED_STRING_UNBOUNDED_SEQUENCE: u8 = 163;
+pub const FIELD_TYPE_FIXED_WSTRING_UNBOUNDED_SEQUENCE: u8 = 164;
+pub const FIELD_TYPE_BOUNDED_STRING_UNBOUNDED_SEQUENCE: u8 = 165;
+pub const FIELD_TYPE_BOUNDED_WSTRING_UNBOUNDED_SEQUENCE: u8 = 166;
 
 impl FieldType {
     /// Create a primitive field type
@@ -168,6 +180,7 @@ impl FieldType {
     /// For primitives: [primitive; N]
     pub fn array(base_type_id: u8, capacity: u64) -> Self {
         let array_type_id = match base_type_id {
+            FIELD_TYPE_NESTED_TYPE => FIELD_TYPE_NESTED_TYPE_ARRAY,
             FIELD_TYPE_INT8 => FIELD_TYPE_INT8_ARRAY,
             FIELD_TYPE_UINT8 => FIELD_TYPE_UINT8_ARRAY,
             FIELD_TYPE_INT16 => FIELD_TYPE_INT16_ARRAY,
@@ -185,7 +198,12 @@ impl FieldType {
             FIELD_TYPE_BYTE => FIELD_TYPE_BYTE_ARRAY,
             FIELD_TYPE_STRING => FIELD_TYPE_STRING_ARRAY,
             FIELD_TYPE_WSTRING => FIELD_TYPE_WSTRING_ARRAY,
-            _ => base_type_id, // Fallback
+            FIELD_TYPE_FIXED_STRING => FIELD_TYPE_FIXED_STRING_ARRAY,
+            FIELD_TYPE_FIXED_WSTRING => FIELD_TYPE_FIXED_WSTRING_ARRAY,
+            FIELD_TYPE_BOUNDED_STRING => FIELD_TYPE_BOUNDED_STRING_ARRAY,
+            FIELD_TYPE_BOUNDED_WSTRING => FIELD_TYPE_BOUNDED_WSTRING_ARRAY,
+            // ROS 2 has no arrays or sequences of arrays and sequences
+            _ => FIELD_TYPE_NOT_SET,
         };
         Self {
             type_id: array_type_id,
@@ -208,6 +226,7 @@ impl FieldType {
     /// Create an unbounded sequence (Vec) of primitives
     pub fn sequence(base_type_id: u8) -> Self {
         let sequence_type_id = match base_type_id {
+            FIELD_TYPE_NESTED_TYPE => FIELD_TYPE_NESTED_TYPE_UNBOUNDED_SEQUENCE,
             FIELD_TYPE_INT8 => FIELD_TYPE_INT8_UNBOUNDED_SEQUENCE,
             FIELD_TYPE_UINT8 => FIELD_TYPE_UINT8_UNBOUNDED_SEQUENCE,
             FIELD_TYPE_INT16 => FIELD_TYPE_INT16_UNBOUNDED_SEQUENCE,
@@ -225,7 +244,12 @@ impl FieldType {
             FIELD_TYPE_BYTE => FIELD_TYPE_BYTE_UNBOUNDED_SEQUENCE,
             FIELD_TYPE_STRING => FIELD_TYPE_STRING_UNBOUNDED_SEQUENCE,
             FIELD_TYPE_WSTRING => FIELD_TYPE_WSTRING_UNBOUNDED_SEQUENCE,
-            _ => base_type_id, // Fallback
+            FIELD_TYPE_FIXED_STRING => FIELD_TYPE_FIXED_STRING_UNBOUNDED_SEQUENCE,
+            FIELD_TYPE_FIXED_WSTRING => FIELD_TYPE_FIXED_WSTRING_UNBOUNDED_SEQUENCE,
+            FIELD_TYPE_BOUNDED_STRING => FIELD_TYPE_BOUNDED_STRING_UNBOUNDED_SEQUENCE,
+            FIELD_TYPE_BOUNDED_WSTRING => FIELD_TYPE_BOUNDED_WSTRING_UNBOUNDED_SEQUENCE,
+            // ROS 2 has no arrays or sequences of arrays and sequences
+            _ => FIELD_TYPE_NOT_SET,
         };
         Self {
             type_id: sequence_type_id,
@@ -268,6 +292,7 @@ impl FieldType {
     /// Create a bounded sequence of primitives
     pub fn bounded_sequence(base_type_id: u8, capacity: u64) -> Self {
         let sequence_type_id = match base_type_id {
+            FIELD_TYPE_NESTED_TYPE => FIELD_TYPE_NESTED_TYPE_BOUNDED_SEQUENCE,
             FIELD_TYPE_INT8 => FIELD_TYPE_INT8_BOUNDED_SEQUENCE,
             FIELD_TYPE_UINT8 => FIELD_TYPE_UINT8_BOUNDED_SEQUENCE,
             FIELD_TYPE_INT16 => FIELD_TYPE_INT16_BOUNDED_SEQUENCE,
@@ -285,7 +310,12 @@ impl FieldType {
             FIELD_TYPE_BYTE => FIELD_TYPE_BYTE_BOUNDED_SEQUENCE,
             FIELD_TYPE_STRING => FIELD_TYPE_STRING_BOUNDED_SEQUENCE,
             FIELD_TYPE_WSTRING => FIELD_TYPE_WSTRING_BOUNDED_SEQUENCE,
-            _ => base_type_id, // Fallback
+            FIELD_TYPE_FIXED_STRING => FIELD_TYPE_FIXED_STRING_BOUNDED_SEQUENCE,
+            FIELD_TYPE_FIXED_WSTRING => FIELD_TYPE_FIXED_WSTRING_BOUNDED_SEQUENCE,
+            FIELD_TYPE_BOUNDED_STRING => FIELD_TYPE_BOUNDED_STRING_BOUNDED_SEQUENCE,
+            FIELD_TYPE_BOUNDED_WSTRING => FIELD_TYPE_BOUNDED_WSTRING_BOUNDED_SEQUENCE,
+            // ROS 2 has no arrays or sequences of arrays and sequences
+            _ => FIELD_TYPE_NOT_SET,
         };
         Self {
             type_id: sequence_type_id,
@@ -324,6 +354,40 @@ impl FieldType {
             nested_type_name: type_name.into(),
         }
     }
+
+    /// Create a fixed-size array of `element`, keeping its nested type name
+    /// and string capacity (e.g., `string<=M[N]`)
+    pub fn array_of(element: FieldType, capacity: u64) -> Self {
+        Self::container_of(element, |type_id| Self::array(type_id, capacity))
+    }
+
+    /// Create an unbounded sequence of `element`, keeping its nested type name
+    /// and string capacity (e.g., `string<=M[]`)
+    pub fn sequence_of(element: FieldType) -> Self {
+        Self::container_of(element, Self::sequence)
+    }
+
+    /// Create a bounded sequence of `element`, keeping its nested type name
+    /// and string capacity (e.g., `string<=M[<=N]`)
+    pub fn bounded_sequence_of(element: FieldType, capacity: u64) -> Self {
+        Self::container_of(element, |type_id| Self::bounded_sequence(type_id, capacity))
+    }
+
+    /// Map the type ID of `element` with `container`, which yields
+    /// [`FIELD_TYPE_NOT_SET`] for an element that is already a container
+    fn container_of(element: FieldType, container: impl FnOnce(u8) -> Self) -> Self {
+        let FieldType {
+            type_id,
+            string_capacity,
+            nested_type_name,
+            ..
+        } = element;
+        Self {
+            string_capacity,
+            nested_type_name,
+            ..container(type_id)
+        }
+    }
 }
 
 impl Field {
@@ -668,3 +732,149 @@ impl TypeDescriptionMsg {
         }
     }
 }
+
+// ============================================================================
+// JSON Schema generation
+// ============================================================================
+
+/// Dialect of the generated JSON Schemas.
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Escape a type name for use in a JSON pointer (`/` becomes `~1`).
+fn json_pointer_escape(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+/// JSON Schema of a base type_id, as serialized by `serde_json`.
+fn base_type_to_json_schema(ft: &FieldType, base_id: u8) -> serde_json::Value {
+    use serde_json::json;
+
+    match base_id {
+        FIELD_TYPE_NESTED_TYPE => json!({
+            "$ref": format!("#/$defs/{}", json_pointer_escape(&ft.nested_type_name)),
+        }),
+        FIELD_TYPE_INT8 => json!({ "type": "integer", "minimum": i8::MIN, "maximum": i8::MAX }),
+        FIELD_TYPE_UINT8 | FIELD_TYPE_CHAR | FIELD_TYPE_BYTE => {
+            json!({ "type": "integer", "minimum": u8::MIN, "maximum": u8::MAX })
+        }
+        FIELD_TYPE_INT16 => json!({ "type": "integer", "minimum": i16::MIN, "maximum": i16::MAX }),
+        FIELD_TYPE_UINT16 | FIELD_TYPE_WCHAR => {
+            json!({ "type": "integer", "minimum": u16::MIN, "maximum": u16::MAX })
+        }
+        FIELD_TYPE_INT32 => json!({ "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX }),
+        FIELD_TYPE_UINT32 => json!({ "type": "integer", "minimum": u32::MIN, "maximum": u32::MAX }),
+        FIELD_TYPE_INT64 => json!({ "type": "integer", "minimum": i64::MIN, "maximum": i64::MAX }),
+        FIELD_TYPE_UINT64 => json!({ "type": "integer", "minimum": u64::MIN, "maximum": u64::MAX }),
+        FIELD_TYPE_FLOAT | FIELD_TYPE_DOUBLE | FIELD_TYPE_LONG_DOUBLE => {
+            json!({ "type": "number" })
+        }
+        FIELD_TYPE_BOOLEAN => json!({ "type": "boolean" }),
+        FIELD_TYPE_STRING
+        | FIELD_TYPE_WSTRING
+        | FIELD_TYPE_BOUNDED_STRING
+        | FIELD_TYPE_BOUNDED_WSTRING
+        | FIELD_TYPE_FIXED_STRING
+        | FIELD_TYPE_FIXED_WSTRING => {
+            if ft.string_capacity > 0 {
+                json!({ "type": "string", "maxLength": ft.string_capacity })
+            } else {
+                json!({ "type": "string" })
+            }
+        }
+        _ => json!({}),
+    }
+}
+
+impl TypeDescriptionMsg {
+    /// Generate a JSON Schema (draft 2020-12) of the JSON representation of
+    /// this type, i.e. of the messages serialized with `serde_json`.
+    ///
+    /// Referenced types are placed under `$defs`, keyed by their fully
+    /// qualified name, and nested fields point to them with `$ref`. Integer
+    /// fields are bounded by the range of their type, arrays and bounded
+    /// sequences by their capacity, and bounded strings by their length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ros2_types::types::{Field, FieldType, IndividualTypeDescription, TypeDescriptionMsg};
+    /// use ros2_types::FIELD_TYPE_INT32;
+    ///
+    /// let desc = TypeDescriptionMsg::new(
+    ///     IndividualTypeDescription::new(
+    ///         "example_interfaces/msg/Int32",
+    ///         vec![Field::new("data", FieldType::primitive(FIELD_TYPE_INT32))],
+    ///     ),
+    ///     vec![],
+    /// );
+    /// let schema = desc.to_json_schema();
+    /// assert_eq!(schema["title"], "example_interfaces/msg/Int32");
+    /// assert_eq!(schema["properties"]["data"]["type"], "integer");
+    /// ```
+    pub fn to_json_schema(&self) -> serde_json::Value {
+        let mut schema = Self::individual_json_schema(&self.type_description);
+        schema["$schema"] = JSON_SCHEMA_DIALECT.into();
+
+        if !self.referenced_type_descriptions.is_empty() {
+            let defs: serde_json::Map<_, _> = self
+                .referenced_type_descriptions
+                .iter()
+                .map(|desc| (desc.type_name.clone(), Self::individual_json_schema(desc)))
+                .collect();
+            schema["$defs"] = defs.into();
+        }
+
+        schema
+    }
+
+    fn individual_json_schema(desc: &IndividualTypeDescription) -> serde_json::Value {
+        let properties: serde_json::Map<_, _> = desc
+            .fields
+            .iter()
+            .map(|field| {
+                (
+                    field.name.clone(),
+                    Self::field_type_to_json_schema(&field.field_type),
+                )
+            })
+            .collect();
+        let required: Vec<_> = desc.fields.iter().map(|field| field.name.clone()).collect();
+
+        serde_json::json!({
+            "title": desc.type_name,
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        })
+    }
+
+    fn field_type_to_json_schema(ft: &FieldType) -> serde_json::Value {
+        let (base_id, kind) = decompose_type_id(ft.type_id);
+        let items = base_type_to_json_schema(ft, base_id);
+
+        match kind {
+            'a' => serde_json::json!({
+                "type": "array",
+                "items": items,
+                "minItems": ft.capacity,
+                "maxItems": ft.capacity,
+            }),
+            'b' => serde_json::json!({
+                "type": "array",
+                "items": items,
+                "maxItems": ft.capacity,
+            }),
+            'u' => serde_json::json!({ "type": "array", "items": items }),
+            _ => items,
+        }
+    }
+
+    /// Generate the JSON Schema of [`TypeDescriptionMsg::to_json_schema`] as a
+    /// [`schemars::Schema`].
+    #[cfg(feature = "schemars")]
+    pub fn to_schemars(&self) -> schemars::Schema {
+        schemars::Schema::try_from(self.to_json_schema())
+            .expect("JSON Schema of a type description is an object")
+    }
+}
@@ -0,0 +1,218 @@
+//! Runtime resolution of the rosidl libraries of interface packages.
+//!
+//! With the `rcl` feature, the generated messages call the C functions of the
+//! `<package>__rosidl_generator_c` and `<package>__rosidl_typesupport_c`
+//! libraries of their package. These are normally linked into the binary, which
+//! then does not start unless every message package it was built with is
+//! installed.
+//!
+//! With the `lazy-typesupport` feature, the generated code resolves these
+//! functions at their first call instead. The library is searched in the library
+//! directory of each prefix of `AMENT_PREFIX_PATH`, then loaded by name through
+//! the paths of the dynamic loader. A missing package is reported as
+//! [`Error::TypeSupportNotFound`]:
+//!
+//! ```text
+//! package nav_msgs typesupport not found in AMENT_PREFIX_PATH (libnav_msgs__rosidl_typesupport_c.so: ...)
+//! ```
+//!
+//! [`TypeSupport::try_type_support`](crate::TypeSupport::try_type_support) and
+//! its service and action counterparts return this error, so creating a
+//! publisher, subscriber, client or server of a missing interface fails with
+//! it. Any other use of the interface, such as `new()`, panics with the same
+//! message.
+
+use crate::{Error, Result};
+use libloading::Library;
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+/// Library of the functions handling the message structures (`init`, `fini`,
+/// `copy`, ...).
+pub const GENERATOR_C: &str = "rosidl_generator_c";
+
+/// Library of the type support handles.
+pub const TYPESUPPORT_C: &str = "rosidl_typesupport_c";
+
+/// Directory of the shared libraries in an install prefix.
+const LIB_DIR: &str = if cfg!(target_os = "windows") {
+    "bin"
+} else {
+    "lib"
+};
+
+/// File name of the `library` of `package`, e.g.
+/// `libstd_msgs__rosidl_typesupport_c.so` on Linux.
+pub fn library_file_name(package: &str, library: &str) -> String {
+    libloading::library_filename(format!("{package}__{library}"))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Directories searched for the libraries, the library directory of each
+/// prefix of `AMENT_PREFIX_PATH`.
+pub fn search_paths() -> Vec<PathBuf> {
+    std::env::var_os("AMENT_PREFIX_PATH")
+        .map(|paths| {
+            std::env::split_paths(&paths)
+                .filter(|path| !path.as_os_str().is_empty())
+                .map(|path| path.join(LIB_DIR))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Load the `library` of `package`.
+///
+/// Libraries are loaded once and never unloaded.
+///
+/// # Errors
+/// Returns [`Error::TypeSupportNotFound`] if the library cannot be loaded.
+pub fn load(package: &str, library: &str) -> Result<&'static Library> {
+    static LIBRARIES: OnceLock<Mutex<HashMap<String, &'static Library>>> = OnceLock::new();
+
+    let file_name = library_file_name(package, library);
+    let mut libraries = LIBRARIES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(lib) = libraries.get(&file_name) {
+        return Ok(lib);
+    }
+
+    let lib = open(&file_name).map_err(|e| Error::TypeSupportNotFound {
+        package: package.to_string(),
+        library: file_name.clone(),
+        reason: e.to_string(),
+    })?;
+    let lib: &'static Library = Box::leak(Box::new(lib));
+    libraries.insert(file_name, lib);
+    Ok(lib)
+}
+
+fn open(file_name: &str) -> std::result::Result<Library, libloading::Error> {
+    for dir in search_paths() {
+        let path = dir.join(file_name);
+        if path.is_file() {
+            // SAFETY: rosidl libraries have no initialization routines with
+            // preconditions.
+            return unsafe { Library::new(path) };
+        }
+    }
+    // Not in AMENT_PREFIX_PATH, e.g. installed along the binary
+    // SAFETY: as above.
+    unsafe { Library::new(file_name) }
+}
+
+/// Resolve the function `name` of the `library` of `package`.
+///
+/// # Errors
+/// Returns [`Error::TypeSupportNotFound`] if the library cannot be loaded, and
+/// [`Error::TypeSupportSymbolNotFound`] if it has no such function.
+///
+/// # Safety
+/// `T` must be the function pointer type of the symbol.
+pub unsafe fn symbol<T: Copy + 'static>(package: &str, library: &str, name: &str) -> Result<T> {
+    let lib = load(package, library)?;
+    // SAFETY: the type of the symbol is guaranteed by the caller.
+    unsafe { lib.get::<T>(name.as_bytes()) }
+        .map(|symbol| *symbol)
+        .map_err(|e| Error::TypeSupportSymbolNotFound {
+            package: package.to_string(),
+            symbol: name.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+/// Call the type support getter `name` of `package`, e.g.
+/// `rosidl_typesupport_c__get_message_type_support_handle__std_msgs__msg__String`.
+///
+/// # Errors
+/// See [`symbol`].
+///
+/// # Safety
+/// `name` must be a type support getter, without arguments and returning the
+/// type support handle.
+pub unsafe fn type_support_handle(package: &str, name: &str) -> Result<*const c_void> {
+    // SAFETY: the signature is guaranteed by the caller.
+    let getter =
+        unsafe { symbol::<unsafe extern "C" fn() -> *const c_void>(package, TYPESUPPORT_C, name)? };
+    // SAFETY: as above.
+    Ok(unsafe { getter() })
+}
+
+/// Check that the libraries of `package` can be loaded, e.g. at startup to
+/// fail early on a missing package.
+///
+/// # Errors
+/// Returns [`Error::TypeSupportNotFound`] for the first library that cannot be
+/// loaded.
+pub fn check_package(package: &str) -> Result<()> {
+    load(package, GENERATOR_C)?;
+    load(package, TYPESUPPORT_C)?;
+    Ok(())
+}
+
+/// Declare functions of a rosidl library, resolved at their first call.
+///
+/// The `Ros2Msg` derive uses it instead of `extern "C"` blocks with the
+/// `lazy-typesupport` feature. The declared functions have the signature of
+/// the C declarations. They panic with the error of [`symbol`] if the library
+/// or the function is missing.
+///
+/// ```ignore
+/// ros2_types::lazy_ffi! {
+///     "std_msgs", ros2_types::typesupport::GENERATOR_C;
+///     fn std_msgs__msg__String__init(msg: *mut String) -> bool;
+/// }
+/// ```
+#[macro_export]
+macro_rules! lazy_ffi {
+    (
+        $package:expr, $library:expr;
+        $(fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;)*
+    ) => {
+        $(
+            #[allow(non_snake_case, dead_code)]
+            unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+                static SYMBOL: ::std::sync::OnceLock<unsafe extern "C" fn($($ty),*) $(-> $ret)?> =
+                    ::std::sync::OnceLock::new();
+                let symbol = SYMBOL.get_or_init(|| {
+                    // SAFETY: the signature is the one of the C declaration.
+                    unsafe { $crate::typesupport::symbol($package, $library, stringify!($name)) }
+                        .unwrap_or_else(|e| panic!("{e}"))
+                });
+                // SAFETY: the caller upholds the contract of the C function.
+                unsafe { symbol($($arg),*) }
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_package() {
+        let err = check_package("oxidros_missing_msgs").unwrap_err();
+        assert!(matches!(
+            &err,
+            Error::TypeSupportNotFound { package, .. } if package == "oxidros_missing_msgs"
+        ));
+        assert!(
+            err.to_string().starts_with(
+                "package oxidros_missing_msgs typesupport not found in AMENT_PREFIX_PATH"
+            ),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_library_file_name() {
+        let name = library_file_name("std_msgs", TYPESUPPORT_C);
+        assert!(name.contains("std_msgs__rosidl_typesupport_c"), "{name}");
+    }
+}
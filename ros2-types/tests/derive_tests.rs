@@ -110,6 +110,25 @@ fn test_nested_type_description() {
     );
 }
 
+#[test]
+fn test_nested_json_schema() {
+    let schema = Pose::json_schema();
+    assert_eq!(schema["title"], "geometry_msgs/msg/Pose");
+    assert_eq!(schema["properties"]["orientation_w"]["type"], "number");
+    assert_eq!(
+        schema["properties"]["position"]["$ref"],
+        "#/$defs/geometry_msgs~1msg~1Point"
+    );
+
+    let point = &schema["$defs"]["geometry_msgs/msg/Point"];
+    assert_eq!(point["type"], "object");
+    assert_eq!(point["required"], serde_json::json!(["x", "y", "z"]));
+
+    let time = Time::json_schema();
+    assert_eq!(time["properties"]["sec"]["minimum"], i32::MIN);
+    assert_eq!(time["properties"]["nanosec"]["maximum"], u32::MAX);
+}
+
 // =============================================================================
 // Service Message Test (Request/Response)
 // =============================================================================
@@ -381,3 +400,191 @@ fn test_large_array_eq() {
     msg2.points[32].y = 42.0;
     assert_eq!(msg1, msg2);
 }
+
+// =============================================================================
+// Field Units Test
+// =============================================================================
+
+/// A message whose fields declare units, like `float64 linear # [m/s]`
+#[derive(Debug, Ros2Msg, TypeDescription, serde::Serialize, serde::Deserialize)]
+#[ros2(package = "test_msgs", interface_type = "msg")]
+#[repr(C)]
+pub struct Velocity {
+    #[ros2(unit = "m/s")]
+    pub linear: f64,
+    #[ros2(unit = "rad/s")]
+    pub angular: f64,
+    pub frame: u8,
+}
+
+#[test]
+fn test_field_units() {
+    assert_eq!(Velocity::field_unit("linear"), Some("m/s"));
+    assert_eq!(Velocity::field_unit("angular"), Some("rad/s"));
+    assert_eq!(Velocity::field_unit("frame"), None);
+    assert_eq!(Velocity::field_unit("missing"), None);
+    assert!(Time::field_units().is_empty());
+}
+
+/// The same message without units
+mod without_units {
+    use ros2_types::{Ros2Msg, TypeDescription};
+
+    #[derive(Debug, Ros2Msg, TypeDescription, serde::Serialize, serde::Deserialize)]
+    #[ros2(package = "test_msgs", interface_type = "msg")]
+    #[repr(C)]
+    pub struct Velocity {
+        pub linear: f64,
+        pub angular: f64,
+        pub frame: u8,
+    }
+}
+
+#[test]
+fn test_field_units_not_hashed() {
+    assert_eq!(
+        Velocity::compute_hash().unwrap(),
+        without_units::Velocity::compute_hash().unwrap()
+    );
+}
+
+// =============================================================================
+// Bounded String Shapes Test
+// =============================================================================
+
+/// Bounded strings in every container, like test_msgs/Strings and
+/// test_msgs/BoundedSequences
+#[derive(Debug, Ros2Msg, TypeDescription, serde::Serialize, serde::Deserialize)]
+#[ros2(package = "test_msgs", interface_type = "msg")]
+#[repr(C)]
+pub struct BoundedShapes {
+    #[ros2(string, capacity = 10)]
+    pub bounded_string: String,
+    #[ros2(string, string_capacity = 5)]
+    pub bounded_string_array: [String; 3],
+    #[ros2(sequence, string, string_capacity = 5)]
+    pub bounded_string_sequence: Vec<String>,
+    #[ros2(sequence, string, capacity = 3, string_capacity = 5)]
+    pub bounded_string_bounded_sequence: Vec<String>,
+    #[ros2(ros2_type = "wstring", capacity = 10)]
+    pub bounded_wstring: String,
+    #[ros2(ros2_type = "wstring", string_capacity = 5)]
+    pub bounded_wstring_array: [String; 3],
+    #[ros2(ros2_type = "wstring", sequence, capacity = 3, string_capacity = 5)]
+    pub bounded_wstring_bounded_sequence: Vec<String>,
+    #[ros2(sequence, capacity = 3)]
+    pub bounded_point_sequence: Vec<Point>,
+}
+
+#[test]
+fn test_bounded_shapes_field_types() {
+    let desc = BoundedShapes::type_description();
+    let fields: Vec<_> = desc
+        .type_description
+        .fields
+        .iter()
+        .map(|f| {
+            let t = &f.field_type;
+            (
+                t.type_id,
+                t.capacity,
+                t.string_capacity,
+                t.nested_type_name.as_str(),
+            )
+        })
+        .collect();
+
+    // Field types as in the type descriptions generated by rosidl
+    assert_eq!(
+        fields,
+        [
+            (21, 0, 10, ""),
+            (69, 3, 5, ""),
+            (165, 0, 5, ""),
+            (117, 3, 5, ""),
+            (22, 0, 10, ""),
+            (70, 3, 5, ""),
+            (118, 3, 5, ""),
+            (97, 3, 0, "geometry_msgs/msg/Point"),
+        ]
+    );
+    assert_eq!(desc.referenced_type_descriptions.len(), 1);
+}
+
+// =============================================================================
+// Stamped Message Test
+// =============================================================================
+
+/// A header like std_msgs/Header, without the frame_id
+#[derive(Debug, Ros2Msg, TypeDescription, serde::Serialize, serde::Deserialize)]
+#[ros2(package = "std_msgs", interface_type = "msg")]
+#[repr(C)]
+pub struct Header {
+    pub stamp: Time,
+}
+
+/// A message with a header
+#[derive(Debug, Ros2Msg, TypeDescription, serde::Serialize, serde::Deserialize)]
+#[ros2(package = "geometry_msgs", interface_type = "msg")]
+#[repr(C)]
+pub struct PointStamped {
+    pub header: Header,
+    pub point: Point,
+}
+
+#[test]
+fn test_stamped() {
+    use ros2_types::{Stamped, UnsafeTime};
+
+    let mut msg = PointStamped::default();
+    assert_eq!(msg.stamp(), UnsafeTime::zero());
+
+    msg.set_stamp(UnsafeTime::new(12, 34));
+    assert_eq!((msg.header.stamp.sec, msg.header.stamp.nanosec), (12, 34));
+    assert_eq!(msg.stamp(), UnsafeTime::new(12, 34));
+}
+
+// =============================================================================
+// Keyed Message Test
+// =============================================================================
+
+/// An identifier with a key field
+#[derive(Debug, Ros2Msg, TypeDescription, serde::Serialize, serde::Deserialize)]
+#[ros2(package = "test_msgs", interface_type = "msg")]
+#[repr(C)]
+pub struct ObjectId {
+    #[ros2(key)]
+    pub uuid: [u8; 4],
+}
+
+/// A tracked object, keyed by its sensor and its nested identifier
+#[derive(Debug, Ros2Msg, TypeDescription, serde::Serialize, serde::Deserialize)]
+#[ros2(package = "test_msgs", interface_type = "msg")]
+#[repr(C)]
+pub struct TrackedObject {
+    #[ros2(key)]
+    pub sensor: String,
+    #[ros2(key)]
+    pub id: ObjectId,
+    pub position: Point,
+}
+
+#[test]
+fn test_keyed() {
+    use ros2_types::Keyed;
+
+    let mut msg = TrackedObject {
+        sensor: "lidar".to_string(),
+        id: ObjectId { uuid: [1, 2, 3, 4] },
+        position: Point::default(),
+    };
+    assert_eq!(msg.id.key(), ([1, 2, 3, 4],));
+    assert_eq!(msg.key(), ("lidar".to_string(), ([1, 2, 3, 4],)));
+
+    // Fields outside of the key do not change the instance
+    let key = msg.key();
+    msg.position.x = 1.0;
+    assert_eq!(msg.key(), key);
+    msg.id.uuid[0] = 9;
+    assert!(msg.key() > key);
+}
@@ -210,3 +210,40 @@ fn test_trait_compute_hash() {
     assert!(hash.starts_with("RIHS01_"));
     assert_eq!(hash.len(), 71);
 }
+
+#[test]
+fn test_container_field_types() {
+    // Containers keep the string capacity and the nested type of the element
+    let bounded = FieldType::bounded_wstring(8);
+    let array = FieldType::array_of(bounded.clone(), 4);
+    assert_eq!(
+        (array.type_id, array.capacity, array.string_capacity),
+        (FIELD_TYPE_BOUNDED_WSTRING_ARRAY, 4, 8)
+    );
+    let sequence = FieldType::sequence_of(bounded.clone());
+    assert_eq!(
+        (
+            sequence.type_id,
+            sequence.capacity,
+            sequence.string_capacity
+        ),
+        (FIELD_TYPE_BOUNDED_WSTRING_UNBOUNDED_SEQUENCE, 0, 8)
+    );
+    let sequence = FieldType::bounded_sequence_of(bounded, 2);
+    assert_eq!(
+        (
+            sequence.type_id,
+            sequence.capacity,
+            sequence.string_capacity
+        ),
+        (FIELD_TYPE_BOUNDED_WSTRING_BOUNDED_SEQUENCE, 2, 8)
+    );
+    assert_eq!(
+        FieldType::bounded_sequence_of(FieldType::nested("pkg/msg/Inner"), 3),
+        FieldType::nested_bounded_sequence("pkg/msg/Inner", 3)
+    );
+
+    // ROS 2 has no sequences of arrays
+    let nested = FieldType::sequence_of(FieldType::array(FIELD_TYPE_INT32, 3));
+    assert_eq!(nested.type_id, FIELD_TYPE_NOT_SET);
+}
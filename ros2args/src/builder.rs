@@ -0,0 +1,416 @@
+//! Generation of ROS2 command-line arguments
+//!
+//! This is the reverse of the parser: [`Ros2Args::to_args`] turns parsed or
+//! built arguments back into a `--ros-args` argv, e.g. to spawn a child
+//! process, and [`Ros2ArgsBuilder`] builds them from typed values.
+
+use std::{fmt::Write, path::PathBuf};
+use yaml_rust2::{Yaml, YamlLoader, yaml::Hash};
+
+use crate::{
+    errors::{Ros2ArgsError, Ros2ArgsResult},
+    types::{LogLevel, LogLevelAssignment, ParamAssignment, RemapRule, Ros2Args},
+};
+
+/// Conversion of a typed value into the YAML value of a parameter
+pub trait IntoYaml {
+    /// Convert the value into YAML
+    fn into_yaml(self) -> Yaml;
+}
+
+impl IntoYaml for Yaml {
+    fn into_yaml(self) -> Yaml {
+        self
+    }
+}
+
+impl IntoYaml for bool {
+    fn into_yaml(self) -> Yaml {
+        Yaml::Boolean(self)
+    }
+}
+
+macro_rules! impl_into_yaml_int {
+    ($($ty:ty),*) => {
+        $(
+            impl IntoYaml for $ty {
+                fn into_yaml(self) -> Yaml {
+                    Yaml::Integer(i64::from(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_into_yaml_int!(i8, i16, i32, i64, u8, u16, u32);
+
+impl IntoYaml for f64 {
+    fn into_yaml(self) -> Yaml {
+        let repr = if self.is_nan() {
+            ".nan".to_string()
+        } else if self.is_infinite() {
+            if self > 0.0 { ".inf" } else { "-.inf" }.to_string()
+        } else {
+            // `{:?}` keeps a decimal point, so that the value stays a float
+            format!("{self:?}")
+        };
+        Yaml::Real(repr)
+    }
+}
+
+impl IntoYaml for f32 {
+    fn into_yaml(self) -> Yaml {
+        f64::from(self).into_yaml()
+    }
+}
+
+impl IntoYaml for &str {
+    fn into_yaml(self) -> Yaml {
+        Yaml::String(self.to_string())
+    }
+}
+
+impl IntoYaml for String {
+    fn into_yaml(self) -> Yaml {
+        Yaml::String(self)
+    }
+}
+
+impl<T: IntoYaml> IntoYaml for Vec<T> {
+    fn into_yaml(self) -> Yaml {
+        Yaml::Array(self.into_iter().map(IntoYaml::into_yaml).collect())
+    }
+}
+
+/// Builder of [`Ros2Args`] from typed values
+///
+/// # Examples
+///
+/// ```
+/// use ros2args::{LogLevel, Ros2ArgsBuilder, parse_ros2_args};
+///
+/// let args = Ros2ArgsBuilder::new()
+///     .remap("image", "/camera/image_raw")
+///     .node_remap("detector", "__ns", "/robot")
+///     .param("use_sim_time", true)
+///     .param("frame_id", "camera: left")
+///     .param("gains", vec![1.0, 0.5])
+///     .log_level(LogLevel::Debug)
+///     .build_args()?;
+/// assert_eq!(args[0], "--ros-args");
+///
+/// // The generated arguments parse back to the same values
+/// let (ros_args, _) = parse_ros2_args(&args)?;
+/// assert_eq!(ros_args.param_assignments[1].as_str(), Some("camera: left"));
+/// # Ok::<(), ros2args::Ros2ArgsError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Ros2ArgsBuilder {
+    args: Ros2Args,
+}
+
+impl Ros2ArgsBuilder {
+    /// Create a builder without argument
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remap `from` to `to` for all nodes
+    #[must_use]
+    pub fn remap(mut self, from: &str, to: &str) -> Self {
+        self.args
+            .remap_rules
+            .push(RemapRule::new_global(from.to_string(), to.to_string()));
+        self
+    }
+
+    /// Remap `from` to `to` for the node `node_name` only
+    #[must_use]
+    pub fn node_remap(mut self, node_name: &str, from: &str, to: &str) -> Self {
+        self.args.remap_rules.push(RemapRule::new_node_specific(
+            node_name.to_string(),
+            from.to_string(),
+            to.to_string(),
+        ));
+        self
+    }
+
+    /// Set the parameter `name` for all nodes
+    #[must_use]
+    pub fn param(mut self, name: &str, value: impl IntoYaml) -> Self {
+        self.args
+            .param_assignments
+            .push(ParamAssignment::new_global(
+                name.to_string(),
+                value.into_yaml(),
+            ));
+        self
+    }
+
+    /// Set the parameter `name` for the node `node_name` only
+    #[must_use]
+    pub fn node_param(mut self, node_name: &str, name: &str, value: impl IntoYaml) -> Self {
+        self.args
+            .param_assignments
+            .push(ParamAssignment::new_node_specific(
+                node_name.to_string(),
+                name.to_string(),
+                value.into_yaml(),
+            ));
+        self
+    }
+
+    /// Load the parameters of a YAML file
+    #[must_use]
+    pub fn params_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.args.param_files.push(path.into());
+        self
+    }
+
+    /// Set the log level of all loggers
+    #[must_use]
+    pub fn log_level(mut self, level: LogLevel) -> Self {
+        self.args
+            .log_levels
+            .push(LogLevelAssignment::new_global(level));
+        self
+    }
+
+    /// Set the log level of the logger `logger_name`
+    #[must_use]
+    pub fn logger_level(mut self, logger_name: &str, level: LogLevel) -> Self {
+        self.args
+            .log_levels
+            .push(LogLevelAssignment::new_logger_specific(
+                logger_name.to_string(),
+                level,
+            ));
+        self
+    }
+
+    /// Set the log configuration file
+    #[must_use]
+    pub fn log_config_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.args.log_config_file = Some(path.into());
+        self
+    }
+
+    /// Enable or disable rosout logging
+    #[must_use]
+    pub fn rosout_logs(mut self, enable: bool) -> Self {
+        self.args.logging_output.rosout = Some(enable);
+        self
+    }
+
+    /// Enable or disable stdout logging
+    #[must_use]
+    pub fn stdout_logs(mut self, enable: bool) -> Self {
+        self.args.logging_output.stdout = Some(enable);
+        self
+    }
+
+    /// Enable or disable external library logging
+    #[must_use]
+    pub fn external_lib_logs(mut self, enable: bool) -> Self {
+        self.args.logging_output.external_lib = Some(enable);
+        self
+    }
+
+    /// Set the security enclave
+    #[must_use]
+    pub fn enclave(mut self, enclave: &str) -> Self {
+        self.args.enclave = Some(enclave.to_string());
+        self
+    }
+
+    /// Build the arguments
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a remapping rule, a parameter name or a logger
+    /// name cannot be written on the command line, e.g. because it contains
+    /// `:=`, or if the enclave is not a fully qualified path.
+    pub fn build(self) -> Ros2ArgsResult<Ros2Args> {
+        for rule in &self.args.remap_rules {
+            let node_ok = rule.node_name.as_deref().is_none_or(is_plain_name);
+            if !node_ok || !is_plain_name(&rule.from) || rule.to.contains(":=") {
+                return Err(Ros2ArgsError::InvalidRemapRule(format_remap_rule(rule)));
+            }
+        }
+        for param in &self.args.param_assignments {
+            let node_ok = param.node_name.as_deref().is_none_or(is_plain_name);
+            if !node_ok || !is_plain_name(&param.name) {
+                return Err(Ros2ArgsError::InvalidParamAssignment(param.name.clone()));
+            }
+        }
+        for log_level in &self.args.log_levels {
+            if log_level
+                .logger_name
+                .as_deref()
+                .is_some_and(|name| name.contains(":="))
+            {
+                return Err(Ros2ArgsError::InvalidLogLevelAssignment(format_log_level(
+                    log_level,
+                )));
+            }
+        }
+        if let Some(enclave) = &self.args.enclave
+            && !enclave.starts_with('/')
+        {
+            return Err(Ros2ArgsError::InvalidEnclavePath(enclave.clone()));
+        }
+        Ok(self.args)
+    }
+
+    /// Build the arguments as a `--ros-args` argv
+    ///
+    /// # Errors
+    ///
+    /// See [`Ros2ArgsBuilder::build`].
+    pub fn build_args(self) -> Ros2ArgsResult<Vec<String>> {
+        Ok(self.build()?.to_args())
+    }
+}
+
+impl Ros2Args {
+    /// Generate the command-line arguments, starting with `--ros-args`
+    ///
+    /// Parsing the result gives back the same arguments. Parameter values are
+    /// written as YAML, quoted when a plain scalar would be read as another
+    /// value or type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ros2args::parse_ros2_args;
+    ///
+    /// let args: Vec<String> = ["program", "--ros-args", "-r", "foo:=bar", "-p", "name:='42'"]
+    ///     .iter()
+    ///     .map(ToString::to_string)
+    ///     .collect();
+    /// let (ros_args, _) = parse_ros2_args(&args)?;
+    ///
+    /// assert_eq!(ros_args.to_args(), ["--ros-args", "-r", "foo:=bar", "-p", "name:='42'"]);
+    /// # Ok::<(), ros2args::Ros2ArgsError>(())
+    /// ```
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec!["--ros-args".to_string()];
+        let mut push = |flag: &str, value: String| {
+            args.push(flag.to_string());
+            args.push(value);
+        };
+
+        for rule in &self.remap_rules {
+            push("-r", format_remap_rule(rule));
+        }
+        for param in &self.param_assignments {
+            let name = match &param.node_name {
+                Some(node) => format!("{node}:{}", param.name),
+                None => param.name.clone(),
+            };
+            push("-p", format!("{name}:={}", yaml_to_arg(&param.value)));
+        }
+        for path in &self.param_files {
+            push("--params-file", path.display().to_string());
+        }
+        for log_level in &self.log_levels {
+            push("--log-level", format_log_level(log_level));
+        }
+        if let Some(path) = &self.log_config_file {
+            push("--log-config-file", path.display().to_string());
+        }
+        if let Some(enclave) = &self.enclave {
+            push("--enclave", enclave.clone());
+        }
+
+        let flags = [
+            (self.logging_output.rosout, "rosout"),
+            (self.logging_output.stdout, "stdout"),
+            (self.logging_output.external_lib, "external-lib"),
+        ];
+        for (enabled, output) in flags {
+            if let Some(enabled) = enabled {
+                let action = if enabled { "enable" } else { "disable" };
+                args.push(format!("--{action}-{output}-logs"));
+            }
+        }
+        args
+    }
+}
+
+/// Names which can appear before `:=` in an argument
+fn is_plain_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(':')
+}
+
+fn format_remap_rule(rule: &RemapRule) -> String {
+    match &rule.node_name {
+        Some(node) => format!("{node}:{}:={}", rule.from, rule.to),
+        None => format!("{}:={}", rule.from, rule.to),
+    }
+}
+
+fn format_log_level(log_level: &LogLevelAssignment) -> String {
+    match &log_level.logger_name {
+        Some(logger) => format!("{logger}:={}", log_level.level.as_str()),
+        None => log_level.level.as_str().to_string(),
+    }
+}
+
+/// Write a YAML value on a single line, in flow style
+fn yaml_to_arg(value: &Yaml) -> String {
+    match value {
+        Yaml::Real(v) => v.clone(),
+        Yaml::Integer(v) => v.to_string(),
+        Yaml::Boolean(v) => v.to_string(),
+        Yaml::String(v) => yaml_string(v),
+        Yaml::Array(items) => {
+            let items: Vec<String> = items.iter().map(yaml_to_arg).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Yaml::Hash(hash) => yaml_hash(hash),
+        Yaml::Alias(_) | Yaml::Null | Yaml::BadValue => "~".to_string(),
+    }
+}
+
+fn yaml_hash(hash: &Hash) -> String {
+    let entries: Vec<String> = hash
+        .iter()
+        .map(|(k, v)| format!("{}: {}", yaml_to_arg(k), yaml_to_arg(v)))
+        .collect();
+    format!("{{{}}}", entries.join(", "))
+}
+
+/// Write a string plain when it reads back as the same string, and quoted
+/// otherwise
+fn yaml_string(s: &str) -> String {
+    const FLOW_INDICATORS: &[char] = &[',', '[', ']', '{', '}'];
+
+    let plain = !s.contains(FLOW_INDICATORS)
+        && YamlLoader::load_from_str(s)
+            .is_ok_and(|docs| docs.len() == 1 && docs[0] == Yaml::String(s.to_string()));
+    if plain {
+        s.to_string()
+    } else if s.chars().any(char::is_control) {
+        let mut quoted = String::from("\"");
+        for c in s.chars() {
+            match c {
+                '"' => quoted.push_str("\\\""),
+                '\\' => quoted.push_str("\\\\"),
+                '\n' => quoted.push_str("\\n"),
+                '\t' => quoted.push_str("\\t"),
+                '\r' => quoted.push_str("\\r"),
+                c if c.is_control() => {
+                    let _ = write!(quoted, "\\u{:04x}", u32::from(c));
+                }
+                c => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        quoted
+    } else {
+        format!("'{}'", s.replace('\'', "''"))
+    }
+}
@@ -0,0 +1,183 @@
+//! ROS2 environment variables
+//!
+//! Reads the standard environment variables that affect how a node joins the
+//! ROS graph:
+//!
+//! - `ROS_NAMESPACE`: namespace of nodes created without an explicit namespace
+//! - `ROS_DOMAIN_ID`: domain ID isolating the graph (default: 0)
+//! - `ROS_LOCALHOST_ONLY`: restrict communication to the local host when set to `1`
+//!
+//! `__ns:=` and `__node:=` remapping rules given on the command line take
+//! precedence over these variables.
+
+use crate::errors::{Ros2ArgsError, Ros2ArgsResult};
+use crate::names::validate_namespace;
+
+/// Environment variable for the default node namespace
+pub const ROS_NAMESPACE: &str = "ROS_NAMESPACE";
+
+/// Environment variable for the ROS domain ID
+pub const ROS_DOMAIN_ID: &str = "ROS_DOMAIN_ID";
+
+/// Environment variable restricting communication to the local host
+pub const ROS_LOCALHOST_ONLY: &str = "ROS_LOCALHOST_ONLY";
+
+/// ROS2 settings read from environment variables
+///
+/// Variables that are unset or empty are treated as not specified.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RosEnv {
+    /// Fully qualified namespace from `ROS_NAMESPACE`
+    pub namespace: Option<String>,
+    /// Domain ID from `ROS_DOMAIN_ID`
+    pub domain_id: Option<u32>,
+    /// Whether `ROS_LOCALHOST_ONLY` is set to `1`
+    pub localhost_only: bool,
+}
+
+impl RosEnv {
+    /// Read the ROS2 environment variables of the current process
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a variable holds an invalid value.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ros2args::RosEnv;
+    ///
+    /// let env = RosEnv::from_env()?;
+    /// println!("domain ID: {}", env.domain_id.unwrap_or(0));
+    /// # Ok::<(), ros2args::Ros2ArgsError>(())
+    /// ```
+    pub fn from_env() -> Ros2ArgsResult<Self> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Read the ROS2 environment variables through `lookup`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a variable holds an invalid value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ros2args::RosEnv;
+    ///
+    /// let env = RosEnv::from_lookup(|name| match name {
+    ///     "ROS_NAMESPACE" => Some("robot1".to_string()),
+    ///     "ROS_DOMAIN_ID" => Some("42".to_string()),
+    ///     _ => None,
+    /// })?;
+    /// assert_eq!(env.namespace.as_deref(), Some("/robot1"));
+    /// assert_eq!(env.domain_id, Some(42));
+    /// assert!(!env.localhost_only);
+    /// # Ok::<(), ros2args::Ros2ArgsError>(())
+    /// ```
+    pub fn from_lookup<F>(lookup: F) -> Ros2ArgsResult<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let get = |name: &str| {
+            lookup(name)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        let namespace = get(ROS_NAMESPACE)
+            .map(|namespace| {
+                let namespace = if namespace.starts_with('/') {
+                    namespace
+                } else {
+                    format!("/{namespace}")
+                };
+                validate_namespace(&namespace).map(|()| namespace)
+            })
+            .transpose()?;
+
+        let domain_id = get(ROS_DOMAIN_ID)
+            .map(|value| {
+                value.parse().map_err(|_| Ros2ArgsError::InvalidEnvVar {
+                    name: ROS_DOMAIN_ID.to_string(),
+                    value,
+                    reason: "expected a non-negative integer".to_string(),
+                })
+            })
+            .transpose()?;
+
+        let localhost_only = get(ROS_LOCALHOST_ONLY).is_some_and(|value| value == "1");
+
+        Ok(Self {
+            namespace,
+            domain_id,
+            localhost_only,
+        })
+    }
+
+    /// Namespace of a node: `namespace` if given and non-empty, otherwise
+    /// `ROS_NAMESPACE`, otherwise the root namespace (empty string)
+    #[must_use]
+    pub fn resolve_namespace<'a>(&'a self, namespace: Option<&'a str>) -> &'a str {
+        match namespace {
+            Some(namespace) if !namespace.is_empty() => namespace,
+            _ => self.namespace.as_deref().unwrap_or(""),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (*value).to_string())
+        }
+    }
+
+    #[test]
+    fn test_unset() {
+        let env = RosEnv::from_lookup(lookup(&[])).unwrap();
+        assert_eq!(env, RosEnv::default());
+        assert_eq!(env.resolve_namespace(None), "");
+    }
+
+    #[test]
+    fn test_values() {
+        let env = RosEnv::from_lookup(lookup(&[
+            (ROS_NAMESPACE, "/robot1/arm"),
+            (ROS_DOMAIN_ID, " 7 "),
+            (ROS_LOCALHOST_ONLY, "1"),
+        ]))
+        .unwrap();
+        assert_eq!(env.namespace.as_deref(), Some("/robot1/arm"));
+        assert_eq!(env.domain_id, Some(7));
+        assert!(env.localhost_only);
+
+        assert_eq!(env.resolve_namespace(None), "/robot1/arm");
+        assert_eq!(env.resolve_namespace(Some("")), "/robot1/arm");
+        assert_eq!(env.resolve_namespace(Some("/explicit")), "/explicit");
+    }
+
+    #[test]
+    fn test_empty_and_disabled() {
+        let env = RosEnv::from_lookup(lookup(&[
+            (ROS_NAMESPACE, ""),
+            (ROS_DOMAIN_ID, ""),
+            (ROS_LOCALHOST_ONLY, "0"),
+        ]))
+        .unwrap();
+        assert_eq!(env, RosEnv::default());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(RosEnv::from_lookup(lookup(&[(ROS_DOMAIN_ID, "abc")])).is_err());
+        assert!(RosEnv::from_lookup(lookup(&[(ROS_DOMAIN_ID, "-1")])).is_err());
+        assert!(RosEnv::from_lookup(lookup(&[(ROS_NAMESPACE, "/bad name")])).is_err());
+    }
+}
@@ -19,6 +19,8 @@ pub enum Ros2ArgsError {
         kind: NameKind,
         /// The invalid name
         name: String,
+        /// Character offset in `name` where the problem was found, if known
+        position: Option<usize>,
         /// The reason the name is invalid
         reason: String,
     },
@@ -65,6 +67,17 @@ pub enum Ros2ArgsError {
     #[error("Missing value for argument '{0}'")]
     MissingArgumentValue(String),
 
+    /// Invalid value of a ROS environment variable
+    #[error("Invalid value '{value}' for environment variable {name}: {reason}")]
+    InvalidEnvVar {
+        /// The environment variable
+        name: String,
+        /// The invalid value
+        value: String,
+        /// The reason the value is invalid
+        reason: String,
+    },
+
     /// Unexpected argument
     #[error("Unexpected argument '{0}' in ROS args section")]
     UnexpectedArgument(String),
@@ -21,6 +21,8 @@
 //! - **Enclave assignment**: Parse `--enclave` / `-e` arguments for security enclaves
 //! - **Wildcard support**: Support wildcard patterns in parameter files (`*`, `**`)
 //! - **Multiple ROS args sections**: Handle multiple `--ros-args` sections in the same command line
+//! - **Environment variables**: Read `ROS_NAMESPACE`, `ROS_DOMAIN_ID` and `ROS_LOCALHOST_ONLY`
+//! - **Argument generation**: Build a `--ros-args` argv from typed values, e.g. to spawn a child process
 //!
 //! # Examples
 //!
@@ -119,19 +121,39 @@
 //! assert_eq!(user_args, vec!["program", "--user-arg"]);
 //! # Ok::<(), ros2args::Ros2ArgsError>(())
 //! ```
+//!
+//! ## Generating Arguments
+//!
+//! ```
+//! use ros2args::{Ros2ArgsBuilder, parse_ros2_args};
+//!
+//! let args = Ros2ArgsBuilder::new()
+//!     .node_remap("talker", "chatter", "/robot/chatter")
+//!     .param("rate", 10.0)
+//!     .build_args()?;
+//! assert_eq!(args, ["--ros-args", "-r", "talker:chatter:=/robot/chatter", "-p", "rate:=10.0"]);
+//!
+//! let (ros_args, _) = parse_ros2_args(&args)?;
+//! assert_eq!(ros_args.param_assignments[0].as_f64(), Some(10.0));
+//! # Ok::<(), ros2args::Ros2ArgsError>(())
+//! ```
 
+mod builder;
+pub mod env;
 mod errors;
 pub mod names;
 mod param_file;
 mod parser;
 mod types;
 
+pub use builder::{IntoYaml, Ros2ArgsBuilder};
+pub use env::RosEnv;
 pub use errors::{Ros2ArgsError, Ros2ArgsResult};
 pub use names::{
-    NameKind, build_node_fqn, expand_topic_name, expand_topic_name_with_fqn, extract_base_name,
-    extract_namespace, is_absolute_name, is_hidden_name, is_private_name, is_relative_name,
-    is_valid_name_char, is_valid_topic_char, validate_fully_qualified_name, validate_namespace,
-    validate_node_name, validate_substitution, validate_topic_name,
+    NameKind, build_node_fqn, expand_substitutions, expand_topic_name, expand_topic_name_with_fqn,
+    extract_base_name, extract_namespace, is_absolute_name, is_hidden_name, is_private_name,
+    is_relative_name, is_valid_name_char, is_valid_topic_char, validate_fully_qualified_name,
+    validate_namespace, validate_node_name, validate_substitution, validate_topic_name,
 };
 pub use param_file::{match_wildcard_pattern, parse_param_file};
 pub use parser::parse_ros2_args;
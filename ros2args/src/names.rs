@@ -231,6 +231,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
         return Err(Ros2ArgsError::InvalidName {
             kind,
             name: name.to_string(),
+            position: None,
             reason: "name must not be empty".to_string(),
         });
     }
@@ -248,6 +249,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(1),
                         reason: "tilde (~) must be followed by a forward slash (/)".to_string(),
                     });
                 }
@@ -260,12 +262,14 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                 return Err(Ros2ArgsError::InvalidName {
                     kind,
                     name: name.to_string(),
+                    position: Some(0),
                     reason: "name must not start with a numeric character".to_string(),
                 });
             } else if !chars[0].is_ascii_alphabetic() && chars[0] != '_' {
                 return Err(Ros2ArgsError::InvalidName {
                     kind,
                     name: name.to_string(),
+                    position: Some(0),
                     reason: format!("invalid character '{}' at position 0", chars[0]),
                 });
             }
@@ -276,6 +280,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                 return Err(Ros2ArgsError::InvalidName {
                     kind,
                     name: name.to_string(),
+                    position: Some(0),
                     reason: "namespace must start with a forward slash (/)".to_string(),
                 });
             }
@@ -289,6 +294,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                 return Err(Ros2ArgsError::InvalidName {
                     kind,
                     name: name.to_string(),
+                    position: Some(1),
                     reason: "namespace token must not start with a numeric character".to_string(),
                 });
             }
@@ -299,6 +305,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                 return Err(Ros2ArgsError::InvalidName {
                     kind,
                     name: name.to_string(),
+                    position: Some(0),
                     reason: "name must not start with a numeric character".to_string(),
                 });
             }
@@ -306,6 +313,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                 return Err(Ros2ArgsError::InvalidName {
                     kind,
                     name: name.to_string(),
+                    position: Some(0),
                     reason: format!("invalid character '{}' at position 0", chars[0]),
                 });
             }
@@ -328,6 +336,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                         return Err(Ros2ArgsError::InvalidName {
                             kind,
                             name: name.to_string(),
+                            position: Some(i),
                             reason: "unbalanced curly braces: unexpected '}'".to_string(),
                         });
                     }
@@ -338,6 +347,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                         return Err(Ros2ArgsError::InvalidName {
                             kind,
                             name: name.to_string(),
+                            position: Some(i),
                             reason: format!(
                                 "invalid character '{c}' inside substitution at position {i}"
                             ),
@@ -347,6 +357,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: format!("invalid character '{c}' at position {i}"),
                     });
                 }
@@ -356,6 +367,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: "name must not contain repeated forward slashes (//)".to_string(),
                     });
                 }
@@ -365,6 +377,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: "name must not contain repeated underscores (__)".to_string(),
                     });
                 }
@@ -374,6 +387,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: "tilde (~) may only appear at the beginning of a name".to_string(),
                     });
                 }
@@ -383,6 +397,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: format!(
                             "token after '/' must not start with a numeric character at position {i}"
                         ),
@@ -394,6 +409,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: format!("invalid character '{c}' at position {i}"),
                     });
                 }
@@ -403,6 +419,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: "namespace must not contain repeated forward slashes (//)"
                             .to_string(),
                     });
@@ -413,6 +430,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: "namespace must not contain repeated underscores (__)".to_string(),
                     });
                 }
@@ -422,6 +440,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: format!(
                             "namespace token after '/' must not start with a numeric character at position {i}"
                         ),
@@ -434,6 +453,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: "node name must not contain forward slash (/)".to_string(),
                     });
                 }
@@ -441,6 +461,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: "node name must not contain tilde (~)".to_string(),
                     });
                 }
@@ -448,6 +469,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: "node name must not contain curly braces".to_string(),
                     });
                 }
@@ -455,6 +477,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: format!("invalid character '{c}' at position {i}"),
                     });
                 }
@@ -464,6 +487,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: "node name must not contain repeated underscores (__)".to_string(),
                     });
                 }
@@ -473,6 +497,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                     return Err(Ros2ArgsError::InvalidName {
                         kind,
                         name: name.to_string(),
+                        position: Some(i),
                         reason: format!("invalid character '{c}' at position {i}"),
                     });
                 }
@@ -491,6 +516,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                 return Err(Ros2ArgsError::InvalidName {
                     kind,
                     name: name.to_string(),
+                    position: Some(chars.len()),
                     reason: "unbalanced curly braces: missing '}'".to_string(),
                 });
             }
@@ -500,6 +526,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                 return Err(Ros2ArgsError::InvalidName {
                     kind,
                     name: name.to_string(),
+                    position: Some(chars.len() - 1),
                     reason: "name must not end with a forward slash (/)".to_string(),
                 });
             }
@@ -510,6 +537,7 @@ fn validate_name_impl(name: &str, kind: NameKind) -> Ros2ArgsResult<()> {
                 return Err(Ros2ArgsError::InvalidName {
                     kind,
                     name: name.to_string(),
+                    position: Some(chars.len() - 1),
                     reason: "namespace must not end with a forward slash (/)".to_string(),
                 });
             }
@@ -547,6 +575,7 @@ pub fn validate_fully_qualified_name(name: &str) -> Ros2ArgsResult<()> {
         return Err(Ros2ArgsError::InvalidName {
             kind: NameKind::Topic,
             name: name.to_string(),
+            position: None,
             reason: "fully qualified name must not be empty".to_string(),
         });
     }
@@ -555,6 +584,7 @@ pub fn validate_fully_qualified_name(name: &str) -> Ros2ArgsResult<()> {
         return Err(Ros2ArgsError::InvalidName {
             kind: NameKind::Topic,
             name: name.to_string(),
+            position: Some(0),
             reason: "fully qualified name must start with a forward slash (/)".to_string(),
         });
     }
@@ -563,6 +593,7 @@ pub fn validate_fully_qualified_name(name: &str) -> Ros2ArgsResult<()> {
         return Err(Ros2ArgsError::InvalidName {
             kind: NameKind::Topic,
             name: name.to_string(),
+            position: name.chars().position(|c| c == '~'),
             reason: "fully qualified name must not contain tilde (~)".to_string(),
         });
     }
@@ -571,6 +602,7 @@ pub fn validate_fully_qualified_name(name: &str) -> Ros2ArgsResult<()> {
         return Err(Ros2ArgsError::InvalidName {
             kind: NameKind::Topic,
             name: name.to_string(),
+            position: name.chars().position(|c| c == '{' || c == '}'),
             reason: "fully qualified name must not contain curly braces ({})".to_string(),
         });
     }
@@ -614,6 +646,78 @@ pub fn is_hidden_name(name: &str) -> bool {
             .any(|token| !token.is_empty() && token.starts_with('_'))
 }
 
+/// Expand the substitutions of a topic or service name
+///
+/// Replaces every `{node}` token with the node's base name and every `{ns}`
+/// or `{namespace}` token with the node's namespace. Other characters,
+/// including a leading `~`, are left untouched.
+///
+/// # Errors
+///
+/// Returns `Ros2ArgsError::InvalidName` if the name contains an unknown or
+/// unterminated substitution.
+///
+/// # Examples
+///
+/// ```
+/// use ros2args::names::expand_substitutions;
+///
+/// let name = expand_substitutions("/my_ns", "my_node", "{node}/status").unwrap();
+/// assert_eq!(name, "my_node/status");
+///
+/// let name = expand_substitutions("/my_ns", "my_node", "{ns}/status").unwrap();
+/// assert_eq!(name, "/my_ns/status");
+///
+/// let name = expand_substitutions("/", "my_node", "{namespace}/status").unwrap();
+/// assert_eq!(name, "/status");
+///
+/// assert!(expand_substitutions("/my_ns", "my_node", "{unknown}/status").is_err());
+/// ```
+pub fn expand_substitutions(
+    node_namespace: &str,
+    node_name: &str,
+    topic_name: &str,
+) -> Ros2ArgsResult<String> {
+    let mut expanded = String::with_capacity(topic_name.len());
+    let mut rest = topic_name;
+    let mut offset = 0;
+
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            return Err(Ros2ArgsError::InvalidName {
+                kind: NameKind::Topic,
+                name: topic_name.to_string(),
+                position: Some(offset + start),
+                reason: "unbalanced curly braces: missing '}'".to_string(),
+            });
+        };
+
+        match &rest[start + 1..start + len] {
+            "node" => expanded.push_str(node_name),
+            // The root namespace is dropped so that `{ns}/foo` becomes `/foo`
+            "ns" | "namespace" if node_namespace == "/" => {}
+            "ns" | "namespace" => expanded.push_str(node_namespace),
+            token => {
+                return Err(Ros2ArgsError::InvalidName {
+                    kind: NameKind::Topic,
+                    name: topic_name.to_string(),
+                    position: Some(offset + start),
+                    reason: format!(
+                        "unknown substitution '{{{token}}}': expected {{node}}, {{ns}} or {{namespace}}"
+                    ),
+                });
+            }
+        }
+
+        offset += start + len + 1;
+        rest = &rest[start + len + 1..];
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
 /// Expand a topic name to its fully qualified form
 ///
 /// This function takes a node's namespace, node name, and a topic name,
@@ -622,6 +726,8 @@ pub fn is_hidden_name(name: &str) -> bool {
 ///
 /// # Expansion Rules
 ///
+/// - **Substitutions** (`{node}`, `{ns}`, `{namespace}`): Expanded first, see
+///   [`expand_substitutions`]
 /// - **Absolute names** (starting with `/`): Returned as-is, ignoring the node's namespace
 /// - **Private names** (starting with `~`): The `~` is replaced with the node's FQN
 ///   (namespace + node name), e.g., `~/foo` becomes `/my_ns/my_node/foo`
@@ -669,6 +775,10 @@ pub fn is_hidden_name(name: &str) -> bool {
 /// // Root namespace with relative topic
 /// let fqn = expand_topic_name("/", "my_node", "relative").unwrap();
 /// assert_eq!(fqn, "/relative");
+///
+/// // Substitutions
+/// let fqn = expand_topic_name("/my_ns", "my_node", "{node}/status").unwrap();
+/// assert_eq!(fqn, "/my_ns/my_node/status");
 /// ```
 pub fn expand_topic_name(
     node_namespace: &str,
@@ -680,9 +790,10 @@ pub fn expand_topic_name(
     validate_node_name(node_name)?;
     validate_topic_name(topic_name)?;
 
+    let topic_name = &expand_substitutions(node_namespace, node_name, topic_name)?;
     let expanded = if is_absolute_name(topic_name) {
         // Absolute names are returned as-is
-        topic_name.to_string()
+        topic_name.clone()
     } else if is_private_name(topic_name) {
         // Private names: replace ~ with node's FQN
         let node_fqn = build_node_fqn(node_namespace, node_name);
@@ -767,9 +878,11 @@ pub fn expand_topic_name_with_fqn(node_fqn: &str, topic_name: &str) -> Ros2ArgsR
     // Extract namespace from node FQN (everything except the last token)
     let node_namespace = extract_namespace(node_fqn);
 
+    let topic_name =
+        &expand_substitutions(node_namespace, extract_base_name(node_fqn), topic_name)?;
     let expanded = if is_absolute_name(topic_name) {
         // Absolute names are returned as-is
-        topic_name.to_string()
+        topic_name.clone()
     } else if is_private_name(topic_name) {
         // Private names: replace ~ with node's FQN
         if topic_name == "~" {
@@ -1247,6 +1360,46 @@ mod tests {
         assert_eq!(fqn, "/relative");
     }
 
+    #[test]
+    fn test_expand_substitutions() {
+        let fqn = expand_topic_name("/my_ns", "my_node", "{node}/status").unwrap();
+        assert_eq!(fqn, "/my_ns/my_node/status");
+
+        let fqn = expand_topic_name("/my_ns", "my_node", "{ns}/status").unwrap();
+        assert_eq!(fqn, "/my_ns/status");
+
+        let fqn = expand_topic_name("/", "my_node", "{namespace}/{node}").unwrap();
+        assert_eq!(fqn, "/my_node");
+
+        let fqn = expand_topic_name("/my_ns", "my_node", "~/{node}_state").unwrap();
+        assert_eq!(fqn, "/my_ns/my_node/my_node_state");
+
+        let fqn = expand_topic_name_with_fqn("/my_ns/my_node", "{node}/status").unwrap();
+        assert_eq!(fqn, "/my_ns/my_node/status");
+    }
+
+    #[test]
+    fn test_expand_unknown_substitution() {
+        match expand_topic_name("/my_ns", "my_node", "foo/{robot}") {
+            Err(Ros2ArgsError::InvalidName { position, .. }) => assert_eq!(position, Some(4)),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_name_position() {
+        let position = |name: &str| match validate_topic_name(name) {
+            Err(Ros2ArgsError::InvalidName { position, .. }) => position,
+            other => panic!("unexpected result for '{name}': {other:?}"),
+        };
+        assert_eq!(position(""), None);
+        assert_eq!(position("1foo"), Some(0));
+        assert_eq!(position("~foo"), Some(1));
+        assert_eq!(position("foo bar"), Some(3));
+        assert_eq!(position("foo//bar"), Some(4));
+        assert_eq!(position("foo/"), Some(3));
+    }
+
     #[test]
     fn test_build_node_fqn() {
         assert_eq!(build_node_fqn("/my_ns", "my_node"), "/my_ns/my_node");
@@ -217,25 +217,25 @@ fn parse_remap_rule(s: &str) -> Ros2ArgsResult<RemapRule> {
 /// - `name:=value` (global)
 /// - `node:name:=value` (node-specific)
 ///
-/// The value is parsed as YAML to preserve type information.
+/// The value is parsed as YAML to preserve type information, and may itself
+/// contain `:=`.
 fn parse_param_assignment(s: &str) -> Ros2ArgsResult<ParamAssignment> {
-    let parts: Vec<&str> = s.split(":=").collect();
-    if parts.len() != 2 {
+    let Some((name, value)) = s.split_once(":=") else {
         return Err(Ros2ArgsError::InvalidParamAssignment(s.to_string()));
-    }
+    };
 
     // Parse the value as YAML to preserve type information
-    let yaml_value = YamlLoader::load_from_str(parts[1])
+    let yaml_value = YamlLoader::load_from_str(value)
         .map_err(|e| {
-            Ros2ArgsError::InvalidYamlValue(parts[1].to_string(), format!("YAML parse error: {e}"))
+            Ros2ArgsError::InvalidYamlValue(value.to_string(), format!("YAML parse error: {e}"))
         })?
         .into_iter()
         .next()
         .ok_or_else(|| {
-            Ros2ArgsError::InvalidYamlValue(parts[1].to_string(), "Empty YAML value".to_string())
+            Ros2ArgsError::InvalidYamlValue(value.to_string(), "Empty YAML value".to_string())
         })?;
 
-    let name_parts: Vec<&str> = parts[0].split(':').collect();
+    let name_parts: Vec<&str> = name.split(':').collect();
 
     if name_parts.len() == 2 {
         // Node-specific: node:name:=value
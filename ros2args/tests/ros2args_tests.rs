@@ -1,7 +1,8 @@
 //! Integration tests for ROS2 command-line arguments parser
 
 use ros2args::{
-    LogLevel, Ros2ArgsError, match_wildcard_pattern, parse_param_file, parse_ros2_args,
+    LogLevel, Ros2ArgsBuilder, Ros2ArgsError, match_wildcard_pattern, parse_param_file,
+    parse_ros2_args,
 };
 use std::io::Write;
 use tempfile::NamedTempFile;
@@ -932,3 +933,103 @@ fn test_error_messages_contain_name() {
     assert!(msg.contains("topic"));
     assert!(msg.contains("repeated forward slashes"));
 }
+
+#[test]
+fn test_builder_round_trip() {
+    let tricky = [
+        "plain",
+        "42",
+        "true",
+        "",
+        " padded ",
+        "camera: left",
+        "it's",
+        "a, b",
+        "# not a comment",
+        "key:=value",
+        "line\nbreak",
+        "tab\t\"quoted\"",
+    ];
+
+    let mut builder = Ros2ArgsBuilder::new()
+        .remap("image", "/camera/image_raw")
+        .node_remap("detector", "__ns", "/robot")
+        .param("use_sim_time", true)
+        .param("rate", 10.0)
+        .param("count", 3)
+        .param("gains", vec![1.5, -0.25])
+        .node_param("detector", "labels", vec!["person", "a, b"])
+        .params_file("/tmp/params.yaml")
+        .log_level(LogLevel::Warn)
+        .logger_level("rclcpp", LogLevel::Debug)
+        .log_config_file("/tmp/log.config")
+        .rosout_logs(false)
+        .stdout_logs(true)
+        .enclave("/robot/detector");
+    for (i, value) in tricky.iter().enumerate() {
+        builder = builder.param(&format!("string_{i}"), *value);
+    }
+
+    let expected = builder.clone().build().unwrap();
+    let args = builder.build_args().unwrap();
+    assert_eq!(args[0], "--ros-args");
+
+    let mut argv = vec!["program".to_string()];
+    argv.extend(args.iter().cloned());
+    let (ros_args, user_args) = parse_ros2_args(&argv).unwrap();
+    assert_eq!(user_args, vec!["program"]);
+    assert_eq!(ros_args, expected);
+
+    for (i, value) in tricky.iter().enumerate() {
+        let param = ros_args
+            .param_assignments
+            .iter()
+            .find(|p| p.name == format!("string_{i}"))
+            .unwrap();
+        assert_eq!(param.as_str(), Some(*value));
+    }
+
+    // Generating the parsed arguments again gives the same argv
+    assert_eq!(ros_args.to_args(), args);
+}
+
+#[test]
+fn test_builder_plain_values() {
+    let args = Ros2ArgsBuilder::new()
+        .param("name", "camera")
+        .param("number_as_string", "42")
+        .param("ratio", 1.0)
+        .param("limit", f64::INFINITY)
+        .build_args()
+        .unwrap();
+    assert_eq!(
+        args,
+        [
+            "--ros-args",
+            "-p",
+            "name:=camera",
+            "-p",
+            "number_as_string:='42'",
+            "-p",
+            "ratio:=1.0",
+            "-p",
+            "limit:=.inf",
+        ]
+    );
+}
+
+#[test]
+fn test_builder_invalid() {
+    assert!(matches!(
+        Ros2ArgsBuilder::new().remap("a:=b", "c").build(),
+        Err(Ros2ArgsError::InvalidRemapRule(_))
+    ));
+    assert!(matches!(
+        Ros2ArgsBuilder::new().node_param("ns:node", "p", 1).build(),
+        Err(Ros2ArgsError::InvalidParamAssignment(_))
+    ));
+    assert!(matches!(
+        Ros2ArgsBuilder::new().enclave("relative").build(),
+        Err(Ros2ArgsError::InvalidEnclavePath(_))
+    ));
+}
@@ -2,8 +2,11 @@
 //!
 //! This example demonstrates the bindgen-style API for generating Rust code
 //! from ROS2 interface files (.msg, .srv, .action, .idl).
+//!
+//! Run with `--check` to verify that `target/generated/` is up to date
+//! instead of writing to it.
 
-use ros2msg::generator::{Generator, ParseCallbacks};
+use ros2msg::generator::{Generator, OutputMode, ParseCallbacks};
 use std::env;
 
 struct MyCallbacks;
@@ -17,6 +20,11 @@ impl ParseCallbacks for MyCallbacks {
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Get ROS2 path from environment or use default
     let ros_path = env::var("ROS_PATH").unwrap_or_else(|_| "/opt/ros/jazzy".to_string());
+    let mode = if env::args().any(|arg| arg == "--check") {
+        OutputMode::Check
+    } else {
+        OutputMode::Write
+    };
 
     println!("=== ROS2 Message to Rust Code Generator ===\n");
 
@@ -33,6 +41,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .include(&header_msg)
         .allowlist_recursively(true)
         .output_dir("target/generated/example1")
+        .output_mode(mode)
         .generate()?;
 
     println!("   ✓ Generated to target/generated/example1/\n");
@@ -54,6 +63,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .includes(messages)
         .output_dir("target/generated/example2")
         .emit_rerun_if_changed(true)
+        .output_mode(mode)
         .generate()?;
 
     println!("   ✓ Generated 4 files to target/generated/example2/\n");
@@ -72,6 +82,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             ros_path
         ))
         .output_dir("target/generated/example3")
+        .output_mode(mode)
         .generate()?;
 
     println!("   ✓ Generated msg, srv, and action files\n");
@@ -85,6 +96,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .parse_callbacks(Box::new(MyCallbacks))
         .include(format!("{}/share/std_msgs/msg/String.msg", ros_path))
         .output_dir("target/generated/example4")
+        .output_mode(mode)
         .generate()?;
 
     println!("   ✓ Generated with 'Ros' prefix\n");
@@ -101,10 +113,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             ros_path
         ))
         .output_dir("target/generated/example5")
+        .output_mode(mode)
         .generate()?;
 
     println!("   ✓ Generated from .idl file\n");
 
+    if mode == OutputMode::Check {
+        println!("✓ Generated files are up to date");
+        return Ok(());
+    }
+
     println!("✓ All examples completed successfully!");
     println!("  Check target/generated/ for output files");
 
@@ -5,7 +5,12 @@
 //! improve performance, it introduces complexity around deduplication and could
 //! cause the same dependency files to be generated multiple times concurrently.
 //! The sequential approach is simpler, safer, and fast enough for most use cases.
-use super::{GeneratedCode, GeneratorResult, codegen::CodeGenerator, config::GeneratorConfig};
+use super::{
+    GeneratedCode, GeneratorResult,
+    codegen::CodeGenerator,
+    config::{CodeTarget, GeneratorConfig, OutputMode},
+};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -491,15 +496,146 @@ impl Generator {
         self
     }
 
+    /// Emit enums for groups of integer constants sharing a prefix
+    ///
+    /// For `sensor_msgs/BatteryState`, the `POWER_SUPPLY_STATUS_*` constants
+    /// produce a `BatteryStatePowerSupplyStatus` enum with `as_u8()` and
+    /// `TryFrom<u8>`, next to the raw constants, which are kept.
+    ///
+    /// Default is `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ros2msg::generator::Generator;
+    ///
+    /// let generator = Generator::new()
+    ///     .constant_enums(true);
+    /// ```
+    #[must_use]
+    pub fn constant_enums(mut self, enable: bool) -> Self {
+        self.config.constant_enums = enable;
+        self
+    }
+
+    /// Emit the `<Service>_Event` structure when converting .srv files to IDL
+    ///
+    /// The event structure is then parsed from the IDL instead of being
+    /// derived from the request and response.
+    ///
+    /// Default is `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ros2msg::generator::Generator;
+    ///
+    /// let generator = Generator::new()
+    ///     .service_events(true);
+    /// ```
+    #[must_use]
+    pub fn service_events(mut self, enable: bool) -> Self {
+        self.config.idl_options.service_events = enable;
+        self
+    }
+
+    /// Post-process the IDL converted from .msg/.srv/.action files
+    ///
+    /// The processor receives the package name, the input file (e.g.
+    /// `msg/Point.msg`) and the IDL, and returns the IDL fed to code
+    /// generation. It is not applied to .idl files.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ros2msg::generator::Generator;
+    ///
+    /// let generator = Generator::new()
+    ///     .idl_post_processor(|_package: &str, _input_file: &str, idl: String| {
+    ///         idl.replace("struct ", "@final\n    struct ")
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn idl_post_processor<P: crate::idl_adapter::IdlPostProcessor + 'static>(
+        mut self,
+        processor: P,
+    ) -> Self {
+        self.config.idl_options.post_processor = Some(Arc::new(processor));
+        self
+    }
+
+    /// Set whether to write the output directory or verify it
+    ///
+    /// In [`OutputMode::Check`], [`generate`](Self::generate) compares the
+    /// generated code with the files already in the output directory and fails
+    /// with [`GenerationError::OutOfDate`](super::GenerationError::OutOfDate)
+    /// if a file differs, is missing, or is a stale `.rs` file no longer generated.
+    /// Nothing is written. This is meant for CI jobs checking that committed
+    /// bindings are up to date.
+    ///
+    /// Default is [`OutputMode::Write`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ros2msg::generator::{Generator, OutputMode};
+    ///
+    /// let generator = Generator::new()
+    ///     .output_dir("src/generated")
+    ///     .output_mode(OutputMode::Check);
+    /// ```
+    #[must_use]
+    pub fn output_mode(mut self, mode: OutputMode) -> Self {
+        self.config.output_mode = mode;
+        self
+    }
+
+    /// Emit code compiling under `no_std + alloc`, with CDR serialization
+    ///
+    /// Strings and sequences use the `alloc` types, C types default to
+    /// `core::ffi`, and every struct implements the `CdrEncode` and
+    /// `CdrDecode` traits of a `cdr` module written next to the packages.
+    /// Its `to_bytes` and `from_bytes` functions use the plain CDR encoding
+    /// of the ROS2 middlewares, so messages can be exchanged with a Zenoh
+    /// router from targets without `std`. The crate including the generated
+    /// code must declare `extern crate alloc;`.
+    ///
+    /// `wstring` fields are encoded as UTF-8 strings.
+    ///
+    /// Default is `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ros2msg::generator::Generator;
+    ///
+    /// let generator = Generator::new()
+    ///     .output_dir("src/generated")
+    ///     .no_std(true);
+    /// ```
+    #[must_use]
+    pub fn no_std(mut self, enable: bool) -> Self {
+        self.config.target = if enable {
+            CodeTarget::NoStd
+        } else {
+            CodeTarget::Std
+        };
+        self
+    }
+
     /// Generate bindings and write to output directory
     ///
     /// This is the main entry point. It generates Rust code from all included
     /// ROS2 interface files and writes them to the output directory.
     /// File types are automatically detected from extensions.
     ///
+    /// The output only depends on the inputs and the configuration: running
+    /// the generator twice produces identical files.
+    ///
     /// # Errors
     ///
-    /// Returns an error if generation fails or output directory is not set
+    /// Returns an error if generation fails or output directory is not set,
+    /// or in [`check`](Self::check) mode if the output directory is out of date
     ///
     /// # Example
     ///
@@ -527,14 +663,24 @@ impl Generator {
             return Err(super::ConfigError::NoInputFiles.into());
         }
 
-        // Create output directory if it doesn't exist
-        std::fs::create_dir_all(output_dir)?;
-
         // Generate all code
         let all_generated = self.generate_all_files()?;
+        let files = self.render_generated_files(output_dir, &all_generated);
+
+        if self.config.output_mode == OutputMode::Check {
+            return Self::check_generated_files(output_dir, &files);
+        }
+
+        // Create output directory if it doesn't exist
+        std::fs::create_dir_all(output_dir)?;
 
         // Write generated files to disk
-        self.write_generated_files(output_dir, &all_generated)?;
+        for (path, content) in &files {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, content)?;
+        }
 
         Ok(())
     }
@@ -601,19 +747,22 @@ impl Generator {
         Ok(all_generated)
     }
 
-    /// Write generated files to disk with proper package structure
-    fn write_generated_files(
+    /// Lay out the generated files with proper package structure
+    ///
+    /// Returns the content of each file keyed by its path in `output_dir`.
+    fn render_generated_files(
         &self,
         output_dir: &Path,
         all_generated: &[GeneratedCode],
-    ) -> GeneratorResult<()> {
-        use std::collections::{HashMap, HashSet};
+    ) -> BTreeMap<PathBuf, String> {
+        use std::collections::HashSet;
+
+        let mut files = BTreeMap::new();
 
         // Group generated files by package and deduplicate by file path
         // This prevents the same file from being written multiple times if:
         // 1. A file appears in both input_files and as a dependency
-        let mut packages: HashMap<String, Vec<&GeneratedCode>> = HashMap::new();
-        let mut all_packages: HashSet<String> = HashSet::new();
+        let mut packages: BTreeMap<String, Vec<&GeneratedCode>> = BTreeMap::new();
         let mut seen_paths: HashSet<PathBuf> = HashSet::new();
 
         for code in all_generated {
@@ -623,7 +772,6 @@ impl Generator {
             }
 
             if self.config.should_include_item(&code.module_name) {
-                all_packages.insert(code.package_name.clone());
                 packages
                     .entry(code.package_name.clone())
                     .or_default()
@@ -634,12 +782,11 @@ impl Generator {
         // Write files in package subdirectories with msg/srv/action organization
         for (package_name, codes) in &packages {
             let package_dir = output_dir.join(package_name);
-            std::fs::create_dir_all(&package_dir)?;
 
             // Group codes by interface kind (msg, srv, action)
             // This uses the semantic type from the content, not the file extension.
             // IDL files are placed in msg/srv/action based on what they contain.
-            let mut type_groups: HashMap<String, Vec<&GeneratedCode>> = HashMap::new();
+            let mut type_groups: BTreeMap<String, Vec<&GeneratedCode>> = BTreeMap::new();
             for code in codes {
                 type_groups
                     .entry(code.interface_kind.dir_name().to_string())
@@ -652,7 +799,6 @@ impl Generator {
             // Write files organized by interface kind
             for (interface_dir, type_codes) in type_groups {
                 let type_dir = package_dir.join(&interface_dir);
-                std::fs::create_dir_all(&type_dir)?;
 
                 // Get interface kind from the first code in the group
                 // (all codes in a group have the same interface_kind)
@@ -661,16 +807,15 @@ impl Generator {
                     .map_or(super::InterfaceKind::Message, |c| c.interface_kind);
 
                 let mut module_names = Vec::new();
-                let mut seen_modules: std::collections::HashSet<String> =
-                    std::collections::HashSet::new();
+                let mut seen_modules: HashSet<String> = HashSet::new();
                 for code in type_codes {
                     // Deduplicate: skip if we've already processed a module with this name
                     // This can happen when the same package exists in multiple search paths
                     if !seen_modules.insert(code.module_name.clone()) {
                         continue;
                     }
-                    let output_path = type_dir.join(format!("{}.rs", code.module_name));
-                    code.write_to_file(output_path)?;
+                    let output_path = type_dir.join(code.suggested_filename());
+                    files.insert(output_path, code.code.clone());
                     module_names.push(code.module_name.clone());
                 }
 
@@ -679,7 +824,7 @@ impl Generator {
                     module_names.sort();
                     let type_mod_rs =
                         self.generate_type_mod_rs(package_name, interface_kind, &module_names);
-                    std::fs::write(type_dir.join("mod.rs"), type_mod_rs)?;
+                    files.insert(type_dir.join("mod.rs"), type_mod_rs);
                     submodule_names.push(interface_dir);
                 }
             }
@@ -688,19 +833,55 @@ impl Generator {
             if !submodule_names.is_empty() {
                 submodule_names.sort();
                 let package_mod_rs = self.generate_interface_mod_rs(package_name, &submodule_names);
-                std::fs::write(package_dir.join("mod.rs"), package_mod_rs)?;
+                files.insert(package_dir.join("mod.rs"), package_mod_rs);
             }
         }
 
         // Generate root mod.rs
-        if !all_packages.is_empty() {
-            let mut package_list: Vec<_> = all_packages.into_iter().collect();
-            package_list.sort();
+        if !packages.is_empty() {
+            let package_list: Vec<_> = packages.into_keys().collect();
             let root_mod_rs = self.generate_root_mod_rs(&package_list);
-            std::fs::write(output_dir.join("mod.rs"), root_mod_rs)?;
+            files.insert(output_dir.join("mod.rs"), root_mod_rs);
+
+            // CDR runtime used by the generated structs
+            if self.config.target == CodeTarget::NoStd {
+                let mut cdr_rs = String::new();
+                if let Some(header) = &self.config.header {
+                    cdr_rs.push_str(header);
+                    cdr_rs.push_str("\n\n");
+                }
+                cdr_rs.push_str(super::CDR_RUNTIME);
+                files.insert(output_dir.join("cdr.rs"), cdr_rs);
+            }
         }
 
-        Ok(())
+        files
+    }
+
+    /// Compare the generated files with the content of `output_dir`
+    fn check_generated_files(
+        output_dir: &Path,
+        files: &BTreeMap<PathBuf, String>,
+    ) -> GeneratorResult<()> {
+        let mut out_of_date: Vec<PathBuf> = files
+            .iter()
+            .filter(|(path, content)| {
+                !std::fs::read_to_string(path).is_ok_and(|existing| existing == **content)
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        // Files left over from a previous generation, e.g. a removed message
+        let mut existing = Vec::new();
+        collect_rs_files(output_dir, &mut existing);
+        out_of_date.extend(existing.into_iter().filter(|p| !files.contains_key(p)));
+        out_of_date.sort();
+
+        if out_of_date.is_empty() {
+            Ok(())
+        } else {
+            Err(super::GenerationError::OutOfDate { files: out_of_date }.into())
+        }
     }
 
     /// Find all message/service/action/idl files for a given package
@@ -745,6 +926,8 @@ impl Generator {
             }
         }
 
+        // `read_dir` order is platform dependent
+        files.sort();
         files
     }
 
@@ -778,6 +961,10 @@ impl Generator {
             content.push('\n');
         }
 
+        if self.config.target == CodeTarget::NoStd {
+            content.push_str("pub mod cdr;\n");
+        }
+
         for package in package_names {
             let info = ModuleInfo::new(
                 package.clone(),
@@ -947,3 +1134,18 @@ impl Default for Generator {
         Self::new()
     }
 }
+
+/// Recursively collect the `.rs` files under `dir`
+fn collect_rs_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_rs_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+}
@@ -474,6 +474,10 @@ pub struct FieldInfo {
     string_capacity: Option<u32>,
     /// Default value for the field
     default_value: Option<String>,
+    /// Comment lines of the field in the interface definition
+    comments: Vec<String>,
+    /// Unit of the field (e.g., "m/s")
+    unit: Option<String>,
 }
 
 impl FieldInfo {
@@ -503,9 +507,19 @@ impl FieldInfo {
             capacity,
             string_capacity,
             default_value,
+            comments: Vec::new(),
+            unit: None,
         }
     }
 
+    /// Attach the comment lines and unit of the field
+    #[must_use]
+    pub(super) fn with_annotations(mut self, comments: Vec<String>, unit: Option<String>) -> Self {
+        self.comments = comments;
+        self.unit = unit;
+        self
+    }
+
     /// Get the field name
     #[must_use]
     pub fn field_name(&self) -> &str {
@@ -554,7 +568,7 @@ impl FieldInfo {
         self.capacity
     }
 
-    /// Get the string capacity for bounded strings within sequences
+    /// Get the string capacity for bounded strings within sequences and arrays
     #[must_use]
     pub fn string_capacity(&self) -> Option<u32> {
         self.string_capacity
@@ -565,4 +579,18 @@ impl FieldInfo {
     pub fn default_value(&self) -> Option<&str> {
         self.default_value.as_deref()
     }
+
+    /// Get the comment lines of the field, without the leading `#`
+    #[must_use]
+    pub fn comments(&self) -> &[String] {
+        &self.comments
+    }
+
+    /// Get the unit of the field from its `@unit` annotation
+    ///
+    /// For `.msg` files, this is the `[unit]` written in the field comment.
+    #[must_use]
+    pub fn unit(&self) -> Option<&str> {
+        self.unit.as_deref()
+    }
 }
@@ -0,0 +1,292 @@
+//! CDR serialization of the generated messages
+//!
+//! Written next to the generated modules in `no_std` mode. It only depends on
+//! `core` and `alloc`, so the crate root must declare `extern crate alloc;`.
+//!
+//! Messages are encoded in plain CDR v1 little endian with the 4-byte
+//! encapsulation header, as `ros2_types::CdrSerde` and the ROS2 middlewares
+//! do, and decoded from little or big endian plain CDR v1.
+
+use ::alloc::{string::String, vec::Vec};
+use ::core::fmt;
+
+/// Encapsulation header of plain CDR v1 little endian
+pub const CDR_LE_HEADER: [u8; 4] = [0x00, 0x01, 0x00, 0x00];
+
+/// Encapsulation header of plain CDR v1 big endian
+pub const CDR_BE_HEADER: [u8; 4] = [0x00, 0x00, 0x00, 0x00];
+
+/// Error decoding a CDR buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CdrError {
+    /// The buffer ends before the message
+    UnexpectedEof,
+    /// The representation identifier is not plain CDR v1
+    UnsupportedEncoding([u8; 2]),
+    /// A string is not null terminated UTF-8
+    InvalidString,
+    /// A boolean is neither 0 nor 1
+    InvalidBool(u8),
+}
+
+impl fmt::Display for CdrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof => write!(f, "unexpected end of the CDR buffer"),
+            Self::UnsupportedEncoding(id) => {
+                write!(f, "unsupported CDR encoding 0x{:02x}{:02x}", id[0], id[1])
+            }
+            Self::InvalidString => write!(f, "invalid CDR string"),
+            Self::InvalidBool(value) => write!(f, "invalid CDR boolean {value}"),
+        }
+    }
+}
+
+impl ::core::error::Error for CdrError {}
+
+/// Buffer a message is encoded into
+pub struct CdrWriter {
+    buf: Vec<u8>,
+}
+
+impl CdrWriter {
+    /// Create a buffer holding the little endian encapsulation header
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            buf: Vec::from(CDR_LE_HEADER),
+        }
+    }
+
+    /// Pad the buffer to a multiple of `alignment` from the end of the header
+    pub fn align(&mut self, alignment: usize) {
+        let pos = self.buf.len() - CDR_LE_HEADER.len();
+        let padding = (alignment - pos % alignment) % alignment;
+        self.buf.resize(self.buf.len() + padding, 0);
+    }
+
+    /// Append raw bytes
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Take the encoded bytes, header included
+    #[must_use]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+impl Default for CdrWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Buffer a message is decoded from
+pub struct CdrReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    little_endian: bool,
+}
+
+impl<'a> CdrReader<'a> {
+    /// Check the encapsulation header and start reading after it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header is missing or not plain CDR v1.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, CdrError> {
+        if bytes.len() < CDR_LE_HEADER.len() {
+            return Err(CdrError::UnexpectedEof);
+        }
+        let little_endian = match [bytes[0], bytes[1]] {
+            [0x00, 0x01] => true,
+            [0x00, 0x00] => false,
+            id => return Err(CdrError::UnsupportedEncoding(id)),
+        };
+        Ok(Self {
+            buf: &bytes[CDR_LE_HEADER.len()..],
+            pos: 0,
+            little_endian,
+        })
+    }
+
+    /// Whether the buffer is little endian
+    #[must_use]
+    pub fn is_little_endian(&self) -> bool {
+        self.little_endian
+    }
+
+    /// Skip the padding up to a multiple of `alignment`
+    pub fn align(&mut self, alignment: usize) {
+        self.pos += (alignment - self.pos % alignment) % alignment;
+    }
+
+    /// Read `len` raw bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer is shorter.
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], CdrError> {
+        let end = self.pos.checked_add(len).ok_or(CdrError::UnexpectedEof)?;
+        let bytes = self.buf.get(self.pos..end).ok_or(CdrError::UnexpectedEof)?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    /// Number of bytes left
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.buf.len().saturating_sub(self.pos)
+    }
+}
+
+/// Type encoded in CDR
+pub trait CdrEncode {
+    /// Append `self` to the buffer
+    fn encode(&self, writer: &mut CdrWriter);
+}
+
+/// Type decoded from CDR
+pub trait CdrDecode: Sized {
+    /// Read a value from the buffer
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer does not hold a valid value.
+    fn decode(reader: &mut CdrReader<'_>) -> Result<Self, CdrError>;
+}
+
+macro_rules! impl_primitive {
+    ($($ty:ty),*) => {
+        $(
+            impl CdrEncode for $ty {
+                fn encode(&self, writer: &mut CdrWriter) {
+                    writer.align(::core::mem::size_of::<$ty>());
+                    writer.write_bytes(&self.to_le_bytes());
+                }
+            }
+
+            impl CdrDecode for $ty {
+                fn decode(reader: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+                    const SIZE: usize = ::core::mem::size_of::<$ty>();
+                    reader.align(SIZE);
+                    let mut bytes = [0; SIZE];
+                    bytes.copy_from_slice(reader.read_bytes(SIZE)?);
+                    Ok(if reader.is_little_endian() {
+                        <$ty>::from_le_bytes(bytes)
+                    } else {
+                        <$ty>::from_be_bytes(bytes)
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+impl CdrEncode for bool {
+    fn encode(&self, writer: &mut CdrWriter) {
+        u8::from(*self).encode(writer);
+    }
+}
+
+impl CdrDecode for bool {
+    fn decode(reader: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+        match u8::decode(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(CdrError::InvalidBool(value)),
+        }
+    }
+}
+
+/// Length prefix of strings and sequences
+fn encode_len(len: usize, writer: &mut CdrWriter) {
+    // Lengths above u32::MAX cannot be represented in CDR
+    u32::try_from(len).unwrap_or(u32::MAX).encode(writer);
+}
+
+impl CdrEncode for String {
+    fn encode(&self, writer: &mut CdrWriter) {
+        // Length including the null terminator
+        encode_len(self.len() + 1, writer);
+        writer.write_bytes(self.as_bytes());
+        writer.write_bytes(&[0]);
+    }
+}
+
+impl CdrDecode for String {
+    fn decode(reader: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+        let len = u32::decode(reader)? as usize;
+        let bytes = reader.read_bytes(len)?;
+        // Some writers omit the null terminator of empty strings
+        let bytes = match bytes.split_last() {
+            Some((0, bytes)) => bytes,
+            Some(_) => return Err(CdrError::InvalidString),
+            None => bytes,
+        };
+        ::core::str::from_utf8(bytes)
+            .map(String::from)
+            .map_err(|_| CdrError::InvalidString)
+    }
+}
+
+impl<T: CdrEncode> CdrEncode for Vec<T> {
+    fn encode(&self, writer: &mut CdrWriter) {
+        encode_len(self.len(), writer);
+        for item in self {
+            item.encode(writer);
+        }
+    }
+}
+
+impl<T: CdrDecode> CdrDecode for Vec<T> {
+    fn decode(reader: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+        let len = u32::decode(reader)? as usize;
+        // Each element takes at least one byte, so a corrupted length cannot
+        // allocate more than the buffer
+        let mut items = Vec::with_capacity(len.min(reader.remaining()));
+        for _ in 0..len {
+            items.push(T::decode(reader)?);
+        }
+        Ok(items)
+    }
+}
+
+impl<T: CdrEncode, const N: usize> CdrEncode for [T; N] {
+    fn encode(&self, writer: &mut CdrWriter) {
+        for item in self {
+            item.encode(writer);
+        }
+    }
+}
+
+impl<T: CdrDecode, const N: usize> CdrDecode for [T; N] {
+    fn decode(reader: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+        let mut items = Vec::with_capacity(N);
+        for _ in 0..N {
+            items.push(T::decode(reader)?);
+        }
+        items.try_into().map_err(|_| CdrError::UnexpectedEof)
+    }
+}
+
+/// Encode a message with the little endian encapsulation header
+#[must_use]
+pub fn to_bytes<T: CdrEncode>(message: &T) -> Vec<u8> {
+    let mut writer = CdrWriter::new();
+    message.encode(&mut writer);
+    writer.into_bytes()
+}
+
+/// Decode a message, encapsulation header included
+///
+/// # Errors
+///
+/// Returns an error if the buffer does not hold a valid message.
+pub fn from_bytes<T: CdrDecode>(bytes: &[u8]) -> Result<T, CdrError> {
+    T::decode(&mut CdrReader::new(bytes)?)
+}
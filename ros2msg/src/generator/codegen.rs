@@ -6,17 +6,19 @@
 
 use super::{
     FileType, GeneratedCode, GeneratorResult, InterfaceKind,
-    config::GeneratorConfig,
-    token_gen::{self, ConstantDef, FieldDefault, StructField},
+    config::{CodeTarget, GeneratorConfig},
+    token_gen::{self, ConstantDef, ConstantEnum, FieldDefault, StructField},
     types::TypeMapper,
 };
 use crate::idl::parse_idl_string;
 use crate::idl::types::{IdlContent, IdlType, Message};
-use crate::idl_adapter::{action_to_idl, message_to_idl, service_to_idl};
+use crate::idl_adapter::{
+    action_to_idl_with_options, message_to_idl_with_options, service_to_idl_with_options,
+};
 use crate::{BaseType, Type, parse_action_file, parse_message_file, parse_service_file};
-use heck::ToSnakeCase;
+use heck::{ToSnakeCase, ToUpperCamelCase};
 use proc_macro2::TokenStream;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::Path;
 
 /// Code generator implementation
@@ -31,6 +33,8 @@ impl CodeGenerator {
     pub fn new(config: GeneratorConfig) -> Self {
         let type_mapper = if let Some(prefix) = &config.ctypes_prefix {
             TypeMapper::with_ctypes_prefix(prefix)
+        } else if config.target == CodeTarget::NoStd {
+            TypeMapper::with_ctypes_prefix("::core::ffi")
         } else {
             TypeMapper::new()
         };
@@ -143,7 +147,12 @@ impl CodeGenerator {
                     .and_then(|n| n.to_str())
                     .unwrap_or("unknown.msg");
                 let input_file = format!("msg/{file_name}");
-                Ok(message_to_idl(&msg_spec, package_name, &input_file))
+                Ok(message_to_idl_with_options(
+                    &msg_spec,
+                    package_name,
+                    &input_file,
+                    &self.config.idl_options,
+                ))
             }
             FileType::Service => {
                 let srv_spec = parse_service_file(package_name, path)?;
@@ -152,7 +161,12 @@ impl CodeGenerator {
                     .and_then(|n| n.to_str())
                     .unwrap_or("unknown.srv");
                 let input_file = format!("srv/{file_name}");
-                Ok(service_to_idl(&srv_spec, package_name, &input_file))
+                Ok(service_to_idl_with_options(
+                    &srv_spec,
+                    package_name,
+                    &input_file,
+                    &self.config.idl_options,
+                ))
             }
             FileType::Action => {
                 let action_spec = parse_action_file(package_name, path)?;
@@ -161,7 +175,12 @@ impl CodeGenerator {
                     .and_then(|n| n.to_str())
                     .unwrap_or("unknown.action");
                 let input_file = format!("action/{file_name}");
-                Ok(action_to_idl(&action_spec, package_name, &input_file))
+                Ok(action_to_idl_with_options(
+                    &action_spec,
+                    package_name,
+                    &input_file,
+                    &self.config.idl_options,
+                ))
             }
             FileType::Idl => Ok(std::fs::read_to_string(path)?),
         }
@@ -197,11 +216,8 @@ impl CodeGenerator {
         )?;
 
         // Extract just package names for recursive dependency discovery
-        let mut package_deps: HashSet<String> = HashSet::new();
-        for (pkg, _, _) in &dependencies {
-            package_deps.insert(pkg.clone());
-        }
-        let dep_strings: Vec<String> = package_deps.into_iter().collect();
+        let package_deps: BTreeSet<&String> = dependencies.iter().map(|(pkg, _, _)| pkg).collect();
+        let dep_strings: Vec<String> = package_deps.into_iter().cloned().collect();
 
         Ok((code, dep_strings, interface_kind))
     }
@@ -215,7 +231,7 @@ impl CodeGenerator {
         content: &IdlContent,
         package_name: &str,
         typedef_map: &HashMap<String, Type>,
-        dependencies: &mut HashSet<(String, String, String)>,
+        dependencies: &mut BTreeSet<(String, String, String)>,
     ) -> GeneratorResult<(String, InterfaceKind)> {
         let mut output = String::new();
 
@@ -391,8 +407,8 @@ impl CodeGenerator {
     fn extract_dependencies_from_includes(
         includes: Vec<&crate::idl::types::Include>,
         current_package: &str,
-    ) -> std::collections::HashSet<(String, String, String)> {
-        let mut dependencies = std::collections::HashSet::new();
+    ) -> BTreeSet<(String, String, String)> {
+        let mut dependencies = BTreeSet::new();
 
         for include in includes {
             let locator = &include.locator;
@@ -426,7 +442,7 @@ impl CodeGenerator {
     /// Returns a set of tuples (`package_name`, `file_type`, `type_name`)
     fn collect_dependencies_from_type(
         idl_type: &IdlType,
-        dependencies: &mut std::collections::HashSet<(String, String, String)>,
+        dependencies: &mut BTreeSet<(String, String, String)>,
     ) {
         match idl_type {
             IdlType::Namespaced(ns_type) => {
@@ -531,34 +547,158 @@ impl CodeGenerator {
         }
     }
 
+    /// Rewrite the `std` paths of a field type or default value to `alloc`
+    /// and `core` ones in `no_std` mode
+    ///
+    /// `Vec` is not in the `no_std` prelude, and `ToString` neither, so
+    /// `.to_string()` defaults become `.into()`.
+    fn native_paths(&self, code: &str) -> String {
+        if self.config.target == CodeTarget::Std {
+            return code.to_string();
+        }
+        let code = code
+            .replace("::std::string::", "::alloc::string::")
+            .replace("::std::vec::", "::alloc::vec::")
+            .replace("::std::mem::", "::core::mem::")
+            .replace(".to_string()", ".into()");
+
+        // Qualify the bare `Vec<...>` types and `vec![...]` macros
+        let mut result = String::with_capacity(code.len());
+        let mut rest = code.as_str();
+        while let Some(pos) = rest.find(['V', 'v']) {
+            let (before, after) = rest.split_at(pos);
+            result.push_str(before);
+            let bare = !result.ends_with(|c: char| c == ':' || c == '_' || c.is_alphanumeric());
+            if bare && after.starts_with("Vec<") {
+                result.push_str("::alloc::vec::Vec<");
+                rest = &after[4..];
+            } else if bare && after.starts_with("vec![") {
+                result.push_str("::alloc::vec![");
+                rest = &after[5..];
+            } else {
+                result.push_str(&after[..1]);
+                rest = &after[1..];
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+
     /// Format a default value from annotation for the given field type
+    ///
+    /// Follows the rosidl rules: float defaults may omit the fractional part,
+    /// string defaults may be quoted, and arrays/sequences are given as
+    /// `[a, b]` or `(a, b)` lists.
     fn format_default_value(field_type: &str, default_val: &str) -> String {
+        let default_val = default_val.trim();
+
         // Handle Vec
-        if field_type.starts_with("Vec<") {
-            // Parse array literal like [1, 2, 3]
-            if default_val.starts_with('[') && default_val.ends_with(']') {
-                return format!("vec!{default_val}");
-            }
-            return "::std::vec::Vec::new()".to_string();
+        if let Some(elem_type) = field_type
+            .strip_prefix("Vec<")
+            .and_then(|t| t.strip_suffix('>'))
+        {
+            return match Self::split_default_list(default_val) {
+                Some(elements) => format!(
+                    "vec![{}]",
+                    Self::format_default_elements(elem_type, &elements)
+                ),
+                None => "::std::vec::Vec::new()".to_string(),
+            };
         }
 
         // Handle arrays
-        if field_type.starts_with('[') {
-            return default_val.to_string();
+        if let Some(inner) = field_type
+            .strip_prefix('[')
+            .and_then(|t| t.strip_suffix(']'))
+            && let Some((elem_type, _)) = inner.rsplit_once(';')
+        {
+            return match Self::split_default_list(default_val) {
+                Some(elements) => format!(
+                    "[{}]",
+                    Self::format_default_elements(elem_type.trim(), &elements)
+                ),
+                None => Self::get_type_default_value(field_type),
+            };
         }
 
         // Handle primitive types
         match field_type {
-            "String" => format!("\"{default_val}\".to_string()"),
+            "String" => format!("{:?}.to_string()", Self::unquote_default(default_val)),
+            "f32" | "f64" => match default_val.parse::<f64>() {
+                Ok(f) => Self::format_float(f, field_type),
+                Err(_) => default_val.to_string(),
+            },
+            "bool" => default_val.to_lowercase(),
             _ => default_val.to_string(),
         }
     }
 
+    /// Format the elements of a list default value, separated by commas
+    fn format_default_elements(elem_type: &str, elements: &[&str]) -> String {
+        elements
+            .iter()
+            .map(|e| Self::format_default_value(elem_type, e))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Split a `[a, b]` or `(a, b)` default value into its elements
+    ///
+    /// Commas inside quoted strings do not separate elements.
+    fn split_default_list(default_val: &str) -> Option<Vec<&str>> {
+        let inner = default_val
+            .strip_prefix('[')
+            .and_then(|s| s.strip_suffix(']'))
+            .or_else(|| {
+                default_val
+                    .strip_prefix('(')
+                    .and_then(|s| s.strip_suffix(')'))
+            })?;
+        if inner.trim().is_empty() {
+            return Some(Vec::new());
+        }
+
+        let mut elements = Vec::new();
+        let mut start = 0;
+        let mut quote = None;
+        let mut escaped = false;
+        for (i, c) in inner.char_indices() {
+            match (quote, c) {
+                (Some(_), '\\') if !escaped => {
+                    escaped = true;
+                    continue;
+                }
+                (Some(q), c) if c == q && !escaped => quote = None,
+                (None, '"' | '\'') => quote = Some(c),
+                (None, ',') => {
+                    elements.push(inner[start..i].trim());
+                    start = i + 1;
+                }
+                _ => (),
+            }
+            escaped = false;
+        }
+        elements.push(inner[start..].trim());
+        Some(elements)
+    }
+
+    /// Remove the quotes around a string default value, if any
+    fn unquote_default(default_val: &str) -> &str {
+        for q in ['"', '\''] {
+            if default_val.len() >= 2
+                && let Some(s) = default_val.strip_prefix(q).and_then(|s| s.strip_suffix(q))
+            {
+                return s;
+            }
+        }
+        default_val
+    }
+
     /// Resolve field type with full paths for nested types to avoid ambiguity
     fn resolve_field_type_with_dependencies(
         field_type: String,
         interface_kind: InterfaceKind,
-        dependencies: &HashSet<(String, String, String)>,
+        dependencies: &BTreeSet<(String, String, String)>,
     ) -> String {
         // For nested types (types from other packages/modules), prepend the full module path
         // to avoid ambiguity with std types (like String vs std::string::String)
@@ -658,7 +798,7 @@ impl CodeGenerator {
         package_name: &str,
         interface_kind: InterfaceKind,
         typedef_map: &HashMap<String, Type>,
-        dependencies: &HashSet<(String, String, String)>,
+        dependencies: &BTreeSet<(String, String, String)>,
     ) -> String {
         let struct_name = self.config.transform_item_name(
             &message.structure.namespaced_type.name,
@@ -755,6 +895,10 @@ impl CodeGenerator {
                     capacity,
                     string_capacity,
                     default_value_annotation.clone(),
+                )
+                .with_annotations(
+                    member.annotations.get_comment_lines(),
+                    member.annotations.get_unit(),
                 );
                 field_attrs = cb.add_field_attributes(&field_info);
             }
@@ -762,7 +906,7 @@ impl CodeGenerator {
             // Create field
             fields.push(StructField {
                 name: field_name.clone(),
-                rust_type: field_type.clone(),
+                rust_type: self.native_paths(&field_type),
                 attributes: field_attrs,
             });
 
@@ -773,7 +917,10 @@ impl CodeGenerator {
                 } else {
                     Self::get_type_default_value(&field_type)
                 };
-                field_defaults.push(FieldDefault::new(field_name, default_value));
+                field_defaults.push(FieldDefault::new(
+                    field_name,
+                    self.native_paths(&default_value),
+                ));
             }
         }
 
@@ -805,6 +952,16 @@ impl CodeGenerator {
             tokens_vec.push(default_tokens);
         }
 
+        // Generate CDR serialization for no_std targets
+        if self.config.target == CodeTarget::NoStd {
+            let runtime_path = format!("{}cdr", "super::".repeat(interface_kind.import_depth()));
+            tokens_vec.push(token_gen::generate_cdr_impl(
+                &struct_name,
+                &fields,
+                &runtime_path,
+            ));
+        }
+
         // Add custom implementations via callback
         if let Some(cb) = &self.config.parse_callbacks {
             use super::callbacks::ItemInfo;
@@ -831,6 +988,12 @@ impl CodeGenerator {
         if !constants.is_empty() {
             let const_tokens = token_gen::generate_constants_impl(&struct_name, &constants);
             tokens_vec.push(const_tokens);
+
+            if self.config.constant_enums {
+                for group in Self::group_constants(&struct_name, &constants) {
+                    tokens_vec.push(token_gen::generate_constant_enum(&struct_name, &group));
+                }
+            }
         }
 
         // Format all tokens together
@@ -1053,10 +1216,15 @@ impl CodeGenerator {
         }
     }
 
-    /// Get the string capacity for bounded strings within sequences
-    /// Returns the string's maximum size if the sequence element is a bounded string
+    /// Get the string capacity for bounded strings within sequences and arrays
+    /// Returns the string's maximum size if the element is a bounded string
     fn get_string_capacity(idl_type: &IdlType) -> Option<u32> {
         match idl_type {
+            IdlType::Array(arr) => match arr.value_type.as_ref() {
+                IdlType::String(s) => s.maximum_size(),
+                IdlType::WString(w) => w.maximum_size(),
+                _ => None,
+            },
             IdlType::BoundedSequence(seq) => match seq.value_type.as_ref() {
                 IdlType::String(s) => s.maximum_size(),
                 IdlType::WString(w) => w.maximum_size(),
@@ -1085,15 +1253,127 @@ impl CodeGenerator {
             IdlValue::UInt32(u) => u.to_string(),
             IdlValue::Int64(i) => i.to_string(),
             IdlValue::UInt64(u) => u.to_string(),
-            IdlValue::Float32(f) => f.to_string(),
-            IdlValue::Float64(f) => f.to_string(),
-            IdlValue::String(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
-            IdlValue::Char(c) => format!("'{c}'"),
+            IdlValue::Float32(f) => Self::format_float(f64::from(*f), "f32"),
+            IdlValue::Float64(f) => Self::format_float(*f, "f64"),
+            // Debug formatting escapes quotes, backslashes and control characters
+            IdlValue::String(s) => format!("{s:?}"),
+            // IDL chars are 8-bit and map to `c_char`, whose signedness depends
+            // on the target, so emit a byte literal cast to the constant type
+            IdlValue::Char(c) => match u8::try_from(*c) {
+                Ok(b) => format!("b'{}' as _", std::ascii::escape_default(b)),
+                Err(_) => format!("compile_error!(\"char constant {c:?} is not 8-bit\")"),
+            },
             IdlValue::Array(_) => "/* array */".to_string(),
             IdlValue::Object(_) => "/* object */".to_string(),
             IdlValue::Null => "/* null */".to_string(),
         }
     }
+
+    /// Detect the groups of integer constants sharing a name prefix, e.g. the
+    /// `POWER_SUPPLY_STATUS_*` constants of `sensor_msgs/BatteryState`
+    ///
+    /// Consecutive constants of the same integer type form a group when their
+    /// names share a `_`-separated prefix. Groups of a single constant, with
+    /// duplicated values, or whose variant names are not identifiers are skipped.
+    fn group_constants(struct_name: &str, constants: &[ConstantDef]) -> Vec<ConstantEnum> {
+        const INTEGER_TYPES: [&str; 8] = ["u8", "i8", "u16", "i16", "u32", "i32", "u64", "i64"];
+
+        let mut groups: Vec<(String, Vec<&ConstantDef>)> = Vec::new();
+        for constant in constants
+            .iter()
+            .filter(|c| INTEGER_TYPES.contains(&c.rust_type.as_str()))
+        {
+            if let Some((prefix, members)) = groups.last_mut()
+                && members[0].rust_type == constant.rust_type
+            {
+                if members.len() > 1 {
+                    if constant.name.starts_with(prefix.as_str()) {
+                        members.push(constant);
+                        continue;
+                    }
+                } else {
+                    let common = Self::common_name_prefix(&members[0].name, &constant.name);
+                    if !common.is_empty() {
+                        *prefix = common;
+                        members.push(constant);
+                        continue;
+                    }
+                }
+            }
+            groups.push((String::new(), vec![constant]));
+        }
+
+        groups
+            .into_iter()
+            .filter(|(_, members)| members.len() > 1)
+            .filter_map(|(prefix, members)| {
+                let variants: Vec<(String, String)> = members
+                    .iter()
+                    .map(|c| {
+                        let variant = c.name[prefix.len()..].to_upper_camel_case();
+                        (variant, c.name.clone())
+                    })
+                    .collect();
+
+                let valid_names = variants.iter().all(|(variant, _)| {
+                    variant.starts_with(|ch: char| ch.is_ascii_alphabetic())
+                        && variant.chars().all(|ch| ch.is_ascii_alphanumeric())
+                        && variant != "Self"
+                });
+                let names: HashSet<_> = variants.iter().map(|(variant, _)| variant).collect();
+                let values: HashSet<_> = members.iter().map(|c| c.value.as_str()).collect();
+                if !valid_names || names.len() != members.len() || values.len() != members.len() {
+                    return None;
+                }
+
+                Some(ConstantEnum {
+                    name: format!(
+                        "{struct_name}{}",
+                        prefix.trim_end_matches('_').to_upper_camel_case()
+                    ),
+                    rust_type: members[0].rust_type.clone(),
+                    variants,
+                })
+            })
+            .collect()
+    }
+
+    /// Longest `_`-separated prefix of two constant names, including the
+    /// trailing `_`, leaving a non-empty suffix to both names
+    fn common_name_prefix(a: &str, b: &str) -> String {
+        use std::fmt::Write;
+
+        let a: Vec<&str> = a.split('_').collect();
+        let b: Vec<&str> = b.split('_').collect();
+        a[..a.len() - 1]
+            .iter()
+            .zip(&b[..b.len() - 1])
+            .take_while(|(x, y)| x == y)
+            .fold(String::new(), |mut prefix, (x, _)| {
+                let _ = write!(prefix, "{x}_");
+                prefix
+            })
+    }
+
+    /// Format a floating point constant as a valid Rust float expression.
+    ///
+    /// `Display` drops the fractional part of whole numbers (`1.0` becomes `1`),
+    /// which would not type check against an `f32`/`f64` constant.
+    fn format_float(f: f64, ty: &str) -> String {
+        if f.is_nan() {
+            format!("{ty}::NAN")
+        } else if f.is_infinite() {
+            let sign = if f < 0.0 { "NEG_" } else { "" };
+            format!("{ty}::{sign}INFINITY")
+        } else if ty == "f32" {
+            // The constant is an `f32`: print the shortest literal of its value
+            #[allow(clippy::cast_possible_truncation)]
+            let f = f as f32;
+            format!("{f:?}")
+        } else {
+            format!("{f:?}")
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1140,4 +1420,120 @@ module test {
         );
         assert_eq!(resolved_type.base_type.type_name, "float64");
     }
+
+    #[test]
+    fn test_format_constant_values() {
+        use crate::idl::values::IdlValue;
+
+        assert_eq!(
+            CodeGenerator::format_idl_value(&IdlValue::Float64(1.0)),
+            "1.0"
+        );
+        assert_eq!(
+            CodeGenerator::format_idl_value(&IdlValue::Float32(0.5)),
+            "0.5"
+        );
+        assert_eq!(
+            CodeGenerator::format_idl_value(&IdlValue::Float64(f64::NEG_INFINITY)),
+            "f64::NEG_INFINITY"
+        );
+        assert_eq!(
+            CodeGenerator::format_idl_value(&IdlValue::String("say \"hi\"\n".into())),
+            "\"say \\\"hi\\\"\\n\""
+        );
+        assert_eq!(
+            CodeGenerator::format_idl_value(&IdlValue::Char('A')),
+            "b'A' as _"
+        );
+        assert_eq!(
+            CodeGenerator::format_idl_value(&IdlValue::Char('\'')),
+            "b'\\'' as _"
+        );
+    }
+
+    #[test]
+    fn test_format_default_values() {
+        // Float defaults without a fractional part, as in geometry_msgs/Quaternion
+        assert_eq!(CodeGenerator::format_default_value("f64", "1"), "1.0");
+        assert_eq!(CodeGenerator::format_default_value("f32", "-0.5"), "-0.5");
+        assert_eq!(CodeGenerator::format_default_value("i8", "-2"), "-2");
+        assert_eq!(CodeGenerator::format_default_value("bool", "True"), "true");
+        assert_eq!(
+            CodeGenerator::format_default_value("String", "say \"hi\""),
+            "\"say \\\"hi\\\"\".to_string()"
+        );
+        assert_eq!(
+            CodeGenerator::format_default_value("String", "'quoted'"),
+            "\"quoted\".to_string()"
+        );
+        assert_eq!(
+            CodeGenerator::format_default_value("Vec<f64>", "(0, 1.5)"),
+            "vec![0.0, 1.5]"
+        );
+        assert_eq!(
+            CodeGenerator::format_default_value("[i32; 3]", "[1, 2, 3]"),
+            "[1, 2, 3]"
+        );
+        assert_eq!(
+            CodeGenerator::format_default_value("Vec<String>", "['a, b', \"c\"]"),
+            "vec![\"a, b\".to_string(), \"c\".to_string()]"
+        );
+        assert_eq!(
+            CodeGenerator::format_default_value("Vec<u8>", "[]"),
+            "vec![]"
+        );
+    }
+
+    #[test]
+    fn test_native_paths() {
+        let mut config = GeneratorConfig::new();
+        config.target = CodeTarget::NoStd;
+        let generator = CodeGenerator::new(config);
+        assert_eq!(
+            generator.native_paths("Vec<::std::string::String /* max_size: 10 */>"),
+            "::alloc::vec::Vec<::alloc::string::String /* max_size: 10 */>"
+        );
+        assert_eq!(
+            generator.native_paths("Vec<super::super::super::pkg::msg::Vec3>"),
+            "::alloc::vec::Vec<super::super::super::pkg::msg::Vec3>"
+        );
+        assert_eq!(
+            generator.native_paths("vec![\"a\".to_string()]"),
+            "::alloc::vec![\"a\".into()]"
+        );
+        assert_eq!(
+            generator.native_paths("unsafe { ::std::mem::zeroed() }"),
+            "unsafe { ::core::mem::zeroed() }"
+        );
+        assert_eq!(
+            CodeGenerator::new(GeneratorConfig::new()).native_paths("Vec<u8>"),
+            "Vec<u8>"
+        );
+    }
+
+    #[test]
+    fn test_group_constants() {
+        let constants = vec![
+            ConstantDef::new("POWER_SUPPLY_STATUS_UNKNOWN", "u8", "0"),
+            ConstantDef::new("POWER_SUPPLY_STATUS_CHARGING", "u8", "1"),
+            ConstantDef::new("POWER_SUPPLY_STATUS_NOT_CHARGING", "u8", "3"),
+            ConstantDef::new("POWER_SUPPLY_HEALTH_UNKNOWN", "u8", "0"),
+            ConstantDef::new("POWER_SUPPLY_HEALTH_GOOD", "u8", "1"),
+            ConstantDef::new("MAX_CELLS", "u32", "16"),
+            ConstantDef::new("NAME", "&str", "\"battery\""),
+            ConstantDef::new("MODE_A", "i8", "1"),
+            ConstantDef::new("MODE_B", "i8", "1"),
+        ];
+
+        let groups = CodeGenerator::group_constants("BatteryState", &constants);
+        assert_eq!(groups.len(), 2, "{groups:?}");
+
+        assert_eq!(groups[0].name, "BatteryStatePowerSupplyStatus");
+        assert_eq!(groups[0].rust_type, "u8");
+        let variants: Vec<_> = groups[0].variants.iter().map(|(v, _)| v.as_str()).collect();
+        assert_eq!(variants, ["Unknown", "Charging", "NotCharging"]);
+
+        assert_eq!(groups[1].name, "BatteryStatePowerSupplyHealth");
+        assert_eq!(groups[1].variants[1].1, "POWER_SUPPLY_HEALTH_GOOD");
+    }
 }
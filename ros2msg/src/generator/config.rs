@@ -1,6 +1,7 @@
 //! Configuration for the code generator
 
 use super::callbacks::ParseCallbacks;
+use crate::idl_adapter::IdlOptions;
 use heck::ToSnakeCase;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -46,6 +47,18 @@ pub struct GeneratorConfig {
 
     /// Package search paths for finding dependencies
     pub package_search_paths: Vec<PathBuf>,
+
+    /// Whether to emit enums for groups of constants sharing a prefix
+    pub constant_enums: bool,
+
+    /// Whether to write to the output directory or compare with it
+    pub output_mode: OutputMode,
+
+    /// Environment the generated code compiles in
+    pub target: CodeTarget,
+
+    /// Options of the conversion of .msg/.srv/.action files to IDL
+    pub idl_options: IdlOptions,
 }
 
 impl GeneratorConfig {
@@ -66,6 +79,10 @@ impl GeneratorConfig {
             blocklist: Vec::new(),
             allowlist_recursively: false,
             package_search_paths: Vec::new(),
+            constant_enums: false,
+            output_mode: OutputMode::Write,
+            target: CodeTarget::Std,
+            idl_options: IdlOptions::default(),
         }
     }
 
@@ -162,6 +179,29 @@ impl GeneratorConfig {
     }
 }
 
+/// What [`Generator::generate`](super::Generator::generate) does with the
+/// generated code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Write the generated files to the output directory
+    #[default]
+    Write,
+
+    /// Compare the generated files with the output directory, writing nothing
+    Check,
+}
+
+/// Environment the generated code compiles in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CodeTarget {
+    /// Code using `std`
+    #[default]
+    Std,
+
+    /// `no_std + alloc` code with CDR serialization
+    NoStd,
+}
+
 impl Default for GeneratorConfig {
     fn default() -> Self {
         Self::new()
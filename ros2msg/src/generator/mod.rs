@@ -25,6 +25,10 @@
 
 mod builder;
 mod callbacks;
+#[cfg(test)]
+#[allow(unreachable_pub, dead_code)]
+#[path = "cdr_runtime.rs"]
+mod cdr_runtime;
 mod codegen;
 mod config;
 mod token_gen;
@@ -33,9 +37,12 @@ mod types;
 pub use builder::Generator;
 pub use callbacks::{FieldInfo, ItemInfo, ModuleInfo, ModuleLevel, ParseCallbacks};
 pub use codegen::CodeGenerator;
-pub use config::{GeneratorConfig, sanitize_rust_identifier};
+pub use config::{CodeTarget, GeneratorConfig, OutputMode, sanitize_rust_identifier};
 pub use types::TypeMapper;
 
+/// CDR runtime written as `cdr.rs` in `no_std` mode
+const CDR_RUNTIME: &str = include_str!("cdr_runtime.rs");
+
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -96,6 +103,21 @@ pub enum GenerationError {
         /// Number of messages found
         found: usize,
     },
+
+    /// Generated files differ from the output directory (check mode)
+    #[error("{} generated file(s) are out of date: {}", .files.len(), display_paths(.files))]
+    OutOfDate {
+        /// Files that differ, are missing, or are no longer generated
+        files: Vec<PathBuf>,
+    },
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 /// Errors that can occur during code generation
@@ -271,3 +293,70 @@ impl GeneratedCode {
         format!("{}.rs", self.module_name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::cdr_runtime::{CdrDecode, CdrEncode, CdrError, CdrReader, from_bytes, to_bytes};
+
+    #[test]
+    fn test_cdr_runtime_round_trip() {
+        let bytes = to_bytes(&(
+            7u8,
+            1.5f64,
+            String::from("hi"),
+            vec![1i16, -2],
+            [true, false],
+        ));
+        assert_eq!(
+            bytes,
+            [
+                0x00, 0x01, 0x00, 0x00, // header
+                7, 0, 0, 0, 0, 0, 0, 0, // u8, padded to 8
+                0, 0, 0, 0, 0, 0, 0xf8, 0x3f, // f64
+                3, 0, 0, 0, b'h', b'i', 0, // string
+                0, 2, 0, 0, 0, // padding, sequence length
+                1, 0, 0xfe, 0xff, // i16 items
+                1, 0, // bool array
+            ]
+        );
+        let decoded: (u8, f64, String, Vec<i16>, [bool; 2]) = from_bytes(&bytes).unwrap();
+        assert_eq!(
+            decoded,
+            (7, 1.5, String::from("hi"), vec![1, -2], [true, false])
+        );
+
+        // Big endian
+        let value: u32 = from_bytes(&[0x00, 0x00, 0x00, 0x00, 0, 0, 1, 2]).unwrap();
+        assert_eq!(value, 0x0102);
+
+        assert_eq!(
+            from_bytes::<u32>(&[0x00, 0x07, 0x00, 0x00]),
+            Err(CdrError::UnsupportedEncoding([0x00, 0x07]))
+        );
+        assert_eq!(
+            from_bytes::<Vec<u64>>(&[0x00, 0x01, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff]),
+            Err(CdrError::UnexpectedEof)
+        );
+        let mut reader = CdrReader::new(&[0x00, 0x01, 0x00, 0x00, 2]).unwrap();
+        assert_eq!(bool::decode(&mut reader), Err(CdrError::InvalidBool(2)));
+    }
+
+    // Tuples stand for generated structs, which encode their fields in order
+    macro_rules! impl_tuple {
+        ($($name:ident: $index:tt),*) => {
+            impl<$($name: CdrEncode),*> CdrEncode for ($($name,)*) {
+                fn encode(&self, writer: &mut super::cdr_runtime::CdrWriter) {
+                    $(self.$index.encode(writer);)*
+                }
+            }
+
+            impl<$($name: CdrDecode),*> CdrDecode for ($($name,)*) {
+                fn decode(reader: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+                    Ok(($($name::decode(reader)?,)*))
+                }
+            }
+        };
+    }
+
+    impl_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4);
+}
@@ -188,6 +188,38 @@ pub(super) fn generate_default_impl(name: &str, field_defaults: &[FieldDefault])
     }
 }
 
+/// Generate the `CdrEncode` and `CdrDecode` implementations of a struct
+///
+/// The fields are encoded in declaration order with the traits of the CDR
+/// runtime at `runtime_path`.
+pub(super) fn generate_cdr_impl(
+    name: &str,
+    fields: &[StructField],
+    runtime_path: &str,
+) -> TokenStream {
+    let struct_name = struct_ident(name);
+    let runtime = parse_type(runtime_path);
+    let field_names: Vec<TokenStream> = fields.iter().map(|f| field_ident(&f.name)).collect();
+
+    quote! {
+        impl #runtime::CdrEncode for #struct_name {
+            fn encode(&self, writer: &mut #runtime::CdrWriter) {
+                #(#runtime::CdrEncode::encode(&self.#field_names, writer);)*
+            }
+        }
+
+        impl #runtime::CdrDecode for #struct_name {
+            fn decode(
+                reader: &mut #runtime::CdrReader<'_>,
+            ) -> ::core::result::Result<Self, #runtime::CdrError> {
+                ::core::result::Result::Ok(Self {
+                    #(#field_names: #runtime::CdrDecode::decode(reader)?,)*
+                })
+            }
+        }
+    }
+}
+
 /// Generate constants in an impl block
 pub(super) fn generate_constants_impl(struct_name: &str, constants: &[ConstantDef]) -> TokenStream {
     if constants.is_empty() {
@@ -216,6 +248,60 @@ pub(super) fn generate_constants_impl(struct_name: &str, constants: &[ConstantDe
     }
 }
 
+/// Generate an enum for a group of integer constants sharing a prefix
+///
+/// The discriminants refer to the raw constants of the struct, so the enum
+/// always matches them. Emits `as_<repr>()`, `TryFrom<repr>` (returning the
+/// unknown value as error) and `From<enum> for repr`.
+pub(super) fn generate_constant_enum(struct_name: &str, group: &ConstantEnum) -> TokenStream {
+    let name = struct_ident(struct_name);
+    let enum_name = struct_ident(&group.name);
+    let repr = struct_ident(&group.rust_type);
+    let as_repr = Ident::new(&format!("as_{}", group.rust_type), Span::call_site());
+
+    let variants: Vec<Ident> = group
+        .variants
+        .iter()
+        .map(|(variant, _)| struct_ident(variant))
+        .collect();
+    let consts: Vec<Ident> = group
+        .variants
+        .iter()
+        .map(|(_, constant)| const_ident(constant))
+        .collect();
+
+    quote! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(#repr)]
+        pub enum #enum_name {
+            #(#variants = #name::#consts,)*
+        }
+
+        impl #enum_name {
+            pub const fn #as_repr(self) -> #repr {
+                self as #repr
+            }
+        }
+
+        impl ::core::convert::TryFrom<#repr> for #enum_name {
+            type Error = #repr;
+
+            fn try_from(value: #repr) -> ::core::result::Result<Self, Self::Error> {
+                match value {
+                    #(#name::#consts => Ok(Self::#variants),)*
+                    _ => Err(value),
+                }
+            }
+        }
+
+        impl ::core::convert::From<#enum_name> for #repr {
+            fn from(value: #enum_name) -> Self {
+                value.#as_repr()
+            }
+        }
+    }
+}
+
 /// Parse a constant value based on its type
 fn parse_const_value(value: &str, rust_type: &str) -> TokenStream {
     // String constants
@@ -331,6 +417,17 @@ impl ConstantDef {
     }
 }
 
+/// Group of constants emitted as an enum
+#[derive(Debug, Clone)]
+pub(super) struct ConstantEnum {
+    /// Enum name
+    pub name: String,
+    /// Integer type of the constants, used as `repr`
+    pub rust_type: String,
+    /// `(variant, constant)` name pairs
+    pub variants: Vec<(String, String)>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -347,6 +444,33 @@ mod tests {
         assert!(!tokens.is_empty());
     }
 
+    #[test]
+    fn test_generate_cdr_impl() {
+        let fields = vec![
+            StructField {
+                name: "r#type".to_string(),
+                rust_type: "u8".to_string(),
+                attributes: Vec::new(),
+            },
+            StructField {
+                name: "data".to_string(),
+                rust_type: "::alloc::vec::Vec<f64>".to_string(),
+                attributes: Vec::new(),
+            },
+        ];
+        let code = format_token_streams(vec![generate_cdr_impl(
+            "Sample",
+            &fields,
+            "super::super::super::cdr",
+        )])
+        .unwrap();
+        assert!(code.contains("impl super::super::super::cdr::CdrEncode for Sample {"));
+        assert!(
+            code.contains("super::super::super::cdr::CdrEncode::encode(&self.r#type, writer);")
+        );
+        assert!(code.contains("data: super::super::super::cdr::CdrDecode::decode(reader)?,"));
+    }
+
     #[test]
     fn test_parse_array_type() {
         let tokens = parse_type("[f64; 9]");
@@ -410,4 +534,33 @@ mod tests {
         assert!(formatted.contains("impl TestStruct"));
         assert!(formatted.contains("pub const MAX_VALUE: u32 = 100"));
     }
+
+    #[test]
+    fn test_generate_constant_enum() {
+        let group = ConstantEnum {
+            name: "BatteryStatePowerSupplyStatus".to_string(),
+            rust_type: "u8".to_string(),
+            variants: vec![
+                (
+                    "Unknown".to_string(),
+                    "POWER_SUPPLY_STATUS_UNKNOWN".to_string(),
+                ),
+                (
+                    "Charging".to_string(),
+                    "POWER_SUPPLY_STATUS_CHARGING".to_string(),
+                ),
+            ],
+        };
+
+        let tokens = generate_constant_enum("BatteryState", &group);
+        let formatted = format_tokens(tokens).unwrap();
+        assert!(formatted.contains("#[repr(u8)]"));
+        assert!(formatted.contains("pub enum BatteryStatePowerSupplyStatus"));
+        assert!(formatted.contains("Unknown = BatteryState::POWER_SUPPLY_STATUS_UNKNOWN"));
+        assert!(formatted.contains("pub const fn as_u8(self) -> u8"));
+        assert!(
+            formatted.contains("BatteryState::POWER_SUPPLY_STATUS_CHARGING => Ok(Self::Charging)")
+        );
+        assert!(formatted.contains("From<BatteryStatePowerSupplyStatus> for u8"));
+    }
 }
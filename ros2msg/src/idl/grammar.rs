@@ -16,7 +16,7 @@
 //! - Expression evaluation with operators
 //! - Complete grammar rules from ROS2 IDL specification
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 use super::errors::{IdlError, IdlResult};
@@ -199,13 +199,15 @@ fn collect_constants(definitions: &[parser_pest::IdlDefinition]) -> HashMap<Stri
     constants_map
 }
 /// Build a map of struct name -> Message for a set of definitions
+///
+/// The map is ordered so that the elements built from it do not depend on hashing.
 fn build_message_map(
     definitions: &[parser_pest::IdlDefinition],
     namespaces: &[String],
     constants_map: &HashMap<String, Vec<Constant>>,
     typedefs: &HashMap<String, IdlType>,
-) -> HashMap<String, Message> {
-    let mut messages = HashMap::new();
+) -> BTreeMap<String, Message> {
+    let mut messages = BTreeMap::new();
 
     for def in definitions {
         if let parser_pest::IdlDefinition::Struct(struct_def) = def {
@@ -337,7 +339,7 @@ fn process_action_module(
     );
 
     // First, extract services (SendGoal, GetResult) from the action module
-    let mut services: HashMap<String, Service> = HashMap::new();
+    let mut services: BTreeMap<String, Service> = BTreeMap::new();
 
     let request_names: Vec<String> = messages
         .keys()
@@ -838,6 +838,19 @@ impl Annotatable {
             .collect()
     }
 
+    /// Get unit from @unit annotation
+    #[must_use]
+    pub fn get_unit(&self) -> Option<String> {
+        match self.get_annotation_value("unit")? {
+            IdlValue::Object(map) => match map.get("value") {
+                Some(IdlValue::String(unit)) => Some(unit.clone()),
+                _ => None,
+            },
+            IdlValue::String(unit) => Some(unit.clone()),
+            _ => None,
+        }
+    }
+
     /// Get default value from @default annotation
     #[must_use]
     pub fn get_default_value(&self) -> Option<String> {
@@ -2,12 +2,92 @@
 //!
 //! This module converts ROS2 message, service, and action definitions to IDL format,
 //! matching the behavior of rosidl_adapter.
+//!
+//! The `*_with_options` variants take [`IdlOptions`], to also emit the event
+//! structures of services and to post-process the IDL with an
+//! [`IdlPostProcessor`] before it is fed to code generation.
+//!
+//! # Example
+//!
+//! ```
+//! use ros2msg::idl_adapter::{IdlOptions, service_to_idl_with_options};
+//! use ros2msg::parse_service_string;
+//! use std::sync::Arc;
+//!
+//! let srv = parse_service_string("my_srvs", "Trigger", "---\nbool success").unwrap();
+//! let options = IdlOptions {
+//!     service_events: true,
+//!     post_processor: Some(Arc::new(|_package: &str, _input_file: &str, idl: String| {
+//!         idl.replace("struct Trigger_Response", "@final\n    struct Trigger_Response")
+//!     })),
+//! };
+//! let idl = service_to_idl_with_options(&srv, "my_srvs", "srv/Trigger.srv", &options);
+//! assert!(idl.contains("struct Trigger_Event {"));
+//! assert!(idl.contains("@final"));
+//! ```
 
 use crate::msg::types::{AnnotationValue, Field, Value};
-use crate::msg::validation::PrimitiveValue;
+use crate::msg::validation::{PrimitiveValue, SERVICE_EVENT_MESSAGE_SUFFIX};
 use crate::{ActionSpecification, MessageSpecification, ServiceSpecification};
 use std::collections::BTreeSet;
 use std::fmt::Write;
+use std::sync::Arc;
+
+/// Include file of the `info` field of service events.
+const SERVICE_EVENT_INFO_INCLUDE: &str = "service_msgs/msg/ServiceEventInfo.idl";
+
+/// Hook post-processing the converted IDL, e.g. to inject custom annotations.
+///
+/// Implemented for closures taking the package name, the input file (e.g.
+/// `srv/AddTwoInts.srv`) and the IDL.
+pub trait IdlPostProcessor: Send + Sync {
+    /// Return the processed IDL converted from `input_file` of `package_name`.
+    fn post_process(&self, package_name: &str, input_file: &str, idl: String) -> String;
+}
+
+impl<F> IdlPostProcessor for F
+where
+    F: Fn(&str, &str, String) -> String + Send + Sync,
+{
+    fn post_process(&self, package_name: &str, input_file: &str, idl: String) -> String {
+        self(package_name, input_file, idl)
+    }
+}
+
+/// Options of the conversions to IDL.
+///
+/// The default options produce the output of `rosidl_adapter`.
+#[derive(Clone, Default)]
+pub struct IdlOptions {
+    /// Also emit the `<Service>_Event` structure of services, which
+    /// `rosidl_parser` derives for service introspection.
+    ///
+    /// Actions are not affected: their services are derived by `rosidl_parser`
+    /// from the goal and result structures.
+    pub service_events: bool,
+
+    /// Hook applied to the converted IDL.
+    pub post_processor: Option<Arc<dyn IdlPostProcessor>>,
+}
+
+impl std::fmt::Debug for IdlOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdlOptions")
+            .field("service_events", &self.service_events)
+            .field("post_processor", &self.post_processor.is_some())
+            .finish()
+    }
+}
+
+impl IdlOptions {
+    /// Apply the post-processor, if any, to `idl`.
+    fn post_process(&self, package_name: &str, input_file: &str, idl: String) -> String {
+        match &self.post_processor {
+            Some(processor) => processor.post_process(package_name, input_file, idl),
+            None => idl,
+        }
+    }
+}
 
 /// Convert a MSG type to its IDL equivalent
 fn msg_type_to_idl(msg_type: &str) -> &'static str {
@@ -199,6 +279,20 @@ fn get_unit_str(
 /// Panics if writing to the output string fails (which should never happen in practice).
 #[must_use]
 pub fn message_to_idl(msg: &MessageSpecification, package_name: &str, input_file: &str) -> String {
+    message_to_idl_with_options(msg, package_name, input_file, &IdlOptions::default())
+}
+
+/// Convert a message to IDL format with the given options
+///
+/// # Panics
+/// Panics if writing to the output string fails (which should never happen in practice).
+#[must_use]
+pub fn message_to_idl_with_options(
+    msg: &MessageSpecification,
+    package_name: &str,
+    input_file: &str,
+    options: &IdlOptions,
+) -> String {
     let mut output = String::new();
 
     // Header comment
@@ -247,7 +341,7 @@ pub fn message_to_idl(msg: &MessageSpecification, package_name: &str, input_file
     writeln!(output, "  }};").unwrap();
     writeln!(output, "}};").unwrap();
 
-    output
+    options.post_process(package_name, input_file, output)
 }
 
 /// Write a struct definition in IDL format
@@ -376,6 +470,20 @@ fn write_struct_idl(
 /// Panics if writing to the output string fails (which should never happen in practice).
 #[must_use]
 pub fn service_to_idl(srv: &ServiceSpecification, package_name: &str, input_file: &str) -> String {
+    service_to_idl_with_options(srv, package_name, input_file, &IdlOptions::default())
+}
+
+/// Convert a service to IDL format with the given options
+///
+/// # Panics
+/// Panics if writing to the output string fails (which should never happen in practice).
+#[must_use]
+pub fn service_to_idl_with_options(
+    srv: &ServiceSpecification,
+    package_name: &str,
+    input_file: &str,
+    options: &IdlOptions,
+) -> String {
     let mut output = String::new();
 
     // Header comment
@@ -399,6 +507,9 @@ pub fn service_to_idl(srv: &ServiceSpecification, package_name: &str, input_file
             includes.insert(include);
         }
     }
+    if options.service_events {
+        includes.insert(SERVICE_EVENT_INFO_INCLUDE.to_string());
+    }
 
     // Write includes
     for include in &includes {
@@ -428,10 +539,49 @@ pub fn service_to_idl(srv: &ServiceSpecification, package_name: &str, input_file
     // Response struct
     write_struct_idl(&mut output, &srv.response, "    ", &typedefs).unwrap();
 
+    // Event struct
+    if options.service_events {
+        writeln!(output).unwrap();
+        write_service_event_idl(&mut output, srv, package_name, "    ").unwrap();
+    }
+
     writeln!(output, "  }};").unwrap();
     writeln!(output, "}};").unwrap();
 
-    output
+    options.post_process(package_name, input_file, output)
+}
+
+/// Write the event structure of a service in IDL format, as derived by
+/// `rosidl_parser`
+fn write_service_event_idl(
+    output: &mut String,
+    srv: &ServiceSpecification,
+    package_name: &str,
+    indent: &str,
+) -> std::fmt::Result {
+    let srv_name = &srv.srv_name;
+    writeln!(
+        output,
+        "{indent}struct {srv_name}{SERVICE_EVENT_MESSAGE_SUFFIX} {{"
+    )?;
+    writeln!(
+        output,
+        "{indent}  service_msgs::msg::ServiceEventInfo info;"
+    )?;
+    writeln!(output)?;
+    writeln!(
+        output,
+        "{indent}  sequence<{package_name}::srv::{}, 1> request;",
+        srv.request.msg_name
+    )?;
+    writeln!(output)?;
+    writeln!(
+        output,
+        "{indent}  sequence<{package_name}::srv::{}, 1> response;",
+        srv.response.msg_name
+    )?;
+    writeln!(output, "{indent}  }};")?;
+    Ok(())
 }
 
 /// Convert an action to IDL format
@@ -440,6 +590,20 @@ pub fn service_to_idl(srv: &ServiceSpecification, package_name: &str, input_file
 /// Panics if writing to the output string fails (which should never happen in practice).
 #[must_use]
 pub fn action_to_idl(action: &ActionSpecification, package_name: &str, input_file: &str) -> String {
+    action_to_idl_with_options(action, package_name, input_file, &IdlOptions::default())
+}
+
+/// Convert an action to IDL format with the given options
+///
+/// # Panics
+/// Panics if writing to the output string fails (which should never happen in practice).
+#[must_use]
+pub fn action_to_idl_with_options(
+    action: &ActionSpecification,
+    package_name: &str,
+    input_file: &str,
+    options: &IdlOptions,
+) -> String {
     let mut output = String::new();
 
     // Header comment
@@ -506,7 +670,7 @@ pub fn action_to_idl(action: &ActionSpecification, package_name: &str, input_fil
     writeln!(output, "  }};").unwrap();
     writeln!(output, "}};").unwrap();
 
-    output
+    options.post_process(package_name, input_file, output)
 }
 
 #[cfg(test)]
@@ -66,6 +66,10 @@
 //! - [`generator`]: Code generator for converting ROS2 interfaces to Rust types
 //! - [`ros2args`]: ROS2 command-line arguments parser
 
+// The CDR runtime of the `no_std` generator mode is tested in this crate
+#[cfg(test)]
+extern crate alloc;
+
 // Public modules
 /// ROS2 Message/Service/Action parser
 ///
@@ -98,9 +102,9 @@ pub use msg::{
     InterfaceSpecification, MessageSpecification, PRIMITIVE_TYPES, ParseError, ParseResult,
     PrimitiveValue, ServiceSpecification, Type, Value, create_feedback_message,
     create_service_event_message, is_valid_constant_name, is_valid_field_name,
-    is_valid_message_name, is_valid_package_name, parse_action_file, parse_action_string,
-    parse_interface_file, parse_message_file, parse_message_string, parse_primitive_value_string,
-    parse_service_file, parse_service_string,
+    is_valid_message_name, is_valid_package_name, message_definition, parse_action_file,
+    parse_action_string, parse_interface_file, parse_message_file, parse_message_string,
+    parse_primitive_value_string, parse_service_file, parse_service_string,
 };
 
 /// Version information
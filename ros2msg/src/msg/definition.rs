@@ -0,0 +1,240 @@
+//! Full message definitions with their dependencies inlined
+//!
+//! Rosbag2 and Foxglove describe the schema of a topic with the text of its
+//! message definition followed by the definitions of every message it
+//! depends on, each introduced by a separator line and a `MSG:` header:
+//!
+//! ```text
+//! std_msgs/Header header
+//! geometry_msgs/Point position
+//! ================================================================================
+//! MSG: std_msgs/msg/Header
+//! builtin_interfaces/Time stamp
+//! string frame_id
+//! ================================================================================
+//! MSG: builtin_interfaces/msg/Time
+//! ...
+//! ```
+//!
+//! The text is rebuilt from parsed specifications, so comments of the original
+//! files are not kept.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use super::errors::{ParseError, ParseResult};
+use super::message::{MessageSpecification, parse_message_file};
+use super::types::BaseType;
+
+/// Line separating the definitions of a full message definition
+pub const DEFINITION_SEPARATOR: &str =
+    "================================================================================";
+
+/// Text of a message definition, without its dependencies
+///
+/// Constants come first, then fields, one per line, as in a `.msg` file.
+#[must_use]
+pub fn definition_text(spec: &MessageSpecification) -> String {
+    let mut text = String::new();
+    for constant in &spec.constants {
+        let _ = writeln!(text, "{constant}");
+    }
+    for field in &spec.fields {
+        let _ = writeln!(text, "{field}");
+    }
+    text
+}
+
+/// Concatenated definition of a message and all the messages it depends on
+///
+/// Dependencies are looked up with `lookup(pkg_name, msg_name)` and appended
+/// once each, depth first in the order of the fields, as rosbag2 does.
+///
+/// # Errors
+///
+/// Returns the error of `lookup` if a dependency cannot be resolved.
+///
+/// # Example
+///
+/// ```rust
+/// use ros2msg::{ParseError, msg::definition::message_definition, parse_message_string};
+///
+/// let point = parse_message_string("geometry_msgs", "Point", "float64 x\nfloat64 y")?;
+/// let pose = parse_message_string("geometry_msgs", "Pose2D", "Point position\nfloat64 theta")?;
+/// let text = message_definition(&pose, |pkg, name| match (pkg, name) {
+///     ("geometry_msgs", "Point") => Ok(point.clone()),
+///     _ => Err(ParseError::UnresolvedType {
+///         type_name: format!("{pkg}/{name}"),
+///     }),
+/// })?;
+/// assert!(text.contains("MSG: geometry_msgs/msg/Point\nfloat64 x\n"));
+/// # Ok::<(), ParseError>(())
+/// ```
+pub fn message_definition<F>(spec: &MessageSpecification, mut lookup: F) -> ParseResult<String>
+where
+    F: FnMut(&str, &str) -> ParseResult<MessageSpecification>,
+{
+    let mut text = definition_text(spec);
+    let mut seen = HashSet::new();
+    seen.insert((spec.pkg_name.clone(), spec.msg_name.clone()));
+    append_dependencies(spec, &mut lookup, &mut seen, &mut text)?;
+    Ok(text)
+}
+
+fn append_dependencies<F>(
+    spec: &MessageSpecification,
+    lookup: &mut F,
+    seen: &mut HashSet<(String, String)>,
+    text: &mut String,
+) -> ParseResult<()>
+where
+    F: FnMut(&str, &str) -> ParseResult<MessageSpecification>,
+{
+    for field in &spec.fields {
+        let BaseType {
+            pkg_name: Some(pkg_name),
+            type_name,
+            ..
+        } = &field.field_type.base_type
+        else {
+            continue;
+        };
+        if !seen.insert((pkg_name.clone(), type_name.clone())) {
+            continue;
+        }
+        let dependency = lookup(pkg_name, type_name)?;
+        let _ = write!(
+            text,
+            "{DEFINITION_SEPARATOR}\nMSG: {pkg_name}/msg/{type_name}\n{}",
+            definition_text(&dependency)
+        );
+        append_dependencies(&dependency, lookup, seen, text)?;
+    }
+    Ok(())
+}
+
+/// Concatenated definition of a message whose dependencies are `.msg` files
+///
+/// A dependency `pkg/Name` is read from the first `<dir>/pkg/msg/Name.msg`
+/// found in `search_paths`, e.g. the `share` directories of the ROS2
+/// installations.
+///
+/// # Errors
+///
+/// Returns [`ParseError::UnresolvedType`] if a dependency is not found, or the
+/// error of parsing its file.
+pub fn message_definition_from_paths<P: AsRef<Path>>(
+    spec: &MessageSpecification,
+    search_paths: &[P],
+) -> ParseResult<String> {
+    message_definition(spec, |pkg_name, msg_name| {
+        let path = find_message_file(search_paths, pkg_name, msg_name).ok_or_else(|| {
+            ParseError::UnresolvedType {
+                type_name: format!("{pkg_name}/{msg_name}"),
+            }
+        })?;
+        parse_message_file(pkg_name, path)
+    })
+}
+
+fn find_message_file<P: AsRef<Path>>(
+    search_paths: &[P],
+    pkg_name: &str,
+    msg_name: &str,
+) -> Option<PathBuf> {
+    search_paths
+        .iter()
+        .map(|dir| {
+            dir.as_ref()
+                .join(pkg_name)
+                .join("msg")
+                .join(format!("{msg_name}.msg"))
+        })
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::message::parse_message_string;
+
+    fn lookup(pkg_name: &str, msg_name: &str) -> ParseResult<MessageSpecification> {
+        let content = match (pkg_name, msg_name) {
+            ("builtin_interfaces", "Time") => "int32 sec\nuint32 nanosec",
+            ("std_msgs", "Header") => "builtin_interfaces/Time stamp\nstring frame_id",
+            ("geometry_msgs", "Point") => "float64 x\nfloat64 y\nfloat64 z",
+            _ => {
+                return Err(ParseError::UnresolvedType {
+                    type_name: format!("{pkg_name}/{msg_name}"),
+                });
+            }
+        };
+        parse_message_string(pkg_name, msg_name, content)
+    }
+
+    #[test]
+    fn test_definition_text() {
+        let spec = parse_message_string(
+            "test_msgs",
+            "Test",
+            "# comment\nint32 MAX=10\nint32[<=3] values\nstring name \"x\"",
+        )
+        .unwrap();
+        assert_eq!(
+            definition_text(&spec),
+            "int32 MAX=10\nint32[<=3] values\nstring name \"x\"\n"
+        );
+    }
+
+    #[test]
+    fn test_message_definition() {
+        let spec = parse_message_string(
+            "geometry_msgs",
+            "PointStamped",
+            "std_msgs/Header header\nPoint point\nPoint[] others\nbuiltin_interfaces/Time t",
+        )
+        .unwrap();
+        let text = message_definition(&spec, lookup).unwrap();
+        let sep = DEFINITION_SEPARATOR;
+        assert_eq!(
+            text,
+            format!(
+                "std_msgs/Header header\ngeometry_msgs/Point point\ngeometry_msgs/Point[] others\n\
+                 builtin_interfaces/Time t\n\
+                 {sep}\nMSG: std_msgs/msg/Header\nbuiltin_interfaces/Time stamp\nstring frame_id\n\
+                 {sep}\nMSG: builtin_interfaces/msg/Time\nint32 sec\nuint32 nanosec\n\
+                 {sep}\nMSG: geometry_msgs/msg/Point\nfloat64 x\nfloat64 y\nfloat64 z\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_message_definition_unresolved() {
+        let spec = parse_message_string("test_msgs", "Test", "unknown_msgs/Unknown u").unwrap();
+        let err = message_definition(&spec, lookup).unwrap_err();
+        assert!(
+            matches!(err, ParseError::UnresolvedType { type_name } if type_name == "unknown_msgs/Unknown")
+        );
+    }
+
+    #[test]
+    fn test_message_definition_from_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let msg_dir = dir.path().join("std_msgs").join("msg");
+        std::fs::create_dir_all(&msg_dir).unwrap();
+        std::fs::write(msg_dir.join("String.msg"), "# data\nstring data\n").unwrap();
+
+        let spec = parse_message_string("test_msgs", "Test", "std_msgs/String s").unwrap();
+        let text = message_definition_from_paths(&spec, &[dir.path()]).unwrap();
+        assert_eq!(
+            text,
+            format!(
+                "std_msgs/String s\n{DEFINITION_SEPARATOR}\nMSG: std_msgs/msg/String\nstring data\n"
+            )
+        );
+
+        let empty: [&Path; 0] = [];
+        assert!(message_definition_from_paths(&spec, &empty).is_err());
+    }
+}
@@ -44,6 +44,9 @@ pub enum ParseError {
 
     #[error("Regex error: {0}")]
     RegexError(#[from] regex::Error),
+
+    #[error("Unresolved type: {type_name}")]
+    UnresolvedType { type_name: String },
 }
 
 /// Result type for parsing operations
@@ -6,6 +6,8 @@
 // Re-export the core modules for the msg parser
 /// Action parsing functionality
 pub mod action;
+/// Full message definitions for rosbag2 and Foxglove schemas
+pub mod definition;
 /// Error types and handling
 pub mod errors;
 /// Message parsing functionality
@@ -21,6 +23,7 @@ pub mod validation;
 pub use action::{
     ActionSpecification, create_feedback_message, parse_action_file, parse_action_string,
 };
+pub use definition::{definition_text, message_definition, message_definition_from_paths};
 pub use errors::{ParseError, ParseResult};
 pub use message::{MessageSpecification, parse_message_file, parse_message_string};
 pub use service::{
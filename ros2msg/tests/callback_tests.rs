@@ -1,4 +1,4 @@
-use ros2msg::generator::{Generator, ItemInfo, ParseCallbacks};
+use ros2msg::generator::{FieldInfo, Generator, ItemInfo, ParseCallbacks};
 use std::fs;
 use tempfile::TempDir;
 
@@ -575,3 +575,51 @@ fn test_module_info_full_path() {
         msg_content
     );
 }
+
+/// Callback turning field comments and units into attributes
+struct FieldMetadataCallbacks;
+
+impl ParseCallbacks for FieldMetadataCallbacks {
+    fn add_field_attributes(&self, field_info: &FieldInfo) -> Vec<String> {
+        let mut attrs = Vec::new();
+        if let Some(unit) = field_info.unit() {
+            attrs.push(format!("#[doc = \"Unit: {unit}\"]"));
+        }
+        if field_info
+            .comments()
+            .iter()
+            .any(|line| line.starts_with("DEPRECATED"))
+        {
+            attrs.push("#[deprecated]".to_string());
+        }
+        attrs
+    }
+}
+
+#[test]
+fn test_callbacks_field_comments_and_unit() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_dir = temp_dir.path().join("generated");
+
+    let msg_file = create_test_msg_file(
+        &temp_dir,
+        "test_msgs",
+        "Odometry",
+        "int32 sequence\nfloat64 speed # DEPRECATED: use velocity [m/s]\nfloat64 velocity # [m/s]\n",
+    );
+
+    let result = Generator::new()
+        .derive_debug(true)
+        .parse_callbacks(Box::new(FieldMetadataCallbacks))
+        .include(msg_file.to_str().unwrap())
+        .output_dir(output_dir.to_str().unwrap())
+        .generate();
+
+    assert!(result.is_ok());
+
+    let generated_file = output_dir.join("test_msgs").join("msg").join("odometry.rs");
+    let content = fs::read_to_string(&generated_file).unwrap();
+
+    assert_eq!(content.matches("Unit: m/s").count(), 2);
+    assert_eq!(content.matches("#[deprecated]").count(), 1);
+}
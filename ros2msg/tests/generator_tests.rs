@@ -1,4 +1,6 @@
-use ros2msg::generator::{FieldInfo, Generator, ParseCallbacks};
+use ros2msg::generator::{
+    FieldInfo, GenerationError, Generator, GeneratorError, OutputMode, ParseCallbacks,
+};
 use std::fs;
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -1008,3 +1010,156 @@ fn test_generator_idl_parameter_value() {
         content
     );
 }
+
+/// Read every file under `dir`, keyed by its path relative to `dir`
+fn read_tree(dir: &std::path::Path) -> std::collections::BTreeMap<PathBuf, String> {
+    fn walk(
+        root: &std::path::Path,
+        dir: &std::path::Path,
+        out: &mut std::collections::BTreeMap<PathBuf, String>,
+    ) {
+        for entry in fs::read_dir(dir).unwrap().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                walk(root, &path, out);
+            } else {
+                let rel = path.strip_prefix(root).unwrap().to_path_buf();
+                out.insert(rel, fs::read_to_string(&path).unwrap());
+            }
+        }
+    }
+    let mut out = std::collections::BTreeMap::new();
+    walk(dir, dir, &mut out);
+    out
+}
+
+#[test]
+fn test_generator_reproducible_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let point = create_test_msg_file(&temp_dir, "geo_msgs", "Point", "float64 x\nfloat64 y\n");
+    let pose = create_test_msg_file(
+        &temp_dir,
+        "nav_msgs",
+        "Pose",
+        "geo_msgs/Point position\ngeo_msgs/Point[] path\nstring frame\n",
+    );
+    let srv = create_test_srv_file(
+        &temp_dir,
+        "nav_msgs",
+        "Plan",
+        "geo_msgs/Point goal\n---\nbool ok\n",
+    );
+
+    let generate = |dir: &str, files: &[&PathBuf]| {
+        let output_dir = temp_dir.path().join(dir);
+        Generator::new()
+            .derive_debug(true)
+            .includes(files.iter().map(|f| f.as_path()))
+            .output_dir(&output_dir)
+            .generate()
+            .unwrap();
+        read_tree(&output_dir)
+    };
+
+    // Same output whatever the order of the inputs and across runs
+    let first = generate("first", &[&point, &pose, &srv]);
+    let second = generate("second", &[&srv, &pose, &point]);
+    let third = generate("third", &[&point, &pose, &srv]);
+    assert!(!first.is_empty());
+    assert_eq!(first, second);
+    assert_eq!(first, third);
+}
+
+#[test]
+fn test_generator_check_mode() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_dir = temp_dir.path().join("generated");
+    let msg = create_test_msg_file(&temp_dir, "test_msgs", "Checked", "int32 value\n");
+
+    let generator = || {
+        Generator::new()
+            .derive_debug(true)
+            .include(&msg)
+            .output_dir(&output_dir)
+    };
+
+    // Nothing generated yet: every file is missing, and nothing is written
+    let err = generator()
+        .output_mode(OutputMode::Check)
+        .generate()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        GeneratorError::GenerationError(GenerationError::OutOfDate { .. })
+    ));
+    assert!(!output_dir.exists());
+
+    generator().generate().unwrap();
+    generator()
+        .output_mode(OutputMode::Check)
+        .generate()
+        .unwrap();
+
+    // Edited file
+    let generated = output_dir.join("test_msgs").join("msg").join("checked.rs");
+    fs::write(&generated, "// edited\n").unwrap();
+    match generator().output_mode(OutputMode::Check).generate() {
+        Err(GeneratorError::GenerationError(GenerationError::OutOfDate { files })) => {
+            assert_eq!(files, vec![generated.clone()]);
+        }
+        other => panic!("expected out of date error, got {other:?}"),
+    }
+    assert_eq!(fs::read_to_string(&generated).unwrap(), "// edited\n");
+
+    // Stale file no longer generated
+    generator().generate().unwrap();
+    let stale = output_dir.join("test_msgs").join("msg").join("removed.rs");
+    fs::write(&stale, "").unwrap();
+    match generator().output_mode(OutputMode::Check).generate() {
+        Err(GeneratorError::GenerationError(GenerationError::OutOfDate { files })) => {
+            assert_eq!(files, vec![stale]);
+        }
+        other => panic!("expected out of date error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_generator_no_std() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_dir = temp_dir.path().join("generated");
+
+    let msg_file = create_test_msg_file(
+        &temp_dir,
+        "test_msgs",
+        "Telemetry",
+        "string label\nfloat64[] samples\nuint8[4] flags\n",
+    );
+
+    let result = Generator::new()
+        .derive_default(true)
+        .no_std(true)
+        .include(msg_file.to_str().unwrap())
+        .output_dir(output_dir.to_str().unwrap())
+        .generate();
+    assert!(result.is_ok(), "Failed to generate: {:?}", result.err());
+
+    let root_mod = fs::read_to_string(output_dir.join("mod.rs")).unwrap();
+    assert!(root_mod.contains("pub mod cdr;"));
+    let runtime = fs::read_to_string(output_dir.join("cdr.rs")).unwrap();
+    assert!(runtime.contains("pub trait CdrEncode"));
+    assert!(!runtime.contains("std::"));
+
+    let content = fs::read_to_string(
+        output_dir
+            .join("test_msgs")
+            .join("msg")
+            .join("telemetry.rs"),
+    )
+    .unwrap();
+    assert!(!content.contains("std::"), "{content}");
+    assert!(content.contains("pub label: ::alloc::string::String"));
+    assert!(content.contains("pub samples: ::alloc::vec::Vec<f64>"));
+    assert!(content.contains("samples: ::alloc::vec::Vec::new()"));
+    assert!(content.contains("impl super::super::super::cdr::CdrEncode for Telemetry"));
+    assert!(content.contains("impl super::super::super::cdr::CdrDecode for Telemetry"));
+}
@@ -0,0 +1,109 @@
+//! Round-trip tests of the MSG/SRV/Action to IDL converter
+//!
+//! The files of `tests/idl_adapter` are converted to IDL and compared with the
+//! output of rosidl_adapter for the same files, after parsing both IDLs.
+
+use ros2msg::idl::{IdlFile, parse_idl_string};
+use ros2msg::idl_adapter::{
+    IdlOptions, action_to_idl, message_to_idl, service_to_idl, service_to_idl_with_options,
+};
+use ros2msg::{parse_action_file, parse_message_file, parse_service_file};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+const PACKAGE: &str = "golden_msgs";
+
+fn golden_path(file: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/idl_adapter")
+        .join(file)
+}
+
+/// Parse the generated IDL and the golden file, and compare them
+fn assert_golden(generated: &str, golden_file: &str) -> IdlFile {
+    let golden = std::fs::read_to_string(golden_path(golden_file)).unwrap();
+    let generated_idl = parse_idl_string(generated)
+        .unwrap_or_else(|e| panic!("Failed to parse generated IDL: {e}\n{generated}"));
+    let golden_idl = parse_idl_string(&golden).unwrap();
+    assert_eq!(
+        generated_idl, golden_idl,
+        "IDL mismatch with {golden_file}\n--- Generated ---\n{generated}"
+    );
+    generated_idl
+}
+
+#[test]
+fn test_golden_message() {
+    let msg = parse_message_file(PACKAGE, golden_path("msg/Golden.msg")).unwrap();
+    let idl = message_to_idl(&msg, PACKAGE, "msg/Golden.msg");
+    assert_golden(&idl, "msg/Golden.idl");
+}
+
+#[test]
+fn test_golden_service() {
+    let srv = parse_service_file(PACKAGE, golden_path("srv/AddTwoInts.srv")).unwrap();
+    let idl = service_to_idl(&srv, PACKAGE, "srv/AddTwoInts.srv");
+    assert_golden(&idl, "srv/AddTwoInts.idl");
+    assert!(!idl.contains("AddTwoInts_Event"));
+}
+
+#[test]
+fn test_golden_service_events() {
+    let srv = parse_service_file(PACKAGE, golden_path("srv/AddTwoInts.srv")).unwrap();
+    let options = IdlOptions {
+        service_events: true,
+        ..Default::default()
+    };
+    let idl = service_to_idl_with_options(&srv, PACKAGE, "srv/AddTwoInts.srv", &options);
+    let with_events = assert_golden(&idl, "srv/AddTwoInts_Event.idl");
+
+    // The emitted event is the one rosidl_parser derives
+    let derived = parse_idl_string(&service_to_idl(&srv, PACKAGE, "srv/AddTwoInts.srv")).unwrap();
+    assert_eq!(
+        with_events.content.get_services(),
+        derived.content.get_services()
+    );
+}
+
+#[test]
+fn test_golden_action() {
+    let action = parse_action_file(PACKAGE, golden_path("action/Fibonacci.action")).unwrap();
+    let idl = action_to_idl(&action, PACKAGE, "action/Fibonacci.action");
+    assert_golden(&idl, "action/Fibonacci.idl");
+}
+
+#[test]
+fn test_post_processor() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let calls_cloned = calls.clone();
+    let options = IdlOptions {
+        service_events: false,
+        post_processor: Some(Arc::new(
+            move |package: &str, input_file: &str, idl: String| {
+                calls_cloned
+                    .lock()
+                    .unwrap()
+                    .push(format!("{package}/{input_file}"));
+                idl.replace(
+                    "    struct AddTwoInts_Response",
+                    "    @verbatim (language=\"comment\", text=\"injected\")\n    struct AddTwoInts_Response",
+                )
+            },
+        )),
+    };
+
+    let srv = parse_service_file(PACKAGE, golden_path("srv/AddTwoInts.srv")).unwrap();
+    let idl = service_to_idl_with_options(&srv, PACKAGE, "srv/AddTwoInts.srv", &options);
+    assert!(idl.contains("text=\"injected\""));
+    assert!(parse_idl_string(&idl).is_ok());
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec!["golden_msgs/srv/AddTwoInts.srv".to_string()]
+    );
+
+    // Without a post-processor, the output is unchanged
+    assert_eq!(
+        service_to_idl_with_options(&srv, PACKAGE, "srv/AddTwoInts.srv", &IdlOptions::default()),
+        service_to_idl(&srv, PACKAGE, "srv/AddTwoInts.srv")
+    );
+}